@@ -7,6 +7,7 @@ export enum ActionState {
   OnHold = "OnHold",
   Queued = "Queued",
   Running = "Running",
+  Cancelled = "Cancelled",
 }
 
 export enum ActionKind {
@@ -53,6 +53,7 @@ export interface RawComponent {
   toDelete: boolean;
   canBeUpgraded: boolean;
   fromBaseChangeSet: boolean;
+  labels: Record<string, string>;
 }
 
 export type EdgeId = string;
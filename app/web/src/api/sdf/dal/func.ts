@@ -17,6 +17,8 @@ export enum FuncKind {
   Qualification = "Qualification",
   SchemaVariantDefinition = "SchemaVariantDefinition",
   Unknown = "Unknown",
+  Reconciliation = "Reconciliation",
+  Import = "Import",
 }
 
 export enum CustomizableFuncKind {
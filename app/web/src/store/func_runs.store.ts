@@ -23,6 +23,7 @@ export enum FuncRunState {
   Running = "running",
   PostProcessing = "postprocessing",
   Failure = "failure",
+  Cancelled = "cancelled",
 }
 
 export enum FuncKind {
@@ -35,20 +36,25 @@ export enum FuncKind {
 
 export enum FuncBackendKind {
   Array,
+  Base64Encode,
   Boolean,
   Diff,
   Identity,
   Integer,
+  JoinArray,
   JsAction,
   JsAttribute,
   JsAuthentication,
   Json,
+  JsonPointer,
   JsReconciliation,
   JsSchemaVariantDefinition,
   JsValidation,
   Map,
+  MapFromList,
   Object,
   String,
+  StringTemplate,
   Unset,
   Validation,
 }
@@ -79,6 +85,7 @@ export interface OutputLine {
   group?: string;
   message: string;
   timestamp: string;
+  fields: Record<string, string>;
 }
 
 export interface FuncRunLog {
@@ -145,6 +145,17 @@ export type WsEventPayloadMap = {
     idle: boolean;
   };
 
+  ResourceDriftDetected: {
+    componentId: ComponentId;
+    changeSetId: ChangeSetId;
+    drifted: boolean;
+    entries: {
+      path: string;
+      modeled: unknown;
+      actual: unknown;
+    }[];
+  };
+
   ResourceRefreshed: {
     component: RawComponent;
     changeSetId: string;
@@ -134,6 +134,14 @@ pub(crate) struct Args {
     /// Limits execution requests to the given value before shutting down
     #[arg(long, group = "request_limiting")]
     pub(crate) limit_requests: Option<u32>,
+
+    /// Limits the memory of each lang server process to the given value in megabytes
+    #[arg(long)]
+    pub(crate) limit_memory_mb: Option<u64>,
+
+    /// Limits the CPU time of each lang server process to the given value in seconds
+    #[arg(long)]
+    pub(crate) limit_cpu_secs: Option<u64>,
 }
 
 impl TryFrom<Args> for Config {
@@ -187,6 +195,9 @@ impl TryFrom<Args> for Config {
             builder.limit_requests(limit_requests);
         }
 
+        builder.limit_memory_mb(args.limit_memory_mb);
+        builder.limit_cpu_secs(args.limit_cpu_secs);
+
         builder.build().map_err(Into::into)
     }
 }
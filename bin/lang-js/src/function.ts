@@ -89,6 +89,7 @@ export interface OutputLine {
   level: "debug" | "info" | "warn" | "error";
   group?: string;
   message: string;
+  fields?: Record<string, string>;
 }
 
 export async function executeFunction(kind: FunctionKind, request: Request) {
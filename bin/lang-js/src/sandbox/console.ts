@@ -9,7 +9,21 @@ const normalizeMessage = (msg: unknown[]): string => {
     .join(" ");
 };
 
-export const makeConsole = (executionId: string) => {
+const normalizeFields = (
+  fields: Record<string, unknown>,
+): Record<string, string> => {
+  return Object.fromEntries(
+    Object.entries(fields).map(([key, value]) => [
+      key,
+      normalizeMessage([value]),
+    ]),
+  );
+};
+
+export const makeConsole = (
+  executionId: string,
+  fields?: Record<string, string>,
+) => {
   function debug(...args: unknown[]): void {
     emitOutputLine({
       protocol: "output",
@@ -18,6 +32,7 @@ export const makeConsole = (executionId: string) => {
       level: "debug",
       group: "log",
       message: normalizeMessage(args),
+      fields,
     });
   }
 
@@ -29,6 +44,7 @@ export const makeConsole = (executionId: string) => {
       level: "error",
       group: "log",
       message: normalizeMessage(args),
+      fields,
     });
   }
 
@@ -40,6 +56,16 @@ export const makeConsole = (executionId: string) => {
       level: "info",
       group: "log",
       message: normalizeMessage(args),
+      fields,
+    });
+  }
+
+  // Returns a console whose lines carry the given fields (on top of this console's own), so
+  // that they can be searched on without parsing the message.
+  function withFields(extra: Record<string, unknown>) {
+    return makeConsole(executionId, {
+      ...fields,
+      ...normalizeFields(extra),
     });
   }
 
@@ -47,5 +73,5 @@ export const makeConsole = (executionId: string) => {
     console.log(JSON.stringify(line));
   }
 
-  return { debug, error, log };
+  return { debug, error, log, withFields };
 };
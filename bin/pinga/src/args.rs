@@ -1,4 +1,4 @@
-use std::path::PathBuf;
+use std::{net::SocketAddr, path::PathBuf};
 
 use clap::{ArgAction, Parser};
 use pinga_server::{Config, ConfigError, ConfigFile, StandardConfigFile};
@@ -59,6 +59,14 @@ pub(crate) struct Args {
     )]
     pub(crate) log_json: bool,
 
+    /// Serves Prometheus metrics at `/metrics` on the given address [example: 0.0.0.0:9090]
+    #[arg(
+        long = "metrics-listen-addr",
+        env = "SI_METRICS_LISTEN_ADDR",
+        hide_env_values = true
+    )]
+    pub(crate) metrics_listen_addr: Option<SocketAddr>,
+
     /// PostgreSQL connection pool dbname [example: myapp]
     #[arg(long)]
     pub(crate) pg_dbname: Option<String>,
@@ -1,4 +1,4 @@
-use std::path::PathBuf;
+use std::{net::SocketAddr, path::PathBuf};
 
 use clap::{ArgAction, Parser};
 use rebaser_server::{Config, ConfigError, ConfigFile, StandardConfigFile};
@@ -55,6 +55,14 @@ pub(crate) struct Args {
     )]
     pub(crate) log_json: bool,
 
+    /// Serves Prometheus metrics at `/metrics` on the given address [example: 0.0.0.0:9090]
+    #[arg(
+        long = "metrics-listen-addr",
+        env = "SI_METRICS_LISTEN_ADDR",
+        hide_env_values = true
+    )]
+    pub(crate) metrics_listen_addr: Option<SocketAddr>,
+
     /// PostgreSQL connection pool dbname [example: myapp]
     #[arg(long)]
     pub(crate) pg_dbname: Option<String>,
@@ -119,6 +127,10 @@ pub(crate) struct Args {
     #[arg(long)]
     pub(crate) concurrency: Option<u32>,
 
+    /// The number of workspace snapshot writes between full checkpoints [default: 20]
+    #[arg(long)]
+    pub(crate) snapshot_checkpoint_interval: Option<u32>,
+
     /// The path at which the layer db cache is created/used on disk [e.g. /banana/]
     #[arg(long)]
     pub(crate) layer_db_disk_path: Option<String>,
@@ -218,6 +230,9 @@ impl TryFrom<Args> for Config {
             if let Some(concurrency) = args.concurrency {
                 config_map.set("concurrency_limit", i64::from(concurrency));
             }
+            if let Some(interval) = args.snapshot_checkpoint_interval {
+                config_map.set("snapshot_checkpoint_interval", i64::from(interval));
+            }
             if let Some(layer_cache_disk_path) = args.layer_db_disk_path {
                 config_map.set("layer_db_config.disk_path", layer_cache_disk_path);
             }
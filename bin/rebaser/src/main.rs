@@ -24,6 +24,7 @@ async fn async_main() -> Result<()> {
                     .then_some(ConsoleLogFormat::Json)
                     .unwrap_or_default(),
             )
+            .metrics_listen_addr(args.metrics_listen_addr)
             .service_name("rebaser")
             .service_namespace("si")
             .log_env_var_prefix("SI")
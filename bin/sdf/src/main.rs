@@ -48,6 +48,7 @@ async fn async_main() -> Result<()> {
                     .then_some(ConsoleLogFormat::Json)
                     .unwrap_or_default(),
             )
+            .metrics_listen_addr(args.metrics_listen_addr)
             .service_name("sdf")
             .service_namespace("si")
             .log_env_var_prefix("SI")
@@ -169,6 +170,11 @@ async fn async_main() -> Result<()> {
             )?;
             let _second_shutdown_broadcast_rx = initial_shutdown_broadcast_rx.resubscribe();
 
+            Server::start_webhook_dispatcher(
+                services_context.clone(),
+                initial_shutdown_broadcast_rx.resubscribe(),
+            )?;
+
             // Server::start_resource_refresh_scheduler(
             //     services_context.clone(),
             //     initial_shutdown_broadcast_rx,
@@ -193,6 +199,11 @@ async fn async_main() -> Result<()> {
             .await?;
             let _second_shutdown_broadcast_rx = initial_shutdown_broadcast_rx.resubscribe();
 
+            Server::start_webhook_dispatcher(
+                services_context.clone(),
+                initial_shutdown_broadcast_rx.resubscribe(),
+            )?;
+
             // Server::start_resource_refresh_scheduler(
             //     services_context.clone(),
             //     initial_shutdown_broadcast_rx,
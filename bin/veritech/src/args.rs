@@ -1,4 +1,4 @@
-use std::path::PathBuf;
+use std::{net::SocketAddr, path::PathBuf};
 
 use clap::{ArgAction, Parser};
 use si_std::SensitiveString;
@@ -55,6 +55,14 @@ pub(crate) struct Args {
     )]
     pub(crate) log_json: bool,
 
+    /// Serves Prometheus metrics at `/metrics` on the given address [example: 0.0.0.0:9090]
+    #[arg(
+        long = "metrics-listen-addr",
+        env = "SI_METRICS_LISTEN_ADDR",
+        hide_env_values = true
+    )]
+    pub(crate) metrics_listen_addr: Option<SocketAddr>,
+
     /// NATS connection URL [example: 0.0.0.0:4222]
     #[arg(long, short = 'u')]
     pub(crate) nats_url: Option<String>,
@@ -87,6 +95,14 @@ pub(crate) struct Args {
     #[arg(long)]
     pub(crate) cyclone_pool_size: Option<u16>,
 
+    /// Minimum cyclone pool size, enabling autoscaling up to the pool size
+    #[arg(long)]
+    pub(crate) cyclone_pool_min_size: Option<u16>,
+
+    /// Maximum number of function requests from a single workspace to run concurrently
+    #[arg(long)]
+    pub(crate) per_workspace_concurrency: Option<u16>,
+
     /// Veritech decryption key file location [example: /run/veritech/veritech.key]
     #[arg(long)]
     pub(crate) decryption_key: Option<PathBuf>,
@@ -121,6 +137,12 @@ impl TryFrom<Args> for Config {
             if let Some(size) = args.cyclone_pool_size {
                 config_map.set("cyclone.pool_size", size);
             }
+            if let Some(size) = args.cyclone_pool_min_size {
+                config_map.set("cyclone_pool_scaling.min_size", size);
+            }
+            if let Some(concurrency) = args.per_workspace_concurrency {
+                config_map.set("request_scheduling.per_workspace_concurrency", concurrency);
+            }
             if let Some(decryption_key_path) = args.decryption_key {
                 config_map.set(
                     "decryption_key_path",
@@ -22,6 +22,7 @@ async fn main() -> Result<()> {
                     .then_some(ConsoleLogFormat::Json)
                     .unwrap_or_default(),
             )
+            .metrics_listen_addr(args.metrics_listen_addr)
             .service_name("veritech")
             .service_namespace("si")
             .log_env_var_prefix("SI")
@@ -835,6 +835,7 @@ mod tests {
                 }"#,
             ),
             before: vec![],
+            timeout_secs: None,
         };
 
         // Start the protocol
@@ -936,6 +937,7 @@ mod tests {
                 }"#,
             ),
             before: vec![],
+            timeout_secs: None,
         };
 
         // Start the protocol
@@ -1010,6 +1012,7 @@ mod tests {
             validation_format: r#"{"type":"number","flags":{"presence":"required"},"rules":[{"name":"integer"},{"name":"min","args":{"limit":33}},{"name":"max","args":{"limit":33}}]}"#.to_string(),
             code_base64: "".to_string(),
             before: vec![],
+            timeout_secs: None,
         };
         let mut progress = client
             .execute_validation(CycloneRequest::from_parts(req, Default::default()))
@@ -1078,6 +1081,7 @@ mod tests {
                 }"#,
             ),
             before: vec![],
+            timeout_secs: None,
         };
 
         // Start the protocol
@@ -1164,6 +1168,7 @@ mod tests {
                 }"#,
             ),
             before: vec![],
+            timeout_secs: None,
         };
 
         // Start the protocol
@@ -1249,6 +1254,7 @@ mod tests {
                 }"#,
             ),
             before: vec![],
+            timeout_secs: None,
         };
 
         // Start the protocol
@@ -1324,6 +1330,7 @@ mod tests {
                 }"#,
             ),
             before: vec![],
+            timeout_secs: None,
         };
 
         // Start the protocol
@@ -1401,6 +1408,7 @@ mod tests {
                     return new AssetBuilder().build();
                 }"#,
             ),
+            timeout_secs: None,
         };
 
         // Start the protocol
@@ -1478,6 +1486,7 @@ mod tests {
                     return new AssetBuilder().build();
                 }"#,
             ),
+            timeout_secs: None,
         };
 
         // Start the protocol
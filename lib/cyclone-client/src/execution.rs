@@ -4,7 +4,9 @@ use std::{
     task::{Context, Poll},
 };
 
-use cyclone_core::{CycloneRequest, FunctionResult, Message, ProgressMessage};
+use cyclone_core::{
+    CancelExecutionRequest, CycloneRequest, FunctionResult, Message, ProgressMessage,
+};
 use futures::{Future, SinkExt, Stream, StreamExt};
 use hyper::client::connect::Connection;
 use serde::{de::DeserializeOwned, Serialize};
@@ -117,6 +119,19 @@ where
     pub async fn finish(self) -> Result<FunctionResult<Success>, ExecutionError<Success>> {
         ExecutionClosing::try_from(self)?.finish().await
     }
+
+    /// Asks the server to cancel the execution. The cancelled result is then received on the
+    /// stream as normal.
+    pub async fn cancel(
+        &mut self,
+        request: &CancelExecutionRequest,
+    ) -> Result<(), ExecutionError<Success>> {
+        let msg = serde_json::to_string(request).map_err(ExecutionError::JSONSerialize)?;
+        self.stream
+            .send(WebSocketMessage::Text(msg))
+            .await
+            .map_err(ExecutionError::WSSendIO)
+    }
 }
 
 impl<T, Success> Stream for ExecutionStarted<T, Success>
@@ -1,19 +1,23 @@
 mod client;
 mod execution;
 mod ping;
+mod replay;
 mod watch;
 
 pub use client::{Client, ClientConfig, ClientError, CycloneClient, HttpClient, UdsClient};
 pub use cyclone_core::{
-    ActionRunRequest, ActionRunResultSuccess, CycloneRequest, LivenessStatus,
-    LivenessStatusParseError, ReadinessStatus, ReadinessStatusParseError, ReconciliationRequest,
-    ReconciliationResultSuccess, ResolverFunctionRequest, ResolverFunctionResultSuccess,
-    SchemaVariantDefinitionRequest, SchemaVariantDefinitionResultSuccess, SensitiveStrings,
+    ActionRunRequest, ActionRunResultSuccess, CancelExecutionRequest, CycloneRequest,
+    LivenessStatus, LivenessStatusParseError, ReadinessStatus, ReadinessStatusParseError,
+    ReconciliationRequest, ReconciliationResultSuccess, RecordedExecution, RedactedSecret,
+    ReplayBundle, ReplayDifference, ReplayRequest, ResolverFunctionRequest,
+    ResolverFunctionResultSuccess, SchemaVariantDefinitionRequest,
+    SchemaVariantDefinitionResultSuccess, SensitiveStrings,
 };
 pub use execution::{Execution, ExecutionError};
 pub use hyper::client::connect::Connection;
 pub use hyperlocal::UnixStream;
 pub use ping::{PingExecution, PingExecutionError};
+pub use replay::{replay, ReplayError, ReplayReport};
 pub use tokio_tungstenite::tungstenite::{
     protocol::frame::CloseFrame as WebSocketCloseFrame, Message as WebSocketMessage,
 };
@@ -0,0 +1,123 @@
+use cyclone_core::{
+    CycloneRequest, FunctionResult, OutputStream, ProgressMessage, ReplayBundle, ReplayDifference,
+    ReplayRequest, ReplayResult, SensitiveStrings,
+};
+use futures::StreamExt;
+use hyper::client::connect::Connection;
+use serde::{de::DeserializeOwned, Serialize};
+use thiserror::Error;
+use tokio::io::{AsyncRead, AsyncWrite};
+
+use crate::{ClientError, CycloneClient, Execution};
+
+#[remain::sorted]
+#[derive(Debug, Error)]
+pub enum ReplayError {
+    #[error("client error: {0}")]
+    Client(#[from] ClientError),
+    #[error("execution error: {0}")]
+    Execution(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
+}
+
+type Result<T> = std::result::Result<T, ReplayError>;
+
+/// The outcome of replaying a [`ReplayBundle`].
+#[derive(Debug)]
+pub struct ReplayReport {
+    /// The output streamed by the function while it was replayed.
+    pub output: Vec<OutputStream>,
+    /// The replayed result, in the same form as the bundle's recorded result.
+    pub result: FunctionResult<serde_json::Value>,
+    /// How the replayed result differs from the recorded one.
+    pub differences: Vec<ReplayDifference>,
+}
+
+impl ReplayReport {
+    /// Whether the replayed result matches the recorded one.
+    pub fn is_match(&self) -> bool {
+        self.differences.is_empty()
+    }
+}
+
+/// Runs the request in a [`ReplayBundle`] and compares its result with the recorded one.
+///
+/// Any secrets the function needs must have been filled in with
+/// [`ReplayBundle::fill_secret`] beforehand, otherwise the function sees the redacted placeholders.
+pub async fn replay<C, Strm>(client: &mut C, bundle: &ReplayBundle) -> Result<ReplayReport>
+where
+    C: CycloneClient<Strm> + Send,
+    Strm: AsyncRead + AsyncWrite + Connection + Unpin + Send + 'static,
+{
+    let (output, result) = match bundle.request.clone() {
+        ReplayRequest::ActionRun(request) => {
+            run(client.execute_action_run(cyclone_request(request)).await?).await?
+        }
+        ReplayRequest::Reconciliation(request) => {
+            run(client
+                .execute_reconciliation(cyclone_request(request))
+                .await?)
+            .await?
+        }
+        ReplayRequest::ResolverFunction(request) => {
+            run(client.execute_resolver(cyclone_request(request)).await?).await?
+        }
+        ReplayRequest::SchemaVariantDefinition(request) => {
+            run(client
+                .execute_schema_variant_definition(cyclone_request(request))
+                .await?)
+            .await?
+        }
+    };
+
+    let differences = match &result {
+        FunctionResult::Success(value) => bundle.compare(Some(value)),
+        FunctionResult::Failure(_) => bundle.compare(None),
+    };
+
+    Ok(ReplayReport {
+        output,
+        result,
+        differences,
+    })
+}
+
+fn cyclone_request<R>(request: R) -> CycloneRequest<R> {
+    CycloneRequest::from_parts(request, SensitiveStrings::default())
+}
+
+async fn run<Strm, Request, Success>(
+    execution: Execution<Strm, Request, Success>,
+) -> Result<(Vec<OutputStream>, FunctionResult<serde_json::Value>)>
+where
+    Strm: AsyncRead + AsyncWrite + Connection + Unpin + Send + 'static,
+    Request: Serialize,
+    Success: ReplayResult + DeserializeOwned + Unpin + std::fmt::Debug + Send + Sync + 'static,
+{
+    let mut progress = execution
+        .start()
+        .await
+        .map_err(|err| ReplayError::Execution(Box::new(err)))?;
+
+    let mut output = Vec::new();
+    while let Some(message) = progress.next().await {
+        match message.map_err(|err| ReplayError::Execution(Box::new(err)))? {
+            ProgressMessage::OutputStream(output_stream) => output.push(output_stream),
+            ProgressMessage::Heartbeat => {}
+        }
+    }
+
+    let result = progress
+        .finish()
+        .await
+        .map_err(|err| ReplayError::Execution(Box::new(err)))?;
+
+    Ok((
+        output,
+        match result {
+            FunctionResult::Success(success) => {
+                FunctionResult::Success(success.into_recorded_value())
+            }
+            FunctionResult::Failure(failure) => FunctionResult::Failure(failure),
+        },
+    ))
+}
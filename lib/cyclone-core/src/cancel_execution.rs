@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+
+/// A request to cancel a function execution which is in flight.
+///
+/// Sent to veritech over NATS and then on to cyclone over the execution's web socket, at which
+/// point the language server process is killed and a cancelled failure result is reported.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CancelExecutionRequest {
+    pub execution_id: String,
+}
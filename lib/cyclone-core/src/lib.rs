@@ -13,6 +13,7 @@
 
 mod action_run;
 mod before;
+mod cancel_execution;
 mod canonical_command;
 mod component_view;
 mod liveness;
@@ -20,6 +21,7 @@ pub mod process;
 mod progress;
 mod readiness;
 mod reconciliation;
+mod replay;
 mod request;
 mod resolver_function;
 mod schema_variant_definition;
@@ -30,6 +32,7 @@ pub use si_crypto::SensitiveStrings;
 
 pub use action_run::{ActionRunRequest, ActionRunResultSuccess, ResourceStatus};
 pub use before::BeforeFunction;
+pub use cancel_execution::CancelExecutionRequest;
 pub use canonical_command::{CanonicalCommand, CanonicalCommandError};
 pub use component_view::{ComponentKind, ComponentView};
 pub use liveness::{LivenessStatus, LivenessStatusParseError};
@@ -39,7 +42,11 @@ pub use progress::{
 };
 pub use readiness::{ReadinessStatus, ReadinessStatusParseError};
 pub use reconciliation::{ReconciliationRequest, ReconciliationResultSuccess};
-pub use request::CycloneRequest;
+pub use replay::{
+    redact_before_functions, RecordedExecution, RedactedSecret, ReplayBundle, ReplayDifference,
+    ReplayRequest, ReplayResult, REDACTED_SECRET_PLACEHOLDER,
+};
+pub use request::{CycloneRequest, FunctionRequest};
 pub use resolver_function::{
     ResolverFunctionComponent, ResolverFunctionRequest, ResolverFunctionResponseType,
     ResolverFunctionResultSuccess,
@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
 /// A line of output, streamed from an executing function.
@@ -32,6 +34,12 @@ pub struct OutputStream {
     ///
     /// The timestamp generated locally when the message was created.
     pub timestamp: u64,
+    /// Structured key/value data attached to the output line.
+    ///
+    /// Functions can attach fields to their log lines so that they can be filtered and searched
+    /// on without parsing the message.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub fields: BTreeMap<String, String>,
 }
 
 /// A message produced as a function is executing.
@@ -110,6 +118,47 @@ pub struct FunctionResultFailureError {
     pub message: String,
 }
 
+impl FunctionResultFailureError {
+    /// The kind of failure when a function is killed because its execution was cancelled.
+    pub const KIND_CANCELLED: &'static str = "cancelled";
+
+    /// The kind of failure when a function is killed by a signal for no reason that is known.
+    pub const KIND_KILLED: &'static str = "killed";
+
+    /// The kind of failure when a function is killed for exceeding its timeout or one of its
+    /// resource limits.
+    pub const KIND_LIMIT_EXCEEDED: &'static str = "limitExceeded";
+
+    /// Creates a failure for a function whose execution was cancelled.
+    pub fn cancelled(message: impl Into<String>) -> Self {
+        Self {
+            kind: Self::KIND_CANCELLED.to_string(),
+            message: message.into(),
+        }
+    }
+
+    /// Creates a failure for a function which was killed by a signal for no reason that is known.
+    pub fn killed(message: impl Into<String>) -> Self {
+        Self {
+            kind: Self::KIND_KILLED.to_string(),
+            message: message.into(),
+        }
+    }
+
+    /// Creates a failure for a function which exceeded its timeout or one of its resource limits.
+    pub fn limit_exceeded(message: impl Into<String>) -> Self {
+        Self {
+            kind: Self::KIND_LIMIT_EXCEEDED.to_string(),
+            message: message.into(),
+        }
+    }
+
+    /// Returns `true` if the function was killed because its execution was cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.kind == Self::KIND_CANCELLED
+    }
+}
+
 #[derive(Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub struct Fail {
     pub message: String,
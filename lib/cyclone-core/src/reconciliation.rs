@@ -1,4 +1,4 @@
-use crate::BeforeFunction;
+use crate::{BeforeFunction, FunctionRequest};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -10,6 +10,20 @@ pub struct ReconciliationRequest {
     pub code_base64: String,
     pub args: serde_json::Value,
     pub before: Vec<BeforeFunction>,
+    /// How long the function may run for, in seconds, before it is killed. When unset, the
+    /// default for this kind of function applies.
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+}
+
+impl FunctionRequest for ReconciliationRequest {
+    fn execution_id(&self) -> &str {
+        &self.execution_id
+    }
+
+    fn timeout_secs(&self) -> Option<u64> {
+        self.timeout_secs
+    }
 }
 
 #[derive(Debug, Deserialize, Eq, PartialEq, Serialize)]
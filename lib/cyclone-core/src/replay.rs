@@ -0,0 +1,381 @@
+//! Self-contained bundles of recorded function executions, which can be re-run against a cyclone
+//! instance and compared with what was originally recorded.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    ActionRunRequest, ActionRunResultSuccess, BeforeFunction, OutputStream, ReconciliationRequest,
+    ReconciliationResultSuccess, ResolverFunctionRequest, ResolverFunctionResultSuccess,
+    SchemaVariantDefinitionRequest, SchemaVariantDefinitionResultSuccess,
+};
+
+/// The value every secret leaf in a before function's argument is replaced with when a bundle is
+/// created.
+pub const REDACTED_SECRET_PLACEHOLDER: &str = "[redacted]";
+
+/// The key of the execution id in recorded results, which differs between executions and so is
+/// never compared.
+const EXECUTION_ID_KEY: &str = "executionId";
+
+/// A recorded function execution, with everything needed to run it again.
+///
+/// Secrets passed to before functions are never included: each secret value is replaced with
+/// [`REDACTED_SECRET_PLACEHOLDER`] and its location is listed in
+/// [`redacted_secrets`](Self::redacted_secrets) so that it can be filled in with
+/// [`fill_secret`](Self::fill_secret) before replaying.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplayBundle {
+    pub func_run_id: String,
+    pub function_name: String,
+    pub request: ReplayRequest,
+    pub redacted_secrets: Vec<RedactedSecret>,
+    pub recorded: RecordedExecution,
+}
+
+impl ReplayBundle {
+    /// Creates a bundle, redacting any secrets in the request's before functions.
+    pub fn new(
+        func_run_id: impl Into<String>,
+        function_name: impl Into<String>,
+        mut request: ReplayRequest,
+        recorded: RecordedExecution,
+    ) -> Self {
+        let redacted_secrets = request
+            .before_mut()
+            .map(|before| redact_before_functions(before))
+            .unwrap_or_default();
+
+        Self {
+            func_run_id: func_run_id.into(),
+            function_name: function_name.into(),
+            request,
+            redacted_secrets,
+            recorded,
+        }
+    }
+
+    /// Replaces a redacted secret with its real value, returning `false` if the bundle has no
+    /// such secret.
+    pub fn fill_secret(&mut self, secret: &RedactedSecret, value: Value) -> bool {
+        let target = self
+            .request
+            .before_mut()
+            .and_then(|before| before.get_mut(secret.before_index))
+            .and_then(|before| before.arg.pointer_mut(&secret.pointer));
+
+        match target {
+            Some(target) => {
+                *target = value;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Compares the result of replaying the bundle with the recorded result, ignoring execution
+    /// ids.
+    pub fn compare(&self, replayed: Option<&Value>) -> Vec<ReplayDifference> {
+        let recorded = self.recorded.result.as_ref().map(comparable);
+        let replayed = replayed.map(comparable);
+
+        let mut differences = Vec::new();
+        diff_into(
+            &mut differences,
+            String::new(),
+            recorded.as_ref(),
+            replayed.as_ref(),
+        );
+        differences
+    }
+}
+
+/// The request sent to cyclone for a recorded execution.
+#[remain::sorted]
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(tag = "kind", content = "request", rename_all = "camelCase")]
+pub enum ReplayRequest {
+    ActionRun(ActionRunRequest),
+    Reconciliation(ReconciliationRequest),
+    ResolverFunction(ResolverFunctionRequest),
+    SchemaVariantDefinition(SchemaVariantDefinitionRequest),
+}
+
+impl ReplayRequest {
+    fn before_mut(&mut self) -> Option<&mut Vec<BeforeFunction>> {
+        match self {
+            Self::ActionRun(request) => Some(&mut request.before),
+            Self::Reconciliation(request) => Some(&mut request.before),
+            Self::ResolverFunction(request) => Some(&mut request.before),
+            Self::SchemaVariantDefinition(_) => None,
+        }
+    }
+}
+
+/// The location of a secret value removed from a bundle: a JSON pointer into the argument of one
+/// of the request's before functions.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RedactedSecret {
+    pub before_index: usize,
+    pub pointer: String,
+}
+
+/// What a function produced when it was originally run.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordedExecution {
+    /// The result as recorded for the function run, or `None` if it did not produce one.
+    pub result: Option<Value>,
+    pub output: Vec<OutputStream>,
+}
+
+/// A value that differs between a recorded and a replayed result.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplayDifference {
+    /// A JSON pointer to the differing value, which is empty for the whole result.
+    pub path: String,
+    /// The recorded value, or `None` if it was missing.
+    pub recorded: Option<Value>,
+    /// The replayed value, or `None` if it was missing.
+    pub replayed: Option<Value>,
+}
+
+/// Converts the successful result of a function into the form it is recorded in.
+pub trait ReplayResult {
+    fn into_recorded_value(self) -> Value;
+}
+
+impl ReplayResult for ActionRunResultSuccess {
+    fn into_recorded_value(self) -> Value {
+        serde_json::to_value(self).unwrap_or(Value::Null)
+    }
+}
+
+impl ReplayResult for ReconciliationResultSuccess {
+    fn into_recorded_value(self) -> Value {
+        let mut value = serde_json::json!({
+            "updates": self.updates,
+            "actions": self.actions,
+        });
+        if let (Some(message), Value::Object(map)) = (self.message, &mut value) {
+            map.insert("message".to_owned(), Value::String(message));
+        }
+        value
+    }
+}
+
+impl ReplayResult for ResolverFunctionResultSuccess {
+    fn into_recorded_value(self) -> Value {
+        self.data
+    }
+}
+
+impl ReplayResult for SchemaVariantDefinitionResultSuccess {
+    fn into_recorded_value(self) -> Value {
+        serde_json::json!({
+            "definition": self.definition,
+            "error": self.error,
+        })
+    }
+}
+
+/// Replaces every value in the before functions' arguments with [`REDACTED_SECRET_PLACEHOLDER`],
+/// returning where each one was.
+pub fn redact_before_functions(before: &mut [BeforeFunction]) -> Vec<RedactedSecret> {
+    let mut redacted = Vec::new();
+    for (before_index, before) in before.iter_mut().enumerate() {
+        redact_into(&mut redacted, before_index, String::new(), &mut before.arg);
+    }
+    redacted
+}
+
+fn redact_into(
+    redacted: &mut Vec<RedactedSecret>,
+    before_index: usize,
+    pointer: String,
+    value: &mut Value,
+) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                redact_into(
+                    redacted,
+                    before_index,
+                    format!("{pointer}/{}", escape_pointer_token(key)),
+                    value,
+                );
+            }
+        }
+        Value::Array(items) => {
+            for (index, value) in items.iter_mut().enumerate() {
+                redact_into(redacted, before_index, format!("{pointer}/{index}"), value);
+            }
+        }
+        Value::Null => {}
+        leaf => {
+            *leaf = Value::String(REDACTED_SECRET_PLACEHOLDER.to_owned());
+            redacted.push(RedactedSecret {
+                before_index,
+                pointer,
+            });
+        }
+    }
+}
+
+fn comparable(value: &Value) -> Value {
+    let mut value = value.to_owned();
+    if let Value::Object(map) = &mut value {
+        map.remove(EXECUTION_ID_KEY);
+    }
+    value
+}
+
+fn diff_into(
+    differences: &mut Vec<ReplayDifference>,
+    path: String,
+    recorded: Option<&Value>,
+    replayed: Option<&Value>,
+) {
+    match (recorded, replayed) {
+        (Some(Value::Object(recorded)), Some(Value::Object(replayed))) => {
+            let mut keys: Vec<&String> = recorded.keys().chain(replayed.keys()).collect();
+            keys.sort();
+            keys.dedup();
+            for key in keys {
+                diff_into(
+                    differences,
+                    format!("{path}/{}", escape_pointer_token(key)),
+                    recorded.get(key),
+                    replayed.get(key),
+                );
+            }
+        }
+        (Some(Value::Array(recorded)), Some(Value::Array(replayed))) => {
+            for index in 0..recorded.len().max(replayed.len()) {
+                diff_into(
+                    differences,
+                    format!("{path}/{index}"),
+                    recorded.get(index),
+                    replayed.get(index),
+                );
+            }
+        }
+        (recorded, replayed) if recorded == replayed => {}
+        (recorded, replayed) => differences.push(ReplayDifference {
+            path,
+            recorded: recorded.cloned(),
+            replayed: replayed.cloned(),
+        }),
+    }
+}
+
+fn escape_pointer_token(token: &str) -> String {
+    token.replace('~', "~0").replace('/', "~1")
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn action_request(before: Vec<BeforeFunction>) -> ReplayRequest {
+        ReplayRequest::ActionRun(ActionRunRequest {
+            execution_id: "execution".to_owned(),
+            handler: "main".to_owned(),
+            code_base64: "Y29kZQ==".to_owned(),
+            args: json!({}),
+            before,
+            timeout_secs: None,
+        })
+    }
+
+    fn bundle(result: Option<Value>) -> ReplayBundle {
+        ReplayBundle::new(
+            "run",
+            "func",
+            action_request(vec![BeforeFunction {
+                handler: "auth".to_owned(),
+                code_base64: "YXV0aA==".to_owned(),
+                arg: json!({ "credentials": { "a/b": "secret", "port": 22 }, "unset": null }),
+            }]),
+            RecordedExecution {
+                result,
+                output: Vec::new(),
+            },
+        )
+    }
+
+    #[test]
+    fn secrets_are_redacted_and_can_be_filled() {
+        let mut bundle = bundle(None);
+
+        assert_eq!(
+            vec![
+                RedactedSecret {
+                    before_index: 0,
+                    pointer: "/credentials/a~1b".to_owned(),
+                },
+                RedactedSecret {
+                    before_index: 0,
+                    pointer: "/credentials/port".to_owned(),
+                },
+            ],
+            bundle.redacted_secrets
+        );
+        let serialized = serde_json::to_string(&bundle).expect("failed to serialize bundle");
+        assert!(!serialized.contains("secret\""));
+
+        let secret = bundle.redacted_secrets[0].clone();
+        assert!(bundle.fill_secret(&secret, json!("filled")));
+        let ReplayRequest::ActionRun(request) = &bundle.request else {
+            unreachable!("bundle was created from an action request");
+        };
+        assert_eq!(
+            json!({ "credentials": { "a/b": "filled", "port": REDACTED_SECRET_PLACEHOLDER }, "unset": null }),
+            request.before[0].arg
+        );
+    }
+
+    #[test]
+    fn compare_reports_differences_by_path() {
+        let bundle = bundle(Some(json!({
+            "executionId": "recorded",
+            "payload": { "tags": ["a", "b"], "name": "same" },
+            "status": "ok",
+        })));
+
+        let replayed = json!({
+            "executionId": "replayed",
+            "payload": { "tags": ["a"], "name": "same" },
+            "status": "error",
+        });
+        assert_eq!(
+            vec![
+                ReplayDifference {
+                    path: "/payload/tags/1".to_owned(),
+                    recorded: Some(json!("b")),
+                    replayed: None,
+                },
+                ReplayDifference {
+                    path: "/status".to_owned(),
+                    recorded: Some(json!("ok")),
+                    replayed: Some(json!("error")),
+                },
+            ],
+            bundle.compare(Some(&replayed))
+        );
+
+        assert_eq!(
+            vec![ReplayDifference {
+                path: String::new(),
+                recorded: bundle.recorded.result.as_ref().map(comparable),
+                replayed: None,
+            }],
+            bundle.compare(None)
+        );
+    }
+}
@@ -23,3 +23,13 @@ impl<R> CycloneRequest<R> {
         (self.request, self.sensitive_strings.into())
     }
 }
+
+/// The parts of a function execution request which are acted on by Cyclone itself, rather than
+/// only being passed through to the language server.
+pub trait FunctionRequest {
+    /// The identifier for the execution of the function.
+    fn execution_id(&self) -> &str;
+
+    /// How long the function may run for, in seconds, if set on the request.
+    fn timeout_secs(&self) -> Option<u64>;
+}
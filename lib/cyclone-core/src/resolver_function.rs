@@ -2,7 +2,7 @@ use crate::before::BeforeFunction;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-use crate::ComponentView;
+use crate::{ComponentView, FunctionRequest};
 
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -13,6 +13,20 @@ pub struct ResolverFunctionRequest {
     pub response_type: ResolverFunctionResponseType,
     pub code_base64: String,
     pub before: Vec<BeforeFunction>,
+    /// How long the function may run for, in seconds, before it is killed. When unset, the
+    /// default for this kind of function applies.
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+}
+
+impl FunctionRequest for ResolverFunctionRequest {
+    fn execution_id(&self) -> &str {
+        &self.execution_id
+    }
+
+    fn timeout_secs(&self) -> Option<u64> {
+        self.timeout_secs
+    }
 }
 
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize, Default)]
@@ -1,11 +1,27 @@
 use serde::{Deserialize, Serialize};
 
+use crate::FunctionRequest;
+
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SchemaVariantDefinitionRequest {
     pub execution_id: String,
     pub handler: String,
     pub code_base64: String,
+    /// How long the function may run for, in seconds, before it is killed. When unset, the
+    /// default for this kind of function applies.
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+}
+
+impl FunctionRequest for SchemaVariantDefinitionRequest {
+    fn execution_id(&self) -> &str {
+        &self.execution_id
+    }
+
+    fn timeout_secs(&self) -> Option<u64> {
+        self.timeout_secs
+    }
 }
 
 #[derive(Debug, Deserialize, Eq, PartialEq, Serialize)]
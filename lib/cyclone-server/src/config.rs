@@ -54,6 +54,12 @@ pub struct Config {
 
     #[builder(setter(into), default)]
     limit_requests: Option<u32>,
+
+    #[builder(setter(into), default)]
+    limit_memory_mb: Option<u64>,
+
+    #[builder(setter(into), default)]
+    limit_cpu_secs: Option<u64>,
 }
 
 impl Config {
@@ -122,6 +128,18 @@ impl Config {
     pub fn limit_requests(&self) -> Option<u32> {
         self.limit_requests
     }
+
+    /// Gets the config's memory limit, in megabytes, for each language server process.
+    #[must_use]
+    pub fn limit_memory_mb(&self) -> Option<u64> {
+        self.limit_memory_mb
+    }
+
+    /// Gets the config's CPU time limit, in seconds, for each language server process.
+    #[must_use]
+    pub fn limit_cpu_secs(&self) -> Option<u64> {
+        self.limit_cpu_secs
+    }
 }
 
 impl ConfigBuilder {
@@ -1,8 +1,12 @@
 use std::{
-    fmt, io,
+    collections::BTreeMap,
+    fmt,
+    future::Future,
+    io,
     marker::{PhantomData, Unpin},
+    os::unix::process::ExitStatusExt,
     path::PathBuf,
-    process::Stdio,
+    process::{ExitStatus, Stdio},
     string::FromUtf8Error,
     sync::Arc,
     time::Duration,
@@ -11,11 +15,12 @@ use std::{
 use axum::extract::ws::WebSocket;
 use bytes_lines_codec::BytesLinesCodec;
 use cyclone_core::{
-    process::{self, ShutdownError},
-    CycloneRequest, FunctionResult, FunctionResultFailure, FunctionResultFailureError, Message,
-    OutputStream,
+    process::{self, ShutdownError, Signal},
+    CancelExecutionRequest, CycloneRequest, FunctionRequest, FunctionResult, FunctionResultFailure,
+    FunctionResultFailureError, Message, OutputStream,
 };
 use futures::{SinkExt, StreamExt, TryStreamExt};
+use nix::sys::resource::{setrlimit, Resource};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_json::Value;
 use si_crypto::SensitiveStrings;
@@ -37,11 +42,13 @@ const LANG_SERVER_PROCESS_TIMEOUT: Duration = Duration::from_secs(32 * 60);
 pub fn new<Request, LangServerSuccess, Success>(
     lang_server_path: impl Into<PathBuf>,
     lang_server_debugging: bool,
+    limits: ExecutionLimits,
     command: String,
 ) -> Execution<Request, LangServerSuccess, Success> {
     Execution {
         lang_server_path: lang_server_path.into(),
         lang_server_debugging,
+        limits,
         command,
         request_marker: PhantomData,
         lang_server_success_marker: PhantomData,
@@ -62,8 +69,6 @@ pub enum ExecutionError {
     ChildShutdown(#[from] ShutdownError),
     #[error("failed to spawn child process; program={0}")]
     ChildSpawn(#[source] io::Error, PathBuf),
-    #[error("child ran for to long")]
-    ChildTimeout,
     #[error("failed to decode string as utf8")]
     FromUtf8(#[from] FromUtf8Error),
     #[error("failed to deserialize json message")]
@@ -86,10 +91,147 @@ pub enum ExecutionError {
 
 type Result<T> = std::result::Result<T, ExecutionError>;
 
+/// Resource limits applied to each language server process.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct ExecutionLimits {
+    /// Maximum address space of the process, in megabytes.
+    pub memory_mb: Option<u64>,
+    /// Maximum CPU time of the process, in seconds.
+    pub cpu_secs: Option<u64>,
+}
+
+impl ExecutionLimits {
+    fn is_empty(&self) -> bool {
+        self.memory_mb.is_none() && self.cpu_secs.is_none()
+    }
+
+    /// Sets the limits as rlimits on the current process. This is called in the child process,
+    /// between fork and exec, so it must only make async-signal-safe calls.
+    fn apply(&self) -> io::Result<()> {
+        if let Some(memory_mb) = self.memory_mb {
+            let bytes = memory_mb.saturating_mul(1024 * 1024);
+            setrlimit(Resource::RLIMIT_AS, bytes, bytes)?;
+        }
+        if let Some(cpu_secs) = self.cpu_secs {
+            // The process is sent `SIGXCPU` at the soft limit and `SIGKILL` at the hard limit
+            setrlimit(Resource::RLIMIT_CPU, cpu_secs, cpu_secs.saturating_add(1))?;
+        }
+
+        Ok(())
+    }
+
+    /// Determines why a language server process was killed by a signal, returning the failure to
+    /// report for it, or `None` if the process exited of its own accord.
+    ///
+    /// A death is only put down to one of the limits when there is evidence for it, otherwise it
+    /// is reported as a plain kill. `out_of_memory` is whether the language server reported
+    /// running out of memory before it died and `cpu_time` is the CPU time it used, if known.
+    fn killed_by(
+        &self,
+        status: ExitStatus,
+        out_of_memory: bool,
+        cpu_time: Option<Duration>,
+    ) -> Option<FunctionResultFailureError> {
+        let signal = status.signal()?;
+        if let Some(cpu_secs) = self.cpu_secs {
+            // NOTE: `SIGKILL` is also sent at the hard CPU limit, but it could have come from
+            // anywhere, so it is only put down to the CPU limit when the process used up its CPU
+            // time.
+            if signal == Signal::SIGXCPU as i32
+                || cpu_time.is_some_and(|cpu_time| cpu_time.as_secs() >= cpu_secs)
+            {
+                return Some(FunctionResultFailureError::limit_exceeded(format!(
+                    "function exceeded its cpu time limit of {cpu_secs}s"
+                )));
+            }
+        }
+        // NOTE: running out of address space has no signal of its own--the language server
+        // aborts or crashes when an allocation fails--so a death by signal is only put down to
+        // the memory limit when the language server said that it ran out of memory.
+        if let Some(memory_mb) = self.memory_mb.filter(|_| out_of_memory) {
+            return Some(FunctionResultFailureError::limit_exceeded(format!(
+                "function exceeded its memory limit of {memory_mb}MB"
+            )));
+        }
+
+        let signal = Signal::try_from(signal)
+            .map(|signal| signal.to_string())
+            .unwrap_or_else(|_| signal.to_string());
+        Some(FunctionResultFailureError::killed(format!(
+            "function was killed by signal {signal}"
+        )))
+    }
+}
+
+/// Clock ticks per second in which `/proc` reports CPU times.
+///
+/// NOTE: this is `USER_HZ`, which is fixed at 100 by the kernel's ABI on the platforms we run on.
+const PROC_CLOCK_TICKS_PER_SEC: u64 = 100;
+
+/// Reads the CPU time used so far by a process from `/proc`, which still holds it for a process
+/// that has exited but not yet been waited on. Returns `None` where `/proc` isn't available.
+fn cpu_time(pid: u32) -> Option<Duration> {
+    let stat = std::fs::read_to_string(format!("/proc/{pid}/stat")).ok()?;
+    // The command name is in parentheses and may contain spaces, so fields are counted from the
+    // closing parenthesis, after which `utime` and `stime` are the 12th and 13th
+    let mut fields = stat
+        .get(stat.rfind(')')? + 1..)?
+        .split_whitespace()
+        .skip(11);
+    let utime: u64 = fields.next()?.parse().ok()?;
+    let stime: u64 = fields.next()?.parse().ok()?;
+
+    Some(Duration::from_millis(
+        (utime + stime) * 1000 / PROC_CLOCK_TICKS_PER_SEC,
+    ))
+}
+
+/// Whether a line written to stderr by the language server reports that it ran out of memory, as
+/// the JavaScript runtime does when an allocation fails.
+fn is_out_of_memory_message(line: &str) -> bool {
+    let line = line.to_ascii_lowercase();
+    [
+        "out of memory",
+        "allocation failed",
+        "cannot allocate memory",
+        "bad_alloc",
+    ]
+    .iter()
+    .any(|message| line.contains(message))
+}
+
+/// How the part of an execution in which the language server runs came to an end.
+#[derive(Debug, Eq, PartialEq)]
+enum ExecutionEnd {
+    /// The language server finished writing its output.
+    Finished,
+    /// The client asked for the execution to be cancelled.
+    Cancelled(CancelExecutionRequest),
+    /// The execution ran for longer than its timeout.
+    TimedOut,
+}
+
+impl ExecutionEnd {
+    /// Runs the loop receiving the language server's output until it finishes, is cancelled or
+    /// runs out of time.
+    async fn wait_for(
+        execution_timeout: Duration,
+        receive_loop: impl Future<Output = Result<Option<CancelExecutionRequest>>>,
+    ) -> Result<Self> {
+        match timeout(execution_timeout, receive_loop).await {
+            Ok(Ok(None)) => Ok(Self::Finished),
+            Ok(Ok(Some(cancel))) => Ok(Self::Cancelled(cancel)),
+            Ok(Err(err)) => Err(err),
+            Err(_) => Ok(Self::TimedOut),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct Execution<Request, LangServerSuccess, Success> {
     lang_server_path: PathBuf,
     lang_server_debugging: bool,
+    limits: ExecutionLimits,
     command: String,
     request_marker: PhantomData<Request>,
     lang_server_success_marker: PhantomData<LangServerSuccess>,
@@ -98,7 +240,7 @@ pub struct Execution<Request, LangServerSuccess, Success> {
 
 impl<Request, LangServerSuccess, Success> Execution<Request, LangServerSuccess, Success>
 where
-    Request: Serialize + DeserializeOwned + Unpin + core::fmt::Debug,
+    Request: Serialize + DeserializeOwned + Unpin + core::fmt::Debug + FunctionRequest,
     LangServerSuccess: DeserializeOwned,
     Success: Serialize,
 {
@@ -111,6 +253,11 @@ where
         // Read the request message from the web socket
         let cyclone_request = Self::read_request(ws).await?;
         let (request, sensitive_strings) = cyclone_request.into_parts();
+        let execution_id = request.execution_id().to_owned();
+        let timeout = request
+            .timeout_secs()
+            .map(Duration::from_secs)
+            .unwrap_or(LANG_SERVER_PROCESS_TIMEOUT);
 
         // Spawn lang server as a child process with handles on all i/o descriptors
         let mut command = Command::new(&self.lang_server_path);
@@ -122,6 +269,13 @@ where
         if self.lang_server_debugging {
             command.env("SI_LANG_JS_LOG", "*");
         }
+        if !self.limits.is_empty() {
+            let limits = self.limits;
+            // SAFETY: `setrlimit` is async-signal-safe and nothing is allocated in the closure
+            unsafe {
+                command.pre_exec(move || limits.apply());
+            }
+        }
         debug!(cmd = ?command, "spawning child process");
         let mut child = command
             .spawn()
@@ -152,6 +306,9 @@ where
             stdout,
             stderr,
             sensitive_strings: Arc::new(sensitive_strings),
+            execution_id,
+            timeout,
+            limits: self.limits,
             success_marker: self.success_marker,
         })
     }
@@ -210,30 +367,43 @@ pub struct ExecutionStarted<LangServerSuccess, Success> {
     stdout: SiFramed<SiMessage<LangServerSuccess>>,
     stderr: FramedRead<ChildStderr, BytesLinesCodec>,
     sensitive_strings: Arc<SensitiveStrings>,
+    execution_id: String,
+    timeout: Duration,
+    limits: ExecutionLimits,
     success_marker: PhantomData<Success>,
 }
 
 // TODO: implement shutdown oneshot
+/// Forwards the language server's stderr, redacted, and returns whether the language server
+/// reported running out of memory.
 async fn handle_stderr(
     stderr: FramedRead<ChildStderr, BytesLinesCodec>,
     sensitive_strings: Arc<SensitiveStrings>,
-) {
+) -> bool {
     async fn handle_stderr_fallible(
         mut stderr: FramedRead<ChildStderr, BytesLinesCodec>,
         sensitive_strings: Arc<SensitiveStrings>,
+        out_of_memory: &mut bool,
     ) -> Result<()> {
         while let Some(line) = stderr.next().await {
             let line = line.map_err(ExecutionError::ChildRecvIO)?;
             let line = String::from_utf8(line.to_vec())?;
             let line = sensitive_strings.redact(line.as_ref());
+            if is_out_of_memory_message(&line) {
+                *out_of_memory = true;
+            }
 
             eprintln!("{line}");
         }
         Ok(())
     }
-    if let Err(error) = handle_stderr_fallible(stderr, sensitive_strings).await {
+
+    let mut out_of_memory = false;
+    if let Err(error) = handle_stderr_fallible(stderr, sensitive_strings, &mut out_of_memory).await
+    {
         error!("Unable to collect stderr: {}", error);
     }
+    out_of_memory
 }
 
 impl<LangServerSuccess, Success> ExecutionStarted<LangServerSuccess, Success>
@@ -244,8 +414,10 @@ where
     SiDecoderError: From<SiJsonError<LangServerSuccess>>,
 {
     pub async fn process(mut self, ws: &mut WebSocket) -> Result<ExecutionClosing<Success>> {
-        tokio::spawn(handle_stderr(self.stderr, self.sensitive_strings.clone()));
+        let stderr_handle =
+            tokio::spawn(handle_stderr(self.stderr, self.sensitive_strings.clone()));
 
+        let mut received_result = false;
         let mut stream = self
             .stdout
             .map(|ls_result| match ls_result {
@@ -262,6 +434,11 @@ where
                 },
                 Err(err) => Err(ExecutionError::ChildRecvIO(err)),
             })
+            .inspect(|msg_result| {
+                if let Ok(Message::Result(_)) = msg_result {
+                    received_result = true;
+                }
+            })
             .map(|msg_result: Result<_>| match msg_result {
                 Ok(msg) => match msg
                     .serialize_to_string()
@@ -274,24 +451,94 @@ where
             });
 
         let receive_loop = async {
-            while let Some(msg) = stream.try_next().await? {
-                ws.send(msg).await.map_err(ExecutionError::WSSendIO)?;
+            let mut ws_open = true;
+            loop {
+                tokio::select! {
+                    msg = stream.try_next() => match msg? {
+                        Some(msg) => ws.send(msg).await.map_err(ExecutionError::WSSendIO)?,
+                        None => break,
+                    },
+                    // Listen for a cancellation from the client while the function runs
+                    incoming = ws.recv(), if ws_open => match incoming {
+                        Some(Ok(WebSocketMessage::Text(json_str))) => {
+                            match serde_json::from_str::<CancelExecutionRequest>(&json_str) {
+                                Ok(cancel) => return Ok(Some(cancel)),
+                                Err(err) => warn!(
+                                    error = ?err,
+                                    "received unexpected message during execution",
+                                ),
+                            }
+                        }
+                        Some(Ok(_)) => {}
+                        Some(Err(_)) | None => ws_open = false,
+                    },
+                }
             }
 
-            Result::<_>::Ok(())
+            Result::<_>::Ok(None)
         };
 
-        match timeout(LANG_SERVER_PROCESS_TIMEOUT, receive_loop).await {
-            Ok(execution) => execution?,
-            Err(_) => {
+        match ExecutionEnd::wait_for(self.timeout, receive_loop).await? {
+            ExecutionEnd::Finished => {}
+            ExecutionEnd::Cancelled(cancel) => {
+                debug!(
+                    execution_id = cancel.execution_id.as_str(),
+                    "cancelling execution"
+                );
+                process::child_shutdown(&mut self.child, Some(process::Signal::SIGTERM), None)
+                    .await?;
+
+                Self::ws_send_failure(
+                    ws,
+                    self.execution_id,
+                    FunctionResultFailureError::cancelled("function execution was cancelled"),
+                )
+                .await?;
+
+                return Ok(ExecutionClosing {
+                    child: self.child,
+                    success_marker: PhantomData,
+                });
+            }
+            ExecutionEnd::TimedOut => {
                 // Exceeded timeout, shutdown child process
                 process::child_shutdown(&mut self.child, Some(process::Signal::SIGTERM), None)
                     .await?;
-                drop(self.child);
 
-                return Err(ExecutionError::ChildTimeout);
+                let message = format!(
+                    "function exceeded its timeout of {}s",
+                    self.timeout.as_secs()
+                );
+                Self::ws_send_failure(
+                    ws,
+                    self.execution_id,
+                    FunctionResultFailureError::limit_exceeded(message),
+                )
+                .await?;
+
+                return Ok(ExecutionClosing {
+                    child: self.child,
+                    success_marker: PhantomData,
+                });
             }
         };
+        drop(stream);
+
+        // If the language server went away without a result, check whether it was killed, and
+        // whether for exceeding one of its resource limits, so that we can report it as such
+        if !received_result && !self.limits.is_empty() {
+            // Read before the process is waited on, after which its CPU time is gone
+            let cpu_time = self.child.id().and_then(cpu_time);
+            let status = process::child_shutdown(&mut self.child, None, None).await?;
+            // The language server's stderr closes when it exits, so it can be read to the end
+            let out_of_memory = matches!(
+                time::timeout(TX_TIMEOUT_SECS, stderr_handle).await,
+                Ok(Ok(true))
+            );
+            if let Some(error) = self.limits.killed_by(status, out_of_memory, cpu_time) {
+                Self::ws_send_failure(ws, self.execution_id, error).await?;
+            }
+        }
 
         Ok(ExecutionClosing {
             child: self.child,
@@ -299,6 +546,26 @@ where
         })
     }
 
+    async fn ws_send_failure(
+        ws: &mut WebSocket,
+        execution_id: String,
+        error: FunctionResultFailureError,
+    ) -> Result<()> {
+        let msg = Message::<Success>::Result(FunctionResult::Failure(FunctionResultFailure {
+            execution_id,
+            error,
+            timestamp: crate::timestamp(),
+        }))
+        .serialize_to_string()
+        .map_err(ExecutionError::JSONSerialize)?;
+
+        time::timeout(TX_TIMEOUT_SECS, ws.send(WebSocketMessage::Text(msg)))
+            .await
+            .map_err(ExecutionError::SendTimeout)?
+            .map_err(ExecutionError::WSSendIO)?;
+        Ok(())
+    }
+
     fn filter_output(
         output: &mut LangServerOutput,
         sensitive_strings: &SensitiveStrings,
@@ -421,6 +688,8 @@ pub struct LangServerOutput {
     level: String,
     group: Option<String>,
     message: String,
+    #[serde(default)]
+    fields: BTreeMap<String, String>,
 }
 
 impl From<LangServerOutput> for OutputStream {
@@ -432,6 +701,7 @@ impl From<LangServerOutput> for OutputStream {
             group: value.group,
             message: value.message,
             timestamp: crate::timestamp(),
+            fields: value.fields,
         }
     }
 }
@@ -478,3 +748,158 @@ struct LangServerFailureError {
     kind: String,
     message: String,
 }
+
+#[cfg(test)]
+mod tests {
+    use std::future;
+
+    use super::*;
+
+    const LIMITS: ExecutionLimits = ExecutionLimits {
+        memory_mb: Some(64),
+        cpu_secs: Some(5),
+    };
+
+    fn killed_by(signal: Signal) -> ExitStatus {
+        ExitStatus::from_raw(signal as i32)
+    }
+
+    fn limit_exceeded(message: &str) -> Option<FunctionResultFailureError> {
+        Some(FunctionResultFailureError::limit_exceeded(message))
+    }
+
+    #[test]
+    fn exiting_is_not_a_kill() {
+        let exited_with_failure = ExitStatus::from_raw(1 << 8);
+
+        assert_eq!(None, LIMITS.killed_by(exited_with_failure, true, None));
+    }
+
+    #[test]
+    fn cpu_signal_exceeds_the_cpu_limit() {
+        assert_eq!(
+            limit_exceeded("function exceeded its cpu time limit of 5s"),
+            LIMITS.killed_by(killed_by(Signal::SIGXCPU), false, None)
+        );
+    }
+
+    #[test]
+    fn kill_after_using_up_cpu_time_exceeds_the_cpu_limit() {
+        assert_eq!(
+            limit_exceeded("function exceeded its cpu time limit of 5s"),
+            LIMITS.killed_by(
+                killed_by(Signal::SIGKILL),
+                false,
+                Some(Duration::from_millis(6010))
+            )
+        );
+    }
+
+    #[test]
+    fn kill_without_evidence_is_a_plain_kill() {
+        let killed = Some(FunctionResultFailureError::killed(
+            "function was killed by signal SIGKILL",
+        ));
+
+        assert_eq!(
+            killed,
+            LIMITS.killed_by(killed_by(Signal::SIGKILL), false, None)
+        );
+        assert_eq!(
+            killed,
+            LIMITS.killed_by(
+                killed_by(Signal::SIGKILL),
+                false,
+                Some(Duration::from_millis(1200))
+            )
+        );
+    }
+
+    #[test]
+    fn signals_without_running_out_of_memory_do_not_exceed_the_memory_limit() {
+        let limits = ExecutionLimits {
+            memory_mb: Some(64),
+            cpu_secs: None,
+        };
+
+        assert_eq!(
+            Some(FunctionResultFailureError::killed(
+                "function was killed by signal SIGSEGV"
+            )),
+            limits.killed_by(killed_by(Signal::SIGSEGV), false, None)
+        );
+    }
+
+    #[test]
+    fn signals_after_running_out_of_memory_exceed_the_memory_limit() {
+        assert_eq!(
+            limit_exceeded("function exceeded its memory limit of 64MB"),
+            LIMITS.killed_by(killed_by(Signal::SIGABRT), true, None)
+        );
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn reads_cpu_time_of_a_running_process() {
+        assert!(cpu_time(std::process::id()).is_some());
+    }
+
+    #[test]
+    fn recognizes_out_of_memory_messages() {
+        assert!(is_out_of_memory_message(
+            "FATAL ERROR: Reached heap limit Allocation failed - JavaScript heap out of memory"
+        ));
+        assert!(is_out_of_memory_message(
+            "Fatal process out of memory: Failed to reserve virtual memory for CodeRange"
+        ));
+        assert!(!is_out_of_memory_message(
+            "TypeError: memory is not defined"
+        ));
+    }
+
+    #[tokio::test]
+    async fn execution_ends_when_output_finishes() {
+        let end = ExecutionEnd::wait_for(Duration::from_secs(5), async { Ok(None) })
+            .await
+            .expect("failed to wait for execution");
+
+        assert_eq!(ExecutionEnd::Finished, end);
+    }
+
+    #[tokio::test]
+    async fn execution_ends_when_cancelled() {
+        let cancel = CancelExecutionRequest {
+            execution_id: "ringo".to_string(),
+        };
+
+        let end = ExecutionEnd::wait_for(Duration::from_secs(5), async { Ok(Some(cancel)) })
+            .await
+            .expect("failed to wait for execution");
+
+        assert_eq!(
+            ExecutionEnd::Cancelled(CancelExecutionRequest {
+                execution_id: "ringo".to_string(),
+            }),
+            end
+        );
+    }
+
+    #[tokio::test]
+    async fn execution_ends_when_it_times_out() {
+        let end = ExecutionEnd::wait_for(Duration::from_millis(10), future::pending())
+            .await
+            .expect("failed to wait for execution");
+
+        assert_eq!(ExecutionEnd::TimedOut, end);
+    }
+
+    #[tokio::test]
+    async fn execution_fails_when_receiving_fails() {
+        let result = ExecutionEnd::wait_for(Duration::from_secs(5), async {
+            Err(ExecutionError::WSRecvClosed)
+        })
+        .await;
+
+        assert!(matches!(result, Err(ExecutionError::WSRecvClosed)));
+    }
+}
@@ -13,8 +13,8 @@ use axum::{
     response::IntoResponse,
 };
 use cyclone_core::{
-    ActionRunRequest, ActionRunResultSuccess, LivenessStatus, Message, ReadinessStatus,
-    ReconciliationRequest, ReconciliationResultSuccess, ResolverFunctionRequest,
+    ActionRunRequest, ActionRunResultSuccess, FunctionRequest, LivenessStatus, Message,
+    ReadinessStatus, ReconciliationRequest, ReconciliationResultSuccess, ResolverFunctionRequest,
     ResolverFunctionResultSuccess, SchemaVariantDefinitionRequest,
     SchemaVariantDefinitionResultSuccess, ValidationRequest, ValidationResultSuccess,
 };
@@ -25,7 +25,7 @@ use telemetry_http::ParentSpan;
 
 use super::extract::LimitRequestGuard;
 use crate::{
-    execution::{self, Execution},
+    execution::{self, Execution, ExecutionLimits},
     result::{
         LangServerActionRunResultSuccess, LangServerReconciliationResultSuccess,
         LangServerResolverFunctionResultSuccess, LangServerValidationResultSuccess,
@@ -102,6 +102,7 @@ pub async fn ws_execute_resolver(
     wsu: WebSocketUpgrade,
     State(lang_server_path): State<LangServerPath>,
     State(telemetry_level): State<TelemetryLevel>,
+    State(execution_limits): State<ExecutionLimits>,
     limit_request_guard: LimitRequestGuard,
     Extension(request_span): Extension<ParentSpan>,
 ) -> impl IntoResponse {
@@ -115,6 +116,7 @@ pub async fn ws_execute_resolver(
             socket,
             lang_server_path,
             telemetry_level,
+            execution_limits,
             limit_request_guard,
             "resolverfunction".to_owned(),
             request,
@@ -130,6 +132,7 @@ pub async fn ws_execute_validation(
     wsu: WebSocketUpgrade,
     State(lang_server_path): State<LangServerPath>,
     State(telemetry_level): State<TelemetryLevel>,
+    State(execution_limits): State<ExecutionLimits>,
     limit_request_guard: LimitRequestGuard,
     Extension(request_span): Extension<ParentSpan>,
 ) -> impl IntoResponse {
@@ -143,6 +146,7 @@ pub async fn ws_execute_validation(
             socket,
             lang_server_path,
             telemetry_level,
+            execution_limits,
             limit_request_guard,
             "validation".to_owned(),
             request,
@@ -158,6 +162,7 @@ pub async fn ws_execute_action_run(
     wsu: WebSocketUpgrade,
     State(lang_server_path): State<LangServerPath>,
     State(telemetry_level): State<TelemetryLevel>,
+    State(execution_limits): State<ExecutionLimits>,
     limit_request_guard: LimitRequestGuard,
     Extension(request_span): Extension<ParentSpan>,
 ) -> impl IntoResponse {
@@ -171,6 +176,7 @@ pub async fn ws_execute_action_run(
             socket,
             lang_server_path,
             telemetry_level,
+            execution_limits,
             limit_request_guard,
             "actionRun".to_owned(),
             request,
@@ -186,6 +192,7 @@ pub async fn ws_execute_reconciliation(
     wsu: WebSocketUpgrade,
     State(lang_server_path): State<LangServerPath>,
     State(telemetry_level): State<TelemetryLevel>,
+    State(execution_limits): State<ExecutionLimits>,
     limit_request_guard: LimitRequestGuard,
     Extension(request_span): Extension<ParentSpan>,
 ) -> impl IntoResponse {
@@ -199,6 +206,7 @@ pub async fn ws_execute_reconciliation(
             socket,
             lang_server_path,
             telemetry_level,
+            execution_limits,
             limit_request_guard,
             "reconciliation".to_owned(),
             request,
@@ -214,6 +222,7 @@ pub async fn ws_execute_schema_variant_definition(
     wsu: WebSocketUpgrade,
     State(lang_server_path): State<LangServerPath>,
     State(telemetry_level): State<TelemetryLevel>,
+    State(execution_limits): State<ExecutionLimits>,
     limit_request_guard: LimitRequestGuard,
     Extension(request_span): Extension<ParentSpan>,
 ) -> impl IntoResponse {
@@ -227,6 +236,7 @@ pub async fn ws_execute_schema_variant_definition(
             socket,
             lang_server_path,
             telemetry_level,
+            execution_limits,
             limit_request_guard,
             "schemaVariantDefinition".to_owned(),
             request,
@@ -249,6 +259,7 @@ async fn handle_socket<Request, LangServerSuccess, Success>(
     mut socket: WebSocket,
     lang_server_path: PathBuf,
     lang_server_debugging: bool,
+    execution_limits: ExecutionLimits,
     _limit_request_guard: LimitRequestGuard,
     sub_command: String,
     _request_marker: PhantomData<Request>,
@@ -256,13 +267,17 @@ async fn handle_socket<Request, LangServerSuccess, Success>(
     success_marker: PhantomData<Success>,
     request_span: Span,
 ) where
-    Request: Serialize + DeserializeOwned + Unpin + fmt::Debug,
+    Request: Serialize + DeserializeOwned + Unpin + fmt::Debug + FunctionRequest,
     Success: Serialize + Unpin + fmt::Debug,
     LangServerSuccess: Serialize + DeserializeOwned + Unpin + fmt::Debug + Into<Success>,
 {
     let proto = {
-        let execution: Execution<Request, LangServerSuccess, Success> =
-            execution::new(lang_server_path, lang_server_debugging, sub_command);
+        let execution: Execution<Request, LangServerSuccess, Success> = execution::new(
+            lang_server_path,
+            lang_server_debugging,
+            execution_limits,
+            sub_command,
+        );
         match execution.start(&mut socket).await {
             Ok(started) => started,
             Err(err) => {
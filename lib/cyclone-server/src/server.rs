@@ -16,8 +16,8 @@ use tokio::{
 };
 
 use crate::{
-    routes::routes, state::AppState, Config, IncomingStream, UdsIncomingStream,
-    UdsIncomingStreamError,
+    execution::ExecutionLimits, routes::routes, state::AppState, Config, IncomingStream,
+    UdsIncomingStream, UdsIncomingStreamError,
 };
 
 #[cfg(target_os = "linux")]
@@ -214,7 +214,11 @@ fn build_service(
 ) -> Result<(IntoMakeService<Router>, oneshot::Receiver<()>)> {
     let (shutdown_tx, shutdown_rx) = mpsc::channel(4);
 
-    let state = AppState::new(config.lang_server_path(), telemetry_level);
+    let execution_limits = ExecutionLimits {
+        memory_mb: config.limit_memory_mb(),
+        cpu_secs: config.limit_cpu_secs(),
+    };
+    let state = AppState::new(config.lang_server_path(), telemetry_level, execution_limits);
 
     let routes = routes(config, state, shutdown_tx);
 
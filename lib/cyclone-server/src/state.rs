@@ -8,20 +8,25 @@ use std::{
 use axum::extract::FromRef;
 use tokio::sync::mpsc;
 
+use crate::execution::ExecutionLimits;
+
 #[derive(Clone, FromRef)]
 pub struct AppState {
     lang_server_path: LangServerPath,
     telemetry_level: TelemetryLevel,
+    execution_limits: ExecutionLimits,
 }
 
 impl AppState {
     pub fn new(
         lang_server_path: impl Into<PathBuf>,
         telemetry_level: Box<dyn telemetry::TelemetryLevel>,
+        execution_limits: ExecutionLimits,
     ) -> Self {
         Self {
             lang_server_path: LangServerPath(Arc::new(lang_server_path.into())),
             telemetry_level: TelemetryLevel(Arc::new(telemetry_level)),
+            execution_limits,
         }
     }
 }
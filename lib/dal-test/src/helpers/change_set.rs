@@ -42,7 +42,7 @@ impl ChangeSetTestHelpers {
                     ActionState::Dispatched | ActionState::Queued | ActionState::Running => {
                         still_active = true;
                     }
-                    ActionState::Failed | ActionState::OnHold => {}
+                    ActionState::Failed | ActionState::OnHold | ActionState::Cancelled => {}
                 }
             }
             if !still_active {
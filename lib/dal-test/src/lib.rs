@@ -583,9 +583,13 @@ pub fn pinga_server(services_context: ServicesContext) -> Result<pinga_server::S
             .wrap_err("failed to build Pinga server config")?
     };
 
+    // Tests enqueue their own refresh actions, so the refresh scheduler is not started, and test
+    // databases are thrown away, so nothing needs to be retained for a limited time either
     let server = pinga_server::Server::from_services(
         config.instance_id(),
         config.concurrency(),
+        None,
+        Default::default(),
         services_context,
     )
     .wrap_err("failed to create Pinga server")?;
@@ -609,6 +613,8 @@ pub fn rebaser_server(
         shutdown_token,
         // a huge interval, to prevent dvu debouncer from running dvus in tests
         std::time::Duration::from_secs(10000),
+        config.concurrency_limit(),
+        config.snapshot_checkpoint_interval(),
     )
     .wrap_err("failed to create Rebaser server")?;
 
@@ -1,13 +1,15 @@
 use std::collections::{HashSet, VecDeque};
+use std::sync::Arc;
 
 use petgraph::prelude::*;
 use postgres_types::{FromSql, ToSql};
 use serde::{Deserialize, Serialize};
-use si_events::ulid::Ulid;
+use si_events::{ulid::Ulid, FuncRunState};
 use si_layer_cache::LayerDbError;
 use strum::{AsRefStr, Display, EnumDiscriminants, EnumIter, EnumString};
 use telemetry::prelude::*;
 use thiserror::Error;
+use veritech_client::CancelExecutionRequest;
 
 use crate::{
     action::{
@@ -31,6 +33,7 @@ use crate::{
 
 pub mod dependency_graph;
 pub mod prototype;
+pub mod refresh_schedule;
 
 #[remain::sorted]
 #[derive(Debug, Error)]
@@ -49,16 +52,22 @@ pub enum ActionError {
     EdgeWeight(#[from] EdgeWeightError),
     #[error("Helper error: {0}")]
     Helper(#[from] HelperError),
+    #[error("only running actions can be cancelled: {0}")]
+    InvalidCancelRunningTransition(ActionId),
     #[error("Layer DB error: {0}")]
     LayerDb(#[from] LayerDbError),
     #[error("Node Weight error: {0}")]
     NodeWeight(#[from] NodeWeightError),
+    #[error("no func run in flight for action: {0}")]
+    NoFuncRunInFlightForAction(ActionId),
     #[error("prototype not found for action: {0}")]
     PrototypeNotFoundForAction(ActionId),
     #[error("Transactions error: {0}")]
     Transactions(#[from] TransactionsError),
     #[error("Unable to determine kind for action: {0}")]
     UnableToGetKind(ActionId),
+    #[error("veritech client error: {0}")]
+    VeritechClient(#[from] veritech_client::ClientError),
     #[error("Workspace Snapshot error: {0}")]
     WorkspaceSnapshot(#[from] WorkspaceSnapshotError),
     #[error("ws event error: {0}")]
@@ -100,6 +109,9 @@ pub enum ActionState {
     /// Action has been dispatched, and started execution in the job system. See the job history
     /// for details.
     Running,
+    /// Action was cancelled by a user while it was running. Like a failed action, it must be
+    /// retried or removed.
+    Cancelled,
 }
 
 /// The completion status of a [`ActionRunner`]
@@ -393,6 +405,50 @@ impl Action {
         Ok(new_action)
     }
 
+    /// Cancels a running [`Action`]: veritech is asked to kill the in-flight execution of its
+    /// function, and both the [`Action`] and the [`FuncRun`](si_events::FuncRun) are moved into
+    /// their cancelled states.
+    #[instrument(level = "info", skip(ctx))]
+    pub async fn cancel_running(ctx: &DalContext, id: ActionId) -> ActionResult<()> {
+        let action = Self::get_by_id(ctx, id).await?;
+        if action.state() != ActionState::Running {
+            return Err(ActionError::InvalidCancelRunningTransition(id));
+        }
+
+        let func_run = ctx
+            .layer_db()
+            .func_run()
+            .get_last_run_for_action_id(ctx.events_tenancy().workspace_pk, id.into())
+            .await?
+            .filter(|func_run| {
+                matches!(
+                    func_run.state(),
+                    FuncRunState::Created | FuncRunState::Dispatched | FuncRunState::Running
+                )
+            })
+            .ok_or(ActionError::NoFuncRunInFlightForAction(id))?;
+
+        ctx.veritech()
+            .cancel_execution(&CancelExecutionRequest {
+                execution_id: func_run.id().to_string(),
+            })
+            .await?;
+
+        let mut cancelled_func_run = func_run;
+        cancelled_func_run.set_state_to_cancelled();
+        ctx.layer_db()
+            .func_run()
+            .write(
+                Arc::new(cancelled_func_run),
+                None,
+                ctx.events_tenancy(),
+                ctx.events_actor(),
+            )
+            .await?;
+
+        Self::set_state(ctx, id, ActionState::Cancelled).await
+    }
+
     pub async fn remove_by_id(ctx: &DalContext, action_id: ActionId) -> ActionResult<()> {
         ctx.workspace_snapshot()?
             .remove_node_by_id(ctx.vector_clock_id()?, action_id)
@@ -549,7 +605,9 @@ impl Action {
         while let Some(action_id) = work_queue.pop_front() {
             let act = Self::get_by_id(ctx, action_id).await?;
             match act.state() {
-                ActionState::Failed | ActionState::OnHold => reasons_for_hold.push(act.id()),
+                ActionState::Failed | ActionState::OnHold | ActionState::Cancelled => {
+                    reasons_for_hold.push(act.id())
+                }
                 _ => (),
             }
             work_queue.extend(Self::get_dependent_actions_by_id(ctx, action_id).await?);
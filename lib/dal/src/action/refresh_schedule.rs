@@ -0,0 +1,237 @@
+//! This module contains [`RefreshSchedule`], which describes how often the resources in a
+//! [`Workspace`](crate::Workspace) should be refreshed in the background.
+//!
+//! Schedules are stored in the database rather than in the snapshot since they are workspace
+//! configuration, not modeled data. A scheduler (see pinga) periodically calls
+//! [`RefreshSchedule::list_due`] and, for each due workspace, claims the schedule and enqueues
+//! [`ActionKind::Refresh`] actions on HEAD with [`RefreshSchedule::claim_and_enqueue`]. The claim
+//! and the actions are committed together, so a failed enqueue leaves the schedule due.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use si_data_pg::{PgError, PgRow};
+use telemetry::prelude::*;
+use thiserror::Error;
+
+use crate::action::prototype::{ActionKind, ActionPrototype, ActionPrototypeError};
+use crate::action::{Action, ActionError, ActionId};
+use crate::{
+    Component, ComponentError, DalContext, TransactionsError, WorkspacePk, WsEvent, WsEventError,
+};
+
+/// The shortest interval, in seconds, that a [`RefreshSchedule`] can be configured with.
+pub const MINIMUM_REFRESH_INTERVAL_SECONDS: i64 = 60;
+
+/// The interval, in seconds, used when a schedule is created without one.
+pub const DEFAULT_REFRESH_INTERVAL_SECONDS: i64 = 3600;
+
+#[remain::sorted]
+#[derive(Debug, Error)]
+pub enum RefreshScheduleError {
+    #[error("action error: {0}")]
+    Action(#[from] ActionError),
+    #[error("action prototype error: {0}")]
+    ActionPrototype(#[from] ActionPrototypeError),
+    #[error("component error: {0}")]
+    Component(#[from] ComponentError),
+    #[error("refresh interval of {0} seconds is below the minimum of {MINIMUM_REFRESH_INTERVAL_SECONDS} seconds")]
+    IntervalTooShort(i64),
+    #[error("no workspace in tenancy")]
+    NoWorkspaceInTenancy,
+    #[error("pg error: {0}")]
+    Pg(#[from] PgError),
+    #[error("transactions error: {0}")]
+    Transactions(#[from] TransactionsError),
+    #[error("ws event error: {0}")]
+    WsEvent(#[from] WsEventError),
+}
+
+pub type RefreshScheduleResult<T> = Result<T, RefreshScheduleError>;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RefreshSchedule {
+    pub workspace_pk: WorkspacePk,
+    pub enabled: bool,
+    pub interval_seconds: i64,
+    pub last_enqueued_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl TryFrom<PgRow> for RefreshSchedule {
+    type Error = RefreshScheduleError;
+
+    fn try_from(row: PgRow) -> Result<Self, Self::Error> {
+        Ok(Self {
+            workspace_pk: row.try_get("workspace_pk")?,
+            enabled: row.try_get("enabled")?,
+            interval_seconds: row.try_get("interval_seconds")?,
+            last_enqueued_at: row.try_get("last_enqueued_at")?,
+            created_at: row.try_get("created_at")?,
+            updated_at: row.try_get("updated_at")?,
+        })
+    }
+}
+
+impl RefreshSchedule {
+    /// Returns the schedule for the [`Workspace`](crate::Workspace) in the current tenancy, if one
+    /// has been configured.
+    pub async fn get_for_workspace(ctx: &DalContext) -> RefreshScheduleResult<Option<Self>> {
+        let workspace_pk = Self::workspace_pk(ctx)?;
+        let maybe_row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_opt(
+                "SELECT * FROM workspace_refresh_schedules WHERE workspace_pk = $1",
+                &[&workspace_pk],
+            )
+            .await?;
+
+        maybe_row.map(Self::try_from).transpose()
+    }
+
+    /// Creates or updates the schedule for the [`Workspace`](crate::Workspace) in the current
+    /// tenancy.
+    pub async fn upsert(
+        ctx: &DalContext,
+        enabled: bool,
+        interval_seconds: i64,
+    ) -> RefreshScheduleResult<Self> {
+        if interval_seconds < MINIMUM_REFRESH_INTERVAL_SECONDS {
+            return Err(RefreshScheduleError::IntervalTooShort(interval_seconds));
+        }
+
+        let workspace_pk = Self::workspace_pk(ctx)?;
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "INSERT INTO workspace_refresh_schedules (workspace_pk, enabled, interval_seconds)
+                 VALUES ($1, $2, $3)
+                 ON CONFLICT (workspace_pk) DO UPDATE
+                 SET enabled = EXCLUDED.enabled,
+                     interval_seconds = EXCLUDED.interval_seconds,
+                     updated_at = CLOCK_TIMESTAMP()
+                 RETURNING *",
+                &[&workspace_pk, &enabled, &interval_seconds],
+            )
+            .await?;
+
+        Self::try_from(row)
+    }
+
+    /// Lists every enabled schedule, across all workspaces, whose interval has elapsed since it
+    /// was last enqueued. Listing does not claim anything: use [`Self::claim_and_enqueue`] for each
+    /// due workspace.
+    pub async fn list_due(ctx: &DalContext) -> RefreshScheduleResult<Vec<Self>> {
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(
+                "SELECT * FROM workspace_refresh_schedules
+                 WHERE enabled
+                   AND (last_enqueued_at IS NULL
+                        OR last_enqueued_at + interval_seconds * INTERVAL '1 second' <= CLOCK_TIMESTAMP())",
+                &[],
+            )
+            .await?;
+
+        rows.into_iter().map(Self::try_from).collect()
+    }
+
+    /// Claims the schedule for the [`Workspace`](crate::Workspace) in the current tenancy if it is
+    /// enabled and due, returning [`None`] otherwise. Claiming bumps `last_enqueued_at` in the same
+    /// statement that checks whether the schedule is due, so concurrent schedulers will never claim
+    /// the same schedule twice for a given interval.
+    ///
+    /// The claim is only durable once the transaction on the provided context is committed.
+    pub async fn claim(ctx: &DalContext) -> RefreshScheduleResult<Option<Self>> {
+        let workspace_pk = Self::workspace_pk(ctx)?;
+        let maybe_row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_opt(
+                "UPDATE workspace_refresh_schedules
+                 SET last_enqueued_at = CLOCK_TIMESTAMP()
+                 WHERE workspace_pk = $1
+                   AND enabled
+                   AND (last_enqueued_at IS NULL
+                        OR last_enqueued_at + interval_seconds * INTERVAL '1 second' <= CLOCK_TIMESTAMP())
+                 RETURNING *",
+                &[&workspace_pk],
+            )
+            .await?;
+
+        maybe_row.map(Self::try_from).transpose()
+    }
+
+    /// Claims the schedule for the [`Workspace`](crate::Workspace) in the current tenancy and, if
+    /// it was due, enqueues refresh actions with [`Self::enqueue_refresh_actions`]. Returns
+    /// [`None`] if the schedule was not due (or was claimed by someone else first).
+    ///
+    /// Both the claim and the actions live in the transaction on the provided context, so they are
+    /// committed together: if enqueueing fails, dropping the context rolls the claim back.
+    pub async fn claim_and_enqueue(
+        ctx: &DalContext,
+    ) -> RefreshScheduleResult<Option<Vec<ActionId>>> {
+        if Self::claim(ctx).await?.is_none() {
+            return Ok(None);
+        }
+
+        Self::enqueue_refresh_actions(ctx).await.map(Some)
+    }
+
+    /// Enqueues an [`ActionKind::Refresh`] action for every [`Component`] in the current change
+    /// set that has a resource. Components that already have an equivalent refresh action enqueued,
+    /// or that are marked for deletion, are skipped.
+    #[instrument(
+        name = "refresh_schedule.enqueue_refresh_actions",
+        level = "info",
+        skip_all
+    )]
+    pub async fn enqueue_refresh_actions(ctx: &DalContext) -> RefreshScheduleResult<Vec<ActionId>> {
+        let mut action_ids = Vec::new();
+
+        for component in Component::list(ctx).await? {
+            if component.to_delete() || component.resource(ctx).await?.is_none() {
+                continue;
+            }
+
+            let schema_variant_id = Component::schema_variant_id(ctx, component.id()).await?;
+            for prototype in ActionPrototype::for_variant(ctx, schema_variant_id).await? {
+                if prototype.kind != ActionKind::Refresh {
+                    continue;
+                }
+                if Action::find_equivalent(ctx, prototype.id(), Some(component.id()))
+                    .await?
+                    .is_some()
+                {
+                    continue;
+                }
+
+                let action = Action::new(ctx, prototype.id(), Some(component.id())).await?;
+                action_ids.push(action.id());
+            }
+        }
+
+        if !action_ids.is_empty() {
+            WsEvent::action_list_updated(ctx)
+                .await?
+                .publish_on_commit(ctx)
+                .await?;
+        }
+
+        Ok(action_ids)
+    }
+
+    fn workspace_pk(ctx: &DalContext) -> RefreshScheduleResult<WorkspacePk> {
+        ctx.tenancy()
+            .workspace_pk()
+            .ok_or(RefreshScheduleError::NoWorkspaceInTenancy)
+    }
+}
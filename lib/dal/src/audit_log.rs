@@ -0,0 +1,331 @@
+//! An append-only log of who did what, when and in which change set.
+//!
+//! Entries are recorded against a [`DalContext`] with [`DalContext::record_audit`] and written in
+//! the same transaction as the rest of the context's changes when it is committed, so an entry
+//! exists if, and only if, the operation it describes was committed. Commits by a user which
+//! change the graph without recording an entry are logged as a [`AuditOperation::GraphUpdate`] of
+//! the nodes they touched, so that no change a user makes goes unrecorded. The table rejects
+//! updates and deletes.
+
+use std::collections::BTreeSet;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use si_data_pg::{PgError, PgRow, PgTxn};
+use strum::{Display, EnumString};
+use thiserror::Error;
+
+use crate::workspace_snapshot::graph::{detect_updates::Update, RebaseBatch};
+use crate::{pk, ChangeSetId, DalContext, HistoryActor, TransactionsError, UserPk, WorkspacePk};
+
+/// The most entries returned by a single page of [`AuditLogEntry::list`].
+pub const MAX_PAGE_SIZE: i64 = 1000;
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum AuditLogError {
+    #[error("no workspace in tenancy")]
+    NoWorkspaceInTenancy,
+    #[error("pg error: {0}")]
+    Pg(#[from] PgError),
+    #[error("error serializing/deserializing json: {0}")]
+    SerdeJson(#[from] serde_json::Error),
+    #[error("transactions error: {0}")]
+    Transactions(#[from] TransactionsError),
+    #[error("unknown audit operation: {0}")]
+    UnknownOperation(String),
+}
+
+pub type AuditLogResult<T> = Result<T, AuditLogError>;
+
+pk!(AuditLogPk);
+
+/// The kind of operation an [`AuditLogEntry`] records.
+#[remain::sorted]
+#[derive(Clone, Copy, Debug, Deserialize, Display, EnumString, Eq, Hash, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[strum(serialize_all = "camelCase")]
+pub enum AuditOperation {
+    /// Actions were queued to run again
+    ActionRetry,
+    /// A change set was applied to its base
+    ApplyChangeSet,
+    /// An attribute value was set or unset
+    AttributeValueUpdate,
+    /// Two components were connected
+    ConnectionCreate,
+    /// A connection between two components was removed
+    ConnectionDelete,
+    /// The code of a func was saved
+    FuncCodeSave,
+    /// The graph was changed by a user's commit which recorded no other operation
+    GraphUpdate,
+}
+
+/// A single, immutable, record in the audit log.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditLogEntry {
+    pk: AuditLogPk,
+    actor: HistoryActor,
+    workspace_pk: WorkspacePk,
+    change_set_id: Option<ChangeSetId>,
+    operation: AuditOperation,
+    entity_ids: Vec<String>,
+    created_at: DateTime<Utc>,
+}
+
+impl TryFrom<PgRow> for AuditLogEntry {
+    type Error = AuditLogError;
+
+    fn try_from(row: PgRow) -> Result<Self, Self::Error> {
+        let actor: serde_json::Value = row.try_get("actor")?;
+        let operation: String = row.try_get("operation")?;
+
+        Ok(Self {
+            pk: row.try_get("pk")?,
+            actor: serde_json::from_value(actor)?,
+            workspace_pk: row.try_get("workspace_pk")?,
+            change_set_id: row.try_get("change_set_id")?,
+            operation: operation
+                .parse()
+                .map_err(|_| AuditLogError::UnknownOperation(operation))?,
+            entity_ids: row.try_get("entity_ids")?,
+            created_at: row.try_get("created_at")?,
+        })
+    }
+}
+
+/// Narrows the entries returned by [`AuditLogEntry::list`]. Unset fields match every entry.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditLogFilter {
+    pub change_set_id: Option<ChangeSetId>,
+    pub operation: Option<AuditOperation>,
+    pub user_pk: Option<UserPk>,
+    pub entity_id: Option<String>,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+}
+
+impl AuditLogEntry {
+    /// Lists the entries of the workspace in the context's tenancy which match the filter, newest
+    /// first. Pages are continued by passing the last entry of the previous page as `before`.
+    pub async fn list(
+        ctx: &DalContext,
+        filter: &AuditLogFilter,
+        before: Option<AuditLogPk>,
+        limit: i64,
+    ) -> AuditLogResult<Vec<Self>> {
+        let workspace_pk = ctx
+            .tenancy()
+            .workspace_pk()
+            .ok_or(AuditLogError::NoWorkspaceInTenancy)?;
+        let operation = filter.operation.map(|operation| operation.to_string());
+        let actor = filter
+            .user_pk
+            .map(|user_pk| serde_json::to_value(HistoryActor::User(user_pk)))
+            .transpose()?;
+        let limit = limit.clamp(1, MAX_PAGE_SIZE);
+
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(
+                "SELECT * FROM audit_logs
+                    WHERE workspace_pk = $1
+                        AND ($2::ident IS NULL OR change_set_id = $2)
+                        AND ($3::text IS NULL OR operation = $3)
+                        AND ($4::jsonb IS NULL OR actor = $4)
+                        AND ($5::text IS NULL OR $5 = ANY(entity_ids))
+                        AND ($6::timestamptz IS NULL OR created_at >= $6)
+                        AND ($7::timestamptz IS NULL OR created_at < $7)
+                        AND ($8::ident IS NULL OR (created_at, pk) < (
+                            SELECT created_at, pk FROM audit_logs WHERE pk = $8
+                        ))
+                    ORDER BY created_at DESC, pk DESC
+                    LIMIT $9",
+                &[
+                    &workspace_pk,
+                    &filter.change_set_id,
+                    &operation,
+                    &actor,
+                    &filter.entity_id,
+                    &filter.since,
+                    &filter.until,
+                    &before,
+                    &limit,
+                ],
+            )
+            .await?;
+
+        rows.into_iter().map(TryInto::try_into).collect()
+    }
+
+    pub fn pk(&self) -> AuditLogPk {
+        self.pk
+    }
+
+    pub fn actor(&self) -> HistoryActor {
+        self.actor
+    }
+
+    pub fn workspace_pk(&self) -> WorkspacePk {
+        self.workspace_pk
+    }
+
+    pub fn change_set_id(&self) -> Option<ChangeSetId> {
+        self.change_set_id
+    }
+
+    pub fn operation(&self) -> AuditOperation {
+        self.operation
+    }
+
+    pub fn entity_ids(&self) -> &[String] {
+        &self.entity_ids
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+}
+
+/// Serializes entries as JSON lines, one entry per line.
+pub fn to_json_lines(entries: &[AuditLogEntry]) -> AuditLogResult<Vec<u8>> {
+    let mut buf = Vec::new();
+    for entry in entries {
+        serde_json::to_writer(&mut buf, entry)?;
+        buf.push(b'\n');
+    }
+    Ok(buf)
+}
+
+/// An entry recorded against a context which is yet to be committed.
+#[derive(Clone, Debug)]
+pub(crate) struct PendingAuditLogEntry {
+    actor: HistoryActor,
+    workspace_pk: WorkspacePk,
+    change_set_id: Option<ChangeSetId>,
+    operation: AuditOperation,
+    entity_ids: Vec<String>,
+}
+
+impl PendingAuditLogEntry {
+    /// The entry of an operation performed with the given context, unless the context has no
+    /// workspace, as only operations performed within a workspace are audited.
+    pub(crate) fn for_ctx(
+        ctx: &DalContext,
+        operation: AuditOperation,
+        entity_ids: Vec<String>,
+    ) -> Option<Self> {
+        let workspace_pk = ctx.tenancy().workspace_pk()?;
+        let change_set_id = ctx.change_set().ok().map(|change_set| change_set.id);
+
+        Some(Self {
+            actor: *ctx.history_actor(),
+            workspace_pk,
+            change_set_id,
+            operation,
+            entity_ids,
+        })
+    }
+
+    pub(crate) async fn insert(&self, txn: &PgTxn) -> Result<(), TransactionsError> {
+        let actor = serde_json::to_value(self.actor)?;
+
+        txn.execute(
+            "INSERT INTO audit_logs (actor, workspace_pk, change_set_id, operation, entity_ids)
+                VALUES ($1, $2, $3, $4, $5)",
+            &[
+                &actor,
+                &self.workspace_pk,
+                &self.change_set_id,
+                &self.operation.to_string(),
+                &self.entity_ids,
+            ],
+        )
+        .await?;
+
+        Ok(())
+    }
+}
+
+/// The ids of the nodes a batch adds, replaces or changes the edges of, without duplicates.
+pub(crate) fn affected_entity_ids(rebase_batch: &RebaseBatch) -> Vec<String> {
+    let mut ids = BTreeSet::new();
+    for update in rebase_batch.updates() {
+        match update {
+            Update::NewEdge {
+                source,
+                destination,
+                ..
+            }
+            | Update::RemoveEdge {
+                source,
+                destination,
+                ..
+            } => {
+                ids.insert(source.id.to_string());
+                ids.insert(destination.id.to_string());
+            }
+            Update::ReplaceNode { node_weight } | Update::NewNode { node_weight } => {
+                ids.insert(node_weight.id().to_string());
+            }
+        }
+    }
+    ids.into_iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn operation_round_trips_through_strings() {
+        for operation in [
+            AuditOperation::ActionRetry,
+            AuditOperation::ApplyChangeSet,
+            AuditOperation::AttributeValueUpdate,
+            AuditOperation::ConnectionCreate,
+            AuditOperation::ConnectionDelete,
+            AuditOperation::FuncCodeSave,
+            AuditOperation::GraphUpdate,
+        ] {
+            assert_eq!(
+                operation,
+                operation
+                    .to_string()
+                    .parse::<AuditOperation>()
+                    .expect("failed to parse operation")
+            );
+        }
+    }
+
+    #[test]
+    fn json_lines_has_one_entry_per_line() {
+        let entry = AuditLogEntry {
+            pk: AuditLogPk::generate(),
+            actor: HistoryActor::SystemInit,
+            workspace_pk: WorkspacePk::generate(),
+            change_set_id: None,
+            operation: AuditOperation::GraphUpdate,
+            entity_ids: vec!["01HRFEV0S23R1G23RP75QQDCA7".to_owned()],
+            created_at: Utc::now(),
+        };
+
+        let json_lines =
+            to_json_lines(&[entry.clone(), entry.clone()]).expect("failed to serialize");
+        let lines: Vec<&str> = std::str::from_utf8(&json_lines)
+            .expect("json lines are utf8")
+            .lines()
+            .collect();
+
+        assert_eq!(2, lines.len());
+        for line in lines {
+            let parsed: AuditLogEntry = serde_json::from_str(line).expect("failed to parse line");
+            assert_eq!(entry, parsed);
+        }
+    }
+}
@@ -13,6 +13,7 @@ use si_data_pg::{PgError, PgRow};
 use si_events::{ulid::Ulid, WorkspaceSnapshotAddress};
 use telemetry::prelude::*;
 
+use crate::audit_log::AuditOperation;
 use crate::context::RebaseRequest;
 use crate::slow_rt::SlowRuntimeError;
 use crate::{
@@ -468,6 +469,11 @@ impl ChangeSet {
             .await?
             .publish_on_commit(ctx)
             .await?;
+        ctx.record_audit(
+            AuditOperation::ApplyChangeSet,
+            vec![self.id.into(), base_change_set_id.into()],
+        )
+        .await?;
 
         Ok(())
     }
@@ -5,7 +5,7 @@ use itertools::Itertools;
 use petgraph::Direction::Outgoing;
 use serde::{Deserialize, Serialize};
 use si_pkg::KeyOrIndex;
-use std::collections::{hash_map, HashMap, HashSet, VecDeque};
+use std::collections::{hash_map, BTreeMap, HashMap, HashSet, VecDeque};
 use std::hash::Hash;
 use std::num::ParseFloatError;
 use std::sync::Arc;
@@ -28,7 +28,7 @@ use crate::change_set::ChangeSetError;
 use crate::code_view::CodeViewError;
 use crate::diagram::{SummaryDiagramComponent, SummaryDiagramInferredEdge};
 use crate::history_event::HistoryEventMetadata;
-use crate::layer_db_types::{ComponentContent, ComponentContentV1};
+use crate::layer_db_types::{ComponentContent, ComponentContentV2};
 use crate::prop::{PropError, PropPath};
 use crate::qualification::QualificationError;
 use crate::schema::variant::leaves::LeafKind;
@@ -60,10 +60,14 @@ use crate::{
 pub mod code;
 pub mod debug;
 pub mod diff;
+pub mod drift;
 pub mod frame;
 pub mod properties;
 pub mod qualification;
+pub mod query;
+pub mod reconciliation;
 pub mod resource;
+pub mod resource_import;
 
 pub const DEFAULT_COMPONENT_X_POSITION: &str = "0";
 pub const DEFAULT_COMPONENT_Y_POSITION: &str = "0";
@@ -117,6 +121,8 @@ pub enum ComponentError {
     InputSocket(#[from] InputSocketError),
     #[error("input socket {0} has more than one attribute value")]
     InputSocketTooManyAttributeValues(InputSocketId),
+    #[error("invalid label key (must be non-empty and only contain alphanumerics, '_', '-', '.' or '/'): {0}")]
+    InvalidLabelKey(String),
     #[error("layer db error: {0}")]
     LayerDb(#[from] si_layer_cache::LayerDbError),
     #[error("missing attribute prototype argument source: {0}")]
@@ -271,9 +277,10 @@ pub struct Component {
     y: String,
     width: Option<String>,
     height: Option<String>,
+    labels: BTreeMap<String, String>,
 }
 
-impl From<Component> for ComponentContentV1 {
+impl From<Component> for ComponentContentV2 {
     fn from(value: Component) -> Self {
         Self {
             timestamp: value.timestamp,
@@ -281,6 +288,7 @@ impl From<Component> for ComponentContentV1 {
             y: value.y,
             width: value.width,
             height: value.height,
+            labels: value.labels,
         }
     }
 }
@@ -302,7 +310,7 @@ pub struct ControllingFuncData {
 }
 
 impl Component {
-    pub fn assemble(node_weight: &ComponentNodeWeight, content: ComponentContentV1) -> Self {
+    pub fn assemble(node_weight: &ComponentNodeWeight, content: ComponentContentV2) -> Self {
         Self {
             id: node_weight.id().into(),
             timestamp: content.timestamp,
@@ -311,6 +319,7 @@ impl Component {
             y: content.y,
             width: content.width,
             height: content.height,
+            labels: content.labels,
         }
     }
 
@@ -342,6 +351,15 @@ impl Component {
         self.to_delete
     }
 
+    /// The user-defined key/value labels for this [`Component`].
+    pub fn labels(&self) -> &BTreeMap<String, String> {
+        &self.labels
+    }
+
+    pub fn label(&self, key: &str) -> Option<&str> {
+        self.labels.get(key).map(|value| value.as_str())
+    }
+
     pub async fn view(&self, ctx: &DalContext) -> ComponentResult<Option<serde_json::Value>> {
         Self::view_by_id(ctx, self.id).await
     }
@@ -408,19 +426,20 @@ impl Component {
         name: impl Into<String>,
         schema_variant_id: SchemaVariantId,
     ) -> ComponentResult<Self> {
-        let content = ComponentContentV1 {
+        let content = ComponentContentV2 {
             timestamp: Timestamp::now(),
             x: DEFAULT_COMPONENT_X_POSITION.to_string(),
             y: DEFAULT_COMPONENT_Y_POSITION.to_string(),
             width: None,
             height: None,
+            labels: BTreeMap::new(),
         };
 
         let (hash, _) = ctx
             .layer_db()
             .cas()
             .write(
-                Arc::new(ComponentContent::V1(content.clone()).into()),
+                Arc::new(ComponentContent::V2(content.clone()).into()),
                 None,
                 ctx.events_tenancy(),
                 ctx.events_actor(),
@@ -1341,7 +1360,7 @@ impl Component {
     async fn try_get_node_weight_and_content(
         ctx: &DalContext,
         component_id: ComponentId,
-    ) -> ComponentResult<Option<(ComponentNodeWeight, ComponentContentV1)>> {
+    ) -> ComponentResult<Option<(ComponentNodeWeight, ComponentContentV2)>> {
         if let Some((component_node_weight, content_hash)) =
             Self::try_get_node_weight_and_content_hash(ctx, component_id).await?
         {
@@ -1354,9 +1373,7 @@ impl Component {
                     component_id.into(),
                 ))?;
 
-            let ComponentContent::V1(inner) = content;
-
-            return Ok(Some((component_node_weight, inner)));
+            return Ok(Some((component_node_weight, content.extract())));
         }
 
         Ok(None)
@@ -1365,7 +1382,7 @@ impl Component {
     async fn get_node_weight_and_content(
         ctx: &DalContext,
         component_id: ComponentId,
-    ) -> ComponentResult<(ComponentNodeWeight, ComponentContentV1)> {
+    ) -> ComponentResult<(ComponentNodeWeight, ComponentContentV2)> {
         Self::try_get_node_weight_and_content(ctx, component_id)
             .await?
             .ok_or(ComponentError::NotFound(component_id))
@@ -1425,10 +1442,7 @@ impl Component {
         for node_weight in node_weights {
             match contents.get(&node_weight.content_hash()) {
                 Some(content) => {
-                    // NOTE(nick,jacob,zack): if we had a v2, then there would be migration logic here.
-                    let ComponentContent::V1(inner) = content;
-
-                    components.push(Self::assemble(&node_weight, inner.to_owned()));
+                    components.push(Self::assemble(&node_weight, content.to_owned().extract()));
                 }
                 None => Err(WorkspaceSnapshotError::MissingContentFromStore(
                     node_weight.id(),
@@ -1538,19 +1552,19 @@ impl Component {
     ) -> ComponentResult<Self> {
         let id: ComponentId = self.id;
 
-        let before = ComponentContentV1::from(self.clone());
+        let before = ComponentContentV2::from(self.clone());
         self.x = x.into();
         self.y = y.into();
         self.width = width.map(|w| w.into());
         self.height = height.map(|h| h.into());
-        let updated = ComponentContentV1::from(self.clone());
+        let updated = ComponentContentV2::from(self.clone());
 
         if updated != before {
             let (hash, _) = ctx
                 .layer_db()
                 .cas()
                 .write(
-                    Arc::new(ComponentContent::V1(updated).into()),
+                    Arc::new(ComponentContent::V2(updated).into()),
                     None,
                     ctx.events_tenancy(),
                     ctx.events_actor(),
@@ -1566,8 +1580,62 @@ impl Component {
         Ok(Self::assemble(&node_weight, content))
     }
 
+    /// Sets the label for `key` on this [`Component`], replacing any existing value.
+    pub async fn set_label(
+        self,
+        ctx: &DalContext,
+        key: impl Into<String>,
+        value: impl Into<String>,
+    ) -> ComponentResult<Self> {
+        let key = key.into();
+        Self::validate_label_key(&key)?;
+        let value = value.into();
+
+        self.modify(ctx, |component| {
+            component.labels.insert(key, value);
+            Ok(())
+        })
+        .await
+    }
+
+    /// Removes the label for `key` from this [`Component`], if it exists.
+    pub async fn remove_label(self, ctx: &DalContext, key: &str) -> ComponentResult<Self> {
+        let key = key.to_owned();
+        self.modify(ctx, |component| {
+            component.labels.remove(&key);
+            Ok(())
+        })
+        .await
+    }
+
+    /// Replaces all labels on this [`Component`] with the provided set.
+    pub async fn set_labels(
+        self,
+        ctx: &DalContext,
+        labels: BTreeMap<String, String>,
+    ) -> ComponentResult<Self> {
+        for key in labels.keys() {
+            Self::validate_label_key(key)?;
+        }
+
+        self.modify(ctx, |component| {
+            component.labels = labels;
+            Ok(())
+        })
+        .await
+    }
+
+    /// Label keys must be usable as-is in a
+    /// [`ComponentQuery`](crate::component::query::ComponentQuery) (e.g. `label.env = "prod"`).
+    fn validate_label_key(key: &str) -> ComponentResult<()> {
+        if key.is_empty() || !key.chars().all(query::is_label_key_char) {
+            return Err(ComponentError::InvalidLabelKey(key.to_owned()));
+        }
+        Ok(())
+    }
+
     // Set the name of the component. Should only be used during component creation
-    async fn set_name(&self, ctx: &DalContext, name: &str) -> ComponentResult<()> {
+    pub(crate) async fn set_name(&self, ctx: &DalContext, name: &str) -> ComponentResult<()> {
         let path = ["root", "si", "name"];
         let sv_id = Self::schema_variant_id(ctx, self.id).await?;
         let name_prop_id = Prop::find_prop_id_by_path(ctx, sv_id, &PropPath::new(path)).await?;
@@ -2482,7 +2550,7 @@ impl Component {
         let original_component = self.clone();
         let mut component = self;
 
-        let before = ComponentContentV1::from(component.clone());
+        let before = ComponentContentV2::from(component.clone());
         lambda(&mut component)?;
 
         // The `to_delete` lives on the node itself, not in the content, so we need to be a little
@@ -2508,13 +2576,13 @@ impl Component {
                 .await?;
         }
 
-        let updated = ComponentContentV1::from(component.clone());
+        let updated = ComponentContentV2::from(component.clone());
         if updated != before {
             let (hash, _) = ctx
                 .layer_db()
                 .cas()
                 .write(
-                    Arc::new(ComponentContent::V1(updated.clone()).into()),
+                    Arc::new(ComponentContent::V2(updated.clone()).into()),
                     None,
                     ctx.events_tenancy(),
                     ctx.events_actor(),
@@ -2798,6 +2866,8 @@ impl Component {
             )
             .await?;
 
+        let pasted_comp = pasted_comp.set_labels(ctx, self.labels.clone()).await?;
+
         pasted_comp.clone_attributes_from(ctx, self.id()).await?;
         Ok(pasted_comp)
     }
@@ -3810,7 +3880,10 @@ impl Component {
             // maybe you shouldn't upgrade a component if an action
             // is dispatched or running for the current?
             match action.state() {
-                ActionState::Failed | ActionState::OnHold | ActionState::Queued => {
+                ActionState::Failed
+                | ActionState::OnHold
+                | ActionState::Queued
+                | ActionState::Cancelled => {
                     let func_id = ActionPrototype::func_id(ctx, action_prototype_id)
                         .await
                         .map_err(|err| ComponentError::ActionPrototype(Box::new(err)))?;
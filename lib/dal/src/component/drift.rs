@@ -0,0 +1,314 @@
+//! This module contains the ability to detect "drift" between the modeled domain of a
+//! [`Component`] and the resource most recently reported for it by a refresh action.
+//!
+//! Drift is only computed for paths that exist in _both_ the domain and the resource payload. The
+//! resource payload usually contains far more information than the model (generated ids, defaults
+//! chosen by the provider, etc.), and the model may contain values that are never echoed back, so
+//! neither side is expected to be a superset of the other.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use si_data_pg::{PgError, PgRow};
+use telemetry::prelude::*;
+use thiserror::Error;
+
+use crate::{
+    ChangeSetId, Component, ComponentError, ComponentId, DalContext, TransactionsError,
+    WorkspacePk, WsEvent, WsEventResult, WsPayload,
+};
+
+#[remain::sorted]
+#[derive(Debug, Error)]
+pub enum ResourceDriftError {
+    #[error("component error: {0}")]
+    Component(#[from] ComponentError),
+    #[error("no workspace in tenancy")]
+    NoWorkspaceInTenancy,
+    #[error("pg error: {0}")]
+    Pg(#[from] PgError),
+    #[error("serde json error: {0}")]
+    SerdeJson(#[from] serde_json::Error),
+    #[error("transactions error: {0}")]
+    Transactions(#[from] TransactionsError),
+}
+
+pub type ResourceDriftResult<T> = Result<T, ResourceDriftError>;
+
+/// A single path at which the modeled value and the actual (resource) value disagree.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceDriftEntry {
+    /// A JSON pointer, relative to `/root/domain` and the resource payload respectively.
+    pub path: String,
+    pub modeled: Value,
+    pub actual: Value,
+}
+
+/// The most recent drift detection result for a [`Component`].
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceDrift {
+    pub component_id: ComponentId,
+    pub detected_at: DateTime<Utc>,
+    pub drifted: bool,
+    pub entries: Vec<ResourceDriftEntry>,
+}
+
+impl TryFrom<PgRow> for ResourceDrift {
+    type Error = ResourceDriftError;
+
+    fn try_from(row: PgRow) -> Result<Self, Self::Error> {
+        let entries: Value = row.try_get("entries")?;
+        Ok(Self {
+            component_id: row.try_get("component_id")?,
+            detected_at: row.try_get("detected_at")?,
+            drifted: row.try_get("drifted")?,
+            entries: serde_json::from_value(entries)?,
+        })
+    }
+}
+
+impl ResourceDrift {
+    /// Compares the modeled domain against a resource payload, returning every path present in
+    /// both where the values differ.
+    pub fn compute(domain: &Value, payload: &Value) -> Vec<ResourceDriftEntry> {
+        let mut entries = Vec::new();
+        Self::compute_inner(domain, payload, &mut String::new(), &mut entries);
+        entries
+    }
+
+    fn compute_inner(
+        modeled: &Value,
+        actual: &Value,
+        path: &mut String,
+        entries: &mut Vec<ResourceDriftEntry>,
+    ) {
+        match (modeled, actual) {
+            (Value::Object(modeled_map), Value::Object(actual_map)) => {
+                for (key, modeled_value) in modeled_map {
+                    if let Some(actual_value) = actual_map.get(key) {
+                        let previous_len = path.len();
+                        path.push('/');
+                        path.push_str(&key.replace('~', "~0").replace('/', "~1"));
+                        Self::compute_inner(modeled_value, actual_value, path, entries);
+                        path.truncate(previous_len);
+                    }
+                }
+            }
+            // An unset modeled value expresses no opinion about the resource.
+            (Value::Null, _) => {}
+            (modeled, actual) => {
+                if !Self::values_match(modeled, actual) {
+                    entries.push(ResourceDriftEntry {
+                        path: path.clone(),
+                        modeled: modeled.clone(),
+                        actual: actual.clone(),
+                    });
+                }
+            }
+        }
+    }
+
+    /// Providers frequently echo scalars back with a different JSON type than the one we modeled
+    /// (e.g. `"80"` vs `80`), so scalars are compared by their string representation.
+    fn values_match(modeled: &Value, actual: &Value) -> bool {
+        match (modeled, actual) {
+            (Value::Array(modeled_items), Value::Array(actual_items)) => {
+                modeled_items.len() == actual_items.len()
+                    && modeled_items
+                        .iter()
+                        .zip(actual_items)
+                        .all(|(modeled, actual)| Self::values_match(modeled, actual))
+            }
+            (Value::Object(_), Value::Object(_)) => Self::compute(modeled, actual).is_empty(),
+            (Value::Object(_) | Value::Array(_), _) | (_, Value::Object(_) | Value::Array(_)) => {
+                false
+            }
+            (modeled, actual) => Self::scalar_string(modeled) == Self::scalar_string(actual),
+        }
+    }
+
+    fn scalar_string(value: &Value) -> String {
+        match value {
+            Value::String(string) => string.to_owned(),
+            other => other.to_string(),
+        }
+    }
+
+    /// Computes drift for the given [`Component`] against the provided resource payload and
+    /// records the result, replacing any previous result for the component.
+    #[instrument(name = "resource_drift.detect", level = "info", skip(ctx, payload))]
+    pub async fn detect(
+        ctx: &DalContext,
+        component_id: ComponentId,
+        payload: &Value,
+    ) -> ResourceDriftResult<Self> {
+        let domain = Component::view_by_id(ctx, component_id)
+            .await?
+            .and_then(|view| view.get("domain").cloned())
+            .unwrap_or(Value::Null);
+
+        let entries = Self::compute(&domain, payload);
+        Self::upsert(ctx, component_id, entries).await
+    }
+
+    async fn upsert(
+        ctx: &DalContext,
+        component_id: ComponentId,
+        entries: Vec<ResourceDriftEntry>,
+    ) -> ResourceDriftResult<Self> {
+        let workspace_pk = Self::workspace_pk(ctx)?;
+        let drifted = !entries.is_empty();
+        let entries = serde_json::to_value(entries)?;
+
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "INSERT INTO component_resource_drift (workspace_pk, component_id, drifted, entries)
+                 VALUES ($1, $2, $3, $4)
+                 ON CONFLICT (workspace_pk, component_id) DO UPDATE
+                 SET drifted = EXCLUDED.drifted,
+                     entries = EXCLUDED.entries,
+                     detected_at = CLOCK_TIMESTAMP()
+                 RETURNING *",
+                &[&workspace_pk, &component_id, &drifted, &entries],
+            )
+            .await?;
+
+        Self::try_from(row)
+    }
+
+    /// Returns the most recent drift result for the given [`Component`], if it has ever been
+    /// refreshed.
+    pub async fn get_for_component(
+        ctx: &DalContext,
+        component_id: ComponentId,
+    ) -> ResourceDriftResult<Option<Self>> {
+        let workspace_pk = Self::workspace_pk(ctx)?;
+        let maybe_row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_opt(
+                "SELECT * FROM component_resource_drift WHERE workspace_pk = $1 AND component_id = $2",
+                &[&workspace_pk, &component_id],
+            )
+            .await?;
+
+        maybe_row.map(Self::try_from).transpose()
+    }
+
+    /// Lists every [`Component`] in the current workspace whose last refresh detected drift.
+    pub async fn list_drifted(ctx: &DalContext) -> ResourceDriftResult<Vec<Self>> {
+        let workspace_pk = Self::workspace_pk(ctx)?;
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(
+                "SELECT * FROM component_resource_drift
+                 WHERE workspace_pk = $1 AND drifted
+                 ORDER BY detected_at DESC",
+                &[&workspace_pk],
+            )
+            .await?;
+
+        rows.into_iter().map(Self::try_from).collect()
+    }
+
+    fn workspace_pk(ctx: &DalContext) -> ResourceDriftResult<WorkspacePk> {
+        ctx.tenancy()
+            .workspace_pk()
+            .ok_or(ResourceDriftError::NoWorkspaceInTenancy)
+    }
+}
+
+#[derive(Clone, Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceDriftDetectedPayload {
+    component_id: ComponentId,
+    change_set_id: ChangeSetId,
+    drifted: bool,
+    entries: Vec<ResourceDriftEntry>,
+}
+
+impl WsEvent {
+    pub async fn resource_drift_detected(
+        ctx: &DalContext,
+        drift: &ResourceDrift,
+    ) -> WsEventResult<Self> {
+        WsEvent::new(
+            ctx,
+            WsPayload::ResourceDriftDetected(ResourceDriftDetectedPayload {
+                component_id: drift.component_id,
+                change_set_id: ctx.change_set_id(),
+                drifted: drift.drifted,
+                entries: drift.entries.clone(),
+            }),
+        )
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn only_shared_paths_are_compared() {
+        let domain = json!({ "name": "poop", "region": "us-east-2", "tags": { "env": "prod" } });
+        let payload = json!({ "id": "i-1234", "region": "us-east-2", "tags": { "env": "prod", "owner": "nick" } });
+
+        assert!(ResourceDrift::compute(&domain, &payload).is_empty());
+    }
+
+    #[test]
+    fn differing_values_are_reported_with_their_path() {
+        let domain = json!({ "region": "us-east-2", "tags": { "env": "prod" } });
+        let payload = json!({ "region": "us-west-1", "tags": { "env": "dev" } });
+
+        assert_eq!(
+            vec![
+                ResourceDriftEntry {
+                    path: "/region".to_owned(),
+                    modeled: json!("us-east-2"),
+                    actual: json!("us-west-1"),
+                },
+                ResourceDriftEntry {
+                    path: "/tags/env".to_owned(),
+                    modeled: json!("prod"),
+                    actual: json!("dev"),
+                },
+            ],
+            ResourceDrift::compute(&domain, &payload)
+        );
+    }
+
+    #[test]
+    fn scalars_are_compared_leniently() {
+        let domain = json!({ "port": "80", "enabled": "true", "unset": null });
+        let payload = json!({ "port": 80, "enabled": true, "unset": "whatever" });
+
+        assert!(ResourceDrift::compute(&domain, &payload).is_empty());
+    }
+
+    #[test]
+    fn arrays_are_compared_as_a_whole() {
+        let domain = json!({ "ports": [80, 443] });
+        let payload = json!({ "ports": [80] });
+
+        assert_eq!(
+            vec![ResourceDriftEntry {
+                path: "/ports".to_owned(),
+                modeled: json!([80, 443]),
+                actual: json!([80]),
+            }],
+            ResourceDrift::compute(&domain, &payload)
+        );
+    }
+}
@@ -0,0 +1,807 @@
+//! This module contains [`ComponentQuery`], a small query language for selecting
+//! [`Components`](crate::Component) by their schema, name, type, labels and attribute values.
+//!
+//! ```text
+//! schema = "AWS EC2 Instance" and label.env = "prod" and /domain/region = "us-east-1"
+//! ```
+//!
+//! The grammar is as follows:
+//!
+//! ```text
+//! query   := or
+//! or      := and ("or" and)*
+//! and     := unary ("and" unary)*
+//! unary   := "not" unary | "(" or ")" | "has" field | field op literal
+//! field   := "id" | "name" | "schema" | "category" | "type" | "label." key | path
+//! path    := "/" segment ("/" segment)*    (a JSON pointer into the component's root view)
+//! op      := "=" | "!=" | "~"
+//! literal := "\"" string "\"" | number | "true" | "false" | "null"
+//! ```
+//!
+//! The `~` operator checks if a string contains the given substring, or if an array contains
+//! the given value. Keywords are case-insensitive.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
+use telemetry::prelude::*;
+use thiserror::Error;
+
+use crate::schema::variant::SchemaVariantError;
+use crate::{Component, ComponentError, ComponentId, DalContext, SchemaVariantId};
+
+#[remain::sorted]
+#[derive(Debug, Error)]
+pub enum ComponentQueryError {
+    #[error("component error: {0}")]
+    Component(#[from] ComponentError),
+    #[error("empty query")]
+    EmptyQuery,
+    #[error("invalid number literal at position {0}: {1}")]
+    InvalidNumber(usize, String),
+    #[error("schema variant error: {0}")]
+    SchemaVariant(#[from] SchemaVariantError),
+    #[error("serde json error: {0}")]
+    Serde(#[from] serde_json::Error),
+    #[error("unexpected character '{1}' at position {0}")]
+    UnexpectedCharacter(usize, char),
+    #[error("unexpected end of query: expected {0}")]
+    UnexpectedEnd(&'static str),
+    #[error("unexpected token at position {0}: expected {1}, found \"{2}\"")]
+    UnexpectedToken(usize, &'static str, String),
+    #[error("unknown field at position {0}: {1}")]
+    UnknownField(usize, String),
+    #[error("unterminated string starting at position {0}")]
+    UnterminatedString(usize),
+}
+
+pub type ComponentQueryResult<T> = Result<T, ComponentQueryError>;
+
+/// Returns true if the character may be used within a label key (and therefore within the
+/// `label.<key>` field of a [`ComponentQuery`]).
+pub fn is_label_key_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.' | '/')
+}
+
+/// A field of a [`Component`] that can be referenced in a [`ComponentQuery`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ComponentQueryField {
+    /// The schema variant category (e.g. "AWS EC2").
+    Category,
+    Id,
+    /// A user-defined label.
+    Label(String),
+    /// The value at `root/si/name`.
+    Name,
+    /// A JSON pointer into the component's root view (e.g. `/domain/region`).
+    Path(String),
+    /// The schema name (e.g. "AWS EC2 Instance").
+    Schema,
+    /// The component type (e.g. "configurationFrameDown").
+    Type,
+}
+
+impl fmt::Display for ComponentQueryField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Category => write!(f, "category"),
+            Self::Id => write!(f, "id"),
+            Self::Label(key) => write!(f, "label.{key}"),
+            Self::Name => write!(f, "name"),
+            Self::Path(path) => write!(f, "{path}"),
+            Self::Schema => write!(f, "schema"),
+            Self::Type => write!(f, "type"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ComponentQueryOperator {
+    Contains,
+    Equal,
+    NotEqual,
+}
+
+impl fmt::Display for ComponentQueryOperator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Contains => write!(f, "~"),
+            Self::Equal => write!(f, "="),
+            Self::NotEqual => write!(f, "!="),
+        }
+    }
+}
+
+/// The parsed expression tree of a [`ComponentQuery`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum ComponentQueryExpr {
+    And(Box<ComponentQueryExpr>, Box<ComponentQueryExpr>),
+    Compare {
+        field: ComponentQueryField,
+        operator: ComponentQueryOperator,
+        value: Value,
+    },
+    Has(ComponentQueryField),
+    Not(Box<ComponentQueryExpr>),
+    Or(Box<ComponentQueryExpr>, Box<ComponentQueryExpr>),
+}
+
+impl ComponentQueryExpr {
+    fn visit_fields<'a>(&'a self, fields: &mut Vec<&'a ComponentQueryField>) {
+        match self {
+            Self::And(left, right) | Self::Or(left, right) => {
+                left.visit_fields(fields);
+                right.visit_fields(fields);
+            }
+            Self::Not(inner) => inner.visit_fields(fields),
+            Self::Compare { field, .. } | Self::Has(field) => fields.push(field),
+        }
+    }
+
+    fn evaluate(&self, facts: &ComponentFacts) -> bool {
+        match self {
+            Self::And(left, right) => left.evaluate(facts) && right.evaluate(facts),
+            Self::Or(left, right) => left.evaluate(facts) || right.evaluate(facts),
+            Self::Not(inner) => !inner.evaluate(facts),
+            Self::Has(field) => !matches!(facts.get(field), None | Some(Value::Null)),
+            Self::Compare {
+                field,
+                operator,
+                value,
+            } => {
+                let actual = facts.get(field);
+                match operator {
+                    ComponentQueryOperator::Equal => {
+                        actual.is_some_and(|actual| values_equal(&actual, value))
+                    }
+                    ComponentQueryOperator::NotEqual => {
+                        !actual.is_some_and(|actual| values_equal(&actual, value))
+                    }
+                    ComponentQueryOperator::Contains => {
+                        actual.is_some_and(|actual| value_contains(&actual, value))
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Display for ComponentQueryExpr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::And(left, right) => write!(f, "({left} and {right})"),
+            Self::Or(left, right) => write!(f, "({left} or {right})"),
+            Self::Not(inner) => write!(f, "not {inner}"),
+            Self::Has(field) => write!(f, "has {field}"),
+            Self::Compare {
+                field,
+                operator,
+                value,
+            } => write!(f, "{field} {operator} {value}"),
+        }
+    }
+}
+
+/// A parsed query that selects [`Components`](Component). See the [module
+/// documentation](self) for the syntax.
+///
+/// A [`ComponentQuery`] serializes to (and deserializes from) its source string.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComponentQuery {
+    source: String,
+    expr: ComponentQueryExpr,
+}
+
+impl ComponentQuery {
+    pub fn parse(source: impl Into<String>) -> ComponentQueryResult<Self> {
+        let source = source.into();
+        let tokens = tokenize(&source)?;
+        if tokens.is_empty() {
+            return Err(ComponentQueryError::EmptyQuery);
+        }
+
+        let mut parser = Parser { tokens, index: 0 };
+        let expr = parser.parse_or()?;
+        if let Some((position, token)) = parser.tokens.get(parser.index) {
+            return Err(ComponentQueryError::UnexpectedToken(
+                *position,
+                "\"and\", \"or\" or end of query",
+                token.to_string(),
+            ));
+        }
+
+        Ok(Self { source, expr })
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    pub fn expr(&self) -> &ComponentQueryExpr {
+        &self.expr
+    }
+
+    /// Returns true if the given [`Component`] matches this query.
+    pub async fn matches(
+        &self,
+        ctx: &DalContext,
+        component: &Component,
+    ) -> ComponentQueryResult<bool> {
+        let mut schema_cache = HashMap::new();
+        let facts = ComponentFacts::gather(ctx, component, self, &mut schema_cache).await?;
+        Ok(self.expr.evaluate(&facts))
+    }
+
+    /// Evaluates this query over [`Component::list`], returning every matching [`Component`].
+    #[instrument(
+        name = "component.query.list_matching",
+        level = "debug",
+        skip(self, ctx),
+        fields(si.component.query = %self.source)
+    )]
+    pub async fn list_matching(&self, ctx: &DalContext) -> ComponentQueryResult<Vec<Component>> {
+        let mut schema_cache = HashMap::new();
+        let mut matching = Vec::new();
+
+        for component in Component::list(ctx).await? {
+            let facts = ComponentFacts::gather(ctx, &component, self, &mut schema_cache).await?;
+            if self.expr.evaluate(&facts) {
+                matching.push(component);
+            }
+        }
+
+        Ok(matching)
+    }
+
+    /// Evaluates this query over [`Component::list`], returning the ids of every matching
+    /// [`Component`].
+    pub async fn list_matching_ids(
+        &self,
+        ctx: &DalContext,
+    ) -> ComponentQueryResult<Vec<ComponentId>> {
+        Ok(self
+            .list_matching(ctx)
+            .await?
+            .iter()
+            .map(|component| component.id())
+            .collect())
+    }
+
+    /// Combines explicitly chosen [`ComponentIds`](ComponentId) with those matching an optional
+    /// [`ComponentQuery`], preserving order and removing duplicates. This lets callers (e.g. bulk
+    /// edits or action queueing) target hand-picked ids, a query, or both.
+    pub async fn select_component_ids(
+        ctx: &DalContext,
+        component_ids: impl IntoIterator<Item = ComponentId>,
+        maybe_query: Option<&ComponentQuery>,
+    ) -> ComponentQueryResult<Vec<ComponentId>> {
+        let mut selected: Vec<ComponentId> = component_ids.into_iter().collect();
+        if let Some(query) = maybe_query {
+            selected.extend(query.list_matching_ids(ctx).await?);
+        }
+
+        let mut seen = HashSet::new();
+        selected.retain(|component_id| seen.insert(*component_id));
+
+        Ok(selected)
+    }
+
+    fn fields(&self) -> Vec<&ComponentQueryField> {
+        let mut fields = Vec::new();
+        self.expr.visit_fields(&mut fields);
+        fields
+    }
+}
+
+impl FromStr for ComponentQuery {
+    type Err = ComponentQueryError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl fmt::Display for ComponentQuery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.source)
+    }
+}
+
+impl Serialize for ComponentQuery {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.source)
+    }
+}
+
+impl<'de> Deserialize<'de> for ComponentQuery {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let source = String::deserialize(deserializer)?;
+        Self::parse(source).map_err(serde::de::Error::custom)
+    }
+}
+
+/// The values of a [`Component`] needed to evaluate a given [`ComponentQuery`]. Only the facts
+/// referenced by the query are gathered, since some (like the root view) are expensive.
+#[derive(Debug, Default)]
+struct ComponentFacts {
+    id: ComponentId,
+    labels: BTreeMap<String, String>,
+    name: Option<String>,
+    schema: Option<String>,
+    category: Option<String>,
+    component_type: Option<String>,
+    view: Option<Value>,
+}
+
+impl ComponentFacts {
+    async fn gather(
+        ctx: &DalContext,
+        component: &Component,
+        query: &ComponentQuery,
+        schema_cache: &mut HashMap<SchemaVariantId, (String, String)>,
+    ) -> ComponentQueryResult<Self> {
+        let mut facts = Self {
+            id: component.id(),
+            labels: component.labels().clone(),
+            ..Default::default()
+        };
+
+        for field in query.fields() {
+            match field {
+                ComponentQueryField::Id | ComponentQueryField::Label(_) => {}
+                ComponentQueryField::Name => {
+                    if facts.name.is_none() {
+                        facts.name = Some(component.name(ctx).await?);
+                    }
+                }
+                ComponentQueryField::Schema | ComponentQueryField::Category => {
+                    if facts.schema.is_none() {
+                        let schema_variant_id =
+                            Component::schema_variant_id(ctx, component.id()).await?;
+                        let (schema, category) = match schema_cache.get(&schema_variant_id) {
+                            Some(cached) => cached.clone(),
+                            None => {
+                                let schema_variant = component.schema_variant(ctx).await?;
+                                let schema = schema_variant.schema(ctx).await?;
+                                let entry = (
+                                    schema.name().to_owned(),
+                                    schema_variant.category().to_owned(),
+                                );
+                                schema_cache.insert(schema_variant_id, entry.clone());
+                                entry
+                            }
+                        };
+                        facts.schema = Some(schema);
+                        facts.category = Some(category);
+                    }
+                }
+                ComponentQueryField::Type => {
+                    if facts.component_type.is_none() {
+                        let component_type = serde_json::to_value(component.get_type(ctx).await?)?;
+                        facts.component_type = component_type.as_str().map(ToOwned::to_owned);
+                    }
+                }
+                ComponentQueryField::Path(_) => {
+                    if facts.view.is_none() {
+                        facts.view = Some(component.view(ctx).await?.unwrap_or(Value::Null));
+                    }
+                }
+            }
+        }
+
+        Ok(facts)
+    }
+
+    fn get(&self, field: &ComponentQueryField) -> Option<Value> {
+        match field {
+            ComponentQueryField::Id => Some(Value::String(self.id.to_string())),
+            ComponentQueryField::Label(key) => self.labels.get(key).cloned().map(Value::String),
+            ComponentQueryField::Name => self.name.clone().map(Value::String),
+            ComponentQueryField::Schema => self.schema.clone().map(Value::String),
+            ComponentQueryField::Category => self.category.clone().map(Value::String),
+            ComponentQueryField::Type => self.component_type.clone().map(Value::String),
+            ComponentQueryField::Path(path) => {
+                // Allow paths to be written either relative to the root prop or including it.
+                let pointer = match path.strip_prefix("/root") {
+                    Some(rest) if rest.is_empty() || rest.starts_with('/') => rest,
+                    _ => path.as_str(),
+                };
+                self.view
+                    .as_ref()
+                    .and_then(|view| view.pointer(pointer))
+                    .cloned()
+            }
+        }
+    }
+}
+
+fn values_equal(actual: &Value, expected: &Value) -> bool {
+    match (actual, expected) {
+        (Value::Number(actual), Value::Number(expected)) => actual.as_f64() == expected.as_f64(),
+        // Attribute values are frequently strings holding numbers or booleans, so compare the
+        // string forms when the types do not line up.
+        (Value::String(actual), Value::Number(_) | Value::Bool(_)) => {
+            *actual == expected.to_string()
+        }
+        (Value::Number(_) | Value::Bool(_), Value::String(expected)) => {
+            actual.to_string() == *expected
+        }
+        (actual, expected) => actual == expected,
+    }
+}
+
+fn value_contains(actual: &Value, expected: &Value) -> bool {
+    match (actual, expected) {
+        (Value::String(actual), Value::String(expected)) => actual.contains(expected.as_str()),
+        (Value::Array(items), expected) => items.iter().any(|item| values_equal(item, expected)),
+        (Value::Object(map), Value::String(key)) => map.contains_key(key),
+        _ => false,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Contains,
+    Equal,
+    LeftParen,
+    NotEqual,
+    Number(serde_json::Number),
+    Path(String),
+    RightParen,
+    String(String),
+    Word(String),
+}
+
+impl Token {
+    fn is_keyword(&self, keyword: &str) -> bool {
+        matches!(self, Token::Word(word) if word.eq_ignore_ascii_case(keyword))
+    }
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Contains => write!(f, "~"),
+            Self::Equal => write!(f, "="),
+            Self::LeftParen => write!(f, "("),
+            Self::NotEqual => write!(f, "!="),
+            Self::Number(number) => write!(f, "{number}"),
+            Self::Path(path) => write!(f, "{path}"),
+            Self::RightParen => write!(f, ")"),
+            Self::String(string) => write!(f, "{}", Value::String(string.to_owned())),
+            Self::Word(word) => write!(f, "{word}"),
+        }
+    }
+}
+
+fn tokenize(source: &str) -> ComponentQueryResult<Vec<(usize, Token)>> {
+    let mut tokens = Vec::new();
+    let mut chars = source.char_indices().peekable();
+
+    while let Some((position, c)) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {}
+            '(' => tokens.push((position, Token::LeftParen)),
+            ')' => tokens.push((position, Token::RightParen)),
+            '~' => tokens.push((position, Token::Contains)),
+            '=' => {
+                // Accept both "=" and "==".
+                chars.next_if(|(_, next)| *next == '=');
+                tokens.push((position, Token::Equal));
+            }
+            '!' => match chars.next_if(|(_, next)| *next == '=') {
+                Some(_) => tokens.push((position, Token::NotEqual)),
+                None => return Err(ComponentQueryError::UnexpectedCharacter(position, c)),
+            },
+            '"' => {
+                let mut string = String::new();
+                let mut terminated = false;
+                let mut escaping = false;
+                for (_, next) in chars.by_ref() {
+                    if escaping {
+                        string.push(match next {
+                            'n' => '\n',
+                            't' => '\t',
+                            escaped => escaped,
+                        });
+                        escaping = false;
+                        continue;
+                    }
+                    match next {
+                        '"' => {
+                            terminated = true;
+                            break;
+                        }
+                        '\\' => escaping = true,
+                        next => string.push(next),
+                    }
+                }
+                if !terminated {
+                    return Err(ComponentQueryError::UnterminatedString(position));
+                }
+                tokens.push((position, Token::String(string)));
+            }
+            '/' => {
+                let mut path = String::from(c);
+                while let Some((_, next)) =
+                    chars.next_if(|(_, next)| !next.is_whitespace() && !"()=!~\"".contains(*next))
+                {
+                    path.push(next);
+                }
+                tokens.push((position, Token::Path(path)));
+            }
+            c if c.is_ascii_digit() || c == '-' => {
+                let mut number = String::from(c);
+                while let Some((_, next)) = chars.next_if(|(_, next)| {
+                    next.is_ascii_digit() || matches!(*next, '.' | 'e' | 'E' | '+' | '-')
+                }) {
+                    number.push(next);
+                }
+                let parsed = serde_json::Number::from_str(&number)
+                    .map_err(|_| ComponentQueryError::InvalidNumber(position, number))?;
+                tokens.push((position, Token::Number(parsed)));
+            }
+            c if c.is_ascii_alphabetic() || c == '_' => {
+                let mut word = String::from(c);
+                while let Some((_, next)) = chars.next_if(|(_, next)| is_label_key_char(*next)) {
+                    word.push(next);
+                }
+                tokens.push((position, Token::Word(word)));
+            }
+            c => return Err(ComponentQueryError::UnexpectedCharacter(position, c)),
+        }
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<(usize, Token)>,
+    index: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.index).map(|(_, token)| token)
+    }
+
+    fn next(&mut self, expected: &'static str) -> ComponentQueryResult<(usize, Token)> {
+        let next = self
+            .tokens
+            .get(self.index)
+            .cloned()
+            .ok_or(ComponentQueryError::UnexpectedEnd(expected))?;
+        self.index += 1;
+        Ok(next)
+    }
+
+    fn parse_or(&mut self) -> ComponentQueryResult<ComponentQueryExpr> {
+        let mut expr = self.parse_and()?;
+        while self.peek().is_some_and(|token| token.is_keyword("or")) {
+            self.index += 1;
+            expr = ComponentQueryExpr::Or(Box::new(expr), Box::new(self.parse_and()?));
+        }
+        Ok(expr)
+    }
+
+    fn parse_and(&mut self) -> ComponentQueryResult<ComponentQueryExpr> {
+        let mut expr = self.parse_unary()?;
+        while self.peek().is_some_and(|token| token.is_keyword("and")) {
+            self.index += 1;
+            expr = ComponentQueryExpr::And(Box::new(expr), Box::new(self.parse_unary()?));
+        }
+        Ok(expr)
+    }
+
+    fn parse_unary(&mut self) -> ComponentQueryResult<ComponentQueryExpr> {
+        match self.peek() {
+            Some(token) if token.is_keyword("not") => {
+                self.index += 1;
+                Ok(ComponentQueryExpr::Not(Box::new(self.parse_unary()?)))
+            }
+            Some(token) if token.is_keyword("has") => {
+                self.index += 1;
+                Ok(ComponentQueryExpr::Has(self.parse_field()?))
+            }
+            Some(Token::LeftParen) => {
+                self.index += 1;
+                let expr = self.parse_or()?;
+                match self.next("\")\"")? {
+                    (_, Token::RightParen) => Ok(expr),
+                    (position, token) => Err(ComponentQueryError::UnexpectedToken(
+                        position,
+                        "\")\"",
+                        token.to_string(),
+                    )),
+                }
+            }
+            _ => {
+                let field = self.parse_field()?;
+                let operator = match self.next("an operator")? {
+                    (_, Token::Equal) => ComponentQueryOperator::Equal,
+                    (_, Token::NotEqual) => ComponentQueryOperator::NotEqual,
+                    (_, Token::Contains) => ComponentQueryOperator::Contains,
+                    (position, token) => {
+                        return Err(ComponentQueryError::UnexpectedToken(
+                            position,
+                            "an operator (\"=\", \"!=\" or \"~\")",
+                            token.to_string(),
+                        ))
+                    }
+                };
+                let value = self.parse_literal()?;
+                Ok(ComponentQueryExpr::Compare {
+                    field,
+                    operator,
+                    value,
+                })
+            }
+        }
+    }
+
+    fn parse_field(&mut self) -> ComponentQueryResult<ComponentQueryField> {
+        match self.next("a field")? {
+            (_, Token::Path(path)) => Ok(ComponentQueryField::Path(path)),
+            (position, Token::Word(word)) => {
+                if let Some(key) = word.strip_prefix("label.") {
+                    if key.is_empty() {
+                        return Err(ComponentQueryError::UnknownField(position, word));
+                    }
+                    return Ok(ComponentQueryField::Label(key.to_owned()));
+                }
+                match word.to_ascii_lowercase().as_str() {
+                    "category" => Ok(ComponentQueryField::Category),
+                    "id" => Ok(ComponentQueryField::Id),
+                    "name" => Ok(ComponentQueryField::Name),
+                    "schema" => Ok(ComponentQueryField::Schema),
+                    "type" => Ok(ComponentQueryField::Type),
+                    _ => Err(ComponentQueryError::UnknownField(position, word)),
+                }
+            }
+            (position, token) => Err(ComponentQueryError::UnexpectedToken(
+                position,
+                "a field",
+                token.to_string(),
+            )),
+        }
+    }
+
+    fn parse_literal(&mut self) -> ComponentQueryResult<Value> {
+        match self.next("a value")? {
+            (_, Token::String(string)) => Ok(Value::String(string)),
+            (_, Token::Number(number)) => Ok(Value::Number(number)),
+            (_, token) if token.is_keyword("true") => Ok(Value::Bool(true)),
+            (_, token) if token.is_keyword("false") => Ok(Value::Bool(false)),
+            (_, token) if token.is_keyword("null") => Ok(Value::Null),
+            (position, token) => Err(ComponentQueryError::UnexpectedToken(
+                position,
+                "a value (a quoted string, number, boolean or null)",
+                token.to_string(),
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn compare(field: ComponentQueryField, value: Value) -> ComponentQueryExpr {
+        ComponentQueryExpr::Compare {
+            field,
+            operator: ComponentQueryOperator::Equal,
+            value,
+        }
+    }
+
+    #[test]
+    fn parse_conjunction() {
+        let query = ComponentQuery::parse(
+            r#"schema = "AWS EC2 Instance" and label.env = "prod" and /domain/region = "us-east-1""#,
+        )
+        .expect("could not parse query");
+
+        assert_eq!(
+            ComponentQueryExpr::And(
+                Box::new(ComponentQueryExpr::And(
+                    Box::new(compare(
+                        ComponentQueryField::Schema,
+                        Value::String("AWS EC2 Instance".into())
+                    )),
+                    Box::new(compare(
+                        ComponentQueryField::Label("env".into()),
+                        Value::String("prod".into())
+                    )),
+                )),
+                Box::new(compare(
+                    ComponentQueryField::Path("/domain/region".into()),
+                    Value::String("us-east-1".into())
+                )),
+            ),
+            *query.expr()
+        );
+    }
+
+    #[test]
+    fn parse_precedence_and_grouping() {
+        let query = ComponentQuery::parse(
+            r#"not has label.team or (type != "component" AND /domain/port = 80)"#,
+        )
+        .expect("could not parse query");
+
+        assert_eq!(
+            r#"(not has label.team or (type != "component" and /domain/port = 80))"#,
+            query.expr().to_string()
+        );
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert!(matches!(
+            ComponentQuery::parse("   "),
+            Err(ComponentQueryError::EmptyQuery)
+        ));
+        assert!(matches!(
+            ComponentQuery::parse(r#"flavor = "vanilla""#),
+            Err(ComponentQueryError::UnknownField(0, _))
+        ));
+        assert!(matches!(
+            ComponentQuery::parse(r#"name = "unterminated"#),
+            Err(ComponentQueryError::UnterminatedString(7))
+        ));
+        assert!(matches!(
+            ComponentQuery::parse(r#"name "poop""#),
+            Err(ComponentQueryError::UnexpectedToken(5, _, _))
+        ));
+        assert!(matches!(
+            ComponentQuery::parse(r#"(name = "poop""#),
+            Err(ComponentQueryError::UnexpectedEnd(_))
+        ));
+    }
+
+    #[test]
+    fn evaluate() {
+        let facts = ComponentFacts {
+            labels: [("env".to_string(), "prod".to_string())].into(),
+            schema: Some("AWS EC2 Instance".into()),
+            view: Some(serde_json::json!({
+                "domain": {
+                    "region": "us-east-1",
+                    "port": "80",
+                    "tags": ["web", "public"],
+                },
+            })),
+            ..Default::default()
+        };
+
+        for (source, expected) in [
+            (
+                r#"schema = "AWS EC2 Instance" and label.env = "prod""#,
+                true,
+            ),
+            (r#"label.env != "prod""#, false),
+            (r#"label.owner != "nick""#, true),
+            (r#"has label.env and not has label.owner"#, true),
+            (r#"/domain/region = "us-east-1""#, true),
+            (r#"/root/domain/region ~ "east""#, true),
+            (r#"/domain/port = 80"#, true),
+            (r#"/domain/tags ~ "public""#, true),
+            (
+                r#"/domain/tags ~ "private" or schema = "Docker Image""#,
+                false,
+            ),
+        ] {
+            let query = ComponentQuery::parse(source).expect("could not parse query");
+            assert_eq!(expected, query.expr().evaluate(&facts), "{source}");
+        }
+    }
+}
@@ -0,0 +1,297 @@
+//! This module contains the ability to reconcile the modeled domain of a [`Component`] with its
+//! resource.
+//!
+//! A [`SchemaVariant`] can ship a reconciliation [`Func`]. When proposing a reconciliation, the
+//! drift between the domain and the resource payload (see [`ResourceDrift`]) is handed to that
+//! func, which decides which domain values should be updated and which actions (if any) should be
+//! enqueued to bring the two back in line. Proposals are never applied automatically: a user
+//! reviews them and accepts them into a change set with [`Reconciliation::accept`].
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use telemetry::prelude::*;
+use thiserror::Error;
+
+use crate::action::prototype::{ActionPrototype, ActionPrototypeError};
+use crate::action::{Action, ActionError, ActionId};
+use crate::attribute::value::AttributeValueError;
+use crate::component::drift::ResourceDrift;
+use crate::func::backend::js_reconciliation::{
+    ReconciliationDiff, ReconciliationDiffDomain, ReconciliationResult as ReconciliationFuncResult,
+};
+use crate::func::runner::{FuncRunner, FuncRunnerError};
+use crate::{
+    AttributeValue, AttributeValueId, Component, ComponentError, ComponentId, DalContext, Func,
+    FuncError, FuncId, SchemaVariant, SchemaVariantError,
+};
+
+#[remain::sorted]
+#[derive(Debug, Error)]
+pub enum ReconciliationError {
+    #[error("action error: {0}")]
+    Action(#[from] ActionError),
+    #[error("no action named {0} found for schema variant of component {1}")]
+    ActionNotFound(String, ComponentId),
+    #[error("action prototype error: {0}")]
+    ActionPrototype(#[from] ActionPrototypeError),
+    #[error("attribute value error: {0}")]
+    AttributeValue(#[from] AttributeValueError),
+    #[error("attribute value {0} does not belong to component {1}")]
+    AttributeValueNotForComponent(AttributeValueId, ComponentId),
+    #[error("attribute value {0} is not in the domain of component {1}")]
+    AttributeValueNotInDomain(AttributeValueId, ComponentId),
+    #[error("component error: {0}")]
+    Component(#[from] ComponentError),
+    #[error("func error: {0}")]
+    Func(#[from] FuncError),
+    #[error("func runner error: {0}")]
+    FuncRunner(#[from] FuncRunnerError),
+    #[error("func runner result channel closed before receiving a value")]
+    FuncRunnerSend,
+    #[error("schema variant error: {0}")]
+    SchemaVariant(#[from] SchemaVariantError),
+    #[error("serde json error: {0}")]
+    SerdeJson(#[from] serde_json::Error),
+}
+
+pub type ReconciliationResult<T> = Result<T, ReconciliationError>;
+
+/// A single domain value that the reconciliation [`Func`] proposes to update.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ReconciliationUpdate {
+    pub attribute_value_id: AttributeValueId,
+    pub path: Option<String>,
+    pub current: Option<Value>,
+    pub proposed: Value,
+}
+
+/// The result of running the reconciliation [`Func`] for a [`Component`].
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ReconciliationProposal {
+    pub component_id: ComponentId,
+    pub func_id: FuncId,
+    pub updates: Vec<ReconciliationUpdate>,
+    pub actions: Vec<String>,
+    pub message: Option<String>,
+}
+
+/// Namespace for proposing and accepting reconciliations.
+pub struct Reconciliation;
+
+impl Reconciliation {
+    /// Runs the reconciliation [`Func`] for the given [`Component`] against the drift between its
+    /// domain and its resource.
+    ///
+    /// Returns `None` if the [`SchemaVariant`] has no reconciliation [`Func`], the component has
+    /// no resource or the domain and resource have not drifted.
+    #[instrument(name = "reconciliation.propose", level = "info", skip(ctx))]
+    pub async fn propose(
+        ctx: &DalContext,
+        component_id: ComponentId,
+    ) -> ReconciliationResult<Option<ReconciliationProposal>> {
+        let schema_variant_id = Component::schema_variant_id(ctx, component_id).await?;
+        let Some(func_id) =
+            SchemaVariant::find_reconciliation_func_id(ctx, schema_variant_id).await?
+        else {
+            return Ok(None);
+        };
+
+        let component = Component::get_by_id(ctx, component_id).await?;
+        let Some(payload) = component
+            .resource(ctx)
+            .await?
+            .and_then(|resource| resource.payload)
+        else {
+            return Ok(None);
+        };
+
+        let domain_av_id = component.domain_prop_attribute_value(ctx).await?;
+        let domain = Component::view_by_id(ctx, component_id)
+            .await?
+            .and_then(|view| view.get("domain").cloned())
+            .unwrap_or(Value::Null);
+
+        let mut diffs = HashMap::new();
+        for entry in ResourceDrift::compute(&domain, &payload) {
+            let Some(attribute_value_id) =
+                Self::resolve_domain_path(ctx, domain_av_id, &entry.path).await?
+            else {
+                continue;
+            };
+
+            diffs.insert(
+                format!("/root/domain{}", entry.path),
+                ReconciliationDiff {
+                    normalized_resource: None,
+                    resource: entry.actual,
+                    domain: ReconciliationDiffDomain {
+                        id: attribute_value_id,
+                        value: entry.modeled,
+                    },
+                },
+            );
+        }
+
+        if diffs.is_empty() {
+            return Ok(None);
+        }
+
+        let result = Self::run(ctx, func_id, component_id, diffs).await?;
+
+        let mut updates = Vec::with_capacity(result.updates.len());
+        for (attribute_value_id, proposed) in result.updates {
+            Self::ensure_in_domain(ctx, component_id, domain_av_id, attribute_value_id).await?;
+
+            let current = AttributeValue::get_by_id_or_error(ctx, attribute_value_id)
+                .await?
+                .value(ctx)
+                .await?;
+            updates.push(ReconciliationUpdate {
+                attribute_value_id,
+                path: AttributeValue::get_path_for_id(ctx, attribute_value_id).await?,
+                current,
+                proposed,
+            });
+        }
+        updates.sort_by(|a, b| a.path.cmp(&b.path));
+
+        Ok(Some(ReconciliationProposal {
+            component_id,
+            func_id,
+            updates,
+            actions: result.actions,
+            message: result.message,
+        }))
+    }
+
+    /// Applies the given domain updates to the [`Component`] and enqueues the named actions. This
+    /// is meant to be called in a change set, with updates and actions that the user picked from a
+    /// [`ReconciliationProposal`].
+    #[instrument(name = "reconciliation.accept", level = "info", skip(ctx, updates))]
+    pub async fn accept(
+        ctx: &DalContext,
+        component_id: ComponentId,
+        updates: Vec<(AttributeValueId, Value)>,
+        actions: Vec<String>,
+    ) -> ReconciliationResult<Vec<ActionId>> {
+        let component = Component::get_by_id(ctx, component_id).await?;
+        let domain_av_id = component.domain_prop_attribute_value(ctx).await?;
+
+        for (attribute_value_id, value) in updates {
+            Self::ensure_in_domain(ctx, component_id, domain_av_id, attribute_value_id).await?;
+            AttributeValue::set_value(ctx, attribute_value_id, Some(value)).await?;
+        }
+
+        let schema_variant_id = Component::schema_variant_id(ctx, component_id).await?;
+        let prototypes = ActionPrototype::for_variant(ctx, schema_variant_id).await?;
+
+        let mut action_ids = Vec::with_capacity(actions.len());
+        for action in actions {
+            let prototype = prototypes
+                .iter()
+                .find(|prototype| prototype.name == action)
+                .ok_or_else(|| ReconciliationError::ActionNotFound(action, component_id))?;
+
+            if Action::find_equivalent(ctx, prototype.id(), Some(component_id))
+                .await?
+                .is_some()
+            {
+                continue;
+            }
+
+            let action = Action::new(ctx, prototype.id(), Some(component_id)).await?;
+            action_ids.push(action.id());
+        }
+
+        Ok(action_ids)
+    }
+
+    async fn run(
+        ctx: &DalContext,
+        func_id: FuncId,
+        component_id: ComponentId,
+        diffs: HashMap<String, ReconciliationDiff>,
+    ) -> ReconciliationResult<ReconciliationFuncResult> {
+        let func = Func::get_by_id_or_error(ctx, func_id).await?;
+        let args = serde_json::to_value(diffs)?;
+
+        let (_, result_channel) =
+            FuncRunner::run_reconciliation(ctx, func, args, component_id).await?;
+        let func_run_value = result_channel
+            .await
+            .map_err(|_| ReconciliationError::FuncRunnerSend)??;
+
+        FuncRunner::record_success(ctx, &func_run_value).await?;
+
+        let value = func_run_value.value().cloned().unwrap_or(Value::Null);
+        Ok(serde_json::from_value(value)?)
+    }
+
+    /// Finds the [`AttributeValue`] under the domain for a JSON pointer relative to the domain.
+    /// Object fields are matched by prop name and map entries by key.
+    async fn resolve_domain_path(
+        ctx: &DalContext,
+        domain_av_id: AttributeValueId,
+        path: &str,
+    ) -> ReconciliationResult<Option<AttributeValueId>> {
+        let mut current = domain_av_id;
+
+        for segment in path.split('/').skip(1) {
+            let segment = segment.replace("~1", "/").replace("~0", "~");
+
+            let mut found = None;
+            for child_av_id in AttributeValue::get_child_av_ids_in_order(ctx, current).await? {
+                let name = match AttributeValue::key_for_id(ctx, child_av_id).await? {
+                    Some(key) => Some(key),
+                    None => AttributeValue::prop_for_id(ctx, child_av_id)
+                        .await?
+                        .map(|prop| prop.name),
+                };
+                if name.as_deref() == Some(segment.as_str()) {
+                    found = Some(child_av_id);
+                    break;
+                }
+            }
+
+            match found {
+                Some(child_av_id) => current = child_av_id,
+                None => return Ok(None),
+            }
+        }
+
+        Ok(Some(current))
+    }
+
+    async fn ensure_in_domain(
+        ctx: &DalContext,
+        component_id: ComponentId,
+        domain_av_id: AttributeValueId,
+        attribute_value_id: AttributeValueId,
+    ) -> ReconciliationResult<()> {
+        if AttributeValue::component_id(ctx, attribute_value_id).await? != component_id {
+            return Err(ReconciliationError::AttributeValueNotForComponent(
+                attribute_value_id,
+                component_id,
+            ));
+        }
+
+        let mut current = attribute_value_id;
+        while let Some(parent_av_id) =
+            AttributeValue::parent_attribute_value_id(ctx, current).await?
+        {
+            if parent_av_id == domain_av_id {
+                return Ok(());
+            }
+            current = parent_av_id;
+        }
+
+        Err(ReconciliationError::AttributeValueNotInDomain(
+            attribute_value_id,
+            component_id,
+        ))
+    }
+}
@@ -0,0 +1,324 @@
+//! This module contains the ability to adopt existing resources as [`Components`](Component).
+//!
+//! A [`SchemaVariant`] can ship an import [`Func`], which takes the identifier of a resource that
+//! already exists and returns the resource alongside the domain that models it. Importing creates
+//! a [`Component`] with the resource already set and without a create action enqueued, since the
+//! resource does not need to be created.
+//!
+//! The import [`Func`] receives `{ "resourceId": "<identifier>" }` as its input and is expected
+//! to return an object shaped like [`ImportFuncOutput`].
+
+use std::collections::VecDeque;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use telemetry::prelude::*;
+use thiserror::Error;
+use veritech_client::ResourceStatus;
+
+use crate::action::prototype::{ActionKind, ActionPrototypeError};
+use crate::action::{Action, ActionError};
+use crate::attribute::value::AttributeValueError;
+use crate::component::frame::{Frame, FrameError};
+use crate::component::resource::ResourceData;
+use crate::func::runner::{FuncRunner, FuncRunnerError};
+use crate::{
+    AttributeValue, AttributeValueId, Component, ComponentError, ComponentId, DalContext, Func,
+    FuncError, PropKind, SchemaVariant, SchemaVariantError, SchemaVariantId,
+};
+
+#[remain::sorted]
+#[derive(Debug, Error)]
+pub enum ResourceImportError {
+    #[error("action error: {0}")]
+    Action(#[from] ActionError),
+    #[error("action prototype error: {0}")]
+    ActionPrototype(#[from] ActionPrototypeError),
+    #[error("attribute value error: {0}")]
+    AttributeValue(#[from] AttributeValueError),
+    #[error("component error: {0}")]
+    Component(#[from] ComponentError),
+    #[error("frame error: {0}")]
+    Frame(#[from] FrameError),
+    #[error("func error: {0}")]
+    Func(#[from] FuncError),
+    #[error("func runner error: {0}")]
+    FuncRunner(#[from] FuncRunnerError),
+    #[error("func runner result channel closed before receiving a value")]
+    FuncRunnerSend,
+    #[error("import func for schema variant {0} returned an error: {1}")]
+    ImportFuncFailed(SchemaVariantId, String),
+    #[error("import func for schema variant {0} did not return a resource")]
+    ImportFuncReturnedNoResource(SchemaVariantId),
+    #[error("schema variant {0} has no import func")]
+    NoImportFunc(SchemaVariantId),
+    #[error("schema variant error: {0}")]
+    SchemaVariant(#[from] SchemaVariantError),
+    #[error("serde json error: {0}")]
+    SerdeJson(#[from] serde_json::Error),
+}
+
+pub type ResourceImportResult<T> = Result<T, ResourceImportError>;
+
+/// The value an import [`Func`] is expected to return.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportFuncOutput {
+    /// The name of the new [`Component`]. Defaults to the resource identifier.
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub status: Option<ResourceStatus>,
+    pub payload: Option<Value>,
+    /// Values for the domain of the new [`Component`]. Fields without a matching prop are ignored.
+    #[serde(default)]
+    pub domain: Option<Value>,
+    #[serde(default)]
+    pub message: Option<String>,
+}
+
+/// The outcome of importing a single resource as part of [`ResourceImport::import_many`].
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceImportOutcome {
+    pub resource_id: String,
+    pub component_id: Option<ComponentId>,
+    pub error: Option<String>,
+}
+
+/// Namespace for importing ("adopting") existing resources.
+pub struct ResourceImport;
+
+impl ResourceImport {
+    /// Imports the resource with the given identifier as a new [`Component`] of the given
+    /// [`SchemaVariant`], optionally placing it in a frame.
+    ///
+    /// When a frame is given, the import [`Func`] runs with the credentials that the frame (or
+    /// the closest frame above it that has them) provides.
+    #[instrument(name = "resource_import.import", level = "info", skip(ctx))]
+    pub async fn import(
+        ctx: &DalContext,
+        schema_variant_id: SchemaVariantId,
+        resource_id: &str,
+        parent_id: Option<ComponentId>,
+    ) -> ResourceImportResult<ComponentId> {
+        let func = Self::import_func(ctx, schema_variant_id).await?;
+
+        Self::import_with(ctx, func, schema_variant_id, resource_id, parent_id).await
+    }
+
+    /// Imports every resource identifier as a new [`Component`] of the given [`SchemaVariant`].
+    ///
+    /// Each resource is imported on its own, so a failure to import one resource leaves no
+    /// component behind for it and does not prevent the others from being imported. The outcome
+    /// of each import is returned in the order the resource identifiers were given.
+    #[instrument(
+        name = "resource_import.import_many",
+        level = "info",
+        skip(ctx, resource_ids),
+        fields(si.resource_import.count = resource_ids.len())
+    )]
+    pub async fn import_many(
+        ctx: &DalContext,
+        schema_variant_id: SchemaVariantId,
+        resource_ids: Vec<String>,
+        parent_id: Option<ComponentId>,
+    ) -> ResourceImportResult<Vec<ResourceImportOutcome>> {
+        let func = Self::import_func(ctx, schema_variant_id).await?;
+
+        let mut outcomes = Vec::with_capacity(resource_ids.len());
+        for resource_id in resource_ids {
+            let outcome = match Self::import_with(
+                ctx,
+                func.clone(),
+                schema_variant_id,
+                &resource_id,
+                parent_id,
+            )
+            .await
+            {
+                Ok(component_id) => ResourceImportOutcome {
+                    resource_id,
+                    component_id: Some(component_id),
+                    error: None,
+                },
+                Err(err) => {
+                    warn!(si.error.message = ?err, %resource_id, "failed to import resource");
+                    ResourceImportOutcome {
+                        resource_id,
+                        component_id: None,
+                        error: Some(err.to_string()),
+                    }
+                }
+            };
+            outcomes.push(outcome);
+        }
+
+        Ok(outcomes)
+    }
+
+    async fn import_func(
+        ctx: &DalContext,
+        schema_variant_id: SchemaVariantId,
+    ) -> ResourceImportResult<Func> {
+        let func_id = SchemaVariant::find_import_func_id(ctx, schema_variant_id)
+            .await?
+            .ok_or(ResourceImportError::NoImportFunc(schema_variant_id))?;
+        Ok(Func::get_by_id_or_error(ctx, func_id).await?)
+    }
+
+    /// Creates a [`Component`] for a resource and adopts the resource with the import [`Func`].
+    /// The component is removed again if any part of the import fails.
+    async fn import_with(
+        ctx: &DalContext,
+        func: Func,
+        schema_variant_id: SchemaVariantId,
+        resource_id: &str,
+        parent_id: Option<ComponentId>,
+    ) -> ResourceImportResult<ComponentId> {
+        let component = Component::new(ctx, resource_id, schema_variant_id).await?;
+        let component_id = component.id();
+
+        match Self::adopt(
+            ctx,
+            func,
+            schema_variant_id,
+            component,
+            resource_id,
+            parent_id,
+        )
+        .await
+        {
+            Ok(()) => Ok(component_id),
+            Err(err) => Err(Self::abandon(ctx, component_id, err).await),
+        }
+    }
+
+    /// Runs the import [`Func`] for a freshly created [`Component`] and sets its resource and
+    /// domain.
+    async fn adopt(
+        ctx: &DalContext,
+        func: Func,
+        schema_variant_id: SchemaVariantId,
+        component: Component,
+        resource_id: &str,
+        parent_id: Option<ComponentId>,
+    ) -> ResourceImportResult<()> {
+        let component_id = component.id();
+
+        // The resource already exists, so the create actions enqueued for new components must go.
+        for action_id in
+            Action::find_for_kind_and_component_id(ctx, component_id, ActionKind::Create).await?
+        {
+            Action::remove_by_id(ctx, action_id).await?;
+        }
+
+        if let Some(parent_id) = parent_id {
+            Frame::upsert_parent(ctx, component_id, parent_id).await?;
+        }
+
+        let output = Self::run(ctx, func, component_id, resource_id).await?;
+        let payload = match (output.status, output.payload) {
+            (Some(ResourceStatus::Error), _) => {
+                return Err(ResourceImportError::ImportFuncFailed(
+                    schema_variant_id,
+                    output.message.unwrap_or_default(),
+                ));
+            }
+            (_, Some(payload)) => payload,
+            (_, None) => {
+                return Err(ResourceImportError::ImportFuncReturnedNoResource(
+                    schema_variant_id,
+                ));
+            }
+        };
+
+        if let Some(name) = output.name {
+            component.set_name(ctx, &name).await?;
+        }
+        if let Some(domain) = output.domain {
+            let domain_av_id = component.domain_prop_attribute_value(ctx).await?;
+            Self::set_domain_values(ctx, domain_av_id, domain).await?;
+        }
+        component
+            .set_resource(
+                ctx,
+                ResourceData::new(output.status.unwrap_or(ResourceStatus::Ok), Some(payload)),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    /// Removes a [`Component`] whose resource could not be imported and returns the error that
+    /// stopped the import. A failure to remove the component is logged rather than returned, so
+    /// that the caller sees why the import failed.
+    async fn abandon(
+        ctx: &DalContext,
+        component_id: ComponentId,
+        err: ResourceImportError,
+    ) -> ResourceImportError {
+        if let Err(remove_err) = Component::remove(ctx, component_id).await {
+            error!(
+                si.error.message = ?remove_err,
+                %component_id,
+                "failed to remove component after failing to import its resource"
+            );
+        }
+        err
+    }
+
+    async fn run(
+        ctx: &DalContext,
+        func: Func,
+        component_id: ComponentId,
+        resource_id: &str,
+    ) -> ResourceImportResult<ImportFuncOutput> {
+        let args = serde_json::json!({ "resourceId": resource_id });
+
+        let (_, result_channel) = FuncRunner::run_import(ctx, func, args, component_id).await?;
+        let func_run_value = result_channel
+            .await
+            .map_err(|_| ResourceImportError::FuncRunnerSend)??;
+
+        FuncRunner::record_success(ctx, &func_run_value).await?;
+
+        let value = func_run_value.value().cloned().unwrap_or(Value::Null);
+        Ok(serde_json::from_value(value)?)
+    }
+
+    /// Sets the values of the domain from the output of an import func, field by field, so that
+    /// props the func says nothing about keep their default values and functions.
+    async fn set_domain_values(
+        ctx: &DalContext,
+        domain_av_id: AttributeValueId,
+        domain: Value,
+    ) -> ResourceImportResult<()> {
+        let mut work_queue = VecDeque::from([(domain_av_id, domain)]);
+
+        while let Some((attribute_value_id, value)) = work_queue.pop_front() {
+            let Value::Object(fields) = value else {
+                continue;
+            };
+
+            for child_av_id in
+                AttributeValue::get_child_av_ids_in_order(ctx, attribute_value_id).await?
+            {
+                let Some(prop) = AttributeValue::prop_for_id(ctx, child_av_id).await? else {
+                    continue;
+                };
+                let Some(field_value) = fields.get(&prop.name) else {
+                    continue;
+                };
+
+                if prop.kind == PropKind::Object {
+                    work_queue.push_back((child_av_id, field_value.to_owned()));
+                } else {
+                    AttributeValue::update(ctx, child_av_id, Some(field_value.to_owned())).await?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
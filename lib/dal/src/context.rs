@@ -25,6 +25,7 @@ use tokio::sync::{MappedMutexGuard, Mutex, MutexGuard};
 use tokio::time::Instant;
 use veritech_client::Client as VeritechClient;
 
+use crate::audit_log::{self, AuditOperation, PendingAuditLogEntry};
 use crate::feature_flags::FeatureFlagService;
 use crate::job::definition::AttributeValueBasedJobIdentifier;
 use crate::layer_db_types::ContentTypes;
@@ -305,6 +306,9 @@ pub struct DalContext {
     /// Determines if we should not enqueue dependent value update jobs for attribute updates in
     /// this context. Useful for builtin migrations, since we don't care about attribute values propagation then.
     no_dependent_values: bool,
+    /// Determines if a commit which records no audit log entries of its own is logged as a graph
+    /// update. Turned off for contexts which no user drives directly, such as those running jobs.
+    audit_graph_updates: bool,
     /// The workspace snapshot for this context
     workspace_snapshot: Option<Arc<WorkspaceSnapshot>>,
     /// The change set for this context
@@ -453,14 +457,75 @@ impl DalContext {
         })
     }
 
+    /// Writes the audit log and the current rebase batch, returning the rebase request to commit
+    /// with, if there is anything to rebase.
+    async fn prepare_commit(&self) -> Result<Option<RebaseRequest>, TransactionsError> {
+        let rebase_batch = match &self.workspace_snapshot {
+            Some(snapshot) => snapshot.current_rebase_batch().await.map_err(Box::new)?,
+            None => None,
+        };
+
+        self.write_audit_log(rebase_batch.as_ref()).await?;
+
+        Ok(match rebase_batch {
+            Some(rebase_batch) => Some(RebaseRequest::new(
+                self.change_set_id(),
+                self.write_rebase_batch(rebase_batch).await?,
+            )),
+            None => None,
+        })
+    }
+
+    /// Writes the audit log entries recorded against this context into its transaction, or an
+    /// entry for the graph update being committed if none were recorded and a user drove it.
+    async fn write_audit_log(
+        &self,
+        rebase_batch: Option<&RebaseBatch>,
+    ) -> Result<(), TransactionsError> {
+        let mut txns = self.txns().await?;
+
+        let mut entries = mem::take(&mut txns.audit_log);
+        // NOTE: commits without a user behind them (dependent values updates and other jobs,
+        // rebaser-driven commits, etc.) follow on from operations which are already audited, so
+        // they get no entry of their own.
+        if entries.is_empty()
+            && self.audit_graph_updates
+            && matches!(self.history_actor, HistoryActor::User(_))
+        {
+            if let Some(rebase_batch) = rebase_batch {
+                entries.extend(PendingAuditLogEntry::for_ctx(
+                    self,
+                    AuditOperation::GraphUpdate,
+                    audit_log::affected_entity_ids(rebase_batch),
+                ));
+            }
+        }
+
+        for entry in entries {
+            entry.insert(txns.pg()).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Records an operation in the audit log, which is written when this context is committed.
+    /// Operations performed outside of a workspace are not audited.
+    pub async fn record_audit(
+        &self,
+        operation: AuditOperation,
+        entity_ids: Vec<Ulid>,
+    ) -> Result<(), TransactionsError> {
+        let entity_ids = entity_ids.iter().map(ToString::to_string).collect();
+        if let Some(entry) = PendingAuditLogEntry::for_ctx(self, operation, entity_ids) {
+            self.txns().await?.audit_log.push(entry);
+        }
+
+        Ok(())
+    }
+
     /// Consumes all inner transactions and committing all changes made within them.
     pub async fn commit(&self) -> Result<(), TransactionsError> {
-        let rebase_request = self
-            .write_current_rebase_batch()
-            .await?
-            .map(|rebase_batch_address| {
-                RebaseRequest::new(self.change_set_id(), rebase_batch_address)
-            });
+        let rebase_request = self.prepare_commit().await?;
 
         if self.blocking {
             self.blocking_commit_internal(rebase_request).await
@@ -470,6 +535,8 @@ impl DalContext {
     }
 
     pub async fn commit_no_rebase(&self) -> Result<(), TransactionsError> {
+        self.write_audit_log(None).await?;
+
         if self.blocking {
             self.blocking_commit_internal(None).await?;
         } else {
@@ -545,6 +612,12 @@ impl DalContext {
         self.no_dependent_values
     }
 
+    /// Stops commits from this context from being logged as graph updates when they record no
+    /// audit log entries of their own.
+    pub fn set_no_graph_update_audit(&mut self) {
+        self.audit_graph_updates = false;
+    }
+
     pub fn services_context(&self) -> ServicesContext {
         self.services_context.clone()
     }
@@ -556,12 +629,7 @@ impl DalContext {
     /// Consumes all inner transactions, committing all changes made within them, and
     /// blocks until all queued jobs have reported as finishing.
     pub async fn blocking_commit(&self) -> Result<(), TransactionsError> {
-        let rebase_request = self
-            .write_current_rebase_batch()
-            .await?
-            .map(|rebase_batch_address| {
-                RebaseRequest::new(self.change_set_id(), rebase_batch_address)
-            });
+        let rebase_request = self.prepare_commit().await?;
 
         info!("rebase_request: {:?}", rebase_request);
 
@@ -569,6 +637,7 @@ impl DalContext {
     }
 
     pub async fn blocking_commit_no_rebase(&self) -> Result<(), TransactionsError> {
+        self.write_audit_log(None).await?;
         self.blocking_commit_internal(None).await?;
         Ok(())
     }
@@ -963,6 +1032,7 @@ impl DalContextBuilder {
             visibility: Visibility::new_head_fake(),
             history_actor: HistoryActor::SystemInit,
             no_dependent_values: self.no_dependent_values,
+            audit_graph_updates: true,
             workspace_snapshot: None,
             change_set: None,
             system_actor_id: Ulid::new(),
@@ -984,6 +1054,7 @@ impl DalContextBuilder {
             history_actor: access_builder.history_actor,
             visibility: Visibility::new_head_fake(),
             no_dependent_values: self.no_dependent_values,
+            audit_graph_updates: true,
             workspace_snapshot: None,
             change_set: None,
             system_actor_id: Ulid::new(),
@@ -1015,6 +1086,7 @@ impl DalContextBuilder {
             visibility: request_context.visibility,
             history_actor: request_context.history_actor,
             no_dependent_values: self.no_dependent_values,
+            audit_graph_updates: true,
             workspace_snapshot: None,
             change_set: None,
             system_actor_id: Ulid::new(),
@@ -1205,6 +1277,8 @@ pub struct Transactions {
     nats_txn: NatsTxn,
     job_processor: Box<dyn JobQueueProcessor + Send + Sync>,
     job_queue: JobQueue,
+    /// Audit log entries to be written when committing.
+    audit_log: Vec<PendingAuditLogEntry>,
 }
 
 #[derive(Clone, Debug)]
@@ -1320,6 +1394,7 @@ impl Transactions {
             nats_txn,
             job_processor,
             job_queue: JobQueue::new(),
+            audit_log: Vec::new(),
         }
     }
 
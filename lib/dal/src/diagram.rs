@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 use si_data_pg::PgError;
-use std::collections::{hash_map, HashMap, HashSet};
+use std::collections::{hash_map, BTreeMap, HashMap, HashSet};
 use std::num::{ParseFloatError, ParseIntError};
 use strum::{AsRefStr, Display, EnumIter, EnumString};
 use telemetry::prelude::*;
@@ -134,6 +134,7 @@ pub struct SummaryDiagramComponent {
     pub to_delete: bool,
     pub can_be_upgraded: bool,
     pub from_base_change_set: bool,
+    pub labels: BTreeMap<String, String>,
 }
 
 impl SummaryDiagramComponent {
@@ -259,6 +260,7 @@ impl SummaryDiagramComponent {
             to_delete: component.to_delete(),
             can_be_upgraded,
             from_base_change_set: false,
+            labels: component.labels().to_owned(),
         })
     }
 }
@@ -17,7 +17,7 @@ use thiserror::Error;
 use crate::change_set::ChangeSetError;
 use crate::func::argument::FuncArgumentId;
 use crate::func::intrinsics::IntrinsicFunc;
-use crate::layer_db_types::{FuncContent, FuncContentV2};
+use crate::layer_db_types::{FuncContent, FuncContentV5};
 use crate::workspace_snapshot::edge_weight::{
     EdgeWeightError, EdgeWeightKind, EdgeWeightKindDiscriminants,
 };
@@ -32,14 +32,17 @@ use crate::{
 };
 
 use self::backend::{FuncBackendKind, FuncBackendResponseType};
+use self::test_case::FuncTestCase;
 
 pub mod argument;
 pub mod authoring;
 pub mod backend;
 pub mod binding;
 pub mod intrinsics;
+pub mod replay;
 pub mod runner;
 pub mod summary;
+pub mod test_case;
 pub mod view;
 
 mod associations;
@@ -104,7 +107,7 @@ pub type FuncResult<T> = Result<T, FuncError>;
 
 impl From<Func> for FuncContent {
     fn from(value: Func) -> Self {
-        Self::V2(FuncContentV2 {
+        Self::V5(FuncContentV5 {
             timestamp: value.timestamp,
             display_name: value.display_name,
             description: value.description,
@@ -117,6 +120,9 @@ impl From<Func> for FuncContent {
             code_base64: value.code_base64,
             code_blake3: value.code_blake3,
             is_locked: value.is_locked,
+            test_cases: value.test_cases,
+            timeout_secs: value.timeout_secs,
+            is_pure: value.is_pure,
         })
     }
 }
@@ -174,10 +180,13 @@ pub struct Func {
     pub code_base64: Option<String>,
     pub code_blake3: ContentHash,
     pub is_locked: bool,
+    pub test_cases: Vec<FuncTestCase>,
+    pub timeout_secs: Option<u64>,
+    pub is_pure: bool,
 }
 
 impl Func {
-    pub fn assemble(node_weight: &FuncNodeWeight, content: FuncContentV2) -> Self {
+    pub fn assemble(node_weight: &FuncNodeWeight, content: FuncContentV5) -> Self {
         Self {
             id: node_weight.id().into(),
             name: node_weight.name().to_owned(),
@@ -195,6 +204,9 @@ impl Func {
             code_base64: content.code_base64,
             code_blake3: content.code_blake3,
             is_locked: content.is_locked,
+            test_cases: content.test_cases,
+            timeout_secs: content.timeout_secs,
+            is_pure: content.is_pure,
         }
     }
 
@@ -250,7 +262,7 @@ impl Func {
             ContentHash::new("".as_bytes())
         };
 
-        let content = FuncContentV2 {
+        let content = FuncContentV5 {
             timestamp,
             display_name: display_name.map(Into::into),
             description: description.map(Into::into),
@@ -263,13 +275,16 @@ impl Func {
             code_base64,
             code_blake3,
             is_locked: false,
+            test_cases: Vec::new(),
+            timeout_secs: None,
+            is_pure: false,
         };
 
         let (hash, _) = ctx
             .layer_db()
             .cas()
             .write(
-                Arc::new(FuncContent::V2(content.clone()).into()),
+                Arc::new(FuncContent::V5(content.clone()).into()),
                 None,
                 ctx.events_tenancy(),
                 ctx.events_actor(),
@@ -351,7 +366,7 @@ impl Func {
         )?;
 
         // migrate if necessary!
-        let inner: FuncContentV2 = content.extract();
+        let inner: FuncContentV5 = content.extract();
 
         Ok(Self::assemble(func_node_weight, inner))
     }
@@ -410,7 +425,13 @@ impl Func {
                 | IntrinsicFunc::SetObject
                 | IntrinsicFunc::SetString
                 | IntrinsicFunc::Unset => false,
-                IntrinsicFunc::Identity | IntrinsicFunc::Validation => true,
+                IntrinsicFunc::Base64Encode
+                | IntrinsicFunc::Identity
+                | IntrinsicFunc::JoinArray
+                | IntrinsicFunc::JsonPointer
+                | IntrinsicFunc::MapFromList
+                | IntrinsicFunc::StringTemplate
+                | IntrinsicFunc::Validation => true,
             },
             None => true,
         }
@@ -519,6 +540,32 @@ impl Func {
         Ok(Self::assemble(&node_weight, updated.extract()))
     }
 
+    /// Sets the [`FuncKind`] of the [`Func`]. This is for kinds that come from how a
+    /// [`SchemaVariant`](crate::SchemaVariant) uses the func rather than from its backend kind
+    /// (e.g. [`FuncKind::Import`]), so it is allowed on locked funcs.
+    pub(crate) async fn set_kind(ctx: &DalContext, id: FuncId, kind: FuncKind) -> FuncResult<()> {
+        let (mut node_weight, _) = Self::get_node_weight_and_content_hash_or_error(ctx, id).await?;
+        if node_weight.func_kind() == kind {
+            return Ok(());
+        }
+
+        let workspace_snapshot = ctx.workspace_snapshot()?;
+        let original_node_index = workspace_snapshot.get_node_index_by_id(id).await?;
+
+        node_weight.set_func_kind(kind);
+
+        workspace_snapshot
+            .add_node(NodeWeight::Func(
+                node_weight.new_with_incremented_vector_clock(ctx.vector_clock_id()?),
+            ))
+            .await?;
+        workspace_snapshot
+            .replace_references(original_node_index)
+            .await?;
+
+        Ok(())
+    }
+
     /// Deletes the [`Func`] and returns the name.
     pub async fn delete_by_id(ctx: &DalContext, id: FuncId) -> FuncResult<String> {
         let func = Self::get_by_id_or_error(ctx, id).await?;
@@ -699,6 +746,32 @@ impl Func {
         FuncArgument::list_for_func(ctx, new_func.id)
             .await
             .map_err(Box::new)?;
+
+        // Kinds that come from how a variant uses the func are not derived from the backend kind.
+        let new_func = if new_func.kind == self.kind {
+            new_func
+        } else {
+            Self::set_kind(ctx, new_func.id, self.kind).await?;
+            Self::get_by_id_or_error(ctx, new_func.id).await?
+        };
+
+        let new_func = if self.test_cases.is_empty() && self.timeout_secs.is_none() && !self.is_pure
+        {
+            new_func
+        } else {
+            let test_cases = self.test_cases.clone();
+            let timeout_secs = self.timeout_secs;
+            let is_pure = self.is_pure;
+            new_func
+                .modify(ctx, |func| {
+                    func.test_cases = test_cases;
+                    func.timeout_secs = timeout_secs;
+                    func.is_pure = is_pure;
+                    Ok(())
+                })
+                .await?
+        };
+
         Ok(new_func)
     }
 
@@ -757,6 +830,8 @@ impl Func {
             display_name: self.display_name.clone(),
             description: self.description.clone(),
             is_locked: self.is_locked,
+            timeout_secs: self.timeout_secs,
+            is_pure: self.is_pure,
             arguments,
             bindings: si_frontend_types::FuncBindings { bindings },
             types: Some(types),
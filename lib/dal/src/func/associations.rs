@@ -246,7 +246,11 @@ impl FuncAssociations {
                     input_types,
                 )
             }
-            FuncKind::Intrinsic | FuncKind::SchemaVariantDefinition | FuncKind::Unknown => {
+            FuncKind::Import
+            | FuncKind::Intrinsic
+            | FuncKind::Reconciliation
+            | FuncKind::SchemaVariantDefinition
+            | FuncKind::Unknown => {
                 debug!(?func.kind, "no associations or input type needed for func kind");
                 (None::<FuncAssociations>, String::new())
             }
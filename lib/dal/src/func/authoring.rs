@@ -54,6 +54,7 @@ use crate::attribute::prototype::AttributePrototypeError;
 use crate::attribute::value::AttributeValueError;
 use crate::func::argument::{FuncArgument, FuncArgumentError, FuncArgumentId, FuncArgumentKind};
 use crate::func::associations::{FuncAssociations, FuncAssociationsError};
+use crate::func::test_case::{FuncTestCase, FuncTestCaseResult};
 use crate::func::view::FuncViewError;
 use crate::func::FuncKind;
 use crate::prop::PropError;
@@ -81,6 +82,7 @@ use super::{AttributePrototypeArgumentBag, AttributePrototypeBag};
 mod create;
 mod execute;
 mod save;
+mod test_case;
 mod ts_types;
 
 #[allow(missing_docs)]
@@ -121,6 +123,8 @@ pub enum FuncAuthoringError {
     FuncRunner(#[from] FuncRunnerError),
     #[error("func runner has failed to send a value and exited")]
     FuncRunnerSend,
+    #[error("func ({0}) has no test case named \"{1}\"")]
+    FuncTestCaseNotFound(FuncId, String),
     #[error("func view error: {0}")]
     FuncView(#[from] FuncViewError),
     #[error("invalid func associations ({0:?}) for func ({1}) of kind: {2}")]
@@ -309,7 +313,7 @@ impl FuncAuthoringClient {
         }
 
         let (func_run_id, result_channel) =
-            FuncRunner::run_test(ctx, func, args, component_id).await?;
+            FuncRunner::run_test(ctx, func, args, Some(component_id)).await?;
 
         let func_run_value = result_channel
             .await
@@ -366,6 +370,89 @@ impl FuncAuthoringClient {
         Ok(func_run_id)
     }
 
+    /// Stores a [`FuncTestCase`] alongside the [`Func`], replacing the test case with the same
+    /// name if there is one.
+    #[instrument(
+        name = "func.authoring.set_test_case",
+        level = "info",
+        skip(ctx, test_case),
+        fields(si.func_test_case.name = test_case.name.as_str())
+    )]
+    pub async fn set_test_case(
+        ctx: &DalContext,
+        id: FuncId,
+        test_case: FuncTestCase,
+    ) -> FuncAuthoringResult<Func> {
+        let func = Func::get_by_id_or_error(ctx, id).await?;
+        func.error_if_locked()?;
+
+        let func = func
+            .modify(ctx, |func| {
+                match func
+                    .test_cases
+                    .iter_mut()
+                    .find(|existing| existing.name == test_case.name)
+                {
+                    Some(existing) => *existing = test_case,
+                    None => func.test_cases.push(test_case),
+                }
+                Ok(())
+            })
+            .await?;
+        Ok(func)
+    }
+
+    /// Removes the [`FuncTestCase`] with the given name from the [`Func`].
+    #[instrument(name = "func.authoring.remove_test_case", level = "info", skip(ctx))]
+    pub async fn remove_test_case(
+        ctx: &DalContext,
+        id: FuncId,
+        name: String,
+    ) -> FuncAuthoringResult<Func> {
+        let func = Func::get_by_id_or_error(ctx, id).await?;
+        func.error_if_locked()?;
+        if func.test_case(&name).is_none() {
+            return Err(FuncAuthoringError::FuncTestCaseNotFound(id, name));
+        }
+
+        let func = func
+            .modify(ctx, |func| {
+                func.test_cases.retain(|test_case| test_case.name != name);
+                Ok(())
+            })
+            .await?;
+        Ok(func)
+    }
+
+    /// Runs every [`FuncTestCase`] stored alongside the [`Func`] (or only the one with the given
+    /// name) and returns their results. Each test case is recorded as a func run.
+    ///
+    /// If a [`Component`](crate::Component) is given, its secrets are made available to the
+    /// [`Func`] as they would be for a test execution.
+    #[instrument(name = "func.authoring.run_test_cases", level = "info", skip(ctx))]
+    pub async fn run_test_cases(
+        ctx: &DalContext,
+        id: FuncId,
+        name: Option<String>,
+        component_id: Option<ComponentId>,
+    ) -> FuncAuthoringResult<Vec<FuncTestCaseResult>> {
+        let func = Func::get_by_id_or_error(ctx, id).await?;
+
+        let test_cases: Vec<&FuncTestCase> = match name {
+            Some(name) => vec![func
+                .test_case(&name)
+                .ok_or_else(|| FuncAuthoringError::FuncTestCaseNotFound(id, name.clone()))?],
+            None => func.test_cases.iter().collect(),
+        };
+
+        let mut results = Vec::with_capacity(test_cases.len());
+        for test_case in test_cases {
+            results.push(test_case::run_test_case(ctx, &func, test_case, component_id).await?);
+        }
+
+        Ok(results)
+    }
+
     /// Executes a [`Func`].
     #[instrument(name = "func.authoring.execute_func", level = "info", skip(ctx))]
     pub async fn execute_func(ctx: &DalContext, id: FuncId) -> FuncAuthoringResult<()> {
@@ -773,18 +860,28 @@ impl FuncAuthoringClient {
 
     /// Save metadata about the [`FuncId`]
     /// Returns an error if the [`Func`] is currently locked
+    ///
+    /// The timeout and purity of the [`Func`] are only changed when they are provided.
     #[instrument(level = "info", name = "func.authoring.update_func", skip(ctx))]
     pub async fn update_func(
         ctx: &DalContext,
         func_id: FuncId,
         display_name: Option<String>,
         description: Option<String>,
+        timeout_secs: Option<u64>,
+        is_pure: Option<bool>,
     ) -> FuncAuthoringResult<Func> {
         let func = Func::get_by_id_or_error(ctx, func_id).await?;
         func.error_if_locked()?;
         let updated_func = Func::modify_by_id(ctx, func.id, |func| {
             display_name.clone_into(&mut func.display_name);
             description.clone_into(&mut func.description);
+            if let Some(timeout_secs) = timeout_secs {
+                func.timeout_secs = Some(timeout_secs);
+            }
+            if let Some(is_pure) = is_pure {
+                func.is_pure = is_pure;
+            }
             Ok(())
         })
         .await?;
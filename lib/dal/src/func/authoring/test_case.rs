@@ -0,0 +1,83 @@
+use serde_json::Value;
+use telemetry::prelude::*;
+
+use crate::func::argument::FuncArgument;
+use crate::func::authoring::{FuncAuthoringError, FuncAuthoringResult};
+use crate::func::runner::FuncRunner;
+use crate::func::test_case::{FuncTestCase, FuncTestCaseInput, FuncTestCaseResult};
+use crate::{ComponentId, DalContext, Func, FuncBackendKind};
+
+#[instrument(
+    name = "func.authoring.run_test_cases.run_test_case",
+    level = "debug",
+    skip(ctx, func, test_case),
+    fields(si.func_test_case.name = test_case.name.as_str())
+)]
+pub(crate) async fn run_test_case(
+    ctx: &DalContext,
+    func: &Func,
+    test_case: &FuncTestCase,
+    component_id: Option<ComponentId>,
+) -> FuncAuthoringResult<FuncTestCaseResult> {
+    let args = args_for_input(ctx, func, &test_case.input).await?;
+
+    let (func_run_id, result_channel) =
+        FuncRunner::run_test(ctx, func.clone(), args, component_id).await?;
+
+    let func_run_value = match result_channel
+        .await
+        .map_err(|_| FuncAuthoringError::FuncRunnerSend)?
+    {
+        Ok(func_run_value) => func_run_value,
+        Err(err) => {
+            return Ok(FuncTestCaseResult {
+                name: test_case.name.clone(),
+                func_run_id: Some(func_run_id),
+                passed: false,
+                output: None,
+                message: Some(err.to_string()),
+            });
+        }
+    };
+
+    FuncRunner::record_success(ctx, &func_run_value).await?;
+
+    let output = func_run_value.value().cloned().unwrap_or(Value::Null);
+    let message = test_case.expectation.check(&output);
+
+    Ok(FuncTestCaseResult {
+        name: test_case.name.clone(),
+        func_run_id: Some(func_run_id),
+        passed: message.is_none(),
+        output: Some(output),
+        message,
+    })
+}
+
+/// Derives the arguments for the [`Func`] from the input of a [`FuncTestCase`]. Component fixtures
+/// are shaped the same way they would be for a real component: action funcs receive the whole
+/// fixture as the component properties and attribute funcs receive the top level fields named by
+/// their arguments (e.g. "domain" for a qualification).
+async fn args_for_input(
+    ctx: &DalContext,
+    func: &Func,
+    input: &FuncTestCaseInput,
+) -> FuncAuthoringResult<Value> {
+    let fixture = match input {
+        FuncTestCaseInput::Args(args) => return Ok(args.to_owned()),
+        FuncTestCaseInput::Component(fixture) => fixture,
+    };
+
+    Ok(match func.backend_kind {
+        FuncBackendKind::JsAction => serde_json::json!({ "properties": fixture }),
+        FuncBackendKind::JsAttribute => {
+            let mut args = serde_json::Map::new();
+            for argument in FuncArgument::list_for_func(ctx, func.id).await? {
+                let value = fixture.get(&argument.name).cloned().unwrap_or(Value::Null);
+                args.insert(argument.name, value);
+            }
+            Value::Object(args)
+        }
+        _ => fixture.to_owned(),
+    })
+}
@@ -2,30 +2,36 @@ use async_trait::async_trait;
 use tokio::sync::mpsc;
 
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use si_events::FuncRunId;
 use strum::{AsRefStr, Display, EnumIter, EnumString};
 use telemetry::prelude::*;
 use thiserror::Error;
 use veritech_client::{
     ActionRunResultSuccess, BeforeFunction, Client as VeritechClient, FunctionResult, OutputStream,
-    ResolverFunctionResponseType,
+    RequestPriority, ResolverFunctionResponseType,
 };
 
 use crate::label_list::ToLabelList;
 use crate::{DalContext, Func, FuncId, PropKind};
 
 pub mod array;
+pub mod base64_encode;
 pub mod boolean;
 pub mod diff;
 pub mod identity;
 pub mod integer;
+pub mod join_array;
 pub mod js_action;
 pub mod js_attribute;
 pub mod js_reconciliation;
 pub mod js_schema_variant_definition;
 pub mod json;
+pub mod json_pointer;
 pub mod map;
+pub mod map_from_list;
 pub mod object;
 pub mod string;
+pub mod string_template;
 pub mod validation;
 
 #[remain::sorted]
@@ -41,6 +47,10 @@ pub enum FuncBackendError {
     FunctionResultActionRun(FunctionResult<ActionRunResultSuccess>),
     #[error("invalid data - expected a valid array entry value, got: {0}")]
     InvalidArrayEntryData(serde_json::Value),
+    #[error("invalid list entry - expected an object with a string value for {0}, got: {1}")]
+    InvalidMapFromListEntry(String, serde_json::Value),
+    #[error("no value provided for template placeholder: {0}")]
+    MissingTemplateValue(String),
     #[error("result failure: kind={kind}, message={message}, backend={backend}")]
     ResultFailure {
         kind: String,
@@ -59,7 +69,8 @@ pub enum FuncBackendError {
 
 pub type FuncBackendResult<T> = Result<T, FuncBackendError>;
 
-#[remain::sorted]
+// NOTE: this enum is stored with postcard, which serializes variants by their position, so it is
+// not sorted and new variants must be added at the end.
 #[derive(
     Deserialize,
     Serialize,
@@ -93,28 +104,43 @@ pub enum FuncBackendKind {
     String,
     Unset,
     Validation,
+    /// Base64 encoding of a string
+    Base64Encode,
+    /// Joining the items of an array into a string
+    JoinArray,
+    /// Picking a value out of JSON by a JSON pointer
+    JsonPointer,
+    /// Building a map from a list of objects
+    MapFromList,
+    /// Rendering a string template
+    StringTemplate,
 }
 
 impl From<FuncBackendKind> for si_events::FuncBackendKind {
     fn from(value: FuncBackendKind) -> Self {
         match value {
             FuncBackendKind::Array => si_events::FuncBackendKind::Array,
+            FuncBackendKind::Base64Encode => si_events::FuncBackendKind::Base64Encode,
             FuncBackendKind::Boolean => si_events::FuncBackendKind::Boolean,
             FuncBackendKind::Diff => si_events::FuncBackendKind::Diff,
             FuncBackendKind::Identity => si_events::FuncBackendKind::Identity,
             FuncBackendKind::Integer => si_events::FuncBackendKind::Integer,
+            FuncBackendKind::JoinArray => si_events::FuncBackendKind::JoinArray,
             FuncBackendKind::JsAction => si_events::FuncBackendKind::JsAction,
             FuncBackendKind::JsAttribute => si_events::FuncBackendKind::JsAttribute,
             FuncBackendKind::JsAuthentication => si_events::FuncBackendKind::JsAuthentication,
             FuncBackendKind::Json => si_events::FuncBackendKind::Json,
+            FuncBackendKind::JsonPointer => si_events::FuncBackendKind::JsonPointer,
             FuncBackendKind::JsReconciliation => si_events::FuncBackendKind::JsReconciliation,
             FuncBackendKind::JsSchemaVariantDefinition => {
                 si_events::FuncBackendKind::JsSchemaVariantDefinition
             }
             FuncBackendKind::JsValidation => si_events::FuncBackendKind::JsValidation,
             FuncBackendKind::Map => si_events::FuncBackendKind::Map,
+            FuncBackendKind::MapFromList => si_events::FuncBackendKind::MapFromList,
             FuncBackendKind::Object => si_events::FuncBackendKind::Object,
             FuncBackendKind::String => si_events::FuncBackendKind::String,
+            FuncBackendKind::StringTemplate => si_events::FuncBackendKind::StringTemplate,
             FuncBackendKind::Unset => si_events::FuncBackendKind::Unset,
             FuncBackendKind::Validation => si_events::FuncBackendKind::Validation,
         }
@@ -125,22 +151,27 @@ impl From<si_events::FuncBackendKind> for FuncBackendKind {
     fn from(value: si_events::FuncBackendKind) -> Self {
         match value {
             si_events::FuncBackendKind::Array => FuncBackendKind::Array,
+            si_events::FuncBackendKind::Base64Encode => FuncBackendKind::Base64Encode,
             si_events::FuncBackendKind::Boolean => FuncBackendKind::Boolean,
             si_events::FuncBackendKind::Diff => FuncBackendKind::Diff,
             si_events::FuncBackendKind::Identity => FuncBackendKind::Identity,
             si_events::FuncBackendKind::Integer => FuncBackendKind::Integer,
+            si_events::FuncBackendKind::JoinArray => FuncBackendKind::JoinArray,
             si_events::FuncBackendKind::JsAction => FuncBackendKind::JsAction,
             si_events::FuncBackendKind::JsAttribute => FuncBackendKind::JsAttribute,
             si_events::FuncBackendKind::JsAuthentication => FuncBackendKind::JsAuthentication,
             si_events::FuncBackendKind::Json => FuncBackendKind::Json,
+            si_events::FuncBackendKind::JsonPointer => FuncBackendKind::JsonPointer,
             si_events::FuncBackendKind::JsReconciliation => FuncBackendKind::JsReconciliation,
             si_events::FuncBackendKind::JsSchemaVariantDefinition => {
                 FuncBackendKind::JsSchemaVariantDefinition
             }
             si_events::FuncBackendKind::JsValidation => FuncBackendKind::JsValidation,
             si_events::FuncBackendKind::Map => FuncBackendKind::Map,
+            si_events::FuncBackendKind::MapFromList => FuncBackendKind::MapFromList,
             si_events::FuncBackendKind::Object => FuncBackendKind::Object,
             si_events::FuncBackendKind::String => FuncBackendKind::String,
+            si_events::FuncBackendKind::StringTemplate => FuncBackendKind::StringTemplate,
             si_events::FuncBackendKind::Unset => FuncBackendKind::Unset,
             si_events::FuncBackendKind::Validation => FuncBackendKind::Validation,
         }
@@ -305,20 +336,37 @@ impl ToLabelList for FuncBackendKind {}
 pub struct FuncDispatchContext {
     pub veritech: VeritechClient,
     pub output_tx: mpsc::Sender<OutputStream>,
+    /// The [`FuncRun`](si_events::FuncRun) being dispatched, used as the execution id for
+    /// requests which can be cancelled while in flight.
+    pub func_run_id: FuncRunId,
 }
 
 impl FuncDispatchContext {
-    pub fn new(ctx: &DalContext) -> (Self, mpsc::Receiver<OutputStream>) {
+    pub fn new(ctx: &DalContext, func_run_id: FuncRunId) -> (Self, mpsc::Receiver<OutputStream>) {
         let (output_tx, rx) = mpsc::channel(64);
+        let veritech = match ctx.tenancy().workspace_pk() {
+            Some(workspace_pk) => ctx
+                .veritech()
+                .clone()
+                .for_workspace(workspace_pk.to_string()),
+            None => ctx.veritech().clone(),
+        };
         (
             Self {
-                veritech: ctx.veritech().clone(),
+                veritech,
                 output_tx,
+                func_run_id,
             },
             rx,
         )
     }
 
+    /// Sets the priority veritech gives this dispatch's request over other waiting requests.
+    pub fn with_priority(mut self, priority: RequestPriority) -> Self {
+        self.veritech = self.veritech.with_priority(priority);
+        self
+    }
+
     pub fn into_inner(self) -> (VeritechClient, mpsc::Sender<OutputStream>) {
         (self.veritech, self.output_tx)
     }
@@ -359,7 +407,14 @@ pub trait FuncDispatch: std::fmt::Debug {
             .handler
             .as_deref()
             .ok_or_else(|| FuncBackendError::DispatchMissingHandler(func.id))?;
-        let value = Self::new(context, code_base64, handler, args, before);
+        let value = Self::new(
+            context,
+            code_base64,
+            handler,
+            args,
+            before,
+            func.timeout_secs,
+        );
         Ok(value)
     }
 
@@ -409,6 +464,7 @@ pub trait FuncDispatch: std::fmt::Debug {
         handler: &str,
         args: Self::Args,
         before: Vec<BeforeFunction>,
+        timeout_secs: Option<u64>,
     ) -> Box<Self>;
     async fn dispatch(self: Box<Self>) -> FuncBackendResult<FunctionResult<Self::Output>>;
 }
@@ -463,3 +519,47 @@ pub trait ExtractPayload {
 
     fn extract(self) -> FuncBackendResult<Self::Payload>;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The variants of [`FuncBackendKind`] as they were before the intrinsic backends were added.
+    #[derive(Serialize, Debug, Display, Clone, Copy, EnumIter)]
+    enum LegacyFuncBackendKind {
+        Array,
+        Boolean,
+        Diff,
+        Identity,
+        Integer,
+        JsAction,
+        JsAttribute,
+        JsAuthentication,
+        Json,
+        JsReconciliation,
+        JsSchemaVariantDefinition,
+        JsValidation,
+        Map,
+        Object,
+        String,
+        Unset,
+        Validation,
+    }
+
+    #[test]
+    fn decodes_kinds_stored_before_intrinsic_backends() {
+        use strum::IntoEnumIterator;
+
+        for legacy in LegacyFuncBackendKind::iter() {
+            let bytes = postcard::to_stdvec(&legacy).expect("failed to serialize legacy kind");
+
+            let kind: FuncBackendKind =
+                postcard::from_bytes(&bytes).expect("failed to deserialize kind");
+            assert_eq!(legacy.to_string(), kind.to_string());
+
+            let kind: si_events::FuncBackendKind =
+                postcard::from_bytes(&bytes).expect("failed to deserialize si_events kind");
+            assert_eq!(legacy.to_string(), kind.to_string());
+        }
+    }
+}
@@ -0,0 +1,33 @@
+use async_trait::async_trait;
+use base64::{engine::general_purpose, Engine};
+use serde::{Deserialize, Serialize};
+
+use crate::func::backend::{FuncBackend, FuncBackendResult};
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct FuncBackendBase64EncodeArgs {
+    pub value: String,
+}
+
+/// Encodes a string as standard, padded base64.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct FuncBackendBase64Encode {
+    args: FuncBackendBase64EncodeArgs,
+}
+
+#[async_trait]
+impl FuncBackend for FuncBackendBase64Encode {
+    type Args = FuncBackendBase64EncodeArgs;
+
+    fn new(args: Self::Args) -> Box<Self> {
+        Box::new(Self { args })
+    }
+
+    async fn inline(
+        self: Box<Self>,
+    ) -> FuncBackendResult<(Option<serde_json::Value>, Option<serde_json::Value>)> {
+        let encoded = general_purpose::STANDARD.encode(self.args.value);
+        let value = serde_json::to_value(encoded)?;
+        Ok((Some(value.clone()), Some(value)))
+    }
+}
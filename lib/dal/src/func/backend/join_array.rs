@@ -0,0 +1,45 @@
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::func::backend::{FuncBackend, FuncBackendResult};
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct FuncBackendJoinArrayArgs {
+    pub items: Option<Vec<serde_json::Value>>,
+    #[serde(default)]
+    pub separator: Option<String>,
+}
+
+/// Joins the items of an array into a string. String items are joined as-is and any other item
+/// is joined as its JSON representation.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct FuncBackendJoinArray {
+    args: FuncBackendJoinArrayArgs,
+}
+
+#[async_trait]
+impl FuncBackend for FuncBackendJoinArray {
+    type Args = FuncBackendJoinArrayArgs;
+
+    fn new(args: Self::Args) -> Box<Self> {
+        Box::new(Self { args })
+    }
+
+    async fn inline(
+        self: Box<Self>,
+    ) -> FuncBackendResult<(Option<serde_json::Value>, Option<serde_json::Value>)> {
+        let joined = self
+            .args
+            .items
+            .unwrap_or_default()
+            .iter()
+            .map(|item| match item {
+                serde_json::Value::String(item) => item.to_owned(),
+                other => other.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join(self.args.separator.as_deref().unwrap_or_default());
+        let value = serde_json::to_value(joined)?;
+        Ok((Some(value.clone()), Some(value)))
+    }
+}
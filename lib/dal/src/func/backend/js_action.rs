@@ -31,15 +31,16 @@ impl FuncDispatch for FuncBackendJsAction {
         handler: &str,
         args: Self::Args,
         before: Vec<BeforeFunction>,
+        timeout_secs: Option<u64>,
     ) -> Box<Self> {
         let request = ActionRunRequest {
-            // Once we start tracking the state of these executions, then this id will be useful,
-            // but for now it's passed along and back, and is opaue
-            execution_id: "ayrtonsennajscommand".to_string(),
+            // The func run id identifies the execution so that it can be cancelled while in flight
+            execution_id: context.func_run_id.to_string(),
             handler: handler.into(),
             code_base64: code_base64.into(),
             args: args.0,
             before,
+            timeout_secs,
         };
 
         Box::new(Self { context, request })
@@ -63,6 +64,7 @@ impl FuncDispatch for FuncBackendJsAction {
                             group: None,
                             message: message.clone(),
                             timestamp: std::cmp::max(Utc::now().timestamp(), 0) as u64,
+                            fields: Default::default(),
                         })
                         .await
                         .map_err(|_| FuncBackendError::SendError)?;
@@ -72,6 +74,10 @@ impl FuncDispatch for FuncBackendJsAction {
 
                 FunctionResult::Success(value)
             }
+            // A cancelled execution is not a failed action, so pass it through as is
+            FunctionResult::Failure(failure) if failure.error.is_cancelled() => {
+                FunctionResult::Failure(failure)
+            }
             FunctionResult::Failure(failure) => {
                 output_tx
                     .send(OutputStream {
@@ -81,6 +87,7 @@ impl FuncDispatch for FuncBackendJsAction {
                         group: None,
                         message: failure.error.message.clone(),
                         timestamp: std::cmp::max(Utc::now().timestamp(), 0) as u64,
+                        fields: Default::default(),
                     })
                     .await
                     .map_err(|_| FuncBackendError::SendError)?;
@@ -31,6 +31,7 @@ impl FuncDispatch for FuncBackendJsAttribute {
         handler: &str,
         args: Self::Args,
         before: Vec<BeforeFunction>,
+        timeout_secs: Option<u64>,
     ) -> Box<Self> {
         let request = ResolverFunctionRequest {
             // Once we start tracking the state of these executions, then this id will be useful,
@@ -41,6 +42,7 @@ impl FuncDispatch for FuncBackendJsAttribute {
             response_type: args.response_type,
             code_base64: code_base64.into(),
             before,
+            timeout_secs,
         };
 
         Box::new(Self { context, request })
@@ -44,6 +44,7 @@ impl FuncDispatch for FuncBackendJsReconciliation {
         handler: &str,
         args: Self::Args,
         before: Vec<BeforeFunction>,
+        timeout_secs: Option<u64>,
     ) -> Box<Self> {
         let request = ReconciliationRequest {
             // Once we start tracking the state of these executions, then this id will be useful,
@@ -54,6 +55,7 @@ impl FuncDispatch for FuncBackendJsReconciliation {
             args: serde_json::to_value(args)
                 .expect("should be impossible to fail serialization here"),
             before,
+            timeout_secs,
         };
 
         Box::new(Self { context, request })
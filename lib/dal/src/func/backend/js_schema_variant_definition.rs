@@ -22,11 +22,13 @@ impl FuncDispatch for FuncBackendJsSchemaVariantDefinition {
         handler: &str,
         _args: Self::Args,
         _before: Vec<BeforeFunction>,
+        timeout_secs: Option<u64>,
     ) -> Box<Self> {
         let request = SchemaVariantDefinitionRequest {
             execution_id: "villanelle".to_string(),
             handler: handler.into(),
             code_base64: code_base64.to_owned(),
+            timeout_secs,
         };
 
         Box::new(Self { context, request })
@@ -0,0 +1,41 @@
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::func::backend::{FuncBackend, FuncBackendResult};
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct FuncBackendJsonPointerArgs {
+    pub value: Option<serde_json::Value>,
+    /// An [RFC 6901](https://datatracker.ietf.org/doc/html/rfc6901) JSON pointer, such as
+    /// `/tags/0/key`.
+    pub pointer: String,
+}
+
+/// Picks the value at a JSON pointer out of a value, returning nothing if there is no value at
+/// the pointer.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct FuncBackendJsonPointer {
+    args: FuncBackendJsonPointerArgs,
+}
+
+#[async_trait]
+impl FuncBackend for FuncBackendJsonPointer {
+    type Args = FuncBackendJsonPointerArgs;
+
+    fn new(args: Self::Args) -> Box<Self> {
+        Box::new(Self { args })
+    }
+
+    async fn inline(
+        self: Box<Self>,
+    ) -> FuncBackendResult<(Option<serde_json::Value>, Option<serde_json::Value>)> {
+        let picked = self
+            .args
+            .value
+            .as_ref()
+            .and_then(|value| value.pointer(&self.args.pointer))
+            .cloned();
+        let value = serde_json::to_value(picked)?;
+        Ok((Some(value.clone()), Some(value)))
+    }
+}
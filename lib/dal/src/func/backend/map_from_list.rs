@@ -0,0 +1,48 @@
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::func::backend::{FuncBackend, FuncBackendError, FuncBackendResult};
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct FuncBackendMapFromListArgs {
+    pub items: Option<Vec<serde_json::Value>>,
+    /// The field of each item whose value becomes that item's key in the map.
+    pub key: String,
+}
+
+/// Builds a map from a list of objects, keyed by the value of one of their fields. Later items
+/// replace earlier items with the same key.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct FuncBackendMapFromList {
+    args: FuncBackendMapFromListArgs,
+}
+
+#[async_trait]
+impl FuncBackend for FuncBackendMapFromList {
+    type Args = FuncBackendMapFromListArgs;
+
+    fn new(args: Self::Args) -> Box<Self> {
+        Box::new(Self { args })
+    }
+
+    async fn inline(
+        self: Box<Self>,
+    ) -> FuncBackendResult<(Option<serde_json::Value>, Option<serde_json::Value>)> {
+        let mut map = serde_json::Map::new();
+        for item in self.args.items.unwrap_or_default() {
+            let key = match item.get(&self.args.key) {
+                Some(serde_json::Value::String(key)) => key.to_owned(),
+                _ => {
+                    return Err(FuncBackendError::InvalidMapFromListEntry(
+                        self.args.key,
+                        item,
+                    ))
+                }
+            };
+            map.insert(key, item);
+        }
+
+        let value = serde_json::Value::Object(map);
+        Ok((Some(value.clone()), Some(value)))
+    }
+}
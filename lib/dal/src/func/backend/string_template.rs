@@ -0,0 +1,108 @@
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::func::backend::{FuncBackend, FuncBackendError, FuncBackendResult};
+
+const OPEN: &str = "{{";
+const CLOSE: &str = "}}";
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct FuncBackendStringTemplateArgs {
+    /// The template, with `{{name}}` placeholders for values.
+    pub template: String,
+    pub values: Option<serde_json::Map<String, serde_json::Value>>,
+}
+
+/// Renders a string template, replacing each `{{name}}` placeholder with the value called `name`.
+/// String values are inserted as-is and any other value as its JSON representation.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct FuncBackendStringTemplate {
+    args: FuncBackendStringTemplateArgs,
+}
+
+#[async_trait]
+impl FuncBackend for FuncBackendStringTemplate {
+    type Args = FuncBackendStringTemplateArgs;
+
+    fn new(args: Self::Args) -> Box<Self> {
+        Box::new(Self { args })
+    }
+
+    async fn inline(
+        self: Box<Self>,
+    ) -> FuncBackendResult<(Option<serde_json::Value>, Option<serde_json::Value>)> {
+        let rendered = render(&self.args.template, &self.args.values.unwrap_or_default())?;
+        let value = serde_json::to_value(rendered)?;
+        Ok((Some(value.clone()), Some(value)))
+    }
+}
+
+fn render(
+    template: &str,
+    values: &serde_json::Map<String, serde_json::Value>,
+) -> FuncBackendResult<String> {
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find(OPEN) {
+        let Some(len) = rest[start + OPEN.len()..].find(CLOSE) else {
+            break;
+        };
+        let name = rest[start + OPEN.len()..start + OPEN.len() + len].trim();
+
+        rendered.push_str(&rest[..start]);
+        match values.get(name) {
+            Some(serde_json::Value::String(value)) => rendered.push_str(value),
+            Some(value) => rendered.push_str(&value.to_string()),
+            None => return Err(FuncBackendError::MissingTemplateValue(name.to_owned())),
+        }
+
+        rest = &rest[start + OPEN.len() + len + CLOSE.len()..];
+    }
+    rendered.push_str(rest);
+
+    Ok(rendered)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn values(values: serde_json::Value) -> serde_json::Map<String, serde_json::Value> {
+        values
+            .as_object()
+            .cloned()
+            .expect("values must be an object")
+    }
+
+    #[test]
+    fn renders_placeholders() {
+        let values = values(json!({ "name": "app", "port": 8080, "tags": ["a"] }));
+
+        assert_eq!(
+            "app.example.com:8080 [\"a\"]",
+            render("{{name}}.example.com:{{ port }} {{tags}}", &values)
+                .expect("failed to render template"),
+        );
+    }
+
+    #[test]
+    fn leaves_unterminated_placeholders() {
+        let values = values(json!({ "name": "canoe" }));
+
+        assert_eq!(
+            "canoe {{name",
+            render("{{name}} {{name", &values).expect("failed to render template"),
+        );
+    }
+
+    #[test]
+    fn errors_on_missing_values() {
+        assert!(matches!(
+            render("{{missing}}", &serde_json::Map::new()),
+            Err(FuncBackendError::MissingTemplateValue(name)) if name == "missing"
+        ));
+    }
+}
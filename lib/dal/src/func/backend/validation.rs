@@ -26,6 +26,7 @@ impl FuncDispatch for FuncBackendValidation {
         _handler: &str,
         args: Self::Args,
         _before: Vec<BeforeFunction>,
+        timeout_secs: Option<u64>,
     ) -> Box<Self> {
         let request = ValidationRequest {
             execution_id: "guarabyra".to_string(),
@@ -34,6 +35,7 @@ impl FuncDispatch for FuncBackendValidation {
             handler: "".to_string(),
             code_base64: "".to_string(),
             before: vec![],
+            timeout_secs,
         };
 
         Box::new(Self { context, request })
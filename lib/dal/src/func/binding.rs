@@ -360,7 +360,11 @@ impl FuncBinding {
                 LeafBinding::assemble_leaf_func_bindings(ctx, func_id, LeafKind::Qualification)
                     .await?
             }
-            FuncKind::SchemaVariantDefinition | FuncKind::Intrinsic | FuncKind::Unknown => vec![],
+            FuncKind::SchemaVariantDefinition
+            | FuncKind::Import
+            | FuncKind::Intrinsic
+            | FuncKind::Reconciliation
+            | FuncKind::Unknown => vec![],
         };
         Ok(bindings)
     }
@@ -524,7 +528,9 @@ impl FuncBinding {
             }
             FuncKind::Attribute => AttributeBinding::compile_attribute_types(ctx, func_id).await?,
             FuncKind::Authentication
+            | FuncKind::Import
             | FuncKind::Intrinsic
+            | FuncKind::Reconciliation
             | FuncKind::SchemaVariantDefinition
             | FuncKind::Unknown => String::new(),
         };
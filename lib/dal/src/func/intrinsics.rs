@@ -11,7 +11,11 @@ use crate::PropKind;
 #[remain::sorted]
 #[derive(AsRefStr, Display, EnumIter, EnumString, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum IntrinsicFunc {
+    Base64Encode,
     Identity,
+    JoinArray,
+    JsonPointer,
+    MapFromList,
     SetArray,
     SetBoolean,
     SetInteger,
@@ -19,6 +23,7 @@ pub enum IntrinsicFunc {
     SetMap,
     SetObject,
     SetString,
+    StringTemplate,
     Unset,
     Validation,
 }
@@ -54,6 +59,13 @@ impl IntrinsicFunc {
         // These magic unique ids are here to keep them consistent with the intrinsic ids in the
         // existing builtin packages (chicken/egg problem here a bit)
         match self {
+            Self::Base64Encode => {
+                builder
+                    .unique_id("c503c36f4101deb7dac53cc9a2d8118254e06854363828278113c2857cea1128");
+                data_builder.backend_kind(FuncSpecBackendKind::Base64Encode);
+                data_builder.response_type(FuncSpecBackendResponseType::String);
+                builder.argument(argument_spec("value", FuncArgumentKind::String, None)?);
+            }
             Self::Identity => {
                 builder
                     .unique_id("c6938e12287ab65f8ba8234559178413f2e2c02c44ea08384ed6687a36ec4f50");
@@ -67,6 +79,38 @@ impl IntrinsicFunc {
                         .map_err(|e| FuncError::IntrinsicSpecCreation(e.to_string()))?,
                 );
             }
+            Self::JoinArray => {
+                builder
+                    .unique_id("8f1f74c6a795f6ffaaa7781b1309eb5a9b07b35cc9f5764fa37da0e5e3329d3b");
+                data_builder.backend_kind(FuncSpecBackendKind::JoinArray);
+                data_builder.response_type(FuncSpecBackendResponseType::String);
+                builder.argument(argument_spec(
+                    "items",
+                    FuncArgumentKind::Array,
+                    Some(FuncArgumentKind::Any),
+                )?);
+                builder.argument(argument_spec("separator", FuncArgumentKind::String, None)?);
+            }
+            Self::JsonPointer => {
+                builder
+                    .unique_id("752ccfd16b869afcd345463d4eaa51b8abc2e36a2356446f65d9021b5da775b2");
+                data_builder.backend_kind(FuncSpecBackendKind::JsonPointer);
+                data_builder.response_type(FuncSpecBackendResponseType::Json);
+                builder.argument(argument_spec("value", FuncArgumentKind::Any, None)?);
+                builder.argument(argument_spec("pointer", FuncArgumentKind::String, None)?);
+            }
+            Self::MapFromList => {
+                builder
+                    .unique_id("babfbe146318ea8d977f7047bbfbd897f9c47f250e94a3d0749535bc8f7fd851");
+                data_builder.backend_kind(FuncSpecBackendKind::MapFromList);
+                data_builder.response_type(FuncSpecBackendResponseType::Map);
+                builder.argument(argument_spec(
+                    "items",
+                    FuncArgumentKind::Array,
+                    Some(FuncArgumentKind::Object),
+                )?);
+                builder.argument(argument_spec("key", FuncArgumentKind::String, None)?);
+            }
             Self::SetArray => {
                 builder
                     .unique_id("51049a590fb64860f159972012ac2657c629479a244d6bcc4b1b73ba4b29f87f");
@@ -160,6 +204,18 @@ impl IntrinsicFunc {
                         .map_err(|e| FuncError::IntrinsicSpecCreation(e.to_string()))?,
                 );
             }
+            Self::StringTemplate => {
+                builder
+                    .unique_id("ea7de4c912465bdec1305d7b9ea8bfdc62b1b9ad3e9a908bd3aebf3c819360a9");
+                data_builder.backend_kind(FuncSpecBackendKind::StringTemplate);
+                data_builder.response_type(FuncSpecBackendResponseType::String);
+                builder.argument(argument_spec("template", FuncArgumentKind::String, None)?);
+                builder.argument(argument_spec(
+                    "values",
+                    FuncArgumentKind::Map,
+                    Some(FuncArgumentKind::Any),
+                )?);
+            }
             Self::Unset => {
                 builder
                     .unique_id("8143ff98fbe8954bb3ab89ee521335d45ba9a42b7b79289eff53b503c4392c37");
@@ -193,7 +249,11 @@ impl IntrinsicFunc {
 
     pub fn name(&self) -> &str {
         match self {
+            Self::Base64Encode => "si:base64Encode",
             Self::Identity => "si:identity",
+            Self::JoinArray => "si:joinArray",
+            Self::JsonPointer => "si:jsonPointer",
+            Self::MapFromList => "si:mapFromList",
             Self::SetArray => "si:setArray",
             Self::SetBoolean => "si:setBoolean",
             Self::SetInteger => "si:setInteger",
@@ -201,6 +261,7 @@ impl IntrinsicFunc {
             Self::SetObject => "si:setObject",
             Self::SetJson => "si:setJson",
             Self::SetString => "si:setString",
+            Self::StringTemplate => "si:stringTemplate",
             Self::Unset => "si:unset",
             Self::Validation => "si:validation",
         }
@@ -208,7 +269,11 @@ impl IntrinsicFunc {
 
     pub fn maybe_from_str(s: impl AsRef<str>) -> Option<Self> {
         Some(match s.as_ref() {
+            "si:base64Encode" => Self::Base64Encode,
             "si:identity" => Self::Identity,
+            "si:joinArray" => Self::JoinArray,
+            "si:jsonPointer" => Self::JsonPointer,
+            "si:mapFromList" => Self::MapFromList,
             "si:setArray" => Self::SetArray,
             "si:setBoolean" => Self::SetBoolean,
             "si:setInteger" => Self::SetInteger,
@@ -216,6 +281,7 @@ impl IntrinsicFunc {
             "si:setObject" => Self::SetObject,
             "si:setJson" => Self::SetJson,
             "si:setString" => Self::SetString,
+            "si:stringTemplate" => Self::StringTemplate,
             "si:unset" => Self::Unset,
             "si:validation" => Self::Validation,
             _ => {
@@ -225,6 +291,19 @@ impl IntrinsicFunc {
     }
 }
 
+fn argument_spec(
+    name: &str,
+    kind: FuncArgumentKind,
+    element_kind: Option<FuncArgumentKind>,
+) -> FuncResult<FuncArgumentSpec> {
+    FuncArgumentSpec::builder()
+        .name(name)
+        .kind(kind)
+        .element_kind(element_kind)
+        .build()
+        .map_err(|e| FuncError::IntrinsicSpecCreation(e.to_string()))
+}
+
 impl From<PropKind> for IntrinsicFunc {
     fn from(value: PropKind) -> Self {
         match value {
@@ -7,7 +7,8 @@ use crate::func::FuncResult;
 use crate::{FuncBackendKind, FuncBackendResponseType, FuncError};
 
 /// Describes the kind of [`Func`](crate::Func).
-#[remain::sorted]
+// NOTE: this enum is stored with postcard, which serializes variants by their position, so it is
+// not sorted and new variants must be added at the end.
 #[derive(AsRefStr, Deserialize, Display, Serialize, Debug, Eq, PartialEq, Clone, Copy, Hash)]
 pub enum FuncKind {
     Action,
@@ -18,6 +19,8 @@ pub enum FuncKind {
     Qualification,
     SchemaVariantDefinition,
     Unknown,
+    Reconciliation,
+    Import,
 }
 
 impl From<EventFuncKind> for FuncKind {
@@ -31,6 +34,8 @@ impl From<EventFuncKind> for FuncKind {
             EventFuncKind::Qualification => FuncKind::Qualification,
             EventFuncKind::SchemaVariantDefinition => FuncKind::SchemaVariantDefinition,
             EventFuncKind::Unknown => FuncKind::Unknown,
+            EventFuncKind::Reconciliation => FuncKind::Reconciliation,
+            EventFuncKind::Import => FuncKind::Import,
         }
     }
 }
@@ -46,6 +51,8 @@ impl From<FuncKind> for si_events::FuncKind {
             FuncKind::Qualification => si_events::FuncKind::Qualification,
             FuncKind::SchemaVariantDefinition => si_events::FuncKind::SchemaVariantDefinition,
             FuncKind::Unknown => si_events::FuncKind::Unknown,
+            FuncKind::Reconciliation => si_events::FuncKind::Reconciliation,
+            FuncKind::Import => si_events::FuncKind::Import,
         }
     }
 }
@@ -64,6 +71,7 @@ impl FuncKind {
             FuncBackendKind::JsAction => Ok(FuncKind::Action),
             FuncBackendKind::JsAuthentication => Ok(FuncKind::Authentication),
             FuncBackendKind::JsSchemaVariantDefinition => Ok(FuncKind::SchemaVariantDefinition),
+            FuncBackendKind::JsReconciliation => Ok(FuncKind::Reconciliation),
             FuncBackendKind::JsValidation => {
                 warn!(
                     ?func_backend_kind,
@@ -73,14 +81,19 @@ impl FuncKind {
                 Ok(FuncKind::Unknown)
             }
             FuncBackendKind::Array
+            | FuncBackendKind::Base64Encode
             | FuncBackendKind::Json
+            | FuncBackendKind::JsonPointer
             | FuncBackendKind::Boolean
             | FuncBackendKind::Diff
             | FuncBackendKind::Identity
             | FuncBackendKind::Integer
+            | FuncBackendKind::JoinArray
             | FuncBackendKind::Map
+            | FuncBackendKind::MapFromList
             | FuncBackendKind::Object
             | FuncBackendKind::String
+            | FuncBackendKind::StringTemplate
             | FuncBackendKind::Unset
             | FuncBackendKind::Validation => Ok(FuncKind::Intrinsic),
             _ => Err(FuncError::UnknownFunctionType(
@@ -90,3 +103,53 @@ impl FuncKind {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use strum::EnumIter;
+
+    use super::*;
+
+    /// The variants of [`FuncKind`] as they were before reconciliation funcs had their own kind.
+    #[derive(Serialize, Debug, Display, Clone, Copy, EnumIter)]
+    enum LegacyFuncKind {
+        Action,
+        Attribute,
+        Authentication,
+        CodeGeneration,
+        Intrinsic,
+        Qualification,
+        SchemaVariantDefinition,
+        Unknown,
+    }
+
+    #[test]
+    fn decodes_kinds_stored_before_reconciliation_kind() {
+        use strum::IntoEnumIterator;
+
+        for legacy in LegacyFuncKind::iter() {
+            let bytes = postcard::to_stdvec(&legacy).expect("failed to serialize legacy kind");
+
+            let kind: FuncKind = postcard::from_bytes(&bytes).expect("failed to deserialize kind");
+            assert_eq!(legacy.to_string(), kind.to_string());
+
+            let kind: EventFuncKind =
+                postcard::from_bytes(&bytes).expect("failed to deserialize si_events kind");
+            assert_eq!(legacy.to_string(), kind.to_string());
+        }
+    }
+
+    #[test]
+    fn reconciliation_funcs_have_their_own_kind() {
+        let kind = FuncKind::new(
+            FuncBackendKind::JsReconciliation,
+            FuncBackendResponseType::Reconciliation,
+        )
+        .expect("could not determine func kind");
+        assert_eq!(FuncKind::Reconciliation, kind);
+        assert_eq!(
+            EventFuncKind::Reconciliation,
+            EventFuncKind::from(FuncKind::Reconciliation)
+        );
+    }
+}
@@ -0,0 +1,189 @@
+//! This module exports recorded [`FuncRuns`](FuncRun) as [`ReplayBundles`](ReplayBundle), which
+//! contain everything needed to run the func again outside of the dal (e.g. against a local
+//! cyclone) and compare the new result with the recorded one.
+//!
+//! Secrets given to the func via its "before funcs" are never exported: the bundle records where
+//! they were, so that whoever replays it can provide their own.
+
+use si_events::{ContentHash, FuncRun, FuncRunId};
+use si_layer_cache::LayerDbError;
+use thiserror::Error;
+use veritech_client::{
+    ActionRunRequest, ComponentView, OutputStream, ReconciliationRequest, RecordedExecution,
+    ReplayBundle, ReplayRequest, ResolverFunctionComponent, ResolverFunctionRequest,
+    SchemaVariantDefinitionRequest,
+};
+
+use crate::func::backend::{
+    FuncBackendKind, FuncBackendResponseType, InvalidResolverFunctionTypeError,
+};
+use crate::func::runner::{FuncRunner, FuncRunnerError};
+use crate::DalContext;
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum FuncReplayError {
+    #[error("func run {0} has no value in the cas at {1}")]
+    CasValueMissing(FuncRunId, ContentHash),
+    #[error("func runner error: {0}")]
+    FuncRunner(#[from] Box<FuncRunnerError>),
+    #[error("func run not found: {0}")]
+    FuncRunNotFound(FuncRunId),
+    #[error("invalid code for func run: {0}")]
+    InvalidCode(FuncRunId),
+    #[error("invalid resolver function type: {0}")]
+    InvalidResolverFunctionType(#[from] InvalidResolverFunctionTypeError),
+    #[error("layerdb error: {0}")]
+    LayerDb(#[from] LayerDbError),
+    #[error("func run did not record its handler: {0}")]
+    MissingHandler(FuncRunId),
+    #[error("func runs with backend kind {0} cannot be replayed")]
+    UnsupportedBackendKind(FuncBackendKind),
+}
+
+impl From<FuncRunnerError> for FuncReplayError {
+    fn from(value: FuncRunnerError) -> Self {
+        Box::new(value).into()
+    }
+}
+
+pub type FuncReplayResult<T> = Result<T, FuncReplayError>;
+
+/// Exports the [`FuncRun`] with the given id as a [`ReplayBundle`].
+///
+/// Only funcs which are run in cyclone can be replayed. Before funcs are those of the func run's
+/// component as it is now, since they are not recorded with the func run.
+pub async fn export_replay_bundle(
+    ctx: &DalContext,
+    func_run_id: FuncRunId,
+) -> FuncReplayResult<ReplayBundle> {
+    let func_run = ctx
+        .layer_db()
+        .func_run()
+        .read(func_run_id)
+        .await?
+        .ok_or(FuncReplayError::FuncRunNotFound(func_run_id))?;
+
+    let request = replay_request(ctx, &func_run).await?;
+
+    let result = match func_run.result_unprocessed_value_cas_address() {
+        Some(address) => FuncRunner::read_cas_value(ctx, address).await?,
+        None => None,
+    };
+    let output = ctx
+        .layer_db()
+        .func_run_log()
+        .get_for_func_run_id(func_run_id)
+        .await?
+        .map(|func_run_log| {
+            func_run_log
+                .logs()
+                .iter()
+                .map(|line| OutputStream {
+                    stream: line.stream.clone(),
+                    execution_id: line.execution_id.clone(),
+                    level: line.level.clone(),
+                    group: line.group.clone(),
+                    message: line.message.clone(),
+                    timestamp: line.timestamp,
+                    fields: line.fields.clone(),
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Ok(ReplayBundle::new(
+        func_run_id.to_string(),
+        func_run.function_name(),
+        request,
+        RecordedExecution { result, output },
+    ))
+}
+
+async fn replay_request(ctx: &DalContext, func_run: &FuncRun) -> FuncReplayResult<ReplayRequest> {
+    let func_run_id = func_run.id();
+    let backend_kind: FuncBackendKind = func_run.backend_kind().into();
+
+    match backend_kind {
+        FuncBackendKind::JsAction
+        | FuncBackendKind::JsAttribute
+        | FuncBackendKind::JsReconciliation
+        | FuncBackendKind::JsSchemaVariantDefinition => {}
+        unsupported => return Err(FuncReplayError::UnsupportedBackendKind(unsupported)),
+    }
+
+    let handler = func_run
+        .function_handler()
+        .ok_or(FuncReplayError::MissingHandler(func_run_id))?
+        .to_owned();
+    let code_base64 =
+        match read_cas_value(ctx, func_run_id, func_run.function_code_cas_address()).await? {
+            serde_json::Value::String(code_base64) => code_base64,
+            _ => return Err(FuncReplayError::InvalidCode(func_run_id)),
+        };
+    let args = read_cas_value(ctx, func_run_id, func_run.function_args_cas_address()).await?;
+
+    // The secrets are redacted when the bundle is created, so the func runner's usual encryption
+    // would only get in the way of filling them back in
+    let before = match func_run.component_id() {
+        Some(component_id) => FuncRunner::decrypted_before_funcs(ctx, component_id.into()).await?,
+        None => Vec::new(),
+    };
+    let execution_id = func_run_id.to_string();
+
+    Ok(match backend_kind {
+        FuncBackendKind::JsAction => ReplayRequest::ActionRun(ActionRunRequest {
+            execution_id,
+            handler,
+            code_base64,
+            args,
+            before,
+            timeout_secs: None,
+        }),
+        FuncBackendKind::JsReconciliation => ReplayRequest::Reconciliation(ReconciliationRequest {
+            execution_id,
+            handler,
+            code_base64,
+            args,
+            before,
+            timeout_secs: None,
+        }),
+        FuncBackendKind::JsSchemaVariantDefinition => {
+            ReplayRequest::SchemaVariantDefinition(SchemaVariantDefinitionRequest {
+                execution_id,
+                handler,
+                code_base64,
+                timeout_secs: None,
+            })
+        }
+        FuncBackendKind::JsAttribute => {
+            let response_type: FuncBackendResponseType = func_run.backend_response_type().into();
+            ReplayRequest::ResolverFunction(ResolverFunctionRequest {
+                execution_id,
+                handler,
+                component: ResolverFunctionComponent {
+                    data: ComponentView {
+                        properties: args,
+                        ..Default::default()
+                    },
+                    parents: Vec::new(),
+                },
+                response_type: response_type.try_into()?,
+                code_base64,
+                before,
+                timeout_secs: None,
+            })
+        }
+        unsupported => return Err(FuncReplayError::UnsupportedBackendKind(unsupported)),
+    })
+}
+
+async fn read_cas_value(
+    ctx: &DalContext,
+    func_run_id: FuncRunId,
+    address: ContentHash,
+) -> FuncReplayResult<serde_json::Value> {
+    FuncRunner::read_cas_value(ctx, address)
+        .await?
+        .ok_or(FuncReplayError::CasValueMissing(func_run_id, address))
+}
@@ -5,16 +5,17 @@ use std::sync::Arc;
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
 use si_events::{
-    ActionResultState, CasValue, ContentHash, EncryptedSecretKey, FuncRun, FuncRunBuilder,
-    FuncRunBuilderError, FuncRunId, FuncRunLog, FuncRunLogId, FuncRunValue,
+    ActionResultState, CasValue, ContentHash, EncryptedSecretKey, FuncMemo, FuncMemoKey,
+    FuncMemoStatus, FuncRun, FuncRunBuilder, FuncRunBuilderError, FuncRunId, FuncRunLog,
+    FuncRunLogId, FuncRunValue,
 };
 use si_layer_cache::LayerDbError;
 use telemetry::prelude::*;
 use thiserror::Error;
 use tokio::sync::{mpsc, oneshot};
 use veritech_client::{
-    encrypt_value_tree, BeforeFunction, OutputStream, ResolverFunctionComponent,
-    VeritechValueEncryptError,
+    encrypt_value_tree, BeforeFunction, FunctionResultFailureError, OutputStream, RequestPriority,
+    ResolverFunctionComponent, VeritechValueEncryptError,
 };
 
 use crate::attribute::prototype::argument::value_source::ValueSource;
@@ -40,18 +41,23 @@ use crate::{
 
 use super::backend::{
     array::FuncBackendArray,
+    base64_encode::FuncBackendBase64Encode,
     boolean::FuncBackendBoolean,
     diff::FuncBackendDiff,
     identity::FuncBackendIdentity,
     integer::FuncBackendInteger,
+    join_array::FuncBackendJoinArray,
     js_action::FuncBackendJsAction,
     js_attribute::{FuncBackendJsAttribute, FuncBackendJsAttributeArgs},
     js_reconciliation::FuncBackendJsReconciliation,
     js_schema_variant_definition::FuncBackendJsSchemaVariantDefinition,
     json::FuncBackendJson,
+    json_pointer::FuncBackendJsonPointer,
     map::FuncBackendMap,
+    map_from_list::FuncBackendMapFromList,
     object::FuncBackendObject,
     string::FuncBackendString,
+    string_template::FuncBackendStringTemplate,
     validation::FuncBackendValidation,
     FuncBackend, FuncDispatch, FuncDispatchContext, InvalidResolverFunctionTypeError,
 };
@@ -141,6 +147,9 @@ pub struct FuncRunner {
     func: Func,
     args: serde_json::Value,
     before: Vec<BeforeFunction>,
+    /// Set when the func is pure, so its result can be served from (and stored in) the
+    /// memoization cache.
+    memo_key: Option<FuncMemoKey>,
 }
 
 impl FuncRunner {
@@ -173,7 +182,7 @@ impl FuncRunner {
         ctx: &DalContext,
         func: Func,
         args: serde_json::Value,
-        component_id: ComponentId,
+        component_id: Option<ComponentId>,
     ) -> FuncRunnerResult<(FuncRunId, FuncRunnerValueChannel)> {
         // Prepares the function for execution.
         //
@@ -190,7 +199,7 @@ impl FuncRunner {
             ctx: &DalContext,
             func: Func,
             args: serde_json::Value,
-            component_id: ComponentId,
+            component_id: Option<ComponentId>,
             span: &Span,
         ) -> FuncRunnerResult<FuncRunner> {
             let function_args: CasValue = args.clone().into();
@@ -204,9 +213,13 @@ impl FuncRunner {
                     ctx.events_actor(),
                 )
                 .await?;
-            let before = FuncRunner::before_funcs(ctx, component_id).await?;
+            // Without a component, there are no secrets to provide to the func.
+            let before = match component_id {
+                Some(component_id) => FuncRunner::before_funcs(ctx, component_id).await?,
+                None => Vec::new(),
+            };
 
-            let component_id = component_id.into();
+            let component_id: Option<si_events::ComponentId> = component_id.map(Into::into);
 
             let func_run_create_time = Utc::now();
             let func_run_inner = FuncRunBuilder::default()
@@ -219,10 +232,11 @@ impl FuncRunner {
                 .function_display_name(func.display_name.clone())
                 .function_description(func.description.clone())
                 .function_link(func.link.clone())
+                .function_handler(func.handler.clone())
                 .function_args_cas_address(function_args_cas_address)
                 .function_code_cas_address(func.code_blake3)
                 .attribute_value_id(None)
-                .component_id(Some(component_id))
+                .component_id(component_id)
                 .created_at(func_run_create_time)
                 .updated_at(func_run_create_time)
                 .build()?;
@@ -245,7 +259,9 @@ impl FuncRunner {
                     "si.change_set.id",
                     func_run_inner.change_set_id().array_to_str(&mut id_buf),
                 );
-                span.record("si.component.id", component_id.array_to_str(&mut id_buf));
+                if let Some(component_id) = component_id {
+                    span.record("si.component.id", component_id.array_to_str(&mut id_buf));
+                }
                 span.record(
                     "si.workspace.id",
                     func_run_inner.workspace_pk().array_to_str(&mut id_buf),
@@ -269,6 +285,7 @@ impl FuncRunner {
                 func,
                 args,
                 before,
+                memo_key: None,
             })
         }
 
@@ -279,11 +296,99 @@ impl FuncRunner {
             .map_err(|err| span.record_err(err))?;
 
         let func_run_id = runner.id();
-        let result_channel = runner.execute(ctx.clone(), span).await;
+        // A user is waiting on the result of a test execution, so it goes ahead of background
+        // recomputation in veritech.
+        let result_channel = runner
+            .execute_with_priority(ctx.clone(), span, Some(RequestPriority::Interactive))
+            .await;
 
         Ok((func_run_id, result_channel))
     }
 
+    #[instrument(
+        name = "func_runner.run_reconciliation",
+        level = "debug",
+        skip_all,
+        fields(
+            job.id = Empty,
+            job.invoked_args = Empty,
+            // job.instance = metadata.job_instance,
+            job.invoked_name = func.name.as_str(),
+            // job.invoked_provider = metadata.job_invoked_provider,
+            otel.kind = SpanKind::Producer.as_str(),
+            otel.status_code = Empty,
+            otel.status_message = Empty,
+            si.change_set.id = Empty,
+            si.component.id = Empty,
+            si.func_run.func.args = Empty,
+            si.func_run.func.backend_kind = func.backend_kind.as_ref(),
+            si.func_run.func.backend_response_type = func.backend_response_type.as_ref(),
+            si.func_run.func.id = Empty,
+            si.func_run.func.kind = func.kind.as_ref(),
+            si.func_run.func.name = func.name.as_str(),
+            si.func_run.id = Empty,
+            si.workspace.id = Empty,
+        )
+    )]
+    /// Runs a reconciliation [`Func`] for the given [`Component`]. The args are expected to be a
+    /// map of attribute paths to their reconciliation diffs.
+    pub async fn run_reconciliation(
+        ctx: &DalContext,
+        func: Func,
+        args: serde_json::Value,
+        component_id: ComponentId,
+    ) -> FuncRunnerResult<(FuncRunId, FuncRunnerValueChannel)> {
+        let span = Span::current();
+
+        let before = FuncRunner::before_funcs(ctx, component_id)
+            .await
+            .map_err(|err| span.record_err(err))?;
+
+        Self::run_for_component(ctx, func, args, component_id, before, span).await
+    }
+
+    #[instrument(
+        name = "func_runner.run_import",
+        level = "debug",
+        skip_all,
+        fields(
+            job.id = Empty,
+            job.invoked_args = Empty,
+            // job.instance = metadata.job_instance,
+            job.invoked_name = func.name.as_str(),
+            // job.invoked_provider = metadata.job_invoked_provider,
+            otel.kind = SpanKind::Producer.as_str(),
+            otel.status_code = Empty,
+            otel.status_message = Empty,
+            si.change_set.id = Empty,
+            si.component.id = Empty,
+            si.func_run.func.args = Empty,
+            si.func_run.func.backend_kind = func.backend_kind.as_ref(),
+            si.func_run.func.backend_response_type = func.backend_response_type.as_ref(),
+            si.func_run.func.id = Empty,
+            si.func_run.func.kind = func.kind.as_ref(),
+            si.func_run.func.name = func.name.as_str(),
+            si.func_run.id = Empty,
+            si.workspace.id = Empty,
+        )
+    )]
+    /// Runs an import [`Func`] for the given [`Component`], which is adopting an existing resource.
+    /// The args are passed to the func as its input.
+    pub async fn run_import(
+        ctx: &DalContext,
+        func: Func,
+        args: serde_json::Value,
+        component_id: ComponentId,
+    ) -> FuncRunnerResult<(FuncRunId, FuncRunnerValueChannel)> {
+        let span = Span::current();
+
+        let before = FuncRunner::before_funcs_from_ancestry(ctx, component_id)
+            .await
+            .map_err(|err| span.record_err(err))?;
+
+        Self::run_for_component(ctx, func, args, component_id, before, span).await
+    }
+
     #[instrument(
         name = "func_runner.run_asset_definition_func",
         level = "debug",
@@ -375,6 +480,7 @@ impl FuncRunner {
                 .function_display_name(func.display_name.clone())
                 .function_description(func.description.clone())
                 .function_link(func.link.clone())
+                .function_handler(func.handler.clone())
                 .function_args_cas_address(function_args_cas_address)
                 .function_code_cas_address(code_cas_hash)
                 .attribute_value_id(None)
@@ -424,6 +530,7 @@ impl FuncRunner {
                 func: func.clone(),
                 args,
                 before: vec![],
+                memo_key: None,
             })
         }
 
@@ -546,6 +653,7 @@ impl FuncRunner {
                 .function_display_name(func.display_name.clone())
                 .function_description(func.description.clone())
                 .function_link(func.link.clone())
+                .function_handler(func.handler.clone())
                 .function_args_cas_address(function_args_cas_address)
                 .function_code_cas_address(code_cas_hash)
                 .attribute_value_id(Some(attribute_value_id))
@@ -609,6 +717,7 @@ impl FuncRunner {
                 func,
                 args,
                 before: vec![],
+                memo_key: None,
             })
         }
 
@@ -723,6 +832,7 @@ impl FuncRunner {
                 .function_display_name(func.display_name.clone())
                 .function_description(func.description.clone())
                 .function_link(func.link.clone())
+                .function_handler(func.handler.clone())
                 .function_args_cas_address(function_args_cas_address)
                 .function_code_cas_address(code_cas_hash)
                 .attribute_value_id(Some(attribute_value_id))
@@ -781,11 +891,17 @@ impl FuncRunner {
                 )
                 .await?;
 
+            // Only funcs that have been marked pure are memoized, and only if they have code to
+            // key on.
+            let memo_key = (func.is_pure && func.code_base64.is_some())
+                .then(|| FuncMemoKey::for_func_run(func.code_blake3, &args));
+
             Ok(FuncRunner {
                 func_run,
                 func,
                 args,
                 before,
+                memo_key,
             })
         }
 
@@ -933,6 +1049,7 @@ impl FuncRunner {
                 .function_display_name(func.display_name.clone())
                 .function_description(func.description.clone())
                 .function_link(func.link.clone())
+                .function_handler(func.handler.clone())
                 .function_args_cas_address(function_args_cas_address)
                 .function_code_cas_address(code_cas_hash)
                 .action_id(maybe_action_id.map(|a| a.into()))
@@ -1007,6 +1124,7 @@ impl FuncRunner {
                 func,
                 args,
                 before,
+                memo_key: None,
             })
         }
 
@@ -1021,13 +1139,181 @@ impl FuncRunner {
         Ok(result_channel)
     }
 
+    /// Runs a [`Func`] on behalf of a [`Component`] with args provided by the caller, rather than
+    /// args assembled from the graph, recording errors in the given span.
+    async fn run_for_component(
+        ctx: &DalContext,
+        func: Func,
+        args: serde_json::Value,
+        component_id: ComponentId,
+        before: Vec<BeforeFunction>,
+        span: Span,
+    ) -> FuncRunnerResult<(FuncRunId, FuncRunnerValueChannel)> {
+        let runner = Self::prepare_for_component(ctx, func, args, component_id, before, &span)
+            .await
+            .map_err(|err| span.record_err(err))?;
+
+        let func_run_id = runner.id();
+        let result_channel = runner.execute(ctx.clone(), span).await;
+
+        Ok((func_run_id, result_channel))
+    }
+
+    /// Prepares a [`Func`] run on behalf of a [`Component`] for execution.
+    ///
+    /// Note: this function is separate so we can record early-returning errors in span metadata
+    /// and in order to time the function's preparation vs. execution timings.
+    #[instrument(
+        name = "func_runner.prepare_for_component",
+        level = "debug",
+        skip_all,
+        fields()
+    )]
+    async fn prepare_for_component(
+        ctx: &DalContext,
+        func: Func,
+        args: serde_json::Value,
+        component_id: ComponentId,
+        before: Vec<BeforeFunction>,
+        span: &Span,
+    ) -> FuncRunnerResult<FuncRunner> {
+        let function_args: CasValue = args.clone().into();
+        let (function_args_cas_address, _) = ctx
+            .layer_db()
+            .cas()
+            .write(
+                Arc::new(function_args.into()),
+                None,
+                ctx.events_tenancy(),
+                ctx.events_actor(),
+            )
+            .await?;
+
+        let component = Component::get_by_id(ctx, component_id).await?;
+        let component_name = component.name(ctx).await?;
+        let schema_name = component.schema(ctx).await?.name;
+
+        let component_id = component_id.into();
+
+        let func_run_create_time = Utc::now();
+        let func_run_inner = FuncRunBuilder::default()
+            .actor(ctx.events_actor())
+            .tenancy(ctx.events_tenancy())
+            .backend_kind(func.backend_kind.into())
+            .backend_response_type(func.backend_response_type.into())
+            .function_name(func.name.clone())
+            .function_kind(func.kind.into())
+            .function_display_name(func.display_name.clone())
+            .function_description(func.description.clone())
+            .function_link(func.link.clone())
+            .function_handler(func.handler.clone())
+            .function_args_cas_address(function_args_cas_address)
+            .function_code_cas_address(func.code_blake3)
+            .attribute_value_id(None)
+            .component_id(Some(component_id))
+            .component_name(Some(component_name))
+            .schema_name(Some(schema_name))
+            .created_at(func_run_create_time)
+            .updated_at(func_run_create_time)
+            .build()?;
+
+        if !span.is_disabled() {
+            let mut id_buf = FuncRunId::array_to_str_buf();
+
+            let id = func_run_inner.id().array_to_str(&mut id_buf);
+            span.record("job.id", &id);
+            span.record("si.func_run.id", &id);
+
+            let invoked_args = serde_json::to_string(&args)
+                .unwrap_or_else(|_| "args failed to serialize".to_owned());
+            span.record("job.invoked_args", invoked_args.as_str());
+            span.record("si.func_run.func.args", invoked_args.as_str());
+
+            span.record("si.func_run.func.id", func.id.array_to_str(&mut id_buf));
+
+            span.record(
+                "si.change_set.id",
+                func_run_inner.change_set_id().array_to_str(&mut id_buf),
+            );
+            span.record("si.component.id", component_id.array_to_str(&mut id_buf));
+            span.record(
+                "si.workspace.id",
+                func_run_inner.workspace_pk().array_to_str(&mut id_buf),
+            );
+        }
+
+        let func_run = Arc::new(func_run_inner);
+
+        ctx.layer_db()
+            .func_run()
+            .write(
+                func_run.clone(),
+                None,
+                ctx.events_tenancy(),
+                ctx.events_actor(),
+            )
+            .await?;
+
+        Ok(FuncRunner {
+            func_run,
+            func,
+            args,
+            before,
+            memo_key: None,
+        })
+    }
+
+    /// Stores the values of a successful run in the CAS and marks its [`FuncRun`] as succeeded,
+    /// for runs whose values are not stored alongside what they were run for (e.g. an
+    /// [`AttributeValue`](crate::AttributeValue)).
+    pub(crate) async fn record_success(
+        ctx: &DalContext,
+        func_run_value: &FuncRunValue,
+    ) -> FuncRunnerResult<()> {
+        let unprocessed_value_address = match func_run_value.unprocessed_value() {
+            Some(value) => Some(FuncRunnerExecutionTask::write_cas_value(ctx, value).await?),
+            None => None,
+        };
+        let value_address = match func_run_value.value() {
+            Some(value) => Some(FuncRunnerExecutionTask::write_cas_value(ctx, value).await?),
+            None => None,
+        };
+
+        ctx.layer_db()
+            .func_run()
+            .set_values_and_set_state_to_success(
+                func_run_value.func_run_id(),
+                unprocessed_value_address,
+                value_address,
+                ctx.events_tenancy(),
+                ctx.events_actor(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
     fn id(&self) -> FuncRunId {
         self.func_run.id()
     }
 
     async fn execute(self, ctx: DalContext, execution_parent_span: Span) -> FuncRunnerValueChannel {
+        self.execute_with_priority(ctx, execution_parent_span, None)
+            .await
+    }
+
+    async fn execute_with_priority(
+        self,
+        ctx: DalContext,
+        execution_parent_span: Span,
+        priority: Option<RequestPriority>,
+    ) -> FuncRunnerValueChannel {
         let func_run_id = self.func_run.id();
-        let (func_dispatch_context, output_stream_rx) = FuncDispatchContext::new(&ctx);
+        let (mut func_dispatch_context, output_stream_rx) =
+            FuncDispatchContext::new(&ctx, func_run_id);
+        if let Some(priority) = priority {
+            func_dispatch_context = func_dispatch_context.with_priority(priority);
+        }
         let (result_tx, result_rx) = tokio::sync::oneshot::channel();
 
         let logs_task = FuncRunnerLogsTask {
@@ -1044,6 +1330,7 @@ impl FuncRunner {
             func: self.func,
             args: self.args,
             before: self.before,
+            memo_key: self.memo_key,
             parent_span: execution_parent_span,
         };
 
@@ -1060,6 +1347,43 @@ impl FuncRunner {
     async fn before_funcs(
         ctx: &DalContext,
         component_id: ComponentId,
+    ) -> FuncRunnerResult<Vec<BeforeFunction>> {
+        Self::collect_before_funcs(ctx, component_id, true).await
+    }
+
+    /// Collects the [`BeforeFunctions`](BeforeFunction) for a [`Component`] that was created in
+    /// this request. Its values have not propagated yet, so the credentials it will get from the
+    /// frames it is in are taken from the closest one that already has them.
+    async fn before_funcs_from_ancestry(
+        ctx: &DalContext,
+        component_id: ComponentId,
+    ) -> FuncRunnerResult<Vec<BeforeFunction>> {
+        let mut maybe_component_id = Some(component_id);
+        while let Some(component_id) = maybe_component_id {
+            let before = Self::before_funcs(ctx, component_id).await?;
+            if !before.is_empty() {
+                return Ok(before);
+            }
+            maybe_component_id = Component::get_parent_by_id(ctx, component_id).await?;
+        }
+
+        Ok(Vec::new())
+    }
+
+    /// Collects all [`BeforeFunctions`](BeforeFunction) for a given [`ComponentId`](Component)
+    /// with their secrets decrypted and without the workspace token, for callers which redact the
+    /// secrets before they leave the dal.
+    pub(crate) async fn decrypted_before_funcs(
+        ctx: &DalContext,
+        component_id: ComponentId,
+    ) -> FuncRunnerResult<Vec<BeforeFunction>> {
+        Self::collect_before_funcs(ctx, component_id, false).await
+    }
+
+    async fn collect_before_funcs(
+        ctx: &DalContext,
+        component_id: ComponentId,
+        for_dispatch: bool,
     ) -> FuncRunnerResult<Vec<BeforeFunction>> {
         let ordered_before_funcs_with_secret_keys =
             Self::ordered_before_funcs_with_secret_keys(ctx, component_id).await?;
@@ -1074,10 +1398,12 @@ impl FuncRunner {
             // Decrypt message from EncryptedSecret
             let mut arg = encrypted_secret.decrypt(ctx).await?.message().into_inner();
 
-            Self::inject_workspace_token(ctx, &mut arg).await?;
+            if for_dispatch {
+                Self::inject_workspace_token(ctx, &mut arg).await?;
 
-            // Re-encrypt raw Value for transmission to Veritech
-            encrypt_value_tree(&mut arg, ctx.encryption_key())?;
+                // Re-encrypt raw Value for transmission to Veritech
+                encrypt_value_tree(&mut arg, ctx.encryption_key())?;
+            }
 
             for func in funcs {
                 before_functions.push(BeforeFunction {
@@ -1315,6 +1641,7 @@ impl FuncRunnerLogsTask {
                 group: item.group,
                 message: item.message,
                 timestamp: item.timestamp,
+                fields: item.fields,
             });
 
             WsEvent::func_run_log_updated(&self.ctx, func_run_log.func_run_id(), func_run_log.id())
@@ -1365,6 +1692,7 @@ struct FuncRunnerExecutionTask {
     func: Func,
     args: serde_json::Value,
     before: Vec<BeforeFunction>,
+    memo_key: Option<FuncMemoKey>,
     parent_span: Span,
 }
 
@@ -1403,7 +1731,46 @@ impl FuncRunnerExecutionTask {
     }
 
     async fn try_run(self) -> FuncRunnerResult<()> {
+        // A failed lookup is treated as a miss: the cache is an optimization and should never be
+        // the reason a func fails to run.
+        let memoized_result = match self.memo_key {
+            Some(memo_key) => Self::memoized_result(&self.ctx, memo_key)
+                .await
+                .unwrap_or_else(|err| {
+                warn!(si.error.message = ?err, %memo_key, "failed to read memoized func result");
+                None
+            }),
+            None => None,
+        };
+
+        if let Some((unprocessed_value, value)) = memoized_result {
+            let mut next_state_inner = Arc::unwrap_or_clone(self.func_run.clone());
+            next_state_inner.set_memo_status(Some(FuncMemoStatus::Hit));
+            next_state_inner.set_state_to_post_processing();
+            let next_state = Arc::new(next_state_inner);
+            self.ctx
+                .layer_db()
+                .func_run()
+                .write(
+                    next_state.clone(),
+                    None,
+                    self.ctx.events_tenancy(),
+                    self.ctx.events_actor(),
+                )
+                .await?;
+            let _ = self.result_tx.send(Ok(FuncRunValue::new(
+                next_state.id(),
+                unprocessed_value,
+                value,
+            )));
+
+            return Ok(());
+        }
+
         let mut running_state_func_run_inner = Arc::unwrap_or_clone(self.func_run.clone());
+        if self.memo_key.is_some() {
+            running_state_func_run_inner.set_memo_status(Some(FuncMemoStatus::Miss));
+        }
         running_state_func_run_inner.set_state_to_running();
         let running_state_func_run = Arc::new(running_state_func_run_inner);
         self.ctx
@@ -1473,6 +1840,21 @@ impl FuncRunnerExecutionTask {
             FuncBackendKind::Map => FuncBackendMap::create_and_execute(&self.args).await,
             FuncBackendKind::Object => FuncBackendObject::create_and_execute(&self.args).await,
             FuncBackendKind::String => FuncBackendString::create_and_execute(&self.args).await,
+            FuncBackendKind::Base64Encode => {
+                FuncBackendBase64Encode::create_and_execute(&self.args).await
+            }
+            FuncBackendKind::JoinArray => {
+                FuncBackendJoinArray::create_and_execute(&self.args).await
+            }
+            FuncBackendKind::JsonPointer => {
+                FuncBackendJsonPointer::create_and_execute(&self.args).await
+            }
+            FuncBackendKind::MapFromList => {
+                FuncBackendMapFromList::create_and_execute(&self.args).await
+            }
+            FuncBackendKind::StringTemplate => {
+                FuncBackendStringTemplate::create_and_execute(&self.args).await
+            }
             FuncBackendKind::Unset => Ok((None, None)),
             FuncBackendKind::Validation => {
                 FuncBackendValidation::create_and_execute(
@@ -1521,6 +1903,20 @@ impl FuncRunnerExecutionTask {
                     value = None;
                 }
 
+                if let Some(memo_key) = self.memo_key {
+                    if let Err(err) = Self::memoize_result(
+                        &self.ctx,
+                        memo_key,
+                        self.func_run.id(),
+                        unprocessed_value.as_ref(),
+                        value.as_ref(),
+                    )
+                    .await
+                    {
+                        warn!(si.error.message = ?err, %memo_key, "failed to memoize func result");
+                    }
+                }
+
                 let mut next_state_inner = Arc::unwrap_or_clone(running_state_func_run.clone());
                 next_state_inner.set_state_to_post_processing();
                 let next_state = Arc::new(next_state_inner);
@@ -1546,7 +1942,11 @@ impl FuncRunnerExecutionTask {
                 backend,
             }) => {
                 let mut next_state_inner = Arc::unwrap_or_clone(running_state_func_run.clone());
-                next_state_inner.set_state_to_failure();
+                if kind == FunctionResultFailureError::KIND_CANCELLED {
+                    next_state_inner.set_state_to_cancelled();
+                } else {
+                    next_state_inner.set_state_to_failure();
+                }
                 let next_state = Arc::new(next_state_inner);
                 self.ctx
                     .layer_db()
@@ -1585,6 +1985,103 @@ impl FuncRunnerExecutionTask {
 
         Ok(())
     }
+
+    /// Looks up a memoized result for the given key, returning the unprocessed value and value.
+    ///
+    /// Returns `None` on a cache miss, including when a memo exists but one of the values it
+    /// points to can no longer be found in the CAS.
+    async fn memoized_result(
+        ctx: &DalContext,
+        memo_key: FuncMemoKey,
+    ) -> FuncRunnerResult<Option<(Option<serde_json::Value>, Option<serde_json::Value>)>> {
+        let memo = match ctx.layer_db().func_memo().read(&memo_key).await? {
+            Some(memo) => memo,
+            None => return Ok(None),
+        };
+
+        let unprocessed_value = match memo.unprocessed_value_cas_address() {
+            Some(address) => match Self::read_cas_value(ctx, address).await? {
+                Some(value) => Some(value),
+                None => return Ok(None),
+            },
+            None => None,
+        };
+        let value = match memo.value_cas_address() {
+            Some(address) => match Self::read_cas_value(ctx, address).await? {
+                Some(value) => Some(value),
+                None => return Ok(None),
+            },
+            None => None,
+        };
+
+        Ok(Some((unprocessed_value, value)))
+    }
+
+    /// Stores the result of a successful run in the memoization cache.
+    async fn memoize_result(
+        ctx: &DalContext,
+        memo_key: FuncMemoKey,
+        func_run_id: FuncRunId,
+        unprocessed_value: Option<&serde_json::Value>,
+        value: Option<&serde_json::Value>,
+    ) -> FuncRunnerResult<()> {
+        let unprocessed_value_cas_address = match unprocessed_value {
+            Some(unprocessed_value) => Some(Self::write_cas_value(ctx, unprocessed_value).await?),
+            None => None,
+        };
+        let value_cas_address = match value {
+            Some(value) => Some(Self::write_cas_value(ctx, value).await?),
+            None => None,
+        };
+
+        ctx.layer_db()
+            .func_memo()
+            .write(
+                Arc::new(FuncMemo::new(
+                    memo_key,
+                    func_run_id,
+                    unprocessed_value_cas_address,
+                    value_cas_address,
+                )),
+                None,
+                ctx.events_tenancy(),
+                ctx.events_actor(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    pub(crate) async fn read_cas_value(
+        ctx: &DalContext,
+        address: ContentHash,
+    ) -> FuncRunnerResult<Option<serde_json::Value>> {
+        Ok(ctx
+            .layer_db()
+            .cas()
+            .try_read_as::<CasValue>(&address)
+            .await?
+            .map(Into::into))
+    }
+
+    async fn write_cas_value(
+        ctx: &DalContext,
+        value: &serde_json::Value,
+    ) -> FuncRunnerResult<ContentHash> {
+        let cas_value: CasValue = value.to_owned().into();
+        let (address, _) = ctx
+            .layer_db()
+            .cas()
+            .write(
+                Arc::new(cas_value.into()),
+                None,
+                ctx.events_tenancy(),
+                ctx.events_actor(),
+            )
+            .await?;
+
+        Ok(address)
+    }
 }
 
 #[derive(Clone, Deserialize, Serialize, Debug, PartialEq, Eq)]
@@ -0,0 +1,199 @@
+//! This module contains [`FuncTestCases`](FuncTestCase), which are stored alongside a [`Func`]
+//! and run via the [`FuncAuthoringClient`](crate::func::authoring::FuncAuthoringClient).
+//!
+//! A test case describes the input a [`Func`] is given and what is expected of its output. Test
+//! cases travel with the [`Func`] when it is exported in a module, so that authors of shared
+//! assets can catch regressions before their modules are installed elsewhere.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use si_events::FuncRunId;
+use si_pkg::{FuncTestCaseExpectationSpec, FuncTestCaseInputSpec, FuncTestCaseSpec};
+
+use crate::Func;
+
+/// A test case for a [`Func`], identified by its name.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct FuncTestCase {
+    pub name: String,
+    pub input: FuncTestCaseInput,
+    pub expectation: FuncTestCaseExpectation,
+}
+
+/// What a [`Func`] is given when running a [`FuncTestCase`].
+#[remain::sorted]
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "kind", content = "value", rename_all = "camelCase")]
+pub enum FuncTestCaseInput {
+    /// Arguments passed to the [`Func`] as they are.
+    Args(Value),
+    /// A fixture shaped like the properties of a component (i.e. with `si`, `domain`, `resource`
+    /// and friends at the top level), from which the arguments are derived for the kind of the
+    /// [`Func`].
+    Component(Value),
+}
+
+/// What is expected of the output of a [`Func`] when running a [`FuncTestCase`].
+#[remain::sorted]
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "kind", content = "value", rename_all = "camelCase")]
+pub enum FuncTestCaseExpectation {
+    /// Every field of the given value must be present in the output, with the same value. Arrays
+    /// must have the same length and match element by element.
+    Contains(Value),
+    /// The output must be exactly the given value.
+    Equals(Value),
+    /// The [`Func`] must run successfully, no matter what it returns.
+    Succeeds,
+}
+
+impl FuncTestCaseExpectation {
+    /// Checks the output of a [`Func`] against the expectation, returning a message describing
+    /// the mismatch if there is one.
+    pub fn check(&self, output: &Value) -> Option<String> {
+        match self {
+            Self::Contains(expected) => contains(expected, output, "")
+                .map(|path| format!("output does not contain the expected value at \"{path}\"")),
+            Self::Equals(expected) => {
+                (expected != output).then(|| format!("expected output {expected}, got {output}"))
+            }
+            Self::Succeeds => None,
+        }
+    }
+}
+
+impl From<FuncTestCaseSpec> for FuncTestCase {
+    fn from(value: FuncTestCaseSpec) -> Self {
+        Self {
+            name: value.name,
+            input: match value.input {
+                FuncTestCaseInputSpec::Args(args) => FuncTestCaseInput::Args(args),
+                FuncTestCaseInputSpec::Component(fixture) => FuncTestCaseInput::Component(fixture),
+            },
+            expectation: match value.expectation {
+                FuncTestCaseExpectationSpec::Contains(expected) => {
+                    FuncTestCaseExpectation::Contains(expected)
+                }
+                FuncTestCaseExpectationSpec::Equals(expected) => {
+                    FuncTestCaseExpectation::Equals(expected)
+                }
+                FuncTestCaseExpectationSpec::Succeeds => FuncTestCaseExpectation::Succeeds,
+            },
+        }
+    }
+}
+
+impl From<FuncTestCase> for FuncTestCaseSpec {
+    fn from(value: FuncTestCase) -> Self {
+        Self {
+            name: value.name,
+            input: match value.input {
+                FuncTestCaseInput::Args(args) => FuncTestCaseInputSpec::Args(args),
+                FuncTestCaseInput::Component(fixture) => FuncTestCaseInputSpec::Component(fixture),
+            },
+            expectation: match value.expectation {
+                FuncTestCaseExpectation::Contains(expected) => {
+                    FuncTestCaseExpectationSpec::Contains(expected)
+                }
+                FuncTestCaseExpectation::Equals(expected) => {
+                    FuncTestCaseExpectationSpec::Equals(expected)
+                }
+                FuncTestCaseExpectation::Succeeds => FuncTestCaseExpectationSpec::Succeeds,
+            },
+            unique_id: None,
+            deleted: false,
+        }
+    }
+}
+
+/// The result of running a [`FuncTestCase`].
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct FuncTestCaseResult {
+    pub name: String,
+    /// The func run for the test case, which can be found in the func run history.
+    pub func_run_id: Option<FuncRunId>,
+    pub passed: bool,
+    pub output: Option<Value>,
+    pub message: Option<String>,
+}
+
+impl Func {
+    /// Finds the [`FuncTestCase`] with the given name.
+    pub fn test_case(&self, name: &str) -> Option<&FuncTestCase> {
+        self.test_cases
+            .iter()
+            .find(|test_case| test_case.name == name)
+    }
+}
+
+/// Returns the JSON pointer of the first place where `expected` is not contained in `actual`.
+fn contains(expected: &Value, actual: &Value, path: &str) -> Option<String> {
+    match (expected, actual) {
+        (Value::Object(expected), Value::Object(actual)) => {
+            expected.iter().find_map(|(key, expected)| {
+                let path = format!("{path}/{}", key.replace('~', "~0").replace('/', "~1"));
+                match actual.get(key) {
+                    Some(actual) => contains(expected, actual, &path),
+                    None => Some(path),
+                }
+            })
+        }
+        (Value::Array(expected), Value::Array(actual)) if expected.len() == actual.len() => {
+            expected
+                .iter()
+                .zip(actual)
+                .enumerate()
+                .find_map(|(index, (expected, actual))| {
+                    contains(expected, actual, &format!("{path}/{index}"))
+                })
+        }
+        (expected, actual) if expected == actual => None,
+        _ => Some(path.to_owned()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn contains_ignores_extra_fields() {
+        let expectation = FuncTestCaseExpectation::Contains(json!({ "result": "success" }));
+        assert_eq!(
+            None,
+            expectation.check(&json!({ "result": "success", "message": "ok" }))
+        );
+    }
+
+    #[test]
+    fn contains_reports_the_mismatched_path() {
+        let expectation = FuncTestCaseExpectation::Contains(json!({
+            "tags": [{ "key": "Name" }],
+            "region": "us-east-1",
+        }));
+        assert_eq!(
+            Some("output does not contain the expected value at \"/tags/0/key\"".to_owned()),
+            expectation.check(&json!({
+                "tags": [{ "key": "Owner" }],
+                "region": "us-east-1",
+            }))
+        );
+        assert_eq!(
+            Some("output does not contain the expected value at \"/region\"".to_owned()),
+            expectation.check(&json!({ "tags": [{ "key": "Name" }] }))
+        );
+    }
+
+    #[test]
+    fn equals_is_exact() {
+        let expectation = FuncTestCaseExpectation::Equals(json!({ "result": "success" }));
+        assert!(expectation
+            .check(&json!({ "result": "success", "message": "ok" }))
+            .is_some());
+        assert!(expectation.check(&json!({ "result": "success" })).is_none());
+    }
+}
@@ -11,6 +11,7 @@ use si_layer_cache::LayerDbError;
 use thiserror::Error;
 use tokio::task::JoinError;
 
+use crate::component::drift::ResourceDriftError;
 use crate::diagram::DiagramError;
 use crate::prop::PropError;
 use crate::validation::ValidationError;
@@ -68,6 +69,8 @@ pub enum JobConsumerError {
     Prop(#[from] PropError),
     // #[error(transparent)]
     // PropertyEditorValuesSummary(#[from] PropertyEditorValuesSummaryError),
+    #[error("resource drift error: {0}")]
+    ResourceDrift(#[from] ResourceDriftError),
     #[error("execution of job {0} failed after {1} retry attempts")]
     RetriesFailed(String, u32),
     #[error(transparent)]
@@ -80,6 +83,8 @@ pub enum JobConsumerError {
     Transactions(#[from] TransactionsError),
     #[error(transparent)]
     UlidDecode(#[from] ulid::DecodeError),
+    #[error("unknown job kind {0}")]
+    UnknownJobKind(String),
     #[error("validation error: {0}")]
     Validation(#[from] ValidationError),
     #[error(transparent)]
@@ -107,6 +112,7 @@ pub struct JobInfo {
     pub blocking: bool,
 }
 
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum RetryBackoff {
     Exponential,
     None,
@@ -114,6 +120,7 @@ pub enum RetryBackoff {
 
 /// Jobs that return a state of `JobCompletionState::Retry` will be retried
 /// with the requested backoff and limit
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum JobCompletionState {
     Retry { limit: u32, backoff: RetryBackoff },
     Done,
@@ -139,11 +146,7 @@ pub trait JobConsumer: std::fmt::Debug + Sync + JobConsumerMetadata {
     async fn run_job(&self, ctx_builder: DalContextBuilder) -> JobConsumerResult<()> {
         let mut retries = 0;
         loop {
-            let mut ctx = ctx_builder
-                .build(self.access_builder().build(self.visibility()))
-                .await?;
-
-            match self.run(&mut ctx).await? {
+            match self.run_once(ctx_builder.clone()).await? {
                 JobCompletionState::Retry { limit, backoff } => {
                     if retries >= limit {
                         return Err(JobConsumerError::RetriesFailed(self.type_name(), retries));
@@ -163,6 +166,23 @@ pub trait JobConsumer: std::fmt::Debug + Sync + JobConsumerMetadata {
 
         Ok(())
     }
+
+    /// Sets up the data necessary to run the job and runs it a single time, leaving any retries
+    /// it asks for to the caller. This lets a job queue persist retries (e.g. by redelivering the
+    /// job later) rather than holding them in memory as [`run_job`](Self::run_job) does.
+    async fn run_once(
+        &self,
+        ctx_builder: DalContextBuilder,
+    ) -> JobConsumerResult<JobCompletionState> {
+        let mut ctx = ctx_builder
+            .build(self.access_builder().build(self.visibility()))
+            .await?;
+        // Jobs carry the actor who enqueued them, but their commits follow on from that actor's
+        // own, which are audited already
+        ctx.set_no_graph_update_audit();
+
+        self.run(&mut ctx).await
+    }
 }
 
 fn calculate_exponential_sleep_ms(retry_no: u32, base: u32) -> Duration {
@@ -13,6 +13,7 @@ use crate::{
         Action, ActionError, ActionId, ActionState,
     },
     change_status::ChangeStatus,
+    component::drift::ResourceDrift,
     diagram::SummaryDiagramComponent,
     job::{
         consumer::{
@@ -253,6 +254,18 @@ async fn process_and_record_execution(
             component.set_resource(&ctx, resource.into()).await?;
         }
 
+        // A refresh tells us what the resource actually looks like, so compare it to the model.
+        // The event is sent even without drift so that clients can clear drift they were showing.
+        if prototype.kind == ActionKind::Refresh && resource.status == ResourceStatus::Ok {
+            if let Some(payload) = &resource.payload {
+                let drift = ResourceDrift::detect(&ctx, component_id, payload).await?;
+                WsEvent::resource_drift_detected(&ctx, &drift)
+                    .await?
+                    .publish_on_commit(&ctx)
+                    .await?;
+            }
+        }
+
         if resource.status == ResourceStatus::Ok {
             // Remove `ActionId` from graph as the execution succeeded
             Action::remove_by_id(&ctx, action_id).await?;
@@ -315,9 +328,17 @@ async fn process_and_record_execution(
     skip_all,
     level = "info",
     fields(si.action.id = ?action_id))]
-async fn process_failed_action(ctx: &DalContext, action_id: ActionId) -> JobConsumerResult<()> {
+async fn process_failed_action(ctx: &mut DalContext, action_id: ActionId) -> JobConsumerResult<()> {
     info!(%action_id, "processing action failed");
 
+    // A user may have cancelled the action while it was running, in which case it has already
+    // been moved into its final state
+    ctx.update_snapshot_to_visibility().await?;
+    if Action::get_by_id(ctx, action_id).await?.state() == ActionState::Cancelled {
+        info!(%action_id, "action was cancelled");
+        return Ok(());
+    }
+
     Action::set_state(ctx, action_id, ActionState::Failed).await?;
 
     ctx.layer_db()
@@ -8,7 +8,7 @@ use crate::{
 };
 
 mod nats_processor;
-pub use nats_processor::NatsProcessor;
+pub use nats_processor::{pinga_jobs_stream, NatsProcessor, NATS_HEADER_REPLY_INBOX};
 
 #[remain::sorted]
 #[derive(Error, Debug)]
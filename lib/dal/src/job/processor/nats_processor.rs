@@ -1,9 +1,15 @@
+use std::sync::Arc;
+
 use async_trait::async_trait;
 use futures::StreamExt;
-use si_data_nats::{NatsClient, Subject};
+use si_data_nats::{
+    async_nats::jetstream::{context::CreateStreamError, stream},
+    jetstream::{context::Publish, Context},
+    HeaderMap, NatsClient, Subject,
+};
 use telemetry::prelude::*;
 use telemetry_nats::propagation;
-use tokio::task::JoinSet;
+use tokio::{sync::OnceCell, task::JoinSet};
 
 use crate::job::{
     consumer::JobInfo,
@@ -14,11 +20,51 @@ use crate::job::{
 use super::{JobQueueProcessor, JobQueueProcessorError, JobQueueProcessorResult};
 
 const NATS_JOB_QUEUE: &str = "pinga-jobs";
+const NATS_JOBS_STREAM_NAME: &str = "PINGA_JOBS";
+
+/// The header holding the subject which the result of a blocking job is published to.
+///
+/// The reply subject of the message itself can't be used, as JetStream uses it to acknowledge the
+/// publish.
+pub const NATS_HEADER_REPLY_INBOX: &str = "X-Reply-Inbox";
+
+/// Returns the JetStream work queue stream which jobs are published to, creating it if it doesn't
+/// yet exist.
+///
+/// A job stays in the stream until a pinga instance acknowledges it, so jobs published while no
+/// pinga is running are processed once one starts.
+pub async fn pinga_jobs_stream(
+    context: &Context,
+    prefix: Option<&str>,
+) -> Result<stream::Stream, CreateStreamError> {
+    let (name, subject) = match prefix {
+        Some(prefix) => (
+            format!("{prefix}_{NATS_JOBS_STREAM_NAME}"),
+            format!("{prefix}.{NATS_JOB_QUEUE}"),
+        ),
+        None => (NATS_JOBS_STREAM_NAME.to_owned(), NATS_JOB_QUEUE.to_owned()),
+    };
+
+    context
+        .get_or_create_stream(stream::Config {
+            name,
+            description: Some("Pinga jobs work queue".to_owned()),
+            subjects: vec![subject],
+            retention: stream::RetentionPolicy::WorkQueue,
+            // Refuse new jobs rather than silently dropping queued ones if the stream is ever full
+            discard: stream::DiscardPolicy::New,
+            ..Default::default()
+        })
+        .await
+}
 
 #[derive(Clone, Debug)]
 pub struct NatsProcessor {
     client: NatsClient,
+    context: Context,
     pinga_subject: Subject,
+    /// Initialized once the jobs stream is known to exist.
+    jobs_stream: Arc<OnceCell<()>>,
 }
 
 impl NatsProcessor {
@@ -28,13 +74,51 @@ impl NatsProcessor {
         } else {
             NATS_JOB_QUEUE.into()
         };
+        let context = si_data_nats::jetstream::new(client.clone());
 
         Self {
             client,
+            context,
             pinga_subject,
+            jobs_stream: Arc::new(OnceCell::new()),
         }
     }
 
+    /// Publishes a job to the jobs stream and waits for the stream to persist it.
+    ///
+    /// The job's id is used as the message id, so the stream drops the job if it is published
+    /// more than once within the stream's duplicate window (e.g. when a publish is retried).
+    async fn publish_job(
+        &self,
+        job_info: &JobInfo,
+        headers: HeaderMap,
+    ) -> JobQueueProcessorResult<()> {
+        self.jobs_stream
+            .get_or_try_init(|| async {
+                pinga_jobs_stream(&self.context, self.client.metadata().subject_prefix())
+                    .await
+                    .map(|_| ())
+            })
+            .await
+            .map_err(|err| JobQueueProcessorError::Transport(Box::new(err)))?;
+
+        let ack = self
+            .context
+            .send_publish(
+                self.pinga_subject.clone(),
+                Publish::build()
+                    .payload(serde_json::to_vec(job_info)?.into())
+                    .headers(headers)
+                    .message_id(&job_info.id),
+            )
+            .await
+            .map_err(|err| JobQueueProcessorError::Transport(Box::new(err)))?;
+        ack.await
+            .map_err(|err| JobQueueProcessorError::Transport(Box::new(err)))?;
+
+        Ok(())
+    }
+
     #[instrument(
         name = "nats_processor.push_all_jobs",
         level = "debug",
@@ -47,17 +131,9 @@ impl NatsProcessor {
         while let Some(element) = queue.fetch_job().await {
             let job_info = JobInfo::new(element)?;
 
-            if let Err(err) = self
-                .client
-                .publish_with_headers(
-                    self.pinga_subject.clone(),
-                    headers.clone(),
-                    serde_json::to_vec(&job_info)?.into(),
-                )
-                .await
-            {
+            if let Err(err) = self.publish_job(&job_info, headers.clone()).await {
                 error!("Nats job push failed, some jobs will be dropped");
-                return Err(JobQueueProcessorError::Transport(Box::new(err)));
+                return Err(err);
             }
         }
         Ok(())
@@ -78,17 +154,15 @@ impl JobQueueProcessor for NatsProcessor {
             .subscribe(job_reply_inbox.clone())
             .await
             .map_err(|e| BlockingJobError::Nats(e.to_string()))?;
-        self.client
-            .publish_with_reply_and_headers(
-                self.pinga_subject.clone(),
-                job_reply_inbox,
-                propagation::empty_injected_headers(),
-                serde_json::to_vec(&job_info)
-                    .map_err(|e| BlockingJobError::Serde(e.to_string()))?
-                    .into(),
-            )
+
+        let mut headers = propagation::empty_injected_headers();
+        headers.insert(NATS_HEADER_REPLY_INBOX, job_reply_inbox.as_str());
+        self.publish_job(&job_info, headers)
             .await
-            .map_err(|e| BlockingJobError::Nats(e.to_string()))?;
+            .map_err(|e| match e {
+                JobQueueProcessorError::Serde(e) => BlockingJobError::Serde(e.to_string()),
+                e => BlockingJobError::Nats(e.to_string()),
+            })?;
 
         match reply_subscriber.next().await {
             Some(message) => serde_json::from_slice::<BlockingJobResult>(message.payload())
@@ -109,7 +183,7 @@ impl JobQueueProcessor for NatsProcessor {
 
         // Fan out, dispatching all queued jobs to pinga over nats.
         for job in jobs {
-            let job_processor = Self::new(self.client.clone());
+            let job_processor = self.clone();
             let parent_span = span.clone();
 
             dispatched_jobs.spawn(async move {
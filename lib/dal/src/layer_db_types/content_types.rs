@@ -2,10 +2,12 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use si_events::ulid::Ulid;
 use si_events::{CasValue, ContentHash};
+use std::collections::BTreeMap;
 use strum::EnumDiscriminants;
 use thiserror::Error;
 
 use crate::action::prototype::ActionKind;
+use crate::func::test_case::FuncTestCase;
 use crate::validation::ValidationStatus;
 use crate::{
     action::ActionCompletionStatus, func::argument::FuncArgumentKind, prop::WidgetOptions,
@@ -211,6 +213,23 @@ pub struct AttributePrototypeContentV1 {
 #[derive(Debug, Clone, EnumDiscriminants, Serialize, Deserialize, PartialEq)]
 pub enum ComponentContent {
     V1(ComponentContentV1),
+    V2(ComponentContentV2),
+}
+
+impl ComponentContent {
+    pub fn extract(self) -> ComponentContentV2 {
+        match self {
+            ComponentContent::V1(v1) => ComponentContentV2 {
+                timestamp: v1.timestamp,
+                x: v1.x,
+                y: v1.y,
+                width: v1.width,
+                height: v1.height,
+                labels: BTreeMap::new(),
+            },
+            ComponentContent::V2(v2) => v2,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
@@ -222,10 +241,25 @@ pub struct ComponentContentV1 {
     pub height: Option<String>,
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct ComponentContentV2 {
+    pub timestamp: Timestamp,
+    pub x: String,
+    pub y: String,
+    pub width: Option<String>,
+    pub height: Option<String>,
+    /// User-defined key/value labels used to select components (see
+    /// [`ComponentQuery`](crate::component::query::ComponentQuery)).
+    pub labels: BTreeMap<String, String>,
+}
+
 #[derive(Debug, Clone, EnumDiscriminants, Serialize, Deserialize, PartialEq)]
 pub enum FuncContent {
     V1(FuncContentV1),
     V2(FuncContentV2),
+    V3(FuncContentV3),
+    V4(FuncContentV4),
+    V5(FuncContentV5),
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
@@ -260,15 +294,81 @@ pub struct FuncContentV2 {
     pub is_locked: bool,
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct FuncContentV3 {
+    pub timestamp: Timestamp,
+    pub display_name: Option<String>,
+    pub description: Option<String>,
+    pub link: Option<String>,
+    pub hidden: bool,
+    pub builtin: bool,
+    pub backend_response_type: FuncBackendResponseType,
+    pub backend_kind: FuncBackendKind,
+    pub handler: Option<String>,
+    pub code_base64: Option<String>,
+    /// A hash of the code above
+    pub code_blake3: ContentHash,
+    pub is_locked: bool,
+    /// Test cases stored alongside the func (see [`FuncTestCase`]).
+    pub test_cases: Vec<FuncTestCase>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct FuncContentV4 {
+    pub timestamp: Timestamp,
+    pub display_name: Option<String>,
+    pub description: Option<String>,
+    pub link: Option<String>,
+    pub hidden: bool,
+    pub builtin: bool,
+    pub backend_response_type: FuncBackendResponseType,
+    pub backend_kind: FuncBackendKind,
+    pub handler: Option<String>,
+    pub code_base64: Option<String>,
+    /// A hash of the code above
+    pub code_blake3: ContentHash,
+    pub is_locked: bool,
+    /// Test cases stored alongside the func (see [`FuncTestCase`]).
+    pub test_cases: Vec<FuncTestCase>,
+    /// How long, in seconds, the func may run for before it is killed. When unset, the default
+    /// for the func's kind applies.
+    pub timeout_secs: Option<u64>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct FuncContentV5 {
+    pub timestamp: Timestamp,
+    pub display_name: Option<String>,
+    pub description: Option<String>,
+    pub link: Option<String>,
+    pub hidden: bool,
+    pub builtin: bool,
+    pub backend_response_type: FuncBackendResponseType,
+    pub backend_kind: FuncBackendKind,
+    pub handler: Option<String>,
+    pub code_base64: Option<String>,
+    /// A hash of the code above
+    pub code_blake3: ContentHash,
+    pub is_locked: bool,
+    /// Test cases stored alongside the func (see [`FuncTestCase`]).
+    pub test_cases: Vec<FuncTestCase>,
+    /// How long, in seconds, the func may run for before it is killed. When unset, the default
+    /// for the func's kind applies.
+    pub timeout_secs: Option<u64>,
+    /// Whether the func is pure: given the same code and arguments, it always produces the same
+    /// result. Only pure attribute funcs have their results memoized.
+    pub is_pure: bool,
+}
+
 #[derive(Debug, Clone, EnumDiscriminants, Serialize, Deserialize, PartialEq)]
 pub enum FuncArgumentContent {
     V1(FuncArgumentContentV1),
 }
 
 impl FuncContent {
-    pub fn extract(self) -> FuncContentV2 {
+    pub fn extract(self) -> FuncContentV5 {
         match self {
-            FuncContent::V1(v1) => FuncContentV2 {
+            FuncContent::V1(v1) => FuncContentV5 {
                 timestamp: v1.timestamp,
                 hidden: v1.hidden,
                 display_name: v1.display_name,
@@ -281,8 +381,62 @@ impl FuncContent {
                 handler: v1.handler,
                 code_base64: v1.code_base64,
                 code_blake3: v1.code_blake3,
+                test_cases: Vec::new(),
+                timeout_secs: None,
+                is_pure: false,
+            },
+            FuncContent::V2(v2) => FuncContentV5 {
+                timestamp: v2.timestamp,
+                hidden: v2.hidden,
+                display_name: v2.display_name,
+                link: v2.link,
+                description: v2.description,
+                is_locked: v2.is_locked,
+                builtin: v2.builtin,
+                backend_response_type: v2.backend_response_type,
+                backend_kind: v2.backend_kind,
+                handler: v2.handler,
+                code_base64: v2.code_base64,
+                code_blake3: v2.code_blake3,
+                test_cases: Vec::new(),
+                timeout_secs: None,
+                is_pure: false,
+            },
+            FuncContent::V3(v3) => FuncContentV5 {
+                timestamp: v3.timestamp,
+                hidden: v3.hidden,
+                display_name: v3.display_name,
+                link: v3.link,
+                description: v3.description,
+                is_locked: v3.is_locked,
+                builtin: v3.builtin,
+                backend_response_type: v3.backend_response_type,
+                backend_kind: v3.backend_kind,
+                handler: v3.handler,
+                code_base64: v3.code_base64,
+                code_blake3: v3.code_blake3,
+                test_cases: v3.test_cases,
+                timeout_secs: None,
+                is_pure: false,
+            },
+            FuncContent::V4(v4) => FuncContentV5 {
+                timestamp: v4.timestamp,
+                hidden: v4.hidden,
+                display_name: v4.display_name,
+                link: v4.link,
+                description: v4.description,
+                is_locked: v4.is_locked,
+                builtin: v4.builtin,
+                backend_response_type: v4.backend_response_type,
+                backend_kind: v4.backend_kind,
+                handler: v4.handler,
+                code_base64: v4.code_base64,
+                code_blake3: v4.code_blake3,
+                test_cases: v4.test_cases,
+                timeout_secs: v4.timeout_secs,
+                is_pure: false,
             },
-            FuncContent::V2(v2) => v2,
+            FuncContent::V5(v5) => v5,
         }
     }
 }
@@ -22,6 +22,7 @@ use tokio::time::Instant;
 pub mod action;
 pub mod actor_view;
 pub mod attribute;
+pub mod audit_log;
 pub mod authentication_prototype;
 pub mod builtins;
 pub mod change_set;
@@ -62,6 +63,7 @@ pub mod timestamp;
 pub mod user;
 pub mod validation;
 pub mod visibility;
+pub mod webhook;
 pub mod workspace;
 pub mod workspace_snapshot;
 pub mod ws_event;
@@ -72,6 +74,9 @@ pub use attribute::{
     prototype::{AttributePrototype, AttributePrototypeId},
     value::{AttributeValue, AttributeValueId},
 };
+pub use audit_log::{
+    AuditLogEntry, AuditLogError, AuditLogFilter, AuditLogPk, AuditLogResult, AuditOperation,
+};
 pub use builtins::{BuiltinsError, BuiltinsResult};
 pub use change_set::status::ChangeSetStatus;
 pub use change_set::ChangeSetApplyError;
@@ -122,6 +127,10 @@ pub use tenancy::{Tenancy, TenancyError};
 pub use timestamp::{Timestamp, TimestampError};
 pub use user::{User, UserClaim, UserError, UserPk, UserResult};
 pub use visibility::Visibility;
+pub use webhook::{
+    WebhookDelivery, WebhookDeliveryPk, WebhookDeliveryStatus, WebhookError, WebhookResult,
+    WebhookSubscription, WebhookSubscriptionPk,
+};
 pub use workspace::{Workspace, WorkspaceError, WorkspacePk, WorkspaceResult};
 pub use workspace_snapshot::graph::{WorkspaceSnapshotGraph, WorkspaceSnapshotGraphV1};
 pub use workspace_snapshot::{
@@ -0,0 +1,21 @@
+CREATE TABLE workspace_refresh_schedules
+(
+    workspace_pk     ident primary key        NOT NULL REFERENCES workspaces (pk) DEFERRABLE,
+    created_at       timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    updated_at       timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    enabled          bool                     NOT NULL DEFAULT FALSE,
+    interval_seconds bigint                   NOT NULL,
+    last_enqueued_at timestamp with time zone
+);
+
+CREATE TABLE component_resource_drift
+(
+    workspace_pk ident                    NOT NULL REFERENCES workspaces (pk) DEFERRABLE,
+    component_id ident                    NOT NULL,
+    detected_at  timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    drifted      bool                     NOT NULL,
+    entries      jsonb                    NOT NULL DEFAULT '[]'::jsonb,
+    PRIMARY KEY (workspace_pk, component_id)
+);
+
+CREATE INDEX component_resource_drift_drifted_idx ON component_resource_drift (workspace_pk) WHERE drifted;
@@ -0,0 +1,9 @@
+CREATE TABLE workspace_snapshot_deltas
+(
+    workspace_snapshot_address text primary key         NOT NULL,
+    created_at                 timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    checkpoint_address         text                     NOT NULL,
+    rebase_batch_addresses     text[]                   NOT NULL
+);
+
+CREATE INDEX ON workspace_snapshot_deltas (checkpoint_address);
@@ -0,0 +1,33 @@
+CREATE TABLE webhook_subscriptions
+(
+    pk                      ident primary key        NOT NULL DEFAULT ident_create_v1(),
+    created_at              timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    updated_at              timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    workspace_pk            ident                    NOT NULL REFERENCES workspaces (pk),
+    url                     text                     NOT NULL,
+    event_kinds             text[]                   NOT NULL,
+    signing_secret_crypted  bytea                    NOT NULL,
+    signing_secret_nonce    bytea                    NOT NULL,
+    signing_secret_key_hash text                     NOT NULL
+);
+
+CREATE INDEX ON webhook_subscriptions (workspace_pk);
+
+CREATE TABLE webhook_deliveries
+(
+    pk                   ident primary key        NOT NULL DEFAULT ident_create_v1(),
+    created_at           timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    updated_at           timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    subscription_pk      ident                    NOT NULL REFERENCES webhook_subscriptions (pk) ON DELETE CASCADE,
+    workspace_pk         ident                    NOT NULL,
+    event_kind           text                     NOT NULL,
+    payload              jsonb                    NOT NULL,
+    status               text                     NOT NULL,
+    attempts             integer                  NOT NULL DEFAULT 0,
+    next_attempt_at      timestamp with time zone,
+    last_response_status integer,
+    last_error           text
+);
+
+CREATE INDEX ON webhook_deliveries (subscription_pk, created_at);
+CREATE INDEX ON webhook_deliveries (next_attempt_at) WHERE status = 'pending';
@@ -0,0 +1,35 @@
+CREATE TABLE audit_logs
+(
+    pk            ident primary key        NOT NULL DEFAULT ident_create_v1(),
+    created_at    timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    workspace_pk  ident                    NOT NULL,
+    change_set_id ident,
+    actor         jsonb                    NOT NULL,
+    operation     text                     NOT NULL,
+    entity_ids    text[]                   NOT NULL
+);
+
+CREATE INDEX ON audit_logs (workspace_pk, created_at DESC, pk DESC);
+CREATE INDEX ON audit_logs USING GIN (entity_ids);
+
+CREATE OR REPLACE FUNCTION audit_logs_append_only_v1()
+    RETURNS TRIGGER
+    LANGUAGE PLPGSQL
+AS
+$$
+BEGIN
+    RAISE EXCEPTION 'audit_logs is append-only; % is not allowed', TG_OP;
+END;
+$$;
+
+CREATE TRIGGER audit_logs_append_only
+    BEFORE UPDATE OR DELETE
+    ON audit_logs
+    FOR EACH ROW
+EXECUTE FUNCTION audit_logs_append_only_v1();
+
+CREATE TRIGGER audit_logs_no_truncate
+    BEFORE TRUNCATE
+    ON audit_logs
+    FOR EACH STATEMENT
+EXECUTE FUNCTION audit_logs_append_only_v1();
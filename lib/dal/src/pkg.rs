@@ -134,19 +134,24 @@ impl From<FuncBackendKind> for FuncSpecBackendKind {
     fn from(value: FuncBackendKind) -> Self {
         match value {
             FuncBackendKind::Array => Self::Array,
+            FuncBackendKind::Base64Encode => Self::Base64Encode,
             FuncBackendKind::Boolean => Self::Boolean,
             FuncBackendKind::Diff => Self::Diff,
             FuncBackendKind::Identity => Self::Identity,
             FuncBackendKind::Integer => Self::Integer,
+            FuncBackendKind::JoinArray => Self::JoinArray,
             FuncBackendKind::JsAction => Self::JsAction,
             FuncBackendKind::JsAttribute => Self::JsAttribute,
             FuncBackendKind::Json => Self::Json,
+            FuncBackendKind::JsonPointer => Self::JsonPointer,
             FuncBackendKind::JsReconciliation => Self::JsReconciliation,
             FuncBackendKind::JsSchemaVariantDefinition => Self::JsSchemaVariantDefinition,
             FuncBackendKind::JsValidation => Self::JsValidation,
             FuncBackendKind::Map => Self::Map,
+            FuncBackendKind::MapFromList => Self::MapFromList,
             FuncBackendKind::Object => Self::Object,
             FuncBackendKind::String => Self::String,
+            FuncBackendKind::StringTemplate => Self::StringTemplate,
             FuncBackendKind::Unset => Self::Unset,
             FuncBackendKind::Validation => Self::Validation,
             FuncBackendKind::JsAuthentication => Self::JsAuthentication,
@@ -158,19 +163,24 @@ impl From<FuncSpecBackendKind> for FuncBackendKind {
     fn from(value: FuncSpecBackendKind) -> Self {
         match value {
             FuncSpecBackendKind::Array => Self::Array,
+            FuncSpecBackendKind::Base64Encode => Self::Base64Encode,
             FuncSpecBackendKind::Boolean => Self::Boolean,
             FuncSpecBackendKind::Diff => Self::Diff,
             FuncSpecBackendKind::Identity => Self::Identity,
             FuncSpecBackendKind::Integer => Self::Integer,
+            FuncSpecBackendKind::JoinArray => Self::JoinArray,
             FuncSpecBackendKind::JsAction => Self::JsAction,
             FuncSpecBackendKind::JsAttribute => Self::JsAttribute,
             FuncSpecBackendKind::Json => Self::Json,
+            FuncSpecBackendKind::JsonPointer => Self::JsonPointer,
             FuncSpecBackendKind::JsReconciliation => Self::JsReconciliation,
             FuncSpecBackendKind::JsSchemaVariantDefinition => Self::JsSchemaVariantDefinition,
             FuncSpecBackendKind::JsValidation => Self::JsValidation,
             FuncSpecBackendKind::Map => Self::Map,
+            FuncSpecBackendKind::MapFromList => Self::MapFromList,
             FuncSpecBackendKind::Object => Self::Object,
             FuncSpecBackendKind::String => Self::String,
+            FuncSpecBackendKind::StringTemplate => Self::StringTemplate,
             FuncSpecBackendKind::Unset => Self::Unset,
             FuncSpecBackendKind::Validation => Self::Validation,
             FuncSpecBackendKind::JsAuthentication => Self::JsAuthentication,
@@ -5,11 +5,12 @@ use strum::IntoEnumIterator;
 
 use si_pkg::{
     ActionFuncSpec, AttrFuncInputSpec, AttrFuncInputSpecKind, AuthenticationFuncSpec,
-    ComponentSpec, EdgeSpec, FuncArgumentSpec, FuncSpec, FuncSpecData, LeafFunctionSpec,
-    MapKeyFuncSpec, PkgSpec, PropSpec, PropSpecBuilder, PropSpecKind, RootPropFuncSpec, SchemaSpec,
-    SchemaSpecData, SchemaVariantSpec, SchemaVariantSpecBuilder, SchemaVariantSpecComponentType,
-    SchemaVariantSpecData, SchemaVariantSpecPropRoot, SiPkg, SiPkgKind, SiPropFuncSpec,
-    SiPropFuncSpecKind, SocketSpec, SocketSpecData, SocketSpecKind, SpecError,
+    ComponentSpec, EdgeSpec, FuncArgumentSpec, FuncSpec, FuncSpecData, ImportFuncSpec,
+    LeafFunctionSpec, MapKeyFuncSpec, PkgSpec, PropSpec, PropSpecBuilder, PropSpecKind,
+    ReconciliationFuncSpec, RootPropFuncSpec, SchemaSpec, SchemaSpecData, SchemaVariantSpec,
+    SchemaVariantSpecBuilder, SchemaVariantSpecComponentType, SchemaVariantSpecData,
+    SchemaVariantSpecPropRoot, SiPkg, SiPkgKind, SiPropFuncSpec, SiPropFuncSpecKind, SocketSpec,
+    SocketSpecData, SocketSpecKind, SpecError,
 };
 use telemetry::prelude::*;
 
@@ -282,6 +283,20 @@ impl PkgExporter {
                 variant_spec_builder.auth_func(spec);
             });
 
+        self.export_import_funcs(ctx, variant.id())
+            .await?
+            .drain(..)
+            .for_each(|spec| {
+                variant_spec_builder.import_func(spec);
+            });
+
+        self.export_reconciliation_funcs(ctx, variant.id())
+            .await?
+            .drain(..)
+            .for_each(|spec| {
+                variant_spec_builder.reconciliation_func(spec);
+            });
+
         self.export_si_prop_funcs(ctx, variant.id())
             .await?
             .drain(..)
@@ -551,6 +566,54 @@ impl PkgExporter {
         Ok(specs)
     }
 
+    async fn export_import_funcs(
+        &self,
+        ctx: &DalContext,
+        schema_variant_id: SchemaVariantId,
+    ) -> PkgResult<Vec<ImportFuncSpec>> {
+        let mut specs = vec![];
+
+        if let Some(func_id) = SchemaVariant::find_import_func_id(ctx, schema_variant_id).await? {
+            let func_spec = self
+                .func_map
+                .get(&func_id)
+                .ok_or(PkgError::MissingExportedFunc(func_id))?;
+
+            specs.push(
+                ImportFuncSpec::builder()
+                    .func_unique_id(&func_spec.unique_id)
+                    .build()?,
+            );
+        }
+
+        Ok(specs)
+    }
+
+    async fn export_reconciliation_funcs(
+        &self,
+        ctx: &DalContext,
+        schema_variant_id: SchemaVariantId,
+    ) -> PkgResult<Vec<ReconciliationFuncSpec>> {
+        let mut specs = vec![];
+
+        if let Some(func_id) =
+            SchemaVariant::find_reconciliation_func_id(ctx, schema_variant_id).await?
+        {
+            let func_spec = self
+                .func_map
+                .get(&func_id)
+                .ok_or(PkgError::MissingExportedFunc(func_id))?;
+
+            specs.push(
+                ReconciliationFuncSpec::builder()
+                    .func_unique_id(&func_spec.unique_id)
+                    .build()?,
+            );
+        }
+
+        Ok(specs)
+    }
+
     async fn export_prop_tree(
         &self,
         ctx: &DalContext,
@@ -879,6 +942,10 @@ impl PkgExporter {
             );
         }
 
+        for test_case in &func.test_cases {
+            func_spec_builder.test_case(test_case.to_owned());
+        }
+
         let func_spec = func_spec_builder.build()?;
         // If we have data, or change set specific arguments, we're valid for this changeset
         let include_in_export = func_spec.data.is_some() || !args.is_empty();
@@ -1,10 +1,11 @@
 use chrono::NaiveDateTime;
 use si_events::ulid::Ulid;
 use si_pkg::{
-    SchemaVariantSpecPropRoot, SiPkg, SiPkgActionFunc, SiPkgAttrFuncInputView, SiPkgAuthFunc,
-    SiPkgComponent, SiPkgEdge, SiPkgError, SiPkgFunc, SiPkgFuncArgument, SiPkgFuncData, SiPkgKind,
-    SiPkgLeafFunction, SiPkgMetadata, SiPkgProp, SiPkgPropData, SiPkgSchema, SiPkgSchemaData,
-    SiPkgSchemaVariant, SiPkgSocket, SiPkgSocketData, SocketSpecKind,
+    FuncTestCaseSpec, SchemaVariantSpecPropRoot, SiPkg, SiPkgActionFunc, SiPkgAttrFuncInputView,
+    SiPkgAuthFunc, SiPkgComponent, SiPkgEdge, SiPkgError, SiPkgFunc, SiPkgFuncArgument,
+    SiPkgFuncData, SiPkgImportFunc, SiPkgKind, SiPkgLeafFunction, SiPkgMetadata, SiPkgProp,
+    SiPkgPropData, SiPkgReconciliationFunc, SiPkgSchema, SiPkgSchemaData, SiPkgSchemaVariant,
+    SiPkgSocket, SiPkgSocketData, SocketSpecKind,
 };
 use std::collections::HashSet;
 use std::fmt::Debug;
@@ -319,6 +320,21 @@ async fn create_func(
     )
     .await?;
 
+    let test_cases = func_spec.test_cases()?;
+    let func = if test_cases.is_empty() {
+        func
+    } else {
+        let mut specs = Vec::with_capacity(test_cases.len());
+        for test_case in test_cases {
+            specs.push(FuncTestCaseSpec::try_from(test_case)?);
+        }
+        func.modify(ctx, |func| {
+            func.test_cases = specs.into_iter().map(Into::into).collect();
+            Ok(())
+        })
+        .await?
+    };
+
     Ok(func)
 }
 
@@ -881,6 +897,56 @@ async fn import_auth_func(
     Ok(prototype)
 }
 
+async fn import_import_func(
+    ctx: &DalContext,
+    func_spec: &SiPkgImportFunc<'_>,
+    schema_variant_id: SchemaVariantId,
+    thing_map: &ThingMap,
+) -> PkgResult<()> {
+    if func_spec.deleted() {
+        return Ok(());
+    }
+
+    match thing_map.get(&func_spec.func_unique_id().to_owned()) {
+        Some(Thing::Func(func)) => {
+            SchemaVariant::set_import_func(ctx, schema_variant_id, func.id).await?;
+        }
+        _ => {
+            return Err(PkgError::MissingFuncUniqueId(
+                func_spec.func_unique_id().into(),
+                "error found while importing import func",
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+async fn import_reconciliation_func(
+    ctx: &DalContext,
+    func_spec: &SiPkgReconciliationFunc<'_>,
+    schema_variant_id: SchemaVariantId,
+    thing_map: &ThingMap,
+) -> PkgResult<()> {
+    if func_spec.deleted() {
+        return Ok(());
+    }
+
+    match thing_map.get(&func_spec.func_unique_id().to_owned()) {
+        Some(Thing::Func(func)) => {
+            SchemaVariant::set_reconciliation_func(ctx, schema_variant_id, func.id).await?;
+        }
+        _ => {
+            return Err(PkgError::MissingFuncUniqueId(
+                func_spec.func_unique_id().into(),
+                "error found while importing reconciliation func",
+            ));
+        }
+    }
+
+    Ok(())
+}
+
 #[derive(Default, Clone, Debug)]
 struct CreatePropsSideEffects {
     attr_funcs: Vec<AttrFuncInfo>,
@@ -1180,6 +1246,15 @@ pub(crate) async fn import_schema_variant(
         }
     }
 
+    for import_func in &variant_spec.import_funcs()? {
+        import_import_func(ctx, import_func, schema_variant.id(), thing_map).await?;
+    }
+
+    for reconciliation_func in &variant_spec.reconciliation_funcs()? {
+        import_reconciliation_func(ctx, reconciliation_func, schema_variant.id(), thing_map)
+            .await?;
+    }
+
     for leaf_func in variant_spec.leaf_functions()? {
         import_leaf_function(ctx, leaf_func, schema_variant.id(), thing_map).await?;
     }
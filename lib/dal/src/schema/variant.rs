@@ -1091,6 +1091,13 @@ impl SchemaVariant {
         discriminant: EdgeWeightKindDiscriminants::AuthenticationPrototype,
         result: SchemaVariantResult,
     );
+    implement_add_edge_to!(
+        source_id: SchemaVariantId,
+        destination_id: FuncId,
+        add_fn: add_edge_to_used_func,
+        discriminant: EdgeWeightKindDiscriminants::Use,
+        result: SchemaVariantResult,
+    );
     implement_add_edge_to!(
         source_id: SchemaVariantId,
         destination_id: InputSocketId,
@@ -1191,6 +1198,119 @@ impl SchemaVariant {
         Ok(())
     }
 
+    /// Sets the reconciliation [`Func`] for the [`SchemaVariant`], replacing the existing one (if
+    /// one exists). A [`SchemaVariant`] can have at most one reconciliation [`Func`].
+    pub async fn set_reconciliation_func(
+        ctx: &DalContext,
+        schema_variant_id: SchemaVariantId,
+        func_id: FuncId,
+    ) -> SchemaVariantResult<()> {
+        Self::remove_reconciliation_func(ctx, schema_variant_id).await?;
+        Self::add_edge_to_used_func(ctx, schema_variant_id, func_id, EdgeWeightKind::new_use())
+            .await?;
+        Ok(())
+    }
+
+    /// Removes the reconciliation [`Func`] from the [`SchemaVariant`], if one is set. The [`Func`]
+    /// itself is left untouched.
+    pub async fn remove_reconciliation_func(
+        ctx: &DalContext,
+        schema_variant_id: SchemaVariantId,
+    ) -> SchemaVariantResult<()> {
+        if let Some(func_id) = Self::find_reconciliation_func_id(ctx, schema_variant_id).await? {
+            ctx.workspace_snapshot()?
+                .remove_edge_for_ulids(
+                    ctx.vector_clock_id()?,
+                    schema_variant_id,
+                    func_id,
+                    EdgeWeightKindDiscriminants::Use,
+                )
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Finds the reconciliation [`Func`] for the [`SchemaVariant`], if one is set.
+    pub async fn find_reconciliation_func_id(
+        ctx: &DalContext,
+        schema_variant_id: SchemaVariantId,
+    ) -> SchemaVariantResult<Option<FuncId>> {
+        Self::find_used_func_id(ctx, schema_variant_id, FuncKind::Reconciliation).await
+    }
+
+    /// Sets the import [`Func`] for the [`SchemaVariant`], replacing the existing one (if one
+    /// exists). Import funcs are attribute funcs that take a resource identifier and return the
+    /// resource and domain for a component adopting it, so the [`Func`] is marked as
+    /// [`FuncKind::Import`] to tell it apart from the variant's other funcs.
+    pub async fn set_import_func(
+        ctx: &DalContext,
+        schema_variant_id: SchemaVariantId,
+        func_id: FuncId,
+    ) -> SchemaVariantResult<()> {
+        Self::remove_import_func(ctx, schema_variant_id).await?;
+        Func::set_kind(ctx, func_id, FuncKind::Import).await?;
+        Self::add_edge_to_used_func(ctx, schema_variant_id, func_id, EdgeWeightKind::new_use())
+            .await?;
+        Ok(())
+    }
+
+    /// Removes the import [`Func`] from the [`SchemaVariant`], if one is set. The [`Func`] itself
+    /// is left untouched.
+    pub async fn remove_import_func(
+        ctx: &DalContext,
+        schema_variant_id: SchemaVariantId,
+    ) -> SchemaVariantResult<()> {
+        if let Some(func_id) = Self::find_import_func_id(ctx, schema_variant_id).await? {
+            ctx.workspace_snapshot()?
+                .remove_edge_for_ulids(
+                    ctx.vector_clock_id()?,
+                    schema_variant_id,
+                    func_id,
+                    EdgeWeightKindDiscriminants::Use,
+                )
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Finds the import [`Func`] for the [`SchemaVariant`], if one is set.
+    pub async fn find_import_func_id(
+        ctx: &DalContext,
+        schema_variant_id: SchemaVariantId,
+    ) -> SchemaVariantResult<Option<FuncId>> {
+        Self::find_used_func_id(ctx, schema_variant_id, FuncKind::Import).await
+    }
+
+    /// Finds the [`Func`] of the given kind that the [`SchemaVariant`] uses directly (i.e. not
+    /// through a prototype).
+    async fn find_used_func_id(
+        ctx: &DalContext,
+        schema_variant_id: SchemaVariantId,
+        func_kind: FuncKind,
+    ) -> SchemaVariantResult<Option<FuncId>> {
+        let workspace_snapshot = ctx.workspace_snapshot()?;
+
+        for node_index in workspace_snapshot
+            .outgoing_targets_for_edge_weight_kind(
+                schema_variant_id,
+                EdgeWeightKindDiscriminants::Use,
+            )
+            .await?
+        {
+            let NodeWeight::Func(func_node_weight) =
+                workspace_snapshot.get_node_weight(node_index).await?
+            else {
+                continue;
+            };
+
+            if func_node_weight.func_kind() == func_kind {
+                return Ok(Some(func_node_weight.id().into()));
+            }
+        }
+
+        Ok(None)
+    }
+
     #[allow(dead_code)]
     async fn get_content(
         ctx: &DalContext,
@@ -1735,6 +1855,14 @@ impl SchemaVariant {
         let auth_func_ids = Self::list_auth_func_ids_for_id(ctx, schema_variant_id).await?;
         all_func_ids.extend(auth_func_ids);
 
+        // Gather the reconciliation and import funcs.
+        if let Some(func_id) = Self::find_reconciliation_func_id(ctx, schema_variant_id).await? {
+            all_func_ids.insert(func_id);
+        }
+        if let Some(func_id) = Self::find_import_func_id(ctx, schema_variant_id).await? {
+            all_func_ids.insert(func_id);
+        }
+
         // Gather all action funcs.
         let action_prototype_nodes = workspace_snapshot
             .outgoing_targets_for_edge_weight_kind(
@@ -1995,6 +2123,25 @@ impl SchemaVariant {
 
         for (edge_weight, source_index, target_index) in maybe_schema_indices {
             let kind = EdgeWeightKindDiscriminants::from(edge_weight.kind());
+
+            // Reconciliation and import funcs can be shared with other variants, so only the edge to
+            // them is removed.
+            if kind == EdgeWeightKindDiscriminants::Use {
+                if let NodeWeight::Func(func_node_weight) =
+                    workspace_snapshot.get_node_weight(target_index).await?
+                {
+                    if matches!(
+                        func_node_weight.func_kind(),
+                        FuncKind::Reconciliation | FuncKind::Import
+                    ) {
+                        workspace_snapshot
+                            .remove_edge(source_index, target_index, kind)
+                            .await?;
+                        continue;
+                    }
+                }
+            }
+
             match kind {
                 EdgeWeightKindDiscriminants::Use
                 | EdgeWeightKindDiscriminants::Socket
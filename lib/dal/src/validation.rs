@@ -25,6 +25,10 @@ use crate::{
 };
 use crate::{ComponentError, DalContext, TransactionsError};
 
+pub mod format;
+
+use format::NativeValidator;
+
 #[allow(clippy::large_enum_variant)]
 #[remain::sorted]
 #[derive(Error, Debug)]
@@ -293,6 +297,17 @@ impl ValidationOutput {
             return Ok(None);
         };
 
+        // Most formats only use rules we can evaluate ourselves, without a round trip to cyclone
+        if let Some(validator) = NativeValidator::from_format(&validation_format) {
+            let message = validator.validate(value.as_ref());
+            let status = if message.is_none() {
+                ValidationStatus::Success
+            } else {
+                ValidationStatus::Failure
+            };
+            return Ok(Some(ValidationOutput { status, message }));
+        }
+
         let result_channel =
             FuncRunner::run_validation_format(ctx, attribute_value_id, value, validation_format)
                 .await
@@ -0,0 +1,448 @@
+//! Native evaluation of [`Prop`](crate::Prop) validation formats.
+//!
+//! A validation format is the JSON description of a Joi schema (the output of Joi's `describe()`)
+//! that is otherwise evaluated by running Joi in cyclone. The common rules are evaluated here
+//! in-process, producing the same messages Joi would. Formats using anything else are left to
+//! cyclone: [`NativeValidator::from_format`] returns `None` for them.
+
+use regex::{Regex, RegexBuilder};
+use serde::Deserialize;
+use serde_json::{Map, Number, Value};
+
+/// A validator for a validation format, built only from the Joi rules it fully supports.
+#[derive(Debug, Clone)]
+pub struct NativeValidator {
+    kind: ValueKind,
+    required: bool,
+    only: bool,
+    allow: Vec<Value>,
+    rules: Vec<Rule>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ValueKind {
+    Boolean,
+    Number,
+    String,
+}
+
+#[derive(Debug, Clone)]
+enum Rule {
+    Email,
+    Greater(Number),
+    Integer,
+    Length(u64),
+    Less(Number),
+    Max(Number),
+    MaxLength(u64),
+    Min(Number),
+    MinLength(u64),
+    Pattern { regex: Regex, source: String },
+    Uri,
+}
+
+#[derive(Debug, Deserialize)]
+struct JoiDescription {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    flags: Map<String, Value>,
+    #[serde(default)]
+    rules: Vec<JoiRule>,
+    #[serde(default)]
+    allow: Vec<Value>,
+    #[serde(flatten)]
+    unsupported: Map<String, Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct JoiRule {
+    name: String,
+    #[serde(default)]
+    args: Map<String, Value>,
+}
+
+impl NativeValidator {
+    /// Builds a validator for a validation format, returning `None` if the format is not valid
+    /// JSON or uses any type, flag or rule that is not supported natively.
+    pub fn from_format(validation_format: &str) -> Option<Self> {
+        let description: JoiDescription = serde_json::from_str(validation_format).ok()?;
+        if !description.unsupported.is_empty() {
+            return None;
+        }
+
+        let kind = match description.kind.as_str() {
+            "boolean" => ValueKind::Boolean,
+            "number" => ValueKind::Number,
+            "string" => ValueKind::String,
+            _ => return None,
+        };
+
+        let mut required = false;
+        let mut only = false;
+        for (flag, value) in &description.flags {
+            match (flag.as_str(), value) {
+                ("presence", Value::String(presence)) if presence == "required" => required = true,
+                ("presence", Value::String(presence)) if presence == "optional" => {}
+                ("only", Value::Bool(flag)) => only = *flag,
+                _ => return None,
+            }
+        }
+
+        let rules = description
+            .rules
+            .into_iter()
+            .map(|rule| Rule::from_joi(kind, rule))
+            .collect::<Option<Vec<_>>>()?;
+
+        Some(Self {
+            kind,
+            required,
+            only,
+            allow: description.allow,
+            rules,
+        })
+    }
+
+    /// Validates a value, returning the message for the first rule it fails, if any.
+    ///
+    /// Like in cyclone, a null value is treated as no value at all.
+    pub fn validate(&self, value: Option<&Value>) -> Option<String> {
+        let value = match value {
+            None | Some(Value::Null) => {
+                return self.required.then(|| "\"value\" is required".to_string());
+            }
+            Some(value) => value,
+        };
+
+        if self.allow.iter().any(|allowed| same_value(allowed, value)) {
+            return None;
+        }
+        if self.only {
+            let valids = self
+                .allow
+                .iter()
+                .map(display_value)
+                .collect::<Vec<_>>()
+                .join(", ");
+            return Some(format!("\"value\" must be one of [{valids}]"));
+        }
+
+        match self.kind {
+            ValueKind::Boolean => match value {
+                Value::Bool(_) => None,
+                Value::String(value)
+                    if value.eq_ignore_ascii_case("true")
+                        || value.eq_ignore_ascii_case("false") =>
+                {
+                    None
+                }
+                _ => Some("\"value\" must be a boolean".to_string()),
+            },
+            ValueKind::Number => {
+                // Joi converts numeric strings to numbers before validating them
+                let number = match value {
+                    Value::Number(number) => number.as_f64(),
+                    Value::String(value) => value.trim().parse::<f64>().ok(),
+                    _ => None,
+                };
+                match number.filter(|number| number.is_finite()) {
+                    Some(number) => self.rules.iter().find_map(|rule| rule.check_number(number)),
+                    None => Some("\"value\" must be a number".to_string()),
+                }
+            }
+            ValueKind::String => match value {
+                Value::String(value) if value.is_empty() => {
+                    Some("\"value\" is not allowed to be empty".to_string())
+                }
+                Value::String(value) => self.rules.iter().find_map(|rule| rule.check_string(value)),
+                _ => Some("\"value\" must be a string".to_string()),
+            },
+        }
+    }
+}
+
+impl Rule {
+    fn from_joi(kind: ValueKind, rule: JoiRule) -> Option<Self> {
+        let limit = || match rule.args.get("limit") {
+            Some(Value::Number(limit)) if rule.args.len() == 1 => Some(limit.to_owned()),
+            _ => None,
+        };
+        let length_limit = || limit().and_then(|limit| limit.as_u64());
+
+        Some(match (kind, rule.name.as_str()) {
+            (ValueKind::String, "email") if rule.args.is_empty() => Self::Email,
+            (ValueKind::String, "length") => Self::Length(length_limit()?),
+            (ValueKind::String, "max") => Self::MaxLength(length_limit()?),
+            (ValueKind::String, "min") => Self::MinLength(length_limit()?),
+            (ValueKind::String, "pattern") => {
+                let source = match rule.args.get("regex") {
+                    Some(Value::String(source)) if rule.args.len() == 1 => source.to_owned(),
+                    _ => return None,
+                };
+                Self::Pattern {
+                    regex: compile_js_regex(&source)?,
+                    source,
+                }
+            }
+            (ValueKind::String, "uri") if rule.args.is_empty() => Self::Uri,
+            (ValueKind::Number, "greater") => Self::Greater(limit()?),
+            (ValueKind::Number, "integer") if rule.args.is_empty() => Self::Integer,
+            (ValueKind::Number, "less") => Self::Less(limit()?),
+            (ValueKind::Number, "max") => Self::Max(limit()?),
+            (ValueKind::Number, "min") => Self::Min(limit()?),
+            _ => return None,
+        })
+    }
+
+    fn check_string(&self, value: &str) -> Option<String> {
+        let length = value.chars().count() as u64;
+        match self {
+            Self::Email if !is_email(value) => Some("\"value\" must be a valid email".to_string()),
+            Self::Length(limit) if length != *limit => {
+                Some(format!("\"value\" length must be {limit} characters long"))
+            }
+            Self::MaxLength(limit) if length > *limit => Some(format!(
+                "\"value\" length must be less than or equal to {limit} characters long"
+            )),
+            Self::MinLength(limit) if length < *limit => Some(format!(
+                "\"value\" length must be at least {limit} characters long"
+            )),
+            Self::Pattern { regex, source } if !regex.is_match(value) => Some(format!(
+                "\"value\" with value \"{value}\" fails to match the required pattern: {source}"
+            )),
+            Self::Uri if url::Url::parse(value).is_err() => {
+                Some("\"value\" must be a valid uri".to_string())
+            }
+            _ => None,
+        }
+    }
+
+    fn check_number(&self, value: f64) -> Option<String> {
+        let limit_of = |limit: &Number| limit.as_f64().unwrap_or(f64::NAN);
+        match self {
+            Self::Greater(limit) if value <= limit_of(limit) => {
+                Some(format!("\"value\" must be greater than {limit}"))
+            }
+            Self::Integer if value.fract() != 0.0 => {
+                Some("\"value\" must be an integer".to_string())
+            }
+            Self::Less(limit) if value >= limit_of(limit) => {
+                Some(format!("\"value\" must be less than {limit}"))
+            }
+            Self::Max(limit) if value > limit_of(limit) => {
+                Some(format!("\"value\" must be less than or equal to {limit}"))
+            }
+            Self::Min(limit) if value < limit_of(limit) => Some(format!(
+                "\"value\" must be greater than or equal to {limit}"
+            )),
+            _ => None,
+        }
+    }
+}
+
+/// Compiles a regex described by Joi as `/source/flags`, returning `None` if it uses flags or
+/// syntax that cannot be matched the same way natively.
+fn compile_js_regex(described: &str) -> Option<Regex> {
+    let described = described.strip_prefix('/')?;
+    let (source, flags) = described.rsplit_once('/')?;
+
+    let mut builder = RegexBuilder::new(source);
+    for flag in flags.chars() {
+        match flag {
+            'i' => builder.case_insensitive(true),
+            'm' => builder.multi_line(true),
+            's' => builder.dot_matches_new_line(true),
+            // Global, sticky and unicode matching do not change whether a value matches
+            'g' | 'y' | 'u' => &mut builder,
+            _ => return None,
+        };
+    }
+
+    builder.build().ok()
+}
+
+/// A simplified version of Joi's email check: a local part, an `@` and a domain of at least two
+/// labels ending in an alphabetic top level domain.
+fn is_email(value: &str) -> bool {
+    let Some((local, domain)) = value.split_once('@') else {
+        return false;
+    };
+    if local.is_empty() || local.chars().any(char::is_whitespace) || domain.contains('@') {
+        return false;
+    }
+
+    let labels: Vec<&str> = domain.split('.').collect();
+    let valid_label = |label: &&str| {
+        !label.is_empty()
+            && !label.starts_with('-')
+            && !label.ends_with('-')
+            && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+    };
+    labels.len() >= 2
+        && labels.iter().all(valid_label)
+        && labels
+            .last()
+            .is_some_and(|tld| tld.len() >= 2 && tld.chars().all(|c| c.is_ascii_alphabetic()))
+}
+
+fn same_value(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::Number(a), Value::Number(b)) => a.as_f64() == b.as_f64(),
+        (a, b) => a == b,
+    }
+}
+
+fn display_value(value: &Value) -> String {
+    match value {
+        Value::String(value) => value.to_owned(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn validator(format: Value) -> NativeValidator {
+        NativeValidator::from_format(&format.to_string()).expect("format should be supported")
+    }
+
+    #[test]
+    fn unsupported_formats_fall_back() {
+        for format in [
+            json!({ "type": "object" }),
+            json!({ "type": "string", "rules": [{ "name": "hostname" }] }),
+            json!({ "type": "string", "flags": { "label": "name" } }),
+            json!({ "type": "string", "rules": [{ "name": "pattern", "args": { "regex": "/(?=a)/" } }] }),
+            json!({ "type": "number", "rules": [{ "name": "min", "args": { "limit": { "ref": { "path": ["a"] } } } }] }),
+            json!({ "type": "string", "messages": {} }),
+        ] {
+            assert!(
+                NativeValidator::from_format(&format.to_string()).is_none(),
+                "{format} should not be supported"
+            );
+        }
+        assert!(NativeValidator::from_format("not json").is_none());
+    }
+
+    #[test]
+    fn strings() {
+        let validator = validator(json!({
+            "type": "string",
+            "flags": { "presence": "required" },
+            "rules": [
+                { "name": "min", "args": { "limit": 2 } },
+                { "name": "max", "args": { "limit": 5 } },
+                { "name": "pattern", "args": { "regex": "/^[a-z]+$/i" } },
+            ],
+        }));
+
+        assert_eq!(None, validator.validate(Some(&json!("Abc"))));
+        assert_eq!(
+            Some("\"value\" is required".to_string()),
+            validator.validate(Some(&Value::Null))
+        );
+        assert_eq!(
+            Some("\"value\" is not allowed to be empty".to_string()),
+            validator.validate(Some(&json!("")))
+        );
+        assert_eq!(
+            Some("\"value\" must be a string".to_string()),
+            validator.validate(Some(&json!(1)))
+        );
+        assert_eq!(
+            Some("\"value\" length must be at least 2 characters long".to_string()),
+            validator.validate(Some(&json!("a")))
+        );
+        assert_eq!(
+            Some("\"value\" length must be less than or equal to 5 characters long".to_string()),
+            validator.validate(Some(&json!("abcdef")))
+        );
+        assert_eq!(
+            Some(
+                "\"value\" with value \"ab1\" fails to match the required pattern: /^[a-z]+$/i"
+                    .to_string()
+            ),
+            validator.validate(Some(&json!("ab1")))
+        );
+    }
+
+    #[test]
+    fn emails_and_uris() {
+        let email = validator(json!({ "type": "string", "rules": [{ "name": "email" }] }));
+        assert_eq!(None, email.validate(Some(&json!("dev@systeminit.com"))));
+        for invalid in [
+            "dev",
+            "dev@",
+            "@systeminit.com",
+            "dev@localhost",
+            "a b@c.com",
+        ] {
+            assert_eq!(
+                Some("\"value\" must be a valid email".to_string()),
+                email.validate(Some(&json!(invalid))),
+                "{invalid} should not be a valid email"
+            );
+        }
+
+        let uri = validator(json!({ "type": "string", "rules": [{ "name": "uri" }] }));
+        assert_eq!(
+            None,
+            uri.validate(Some(&json!("https://systeminit.com/docs")))
+        );
+        assert_eq!(
+            Some("\"value\" must be a valid uri".to_string()),
+            uri.validate(Some(&json!("systeminit.com")))
+        );
+    }
+
+    #[test]
+    fn numbers() {
+        let validator = validator(json!({
+            "type": "number",
+            "rules": [
+                { "name": "integer" },
+                { "name": "min", "args": { "limit": 1 } },
+                { "name": "less", "args": { "limit": 65536 } },
+            ],
+        }));
+
+        assert_eq!(None, validator.validate(None));
+        assert_eq!(None, validator.validate(Some(&json!(443))));
+        assert_eq!(None, validator.validate(Some(&json!("443"))));
+        assert_eq!(
+            Some("\"value\" must be a number".to_string()),
+            validator.validate(Some(&json!("https")))
+        );
+        assert_eq!(
+            Some("\"value\" must be an integer".to_string()),
+            validator.validate(Some(&json!(1.5)))
+        );
+        assert_eq!(
+            Some("\"value\" must be greater than or equal to 1".to_string()),
+            validator.validate(Some(&json!(0)))
+        );
+        assert_eq!(
+            Some("\"value\" must be less than 65536".to_string()),
+            validator.validate(Some(&json!(65536)))
+        );
+    }
+
+    #[test]
+    fn allowed_values() {
+        let validator = validator(json!({
+            "type": "string",
+            "flags": { "only": true },
+            "allow": ["us-east-1", "us-west-2"],
+        }));
+
+        assert_eq!(None, validator.validate(Some(&json!("us-west-2"))));
+        assert_eq!(
+            Some("\"value\" must be one of [us-east-1, us-west-2]".to_string()),
+            validator.validate(Some(&json!("eu-west-1")))
+        );
+    }
+}
@@ -0,0 +1,336 @@
+//! Outbound webhook subscriptions, which deliver a workspace's [`WsEvents`](crate::WsEvent) to
+//! external consumers.
+//!
+//! A [`WebhookSubscription`] belongs to a workspace and names the kinds of
+//! [`WsPayload`](crate::WsPayload) it wants to receive and the URL to deliver them to. Each event
+//! delivered to a subscription is recorded as a [`WebhookDelivery`], which is both the retry queue
+//! for failed deliveries and the log of deliveries made.
+//!
+//! Payloads are signed with an HMAC-SHA256 of `{timestamp}.{body}`, keyed by a secret generated
+//! for the subscription and stored encrypted with the symmetric crypto service, so that
+//! consumers can verify deliveries came from us and reject replays of old ones.
+
+use std::{fmt, str::FromStr};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use si_crypto::{SymmetricCryptoError, SymmetricCryptoService, SymmetricNonce};
+use si_data_pg::{PgError, PgRow};
+use si_hash::Hash;
+use sodiumoxide::crypto::auth::hmacsha256;
+use thiserror::Error;
+
+use crate::{pk, DalContext, Timestamp, TransactionsError, WorkspacePk, WsPayload};
+
+pub mod delivery;
+
+pub use delivery::{WebhookDelivery, WebhookDeliveryPk, WebhookDeliveryStatus};
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum WebhookError {
+    #[error("webhook subscription must filter on at least one event kind")]
+    EmptyEventKinds,
+    #[error("invalid retry backoff")]
+    InvalidBackoff,
+    #[error("invalid signing secret key hash: {0}")]
+    InvalidKeyHash(String),
+    #[error("invalid signing secret bytes")]
+    InvalidSigningSecretBytes,
+    #[error("invalid signing secret nonce bytes")]
+    InvalidSigningSecretNonce,
+    #[error("no workspace in tenancy")]
+    NoWorkspaceInTenancy,
+    #[error("pg error: {0}")]
+    Pg(#[from] PgError),
+    #[error("symmetric crypto error: {0}")]
+    SymmetricCrypto(#[from] SymmetricCryptoError),
+    #[error("transactions error: {0}")]
+    Transactions(#[from] TransactionsError),
+    #[error("unknown webhook delivery status: {0}")]
+    UnknownDeliveryStatus(String),
+    #[error("unknown event kind: {0}")]
+    UnknownEventKind(String),
+    #[error("webhook subscription not found: {0}")]
+    WebhookSubscriptionNotFound(WebhookSubscriptionPk),
+}
+
+pub type WebhookResult<T> = Result<T, WebhookError>;
+
+pk!(WebhookSubscriptionPk);
+
+/// The header carrying the signature of a delivery's payload.
+pub const SIGNATURE_HEADER: &str = "X-SI-Webhook-Signature";
+/// The header carrying the timestamp a delivery's payload was signed at.
+pub const TIMESTAMP_HEADER: &str = "X-SI-Webhook-Timestamp";
+/// The header carrying the kind of event delivered.
+pub const EVENT_KIND_HEADER: &str = "X-SI-Webhook-Event";
+/// The header carrying the id of the delivery, which is the same across retries.
+pub const DELIVERY_HEADER: &str = "X-SI-Webhook-Delivery";
+
+/// A workspace's subscription to have some kinds of [`WsEvent`](crate::WsEvent) delivered to a
+/// URL.
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookSubscription {
+    pk: WebhookSubscriptionPk,
+    workspace_pk: WorkspacePk,
+    url: String,
+    event_kinds: Vec<String>,
+    #[serde(skip)]
+    signing_secret: Vec<u8>,
+    #[serde(flatten)]
+    timestamp: Timestamp,
+}
+
+impl fmt::Debug for WebhookSubscription {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WebhookSubscription")
+            .field("pk", &self.pk)
+            .field("workspace_pk", &self.workspace_pk)
+            .field("url", &self.url)
+            .field("event_kinds", &self.event_kinds)
+            .finish_non_exhaustive()
+    }
+}
+
+impl WebhookSubscription {
+    /// Creates a subscription for the workspace in the context's tenancy, generating a new signing
+    /// secret for it.
+    pub async fn new(
+        ctx: &DalContext,
+        url: impl Into<String>,
+        event_kinds: Vec<String>,
+    ) -> WebhookResult<Self> {
+        let workspace_pk = ctx
+            .tenancy()
+            .workspace_pk()
+            .ok_or(WebhookError::NoWorkspaceInTenancy)?;
+        let url: String = url.into();
+        validate_event_kinds(&event_kinds)?;
+
+        let signing_secret = hmacsha256::gen_key();
+        let (crypted, nonce, key_hash) = ctx
+            .symmetric_crypto_service()
+            .encrypt(signing_secret.as_ref());
+
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "INSERT INTO webhook_subscriptions (workspace_pk, url, event_kinds, signing_secret_crypted, signing_secret_nonce, signing_secret_key_hash)
+                    VALUES ($1, $2, $3, $4, $5, $6) RETURNING *",
+                &[
+                    &workspace_pk,
+                    &url,
+                    &event_kinds,
+                    &crypted,
+                    &nonce.as_ref(),
+                    &key_hash.to_string(),
+                ],
+            )
+            .await?;
+
+        Self::from_row(ctx.symmetric_crypto_service(), row)
+    }
+
+    pub async fn get_by_pk(ctx: &DalContext, pk: WebhookSubscriptionPk) -> WebhookResult<Self> {
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_opt(
+                "SELECT * FROM webhook_subscriptions WHERE pk = $1 AND workspace_pk = $2",
+                &[&pk, &ctx.tenancy().workspace_pk()],
+            )
+            .await?
+            .ok_or(WebhookError::WebhookSubscriptionNotFound(pk))?;
+
+        Self::from_row(ctx.symmetric_crypto_service(), row)
+    }
+
+    /// Lists the subscriptions of the workspace in the context's tenancy.
+    pub async fn list(ctx: &DalContext) -> WebhookResult<Vec<Self>> {
+        let workspace_pk = ctx
+            .tenancy()
+            .workspace_pk()
+            .ok_or(WebhookError::NoWorkspaceInTenancy)?;
+
+        Self::list_for_workspace(ctx, workspace_pk).await
+    }
+
+    /// Lists the subscriptions of a workspace, regardless of the context's tenancy.
+    pub async fn list_for_workspace(
+        ctx: &DalContext,
+        workspace_pk: WorkspacePk,
+    ) -> WebhookResult<Vec<Self>> {
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(
+                "SELECT * FROM webhook_subscriptions WHERE workspace_pk = $1 ORDER BY created_at",
+                &[&workspace_pk],
+            )
+            .await?;
+
+        rows.into_iter()
+            .map(|row| Self::from_row(ctx.symmetric_crypto_service(), row))
+            .collect()
+    }
+
+    /// Deletes the subscription, along with its log of deliveries and any pending retries.
+    pub async fn delete(self, ctx: &DalContext) -> WebhookResult<()> {
+        ctx.txns()
+            .await?
+            .pg()
+            .execute(
+                "DELETE FROM webhook_subscriptions WHERE pk = $1",
+                &[&self.pk],
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    pub fn pk(&self) -> WebhookSubscriptionPk {
+        self.pk
+    }
+
+    pub fn workspace_pk(&self) -> WorkspacePk {
+        self.workspace_pk
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    pub fn event_kinds(&self) -> &[String] {
+        &self.event_kinds
+    }
+
+    /// The secret payloads are signed with, hex encoded, which is shared with the consumer so it
+    /// can verify signatures.
+    pub fn signing_secret_hex(&self) -> String {
+        hex::encode(&self.signing_secret)
+    }
+
+    /// Whether the subscription wants events of the given kind.
+    pub fn accepts(&self, event_kind: &str) -> bool {
+        self.event_kinds.iter().any(|kind| kind == event_kind)
+    }
+
+    /// Signs a payload as sent at the given timestamp, returning the value of the
+    /// [`SIGNATURE_HEADER`].
+    pub fn sign(&self, timestamp: DateTime<Utc>, body: &[u8]) -> WebhookResult<String> {
+        sign(&self.signing_secret, timestamp, body)
+    }
+
+    fn from_row(
+        symmetric_crypto_service: &SymmetricCryptoService,
+        row: PgRow,
+    ) -> WebhookResult<Self> {
+        let created_at: DateTime<Utc> = row.try_get("created_at")?;
+        let updated_at: DateTime<Utc> = row.try_get("updated_at")?;
+
+        let crypted: Vec<u8> = row.try_get("signing_secret_crypted")?;
+        let nonce_bytes: Vec<u8> = row.try_get("signing_secret_nonce")?;
+        let key_hash: String = row.try_get("signing_secret_key_hash")?;
+
+        let nonce = SymmetricNonce::from_slice(&nonce_bytes)
+            .ok_or(WebhookError::InvalidSigningSecretNonce)?;
+        let key_hash =
+            Hash::from_str(&key_hash).map_err(|_| WebhookError::InvalidKeyHash(key_hash))?;
+        let signing_secret = symmetric_crypto_service.decrypt(&crypted, &nonce, &key_hash)?;
+
+        Ok(Self {
+            pk: row.try_get("pk")?,
+            workspace_pk: row.try_get("workspace_pk")?,
+            url: row.try_get("url")?,
+            event_kinds: row.try_get("event_kinds")?,
+            signing_secret,
+            timestamp: Timestamp::assemble(created_at, updated_at),
+        })
+    }
+}
+
+fn validate_event_kinds(event_kinds: &[String]) -> WebhookResult<()> {
+    use strum::VariantNames;
+
+    // Subscribing to everything would include high-frequency events such as cursor movements,
+    // so subscriptions must opt in to the kinds they want
+    if event_kinds.is_empty() {
+        return Err(WebhookError::EmptyEventKinds);
+    }
+    if let Some(unknown) = event_kinds
+        .iter()
+        .find(|kind| !WsPayload::VARIANTS.contains(&kind.as_str()))
+    {
+        return Err(WebhookError::UnknownEventKind(unknown.to_owned()));
+    }
+
+    Ok(())
+}
+
+fn sign(secret: &[u8], timestamp: DateTime<Utc>, body: &[u8]) -> WebhookResult<String> {
+    let key = hmacsha256::Key::from_slice(secret).ok_or(WebhookError::InvalidSigningSecretBytes)?;
+
+    let mut state = hmacsha256::State::init(key.as_ref());
+    state.update(timestamp.timestamp().to_string().as_bytes());
+    state.update(b".");
+    state.update(body);
+
+    Ok(format!("sha256={}", hex::encode(state.finalize())))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    #[test]
+    fn sign_covers_timestamp_and_body() {
+        sodiumoxide::init().expect("crypto failed to init");
+        let secret = hmacsha256::gen_key();
+        let timestamp = Utc
+            .timestamp_opt(1_700_000_000, 0)
+            .single()
+            .expect("timestamp is valid");
+        let later = Utc
+            .timestamp_opt(1_700_000_001, 0)
+            .single()
+            .expect("timestamp is valid");
+
+        let signature = sign(secret.as_ref(), timestamp, b"{}").expect("failed to sign");
+
+        let expected = hmacsha256::authenticate(b"1700000000.{}", &secret);
+        assert_eq!(format!("sha256={}", hex::encode(expected)), signature);
+        assert_ne!(
+            signature,
+            sign(secret.as_ref(), later, b"{}").expect("failed to sign")
+        );
+        assert_ne!(
+            signature,
+            sign(secret.as_ref(), timestamp, b"[]").expect("failed to sign")
+        );
+    }
+
+    #[test]
+    fn event_kinds_must_be_known_and_non_empty() {
+        assert!(matches!(
+            validate_event_kinds(&[]),
+            Err(WebhookError::EmptyEventKinds)
+        ));
+        assert!(matches!(
+            validate_event_kinds(&["NotAnEvent".to_owned()]),
+            Err(WebhookError::UnknownEventKind(kind)) if kind == "NotAnEvent"
+        ));
+        assert!(validate_event_kinds(&[
+            "ChangeSetApplied".to_owned(),
+            "ComponentUpdated".to_owned()
+        ])
+        .is_ok());
+    }
+}
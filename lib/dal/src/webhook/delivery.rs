@@ -0,0 +1,312 @@
+//! The deliveries of events to [`WebhookSubscriptions`](super::WebhookSubscription).
+//!
+//! A delivery is recorded as pending when an event is accepted by a subscription, and is claimed
+//! by a dispatcher to be sent. Failed attempts are retried with exponential backoff until they
+//! succeed or run out of attempts. Claiming a delivery pushes back its next attempt rather than
+//! locking it, so that a dispatcher which goes away mid-attempt only delays the delivery.
+
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use si_data_pg::PgRow;
+use strum::{Display, EnumString};
+
+use crate::{pk, DalContext, Timestamp, WorkspacePk};
+
+use super::{WebhookError, WebhookResult, WebhookSubscriptionPk};
+
+/// The number of attempts made to deliver an event before giving up on it.
+pub const MAX_ATTEMPTS: i32 = 8;
+
+/// How long a claimed delivery is left to its dispatcher before being claimed again.
+pub const CLAIM_TIMEOUT: Duration = Duration::from_secs(60);
+
+const BASE_BACKOFF: Duration = Duration::from_secs(10);
+const MAX_BACKOFF: Duration = Duration::from_secs(60 * 60);
+
+pk!(WebhookDeliveryPk);
+
+#[remain::sorted]
+#[derive(Clone, Copy, Debug, Deserialize, Display, EnumString, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[strum(serialize_all = "camelCase")]
+pub enum WebhookDeliveryStatus {
+    /// Every attempt failed
+    Failed,
+    /// Waiting for its first attempt or a retry
+    Pending,
+    /// Delivered successfully
+    Succeeded,
+}
+
+/// An event delivered, or to be delivered, to a subscription.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookDelivery {
+    pk: WebhookDeliveryPk,
+    subscription_pk: WebhookSubscriptionPk,
+    workspace_pk: WorkspacePk,
+    event_kind: String,
+    payload: serde_json::Value,
+    status: WebhookDeliveryStatus,
+    attempts: i32,
+    next_attempt_at: Option<DateTime<Utc>>,
+    last_response_status: Option<i32>,
+    last_error: Option<String>,
+    #[serde(flatten)]
+    timestamp: Timestamp,
+}
+
+impl TryFrom<PgRow> for WebhookDelivery {
+    type Error = WebhookError;
+
+    fn try_from(row: PgRow) -> Result<Self, Self::Error> {
+        let created_at: DateTime<Utc> = row.try_get("created_at")?;
+        let updated_at: DateTime<Utc> = row.try_get("updated_at")?;
+        let status: String = row.try_get("status")?;
+
+        Ok(Self {
+            pk: row.try_get("pk")?,
+            subscription_pk: row.try_get("subscription_pk")?,
+            workspace_pk: row.try_get("workspace_pk")?,
+            event_kind: row.try_get("event_kind")?,
+            payload: row.try_get("payload")?,
+            status: status
+                .parse()
+                .map_err(|_| WebhookError::UnknownDeliveryStatus(status))?,
+            attempts: row.try_get("attempts")?,
+            next_attempt_at: row.try_get("next_attempt_at")?,
+            last_response_status: row.try_get("last_response_status")?,
+            last_error: row.try_get("last_error")?,
+            timestamp: Timestamp::assemble(created_at, updated_at),
+        })
+    }
+}
+
+impl WebhookDelivery {
+    /// Records a pending delivery of an event to a subscription, due to be attempted straight
+    /// away.
+    pub async fn enqueue(
+        ctx: &DalContext,
+        subscription_pk: WebhookSubscriptionPk,
+        workspace_pk: WorkspacePk,
+        event_kind: impl Into<String>,
+        payload: serde_json::Value,
+    ) -> WebhookResult<Self> {
+        let event_kind: String = event_kind.into();
+
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "INSERT INTO webhook_deliveries (subscription_pk, workspace_pk, event_kind, payload, status, next_attempt_at)
+                    VALUES ($1, $2, $3, $4, $5, CLOCK_TIMESTAMP()) RETURNING *",
+                &[
+                    &subscription_pk,
+                    &workspace_pk,
+                    &event_kind,
+                    &payload,
+                    &WebhookDeliveryStatus::Pending.to_string(),
+                ],
+            )
+            .await?;
+
+        row.try_into()
+    }
+
+    /// Claims up to `limit` pending deliveries which are due an attempt, pushing back their next
+    /// attempt by the [`CLAIM_TIMEOUT`] so that other dispatchers don't claim them too.
+    pub async fn claim_due(ctx: &DalContext, limit: i64) -> WebhookResult<Vec<Self>> {
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(
+                "UPDATE webhook_deliveries
+                    SET next_attempt_at = CLOCK_TIMESTAMP() + make_interval(secs => $3)
+                    WHERE pk IN (
+                        SELECT pk FROM webhook_deliveries
+                            WHERE status = $1 AND next_attempt_at <= CLOCK_TIMESTAMP()
+                            ORDER BY next_attempt_at
+                            LIMIT $2
+                            FOR UPDATE SKIP LOCKED
+                    )
+                    RETURNING *",
+                &[
+                    &WebhookDeliveryStatus::Pending.to_string(),
+                    &limit,
+                    &CLAIM_TIMEOUT.as_secs_f64(),
+                ],
+            )
+            .await?;
+
+        rows.into_iter().map(TryInto::try_into).collect()
+    }
+
+    /// Lists the most recent deliveries to a subscription, newest first.
+    pub async fn list_for_subscription(
+        ctx: &DalContext,
+        subscription_pk: WebhookSubscriptionPk,
+        limit: i64,
+    ) -> WebhookResult<Vec<Self>> {
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(
+                "SELECT * FROM webhook_deliveries WHERE subscription_pk = $1 ORDER BY created_at DESC LIMIT $2",
+                &[&subscription_pk, &limit],
+            )
+            .await?;
+
+        rows.into_iter().map(TryInto::try_into).collect()
+    }
+
+    /// Records a successful attempt.
+    pub async fn record_success(
+        &mut self,
+        ctx: &DalContext,
+        response_status: u16,
+    ) -> WebhookResult<()> {
+        self.record_attempt(
+            ctx,
+            WebhookDeliveryStatus::Succeeded,
+            None,
+            Some(response_status),
+            None,
+        )
+        .await
+    }
+
+    /// Records a failed attempt, scheduling a retry unless the delivery is out of attempts.
+    pub async fn record_failure(
+        &mut self,
+        ctx: &DalContext,
+        response_status: Option<u16>,
+        error: impl Into<String>,
+    ) -> WebhookResult<()> {
+        let attempts = self.attempts + 1;
+        let (status, next_attempt_at) = if attempts >= MAX_ATTEMPTS {
+            (WebhookDeliveryStatus::Failed, None)
+        } else {
+            let delay = chrono::Duration::from_std(backoff(attempts))
+                .map_err(|_| WebhookError::InvalidBackoff)?;
+            (WebhookDeliveryStatus::Pending, Some(Utc::now() + delay))
+        };
+
+        self.record_attempt(
+            ctx,
+            status,
+            next_attempt_at,
+            response_status,
+            Some(error.into()),
+        )
+        .await
+    }
+
+    async fn record_attempt(
+        &mut self,
+        ctx: &DalContext,
+        status: WebhookDeliveryStatus,
+        next_attempt_at: Option<DateTime<Utc>>,
+        response_status: Option<u16>,
+        error: Option<String>,
+    ) -> WebhookResult<()> {
+        let response_status = response_status.map(i32::from);
+
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "UPDATE webhook_deliveries
+                    SET status = $2, attempts = attempts + 1, next_attempt_at = $3,
+                        last_response_status = $4, last_error = $5, updated_at = CLOCK_TIMESTAMP()
+                    WHERE pk = $1
+                    RETURNING *",
+                &[
+                    &self.pk,
+                    &status.to_string(),
+                    &next_attempt_at,
+                    &response_status,
+                    &error,
+                ],
+            )
+            .await?;
+
+        *self = row.try_into()?;
+        Ok(())
+    }
+
+    pub fn pk(&self) -> WebhookDeliveryPk {
+        self.pk
+    }
+
+    pub fn subscription_pk(&self) -> WebhookSubscriptionPk {
+        self.subscription_pk
+    }
+
+    pub fn workspace_pk(&self) -> WorkspacePk {
+        self.workspace_pk
+    }
+
+    pub fn event_kind(&self) -> &str {
+        &self.event_kind
+    }
+
+    pub fn payload(&self) -> &serde_json::Value {
+        &self.payload
+    }
+
+    pub fn status(&self) -> WebhookDeliveryStatus {
+        self.status
+    }
+
+    pub fn attempts(&self) -> i32 {
+        self.attempts
+    }
+}
+
+/// How long to wait before retrying a delivery which has failed `attempts` times, doubling with
+/// each attempt up to a maximum.
+fn backoff(attempts: i32) -> Duration {
+    let exponent = u32::try_from(attempts.saturating_sub(1))
+        .unwrap_or(0)
+        .min(16);
+    BASE_BACKOFF
+        .saturating_mul(2u32.saturating_pow(exponent))
+        .min(MAX_BACKOFF)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_doubles_up_to_max() {
+        assert_eq!(Duration::from_secs(10), backoff(1));
+        assert_eq!(Duration::from_secs(20), backoff(2));
+        assert_eq!(Duration::from_secs(40), backoff(3));
+        assert_eq!(MAX_BACKOFF, backoff(12));
+        assert_eq!(MAX_BACKOFF, backoff(i32::MAX));
+    }
+
+    #[test]
+    fn status_round_trips_through_strings() {
+        for status in [
+            WebhookDeliveryStatus::Failed,
+            WebhookDeliveryStatus::Pending,
+            WebhookDeliveryStatus::Succeeded,
+        ] {
+            assert_eq!(
+                status,
+                status
+                    .to_string()
+                    .parse::<WebhookDeliveryStatus>()
+                    .expect("failed to parse status")
+            );
+        }
+    }
+}
@@ -22,6 +22,7 @@
 // )]
 
 pub mod content_address;
+pub mod delta;
 pub mod edge_weight;
 pub mod graph;
 pub mod lamport_clock;
@@ -40,7 +41,9 @@ use petgraph::prelude::*;
 pub use petgraph::Direction;
 use serde::{Deserialize, Serialize};
 use si_data_pg::PgError;
-use si_events::{ulid::Ulid, ContentHash, WorkspaceSnapshotAddress};
+use si_events::{
+    rebase_batch_address::RebaseBatchAddress, ulid::Ulid, ContentHash, WorkspaceSnapshotAddress,
+};
 use si_layer_cache::LayerDbError;
 use strum::IntoEnumIterator;
 use telemetry::prelude::*;
@@ -48,6 +51,7 @@ use thiserror::Error;
 use tokio::sync::{Mutex, RwLock, RwLockReadGuard, RwLockWriteGuard};
 use tokio::task::JoinError;
 
+use self::delta::WorkspaceSnapshotDelta;
 use self::node_weight::{NodeWeightDiscriminants, OrderingNodeWeight};
 use crate::action::{Action, ActionError};
 use crate::attribute::prototype::argument::{
@@ -116,6 +120,8 @@ pub enum WorkspaceSnapshotError {
     Pg(#[from] PgError),
     #[error("postcard error: {0}")]
     Postcard(#[from] postcard::Error),
+    #[error("rebase batch missing at address: {0}")]
+    RebaseBatchMissing(RebaseBatchAddress),
     #[error("recently seen clocks missing for change set id {0}")]
     RecentlySeenClocksMissing(ChangeSetId),
     #[error("serde json error: {0}")]
@@ -438,6 +444,58 @@ impl WorkspaceSnapshot {
         Ok(new_address)
     }
 
+    /// Writes the snapshot as a delta: the batch at `rebase_batch_address`, which must be the only
+    /// change made to the snapshot since it was found, performed on top of the snapshot found.
+    ///
+    /// Every `checkpoint_interval` writes, the snapshot is written in full as a new checkpoint
+    /// instead, which bounds the number of batches performed when rebuilding it. An interval of 1
+    /// writes every snapshot in full.
+    #[instrument(
+        name = "workspace_snapshot.write_delta",
+        level = "debug",
+        skip_all,
+        fields(
+            si.workspace_snapshot.address = Empty,
+            si.workspace_snapshot.delta_depth = Empty,
+        )
+    )]
+    pub async fn write_delta(
+        &self,
+        ctx: &DalContext,
+        rebase_batch_address: RebaseBatchAddress,
+        checkpoint_interval: usize,
+    ) -> WorkspaceSnapshotResult<WorkspaceSnapshotAddress> {
+        let span = Span::current();
+
+        let base_address = self.id().await;
+        let base = WorkspaceSnapshotDelta::find(ctx, base_address).await?;
+        let delta = WorkspaceSnapshotDelta::append(base_address, base, rebase_batch_address);
+
+        if delta.depth() >= checkpoint_interval {
+            span.record("si.workspace_snapshot.delta_depth", 0);
+            return self.write(ctx).await;
+        }
+        span.record("si.workspace_snapshot.delta_depth", delta.depth());
+
+        let graph = {
+            let mut working_copy = self.working_copy_mut().await;
+            working_copy.cleanup();
+            Arc::new(WorkspaceSnapshotGraph::V1(working_copy.clone()))
+        };
+
+        delta.insert(ctx).await?;
+        ctx.layer_db()
+            .workspace_snapshot()
+            .insert_into_memory(&delta.workspace_snapshot_address, graph)
+            .await;
+
+        let new_address = delta.workspace_snapshot_address;
+        span.record("si.workspace_snapshot.address", new_address.to_string());
+        *self.address.write().await = new_address;
+
+        Ok(new_address)
+    }
+
     pub async fn id(&self) -> WorkspaceSnapshotAddress {
         *self.address.read().await
     }
@@ -852,32 +910,104 @@ impl WorkspaceSnapshot {
         ctx: &DalContext,
         workspace_snapshot_addr: WorkspaceSnapshotAddress,
     ) -> WorkspaceSnapshotResult<Self> {
-        let snapshot = match ctx
+        // A snapshot stored as a delta is only ever in memory, so check there before looking for
+        // a delta to rebuild it from
+        if let Some(snapshot) = ctx
+            .layer_db()
+            .workspace_snapshot()
+            .read_from_memory(&workspace_snapshot_addr)
+            .await
+        {
+            return Ok(Self::from_graph(workspace_snapshot_addr, snapshot));
+        }
+
+        if let Some(delta) = WorkspaceSnapshotDelta::find(ctx, workspace_snapshot_addr).await? {
+            return Self::rebuild_from_delta(ctx, delta).await;
+        }
+
+        let snapshot = Self::read_graph(ctx, workspace_snapshot_addr).await?;
+
+        Ok(Self::from_graph(workspace_snapshot_addr, snapshot))
+    }
+
+    async fn read_graph(
+        ctx: &DalContext,
+        workspace_snapshot_addr: WorkspaceSnapshotAddress,
+    ) -> WorkspaceSnapshotResult<Arc<WorkspaceSnapshotGraph>> {
+        match ctx
             .layer_db()
             .workspace_snapshot()
             .read_wait_for_memory(&workspace_snapshot_addr)
             .await
         {
-            Ok(snapshot) => snapshot.ok_or(
-                WorkspaceSnapshotError::WorkspaceSnapshotGraphMissing(workspace_snapshot_addr),
-            )?,
+            Ok(snapshot) => snapshot.ok_or(WorkspaceSnapshotError::WorkspaceSnapshotGraphMissing(
+                workspace_snapshot_addr,
+            )),
             Err(err) => match err {
-                LayerDbError::Postcard(_) => {
-                    return Err(WorkspaceSnapshotError::WorkspaceSnapshotNotMigrated(
-                        workspace_snapshot_addr,
-                    ));
-                }
-                err => Err(err)?,
+                LayerDbError::Postcard(_) => Err(
+                    WorkspaceSnapshotError::WorkspaceSnapshotNotMigrated(workspace_snapshot_addr),
+                ),
+                err => Err(err.into()),
             },
-        };
+        }
+    }
 
-        Ok(Self {
+    /// Rebuilds a snapshot stored as a delta by performing its batches on top of its checkpoint,
+    /// caching the result in memory for subsequent reads.
+    #[instrument(
+        name = "workspace_snapshot.rebuild_from_delta",
+        level = "debug",
+        skip_all,
+        fields(
+            si.workspace_snapshot.address = %delta.workspace_snapshot_address,
+            si.workspace_snapshot.checkpoint_address = %delta.checkpoint_address,
+            si.workspace_snapshot.delta_depth = delta.depth(),
+        )
+    )]
+    async fn rebuild_from_delta(
+        ctx: &DalContext,
+        delta: WorkspaceSnapshotDelta,
+    ) -> WorkspaceSnapshotResult<Self> {
+        let checkpoint = Self::read_graph(ctx, delta.checkpoint_address).await?;
+        let snapshot = Self::from_graph(delta.checkpoint_address, checkpoint);
+
+        for rebase_batch_address in &delta.rebase_batch_addresses {
+            let rebase_batch = ctx
+                .layer_db()
+                .rebase_batch()
+                .read(rebase_batch_address)
+                .await?
+                .ok_or(WorkspaceSnapshotError::RebaseBatchMissing(
+                    *rebase_batch_address,
+                ))?;
+
+            // Each batch is performed and cleaned up just as it was when the delta was written
+            snapshot.perform_updates(rebase_batch.updates()).await?;
+            snapshot.working_copy_mut().await.cleanup();
+        }
+
+        let graph = Arc::new(WorkspaceSnapshotGraph::V1(
+            snapshot.working_copy_mut().await.clone(),
+        ));
+        ctx.layer_db()
+            .workspace_snapshot()
+            .insert_into_memory(&delta.workspace_snapshot_address, graph.clone())
+            .await;
+
+        Ok(Self::from_graph(delta.workspace_snapshot_address, graph))
+    }
+
+    fn from_graph(
+        workspace_snapshot_addr: WorkspaceSnapshotAddress,
+        graph: Arc<WorkspaceSnapshotGraph>,
+    ) -> Self {
+        Self {
             address: Arc::new(RwLock::new(workspace_snapshot_addr)),
-            read_only_graph: snapshot,
+            read_only_graph: graph,
             working_copy: Arc::new(RwLock::new(None)),
             cycle_check: Arc::new(AtomicBool::new(false)),
             dvu_roots: Arc::new(Mutex::new(HashSet::new())),
-        })
+        }
     }
 
     pub async fn find_for_change_set(
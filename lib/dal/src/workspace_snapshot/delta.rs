@@ -0,0 +1,178 @@
+//! Workspace snapshots stored as a chain of deltas from a checkpoint.
+//!
+//! Rather than serializing the whole graph each time a [`RebaseBatch`](super::graph::RebaseBatch)
+//! is performed, a snapshot can be stored as the address of a checkpoint (a snapshot stored in
+//! full) and the ordered [`RebaseBatch`](super::graph::RebaseBatch)es performed on top of it. The
+//! graph is rebuilt from the checkpoint when read by an instance which doesn't have it in memory.
+
+use si_data_pg::PgRow;
+use si_events::{rebase_batch_address::RebaseBatchAddress, WorkspaceSnapshotAddress};
+
+use crate::DalContext;
+
+use super::{WorkspaceSnapshotError, WorkspaceSnapshotResult};
+
+/// The default number of writes between checkpoints, so the longest chain of deltas is one less.
+pub const DEFAULT_CHECKPOINT_INTERVAL: usize = 20;
+
+/// A workspace snapshot stored as a checkpoint plus the batches performed on top of it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkspaceSnapshotDelta {
+    pub workspace_snapshot_address: WorkspaceSnapshotAddress,
+    pub checkpoint_address: WorkspaceSnapshotAddress,
+    pub rebase_batch_addresses: Vec<RebaseBatchAddress>,
+}
+
+impl TryFrom<PgRow> for WorkspaceSnapshotDelta {
+    type Error = WorkspaceSnapshotError;
+
+    fn try_from(value: PgRow) -> Result<Self, Self::Error> {
+        Ok(Self {
+            workspace_snapshot_address: value.try_get("workspace_snapshot_address")?,
+            checkpoint_address: value.try_get("checkpoint_address")?,
+            rebase_batch_addresses: value.try_get("rebase_batch_addresses")?,
+        })
+    }
+}
+
+impl WorkspaceSnapshotDelta {
+    /// The delta of performing a batch on top of the snapshot at `base_address`, where `base` is
+    /// the delta the base snapshot is stored as, if any.
+    pub fn append(
+        base_address: WorkspaceSnapshotAddress,
+        base: Option<Self>,
+        rebase_batch_address: RebaseBatchAddress,
+    ) -> Self {
+        let (checkpoint_address, mut rebase_batch_addresses) = match base {
+            Some(base) => (base.checkpoint_address, base.rebase_batch_addresses),
+            None => (base_address, Vec::new()),
+        };
+        rebase_batch_addresses.push(rebase_batch_address);
+
+        // The address is derived from the base and the batch rather than the serialized graph,
+        // which is never serialized for a delta
+        let workspace_snapshot_address = WorkspaceSnapshotAddress::new(
+            format!("{base_address}:{rebase_batch_address}").as_bytes(),
+        );
+
+        Self {
+            workspace_snapshot_address,
+            checkpoint_address,
+            rebase_batch_addresses,
+        }
+    }
+
+    /// The number of batches performed on top of the checkpoint.
+    pub fn depth(&self) -> usize {
+        self.rebase_batch_addresses.len()
+    }
+
+    pub async fn find(
+        ctx: &DalContext,
+        workspace_snapshot_address: WorkspaceSnapshotAddress,
+    ) -> WorkspaceSnapshotResult<Option<Self>> {
+        let maybe_row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_opt(
+                "SELECT * FROM workspace_snapshot_deltas WHERE workspace_snapshot_address = $1",
+                &[&workspace_snapshot_address],
+            )
+            .await?;
+
+        maybe_row.map(TryInto::try_into).transpose()
+    }
+
+    pub async fn insert(&self, ctx: &DalContext) -> WorkspaceSnapshotResult<()> {
+        ctx.txns()
+            .await?
+            .pg()
+            .execute(
+                "INSERT INTO workspace_snapshot_deltas (workspace_snapshot_address, checkpoint_address, rebase_batch_addresses)
+                    VALUES ($1, $2, $3) ON CONFLICT (workspace_snapshot_address) DO NOTHING",
+                &[
+                    &self.workspace_snapshot_address,
+                    &self.checkpoint_address,
+                    &self.rebase_batch_addresses,
+                ],
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn delete(&self, ctx: &DalContext) -> WorkspaceSnapshotResult<()> {
+        ctx.txns()
+            .await?
+            .pg()
+            .execute(
+                "DELETE FROM workspace_snapshot_deltas WHERE workspace_snapshot_address = $1",
+                &[&self.workspace_snapshot_address],
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    /// Whether any delta is built on top of the checkpoint, in which case it must not be evicted.
+    pub async fn checkpoint_in_use(
+        ctx: &DalContext,
+        checkpoint_address: &WorkspaceSnapshotAddress,
+    ) -> WorkspaceSnapshotResult<bool> {
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "SELECT count(*) AS count FROM workspace_snapshot_deltas WHERE checkpoint_address = $1",
+                &[checkpoint_address],
+            )
+            .await?;
+
+        let count: i64 = row.try_get("count")?;
+        Ok(count > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn append_to_checkpoint_starts_chain() {
+        let checkpoint = WorkspaceSnapshotAddress::new(b"checkpoint");
+        let batch = RebaseBatchAddress::new(b"batch");
+
+        let delta = WorkspaceSnapshotDelta::append(checkpoint, None, batch);
+
+        assert_eq!(checkpoint, delta.checkpoint_address);
+        assert_eq!(vec![batch], delta.rebase_batch_addresses);
+        assert_ne!(checkpoint, delta.workspace_snapshot_address);
+    }
+
+    #[test]
+    fn append_to_delta_extends_chain() {
+        let checkpoint = WorkspaceSnapshotAddress::new(b"checkpoint");
+        let first_batch = RebaseBatchAddress::new(b"first");
+        let second_batch = RebaseBatchAddress::new(b"second");
+
+        let first = WorkspaceSnapshotDelta::append(checkpoint, None, first_batch);
+        let second = WorkspaceSnapshotDelta::append(
+            first.workspace_snapshot_address,
+            Some(first.clone()),
+            second_batch,
+        );
+
+        assert_eq!(checkpoint, second.checkpoint_address);
+        assert_eq!(
+            vec![first_batch, second_batch],
+            second.rebase_batch_addresses
+        );
+        assert_eq!(2, second.depth());
+        assert_ne!(
+            first.workspace_snapshot_address,
+            second.workspace_snapshot_address
+        );
+    }
+}
@@ -10,6 +10,7 @@ use ulid::Ulid;
 use crate::change_set::event::{
     ChangeSetActorPayload, ChangeSetAppliedPayload, ChangeSetMergeVotePayload,
 };
+use crate::component::drift::ResourceDriftDetectedPayload;
 use crate::component::{
     ComponentCreatedPayload, ComponentDeletedPayload, ComponentSetPositionPayload,
     ComponentUpdatedPayload, ComponentUpgradedPayload, ConnectionCreatedPayload,
@@ -61,7 +62,9 @@ pub enum WsEventError {
 pub type WsEventResult<T> = Result<T, WsEventError>;
 
 #[remain::sorted]
-#[derive(Deserialize, Serialize, Debug, Clone, Eq, PartialEq)]
+#[derive(
+    Deserialize, Serialize, Debug, Clone, Eq, PartialEq, strum::AsRefStr, strum::VariantNames,
+)]
 #[serde(tag = "kind", content = "data")]
 #[allow(clippy::large_enum_variant)]
 pub enum WsPayload {
@@ -99,6 +102,7 @@ pub enum WsPayload {
     InferredEdgeUpsert(InferredEdgeUpsertPayload),
     ModuleImported(ModuleImportedPayload),
     Online(OnlinePayload),
+    ResourceDriftDetected(ResourceDriftDetectedPayload),
     ResourceRefreshed(ComponentUpdatedPayload),
     SchemaVariantCloned(SchemaVariantClonedPayload),
     SchemaVariantCreated(frontend_types::SchemaVariant),
@@ -182,6 +186,15 @@ impl WsEvent {
         self.change_set_id
     }
 
+    pub fn payload(&self) -> &WsPayload {
+        &self.payload
+    }
+
+    /// The kind of the event's payload, as it is tagged when serialized.
+    pub fn kind(&self) -> &str {
+        self.payload.as_ref()
+    }
+
     fn workspace_subject(&self) -> String {
         format!("si.workspace_pk.{}.event", self.workspace_pk)
     }
@@ -7,6 +7,8 @@ use dal_test::helpers::ChangeSetTestHelpers;
 use dal_test::test;
 use pretty_assertions_sorted::assert_eq;
 
+mod refresh_schedule;
+
 #[test]
 async fn prototype_id(ctx: &mut DalContext) {
     let component = create_component_for_default_schema_name(ctx, "swifty", "shake it off")
@@ -0,0 +1,119 @@
+use dal::action::prototype::{ActionKind, ActionPrototype};
+use dal::action::refresh_schedule::RefreshSchedule;
+use dal::action::Action;
+use dal::component::resource::ResourceData;
+use dal::DalContext;
+use dal_test::helpers::{create_component_for_default_schema_name, ChangeSetTestHelpers};
+use dal_test::test;
+use pretty_assertions_sorted::assert_eq;
+use serde_json::json;
+use veritech_client::ResourceStatus;
+
+#[test]
+async fn claim_and_enqueue(ctx: &mut DalContext) {
+    let component = create_component_for_default_schema_name(ctx, "small odd lego", "lego")
+        .await
+        .expect("could not create component");
+    component
+        .set_resource(
+            ctx,
+            ResourceData::new(ResourceStatus::Ok, Some(json!({ "one": "actual" }))),
+        )
+        .await
+        .expect("could not set resource");
+    RefreshSchedule::upsert(ctx, true, 60)
+        .await
+        .expect("could not upsert refresh schedule");
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update snapshot to visibility");
+
+    let action_ids = RefreshSchedule::claim_and_enqueue(ctx)
+        .await
+        .expect("could not claim and enqueue")
+        .expect("schedule was not due");
+    assert_eq!(1, action_ids.len());
+
+    // The schedule has been claimed for this interval, so claiming again does nothing.
+    assert!(RefreshSchedule::claim_and_enqueue(ctx)
+        .await
+        .expect("could not claim and enqueue")
+        .is_none());
+
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update snapshot to visibility");
+
+    let action_id = action_ids[0];
+    assert_eq!(
+        Some(component.id()),
+        Action::component_id(ctx, action_id)
+            .await
+            .expect("could not get component id")
+    );
+    let prototype_id = Action::prototype_id(ctx, action_id)
+        .await
+        .expect("could not get prototype id");
+    assert_eq!(
+        ActionKind::Refresh,
+        ActionPrototype::get_by_id(ctx, prototype_id)
+            .await
+            .expect("could not get prototype")
+            .kind
+    );
+    assert!(RefreshSchedule::get_for_workspace(ctx)
+        .await
+        .expect("could not get refresh schedule")
+        .expect("no refresh schedule")
+        .last_enqueued_at
+        .is_some());
+}
+
+#[test]
+async fn rolled_back_claim_stays_due(ctx: &mut DalContext) {
+    let component = create_component_for_default_schema_name(ctx, "small odd lego", "lego")
+        .await
+        .expect("could not create component");
+    component
+        .set_resource(
+            ctx,
+            ResourceData::new(ResourceStatus::Ok, Some(json!({ "one": "actual" }))),
+        )
+        .await
+        .expect("could not set resource");
+    let schedule = RefreshSchedule::upsert(ctx, true, 60)
+        .await
+        .expect("could not upsert refresh schedule");
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update snapshot to visibility");
+
+    RefreshSchedule::claim_and_enqueue(ctx)
+        .await
+        .expect("could not claim and enqueue")
+        .expect("schedule was not due");
+
+    // Enqueueing failed before the commit: the claim goes away with the actions.
+    ctx.rollback().await.expect("could not roll back");
+
+    assert!(RefreshSchedule::list_due(ctx)
+        .await
+        .expect("could not list due schedules")
+        .contains(&schedule));
+    assert!(RefreshSchedule::claim_and_enqueue(ctx)
+        .await
+        .expect("could not claim and enqueue")
+        .is_some());
+}
+
+#[test]
+async fn disabled_schedule_is_not_claimed(ctx: &mut DalContext) {
+    RefreshSchedule::upsert(ctx, false, 60)
+        .await
+        .expect("could not upsert refresh schedule");
+
+    assert!(RefreshSchedule::claim(ctx)
+        .await
+        .expect("could not claim")
+        .is_none());
+}
@@ -16,6 +16,9 @@ mod debug;
 mod delete;
 mod get_code;
 mod get_diff;
+mod query;
+mod reconciliation;
+mod resource_import;
 mod set_type;
 mod upgrade;
 
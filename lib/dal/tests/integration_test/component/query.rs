@@ -0,0 +1,118 @@
+use dal::component::query::ComponentQuery;
+use dal::{Component, ComponentId, DalContext};
+use dal_test::helpers::{
+    create_component_for_default_schema_name, update_attribute_value_for_component,
+    ChangeSetTestHelpers,
+};
+use dal_test::test;
+use pretty_assertions_sorted::assert_eq;
+
+#[test]
+async fn labels(ctx: &mut DalContext) {
+    let component = create_component_for_default_schema_name(ctx, "starfield", "ceres")
+        .await
+        .expect("could not create component");
+    assert!(component.labels().is_empty());
+
+    let component = component
+        .set_label(ctx, "env", "prod")
+        .await
+        .expect("could not set label")
+        .set_label(ctx, "team", "constellation")
+        .await
+        .expect("could not set label");
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update snapshot to visibility");
+
+    let component = Component::get_by_id(ctx, component.id())
+        .await
+        .expect("could not get component");
+    assert_eq!(Some("prod"), component.label("env"));
+    assert_eq!(Some("constellation"), component.label("team"));
+
+    let component = component
+        .remove_label(ctx, "team")
+        .await
+        .expect("could not remove label");
+    assert_eq!(None, component.label("team"));
+    assert_eq!(1, component.labels().len());
+
+    assert!(component
+        .set_label(ctx, "not a valid key", "value")
+        .await
+        .is_err());
+}
+
+#[test]
+async fn list_matching(ctx: &mut DalContext) {
+    let prod = create_component_for_default_schema_name(ctx, "starfield", "new atlantis")
+        .await
+        .expect("could not create component")
+        .set_label(ctx, "env", "prod")
+        .await
+        .expect("could not set label");
+    let staging = create_component_for_default_schema_name(ctx, "starfield", "akila")
+        .await
+        .expect("could not create component")
+        .set_label(ctx, "env", "staging")
+        .await
+        .expect("could not set label");
+    let swifty = create_component_for_default_schema_name(ctx, "swifty", "shake it off")
+        .await
+        .expect("could not create component")
+        .set_label(ctx, "env", "prod")
+        .await
+        .expect("could not set label");
+
+    update_attribute_value_for_component(
+        ctx,
+        staging.id(),
+        &["root", "domain", "freestar"],
+        serde_json::json!("neon"),
+    )
+    .await
+    .expect("could not update attribute value");
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update snapshot to visibility");
+
+    let mut all_prod = vec![prod.id(), swifty.id()];
+    all_prod.sort();
+    assert_eq!(all_prod, matching_ids(ctx, r#"label.env = "prod""#).await);
+    assert_eq!(
+        vec![prod.id()],
+        matching_ids(ctx, r#"schema = "starfield" and label.env = "prod""#).await
+    );
+    assert_eq!(
+        vec![staging.id()],
+        matching_ids(ctx, r#"schema = "starfield" and /domain/freestar = "neon""#).await
+    );
+    assert_eq!(
+        vec![swifty.id()],
+        matching_ids(ctx, r#"name = "shake it off" or (label.env = "nope")"#).await
+    );
+    assert!(matching_ids(ctx, r#"has label.owner"#).await.is_empty());
+
+    let selected = ComponentQuery::select_component_ids(
+        ctx,
+        vec![staging.id()],
+        Some(
+            &ComponentQuery::parse(r#"label.env = "staging" or name = "new atlantis""#)
+                .expect("could not parse query"),
+        ),
+    )
+    .await
+    .expect("could not select component ids");
+    assert_eq!(vec![staging.id(), prod.id()], selected);
+}
+
+async fn matching_ids(ctx: &DalContext, source: &str) -> Vec<ComponentId> {
+    let mut ids = ComponentQuery::parse(source)
+        .expect("could not parse query")
+        .list_matching_ids(ctx)
+        .await
+        .expect("could not list matching components");
+    ids.sort();
+    ids
+}
@@ -0,0 +1,172 @@
+use base64::{engine::general_purpose, Engine};
+use dal::component::reconciliation::Reconciliation;
+use dal::component::resource::ResourceData;
+use dal::func::FuncKind;
+use dal::{
+    AttributeValue, Component, ComponentType, DalContext, Func, FuncBackendKind,
+    FuncBackendResponseType, SchemaVariant,
+};
+use dal_test::helpers::{
+    create_component_for_default_schema_name, create_schema, ChangeSetTestHelpers,
+};
+use dal_test::test;
+use pretty_assertions_sorted::assert_eq;
+use serde_json::json;
+use veritech_client::ResourceStatus;
+
+async fn create_reconciliation_func(ctx: &DalContext) -> Func {
+    let code = "async function main(diffs) {
+        const updates = {};
+        for (const diff of Object.values(diffs)) {
+            updates[diff.domain.id] = diff.resource;
+        }
+        return { updates, actions: [], message: \"adopt the resource values\" };
+    }";
+
+    Func::new(
+        ctx,
+        "test:reconcile",
+        None::<String>,
+        None::<String>,
+        None::<String>,
+        false,
+        false,
+        FuncBackendKind::JsReconciliation,
+        FuncBackendResponseType::Reconciliation,
+        Some("main"),
+        Some(general_purpose::STANDARD_NO_PAD.encode(code)),
+    )
+    .await
+    .expect("could not create func")
+}
+
+#[test]
+async fn propose_and_accept(ctx: &mut DalContext) {
+    let component = create_component_for_default_schema_name(ctx, "small odd lego", "lego")
+        .await
+        .expect("could not create component");
+    let schema_variant_id = Component::schema_variant_id(ctx, component.id())
+        .await
+        .expect("could not get schema variant id");
+
+    let func = create_reconciliation_func(ctx).await;
+    assert_eq!(FuncKind::Reconciliation, func.kind);
+    SchemaVariant::set_reconciliation_func(ctx, schema_variant_id, func.id)
+        .await
+        .expect("could not set reconciliation func");
+    assert_eq!(
+        Some(func.id),
+        SchemaVariant::find_reconciliation_func_id(ctx, schema_variant_id)
+            .await
+            .expect("could not find reconciliation func")
+    );
+
+    let one_av_id = component
+        .attribute_values_for_prop(ctx, &["root", "domain", "one"])
+        .await
+        .expect("could not find attribute values")
+        .pop()
+        .expect("no attribute value for domain one");
+    AttributeValue::update(ctx, one_av_id, Some(json!("modeled")))
+        .await
+        .expect("could not update attribute value");
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update snapshot to visibility");
+
+    // Without a resource, there is nothing to reconcile against.
+    assert!(Reconciliation::propose(ctx, component.id())
+        .await
+        .expect("could not propose reconciliation")
+        .is_none());
+
+    component
+        .set_resource(
+            ctx,
+            ResourceData::new(ResourceStatus::Ok, Some(json!({ "one": "actual" }))),
+        )
+        .await
+        .expect("could not set resource");
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update snapshot to visibility");
+
+    let proposal = Reconciliation::propose(ctx, component.id())
+        .await
+        .expect("could not propose reconciliation")
+        .expect("no proposal for drifted component");
+    assert_eq!(func.id, proposal.func_id);
+    assert_eq!(1, proposal.updates.len());
+    let update = &proposal.updates[0];
+    assert_eq!(one_av_id, update.attribute_value_id);
+    assert_eq!(Some(json!("modeled")), update.current);
+    assert_eq!(json!("actual"), update.proposed);
+
+    let action_ids = Reconciliation::accept(
+        ctx,
+        component.id(),
+        vec![(update.attribute_value_id, update.proposed.clone())],
+        proposal.actions.clone(),
+    )
+    .await
+    .expect("could not accept reconciliation");
+    assert!(action_ids.is_empty());
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update snapshot to visibility");
+
+    assert_eq!(
+        Some(json!("actual")),
+        AttributeValue::get_by_id_or_error(ctx, one_av_id)
+            .await
+            .expect("could not get attribute value")
+            .value(ctx)
+            .await
+            .expect("could not get value")
+    );
+    assert!(Reconciliation::propose(ctx, component.id())
+        .await
+        .expect("could not propose reconciliation")
+        .is_none());
+}
+
+#[test]
+async fn removing_external_connections_keeps_reconciliation_func(ctx: &DalContext) {
+    let schema = create_schema(ctx).await.expect("could not create schema");
+    let (variant, _) = SchemaVariant::new(
+        ctx,
+        schema.id(),
+        "v0",
+        "v0".to_string(),
+        "demo",
+        "#000000",
+        ComponentType::Component,
+        None,
+        None,
+        None,
+        false,
+    )
+    .await
+    .expect("could not create schema variant");
+
+    let func = create_reconciliation_func(ctx).await;
+    SchemaVariant::set_reconciliation_func(ctx, variant.id(), func.id)
+        .await
+        .expect("could not set reconciliation func");
+
+    variant
+        .remove_external_connections(ctx)
+        .await
+        .expect("could not remove external connections");
+
+    assert_eq!(
+        None,
+        SchemaVariant::find_reconciliation_func_id(ctx, variant.id())
+            .await
+            .expect("could not find reconciliation func")
+    );
+    let func = Func::get_by_id_or_error(ctx, func.id)
+        .await
+        .expect("reconciliation func was removed");
+    assert_eq!(FuncKind::Reconciliation, func.kind);
+}
@@ -0,0 +1,290 @@
+use base64::{engine::general_purpose, Engine};
+use dal::component::resource_import::{ResourceImport, ResourceImportError};
+use dal::func::FuncKind;
+use dal::{
+    AttributeValue, Component, ComponentType, DalContext, Func, FuncBackendKind,
+    FuncBackendResponseType, Schema, SchemaVariant, SchemaVariantId,
+};
+use dal_test::helpers::{create_schema, ChangeSetTestHelpers};
+use dal_test::test;
+use pretty_assertions_sorted::assert_eq;
+use serde_json::json;
+
+async fn create_import_func(ctx: &DalContext, code: &str) -> Func {
+    Func::new(
+        ctx,
+        "test:import",
+        None::<String>,
+        None::<String>,
+        None::<String>,
+        false,
+        false,
+        FuncBackendKind::JsAttribute,
+        FuncBackendResponseType::Object,
+        Some("main"),
+        Some(general_purpose::STANDARD_NO_PAD.encode(code)),
+    )
+    .await
+    .expect("could not create func")
+}
+
+async fn small_odd_lego_variant_id(ctx: &DalContext) -> SchemaVariantId {
+    let schema = Schema::find_by_name(ctx, "small odd lego")
+        .await
+        .expect("could not find schema")
+        .expect("schema not found");
+    SchemaVariant::get_default_id_for_schema(ctx, schema.id())
+        .await
+        .expect("could not get default schema variant id")
+}
+
+#[test]
+async fn import_adopts_resource(ctx: &mut DalContext) {
+    let schema_variant_id = small_odd_lego_variant_id(ctx).await;
+    let func = create_import_func(
+        ctx,
+        "async function main(input) {
+            return {
+                name: `lego ${input.resourceId}`,
+                payload: { one: \"actual\" },
+                domain: { one: \"actual\" },
+            };
+        }",
+    )
+    .await;
+    SchemaVariant::set_import_func(ctx, schema_variant_id, func.id)
+        .await
+        .expect("could not set import func");
+
+    let component_id = ResourceImport::import(ctx, schema_variant_id, "brick", None)
+        .await
+        .expect("could not import resource");
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update snapshot to visibility");
+
+    let component = Component::get_by_id(ctx, component_id)
+        .await
+        .expect("could not get component");
+    assert_eq!(
+        "lego brick",
+        component.name(ctx).await.expect("could not get name")
+    );
+    assert_eq!(
+        Some(json!({ "one": "actual" })),
+        component
+            .resource(ctx)
+            .await
+            .expect("could not get resource")
+            .and_then(|resource| resource.payload)
+    );
+
+    let one_av_id = component
+        .attribute_values_for_prop(ctx, &["root", "domain", "one"])
+        .await
+        .expect("could not find attribute values")
+        .pop()
+        .expect("no attribute value for domain one");
+    assert_eq!(
+        Some(json!("actual")),
+        AttributeValue::get_by_id_or_error(ctx, one_av_id)
+            .await
+            .expect("could not get attribute value")
+            .value(ctx)
+            .await
+            .expect("could not get value")
+    );
+}
+
+#[test]
+async fn failed_import_removes_component(ctx: &mut DalContext) {
+    let schema_variant_id = small_odd_lego_variant_id(ctx).await;
+    let func = create_import_func(
+        ctx,
+        "async function main(input) {
+            return { status: \"error\", message: `no such brick: ${input.resourceId}` };
+        }",
+    )
+    .await;
+    SchemaVariant::set_import_func(ctx, schema_variant_id, func.id)
+        .await
+        .expect("could not set import func");
+
+    let components_before = Component::list(ctx)
+        .await
+        .expect("could not list components")
+        .len();
+
+    let err = ResourceImport::import(ctx, schema_variant_id, "brick", None)
+        .await
+        .expect_err("importing should have failed");
+    assert!(matches!(
+        err,
+        ResourceImportError::ImportFuncFailed(id, ref message)
+            if id == schema_variant_id && message == "no such brick: brick"
+    ));
+
+    let outcomes = ResourceImport::import_many(
+        ctx,
+        schema_variant_id,
+        vec!["brick".to_string(), "block".to_string()],
+        None,
+    )
+    .await
+    .expect("could not import resources");
+    assert_eq!(2, outcomes.len());
+    assert!(outcomes
+        .iter()
+        .all(|outcome| outcome.component_id.is_none() && outcome.error.is_some()));
+
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update snapshot to visibility");
+    assert_eq!(
+        components_before,
+        Component::list(ctx)
+            .await
+            .expect("could not list components")
+            .len()
+    );
+}
+
+#[test]
+async fn import_many_imports_each_resource_on_its_own(ctx: &mut DalContext) {
+    let schema_variant_id = small_odd_lego_variant_id(ctx).await;
+    let func = create_import_func(
+        ctx,
+        "async function main(input) {
+            if (input.resourceId === \"block\") {
+                return { status: \"error\", message: \"no such block\" };
+            }
+            return { payload: { one: input.resourceId } };
+        }",
+    )
+    .await;
+    SchemaVariant::set_import_func(ctx, schema_variant_id, func.id)
+        .await
+        .expect("could not set import func");
+
+    let components_before = Component::list(ctx)
+        .await
+        .expect("could not list components")
+        .len();
+
+    let outcomes = ResourceImport::import_many(
+        ctx,
+        schema_variant_id,
+        vec![
+            "brick".to_string(),
+            "block".to_string(),
+            "plate".to_string(),
+        ],
+        None,
+    )
+    .await
+    .expect("could not import resources");
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update snapshot to visibility");
+
+    assert_eq!(
+        vec!["brick", "block", "plate"],
+        outcomes
+            .iter()
+            .map(|outcome| outcome.resource_id.as_str())
+            .collect::<Vec<_>>()
+    );
+    assert!(outcomes[1].component_id.is_none());
+    assert!(outcomes[1]
+        .error
+        .as_deref()
+        .is_some_and(|error| error.contains("no such block")));
+
+    for outcome in [&outcomes[0], &outcomes[2]] {
+        assert_eq!(None, outcome.error);
+        let component = Component::get_by_id(
+            ctx,
+            outcome
+                .component_id
+                .expect("resource should have been imported"),
+        )
+        .await
+        .expect("could not get component");
+        assert_eq!(
+            Some(json!({ "one": outcome.resource_id })),
+            component
+                .resource(ctx)
+                .await
+                .expect("could not get resource")
+                .and_then(|resource| resource.payload)
+        );
+    }
+    assert_eq!(
+        components_before + 2,
+        Component::list(ctx)
+            .await
+            .expect("could not list components")
+            .len()
+    );
+}
+
+#[test]
+async fn import_func_is_found_by_kind(ctx: &DalContext) {
+    let schema = create_schema(ctx).await.expect("could not create schema");
+    let (variant, _) = SchemaVariant::new(
+        ctx,
+        schema.id(),
+        "v0",
+        "v0".to_string(),
+        "demo",
+        "#000000",
+        ComponentType::Component,
+        None,
+        None,
+        None,
+        false,
+    )
+    .await
+    .expect("could not create schema variant");
+
+    let func = create_import_func(ctx, "async function main() { return {}; }").await;
+    assert_eq!(FuncKind::Attribute, func.kind);
+    assert_eq!(
+        None,
+        SchemaVariant::find_import_func_id(ctx, variant.id())
+            .await
+            .expect("could not find import func")
+    );
+
+    SchemaVariant::set_import_func(ctx, variant.id(), func.id)
+        .await
+        .expect("could not set import func");
+    assert_eq!(
+        Some(func.id),
+        SchemaVariant::find_import_func_id(ctx, variant.id())
+            .await
+            .expect("could not find import func")
+    );
+    assert_eq!(
+        FuncKind::Import,
+        Func::get_by_id_or_error(ctx, func.id)
+            .await
+            .expect("could not get func")
+            .kind
+    );
+
+    // Import funcs can be shared with other variants, so they outlive the connection to this one.
+    variant
+        .remove_external_connections(ctx)
+        .await
+        .expect("could not remove external connections");
+    assert_eq!(
+        None,
+        SchemaVariant::find_import_func_id(ctx, variant.id())
+            .await
+            .expect("could not find import func")
+    );
+    Func::get_by_id_or_error(ctx, func.id)
+        .await
+        .expect("import func was removed");
+}
@@ -17,6 +17,7 @@ mod create_func;
 mod func_argument;
 mod save_and_exec;
 mod save_func;
+mod test_case;
 mod test_execute;
 
 #[test]
@@ -52,7 +53,8 @@ async fn create_unlocked_copy_code_gen_func(ctx: &mut DalContext) {
     let new_display_name = Some("woo hoo".to_string());
 
     // try and change something, this fails because the function is locked on import!
-    let res = FuncAuthoringClient::update_func(ctx, func_id, new_display_name, None).await;
+    let res =
+        FuncAuthoringClient::update_func(ctx, func_id, new_display_name, None, None, None).await;
 
     assert!(res.is_err());
 
@@ -165,7 +167,8 @@ async fn create_unlocked_copy_attribute_func(ctx: &mut DalContext) {
 
     let new_display_name = Some("woo hoo".to_string());
 
-    let res = FuncAuthoringClient::update_func(ctx, func_id, new_display_name, None).await;
+    let res =
+        FuncAuthoringClient::update_func(ctx, func_id, new_display_name, None, None, None).await;
 
     assert!(res.is_err());
 
@@ -271,7 +274,8 @@ async fn create_unlocked_copy_auth_func(ctx: &mut DalContext) {
     let new_display_name = Some("woo hoo".to_string());
 
     // try and change something, this fails because the function is locked on import!
-    let res = FuncAuthoringClient::update_func(ctx, func_id, new_display_name, None).await;
+    let res =
+        FuncAuthoringClient::update_func(ctx, func_id, new_display_name, None, None, None).await;
 
     assert!(res.is_err());
     // create an unlocked copy
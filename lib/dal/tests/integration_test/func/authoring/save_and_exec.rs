@@ -18,9 +18,16 @@ async fn save_and_exec_action_func(ctx: &mut DalContext) {
     let new_func = FuncAuthoringClient::create_unlocked_func_copy(ctx, func_id, None)
         .await
         .expect("could not create unlocked copy");
-    FuncAuthoringClient::update_func(ctx, new_func.id, Some("woo hoo".to_string()), None)
-        .await
-        .expect("could not update func");
+    FuncAuthoringClient::update_func(
+        ctx,
+        new_func.id,
+        Some("woo hoo".to_string()),
+        None,
+        None,
+        None,
+    )
+    .await
+    .expect("could not update func");
 
     FuncAuthoringClient::execute_func(ctx, new_func.id)
         .await
@@ -47,9 +54,16 @@ async fn save_and_exec_attribute_func(ctx: &mut DalContext) {
     let new_func = FuncAuthoringClient::create_unlocked_func_copy(ctx, func_id, None)
         .await
         .expect("could not create unlocked copy");
-    FuncAuthoringClient::update_func(ctx, new_func.id, Some("woo hoo".to_string()), None)
-        .await
-        .expect("could not update func");
+    FuncAuthoringClient::update_func(
+        ctx,
+        new_func.id,
+        Some("woo hoo".to_string()),
+        None,
+        None,
+        None,
+    )
+    .await
+    .expect("could not update func");
 
     FuncAuthoringClient::execute_func(ctx, new_func.id)
         .await
@@ -29,6 +29,43 @@ async fn qualification(ctx: &mut DalContext) {
         save_func_setup(ctx, "test:qualificationDummySecretStringIsTodd").await;
 }
 
+#[test]
+async fn rename_keeps_timeout_and_purity(ctx: &mut DalContext) {
+    let old_func_id = Func::find_id_by_name(ctx, "test:createActionStarfield")
+        .await
+        .expect("could not perform find func by name")
+        .expect("no func found");
+    let func_id = FuncAuthoringClient::create_unlocked_func_copy(ctx, old_func_id, None)
+        .await
+        .expect("could not create unlocked copy")
+        .id;
+
+    FuncAuthoringClient::update_func(ctx, func_id, None, None, Some(42), Some(true))
+        .await
+        .expect("could not set timeout and purity");
+    FuncAuthoringClient::update_func(
+        ctx,
+        func_id,
+        Some("starfield creator".to_string()),
+        Some("creates a starfield".to_string()),
+        None,
+        None,
+    )
+    .await
+    .expect("could not rename func");
+
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update snapshot to visibility");
+
+    let func = Func::get_by_id_or_error(ctx, func_id)
+        .await
+        .expect("could not get func by id");
+    assert_eq!(Some("starfield creator"), func.display_name.as_deref());
+    assert_eq!(Some(42), func.timeout_secs);
+    assert!(func.is_pure);
+}
+
 // Sets up the tests within the module. Find the func to be saved by name and then save it
 // immediately when found. This is the basic "does it work in place" check.
 pub async fn save_func_setup(
@@ -51,7 +88,7 @@ pub async fn save_func_setup(
         .await
         .expect("could not assemble func view");
 
-    FuncAuthoringClient::update_func(ctx, func_id, Some("woo hoo".to_string()), None)
+    FuncAuthoringClient::update_func(ctx, func_id, Some("woo hoo".to_string()), None, None, None)
         .await
         .expect("could not save func");
 
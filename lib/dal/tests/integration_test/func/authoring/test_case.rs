@@ -0,0 +1,85 @@
+use base64::{engine::general_purpose, Engine};
+use dal::func::authoring::{FuncAuthoringClient, FuncAuthoringError};
+use dal::func::test_case::{FuncTestCase, FuncTestCaseExpectation, FuncTestCaseInput};
+use dal::{DalContext, Func, FuncBackendKind, FuncBackendResponseType, FuncError};
+use dal_test::test;
+use pretty_assertions_sorted::assert_eq;
+use serde_json::json;
+
+fn test_case(name: &str, expected: serde_json::Value) -> FuncTestCase {
+    FuncTestCase {
+        name: name.to_string(),
+        input: FuncTestCaseInput::Args(json!({ "name": "ringo" })),
+        expectation: FuncTestCaseExpectation::Equals(expected),
+    }
+}
+
+#[test]
+async fn set_and_remove_test_cases(ctx: &DalContext) {
+    let func = Func::new(
+        ctx,
+        "test:testCases",
+        None::<String>,
+        None::<String>,
+        None::<String>,
+        false,
+        false,
+        FuncBackendKind::JsAttribute,
+        FuncBackendResponseType::String,
+        Some("main"),
+        Some(
+            general_purpose::STANDARD_NO_PAD
+                .encode("async function main(input) { return input.name; }"),
+        ),
+    )
+    .await
+    .expect("could not create func");
+
+    FuncAuthoringClient::set_test_case(ctx, func.id, test_case("drummer", json!("paul")))
+        .await
+        .expect("could not set test case");
+    let func =
+        FuncAuthoringClient::set_test_case(ctx, func.id, test_case("drummer", json!("ringo")))
+            .await
+            .expect("could not replace test case");
+    assert_eq!(vec![test_case("drummer", json!("ringo"))], func.test_cases);
+
+    let func = FuncAuthoringClient::remove_test_case(ctx, func.id, "drummer".to_string())
+        .await
+        .expect("could not remove test case");
+    assert!(func.test_cases.is_empty());
+
+    let result = FuncAuthoringClient::remove_test_case(ctx, func.id, "drummer".to_string()).await;
+    assert!(matches!(
+        result,
+        Err(FuncAuthoringError::FuncTestCaseNotFound(func_id, _)) if func_id == func.id
+    ));
+}
+
+#[test]
+async fn locked_funcs_keep_their_test_cases(ctx: &DalContext) {
+    let func_id = Func::find_id_by_name(ctx, "test:generateStringCode")
+        .await
+        .expect("could not find func")
+        .expect("func not found");
+    let func = Func::get_by_id_or_error(ctx, func_id)
+        .await
+        .expect("could not get func");
+    assert!(func.is_locked);
+
+    let result =
+        FuncAuthoringClient::set_test_case(ctx, func_id, test_case("drummer", json!("ringo")))
+            .await;
+    assert!(matches!(
+        result,
+        Err(FuncAuthoringError::Func(FuncError::FuncLocked(id))) if id == func_id
+    ));
+
+    // Removing a test case from a locked func fails because it is locked, whether or not the
+    // test case exists
+    let result = FuncAuthoringClient::remove_test_case(ctx, func_id, "drummer".to_string()).await;
+    assert!(matches!(
+        result,
+        Err(FuncAuthoringError::Func(FuncError::FuncLocked(id))) if id == func_id
+    ));
+}
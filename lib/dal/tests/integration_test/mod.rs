@@ -19,4 +19,6 @@ mod rebaser;
 mod schema;
 mod secret;
 mod validations;
+mod webhook;
 mod workspace;
+mod workspace_snapshot;
@@ -0,0 +1,88 @@
+use dal::webhook::delivery::MAX_ATTEMPTS;
+use dal::{
+    DalContext, WebhookDelivery, WebhookDeliveryPk, WebhookDeliveryStatus, WebhookSubscription,
+};
+use dal_test::test;
+use pretty_assertions_sorted::assert_eq;
+
+const EVENT_KIND: &str = "ChangeSetApplied";
+
+#[test]
+async fn failed_deliveries_are_retried_until_out_of_attempts(ctx: &mut DalContext) {
+    let delivery = enqueue(ctx).await;
+    assert_eq!(WebhookDeliveryStatus::Pending, delivery.status());
+    assert_eq!(0, delivery.attempts());
+
+    let mut claimed = claim(ctx, delivery.pk())
+        .await
+        .expect("enqueued delivery should be due");
+    // A claimed delivery is left to its dispatcher rather than claimed again straight away
+    assert!(claim(ctx, delivery.pk()).await.is_none());
+
+    claimed
+        .record_failure(ctx, Some(500), "unexpected response status: 500")
+        .await
+        .expect("could not record failure");
+    assert_eq!(WebhookDeliveryStatus::Pending, claimed.status());
+    assert_eq!(1, claimed.attempts());
+    // Retries back off rather than being due straight away
+    assert!(claim(ctx, delivery.pk()).await.is_none());
+
+    for _ in 1..MAX_ATTEMPTS {
+        claimed
+            .record_failure(ctx, None, "connection refused")
+            .await
+            .expect("could not record failure");
+    }
+    assert_eq!(WebhookDeliveryStatus::Failed, claimed.status());
+    assert_eq!(MAX_ATTEMPTS, claimed.attempts());
+}
+
+#[test]
+async fn delivered_deliveries_are_not_claimed_again(ctx: &mut DalContext) {
+    let delivery = enqueue(ctx).await;
+
+    let mut claimed = claim(ctx, delivery.pk())
+        .await
+        .expect("enqueued delivery should be due");
+    claimed
+        .record_success(ctx, 204)
+        .await
+        .expect("could not record success");
+    assert_eq!(WebhookDeliveryStatus::Succeeded, claimed.status());
+    assert_eq!(1, claimed.attempts());
+
+    let deliveries = WebhookDelivery::list_for_subscription(ctx, claimed.subscription_pk(), 10)
+        .await
+        .expect("could not list deliveries");
+    assert_eq!(vec![claimed], deliveries);
+}
+
+async fn enqueue(ctx: &DalContext) -> WebhookDelivery {
+    let subscription = WebhookSubscription::new(
+        ctx,
+        "https://example.com/hooks",
+        vec![EVENT_KIND.to_owned()],
+    )
+    .await
+    .expect("could not create subscription");
+
+    WebhookDelivery::enqueue(
+        ctx,
+        subscription.pk(),
+        subscription.workspace_pk(),
+        EVENT_KIND,
+        serde_json::json!({ "kind": EVENT_KIND }),
+    )
+    .await
+    .expect("could not enqueue delivery")
+}
+
+// Claims every due delivery, as others may be due too, returning the one asked for if it was.
+async fn claim(ctx: &DalContext, pk: WebhookDeliveryPk) -> Option<WebhookDelivery> {
+    WebhookDelivery::claim_due(ctx, 1000)
+        .await
+        .expect("could not claim due deliveries")
+        .into_iter()
+        .find(|delivery| delivery.pk() == pk)
+}
@@ -0,0 +1,120 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use dal::workspace_snapshot::delta::{WorkspaceSnapshotDelta, DEFAULT_CHECKPOINT_INTERVAL};
+use dal::{DalContext, WorkspaceSnapshot};
+use dal_test::helpers::create_component_for_default_schema_name;
+use dal_test::test;
+use pretty_assertions_sorted::assert_eq;
+use si_events::WorkspaceSnapshotAddress;
+
+/// Performs the changes made in the context on top of the snapshot, writing it as a delta just as
+/// the rebaser does.
+async fn write_changes_as_delta(
+    ctx: &DalContext,
+    snapshot: &Arc<WorkspaceSnapshot>,
+) -> WorkspaceSnapshotAddress {
+    let rebase_batch = WorkspaceSnapshot::calculate_rebase_batch(
+        snapshot.clone(),
+        ctx.workspace_snapshot().expect("could not get snapshot"),
+    )
+    .await
+    .expect("could not calculate rebase batch")
+    .expect("no changes to write");
+    let rebase_batch_address = ctx
+        .write_rebase_batch(rebase_batch.clone())
+        .await
+        .expect("could not write rebase batch");
+
+    snapshot
+        .perform_updates(rebase_batch.updates())
+        .await
+        .expect("could not perform updates");
+    snapshot
+        .write_delta(ctx, rebase_batch_address, DEFAULT_CHECKPOINT_INTERVAL)
+        .await
+        .expect("could not write delta")
+}
+
+/// The nodes and edges of the snapshot, keyed by id so that node indexes don't matter.
+async fn graph_contents(
+    snapshot: &WorkspaceSnapshot,
+) -> (BTreeMap<String, String>, Vec<(String, String, String)>) {
+    let mut ids = BTreeMap::new();
+    let mut nodes = BTreeMap::new();
+    for (weight, index) in snapshot.nodes().await.expect("could not get nodes") {
+        ids.insert(index, weight.id().to_string());
+        nodes.insert(weight.id().to_string(), format!("{weight:?}"));
+    }
+
+    let mut edges: Vec<_> = snapshot
+        .edges()
+        .await
+        .expect("could not get edges")
+        .into_iter()
+        .map(|(weight, from, to)| (ids[&from].clone(), ids[&to].clone(), format!("{weight:?}")))
+        .collect();
+    edges.sort();
+
+    (nodes, edges)
+}
+
+#[test]
+async fn rebuilt_from_delta_matches_original(ctx: &mut DalContext) {
+    // Write the current snapshot in full so that it is the checkpoint for the deltas below
+    let snapshot = Arc::new(
+        WorkspaceSnapshot::find(
+            ctx,
+            ctx.workspace_snapshot()
+                .expect("could not get snapshot")
+                .id()
+                .await,
+        )
+        .await
+        .expect("could not find snapshot"),
+    );
+    let checkpoint_address = snapshot.write(ctx).await.expect("could not write snapshot");
+
+    create_component_for_default_schema_name(ctx, "small odd lego", "first")
+        .await
+        .expect("could not create component");
+    write_changes_as_delta(ctx, &snapshot).await;
+    create_component_for_default_schema_name(ctx, "small even lego", "second")
+        .await
+        .expect("could not create component");
+    let delta_address = write_changes_as_delta(ctx, &snapshot).await;
+
+    let delta = WorkspaceSnapshotDelta::find(ctx, delta_address)
+        .await
+        .expect("could not find delta")
+        .expect("snapshot was not written as a delta");
+    assert_eq!(checkpoint_address, delta.checkpoint_address);
+    assert_eq!(2, delta.depth());
+
+    // Drop the in-memory copy so that finding the snapshot rebuilds it from the checkpoint
+    ctx.layer_db()
+        .workspace_snapshot()
+        .evict(&delta_address, ctx.events_tenancy(), ctx.events_actor())
+        .await
+        .expect("could not evict snapshot");
+    assert!(ctx
+        .layer_db()
+        .workspace_snapshot()
+        .read_from_memory(&delta_address)
+        .await
+        .is_none());
+
+    let rebuilt = WorkspaceSnapshot::find(ctx, delta_address)
+        .await
+        .expect("could not rebuild snapshot");
+    assert_eq!(delta_address, rebuilt.id().await);
+    assert_eq!(
+        graph_contents(&snapshot).await,
+        graph_contents(&rebuilt).await
+    );
+    assert!(snapshot
+        .detect_updates(&rebuilt)
+        .await
+        .expect("could not detect updates")
+        .is_empty());
+}
@@ -70,3 +70,86 @@ impl error::Error for StringRejection {
         }
     }
 }
+
+#[derive(Debug)]
+pub struct InvalidJson(Error);
+
+impl InvalidJson {
+    pub(crate) fn from_err<E>(err: E) -> Self
+    where
+        E: Into<BoxError>,
+    {
+        Self(Error::new(err))
+    }
+}
+
+impl IntoResponse for InvalidJson {
+    fn into_response(self) -> Response {
+        // A malformed payload will never deserialize, no matter how often it is retried
+        Response::client_error()
+    }
+}
+
+impl fmt::Display for InvalidJson {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "failed to deserialize message payload as JSON: {:?}",
+            self.0
+        )
+    }
+}
+
+impl error::Error for InvalidJson {}
+
+#[derive(Debug)]
+pub enum JsonRejection {
+    InvalidJson(InvalidJson),
+}
+
+impl IntoResponse for JsonRejection {
+    fn into_response(self) -> Response {
+        match self {
+            JsonRejection::InvalidJson(inner) => inner.into_response(),
+        }
+    }
+}
+
+impl From<InvalidJson> for JsonRejection {
+    fn from(value: InvalidJson) -> Self {
+        Self::InvalidJson(value)
+    }
+}
+
+impl fmt::Display for JsonRejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidJson(inner) => write!(f, "{inner}"),
+        }
+    }
+}
+
+impl error::Error for JsonRejection {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::InvalidJson(inner) => inner.source(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct MissingReplySubject;
+
+impl IntoResponse for MissingReplySubject {
+    fn into_response(self) -> Response {
+        Response::client_error()
+    }
+}
+
+impl fmt::Display for MissingReplySubject {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "message has no reply subject")
+    }
+}
+
+impl error::Error for MissingReplySubject {}
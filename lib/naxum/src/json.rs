@@ -0,0 +1,72 @@
+use std::ops;
+
+use async_trait::async_trait;
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{
+    extract::{
+        rejection::{InvalidJson, JsonRejection},
+        FromMessage,
+    },
+    response::{IntoResponse, Response},
+    MessageHead,
+};
+
+/// JSON extractor and response.
+///
+/// As an extractor, deserializes the message payload into `T`, rejecting the message as a client
+/// error if it can't be deserialized. As a response, serializes `T` into the body of the reply.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Json<T>(pub T);
+
+#[async_trait]
+impl<S, R, T> FromMessage<S, R> for Json<T>
+where
+    S: Send + Sync,
+    R: MessageHead + Send + 'static,
+    T: DeserializeOwned,
+{
+    type Rejection = JsonRejection;
+
+    async fn from_message(req: R, _state: &S) -> Result<Self, Self::Rejection> {
+        let (_head, payload) = req.into_parts();
+        let value = serde_json::from_slice(&payload).map_err(InvalidJson::from_err)?;
+
+        Ok(Self(value))
+    }
+}
+
+impl<T> IntoResponse for Json<T>
+where
+    T: Serialize,
+{
+    fn into_response(self) -> Response {
+        match serde_json::to_vec(&self.0) {
+            Ok(body) => body.into_response(),
+            Err(err) => {
+                tracing::error!(error = ?err, "failed to serialize response as JSON");
+                Response::server_error()
+            }
+        }
+    }
+}
+
+impl<T> From<T> for Json<T> {
+    fn from(value: T) -> Self {
+        Self(value)
+    }
+}
+
+impl<T> ops::Deref for Json<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> ops::DerefMut for Json<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
@@ -1,5 +1,7 @@
 pub mod ack;
+pub mod dead_letter;
 pub mod delay;
+pub mod reply;
 pub mod trace;
 
 #[non_exhaustive]
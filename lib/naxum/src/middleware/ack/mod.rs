@@ -1,10 +1,13 @@
 mod future;
 mod layer;
-mod maintain_progress;
+pub(crate) mod maintain_progress;
 mod on_failure;
 mod on_success;
 mod service;
 
 pub use self::{
-    layer::AckLayer, on_failure::DefaultOnFailure, on_success::DefaultOnSuccess, service::Ack,
+    layer::AckLayer,
+    on_failure::{DefaultOnFailure, OnFailure},
+    on_success::{DefaultOnSuccess, OnSuccess},
+    service::Ack,
 };
@@ -0,0 +1,36 @@
+use std::time::Duration;
+
+/// How long to wait before redelivering a message which failed to process.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Backoff {
+    /// Waits the same amount of time before every redelivery.
+    Constant(Duration),
+    /// Doubles the wait before each redelivery, starting from `initial` and never going over
+    /// `max`.
+    Exponential { initial: Duration, max: Duration },
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Self::Exponential {
+            initial: Duration::from_secs(1),
+            max: Duration::from_secs(5 * 60),
+        }
+    }
+}
+
+impl Backoff {
+    /// Returns how long to wait before redelivering a message which has been delivered the given
+    /// number of times.
+    pub fn delay(&self, delivered: u64) -> Duration {
+        match self {
+            Self::Constant(delay) => *delay,
+            Self::Exponential { initial, max } => {
+                let exponent = u32::try_from(delivered.saturating_sub(1)).unwrap_or(u32::MAX);
+                initial
+                    .saturating_mul(2_u32.saturating_pow(exponent))
+                    .min(*max)
+            }
+        }
+    }
+}
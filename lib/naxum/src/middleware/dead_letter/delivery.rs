@@ -0,0 +1,72 @@
+/// The JetStream metadata of a delivered message, as encoded in its reply (i.e. ack) subject.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DeliveryInfo {
+    /// The stream the message was consumed from.
+    pub stream: String,
+    /// The consumer the message was delivered by.
+    pub consumer: String,
+    /// How many times the message has been delivered, counting this delivery.
+    pub delivered: u64,
+    /// The sequence of the message in its stream.
+    pub stream_sequence: u64,
+}
+
+impl DeliveryInfo {
+    /// Parses the delivery info from a JetStream ack subject, returning `None` if the subject is
+    /// not one.
+    ///
+    /// The ack subject is either
+    /// `$JS.ACK.<stream>.<consumer>.<delivered>.<stream_seq>.<consumer_seq>.<timestamp>.<pending>`
+    /// or, in its newer form, the same with a domain and account hash before the stream name and
+    /// a random token at the end.
+    pub fn from_reply(reply: &str) -> Option<Self> {
+        let tokens: Vec<_> = reply.split('.').collect();
+        if tokens.first() != Some(&"$JS") || tokens.get(1) != Some(&"ACK") {
+            return None;
+        }
+
+        let offset = match tokens.len() {
+            9 => 2,
+            len if len >= 12 => 4,
+            _ => return None,
+        };
+
+        Some(Self {
+            stream: tokens[offset].to_owned(),
+            consumer: tokens[offset + 1].to_owned(),
+            delivered: tokens[offset + 2].parse().ok()?,
+            stream_sequence: tokens[offset + 3].parse().ok()?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_reply() {
+        assert_eq!(
+            Some(DeliveryInfo {
+                stream: "JOBS".to_owned(),
+                consumer: "workers".to_owned(),
+                delivered: 3,
+                stream_sequence: 120,
+            }),
+            DeliveryInfo::from_reply("$JS.ACK.JOBS.workers.3.120.118.1718000000000000000.0")
+        );
+        assert_eq!(
+            Some(DeliveryInfo {
+                stream: "JOBS".to_owned(),
+                consumer: "workers".to_owned(),
+                delivered: 2,
+                stream_sequence: 121,
+            }),
+            DeliveryInfo::from_reply(
+                "$JS.ACK.hub.ACCHASH.JOBS.workers.2.121.119.1718000000000000000.0.token"
+            )
+        );
+        assert_eq!(None, DeliveryInfo::from_reply("_INBOX.abc123"));
+        assert_eq!(None, DeliveryInfo::from_reply("$JS.ACK.JOBS.workers"));
+    }
+}
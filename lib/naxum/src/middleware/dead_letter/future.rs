@@ -0,0 +1,228 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use async_nats::{
+    jetstream::{self, message::Acker},
+    Subject,
+};
+use bytes::Bytes;
+use futures::future::BoxFuture;
+use pin_project_lite::pin_project;
+use tokio_util::sync::DropGuard;
+use tower::Service;
+use tracing::{trace, warn};
+
+use crate::{response::Response, Head};
+
+use super::{
+    backoff::Backoff, delivery::DeliveryInfo, on_dead_letter::OnDeadLetter,
+    NATS_HEADER_DEAD_LETTER_REASON, NATS_HEADER_DELIVERED_COUNT, NATS_HEADER_ORIGINAL_SEQUENCE,
+    NATS_HEADER_ORIGINAL_STREAM, NATS_HEADER_ORIGINAL_SUBJECT,
+};
+
+pin_project! {
+    pub struct ResponseFuture<S, OnDeadLetter>
+    where
+        S: Service<async_nats::Message>,
+    {
+        #[pin]
+        pub(crate) inner: Option<S::Future>,
+        pub(crate) delivery: Option<Delivery<OnDeadLetter>>,
+        pub(crate) finish: Option<BoxFuture<'static, ()>>,
+        pub(crate) result: Option<Result<S::Response, S::Error>>,
+        pub(crate) shutdown_guard: Option<DropGuard>,
+    }
+}
+
+impl<S, OnDeadLetterT> Future for ResponseFuture<S, OnDeadLetterT>
+where
+    S: Service<async_nats::Message, Response = Response>,
+    OnDeadLetterT: OnDeadLetter + Send + 'static,
+{
+    type Output = Result<S::Response, S::Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut this = self.project();
+
+        loop {
+            // Poll the finishing (i.e. acking) future and when ready return the inner result
+            if let Some(finish) = this.finish.as_mut() {
+                futures::ready!(finish.as_mut().poll(cx));
+                return Poll::Ready(
+                    this.result
+                        .take()
+                        .expect("extracting owned value only happens once"),
+                );
+            }
+
+            // Poll the nested service to yield our result
+            let inner = this
+                .inner
+                .as_mut()
+                .as_pin_mut()
+                .expect("inner future is only unset when finishing from the start");
+            let result = futures::ready!(inner.poll(cx));
+
+            // Cancel the associated `MaintainProgressTask`, as in the ack middleware
+            if let Some(shutdown_guard) = this.shutdown_guard.take() {
+                shutdown_guard.disarm().cancel();
+            }
+
+            let outcome = match &result {
+                Ok(response) if response.status().is_server_error() => {
+                    Outcome::Retry(response.retry_delay())
+                }
+                Ok(response) if response.status().is_client_error() => {
+                    Outcome::DeadLetter(format!(
+                        "handler responded with status {}",
+                        response.status().as_u16()
+                    ))
+                }
+                Ok(_) => Outcome::Ack,
+                Err(_) => Outcome::Retry(None),
+            };
+
+            let delivery = this
+                .delivery
+                .take()
+                .expect("extracting owned value only happens once");
+            *this.finish = Some(delivery.finish(outcome));
+            *this.result = Some(result);
+        }
+    }
+}
+
+pub(crate) enum Outcome {
+    Ack,
+    /// Redelivers the message after the given delay, or after the backoff when there is none.
+    Retry(Option<Duration>),
+    DeadLetter(String),
+}
+
+/// Everything needed to settle a delivered message once it has been processed.
+pub(crate) struct Delivery<OnDeadLetter> {
+    pub(crate) head: Arc<Head>,
+    pub(crate) payload: Bytes,
+    pub(crate) info: Option<DeliveryInfo>,
+    pub(crate) acker: Arc<Acker>,
+    pub(crate) context: jetstream::Context,
+    pub(crate) dead_letter_subject: Subject,
+    pub(crate) max_deliveries: u64,
+    pub(crate) backoff: Backoff,
+    pub(crate) on_dead_letter: OnDeadLetter,
+}
+
+impl<OnDeadLetterT> Delivery<OnDeadLetterT>
+where
+    OnDeadLetterT: OnDeadLetter + Send + 'static,
+{
+    pub(crate) fn finish(mut self, outcome: Outcome) -> BoxFuture<'static, ()> {
+        let delivered = self.info.as_ref().map(|info| info.delivered).unwrap_or(1);
+
+        let outcome = match outcome {
+            Outcome::Retry(_) if delivered >= self.max_deliveries => Outcome::DeadLetter(format!(
+                "message failed to process on each of its {delivered} deliveries"
+            )),
+            outcome => outcome,
+        };
+
+        match outcome {
+            Outcome::Ack => Box::pin(async move {
+                trace!("double acking message");
+                if let Err(err) = self.acker.double_ack().await {
+                    warn!(
+                        error = ?err,
+                        subject = self.head.subject.as_str(),
+                        "failed to double ack the message",
+                    );
+                }
+            }),
+            Outcome::Retry(delay) => {
+                let delay = delay.unwrap_or_else(|| self.backoff.delay(delivered));
+                Box::pin(nak(self.acker, self.head, delivered, delay))
+            }
+            Outcome::DeadLetter(reason) => {
+                let on_dead_letter = self.on_dead_letter.call(self.head.clone(), &reason);
+                let publish = self.publish_dead_letter(delivered, &reason);
+                Box::pin(async move {
+                    match publish.await {
+                        Ok(()) => {
+                            trace!(%reason, "terminating dead-lettered message");
+                            if let Err(err) = self.acker.ack_with(jetstream::AckKind::Term).await {
+                                warn!(
+                                    error = ?err,
+                                    subject = self.head.subject.as_str(),
+                                    "failed to term the message",
+                                );
+                            }
+                            on_dead_letter.await;
+                        }
+                        // Leave the message to be dead-lettered again on its next delivery
+                        // rather than losing it
+                        Err(err) => {
+                            warn!(
+                                error = ?err,
+                                subject = self.head.subject.as_str(),
+                                "failed to publish the message to the dead-letter subject",
+                            );
+                            let delay = self.backoff.delay(delivered);
+                            nak(self.acker, self.head, delivered, delay).await;
+                        }
+                    }
+                })
+            }
+        }
+    }
+
+    /// Builds the publish of the message to the dead-letter subject up front, so that the future
+    /// doesn't borrow the delivery (and so need the [`OnDeadLetter`] to be `Sync`).
+    fn publish_dead_letter(
+        &self,
+        delivered: u64,
+        reason: &str,
+    ) -> impl Future<Output = Result<(), async_nats::Error>> + Send + 'static {
+        let mut headers = self.head.headers.clone().unwrap_or_default();
+        // Header values can't span lines, which error messages often do
+        headers.insert(
+            NATS_HEADER_DEAD_LETTER_REASON,
+            reason.replace(['\r', '\n'], " ").as_str(),
+        );
+        headers.insert(NATS_HEADER_ORIGINAL_SUBJECT, self.head.subject.as_str());
+        headers.insert(NATS_HEADER_DELIVERED_COUNT, delivered.to_string().as_str());
+        if let Some(info) = &self.info {
+            headers.insert(NATS_HEADER_ORIGINAL_STREAM, info.stream.as_str());
+            headers.insert(
+                NATS_HEADER_ORIGINAL_SEQUENCE,
+                info.stream_sequence.to_string().as_str(),
+            );
+        }
+
+        let context = self.context.clone();
+        let dead_letter_subject = self.dead_letter_subject.clone();
+        let payload = self.payload.clone();
+        async move {
+            context
+                .publish_with_headers(dead_letter_subject, headers, payload)
+                .await?
+                .await?;
+
+            Ok(())
+        }
+    }
+}
+
+async fn nak(acker: Arc<Acker>, head: Arc<Head>, delivered: u64, delay: Duration) {
+    trace!(delivered, ?delay, "nacking message with delay");
+    if let Err(err) = acker.ack_with(jetstream::AckKind::Nak(Some(delay))).await {
+        warn!(
+            error = ?err,
+            subject = head.subject.as_str(),
+            "failed to nack the message",
+        );
+    }
+}
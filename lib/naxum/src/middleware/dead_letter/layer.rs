@@ -0,0 +1,90 @@
+use std::time::Duration;
+
+use async_nats::Subject;
+use tower::Layer;
+
+use super::{backoff::Backoff, on_dead_letter::DefaultOnDeadLetter, service::DeadLetter};
+
+// Default `ack_wait` period when unset is 30 seconds (a NATS server default)
+const DEFAULT_PROGRESS_PERIOD: Duration = Duration::from_secs(30 - 1);
+
+const DEFAULT_MAX_DELIVERIES: u64 = 5;
+
+pub struct DeadLetterLayer<OnDeadLetter = DefaultOnDeadLetter> {
+    pub(crate) dead_letter_subject: Subject,
+    pub(crate) max_deliveries: u64,
+    pub(crate) backoff: Backoff,
+    pub(crate) progress_period: Duration,
+    pub(crate) on_dead_letter: OnDeadLetter,
+}
+
+impl DeadLetterLayer {
+    /// Creates a new [`DeadLetterLayer`] which publishes messages it gives up on to the given
+    /// subject.
+    pub fn new(dead_letter_subject: impl Into<Subject>) -> Self {
+        Self {
+            dead_letter_subject: dead_letter_subject.into(),
+            max_deliveries: DEFAULT_MAX_DELIVERIES,
+            backoff: Backoff::default(),
+            progress_period: DEFAULT_PROGRESS_PERIOD,
+            on_dead_letter: Default::default(),
+        }
+    }
+}
+
+impl<OnDeadLetter> DeadLetterLayer<OnDeadLetter> {
+    /// Sets how many times a message is delivered before it is dead-lettered.
+    pub fn max_deliveries(mut self, max_deliveries: u64) -> Self {
+        self.max_deliveries = max_deliveries;
+        self
+    }
+
+    /// Sets how long to wait before redelivering a message which failed to process.
+    pub fn backoff(mut self, backoff: Backoff) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    pub fn progress_period(mut self, progress_period: Duration) -> Self {
+        self.progress_period = progress_period;
+        self
+    }
+
+    pub fn on_dead_letter<NewOnDeadLetter>(
+        self,
+        new_on_dead_letter: NewOnDeadLetter,
+    ) -> DeadLetterLayer<NewOnDeadLetter> {
+        let Self {
+            dead_letter_subject,
+            max_deliveries,
+            backoff,
+            progress_period,
+            on_dead_letter: _,
+        } = self;
+        DeadLetterLayer {
+            dead_letter_subject,
+            max_deliveries,
+            backoff,
+            progress_period,
+            on_dead_letter: new_on_dead_letter,
+        }
+    }
+}
+
+impl<S, OnDeadLetter> Layer<S> for DeadLetterLayer<OnDeadLetter>
+where
+    OnDeadLetter: Clone,
+{
+    type Service = DeadLetter<S, OnDeadLetter>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        DeadLetter {
+            inner,
+            dead_letter_subject: self.dead_letter_subject.clone(),
+            max_deliveries: self.max_deliveries,
+            backoff: self.backoff,
+            progress_period: self.progress_period,
+            on_dead_letter: self.on_dead_letter.clone(),
+        }
+    }
+}
@@ -0,0 +1,158 @@
+//! Acknowledges JetStream messages like the [`ack`](super::ack) middleware, but redelivers failed
+//! messages with a backoff and, once a message has failed too many times, moves it to a
+//! dead-letter subject rather than redelivering it forever.
+//!
+//! Messages whose handler responds with a server error are nacked with a delay, and dead-lettered
+//! if they were on their last delivery. The delay comes from the backoff, unless the handler
+//! asked for one with [`Response::retry_after`](crate::response::Response::retry_after). Messages whose handler responds with a client error can
+//! never succeed, so they are dead-lettered straight away.
+
+mod backoff;
+mod delivery;
+mod future;
+mod layer;
+mod on_dead_letter;
+mod replay;
+mod service;
+
+pub use self::{
+    backoff::Backoff,
+    delivery::DeliveryInfo,
+    layer::DeadLetterLayer,
+    on_dead_letter::{DefaultOnDeadLetter, OnDeadLetter},
+    replay::replay,
+    service::DeadLetter,
+};
+
+/// The header holding why a message was dead-lettered.
+pub const NATS_HEADER_DEAD_LETTER_REASON: &str = "X-Dead-Letter-Reason";
+/// The header holding the subject a dead-lettered message was originally published to.
+pub const NATS_HEADER_ORIGINAL_SUBJECT: &str = "X-Original-Subject";
+/// The header holding the stream a dead-lettered message was consumed from.
+pub const NATS_HEADER_ORIGINAL_STREAM: &str = "X-Original-Stream";
+/// The header holding the sequence of a dead-lettered message in the stream it was consumed from.
+pub const NATS_HEADER_ORIGINAL_SEQUENCE: &str = "X-Original-Sequence";
+/// The header holding how many times a message was delivered before it was dead-lettered.
+pub const NATS_HEADER_DELIVERED_COUNT: &str = "X-Delivered-Count";
+
+#[cfg(test)]
+mod tests {
+    use std::{convert::Infallible, env};
+
+    use async_nats::jetstream::{self, consumer::pull, stream};
+    use bytes::Bytes;
+    use futures::StreamExt;
+    use tower::{service_fn, ServiceBuilder, ServiceExt};
+
+    use super::*;
+    use crate::response::Response;
+
+    async fn nats() -> async_nats::Client {
+        #[allow(clippy::disallowed_methods)] // Used only in tests & so prefixed with `SI_TEST_`
+        let url = env::var("SI_TEST_NATS_URL").unwrap_or_else(|_| "localhost".to_owned());
+
+        async_nats::connect(url)
+            .await
+            .expect("failed to connect to NATS")
+    }
+
+    async fn create_stream(context: &jetstream::Context, name: &str, subject: &str) {
+        context
+            .create_stream(stream::Config {
+                name: name.to_owned(),
+                subjects: vec![subject.to_owned()],
+                ..Default::default()
+            })
+            .await
+            .expect("failed to create stream");
+    }
+
+    async fn stream_state(context: &jetstream::Context, name: &str) -> stream::State {
+        context
+            .get_stream(name)
+            .await
+            .expect("failed to get stream")
+            .cached_info()
+            .state
+    }
+
+    #[tokio::test]
+    async fn dead_lettered_message_is_replayed_to_its_original_subject() {
+        let client = nats().await;
+        let context = jetstream::new(client.clone());
+        let id = client.new_inbox().replace("_INBOX.", "");
+        let (work_stream, work_subject) = (format!("NAXUM_TEST_{id}"), format!("test.{id}.work"));
+        let (dead_letter_stream, dead_letter_subject) = (
+            format!("NAXUM_TEST_{id}_DEAD_LETTER"),
+            format!("test.{id}.dead-letter"),
+        );
+        create_stream(&context, &work_stream, &work_subject).await;
+        create_stream(&context, &dead_letter_stream, &dead_letter_subject).await;
+
+        context
+            .publish(work_subject.clone(), Bytes::from_static(b"brick"))
+            .await
+            .expect("failed to publish")
+            .await
+            .expect("failed to get publish ack");
+        let consumer = context
+            .get_stream(&work_stream)
+            .await
+            .expect("failed to get stream")
+            .create_consumer(pull::Config::default())
+            .await
+            .expect("failed to create consumer");
+        let next_message = || async {
+            consumer
+                .fetch()
+                .max_messages(1)
+                .messages()
+                .await
+                .expect("failed to fetch")
+                .next()
+                .await
+                .expect("no message fetched")
+                .expect("failed to receive message")
+        };
+
+        // A client error can never succeed, so the message is dead-lettered straight away
+        let app = ServiceBuilder::new()
+            .layer(DeadLetterLayer::new(dead_letter_subject))
+            .service(service_fn(|_: async_nats::Message| async {
+                Ok::<_, Infallible>(Response::client_error())
+            }));
+        app.oneshot(next_message().await)
+            .await
+            .expect("service is infallible");
+        assert_eq!(
+            1,
+            stream_state(&context, &dead_letter_stream).await.messages
+        );
+
+        let replayed = replay(&context, &dead_letter_stream, 10)
+            .await
+            .expect("failed to replay");
+        assert_eq!(1, replayed);
+
+        let message = next_message().await;
+        assert_eq!(work_subject.as_str(), message.subject.as_str());
+        assert_eq!(Bytes::from_static(b"brick"), message.payload);
+        assert!(message
+            .headers
+            .as_ref()
+            .and_then(|headers| headers.get(NATS_HEADER_DEAD_LETTER_REASON))
+            .is_some());
+
+        // The replayed message is gone from the dead-letter stream, as is the replay's consumer
+        let state = stream_state(&context, &dead_letter_stream).await;
+        assert_eq!(0, state.messages);
+        assert_eq!(0, state.consumer_count);
+
+        for stream in [work_stream, dead_letter_stream] {
+            context
+                .delete_stream(stream)
+                .await
+                .expect("failed to delete stream");
+        }
+    }
+}
@@ -0,0 +1,26 @@
+use std::sync::Arc;
+
+use futures::future::BoxFuture;
+
+use crate::Head;
+
+/// Called after a message has been published to the dead-letter subject, e.g. to let whoever is
+/// waiting on the message know that it will never be processed.
+pub trait OnDeadLetter {
+    fn call(&mut self, head: Arc<Head>, reason: &str) -> BoxFuture<'static, ()>;
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct DefaultOnDeadLetter {}
+
+impl DefaultOnDeadLetter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl OnDeadLetter for DefaultOnDeadLetter {
+    fn call(&mut self, _head: Arc<Head>, _reason: &str) -> BoxFuture<'static, ()> {
+        Box::pin(async {})
+    }
+}
@@ -0,0 +1,129 @@
+use async_nats::{
+    header,
+    jetstream::{
+        self,
+        consumer::{pull, PullConsumer},
+        stream::Stream,
+    },
+};
+use futures::TryStreamExt;
+use tracing::{debug, warn};
+
+use crate::Error;
+
+use super::{delivery::DeliveryInfo, NATS_HEADER_ORIGINAL_SUBJECT};
+
+/// Publishes up to `max_messages` messages from a dead-letter stream back to the subjects they
+/// were originally published to, removing each from the dead-letter stream once it has been
+/// published. Returns how many messages were replayed.
+///
+/// Replayed messages keep the headers added when they were dead-lettered, so that their
+/// handlers can tell they are being replayed, but are given a new message id so that the stream
+/// they are published to does not drop them as duplicates.
+pub async fn replay(
+    context: &jetstream::Context,
+    dead_letter_stream: &str,
+    max_messages: usize,
+) -> Result<usize, Error> {
+    let stream = context
+        .get_stream(dead_letter_stream)
+        .await
+        .map_err(Error::new)?;
+    let consumer = stream
+        .create_consumer(pull::Config {
+            description: Some("Dead-letter replay".to_owned()),
+            ..Default::default()
+        })
+        .await
+        .map_err(Error::new)?;
+    let consumer_name = consumer.cached_info().name.clone();
+
+    let result = replay_from(
+        context,
+        &stream,
+        &consumer,
+        dead_letter_stream,
+        max_messages,
+    )
+    .await;
+
+    // The consumer is only used for this replay, so it is removed rather than being left with
+    // its fetched messages unacked until the server reaps it
+    if let Err(err) = stream.delete_consumer(&consumer_name).await {
+        warn!(
+            error = ?err,
+            consumer = %consumer_name,
+            "failed to delete dead-letter replay consumer",
+        );
+    }
+
+    result
+}
+
+async fn replay_from(
+    context: &jetstream::Context,
+    stream: &Stream,
+    consumer: &PullConsumer,
+    dead_letter_stream: &str,
+    max_messages: usize,
+) -> Result<usize, Error> {
+    let mut messages = consumer
+        .fetch()
+        .max_messages(max_messages)
+        .messages()
+        .await
+        .map_err(Error::new)?;
+
+    let mut replayed = 0;
+    while let Some(message) = messages.try_next().await.map_err(Error::new)? {
+        let Some(info) = message
+            .message
+            .reply
+            .as_ref()
+            .and_then(|reply| DeliveryInfo::from_reply(reply.as_str()))
+        else {
+            warn!("dead-lettered message has no delivery info, skipping");
+            continue;
+        };
+        let Some(mut headers) = message.message.headers.clone() else {
+            warn!(
+                stream_sequence = info.stream_sequence,
+                "dead-lettered message has no headers, skipping"
+            );
+            continue;
+        };
+        let Some(original_subject) = headers
+            .get(NATS_HEADER_ORIGINAL_SUBJECT)
+            .map(|value| value.as_str().to_owned())
+        else {
+            warn!(
+                stream_sequence = info.stream_sequence,
+                "dead-lettered message has no original subject, skipping"
+            );
+            continue;
+        };
+
+        headers.insert(
+            header::NATS_MESSAGE_ID,
+            format!("{dead_letter_stream}-{}", info.stream_sequence).as_str(),
+        );
+        context
+            .publish_with_headers(original_subject, headers, message.message.payload.clone())
+            .await
+            .map_err(Error::new)?
+            .await
+            .map_err(Error::new)?;
+        stream
+            .delete_message(info.stream_sequence)
+            .await
+            .map_err(Error::new)?;
+
+        debug!(
+            stream_sequence = info.stream_sequence,
+            "replayed dead-lettered message"
+        );
+        replayed += 1;
+    }
+
+    Ok(replayed)
+}
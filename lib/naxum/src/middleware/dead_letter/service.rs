@@ -0,0 +1,106 @@
+use std::{
+    sync::Arc,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use async_nats::{jetstream, Subject};
+use tokio_util::sync::CancellationToken;
+use tower::Service;
+
+use crate::{
+    middleware::ack::maintain_progress::MaintainProgressTask, response::Response, MessageHead,
+};
+
+use super::{
+    backoff::Backoff,
+    delivery::DeliveryInfo,
+    future::{Delivery, Outcome, ResponseFuture},
+    on_dead_letter::OnDeadLetter,
+};
+
+#[derive(Clone, Debug)]
+pub struct DeadLetter<S, OnDeadLetter> {
+    pub(crate) inner: S,
+    pub(crate) dead_letter_subject: Subject,
+    pub(crate) max_deliveries: u64,
+    pub(crate) backoff: Backoff,
+    pub(crate) progress_period: Duration,
+    pub(crate) on_dead_letter: OnDeadLetter,
+}
+
+impl<S, OnDeadLetterT> Service<jetstream::Message> for DeadLetter<S, OnDeadLetterT>
+where
+    S: Service<async_nats::Message, Response = Response>,
+    OnDeadLetterT: OnDeadLetter + Clone + Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = ResponseFuture<S, OnDeadLetterT>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: jetstream::Message) -> Self::Future {
+        let info = req
+            .message
+            .reply
+            .as_ref()
+            .and_then(|reply| DeliveryInfo::from_reply(reply.as_str()));
+        let context = req.context.clone();
+        let (message, acker) = req.split();
+        let acker = Arc::new(acker);
+        let (head, payload) = message.into_parts();
+        let message =
+            match <async_nats::Message as MessageHead>::from_parts(head.clone(), payload.clone()) {
+                Ok(message) => message,
+                Err(err) => unreachable!(
+                    "NATS core message from parts is infallible, this is a bug!; error={:?}",
+                    err
+                ),
+            };
+
+        let delivered = info.as_ref().map(|info| info.delivered).unwrap_or(1);
+        let delivery = Delivery {
+            head: Arc::new(head),
+            payload,
+            info,
+            acker: acker.clone(),
+            context,
+            dead_letter_subject: self.dead_letter_subject.clone(),
+            max_deliveries: self.max_deliveries,
+            backoff: self.backoff,
+            on_dead_letter: self.on_dead_letter.clone(),
+        };
+
+        // A message delivered more times than allowed has never finished processing (e.g. its
+        // handler keeps crashing the process), so it is dead-lettered without being processed
+        if delivered > self.max_deliveries {
+            let reason = format!("message was delivered {delivered} times without completing");
+            return ResponseFuture {
+                inner: None,
+                delivery: None,
+                finish: Some(delivery.finish(Outcome::DeadLetter(reason))),
+                result: Some(Ok(Response::default())),
+                shutdown_guard: None,
+            };
+        }
+
+        let task_shutdown = CancellationToken::new();
+
+        let task = MaintainProgressTask::new(acker, self.progress_period, task_shutdown.clone());
+        tokio::spawn(task.run());
+        // The drop guard will trigger a `cancel` on the token to ensure the task is shutdown even
+        // if the response future has issues
+        let shutdown_guard = task_shutdown.drop_guard();
+
+        ResponseFuture {
+            inner: Some(self.inner.call(message)),
+            delivery: Some(delivery),
+            finish: None,
+            result: None,
+            shutdown_guard: Some(shutdown_guard),
+        }
+    }
+}
@@ -0,0 +1,80 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use async_nats::Subject;
+use futures::future::BoxFuture;
+use pin_project_lite::pin_project;
+use tracing::{trace, warn};
+
+use crate::response::Response;
+
+pin_project! {
+    pub struct ResponseFuture<F, E> {
+        #[pin]
+        pub(crate) inner: F,
+        pub(crate) client: async_nats::Client,
+        pub(crate) subject: Option<Subject>,
+        pub(crate) publish: Option<BoxFuture<'static, ()>>,
+        pub(crate) result: Option<Result<Response, E>>,
+    }
+}
+
+impl<F, E> Future for ResponseFuture<F, E>
+where
+    F: Future<Output = Result<Response, E>>,
+{
+    type Output = Result<Response, E>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut this = self.project();
+
+        loop {
+            // Poll the publishing future and when ready return the inner result
+            if let Some(publish) = this.publish.as_mut() {
+                futures::ready!(publish.as_mut().poll(cx));
+                return Poll::Ready(
+                    this.result
+                        .take()
+                        .expect("extracting owned value only happens once"),
+                );
+            }
+
+            // Poll the nested service to yield our result
+            let result = futures::ready!(this.inner.as_mut().poll(cx));
+
+            // A message without a reply subject has nobody waiting on a reply
+            let Some(subject) = this.subject.take() else {
+                return Poll::Ready(result);
+            };
+
+            if let Ok(response) = &result {
+                let (headers, body) = response.clone().into_parts();
+                let client = this.client.clone();
+                *this.publish = Some(Box::pin(async move {
+                    trace!(subject = subject.as_str(), "publishing final reply");
+                    let published = match headers {
+                        Some(headers) => {
+                            client
+                                .publish_with_headers(subject.clone(), headers, body)
+                                .await
+                        }
+                        None => client.publish(subject.clone(), body).await,
+                    };
+                    if let Err(err) = published {
+                        warn!(
+                            error = ?err,
+                            subject = subject.as_str(),
+                            "failed to publish final reply",
+                        );
+                    }
+                }));
+            } else {
+                *this.publish = Some(Box::pin(async {}));
+            }
+            *this.result = Some(result);
+        }
+    }
+}
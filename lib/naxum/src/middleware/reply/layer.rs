@@ -0,0 +1,39 @@
+use tower::Layer;
+
+use super::service::Reply;
+
+pub struct ReplyLayer {
+    pub(crate) client: async_nats::Client,
+    pub(crate) final_reply_subject: fn(&str) -> String,
+}
+
+impl ReplyLayer {
+    /// Creates a new [`ReplyLayer`] which publishes final replies with the given client.
+    pub fn new(client: async_nats::Client) -> Self {
+        Self {
+            client,
+            final_reply_subject: str::to_owned,
+        }
+    }
+
+    /// Sets the subject the final reply is published to, derived from the reply subject of the
+    /// message.
+    ///
+    /// By default, the final reply is published to the reply subject itself.
+    pub fn final_reply_subject(mut self, final_reply_subject: fn(&str) -> String) -> Self {
+        self.final_reply_subject = final_reply_subject;
+        self
+    }
+}
+
+impl<S> Layer<S> for ReplyLayer {
+    type Service = Reply<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        Reply {
+            inner,
+            client: self.client.clone(),
+            final_reply_subject: self.final_reply_subject,
+        }
+    }
+}
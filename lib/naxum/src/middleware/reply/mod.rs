@@ -0,0 +1,128 @@
+//! Replies to NATS request/reply messages.
+//!
+//! The [`ReplyLayer`] publishes the response of a handler as the final reply to a message which has
+//! a reply subject, so a handler only has to return a typed response (e.g. a
+//! [`Json`](crate::Json)). Handlers which report progress before their final reply can extract a
+//! [`Replier`] to send any number of intermediate replies.
+
+mod future;
+mod layer;
+mod replier;
+mod service;
+
+pub use self::{layer::ReplyLayer, replier::Replier, service::Reply};
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use async_nats::HeaderMap;
+    use bytes::Bytes;
+    use futures::StreamExt;
+    use tower::{ServiceBuilder, ServiceExt};
+
+    use super::*;
+    use crate::{handler::Handler, Json};
+
+    async fn nats() -> async_nats::Client {
+        #[allow(clippy::disallowed_methods)] // Used only in tests & so prefixed with `SI_TEST_`
+        let url = env::var("SI_TEST_NATS_URL").unwrap_or_else(|_| "localhost".to_owned());
+
+        async_nats::connect(url)
+            .await
+            .expect("failed to connect to NATS")
+    }
+
+    fn request(
+        subject: String,
+        reply: Option<String>,
+        payload: &'static str,
+    ) -> async_nats::Message {
+        async_nats::Message {
+            subject: subject.into(),
+            reply: reply.map(Into::into),
+            payload: Bytes::from_static(payload.as_bytes()),
+            headers: None,
+            status: None,
+            description: None,
+            length: 0,
+        }
+    }
+
+    async fn increment(replier: Replier, Json(n): Json<u32>) -> Json<u32> {
+        replier
+            .send(HeaderMap::new(), Bytes::from_static(b"progress"))
+            .await
+            .expect("failed to send progress");
+
+        Json(n + 1)
+    }
+
+    #[tokio::test]
+    async fn replies_with_progress_then_response() {
+        let client = nats().await;
+        let inbox = client.new_inbox();
+        let mut progress = client
+            .subscribe(inbox.clone())
+            .await
+            .expect("failed to subscribe");
+        let mut result = client
+            .subscribe(format!("{inbox}.result"))
+            .await
+            .expect("failed to subscribe");
+
+        let app = ServiceBuilder::new()
+            .layer(
+                ReplyLayer::new(client.clone())
+                    .final_reply_subject(|reply| format!("{reply}.result")),
+            )
+            .service(increment.with_state(client.clone()));
+        let response = app
+            .oneshot(request(client.new_inbox(), Some(inbox), "41"))
+            .await
+            .expect("service is infallible");
+        assert_eq!(&Bytes::from_static(b"42"), response.body());
+
+        let msg = progress.next().await.expect("subscription ended");
+        assert_eq!(Bytes::from_static(b"progress"), msg.payload);
+        let msg = result.next().await.expect("subscription ended");
+        assert_eq!(Bytes::from_static(b"42"), msg.payload);
+    }
+
+    #[tokio::test]
+    async fn rejected_message_is_replied_to_with_rejection() {
+        let client = nats().await;
+        let inbox = client.new_inbox();
+        let mut replies = client
+            .subscribe(inbox.clone())
+            .await
+            .expect("failed to subscribe");
+
+        let app = ServiceBuilder::new()
+            .layer(ReplyLayer::new(client.clone()))
+            .service(increment.with_state(client.clone()));
+        let response = app
+            .oneshot(request(client.new_inbox(), Some(inbox), "not json"))
+            .await
+            .expect("service is infallible");
+        assert_eq!(400, response.status().as_u16());
+
+        // The handler never ran, so the only reply is the rejection
+        let msg = replies.next().await.expect("subscription ended");
+        assert_eq!(response.body(), &msg.payload);
+    }
+
+    #[tokio::test]
+    async fn message_without_reply_subject_is_rejected_by_replier() {
+        let client = nats().await;
+
+        let app = ServiceBuilder::new()
+            .layer(ReplyLayer::new(client.clone()))
+            .service(increment.with_state(client.clone()));
+        let response = app
+            .oneshot(request(client.new_inbox(), None, "41"))
+            .await
+            .expect("service is infallible");
+        assert_eq!(400, response.status().as_u16());
+    }
+}
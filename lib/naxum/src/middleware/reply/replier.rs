@@ -0,0 +1,64 @@
+use async_nats::{HeaderMap, Subject};
+use async_trait::async_trait;
+use bytes::Bytes;
+
+use crate::{
+    extract::{rejection::MissingReplySubject, FromMessageHead, FromRef},
+    Head,
+};
+
+/// Extractor which sends intermediate replies to a message, such as progress updates, ahead of the
+/// final reply.
+///
+/// Requires the state to provide an [`async_nats::Client`] and rejects messages without a reply
+/// subject.
+#[derive(Clone, Debug)]
+pub struct Replier {
+    client: async_nats::Client,
+    subject: Subject,
+}
+
+impl Replier {
+    /// The subject replies are published to.
+    pub fn subject(&self) -> &Subject {
+        &self.subject
+    }
+
+    /// Returns a [`Replier`] which publishes to a mailbox derived from the reply subject, for
+    /// protocols which send different kinds of replies to different subjects.
+    pub fn for_mailbox(&self, mailbox: impl FnOnce(&str) -> String) -> Self {
+        Self {
+            client: self.client.clone(),
+            subject: mailbox(self.subject.as_str()).into(),
+        }
+    }
+
+    /// Publishes a reply.
+    pub async fn send(
+        &self,
+        headers: HeaderMap,
+        payload: Bytes,
+    ) -> Result<(), async_nats::PublishError> {
+        self.client
+            .publish_with_headers(self.subject.clone(), headers, payload)
+            .await
+    }
+}
+
+#[async_trait]
+impl<S> FromMessageHead<S> for Replier
+where
+    async_nats::Client: FromRef<S>,
+    S: Send + Sync,
+{
+    type Rejection = MissingReplySubject;
+
+    async fn from_message_head(head: &mut Head, state: &S) -> Result<Self, Self::Rejection> {
+        let subject = head.reply.clone().ok_or(MissingReplySubject)?;
+
+        Ok(Self {
+            client: async_nats::Client::from_ref(state),
+            subject,
+        })
+    }
+}
@@ -0,0 +1,53 @@
+use std::task::{Context, Poll};
+
+use async_nats::Subject;
+use tower::Service;
+
+use crate::{response::Response, MessageHead};
+
+use super::future::ResponseFuture;
+
+#[derive(Clone, Debug)]
+pub struct Reply<S> {
+    pub(crate) inner: S,
+    pub(crate) client: async_nats::Client,
+    pub(crate) final_reply_subject: fn(&str) -> String,
+}
+
+impl<S> Reply<S> {
+    pub fn new(inner: S, client: async_nats::Client) -> Self {
+        Self {
+            inner,
+            client,
+            final_reply_subject: str::to_owned,
+        }
+    }
+}
+
+impl<S, R> Service<R> for Reply<S>
+where
+    S: Service<R, Response = Response>,
+    R: MessageHead,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = ResponseFuture<S::Future, S::Error>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: R) -> Self::Future {
+        let subject = req
+            .reply()
+            .map(|reply| Subject::from((self.final_reply_subject)(reply.as_str())));
+
+        ResponseFuture {
+            inner: self.inner.call(req),
+            client: self.client.clone(),
+            subject,
+            publish: None,
+            result: None,
+        }
+    }
+}
@@ -1,24 +1,69 @@
 mod into_response;
 
-use async_nats::StatusCode;
+use std::time::Duration;
+
+use async_nats::{HeaderMap, StatusCode};
+use bytes::Bytes;
 
 pub use self::into_response::IntoResponse;
 
-#[derive(Clone, Copy, Debug, Default)]
+#[derive(Clone, Debug, Default)]
 pub struct Response {
     status: StatusCode,
+    headers: Option<HeaderMap>,
+    body: Bytes,
+    retry_after: Option<Duration>,
 }
 
 impl Response {
+    /// A response for a message which can never be processed (e.g. because it is malformed), so
+    /// retrying it is pointless.
+    pub fn client_error() -> Self {
+        Self {
+            status: StatusCode::from_u16(400).expect("status code is in valid range"),
+            ..Default::default()
+        }
+    }
+
     pub fn server_error() -> Self {
         Self {
             status: StatusCode::from_u16(500).expect("status code is in valid range"),
+            ..Default::default()
+        }
+    }
+
+    /// A response for a message which failed to process and should be redelivered after the
+    /// given delay, rather than after the delay a middleware would otherwise choose.
+    pub fn retry_after(delay: Duration) -> Self {
+        Self {
+            retry_after: Some(delay),
+            ..Self::server_error()
         }
     }
 
-    pub fn status(self) -> StatusCode {
+    pub fn status(&self) -> StatusCode {
         self.status
     }
+
+    /// Headers sent with the reply to the message, if any.
+    pub fn headers(&self) -> Option<&HeaderMap> {
+        self.headers.as_ref()
+    }
+
+    /// The body of the reply to the message, which is empty when the handler has nothing to reply
+    /// with.
+    pub fn body(&self) -> &Bytes {
+        &self.body
+    }
+
+    /// How long the handler asked to wait before the message is redelivered, if it asked.
+    pub fn retry_delay(&self) -> Option<Duration> {
+        self.retry_after
+    }
+
+    pub fn into_parts(self) -> (Option<HeaderMap>, Bytes) {
+        (self.headers, self.body)
+    }
 }
 
 pub type Result<T, E = ErrorResponse> = std::result::Result<T, E>;
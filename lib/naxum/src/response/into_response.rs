@@ -12,7 +12,10 @@ pub trait IntoResponse {
 
 impl IntoResponse for StatusCode {
     fn into_response(self) -> Response {
-        Response { status: self }
+        Response {
+            status: self,
+            ..Default::default()
+        }
     }
 }
 
@@ -49,37 +52,40 @@ impl IntoResponse for Response {
 
 impl IntoResponse for &'static str {
     fn into_response(self) -> Response {
-        Response::default()
+        body(self)
     }
 }
 
 impl IntoResponse for String {
     fn into_response(self) -> Response {
-        Response::default()
+        body(self)
     }
 }
 
 impl IntoResponse for Box<str> {
     fn into_response(self) -> Response {
-        Response::default()
+        body(String::from(self))
     }
 }
 
 impl IntoResponse for Cow<'static, str> {
     fn into_response(self) -> Response {
-        Response::default()
+        match self {
+            Cow::Borrowed(s) => s.into_response(),
+            Cow::Owned(s) => s.into_response(),
+        }
     }
 }
 
 impl IntoResponse for Bytes {
     fn into_response(self) -> Response {
-        Response::default()
+        body(self)
     }
 }
 
 impl IntoResponse for BytesMut {
     fn into_response(self) -> Response {
-        Response::default()
+        body(self.freeze())
     }
 }
 
@@ -88,38 +94,42 @@ where
     T: Buf + Unpin + Send + 'static,
     U: Buf + Unpin + Send + 'static,
 {
-    fn into_response(self) -> Response {
-        Response::default()
+    fn into_response(mut self) -> Response {
+        let len = self.remaining();
+        body(self.copy_to_bytes(len))
     }
 }
 
 impl IntoResponse for &'static [u8] {
     fn into_response(self) -> Response {
-        Response::default()
+        body(self)
     }
 }
 
 impl<const N: usize> IntoResponse for [u8; N] {
     fn into_response(self) -> Response {
-        Response::default()
+        self.to_vec().into_response()
     }
 }
 
 impl IntoResponse for Vec<u8> {
     fn into_response(self) -> Response {
-        Response::default()
+        body(self)
     }
 }
 
 impl IntoResponse for Box<[u8]> {
     fn into_response(self) -> Response {
-        Response::default()
+        body(self)
     }
 }
 
 impl IntoResponse for Cow<'static, [u8]> {
     fn into_response(self) -> Response {
-        Response::default()
+        match self {
+            Cow::Borrowed(s) => s.into_response(),
+            Cow::Owned(s) => s.into_response(),
+        }
     }
 }
 
@@ -128,13 +138,34 @@ where
     R: IntoResponse,
 {
     fn into_response(self) -> Response {
-        Response { status: self.0 }
+        let mut response = self.1.into_response();
+        response.status = self.0;
+        response
+    }
+}
+
+impl<R> IntoResponse for (HeaderMap, R)
+where
+    R: IntoResponse,
+{
+    fn into_response(self) -> Response {
+        let mut response = self.1.into_response();
+        let headers = response.headers.get_or_insert_with(HeaderMap::new);
+        for (key, values) in self.0.iter() {
+            for value in values {
+                headers.append(key.clone(), value.clone());
+            }
+        }
+        response
     }
 }
 
 impl IntoResponse for HeaderMap {
     fn into_response(self) -> Response {
-        Response::default()
+        Response {
+            headers: Some(self),
+            ..Default::default()
+        }
     }
 }
 
@@ -146,7 +177,19 @@ where
     V::Error: fmt::Display,
 {
     fn into_response(self) -> Response {
-        Response::default()
+        let mut headers = HeaderMap::new();
+        for (key, value) in self {
+            let key = match key.try_into() {
+                Ok(key) => key,
+                Err(_) => return Response::server_error(),
+            };
+            let value = match value.try_into() {
+                Ok(value) => value,
+                Err(_) => return Response::server_error(),
+            };
+            headers.insert(key, value);
+        }
+        headers.into_response()
     }
 }
 
@@ -155,6 +198,13 @@ where
     R: IntoResponse,
 {
     fn into_response(self) -> Response {
-        Response::default()
+        self.0.into_response()
+    }
+}
+
+fn body(body: impl Into<Bytes>) -> Response {
+    Response {
+        body: body.into(),
+        ..Default::default()
     }
 }
@@ -5,15 +5,23 @@ use std::{
     io,
     marker::PhantomData,
     ops,
+    sync::Arc,
 };
 
 use futures::{Stream, TryStreamExt};
+use tokio::sync::Semaphore;
 use tokio_util::{sync::CancellationToken, task::TaskTracker};
 use tower::{Service, ServiceExt};
 use tracing::{trace, warn};
 
 use crate::{message::MessageHead, response::Response};
 
+use self::partition::{run_partition, PartitionQueues, Queued};
+
+pub use self::partition::Partitions;
+
+mod partition;
+
 const MAX_FAILED_MESSAGES: usize = 4;
 
 pub fn serve<M, S, T, E, R>(stream: T, make_service: M) -> Serve<M, S, T, E, R>
@@ -28,6 +36,8 @@ where
     Serve {
         stream,
         make_service,
+        concurrency_limit: None,
+        partitions: None,
         _service_marker: PhantomData,
         _stream_error_marker: PhantomData,
         _request_marker: PhantomData,
@@ -38,6 +48,8 @@ where
 pub struct Serve<M, S, T, E, R> {
     stream: T,
     make_service: M,
+    concurrency_limit: Option<usize>,
+    partitions: Option<Partitions>,
     _service_marker: PhantomData<S>,
     _stream_error_marker: PhantomData<E>,
     _request_marker: PhantomData<R>,
@@ -50,11 +62,30 @@ where
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("Serve")
             .field("make_service", &self.make_service)
+            .field("concurrency_limit", &self.concurrency_limit)
+            .field("partitions", &self.partitions)
             .finish_non_exhaustive()
     }
 }
 
 impl<M, S, T, E, R> Serve<M, S, T, E, R> {
+    /// Limits how many messages are in flight at once.
+    ///
+    /// A message counts as in flight from when it is read off the stream, including any time
+    /// spent waiting on its partition, until it has been processed. No more messages are read
+    /// while the limit is reached, so they stay available to other consumers.
+    pub fn with_concurrency_limit(mut self, limit: usize) -> Self {
+        self.concurrency_limit = Some(limit);
+        self
+    }
+
+    /// Processes messages with the same partition key strictly in order, while messages with
+    /// different keys are processed concurrently.
+    pub fn with_partitions(mut self, partitions: Partitions) -> Self {
+        self.partitions = Some(partitions);
+        self
+    }
+
     pub fn with_graceful_shutdown<F>(self, signal: F) -> WithGracefulShutdown<M, S, T, E, R, F>
     where
         F: Future<Output = ()> + Send + 'static,
@@ -62,6 +93,8 @@ impl<M, S, T, E, R> Serve<M, S, T, E, R> {
         WithGracefulShutdown {
             stream: self.stream,
             make_service: self.make_service,
+            concurrency_limit: self.concurrency_limit,
+            partitions: self.partitions,
             signal,
             _service_marker: PhantomData,
             _stream_error_marker: PhantomData,
@@ -75,6 +108,8 @@ impl<M, S, T, E, R> Serve<M, S, T, E, R> {
 pub struct WithGracefulShutdown<M, S, T, E, R, F> {
     stream: T,
     make_service: M,
+    concurrency_limit: Option<usize>,
+    partitions: Option<Partitions>,
     signal: F,
     _service_marker: PhantomData<S>,
     _stream_error_marker: PhantomData<E>,
@@ -90,6 +125,8 @@ where
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("WithGracefulShutdown")
             .field("make_service", &self.make_service)
+            .field("concurrency_limit", &self.concurrency_limit)
+            .field("partitions", &self.partitions)
             .field("signal", &self.signal)
             .finish_non_exhaustive()
     }
@@ -113,10 +150,15 @@ where
         let Self {
             mut stream,
             mut make_service,
+            concurrency_limit,
+            partitions,
             signal,
             ..
         } = self;
 
+        let limit = concurrency_limit.map(|limit| Arc::new(Semaphore::new(limit)));
+        let partition_queues = PartitionQueues::new();
+
         let tracker = TaskTracker::new();
         let graceful_token = CancellationToken::new();
 
@@ -131,6 +173,21 @@ where
 
         private::ServeFuture(Box::pin(async move {
             loop {
+                // Wait for room before reading another message, so that messages which can't be
+                // processed yet are left on the stream
+                let permit = match &limit {
+                    Some(limit) => tokio::select! {
+                        // The semaphore is never closed, so a permit is always acquired
+                        permit = limit.clone().acquire_owned() => permit.ok(),
+                        _ = graceful_token.cancelled() => {
+                            trace!("signal received, not accepting new messages");
+                            tracker.close();
+                            break;
+                        }
+                    },
+                    None => None,
+                };
+
                 let msg = tokio::select! {
                     msg = next_message(&mut stream, failed_count) => {
                         match msg {
@@ -170,6 +227,28 @@ where
                     .unwrap_or_else(|err| match err {});
 
                 let graceful_token = graceful_token.clone();
+
+                if let Some(key) = partitions
+                    .as_ref()
+                    .and_then(|partitions| partitions.key(&msg))
+                {
+                    let queued = Queued {
+                        msg,
+                        svc: tower_svc,
+                        permit,
+                    };
+                    // Only start a task for the partition if one isn't already processing it
+                    if let Some(first) = partition_queues.push(&key, queued) {
+                        tracker.spawn(run_partition(
+                            partition_queues.clone(),
+                            key,
+                            first,
+                            graceful_token,
+                        ));
+                    }
+                    continue;
+                }
+
                 tracker.spawn(async move {
                     tokio::select! {
                         _result = tower_svc.oneshot(msg) => {
@@ -179,6 +258,7 @@ where
                             trace!("signal received in task, starting graceful shutdown");
                         }
                     }
+                    drop(permit);
                 });
             }
 
@@ -0,0 +1,220 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    convert::Infallible,
+    sync::{Arc, Mutex, PoisonError},
+};
+
+use tokio::sync::OwnedSemaphorePermit;
+use tokio_util::sync::CancellationToken;
+use tower::{Service, ServiceExt};
+use tracing::trace;
+
+use crate::{message::MessageHead, response::Response};
+
+/// How messages are grouped into partitions when serving.
+///
+/// Messages with the same partition key are processed one at a time, in the order they were
+/// received, while messages with different keys are processed concurrently. Messages without a
+/// key (e.g. a subject with too few tokens or a missing header) are not ordered with respect to
+/// any other message.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Partitions {
+    /// Keys messages by the subject tokens at the given (zero-based) indexes.
+    SubjectTokens(Vec<usize>),
+    /// Keys messages by the value of the given header.
+    Header(String),
+}
+
+impl Partitions {
+    /// Keys messages by the subject tokens at the given (zero-based) indexes.
+    pub fn subject_tokens(indexes: impl IntoIterator<Item = usize>) -> Self {
+        Self::SubjectTokens(indexes.into_iter().collect())
+    }
+
+    /// Keys messages by the value of the given header.
+    pub fn header(name: impl Into<String>) -> Self {
+        Self::Header(name.into())
+    }
+
+    pub(crate) fn key<R>(&self, msg: &R) -> Option<String>
+    where
+        R: MessageHead,
+    {
+        match self {
+            Self::SubjectTokens(indexes) => {
+                let tokens: Vec<_> = msg.subject().as_str().split('.').collect();
+                let key: Option<Vec<_>> = indexes
+                    .iter()
+                    .map(|index| tokens.get(*index).copied())
+                    .collect();
+                key.map(|key| key.join("."))
+            }
+            Self::Header(name) => msg
+                .headers()
+                .and_then(|headers| headers.get(name.as_str()))
+                .map(|value| value.as_str().to_owned()),
+        }
+    }
+}
+
+pub(crate) struct Queued<R, S> {
+    pub(crate) msg: R,
+    pub(crate) svc: S,
+    pub(crate) permit: Option<OwnedSemaphorePermit>,
+}
+
+/// The messages waiting on each partition which has a message being processed.
+///
+/// A partition is present for as long as a task is processing its messages, so a message for a
+/// present partition is queued rather than starting another task.
+pub(crate) struct PartitionQueues<R, S> {
+    queues: Arc<Mutex<Queues<R, S>>>,
+}
+
+/// The queue of waiting messages for each partition, by partition key.
+type Queues<R, S> = HashMap<String, VecDeque<Queued<R, S>>>;
+
+impl<R, S> Clone for PartitionQueues<R, S> {
+    fn clone(&self) -> Self {
+        Self {
+            queues: self.queues.clone(),
+        }
+    }
+}
+
+impl<R, S> PartitionQueues<R, S> {
+    pub(crate) fn new() -> Self {
+        Self {
+            queues: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Queues a message on its partition, or returns it if no task is processing the partition
+    /// and so one must be started.
+    pub(crate) fn push(&self, key: &str, queued: Queued<R, S>) -> Option<Queued<R, S>> {
+        let mut queues = self.queues.lock().unwrap_or_else(PoisonError::into_inner);
+        match queues.get_mut(key) {
+            Some(queue) => {
+                queue.push_back(queued);
+                None
+            }
+            None => {
+                queues.insert(key.to_owned(), VecDeque::new());
+                Some(queued)
+            }
+        }
+    }
+
+    /// Takes the next message of a partition, forgetting the partition if it has none left.
+    fn pop(&self, key: &str) -> Option<Queued<R, S>> {
+        let mut queues = self.queues.lock().unwrap_or_else(PoisonError::into_inner);
+        let next = queues.get_mut(key).and_then(VecDeque::pop_front);
+        if next.is_none() {
+            queues.remove(key);
+        }
+        next
+    }
+
+    /// Drops all queued messages of a partition and forgets it.
+    fn clear(&self, key: &str) {
+        let mut queues = self.queues.lock().unwrap_or_else(PoisonError::into_inner);
+        queues.remove(key);
+    }
+}
+
+/// Processes the messages of one partition in order until none are left.
+pub(crate) async fn run_partition<R, S>(
+    queues: PartitionQueues<R, S>,
+    key: String,
+    first: Queued<R, S>,
+    graceful_token: CancellationToken,
+) where
+    S: Service<R, Response = Response, Error = Infallible> + Send + 'static,
+    S::Future: Send,
+    R: MessageHead + Send + 'static,
+{
+    let mut next = Some(first);
+
+    while let Some(Queued { msg, svc, permit }) = next {
+        tokio::select! {
+            _result = svc.oneshot(msg) => {}
+            _ = graceful_token.cancelled() => {
+                trace!(partition = key.as_str(), "signal received in partition task, dropping queued messages");
+                queues.clear(&key);
+                return;
+            }
+        }
+        drop(permit);
+
+        next = queues.pop(&key);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use async_nats::HeaderMap;
+    use bytes::Bytes;
+
+    use super::*;
+
+    fn message(subject: &str, headers: Option<HeaderMap>) -> async_nats::Message {
+        async_nats::Message {
+            subject: subject.into(),
+            reply: None,
+            payload: Bytes::new(),
+            headers,
+            status: None,
+            description: None,
+            length: 0,
+        }
+    }
+
+    #[test]
+    fn subject_tokens_key() {
+        let partitions = Partitions::subject_tokens([1, 3]);
+
+        assert_eq!(
+            Some("ws1.cs1".to_owned()),
+            partitions.key(&message("requests.ws1.x.cs1.more", None))
+        );
+        assert_eq!(None, partitions.key(&message("requests.ws1.x", None)));
+    }
+
+    #[test]
+    fn header_key() {
+        let partitions = Partitions::header("X-Key");
+        let mut headers = HeaderMap::new();
+        headers.insert("X-Key", "abc");
+
+        assert_eq!(
+            Some("abc".to_owned()),
+            partitions.key(&message("subject", Some(headers)))
+        );
+        assert_eq!(None, partitions.key(&message("subject", None)));
+    }
+
+    #[test]
+    fn queues_messages_while_partition_is_running() {
+        let queues: PartitionQueues<u8, ()> = PartitionQueues::new();
+        let queued = |msg| Queued {
+            msg,
+            svc: (),
+            permit: None,
+        };
+
+        // The first message of a partition must be run by a new task
+        assert_eq!(Some(1), queues.push("a", queued(1)).map(|q| q.msg));
+        // Later messages wait for it, in order
+        assert!(queues.push("a", queued(2)).is_none());
+        assert!(queues.push("a", queued(3)).is_none());
+        // Other partitions are independent
+        assert_eq!(Some(4), queues.push("b", queued(4)).map(|q| q.msg));
+
+        assert_eq!(Some(2), queues.pop("a").map(|q| q.msg));
+        assert_eq!(Some(3), queues.pop("a").map(|q| q.msg));
+        assert!(queues.pop("a").is_none());
+
+        // Once drained, the partition is forgotten and the next message starts a new task
+        assert_eq!(Some(5), queues.push("a", queued(5)).map(|q| q.msg));
+    }
+}
@@ -0,0 +1,26 @@
+//! Application state for processing jobs.
+
+use std::sync::Arc;
+
+use dal::DalContextBuilder;
+
+use crate::server::ServerMetadata;
+
+/// Application state.
+#[derive(Clone, Debug)]
+pub struct AppState {
+    /// Server metadata, used with telemetry
+    pub metadata: Arc<ServerMetadata>,
+    /// DAL context builder for each processing job
+    pub ctx_builder: DalContextBuilder,
+}
+
+impl AppState {
+    /// Creates a new [`AppState`].
+    pub fn new(metadata: Arc<ServerMetadata>, ctx_builder: DalContextBuilder) -> Self {
+        Self {
+            metadata,
+            ctx_builder,
+        }
+    }
+}
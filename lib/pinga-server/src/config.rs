@@ -1,6 +1,7 @@
-use std::{env, path::Path};
+use std::{collections::HashMap, env, path::Path, time::Duration};
 
 use buck2_resources::Buck2Resources;
+use dal::func::FuncKind;
 use derive_builder::Builder;
 use serde::{Deserialize, Serialize};
 use si_crypto::{
@@ -17,6 +18,7 @@ use ulid::Ulid;
 pub use si_settings::{StandardConfig, StandardConfigFile};
 
 const DEFAULT_CONCURRENCY_LIMIT: usize = 5;
+const DEFAULT_REFRESH_SCHEDULER_INTERVAL_SECS: u64 = 60;
 
 #[remain::sorted]
 #[derive(Debug, Error)]
@@ -63,6 +65,12 @@ pub struct Config {
 
     #[builder(default = "default_layer_db_config()")]
     layer_db_config: LayerDbConfig,
+
+    #[builder(default = "default_refresh_scheduler_interval()")]
+    refresh_scheduler_interval: Option<Duration>,
+
+    #[builder(default)]
+    func_run_retention: HashMap<FuncKind, Duration>,
 }
 
 impl StandardConfig for Config {
@@ -111,6 +119,17 @@ impl Config {
     pub fn layer_db_config(&self) -> &LayerDbConfig {
         &self.layer_db_config
     }
+
+    /// Gets how often the refresh scheduler checks for due refresh schedules, if it is enabled.
+    pub fn refresh_scheduler_interval(&self) -> Option<Duration> {
+        self.refresh_scheduler_interval
+    }
+
+    /// Gets how long func runs of each kind are kept before they are deleted. Func runs of kinds
+    /// without a retention period are kept forever.
+    pub fn func_run_retention(&self) -> &HashMap<FuncKind, Duration> {
+        &self.func_run_retention
+    }
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -129,6 +148,12 @@ pub struct ConfigFile {
     layer_db_config: LayerDbConfig,
     #[serde(default = "default_symmetric_crypto_config")]
     symmetric_crypto_service: SymmetricCryptoServiceConfigFile,
+    /// Seconds between refresh scheduler ticks; `0` disables the scheduler.
+    #[serde(default = "default_refresh_scheduler_interval_secs")]
+    refresh_scheduler_interval_secs: u64,
+    /// Days to keep func runs of each kind for, e.g. `{ Attribute = 7, Action = 90 }`.
+    #[serde(default)]
+    func_run_retention_days: HashMap<FuncKind, u64>,
 }
 
 impl Default for ConfigFile {
@@ -141,6 +166,8 @@ impl Default for ConfigFile {
             instance_id: random_instance_id(),
             layer_db_config: default_layer_db_config(),
             symmetric_crypto_service: default_symmetric_crypto_config(),
+            refresh_scheduler_interval_secs: default_refresh_scheduler_interval_secs(),
+            func_run_retention_days: HashMap::new(),
         }
     }
 }
@@ -163,6 +190,17 @@ impl TryFrom<ConfigFile> for Config {
         config.instance_id(value.instance_id);
         config.symmetric_crypto_service(value.symmetric_crypto_service.try_into()?);
         config.layer_db_config(value.layer_db_config);
+        config.refresh_scheduler_interval(match value.refresh_scheduler_interval_secs {
+            0 => None,
+            secs => Some(Duration::from_secs(secs)),
+        });
+        config.func_run_retention(
+            value
+                .func_run_retention_days
+                .into_iter()
+                .map(|(kind, days)| (kind, Duration::from_secs(days * 24 * 60 * 60)))
+                .collect(),
+        );
         config.build().map_err(Into::into)
     }
 }
@@ -183,6 +221,14 @@ fn default_concurrency_limit() -> usize {
     DEFAULT_CONCURRENCY_LIMIT
 }
 
+fn default_refresh_scheduler_interval_secs() -> u64 {
+    DEFAULT_REFRESH_SCHEDULER_INTERVAL_SECS
+}
+
+fn default_refresh_scheduler_interval() -> Option<Duration> {
+    Some(Duration::from_secs(DEFAULT_REFRESH_SCHEDULER_INTERVAL_SECS))
+}
+
 fn default_layer_db_config() -> LayerDbConfig {
     LayerDbConfig::default_for_service("pinga")
 }
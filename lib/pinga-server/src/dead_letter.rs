@@ -0,0 +1,104 @@
+//! The dead-letter stream, which holds jobs pinga has given up on so that they can be inspected
+//! and replayed rather than being lost.
+
+use std::{sync::Arc, time::Duration};
+
+use dal::job::{processor::NATS_HEADER_REPLY_INBOX, producer::BlockingJobError};
+use futures::future::BoxFuture;
+use naxum::{middleware::dead_letter::OnDeadLetter, Head};
+use si_data_nats::{
+    async_nats::jetstream::{context::CreateStreamError, stream},
+    jetstream::Context,
+    NatsClient, Subject,
+};
+use telemetry::prelude::*;
+
+use crate::nats_subject;
+
+const NATS_DEAD_LETTER_STREAM_NAME: &str = "PINGA_JOBS_DEAD_LETTER";
+const NATS_DEAD_LETTER_SUBJECT: &str = "pinga-jobs-dead-letter";
+
+/// How long dead-lettered jobs are kept.
+const MAX_AGE: Duration = Duration::from_secs(60 * 60 * 24 * 7);
+
+pub fn nats_dead_letter_subject(prefix: Option<&str>) -> String {
+    nats_subject(prefix, NATS_DEAD_LETTER_SUBJECT)
+}
+
+fn nats_dead_letter_stream_name(prefix: Option<&str>) -> String {
+    match prefix {
+        Some(prefix) => format!("{prefix}_{NATS_DEAD_LETTER_STREAM_NAME}"),
+        None => NATS_DEAD_LETTER_STREAM_NAME.to_owned(),
+    }
+}
+
+/// Returns the dead-letter stream and creates it if it doesn't yet exist.
+pub(crate) async fn dead_letter_stream(
+    context: &Context,
+    prefix: Option<&str>,
+) -> Result<stream::Stream, CreateStreamError> {
+    context
+        .get_or_create_stream(stream::Config {
+            name: nats_dead_letter_stream_name(prefix),
+            description: Some("Pinga jobs which could not be processed".to_owned()),
+            subjects: vec![nats_dead_letter_subject(prefix)],
+            retention: stream::RetentionPolicy::Limits,
+            discard: stream::DiscardPolicy::Old,
+            max_age: MAX_AGE,
+            ..Default::default()
+        })
+        .await
+}
+
+/// Publishes up to `max_jobs` dead-lettered jobs back to the jobs stream, so that they are run
+/// again. Returns how many jobs were replayed.
+pub async fn replay_dead_lettered_jobs(
+    nats: &NatsClient,
+    max_jobs: usize,
+) -> Result<usize, naxum::Error> {
+    let prefix = nats.metadata().subject_prefix();
+    let context = si_data_nats::jetstream::new(nats.clone());
+
+    naxum::middleware::dead_letter::replay(
+        context.as_inner(),
+        &nats_dead_letter_stream_name(prefix),
+        max_jobs,
+    )
+    .await
+}
+
+/// Lets whoever is blocking on a dead-lettered job know that it will never complete.
+#[derive(Clone, Debug)]
+pub(crate) struct ReplyOnDeadLetter {
+    nats: NatsClient,
+}
+
+impl ReplyOnDeadLetter {
+    pub(crate) fn new(nats: NatsClient) -> Self {
+        Self { nats }
+    }
+}
+
+impl OnDeadLetter for ReplyOnDeadLetter {
+    fn call(&mut self, head: Arc<Head>, reason: &str) -> BoxFuture<'static, ()> {
+        let nats = self.nats.clone();
+        let reply_message: Result<(), _> = Err(BlockingJobError::JobExecution(reason.to_owned()));
+
+        Box::pin(async move {
+            let Some(reply_inbox) = head
+                .headers
+                .as_ref()
+                .and_then(|headers| headers.get(NATS_HEADER_REPLY_INBOX))
+                .map(|value| Subject::from(value.as_str()))
+            else {
+                return;
+            };
+
+            if let Ok(message) = serde_json::to_vec(&reply_message) {
+                if let Err(err) = nats.publish(reply_inbox, message.into()).await {
+                    error!(error = ?err, "Unable to notify spawning job of dead-lettered job");
+                }
+            }
+        })
+    }
+}
@@ -0,0 +1,85 @@
+//! A background task that periodically deletes func runs, and their logs, which are older than the
+//! retention period configured for their kind of func.
+//!
+//! Every pinga instance runs the task. Deleting is idempotent, so instances racing each other only
+//! cost some wasted queries.
+
+use std::{collections::HashMap, time::Duration};
+
+use chrono::Utc;
+use dal::{func::FuncKind, DalLayerDb};
+use si_layer_cache::LayerDbError;
+use telemetry::prelude::*;
+use tokio::{select, sync::watch, time};
+
+/// How often expired func runs are looked for.
+const TICK_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// How many func runs are deleted per query, so that a large backlog does not hold locks on the
+/// tables for long.
+const BATCH_SIZE: i64 = 1000;
+
+pub(crate) async fn func_run_retention_task(
+    layer_db: DalLayerDb,
+    retention: HashMap<FuncKind, Duration>,
+    mut shutdown_watch_rx: watch::Receiver<()>,
+) {
+    info!(?retention, "booting func run retention");
+
+    let mut ticker = time::interval(TICK_INTERVAL);
+    ticker.set_missed_tick_behavior(time::MissedTickBehavior::Delay);
+
+    loop {
+        select! {
+            _ = shutdown_watch_rx.changed() => {
+                info!("func run retention received shutdown, stopping");
+                return;
+            }
+            _ = ticker.tick() => {
+                for (function_kind, max_age) in &retention {
+                    if let Err(err) = delete_expired(&layer_db, *function_kind, *max_age).await {
+                        error!(
+                            error = ?err,
+                            %function_kind,
+                            "failed to delete expired func runs"
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[instrument(
+    name = "pinga.func_run_retention.delete_expired",
+    level = "info",
+    skip(layer_db)
+)]
+async fn delete_expired(
+    layer_db: &DalLayerDb,
+    function_kind: FuncKind,
+    max_age: Duration,
+) -> Result<(), LayerDbError> {
+    let Ok(max_age) = chrono::Duration::from_std(max_age) else {
+        // A retention period too long to represent never expires anything
+        return Ok(());
+    };
+    let cutoff = Utc::now() - max_age;
+
+    let mut deleted = 0;
+    loop {
+        let deleted_in_batch = layer_db
+            .func_run()
+            .delete_expired_batch(function_kind.into(), cutoff, BATCH_SIZE)
+            .await?;
+        deleted += deleted_in_batch;
+        if deleted_in_batch < BATCH_SIZE as u64 {
+            break;
+        }
+    }
+
+    if deleted > 0 {
+        info!(deleted, "deleted expired func runs");
+    }
+    Ok(())
+}
@@ -0,0 +1,245 @@
+//! Application handlers for processing jobs.
+
+use std::{
+    result,
+    sync::atomic::{self, AtomicUsize},
+    time::Duration,
+};
+
+use dal::{
+    job::{
+        consumer::{JobCompletionState, JobConsumer, JobConsumerError, JobInfo, RetryBackoff},
+        definition::{compute_validation::ComputeValidation, ActionJob, DependentValuesUpdate},
+        processor::NATS_HEADER_REPLY_INBOX,
+        producer::{BlockingJobError, BlockingJobResult},
+    },
+    DalContextBuilder, JobFailure, JobFailureError, TransactionsError,
+};
+use naxum::{
+    extract::State,
+    middleware::dead_letter::{Backoff, DeliveryInfo},
+    response::{IntoResponse, Response},
+};
+use si_data_nats::{InnerMessage, Subject};
+use telemetry::prelude::*;
+use telemetry_utils::metric;
+use thiserror::Error;
+
+use crate::app_state::AppState;
+
+/// An error that can occur when processing a job message.
+#[remain::sorted]
+#[derive(Debug, Error)]
+pub enum HandlerError {
+    /// When a job can't be run at all, e.g. because it can't be deserialized
+    #[error("invalid job: {0}")]
+    InvalidJob(#[source] JobConsumerError),
+    /// When a job asks to be retried and has retries left
+    #[error("job {0} asked to be retried in {1:?}")]
+    Retry(String, Duration),
+}
+
+type Result<T> = result::Result<T, HandlerError>;
+
+impl IntoResponse for HandlerError {
+    fn into_response(self) -> Response {
+        match self {
+            // Running the job again would fail the same way, so it is dead-lettered
+            Self::InvalidJob(_) => {
+                error!(si.error.message = ?self, "failed to process message");
+                Response::client_error()
+            }
+            Self::Retry(_, delay) => {
+                debug!(si.error.message = ?self, "job will be redelivered");
+                Response::retry_after(delay)
+            }
+        }
+    }
+}
+
+/// How long to wait before redelivering a job which asked to be retried with an exponential
+/// backoff.
+const RETRY_BACKOFF: Backoff = Backoff::Exponential {
+    initial: Duration::from_millis(100),
+    max: Duration::from_secs(30),
+};
+
+static CONCURRENT_TASKS: AtomicUsize = AtomicUsize::new(0);
+
+/// Process a job.
+///
+/// The job is acknowledged once it has run, whether it succeeded or not, unless it asks to be
+/// retried. Jobs which can't be run at all are dead-lettered.
+pub async fn process_request(State(state): State<AppState>, msg: InnerMessage) -> Result<()> {
+    let delivered = msg
+        .reply
+        .as_ref()
+        .and_then(|reply| DeliveryInfo::from_reply(reply.as_str()))
+        .map(|info| info.delivered)
+        .unwrap_or(1);
+    let reply_inbox = msg
+        .headers
+        .as_ref()
+        .and_then(|headers| headers.get(NATS_HEADER_REPLY_INBOX))
+        .map(|value| Subject::from(value.as_str()));
+
+    let (job_info, job) = job_from_payload(&msg.payload).map_err(HandlerError::InvalidJob)?;
+
+    let concurrency_count = CONCURRENT_TASKS.fetch_add(1, atomic::Ordering::Relaxed) + 1;
+    metric!(counter.pinga.concurrency_count = 1);
+
+    let result = execute_job(&state, &job_info, job, delivered, concurrency_count).await;
+
+    CONCURRENT_TASKS.fetch_sub(1, atomic::Ordering::Relaxed);
+    metric!(counter.pinga.concurrency_count = -1);
+
+    // A job being retried is still running as far as whoever is blocking on it is concerned
+    let reply_message = result?;
+    reply(&state.ctx_builder, reply_inbox, reply_message).await;
+
+    Ok(())
+}
+
+#[instrument(
+    name = "execute_job",
+    level = "info",
+    skip_all,
+    fields(
+        job.blocking = job_info.blocking,
+        job.delivered = delivered,
+        job.id = job_info.id,
+        job.instance = state.metadata.job_instance(),
+        job.invoked_name = job_info.kind,
+        job.invoked_provider = state.metadata.job_invoked_provider(),
+        job.trigger = "jetstream",
+        job.visibility = ?job_info.visibility,
+        concurrency.count = concurrency_count,
+        otel.status_code = Empty,
+        otel.status_message = Empty,
+    )
+)]
+async fn execute_job(
+    state: &AppState,
+    job_info: &JobInfo,
+    job: Box<dyn JobConsumer + Send + Sync>,
+    delivered: u64,
+    concurrency_count: usize,
+) -> Result<BlockingJobResult> {
+    let span = Span::current();
+
+    let mut ctx_builder = state.ctx_builder.clone();
+    if job_info.blocking {
+        ctx_builder.set_blocking();
+    }
+
+    info!("Processing job");
+
+    let result = match job.run_once(ctx_builder.clone()).await {
+        Ok(JobCompletionState::Done) => Ok(()),
+        // Retrying by redelivering the job, rather than in memory, means a retry survives the
+        // job's pinga instance going away
+        Ok(JobCompletionState::Retry { limit, backoff }) => {
+            let retries = u32::try_from(delivered.saturating_sub(1)).unwrap_or(u32::MAX);
+            if retries < limit {
+                let delay = match backoff {
+                    RetryBackoff::Exponential => RETRY_BACKOFF.delay(delivered),
+                    RetryBackoff::None => Duration::ZERO,
+                };
+                return Err(HandlerError::Retry(job_info.kind.clone(), delay));
+            }
+            Err(JobConsumerError::RetriesFailed(job.type_name(), retries))
+        }
+        Err(err) => Err(err),
+    };
+
+    info!("Finished processing job");
+
+    match result {
+        Ok(()) => {
+            span.record_ok();
+            Ok(Ok(()))
+        }
+        Err(err) => {
+            error!(
+                error = ?err,
+                job.invocation_id = %job_info.id,
+                job.instance = state.metadata.job_instance(),
+                "job execution failed"
+            );
+            let reply_message = Err(BlockingJobError::JobExecution(err.to_string()));
+
+            if let Err(record_err) = record_job_failure(ctx_builder, job, &err).await {
+                error!(error = ?record_err, "failed to record job failure");
+            }
+            span.record_err(err);
+
+            Ok(reply_message)
+        }
+    }
+}
+
+fn job_from_payload(
+    payload: &[u8],
+) -> result::Result<(JobInfo, Box<dyn JobConsumer + Send + Sync>), JobConsumerError> {
+    let job_info: JobInfo = serde_json::from_slice(payload)?;
+
+    let job = match job_info.kind.as_str() {
+        stringify!(DependentValuesUpdate) => {
+            Box::new(DependentValuesUpdate::try_from(job_info.clone())?)
+                as Box<dyn JobConsumer + Send + Sync>
+        }
+        stringify!(ActionJob) => {
+            Box::new(ActionJob::try_from(job_info.clone())?) as Box<dyn JobConsumer + Send + Sync>
+        }
+        stringify!(ComputeValidation) => Box::new(ComputeValidation::try_from(job_info.clone())?)
+            as Box<dyn JobConsumer + Send + Sync>,
+        kind => return Err(JobConsumerError::UnknownJobKind(kind.to_owned())),
+    };
+
+    Ok((job_info, job))
+}
+
+async fn reply(
+    ctx_builder: &DalContextBuilder,
+    reply_inbox: Option<Subject>,
+    reply_message: BlockingJobResult,
+) {
+    if let Some(reply_inbox) = reply_inbox {
+        if let Ok(message) = serde_json::to_vec(&reply_message) {
+            if let Err(err) = ctx_builder
+                .nats_conn()
+                .publish(reply_inbox, message.into())
+                .await
+            {
+                error!(error = ?err, "Unable to notify spawning job of blocking job completion");
+            };
+        }
+    }
+}
+
+#[remain::sorted]
+#[derive(Debug, Error)]
+enum RecordJobFailureError {
+    #[error(transparent)]
+    JobFailure(#[from] JobFailureError),
+    #[error(transparent)]
+    Transactions(#[from] TransactionsError),
+}
+
+async fn record_job_failure(
+    ctx_builder: DalContextBuilder,
+    job: Box<dyn JobConsumer + Send + Sync>,
+    err: &JobConsumerError,
+) -> result::Result<(), RecordJobFailureError> {
+    warn!(error = ?err, "job execution failed, recording a job failure to the database");
+
+    let access_builder = job.access_builder();
+    let visibility = job.visibility();
+    let ctx = ctx_builder.build(access_builder.build(visibility)).await?;
+
+    JobFailure::new(&ctx, job.type_name(), err.to_string()).await?;
+
+    ctx.commit().await?;
+
+    Ok(())
+}
@@ -1,4 +1,9 @@
+mod app_state;
 mod config;
+mod dead_letter;
+mod func_run_retention;
+mod handlers;
+mod refresh_scheduler;
 pub mod server;
 
 pub use crate::{
@@ -6,11 +11,12 @@ pub use crate::{
         detect_and_configure_development, Config, ConfigBuilder, ConfigError, ConfigFile,
         StandardConfig, StandardConfigFile,
     },
+    dead_letter::{nats_dead_letter_subject, replay_dead_lettered_jobs},
     server::{Server, ServerError},
 };
 
 const NATS_JOBS_DEFAULT_SUBJECT: &str = "pinga-jobs";
-const NATS_JOBS_DEFAULT_QUEUE: &str = "pinga";
+const NATS_JOBS_CONSUMER_NAME: &str = "pinga-jobs";
 
 pub fn nats_jobs_subject(prefix: Option<&str>) -> String {
     nats_subject(prefix, NATS_JOBS_DEFAULT_SUBJECT)
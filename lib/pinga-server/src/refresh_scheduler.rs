@@ -0,0 +1,108 @@
+//! A background task that periodically enqueues refresh actions for workspaces with an enabled
+//! [`RefreshSchedule`].
+//!
+//! Every pinga instance runs a scheduler, but each due schedule is claimed atomically in the
+//! database, so a workspace is only refreshed once per interval regardless of how many instances
+//! are running. The claim is committed alongside the actions, which are enqueued on HEAD and
+//! dispatched by the rebaser once the enqueueing commit has been applied.
+
+use std::time::Duration;
+
+use dal::{
+    action::refresh_schedule::{RefreshSchedule, RefreshScheduleError},
+    AccessBuilder, DalContextBuilder, HistoryActor, Tenancy, TransactionsError, WorkspacePk,
+};
+use telemetry::prelude::*;
+use thiserror::Error;
+use tokio::{select, sync::watch, time};
+
+#[remain::sorted]
+#[derive(Debug, Error)]
+pub enum RefreshSchedulerError {
+    #[error("refresh schedule error: {0}")]
+    RefreshSchedule(#[from] RefreshScheduleError),
+    #[error("transactions error: {0}")]
+    Transactions(#[from] TransactionsError),
+}
+
+type RefreshSchedulerResult<T> = Result<T, RefreshSchedulerError>;
+
+pub(crate) async fn refresh_scheduler_task(
+    ctx_builder: DalContextBuilder,
+    tick_interval: Duration,
+    mut shutdown_watch_rx: watch::Receiver<()>,
+) {
+    info!(?tick_interval, "booting refresh scheduler");
+
+    let mut ticker = time::interval(tick_interval);
+    ticker.set_missed_tick_behavior(time::MissedTickBehavior::Delay);
+
+    loop {
+        select! {
+            _ = shutdown_watch_rx.changed() => {
+                info!("refresh scheduler received shutdown, stopping");
+                return;
+            }
+            _ = ticker.tick() => {
+                if let Err(err) = enqueue_due_refreshes(&ctx_builder).await {
+                    error!(error = ?err, "failed to enqueue scheduled refreshes");
+                }
+            }
+        }
+    }
+}
+
+#[instrument(
+    name = "pinga.refresh_scheduler.enqueue_due_refreshes",
+    level = "debug",
+    skip_all
+)]
+async fn enqueue_due_refreshes(ctx_builder: &DalContextBuilder) -> RefreshSchedulerResult<()> {
+    let ctx = ctx_builder.build_default().await?;
+    let due = RefreshSchedule::list_due(&ctx).await?;
+    ctx.rollback().await?;
+
+    for schedule in due {
+        if let Err(err) = enqueue_for_workspace(ctx_builder, schedule.workspace_pk).await {
+            error!(
+                error = ?err,
+                si.workspace.pk = %schedule.workspace_pk,
+                "failed to enqueue scheduled refresh for workspace"
+            );
+        }
+    }
+
+    Ok(())
+}
+
+#[instrument(
+    name = "pinga.refresh_scheduler.enqueue_for_workspace",
+    level = "info",
+    skip(ctx_builder),
+    fields(si.action.count = Empty)
+)]
+async fn enqueue_for_workspace(
+    ctx_builder: &DalContextBuilder,
+    workspace_pk: WorkspacePk,
+) -> RefreshSchedulerResult<()> {
+    let ctx = ctx_builder
+        .build_head(AccessBuilder::new(
+            Tenancy::new(workspace_pk),
+            HistoryActor::SystemInit,
+        ))
+        .await?;
+
+    // Another scheduler may have claimed the schedule since it was listed, in which case there is
+    // nothing to do.
+    let Some(action_ids) = RefreshSchedule::claim_and_enqueue(&ctx).await? else {
+        return Ok(());
+    };
+    Span::current().record("si.action.count", action_ids.len());
+
+    // Committing with a rebase is what gets the actions dispatched, since the rebaser dispatches
+    // actions after every successful rebase onto HEAD. The claim is committed in the same
+    // transaction, so returning early with an error leaves the schedule due for the next tick.
+    ctx.commit().await?;
+
+    Ok(())
+}
@@ -1,48 +1,56 @@
-use std::{
-    future::IntoFuture,
-    io,
-    sync::atomic::{self, AtomicUsize},
-    sync::Arc,
-};
+use std::{collections::HashMap, future::IntoFuture, io, sync::Arc, time::Duration};
 use telemetry_utils::metric;
 
 use dal::{
-    feature_flags::FeatureFlagService, job::definition::compute_validation::ComputeValidation,
+    feature_flags::FeatureFlagService, func::FuncKind, job::processor::pinga_jobs_stream,
+    DalContext, InitializationError, JobQueueProcessor, NatsProcessor, ServicesContext,
 };
-use dal::{
-    job::{
-        consumer::{JobConsumer, JobConsumerError, JobInfo},
-        definition::{ActionJob, DependentValuesUpdate},
-        producer::BlockingJobError,
+use naxum::{
+    handler::Handler,
+    middleware::{
+        dead_letter::{Backoff, DeadLetterLayer},
+        trace::{DefaultMakeSpan, DefaultOnRequest, DefaultOnResponse, TraceLayer},
     },
-    DalContext, DalContextBuilder, InitializationError, JobFailure, JobFailureError,
-    JobQueueProcessor, NatsProcessor, ServicesContext, TransactionsError,
+    ServiceExt as _,
 };
-use futures::{FutureExt, Stream, StreamExt};
-use nats_subscriber::{Request, SubscriberError};
 use si_crypto::{
     SymmetricCryptoError, SymmetricCryptoService, SymmetricCryptoServiceConfig,
     VeritechCryptoConfig, VeritechEncryptionKey, VeritechEncryptionKeyError,
 };
-use si_data_nats::{NatsClient, NatsConfig, NatsError};
+use si_data_nats::{
+    async_nats::jetstream::{self, context::CreateStreamError},
+    NatsClient, NatsConfig, NatsError,
+};
 use si_data_pg::{PgPool, PgPoolConfig, PgPoolError};
 use si_layer_cache::{error::LayerDbError, LayerDb};
-use stream_cancel::StreamExt as StreamCancelStreamExt;
 use telemetry::prelude::*;
 use thiserror::Error;
 use tokio::{
     signal::unix,
-    sync::{
-        mpsc::{self, UnboundedReceiver, UnboundedSender},
-        oneshot, watch,
-    },
+    sync::{mpsc, oneshot, watch},
     task,
 };
-use tokio_stream::wrappers::UnboundedReceiverStream;
 use tokio_util::{sync::CancellationToken, task::TaskTracker};
+use tower::ServiceBuilder;
 use veritech_client::Client as VeritechClient;
 
-use crate::{nats_jobs_subject, Config, NATS_JOBS_DEFAULT_QUEUE};
+use crate::{
+    app_state::AppState,
+    dead_letter::{dead_letter_stream, nats_dead_letter_subject, ReplyOnDeadLetter},
+    func_run_retention::func_run_retention_task,
+    handlers,
+    refresh_scheduler::refresh_scheduler_task,
+    Config, NATS_JOBS_CONSUMER_NAME,
+};
+
+/// How many times a job is delivered before it is dead-lettered.
+const MAX_DELIVERIES: u64 = 5;
+
+/// How long to wait before redelivering a job which failed to run.
+const BACKOFF: Backoff = Backoff::Exponential {
+    initial: Duration::from_secs(30),
+    max: Duration::from_secs(15 * 60),
+};
 
 #[remain::sorted]
 #[derive(Debug, Error)]
@@ -51,30 +59,26 @@ pub enum ServerError {
     EncryptionKey(#[from] VeritechEncryptionKeyError),
     #[error(transparent)]
     Initialization(#[from] InitializationError),
-    #[error(transparent)]
-    JobConsumer(#[from] JobConsumerError),
-    #[error(transparent)]
-    JobFailure(#[from] Box<JobFailureError>),
+    #[error("jetstream consumer error: {0}")]
+    JsConsumer(#[from] jetstream::stream::ConsumerError),
+    #[error("consumer stream error: {0}")]
+    JsConsumerStream(#[from] jetstream::consumer::StreamError),
+    #[error("jetstream create stream error: {0}")]
+    JsCreateStream(#[from] CreateStreamError),
     #[error("layer cache error: {0}")]
     LayerCache(#[from] LayerDbError),
     #[error(transparent)]
     Nats(#[from] NatsError),
+    #[error("naxum error: {0}")]
+    Naxum(#[source] io::Error),
     #[error(transparent)]
     PgPool(#[from] Box<PgPoolError>),
-    #[error(transparent)]
-    SerdeJson(#[from] serde_json::Error),
     #[error("failed to setup signal handler")]
     Signal(#[source] io::Error),
     #[error(transparent)]
-    Subscriber(#[from] SubscriberError),
-    #[error(transparent)]
     SymmetricCryptoService(#[from] SymmetricCryptoError),
-    #[error(transparent)]
-    Transactions(#[from] Box<TransactionsError>),
     #[error("unable to connect to database: {0}")]
     UnableToConnectToDatabase(Box<PgPoolError>),
-    #[error("unknown job kind {0}")]
-    UnknownJobKind(String),
 }
 
 impl From<PgPoolError> for ServerError {
@@ -83,22 +87,14 @@ impl From<PgPoolError> for ServerError {
     }
 }
 
-impl From<JobFailureError> for ServerError {
-    fn from(e: JobFailureError) -> Self {
-        Self::JobFailure(Box::new(e))
-    }
-}
-
-impl From<TransactionsError> for ServerError {
-    fn from(e: TransactionsError) -> Self {
-        Self::Transactions(Box::new(e))
-    }
-}
-
 type Result<T> = std::result::Result<T, ServerError>;
 
 pub struct Server {
     concurrency_limit: usize,
+    /// How often to check for due refresh schedules, or `None` if the scheduler is disabled.
+    refresh_scheduler_interval: Option<Duration>,
+    /// How long func runs of each kind are kept before they are deleted.
+    func_run_retention: HashMap<FuncKind, Duration>,
     services_context: ServicesContext,
     /// An internal shutdown watch receiver handle which can be provided to internal tasks which
     /// want to be notified when a shutdown event is in progress.
@@ -149,6 +145,8 @@ impl Server {
         Self::from_services(
             config.instance_id().to_string(),
             config.concurrency(),
+            config.refresh_scheduler_interval(),
+            config.func_run_retention().clone(),
             services_context,
         )
     }
@@ -157,6 +155,8 @@ impl Server {
     pub fn from_services(
         instance_id: impl Into<String>,
         concurrency_limit: usize,
+        refresh_scheduler_interval: Option<Duration>,
+        func_run_retention: HashMap<FuncKind, Duration>,
         services_context: ServicesContext,
     ) -> Result<Self> {
         // An mpsc channel which can be used to externally shut down the server.
@@ -179,6 +179,8 @@ impl Server {
         metric!(monotonic_counter.pinga.concurrency_limit = concurrency_limit);
         Ok(Server {
             concurrency_limit,
+            refresh_scheduler_interval,
+            func_run_retention,
             services_context,
             shutdown_watch_rx,
             external_shutdown_tx,
@@ -195,23 +197,66 @@ impl Server {
             .await
             .map_err(|e| ServerError::UnableToConnectToDatabase(Box::new(e)))?;
 
-        let (tx, rx) = mpsc::unbounded_channel();
+        // Spawn a task to periodically enqueue refresh actions for workspaces with a schedule
+        if let Some(refresh_scheduler_interval) = self.refresh_scheduler_interval {
+            drop(task::spawn(refresh_scheduler_task(
+                DalContext::builder(self.services_context.clone(), false),
+                refresh_scheduler_interval,
+                self.shutdown_watch_rx.clone(),
+            )));
+        }
+
+        // Spawn a task to periodically delete func runs which have outlived their retention
+        if !self.func_run_retention.is_empty() {
+            drop(task::spawn(func_run_retention_task(
+                self.services_context.layer_db().clone(),
+                self.func_run_retention.clone(),
+                self.shutdown_watch_rx.clone(),
+            )));
+        }
 
-        // Span a task to receive and process jobs from the unbounded channel
-        drop(task::spawn(process_job_requests_task(
-            rx,
-            self.concurrency_limit,
-        )));
+        let nats = self.services_context.nats_conn().clone();
+        let prefix = nats.metadata().subject_prefix().map(ToOwned::to_owned);
+        let context = si_data_nats::jetstream::new(nats.clone());
 
-        // Run "the main loop" which pulls message from a subscription off NATS and forwards each
-        // request to an unbounded channel
-        receive_job_requests_task(
-            tx,
+        let incoming = pinga_jobs_stream(&context, prefix.as_deref())
+            .await?
+            .create_consumer(Self::consumer_config())
+            .await?
+            .messages()
+            .await?;
+        dead_letter_stream(&context, prefix.as_deref()).await?;
+
+        let state = AppState::new(
             self.metadata,
-            self.services_context,
-            self.shutdown_watch_rx,
-        )
-        .await;
+            DalContext::builder(self.services_context, false),
+        );
+
+        let app = ServiceBuilder::new()
+            .concurrency_limit(self.concurrency_limit)
+            .layer(
+                TraceLayer::new()
+                    .make_span_with(DefaultMakeSpan::new().level(Level::TRACE))
+                    .on_request(DefaultOnRequest::new().level(Level::TRACE))
+                    .on_response(DefaultOnResponse::new().level(Level::TRACE)),
+            )
+            .layer(
+                DeadLetterLayer::new(nats_dead_letter_subject(prefix.as_deref()))
+                    .max_deliveries(MAX_DELIVERIES)
+                    .backoff(BACKOFF)
+                    .on_dead_letter(ReplyOnDeadLetter::new(nats)),
+            )
+            .service(handlers::process_request.with_state(state));
+
+        // Process jobs off the stream until a shutdown is signaled. Jobs which are still running
+        // when that happens are never acked, so they are redelivered to another pinga instance.
+        let mut shutdown_watch_rx = self.shutdown_watch_rx;
+        naxum::serve(incoming, app.into_make_service())
+            .with_graceful_shutdown(async move {
+                let _ = shutdown_watch_rx.changed().await;
+            })
+            .await
+            .map_err(ServerError::Naxum)?;
 
         let _ = self.graceful_shutdown_rx.await;
         info!("received and processed graceful shutdown, terminating server instance");
@@ -227,6 +272,21 @@ impl Server {
         }
     }
 
+    #[inline]
+    fn consumer_config() -> jetstream::consumer::pull::Config {
+        jetstream::consumer::pull::Config {
+            durable_name: Some(NATS_JOBS_CONSUMER_NAME.to_owned()),
+            description: Some("Pinga jobs".to_owned()),
+            // Jobs which aren't acked in time are redelivered with these delays. The dead-letter
+            // middleware acks running jobs with progress, so only jobs whose instance went away
+            // mid-run time out.
+            backoff: (1..=MAX_DELIVERIES)
+                .map(|delivered| BACKOFF.delay(delivered))
+                .collect(),
+            ..Default::default()
+        }
+    }
+
     #[instrument(name = "pinga.init.load_encryption_key", level = "info", skip_all)]
     async fn load_encryption_key(
         crypto_config: VeritechCryptoConfig,
@@ -280,6 +340,16 @@ pub struct ServerMetadata {
     job_invoked_provider: &'static str,
 }
 
+impl ServerMetadata {
+    pub fn job_instance(&self) -> &str {
+        &self.job_instance
+    }
+
+    pub fn job_invoked_provider(&self) -> &'static str {
+        self.job_invoked_provider
+    }
+}
+
 pub struct PingaShutdownHandle {
     shutdown_tx: mpsc::Sender<ShutdownSource>,
 }
@@ -304,257 +374,6 @@ impl Default for ShutdownSource {
     }
 }
 
-pub struct JobItem {
-    metadata: Arc<ServerMetadata>,
-    ctx_builder: DalContextBuilder,
-    request: Result<Request<JobInfo>>,
-}
-
-pub struct Subscriber;
-
-impl Subscriber {
-    pub async fn jobs(
-        metadata: Arc<ServerMetadata>,
-        services_context: ServicesContext,
-    ) -> Result<impl Stream<Item = JobItem>> {
-        let nats = services_context.nats_conn().clone();
-
-        let subject = nats_jobs_subject(nats.metadata().subject_prefix());
-        debug!(
-            messaging.destination.name = subject.as_str(),
-            "subscribing for job requests"
-        );
-
-        // Make non blocking context here, and update it for each job
-        // Since the any blocking job should block on its child jobs
-        let ctx_builder = DalContext::builder(services_context, false);
-
-        Ok(nats_subscriber::Subscriber::create(subject)
-            .queue_name(NATS_JOBS_DEFAULT_QUEUE)
-            .start(&nats)
-            .await?
-            .map(move |request| JobItem {
-                metadata: metadata.clone(),
-                ctx_builder: ctx_builder.clone(),
-                request: request.map_err(Into::into),
-            }))
-    }
-}
-
-async fn receive_job_requests_task(
-    tx: UnboundedSender<JobItem>,
-    metadata: Arc<ServerMetadata>,
-    services_context: ServicesContext,
-    shutdown_watch_rx: watch::Receiver<()>,
-) {
-    if let Err(err) = receive_job_requests(tx, metadata, services_context, shutdown_watch_rx).await
-    {
-        warn!(error = ?err, "processing job requests failed");
-    }
-}
-
-async fn receive_job_requests(
-    tx: UnboundedSender<JobItem>,
-    metadata: Arc<ServerMetadata>,
-    services_context: ServicesContext,
-    mut shutdown_watch_rx: watch::Receiver<()>,
-) -> Result<()> {
-    let mut requests = Subscriber::jobs(metadata, services_context)
-        .await?
-        .take_until_if(Box::pin(shutdown_watch_rx.changed().map(|_| true)));
-
-    // Forward each request off the stream to a consuming task via an *unbounded* channel so we
-    // buffer requests until we run out of memory. Have fun!
-    while let Some(job) = requests.next().await {
-        if let Err(_job) = tx.send(job) {
-            error!("process_job_requests rx has already closed");
-        }
-    }
-
-    Ok(())
-}
-
-static CONCURRENT_TASKS: AtomicUsize = AtomicUsize::new(0);
-
-#[instrument(level = "info", skip(rx))]
-async fn process_job_requests_task(rx: UnboundedReceiver<JobItem>, concurrency_limit: usize) {
-    UnboundedReceiverStream::new(rx)
-        .for_each_concurrent(concurrency_limit, |job| async move {
-            let concurrency_count = CONCURRENT_TASKS.fetch_add(1, atomic::Ordering::Relaxed) + 1;
-            metric!(counter.pinga.concurrency_count = 1);
-
-            let span = Span::current();
-            span.record("concurrency.count", concurrency_count);
-
-            // Got the next message from the subscriber
-            trace!("pulled request into an available concurrent task");
-
-            match job.request {
-                Ok(request) => {
-                    // Spawn a task and process the request
-                    let join_handle = task::spawn(execute_job_task(
-                        job.metadata,
-                        job.ctx_builder,
-                        request,
-                        concurrency_count,
-                        concurrency_limit,
-                    ));
-                    if let Err(err) = join_handle.await {
-                        // NOTE(fnichol): This likely happens when there is contention or
-                        // an error in the Tokio runtime so we will be loud and log an
-                        // error under the assumptions that 1) this event rarely
-                        // happens and 2) the task code did not contribute to trigger
-                        // the `JoinError`.
-                        error!(
-                            error = ?err,
-                            "execute-job-task failed to execute to completion"
-                        );
-                    };
-                }
-                Err(err) => {
-                    warn!(error = ?err, "next job request had an error, job will not be executed");
-                }
-            }
-
-            let concurrency_count = CONCURRENT_TASKS.fetch_sub(1, atomic::Ordering::Relaxed) - 1;
-            metric!(counter.pinga.concurrency_count = -1);
-
-            let span = Span::current();
-            span.record("concurrency.count", concurrency_count);
-        })
-        .await;
-}
-
-#[instrument(
-    name = "execute_job_task",
-    parent = &request.process_span,
-    level = "info",
-    skip_all,
-    fields(
-        job.blocking = request.payload.blocking,
-        job.id = request.payload.id,
-        job.instance = metadata.job_instance,
-        job.invoked_args = Empty,
-        job.invoked_name = request.payload.kind,
-        job.invoked_provider = metadata.job_invoked_provider,
-        job.trigger = "pubsub",
-        job.visibility = ?request.payload.visibility,
-        concurrency.count = concurrency_count,
-        concurrency.limit = concurrency_limit,
-        concurrency.at_capacity = concurrency_limit == concurrency_count,
-        messaging.destination = Empty,
-        messaging.destination_kind = "topic",
-        messaging.operation = "process",
-        otel.kind = SpanKind::Consumer.as_str(),
-        otel.name = Empty,
-        otel.status_code = Empty,
-        otel.status_message = Empty,
-    )
-)]
-async fn execute_job_task(
-    metadata: Arc<ServerMetadata>,
-    ctx_builder: DalContextBuilder,
-    request: Request<JobInfo>,
-    concurrency_count: usize,
-    concurrency_limit: usize,
-) {
-    let span = Span::current();
-    let id = request.payload.id.clone();
-
-    let arg_str = serde_json::to_string(&request.payload.arg)
-        .unwrap_or_else(|_| "arg failed to serialize".to_string());
-
-    let messaging_destination = request.subject;
-
-    span.record("job.invoked_arg", arg_str);
-    span.record("messaging.destination", messaging_destination.as_str());
-    span.record(
-        "otel.name",
-        format!("{} process", &messaging_destination).as_str(),
-    );
-
-    let maybe_reply_channel = request.reply.clone();
-    let reply_message = match execute_job(ctx_builder.clone(), request.payload).await {
-        Ok(_) => {
-            span.record_ok();
-            Ok(())
-        }
-        Err(err) => {
-            error!(
-                error = ?err,
-                job.invocation_id = %id,
-                job.instance = &metadata.job_instance,
-                "job execution failed"
-            );
-            let new_err = Err(BlockingJobError::JobExecution(err.to_string()));
-            span.record_err(err);
-
-            new_err
-        }
-    };
-
-    if let Some(reply_channel) = maybe_reply_channel {
-        if let Ok(message) = serde_json::to_vec(&reply_message) {
-            if let Err(err) = ctx_builder
-                .nats_conn()
-                .publish(reply_channel, message.into())
-                .await
-            {
-                error!(error = ?err, "Unable to notify spawning job of blocking job completion");
-            };
-        }
-    }
-}
-
-async fn execute_job(mut ctx_builder: DalContextBuilder, job_info: JobInfo) -> Result<()> {
-    if job_info.blocking {
-        ctx_builder.set_blocking();
-    }
-
-    let job = match job_info.kind.as_str() {
-        stringify!(DependentValuesUpdate) => {
-            Box::new(DependentValuesUpdate::try_from(job_info.clone())?)
-                as Box<dyn JobConsumer + Send + Sync>
-        }
-        stringify!(ActionJob) => {
-            Box::new(ActionJob::try_from(job_info.clone())?) as Box<dyn JobConsumer + Send + Sync>
-        }
-        stringify!(ComputeValidation) => Box::new(ComputeValidation::try_from(job_info.clone())?)
-            as Box<dyn JobConsumer + Send + Sync>,
-        kind => return Err(ServerError::UnknownJobKind(kind.to_owned())),
-    };
-
-    info!("Processing job");
-
-    if let Err(err) = job.run_job(ctx_builder.clone()).await {
-        // The missing part is this, should we execute subsequent jobs if the one they depend on fail or not?
-        record_job_failure(ctx_builder, job, err).await?;
-    }
-
-    info!("Finished processing job");
-
-    Ok(())
-}
-
-#[allow(dead_code)]
-async fn record_job_failure(
-    ctx_builder: DalContextBuilder,
-    job: Box<dyn JobConsumer + Send + Sync>,
-    err: JobConsumerError,
-) -> Result<()> {
-    warn!(error = ?err, "job execution failed, recording a job failure to the database");
-
-    let access_builder = job.access_builder();
-    let visibility = job.visibility();
-    let ctx = ctx_builder.build(access_builder.build(visibility)).await?;
-
-    JobFailure::new(&ctx, job.type_name(), err.to_string()).await?;
-
-    ctx.commit().await?;
-
-    Err(err.into())
-}
-
 fn prepare_graceful_shutdown(
     mut external_shutdown_rx: mpsc::Receiver<ShutdownSource>,
     shutdown_watch_tx: watch::Sender<()>,
@@ -1,5 +1,9 @@
-//! A micro-service which processes a stream of rebaser requests for a given change set in a
-//! workspace.
+//! Processing of rebaser requests across change sets.
+//!
+//! Each change set has a work queue of rebaser requests, which a [`ChangeSetRequestsTask`]
+//! forwards to a single stream served by [`serve_requests`]. Requests are partitioned by change
+//! set so that the requests of a change set are processed one at a time, in order, while requests
+//! of different change sets are processed concurrently.
 
 use std::{
     fmt,
@@ -10,28 +14,87 @@ use std::{
 };
 
 use dal::DalContextBuilder;
-use naxum::handler::Handler;
-use naxum::middleware::ack::AckLayer;
-use naxum::middleware::trace::{DefaultMakeSpan, DefaultOnRequest, DefaultOnResponse, TraceLayer};
-use naxum::ServiceExt;
+use futures::{stream, StreamExt};
+use naxum::{
+    handler::Handler,
+    middleware::{
+        ack::AckLayer,
+        trace::{DefaultMakeSpan, DefaultOnRequest, DefaultOnResponse, TraceLayer},
+    },
+    serve::Partitions,
+    ServiceExt,
+};
 use si_data_nats::async_nats::jetstream;
 use si_events::{ChangeSetId, WorkspacePk};
 use telemetry::prelude::*;
+use tokio::sync::mpsc;
 use tokio_util::sync::CancellationToken;
 use tower::ServiceBuilder;
 
-use crate::{dvu_debouncer::DvuDebouncer, ServerError as Error, ServerMetadata, ServerResult};
+use crate::{dvu_debouncer::DvuDebouncer, ServerMetadata, ServerResult};
 
 use self::app_state::AppState;
 
 pub mod app_state;
 pub mod handlers;
 
-/// A micro service which processes a stream of rebaser requests for a given change set in a
-/// workspace.
+/// Creates a future which processes the rebaser requests forwarded by all
+/// [`ChangeSetRequestsTask`]s until the shutdown token is cancelled.
+pub(crate) fn serve_requests(
+    requests_rx: mpsc::Receiver<jetstream::Message>,
+    ctx_builder: DalContextBuilder,
+    concurrency_limit: usize,
+    snapshot_checkpoint_interval: usize,
+    shutdown_token: CancellationToken,
+) -> impl Future<Output = io::Result<()>> {
+    let partitions = change_set_partitions(ctx_builder.nats_conn().metadata().subject_prefix());
+    let state = AppState::new(ctx_builder, snapshot_checkpoint_interval);
+
+    let app = ServiceBuilder::new()
+        .layer(
+            TraceLayer::new()
+                .make_span_with(DefaultMakeSpan::new().level(Level::TRACE))
+                .on_request(DefaultOnRequest::new().level(Level::TRACE))
+                .on_response(DefaultOnResponse::new().level(Level::TRACE)),
+        )
+        .layer(AckLayer::new())
+        .service(handlers::process_request.with_state(state));
+
+    let incoming = stream::unfold(requests_rx, |mut requests_rx| async move {
+        requests_rx
+            .recv()
+            .await
+            .map(|msg| (Ok::<_, std::convert::Infallible>(msg), requests_rx))
+    })
+    .boxed();
+
+    naxum::serve(incoming, app.into_make_service())
+        .with_concurrency_limit(concurrency_limit)
+        .with_partitions(partitions)
+        .with_graceful_shutdown(naxum::wait_on_cancelled(shutdown_token))
+        .into_future()
+}
+
+/// Partitions rebaser requests by change set.
+///
+/// Request subjects are `si.layerdb.activities.$workspace_id.$change_set_id.$kind`, following any
+/// subject prefix.
+fn change_set_partitions(prefix: Option<&str>) -> Partitions {
+    let prefix_tokens = prefix.map(|prefix| prefix.split('.').count()).unwrap_or(0);
+    Partitions::subject_tokens([prefix_tokens + 4])
+}
+
+/// A micro service which forwards the work queue of rebaser requests for a given change set in a
+/// workspace to the requests being served, and which debounces dependent values updates for the
+/// change set.
+///
+/// A task only runs on the Rebaser server instance which owns the change set. The work queue
+/// delivers one request at a time, which preserves the total order of requests within a change set
+/// even while ownership moves between instances.
 pub struct ChangeSetRequestsTask {
     metadata: Arc<ServerMetadata>,
-    inner: Box<dyn Future<Output = io::Result<()>> + Unpin + Send>,
+    incoming: jetstream::consumer::pull::Stream,
+    requests_tx: mpsc::Sender<jetstream::Message>,
     shutdown_token: CancellationToken,
 }
 
@@ -48,41 +111,30 @@ impl ChangeSetRequestsTask {
     const NAME: &'static str = "Rebaser::ChangeSetRequestsTask";
 
     /// Creates and returns a runnable [`ChangeSetRequestsTask`].
+    #[allow(clippy::too_many_arguments)]
     pub fn create(
         metadata: Arc<ServerMetadata>,
         workspace_id: WorkspacePk,
         change_set_id: ChangeSetId,
         incoming: jetstream::consumer::pull::Stream,
+        requests_tx: mpsc::Sender<jetstream::Message>,
         ctx_builder: DalContextBuilder,
         shutdown_token: CancellationToken,
         dvu_interval: Duration,
     ) -> Self {
-        let dvu_debouncer = DvuDebouncer::new(
+        // The debouncer runs in its own task until the shutdown token is cancelled
+        DvuDebouncer::new(
             workspace_id,
             change_set_id,
             shutdown_token.clone(),
-            ctx_builder.clone(),
+            ctx_builder,
             dvu_interval,
         );
-        let state = AppState::new(workspace_id, change_set_id, ctx_builder, dvu_debouncer);
-
-        let app = ServiceBuilder::new()
-            .concurrency_limit(1)
-            .layer(
-                TraceLayer::new()
-                    .make_span_with(DefaultMakeSpan::new().level(Level::TRACE))
-                    .on_request(DefaultOnRequest::new().level(Level::TRACE))
-                    .on_response(DefaultOnResponse::new().level(Level::TRACE)),
-            )
-            .layer(AckLayer::new())
-            .service(handlers::process_request.with_state(state));
-
-        let inner = naxum::serve(incoming, app.into_make_service())
-            .with_graceful_shutdown(naxum::wait_on_cancelled(shutdown_token.clone()));
 
         Self {
             metadata,
-            inner: Box::new(inner.into_future()),
+            incoming,
+            requests_tx,
             shutdown_token,
         }
     }
@@ -97,8 +149,39 @@ impl ChangeSetRequestsTask {
 
     /// Runs the service to completion, returning its result (i.e. whether it successful or an
     /// internal error was encountered).
-    pub async fn try_run(self) -> ServerResult<()> {
-        self.inner.await.map_err(Error::Naxum)?;
+    pub async fn try_run(mut self) -> ServerResult<()> {
+        loop {
+            let msg = tokio::select! {
+                _ = self.shutdown_token.cancelled() => break,
+                maybe_msg = self.incoming.next() => match maybe_msg {
+                    Some(Ok(msg)) => msg,
+                    Some(Err(err)) => {
+                        warn!(
+                            task = Self::NAME,
+                            error = ?err,
+                            "failed to read next message from stream",
+                        );
+                        continue;
+                    }
+                    None => {
+                        trace!(task = Self::NAME, "stream is closed");
+                        break;
+                    }
+                },
+            };
+
+            // A request which is never forwarded is never acked, so it is redelivered later
+            tokio::select! {
+                _ = self.shutdown_token.cancelled() => break,
+                result = self.requests_tx.send(msg) => {
+                    if result.is_err() {
+                        trace!(task = Self::NAME, "requests are no longer being served");
+                        break;
+                    }
+                }
+            }
+        }
+
         debug!(task = Self::NAME, "main loop shutdown complete");
         Ok(())
     }
@@ -80,7 +80,7 @@ pub async fn process_request(State(state): State<AppState>, msg: InnerMessage) -
 
     let mut ctx = state.ctx_builder.build(request_ctx).await?;
 
-    let rebase_status = perform_rebase(&mut ctx, &message)
+    let rebase_status = perform_rebase(&mut ctx, &message, state.snapshot_checkpoint_interval)
         .await
         .unwrap_or_else(|err| {
             error!(error = ?err, ?message, "performing rebase failed, attempting to reply");
@@ -15,9 +15,13 @@ use si_std::CanonicalFileError;
 use telemetry::prelude::*;
 use thiserror::Error;
 
+use dal::workspace_snapshot::delta::DEFAULT_CHECKPOINT_INTERVAL;
+
 use crate::StandardConfig;
 use crate::StandardConfigFile;
 
+const DEFAULT_CONCURRENCY_LIMIT: usize = 10;
+
 #[allow(missing_docs)]
 #[remain::sorted]
 #[derive(Debug, Error)]
@@ -68,6 +72,12 @@ pub struct Config {
 
     #[builder(default = "5000")]
     dvu_interval_millis: u64,
+
+    #[builder(default = "default_concurrency_limit()")]
+    concurrency_limit: usize,
+
+    #[builder(default = "default_snapshot_checkpoint_interval()")]
+    snapshot_checkpoint_interval: usize,
 }
 
 impl StandardConfig for Config {
@@ -123,6 +133,17 @@ impl Config {
     pub fn dvu_interval(&self) -> std::time::Duration {
         std::time::Duration::from_millis(self.dvu_interval_millis)
     }
+
+    /// Gets the maximum number of rebaser requests processed at once, across all change sets.
+    pub fn concurrency_limit(&self) -> usize {
+        self.concurrency_limit
+    }
+
+    /// Gets the number of workspace snapshot writes between full checkpoints, with the writes in
+    /// between stored as deltas.
+    pub fn snapshot_checkpoint_interval(&self) -> usize {
+        self.snapshot_checkpoint_interval
+    }
 }
 
 /// The configuration file for creating a [`Server`].
@@ -142,6 +163,10 @@ pub struct ConfigFile {
     layer_db_config: LayerDbConfig,
     #[serde(default = "random_instance_id")]
     instance_id: String,
+    #[serde(default = "default_concurrency_limit")]
+    concurrency_limit: usize,
+    #[serde(default = "default_snapshot_checkpoint_interval")]
+    snapshot_checkpoint_interval: usize,
 }
 
 impl Default for ConfigFile {
@@ -154,6 +179,8 @@ impl Default for ConfigFile {
             layer_db_config: default_layer_db_config(),
             messaging_config: Default::default(),
             instance_id: random_instance_id(),
+            concurrency_limit: default_concurrency_limit(),
+            snapshot_checkpoint_interval: default_snapshot_checkpoint_interval(),
         }
     }
 }
@@ -175,6 +202,8 @@ impl TryFrom<ConfigFile> for Config {
         config.symmetric_crypto_service(value.symmetric_crypto_service.try_into()?);
         config.layer_db_config(value.layer_db_config);
         config.instance_id(value.instance_id);
+        config.concurrency_limit(value.concurrency_limit);
+        config.snapshot_checkpoint_interval(value.snapshot_checkpoint_interval);
         config.build().map_err(Into::into)
     }
 }
@@ -183,6 +212,14 @@ fn random_instance_id() -> String {
     Ulid::new().to_string()
 }
 
+fn default_concurrency_limit() -> usize {
+    DEFAULT_CONCURRENCY_LIMIT
+}
+
+fn default_snapshot_checkpoint_interval() -> usize {
+    DEFAULT_CHECKPOINT_INTERVAL
+}
+
 fn default_symmetric_crypto_config() -> SymmetricCryptoServiceConfigFile {
     SymmetricCryptoServiceConfigFile {
         active_key: None,
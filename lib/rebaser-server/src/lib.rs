@@ -32,12 +32,14 @@ use thiserror::Error;
 pub mod change_set_requests;
 mod config;
 pub mod dvu_debouncer;
+mod ownership;
 mod rebase;
 mod server;
 
 pub use config::{
     detect_and_configure_development, Config, ConfigBuilder, ConfigError, ConfigFile,
 };
+pub use ownership::ChangeSetOwnershipError;
 pub use rebaser_core::RebaserMessagingConfig;
 pub use server::{Server, ServerMetadata};
 pub use si_settings::{StandardConfig, StandardConfigFile};
@@ -46,6 +48,9 @@ pub use si_settings::{StandardConfig, StandardConfigFile};
 #[remain::sorted]
 #[derive(Debug, Error)]
 pub enum ServerError {
+    /// When failing to acquire, renew or release ownership of change sets
+    #[error("change set ownership error: {0}")]
+    ChangeSetOwnership(#[from] ChangeSetOwnershipError),
     /// When a Cyclone encryption key failed to be loaded
     #[error("error when loading encryption key: {0}")]
     CycloneEncryptionKey(#[source] si_crypto::VeritechEncryptionKeyError),
@@ -0,0 +1,443 @@
+//! Lease-based ownership of change sets across Rebaser server instances.
+//!
+//! Each change set is owned by at most one Rebaser server instance at a time, which is the only
+//! instance to consume the change set's work queue of requests. Routing all of a change set's
+//! requests to one instance keeps its workspace snapshots hot in that instance's memory.
+//!
+//! Ownership is recorded as leases in a NATS KV bucket which expire unless their owner renews
+//! them, so the change sets of an instance which goes away are freed up for others. Instances also
+//! register themselves as members in the bucket, and each change set is assigned to a member by
+//! rendezvous hashing. When members join or leave, every instance computes the same new
+//! assignment, releases the leases of change sets it should no longer own and acquires the leases
+//! of change sets it should now own.
+
+use std::{collections::HashMap, result, time::Duration};
+
+use futures::TryStreamExt;
+use si_data_nats::{
+    async_nats::{
+        self,
+        jetstream::{
+            context::{CreateKeyValueError, KeyValueError},
+            kv,
+        },
+    },
+    jetstream, NatsClient,
+};
+use si_events::ChangeSetId;
+use telemetry::prelude::*;
+use thiserror::Error;
+
+const NATS_KV_BUCKET_NAME: &str = "REBASER_CHANGE_SET_OWNERS";
+
+const MEMBER_KEY_PREFIX: &str = "members.";
+const CHANGE_SET_KEY_PREFIX: &str = "change_sets.";
+
+/// How long a lease or a membership lasts without being renewed.
+pub(crate) const LEASE_TTL: Duration = Duration::from_secs(30);
+
+/// How often leases and memberships are renewed, which is also how often change sets are
+/// rebalanced across members.
+pub(crate) const RENEW_INTERVAL: Duration = Duration::from_secs(10);
+
+/// An error that can occur when managing change set ownership.
+#[remain::sorted]
+#[derive(Debug, Error)]
+pub enum ChangeSetOwnershipError {
+    /// When failing to create the ownership KV bucket
+    #[error("failed to create kv bucket: {0}")]
+    CreateBucket(#[source] CreateKeyValueError),
+    /// When failing to create a lease for a reason other than it being held by another instance
+    #[error("failed to create lease: {0}")]
+    CreateLease(#[source] kv::CreateError),
+    /// When failing to delete a lease or membership
+    #[error("failed to delete key: {0}")]
+    Delete(#[source] kv::DeleteError),
+    /// When failing to get the ownership KV bucket
+    #[error("failed to get kv bucket: {0}")]
+    GetBucket(#[source] KeyValueError),
+    /// When failing to list the keys of the ownership KV bucket
+    #[error("failed to list keys: {0}")]
+    ListKeys(#[source] async_nats::Error),
+    /// When failing to register or renew membership
+    #[error("failed to put membership: {0}")]
+    PutMember(#[source] kv::PutError),
+}
+
+type Result<T> = result::Result<T, ChangeSetOwnershipError>;
+
+/// The change sets owned by this Rebaser server instance, and the members it shares change sets
+/// with.
+#[derive(Debug)]
+pub(crate) struct ChangeSetOwnership {
+    instance_id: String,
+    store: kv::Store,
+    members: Vec<String>,
+    /// The revision of each lease held, which must be current to renew or release it
+    leases: HashMap<ChangeSetId, u64>,
+}
+
+impl ChangeSetOwnership {
+    /// Joins the members sharing change sets, creating the ownership KV bucket if it doesn't yet
+    /// exist.
+    pub(crate) async fn join(nats: &NatsClient, instance_id: impl Into<String>) -> Result<Self> {
+        let bucket = nats_kv_bucket_name(nats.metadata().subject_prefix());
+        let context = jetstream::new(nats.clone());
+
+        let store = match context.get_key_value(bucket.as_str()).await {
+            Ok(store) => store,
+            Err(err) => {
+                debug!(error = ?err, "failed to get kv bucket, creating it");
+                context
+                    .create_key_value(kv::Config {
+                        bucket,
+                        description: "Leases of change sets owned by Rebaser servers".to_owned(),
+                        history: 1,
+                        max_age: LEASE_TTL,
+                        ..Default::default()
+                    })
+                    .await
+                    .map_err(ChangeSetOwnershipError::CreateBucket)?
+            }
+        };
+
+        let mut ownership = Self {
+            instance_id: instance_id.into(),
+            store,
+            members: Vec::new(),
+            leases: HashMap::new(),
+        };
+        ownership.refresh_members().await?;
+
+        Ok(ownership)
+    }
+
+    /// Renews this instance's membership and reloads the list of members, returning whether it
+    /// changed.
+    pub(crate) async fn refresh_members(&mut self) -> Result<bool> {
+        self.store
+            .put(self.member_key(), self.instance_id.clone().into())
+            .await
+            .map_err(ChangeSetOwnershipError::PutMember)?;
+
+        let mut members: Vec<String> = self
+            .store
+            .keys()
+            .await
+            .map_err(|err| ChangeSetOwnershipError::ListKeys(err.into()))?
+            .try_filter_map(|key| async move {
+                Ok(key.strip_prefix(MEMBER_KEY_PREFIX).map(ToOwned::to_owned))
+            })
+            .try_collect()
+            .await
+            .map_err(|err| ChangeSetOwnershipError::ListKeys(err.into()))?;
+        members.sort();
+
+        let changed = members != self.members;
+        if changed {
+            info!(?members, "rebaser members changed");
+            self.members = members;
+        }
+
+        Ok(changed)
+    }
+
+    /// Whether this instance should own the change set, given the current members.
+    pub(crate) fn should_own(&self, change_set_id: ChangeSetId) -> bool {
+        assigned_member(&self.members, change_set_id)
+            .map(|member| member == self.instance_id)
+            // Until members are known, claim whatever is free rather than processing nothing
+            .unwrap_or(true)
+    }
+
+    /// Whether this instance holds the lease of the change set.
+    pub(crate) fn owns(&self, change_set_id: ChangeSetId) -> bool {
+        self.leases.contains_key(&change_set_id)
+    }
+
+    /// The change sets this instance holds leases of.
+    pub(crate) fn owned(&self) -> impl Iterator<Item = ChangeSetId> + '_ {
+        self.leases.keys().copied()
+    }
+
+    /// Acquires the lease of the change set, returning whether it was free to acquire.
+    pub(crate) async fn acquire(&mut self, change_set_id: ChangeSetId) -> Result<bool> {
+        if self.owns(change_set_id) {
+            return Ok(true);
+        }
+
+        match self
+            .store
+            .create(lease_key(change_set_id), self.instance_id.clone().into())
+            .await
+        {
+            Ok(revision) => {
+                debug!(si.change_set.id = %change_set_id, "acquired change set lease");
+                self.leases.insert(change_set_id, revision);
+                Ok(true)
+            }
+            Err(err) if err.kind() == kv::CreateErrorKind::AlreadyExists => {
+                trace!(si.change_set.id = %change_set_id, "change set lease is held elsewhere");
+                Ok(false)
+            }
+            Err(err) => Err(ChangeSetOwnershipError::CreateLease(err)),
+        }
+    }
+
+    /// Renews all leases held, returning the change sets whose leases were lost (e.g. because
+    /// they expired while this instance was unreachable) and so are no longer owned.
+    pub(crate) async fn renew(&mut self) -> Vec<ChangeSetId> {
+        let mut lost = Vec::new();
+
+        for (change_set_id, revision) in self.leases.iter_mut() {
+            match self
+                .store
+                .update(
+                    lease_key(*change_set_id),
+                    self.instance_id.clone().into(),
+                    *revision,
+                )
+                .await
+            {
+                Ok(new_revision) => *revision = new_revision,
+                Err(err) => {
+                    warn!(
+                        error = ?err,
+                        si.change_set.id = %change_set_id,
+                        "failed to renew change set lease, giving up ownership",
+                    );
+                    lost.push(*change_set_id);
+                }
+            }
+        }
+        for change_set_id in &lost {
+            self.leases.remove(change_set_id);
+        }
+
+        lost
+    }
+
+    /// Releases the lease of the change set so that another member can acquire it.
+    pub(crate) async fn release(&mut self, change_set_id: ChangeSetId) -> Result<()> {
+        let Some(revision) = self.leases.remove(&change_set_id) else {
+            return Ok(());
+        };
+
+        debug!(si.change_set.id = %change_set_id, "releasing change set lease");
+        self.store
+            .delete_expect_revision(lease_key(change_set_id), Some(revision))
+            .await
+            .map_err(ChangeSetOwnershipError::Delete)
+    }
+
+    /// Releases all leases and leaves the members, so that the remaining members take over this
+    /// instance's change sets straight away rather than once its leases expire.
+    pub(crate) async fn leave(mut self) -> Result<()> {
+        let change_set_ids: Vec<_> = self.owned().collect();
+        for change_set_id in change_set_ids {
+            if let Err(err) = self.release(change_set_id).await {
+                warn!(error = ?err, si.change_set.id = %change_set_id, "failed to release lease");
+            }
+        }
+
+        self.store
+            .delete(self.member_key())
+            .await
+            .map_err(ChangeSetOwnershipError::Delete)
+    }
+
+    fn member_key(&self) -> String {
+        format!("{MEMBER_KEY_PREFIX}{}", self.instance_id)
+    }
+}
+
+fn nats_kv_bucket_name(prefix: Option<&str>) -> String {
+    match prefix {
+        Some(prefix) => format!("{prefix}_{NATS_KV_BUCKET_NAME}"),
+        None => NATS_KV_BUCKET_NAME.to_owned(),
+    }
+}
+
+fn lease_key(change_set_id: ChangeSetId) -> String {
+    format!("{CHANGE_SET_KEY_PREFIX}{change_set_id}")
+}
+
+/// Assigns a change set to a member by rendezvous hashing, which only moves the change sets of a
+/// member which joins or leaves.
+fn assigned_member(members: &[String], change_set_id: ChangeSetId) -> Option<&str> {
+    let change_set_id = change_set_id.to_string();
+
+    members
+        .iter()
+        .max_by_key(|member| fnv1a(&[member.as_bytes(), b".", change_set_id.as_bytes()]))
+        .map(String::as_str)
+}
+
+/// A hash which, unlike the standard library's, is the same on every instance whatever version of
+/// Rust it was built with.
+fn fnv1a(parts: &[&[u8]]) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    parts
+        .iter()
+        .flat_map(|part| part.iter())
+        .fold(OFFSET_BASIS, |hash, byte| {
+            (hash ^ u64::from(*byte)).wrapping_mul(PRIME)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use si_data_nats::NatsConfig;
+
+    use super::*;
+
+    async fn nats() -> NatsClient {
+        let mut config = NatsConfig::default();
+        #[allow(clippy::disallowed_methods)] // Used only in tests & so prefixed with `SI_TEST_`
+        if let Ok(value) = env::var("SI_TEST_NATS_URL") {
+            config.url = value;
+        }
+        // A unique prefix gives every test a bucket of its own
+        config.subject_prefix = Some(ulid::Ulid::new().to_string());
+
+        NatsClient::new(&config)
+            .await
+            .expect("failed to connect to NATS")
+    }
+
+    fn members(names: &[&str]) -> Vec<String> {
+        names.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn fnv1a_matches_reference_values() {
+        assert_eq!(0xcbf2_9ce4_8422_2325, fnv1a(&[]));
+        assert_eq!(0xaf63_dc4c_8601_ec8c, fnv1a(&[b"a"]));
+        assert_eq!(0x8594_4171_f739_67e8, fnv1a(&[b"foobar"]));
+    }
+
+    #[test]
+    fn fnv1a_hashes_parts_as_if_concatenated() {
+        assert_eq!(fnv1a(&[b"foobar"]), fnv1a(&[b"foo", b"", b"bar"]));
+    }
+
+    #[test]
+    fn no_member_is_assigned_without_members() {
+        assert_eq!(None, assigned_member(&[], ChangeSetId::new()));
+    }
+
+    #[test]
+    fn assignment_ignores_member_order() {
+        let change_set_id = ChangeSetId::new();
+
+        assert_eq!(
+            assigned_member(&members(&["a", "b", "c"]), change_set_id),
+            assigned_member(&members(&["c", "a", "b"]), change_set_id),
+        );
+    }
+
+    #[test]
+    fn joining_member_only_takes_change_sets_from_others() {
+        let before = members(&["a", "b", "c"]);
+        let after = members(&["a", "b", "c", "d"]);
+
+        let mut moved = 0;
+        for _ in 0..1000 {
+            let change_set_id = ChangeSetId::new();
+            let old = assigned_member(&before, change_set_id).expect("no member assigned");
+            let new = assigned_member(&after, change_set_id).expect("no member assigned");
+            if old != new {
+                assert_eq!("d", new);
+                moved += 1;
+            }
+        }
+
+        // The new member takes roughly its share of the change sets, and no more
+        assert!((150..350).contains(&moved), "moved {moved} change sets");
+    }
+
+    #[tokio::test]
+    async fn leases_are_held_by_one_member_at_a_time() {
+        let nats = nats().await;
+        let mut one = ChangeSetOwnership::join(&nats, "one")
+            .await
+            .expect("failed to join");
+        let mut two = ChangeSetOwnership::join(&nats, "two")
+            .await
+            .expect("failed to join");
+        let change_set_id = ChangeSetId::new();
+
+        assert!(one.acquire(change_set_id).await.expect("failed to acquire"));
+        assert!(one.owns(change_set_id));
+        // Acquiring a lease which is already held is a no-op
+        assert!(one.acquire(change_set_id).await.expect("failed to acquire"));
+        assert!(!two.acquire(change_set_id).await.expect("failed to acquire"));
+        assert!(!two.owns(change_set_id));
+
+        assert!(one.renew().await.is_empty());
+        assert!(one.owns(change_set_id));
+        assert!(!two.acquire(change_set_id).await.expect("failed to acquire"));
+
+        one.release(change_set_id).await.expect("failed to release");
+        assert!(!one.owns(change_set_id));
+        assert!(two.acquire(change_set_id).await.expect("failed to acquire"));
+        assert_eq!(vec![change_set_id], two.owned().collect::<Vec<_>>());
+    }
+
+    #[tokio::test]
+    async fn lost_leases_are_given_up_on_renewal() {
+        let nats = nats().await;
+        let mut one = ChangeSetOwnership::join(&nats, "one")
+            .await
+            .expect("failed to join");
+        let mut two = ChangeSetOwnership::join(&nats, "two")
+            .await
+            .expect("failed to join");
+        let change_set_id = ChangeSetId::new();
+
+        assert!(one.acquire(change_set_id).await.expect("failed to acquire"));
+        // As if the lease expired and was taken by another member
+        one.store
+            .purge(lease_key(change_set_id))
+            .await
+            .expect("failed to purge lease");
+        assert!(two.acquire(change_set_id).await.expect("failed to acquire"));
+
+        assert_eq!(vec![change_set_id], one.renew().await);
+        assert!(!one.owns(change_set_id));
+        assert!(two.renew().await.is_empty());
+        assert!(two.owns(change_set_id));
+    }
+
+    #[tokio::test]
+    async fn leaving_frees_leases_and_membership() {
+        let nats = nats().await;
+        let mut one = ChangeSetOwnership::join(&nats, "one")
+            .await
+            .expect("failed to join");
+        let mut two = ChangeSetOwnership::join(&nats, "two")
+            .await
+            .expect("failed to join");
+        assert!(one
+            .refresh_members()
+            .await
+            .expect("failed to refresh members"));
+        assert_eq!(members(&["one", "two"]), one.members);
+
+        let change_set_id = ChangeSetId::new();
+        assert!(one.acquire(change_set_id).await.expect("failed to acquire"));
+        one.leave().await.expect("failed to leave");
+
+        assert!(two
+            .refresh_members()
+            .await
+            .expect("failed to refresh members"));
+        assert_eq!(members(&["two"]), two.members);
+        assert!(two.should_own(change_set_id));
+        assert!(two.acquire(change_set_id).await.expect("failed to acquire"));
+    }
+}
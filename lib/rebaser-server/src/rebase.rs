@@ -1,5 +1,5 @@
 use dal::change_set::{ChangeSet, ChangeSetError, ChangeSetId};
-use dal::workspace_snapshot::WorkspaceSnapshotError;
+use dal::workspace_snapshot::{delta::WorkspaceSnapshotDelta, WorkspaceSnapshotError};
 use dal::{DalContext, TransactionsError, WorkspaceSnapshot, WsEventError};
 use si_events::rebase_batch_address::RebaseBatchAddress;
 use si_events::WorkspaceSnapshotAddress;
@@ -50,6 +50,7 @@ type RebaseResult<T> = Result<T, RebaseError>;
 pub async fn perform_rebase(
     ctx: &mut DalContext,
     message: &ActivityRebaseRequest,
+    snapshot_checkpoint_interval: usize,
 ) -> RebaseResult<RebaseStatus> {
     let span = Span::current();
     span.record(
@@ -99,8 +100,15 @@ pub async fn perform_rebase(
 
     if !rebase_batch.updates().is_empty() {
         // Once all updates have been performed, we can write out, mark everything as recently seen
-        // and update the pointer.
-        to_rebase_workspace_snapshot.write(ctx).await?;
+        // and update the pointer. The snapshot is written as a delta of the batch just performed,
+        // unless it's time for a new checkpoint.
+        to_rebase_workspace_snapshot
+            .write_delta(
+                ctx,
+                message.payload.rebase_batch_address,
+                snapshot_checkpoint_interval,
+            )
+            .await?;
         debug!("snapshot written: {:?}", start.elapsed());
         to_rebase_change_set
             .update_pointer(ctx, to_rebase_workspace_snapshot.id().await)
@@ -142,15 +150,44 @@ pub(crate) async fn evict_unused_snapshots(
     ctx: &DalContext,
     workspace_snapshot_address: &WorkspaceSnapshotAddress,
 ) -> RebaseResult<()> {
-    if !ChangeSet::workspace_snapshot_address_in_use(ctx, workspace_snapshot_address).await? {
-        ctx.layer_db()
-            .workspace_snapshot()
-            .evict(
-                workspace_snapshot_address,
-                ctx.events_tenancy(),
-                ctx.events_actor(),
-            )
-            .await?;
+    if let Some(checkpoint_address) = evict_unused_snapshot(ctx, workspace_snapshot_address).await?
+    {
+        // The checkpoint may have only been kept for the delta which was just evicted
+        evict_unused_snapshot(ctx, &checkpoint_address).await?;
     }
     Ok(())
 }
+
+/// Evicts the snapshot if no change set points to it and no delta is built on top of it,
+/// returning the address of its checkpoint if it was stored as a delta.
+async fn evict_unused_snapshot(
+    ctx: &DalContext,
+    workspace_snapshot_address: &WorkspaceSnapshotAddress,
+) -> RebaseResult<Option<WorkspaceSnapshotAddress>> {
+    if ChangeSet::workspace_snapshot_address_in_use(ctx, workspace_snapshot_address).await?
+        || WorkspaceSnapshotDelta::checkpoint_in_use(ctx, workspace_snapshot_address).await?
+    {
+        return Ok(None);
+    }
+
+    ctx.layer_db()
+        .workspace_snapshot()
+        .evict(
+            workspace_snapshot_address,
+            ctx.events_tenancy(),
+            ctx.events_actor(),
+        )
+        .await?;
+
+    let Some(delta) = WorkspaceSnapshotDelta::find(ctx, *workspace_snapshot_address).await? else {
+        return Ok(None);
+    };
+
+    // The context's transactions are shared with the request which spawned the eviction, so the
+    // delta is deleted in transactions of its own
+    let delete_ctx = ctx.to_builder().build_default().await?;
+    delta.delete(&delete_ctx).await?;
+    delete_ctx.commit_no_rebase().await?;
+
+    Ok(Some(delta.checkpoint_address))
+}
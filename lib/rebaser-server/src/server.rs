@@ -2,7 +2,7 @@ use std::{
     collections::{HashMap, HashSet},
     future::IntoFuture,
     sync::Arc,
-    time::Duration,
+    time::{Duration, Instant},
 };
 
 use dal::feature_flags::FeatureFlagService;
@@ -20,40 +20,53 @@ use si_data_pg::{InstrumentedClient, PgPool, PgPoolConfig};
 use si_events::{ChangeSetId, WorkspacePk};
 use si_layer_cache::activities::{Activity, ActivityPayload};
 use telemetry::prelude::*;
-use tokio::task::JoinHandle;
+use tokio::{sync::mpsc, task::JoinHandle};
 use tokio_util::{sync::CancellationToken, task::TaskTracker};
 use veritech_client::Client as VeritechClient;
 
 use crate::{
-    change_set_requests::ChangeSetRequestsTask, Config, ServerError as Error, ServerResult,
+    change_set_requests::{serve_requests, ChangeSetRequestsTask},
+    ownership::{ChangeSetOwnership, RENEW_INTERVAL},
+    Config, ServerError as Error, ServerResult,
 };
 
 const CONSUMER_NAME: &str = "rebaser-requests";
 
+/// How long a change set can go without a rebase request before it is forgotten, stopping its task
+/// and releasing its lease until its next request.
+const CHANGE_SET_IDLE_TIMEOUT: Duration = Duration::from_secs(60 * 60);
+
 /// Server metadata, used with telemetry.
 #[derive(Clone, Debug)]
 pub struct ServerMetadata {
-    #[allow(dead_code)] // TODO(fnichol): this will be used in telemetry, so drop the dead_code
-    // exception then
     instance_id: String,
 }
 
 /// A service which concurrently processes rebaser requests across multiple change sets.
 ///
-/// Each change set is processed with a dedicated micro service (a [`ChangeSetRequestsTask`]) which
-/// consumes a work queue of rebaser requests. The work queue semantics allows multiple Rebaser
-/// server instances to run and share work while collectively processing one message at a time,
-/// thus preserving the total order of requests within the scope of a chanage set.
+/// Each change set has a dedicated micro service (a [`ChangeSetRequestsTask`]) which consumes a
+/// work queue of rebaser requests. Change sets are shared out between Rebaser server instances by
+/// lease, so that only the instance which owns a change set runs its task and all of the change
+/// set's requests are processed by the same instance. The work queue semantics additionally
+/// ensure that one message is processed at a time, thus preserving the total order of requests
+/// within the scope of a change set even while ownership moves between instances. The requests of
+/// all owned change sets are processed by one service, which runs up to a concurrency limit of
+/// requests at once and never more than one per change set.
 ///
-/// An activity stream of all rebaser requests is processed in the main loop to determine if new
-/// change set tasks should be spawned.
+/// An activity stream of all rebaser requests is processed in the main loop to learn of change
+/// sets and spawn tasks for those this instance should own. Ownership is periodically renewed and
+/// rebalanced as instances join and leave, and change sets which have gone idle are forgotten.
 #[derive(Debug)]
 pub struct Server {
     metadata: Arc<ServerMetadata>,
     ctx_builder: DalContextBuilder,
     change_set_tasks: HashMap<ChangeSetId, RunningTask>,
+    /// Every change set seen in the activity stream, whether or not this instance owns it
+    known_change_sets: HashMap<ChangeSetId, KnownChangeSet>,
     shutdown_token: CancellationToken,
     dvu_interval: Duration,
+    concurrency_limit: usize,
+    snapshot_checkpoint_interval: usize,
 }
 
 impl Server {
@@ -97,6 +110,8 @@ impl Server {
             services_context,
             shutdown_token,
             config.dvu_interval(),
+            config.concurrency_limit(),
+            config.snapshot_checkpoint_interval(),
         )
     }
 
@@ -107,6 +122,8 @@ impl Server {
         services_context: ServicesContext,
         shutdown_token: CancellationToken,
         dvu_interval: Duration,
+        concurrency_limit: usize,
+        snapshot_checkpoint_interval: usize,
     ) -> ServerResult<Self> {
         dal::init()?;
 
@@ -120,8 +137,11 @@ impl Server {
             metadata: Arc::new(metadata),
             ctx_builder,
             change_set_tasks: HashMap::default(),
+            known_change_sets: HashMap::default(),
             shutdown_token,
             dvu_interval,
+            concurrency_limit,
+            snapshot_checkpoint_interval,
         })
     }
 
@@ -136,6 +156,16 @@ impl Server {
     /// Runs the service to completion, returning its result (i.e. whether it successful or an
     /// internal error was encountered).
     pub async fn try_run(mut self) -> ServerResult<()> {
+        // Serve the requests which change set tasks forward, until a shutdown is signaled
+        let (requests_tx, requests_rx) = mpsc::channel(self.concurrency_limit);
+        let requests = tokio::spawn(serve_requests(
+            requests_rx,
+            self.ctx_builder.clone(),
+            self.concurrency_limit,
+            self.snapshot_checkpoint_interval,
+            self.shutdown_token.clone(),
+        ));
+
         // TODO(fnichol): while it would be great to query the database on launch to get the active
         // change sets, in an initial cluster deployment, the database may not yet be created or
         // migrated. This is an outstanding issue and a micro-service "smell" as the Rebaser is not
@@ -145,7 +175,14 @@ impl Server {
         // commented out) as a marker of what we *should* be doing, once we've properly figured
         // that out ;)
         //
-        // self.launch_initial_change_set_tasks().await?;
+        // self.load_initial_change_sets().await?;
+
+        // Join the instances sharing change sets before claiming any
+        let mut ownership =
+            ChangeSetOwnership::join(self.ctx_builder.nats_conn(), &self.metadata.instance_id)
+                .await?;
+        let mut rebalance = tokio::time::interval(RENEW_INTERVAL);
+        rebalance.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
 
         // Set up an activity stream with change set-related messages
         let mut activities = self
@@ -169,7 +206,10 @@ impl Server {
                     match maybe_result {
                         // Successfully received a new activity message
                         Some(Ok(activity)) => {
-                            if let Err(err) = self.process_activity(activity).await {
+                            if let Err(err) = self
+                                .process_activity(activity, &mut ownership, &requests_tx)
+                                .await
+                            {
                                 warn!(error = ?err, "failed to process an activity message");
                             }
                         }
@@ -190,27 +230,51 @@ impl Server {
                         }
                     }
                 }
+                // Time to renew leases and rebalance change sets across instances
+                _ = rebalance.tick() => {
+                    if let Err(err) = self.rebalance(&mut ownership, &requests_tx).await {
+                        warn!(error = ?err, "failed to rebalance change sets");
+                    }
+                }
             }
         }
 
         self.terminate_all_change_set_tasks().await?;
+        if let Err(err) = ownership.leave().await {
+            warn!(error = ?err, "failed to leave change set ownership");
+        }
+
+        drop(requests_tx);
+        match requests.await {
+            Ok(result) => result.map_err(Error::Naxum)?,
+            Err(err) => warn!(error = ?err, "requests task failed to complete"),
+        }
 
         info!("main loop shutdown complete");
         Ok(())
     }
 
     #[inline]
-    async fn process_activity(&mut self, activity: Activity) -> ServerResult<()> {
+    async fn process_activity(
+        &mut self,
+        activity: Activity,
+        ownership: &mut ChangeSetOwnership,
+        requests_tx: &mpsc::Sender<jetstream::Message>,
+    ) -> ServerResult<()> {
         match activity.payload {
             // A rebase request implies a work queue should be set up for the associated change
-            // set, so we'll launch a task to process from this queue.
+            // set, so we'll launch a task to process from this queue if we own the change set.
             ActivityPayload::RebaseRequest(req) => {
                 let workspace_id = activity.metadata.tenancy.workspace_pk;
                 let change_set_id = req.to_rebase_change_set_id.into();
                 trace!(%workspace_id, %change_set_id, "processing rebase request activity");
 
-                if !self.running_change_set_task(change_set_id) {
-                    self.launch_change_set_task(workspace_id, change_set_id)
+                self.known_change_sets
+                    .insert(change_set_id, KnownChangeSet::seen_now(workspace_id));
+                if !self.running_change_set_task(change_set_id)
+                    && ownership.should_own(change_set_id)
+                {
+                    self.claim_change_set(workspace_id, change_set_id, ownership, requests_tx)
                         .await?;
                 }
             }
@@ -237,15 +301,100 @@ impl Server {
         self.change_set_tasks.contains_key(&change_set_id)
     }
 
-    async fn launch_initial_change_set_tasks(&mut self) -> ServerResult<()> {
+    /// Renews the leases of owned change sets and moves change sets between instances as they
+    /// join and leave.
+    #[instrument(name = "rebaser.rebalance", level = "debug", skip_all)]
+    async fn rebalance(
+        &mut self,
+        ownership: &mut ChangeSetOwnership,
+        requests_tx: &mpsc::Sender<jetstream::Message>,
+    ) -> ServerResult<()> {
+        ownership.refresh_members().await?;
+
+        // Idle change sets are forgotten so that they don't hold onto tasks and leases forever.
+        // Their next request makes them known again.
+        for change_set_id in idle_change_sets(&self.known_change_sets, Instant::now()) {
+            debug!(%change_set_id, "forgetting idle change set");
+            self.known_change_sets.remove(&change_set_id);
+            if self.running_change_set_task(change_set_id) {
+                if let Err(err) = self.terminate_change_set_task(change_set_id)?.await {
+                    warn!(error = ?err, %change_set_id, "change set task failed to complete");
+                }
+            }
+            ownership.release(change_set_id).await?;
+        }
+
+        // Another instance may already be processing change sets whose leases were lost
+        let mut to_terminate = ownership.renew().await;
+        // Change sets assigned to other instances are handed over to them
+        to_terminate.extend(
+            ownership
+                .owned()
+                .filter(|change_set_id| !ownership.should_own(*change_set_id)),
+        );
+
+        for change_set_id in to_terminate {
+            if self.running_change_set_task(change_set_id) {
+                if let Err(err) = self.terminate_change_set_task(change_set_id)?.await {
+                    warn!(error = ?err, %change_set_id, "change set task failed to complete");
+                }
+            }
+            // The task has stopped consuming requests, so the new owner can safely take over
+            ownership.release(change_set_id).await?;
+        }
+
+        let to_claim: Vec<_> = self
+            .known_change_sets
+            .iter()
+            .filter(|(change_set_id, _)| {
+                !self.running_change_set_task(**change_set_id)
+                    && ownership.should_own(**change_set_id)
+            })
+            .map(|(change_set_id, known)| (known.workspace_id, *change_set_id))
+            .collect();
+        for (workspace_id, change_set_id) in to_claim {
+            self.claim_change_set(workspace_id, change_set_id, ownership, requests_tx)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Acquires the lease of a change set and, if it was free, launches its task.
+    async fn claim_change_set(
+        &mut self,
+        workspace_id: WorkspacePk,
+        change_set_id: ChangeSetId,
+        ownership: &mut ChangeSetOwnership,
+        requests_tx: &mpsc::Sender<jetstream::Message>,
+    ) -> ServerResult<()> {
+        // A lease held elsewhere is released or expires, and is claimed on a later rebalance
+        if !ownership.acquire(change_set_id).await? {
+            return Ok(());
+        }
+
+        if let Err(err) = self
+            .launch_change_set_task(workspace_id, change_set_id, requests_tx.clone())
+            .await
+        {
+            ownership.release(change_set_id).await?;
+            return Err(err);
+        }
+
+        Ok(())
+    }
+
+    /// Learns of all open change sets, so that those this instance should own are claimed on the
+    /// next rebalance.
+    async fn load_initial_change_sets(&mut self) -> ServerResult<()> {
         let ctx = self.ctx_builder.build_default().await?;
         let pg = ctx.pg_pool().get().await.map_err(Error::dal_pg_pool)?;
         let ids = Self::all_open_change_sets(&pg).await?;
 
-        for (workspace_id, change_set_id) in ids {
-            self.launch_change_set_task(workspace_id, change_set_id)
-                .await?;
-        }
+        self.known_change_sets
+            .extend(ids.into_iter().map(|(workspace_id, change_set_id)| {
+                (change_set_id, KnownChangeSet::seen_now(workspace_id))
+            }));
 
         Ok(())
     }
@@ -263,6 +412,7 @@ impl Server {
         &mut self,
         workspace_id: WorkspacePk,
         change_set_id: ChangeSetId,
+        requests_tx: mpsc::Sender<jetstream::Message>,
     ) -> ServerResult<()> {
         if self.running_change_set_task(change_set_id) {
             return Err(Error::ExistingChangeSetTask(change_set_id));
@@ -285,6 +435,7 @@ impl Server {
             workspace_id,
             change_set_id,
             incoming,
+            requests_tx,
             self.ctx_builder.clone(),
             token.clone(),
             self.dvu_interval,
@@ -439,6 +590,37 @@ struct RunningTask {
     token: CancellationToken,
 }
 
+/// A change set seen in the activity stream.
+#[derive(Clone, Copy, Debug)]
+struct KnownChangeSet {
+    workspace_id: WorkspacePk,
+    /// When the change set's latest rebase request was seen
+    last_seen: Instant,
+}
+
+impl KnownChangeSet {
+    fn seen_now(workspace_id: WorkspacePk) -> Self {
+        Self {
+            workspace_id,
+            last_seen: Instant::now(),
+        }
+    }
+}
+
+/// The known change sets which haven't seen a rebase request for [`CHANGE_SET_IDLE_TIMEOUT`].
+fn idle_change_sets(
+    known_change_sets: &HashMap<ChangeSetId, KnownChangeSet>,
+    now: Instant,
+) -> Vec<ChangeSetId> {
+    known_change_sets
+        .iter()
+        .filter(|(_, known)| {
+            now.saturating_duration_since(known.last_seen) >= CHANGE_SET_IDLE_TIMEOUT
+        })
+        .map(|(change_set_id, _)| *change_set_id)
+        .collect()
+}
+
 #[derive(Clone, Debug)]
 pub struct ShutdownHandle {
     token: CancellationToken,
@@ -449,3 +631,50 @@ impl ShutdownHandle {
         self.token.cancel()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn idle_change_sets_are_those_without_recent_requests() {
+        let seen = Instant::now();
+        let now = seen + CHANGE_SET_IDLE_TIMEOUT;
+        let workspace_id = WorkspacePk::new();
+        let busy = ChangeSetId::new();
+        let idle = ChangeSetId::new();
+
+        let known_change_sets = HashMap::from([
+            (
+                busy,
+                KnownChangeSet {
+                    workspace_id,
+                    last_seen: seen + CHANGE_SET_IDLE_TIMEOUT / 2,
+                },
+            ),
+            (
+                idle,
+                KnownChangeSet {
+                    workspace_id,
+                    last_seen: seen,
+                },
+            ),
+        ]);
+
+        assert_eq!(vec![idle], idle_change_sets(&known_change_sets, now));
+    }
+
+    #[test]
+    fn change_sets_seen_after_now_are_not_idle() {
+        let now = Instant::now();
+        let known_change_sets = HashMap::from([(
+            ChangeSetId::new(),
+            KnownChangeSet {
+                workspace_id: WorkspacePk::new(),
+                last_seen: now + Duration::from_secs(1),
+            },
+        )]);
+
+        assert!(idle_change_sets(&known_change_sets, now).is_empty());
+    }
+}
@@ -12,6 +12,7 @@ pub use si_data_pg::PgPool;
 pub use si_layer_cache::LayerDb;
 pub use telemetry::prelude::*;
 pub use uds::{UdsIncomingStream, UdsIncomingStreamError};
+pub use webhooks::{WebhookDispatcher, WebhookDispatcherError};
 
 mod config;
 pub(crate) mod extract;
@@ -23,6 +24,7 @@ pub mod service;
 pub mod state;
 pub mod tracking;
 mod uds;
+mod webhooks;
 
 macro_rules! impl_default_error_into_response {
     (
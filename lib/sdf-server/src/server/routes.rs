@@ -35,6 +35,10 @@ pub fn routes(state: AppState) -> Router {
             "/api/attribute",
             crate::server::service::attribute::routes(),
         )
+        .nest(
+            "/api/audit_log",
+            crate::server::service::audit_log::routes(),
+        )
         .nest(
             "/api/change_set",
             crate::server::service::change_set::routes(),
@@ -54,6 +58,7 @@ pub fn routes(state: AppState) -> Router {
         .nest("/api/ws", crate::server::service::ws::routes())
         .nest("/api/module", crate::server::service::module::routes())
         .nest("/api/variant", crate::server::service::variant::routes())
+        .nest("/api/webhook", crate::server::service::webhook::routes())
         .nest("/api/v2", crate::server::service::v2::routes())
         .layer(CompressionLayer::new())
         // allows us to be permissive about cors from our owned subdomains
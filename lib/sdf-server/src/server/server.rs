@@ -40,8 +40,8 @@ use veritech_client::Client as VeritechClient;
 
 use super::state::AppState;
 use super::{
-    routes, Config, IncomingStream, UdsIncomingStream, UdsIncomingStreamError,
-    WorkspacePermissions, WorkspacePermissionsMode,
+    routes, Config, IncomingStream, UdsIncomingStream, UdsIncomingStreamError, WebhookDispatcher,
+    WebhookDispatcherError, WorkspacePermissions, WorkspacePermissionsMode,
 };
 use crate::server::config::VeritechKeyPair;
 
@@ -98,6 +98,8 @@ pub enum ServerError {
     VeritechPublicKeyAlreadySet,
     #[error("veritech public key error: {0}")]
     VeritechPublicKeyErr(#[from] VeritechKeyPairError),
+    #[error("webhook dispatcher error: {0}")]
+    WebhookDispatcher(#[from] WebhookDispatcherError),
     #[error(transparent)]
     Workspace(#[from] WorkspaceError),
     #[error("wrong incoming stream for {0} server: {1:?}")]
@@ -217,6 +219,16 @@ impl Server<(), ()> {
         Ok(dal::init()?)
     }
 
+    /// Starts the dispatcher which delivers workspace events to webhook subscriptions.
+    pub fn start_webhook_dispatcher(
+        services_context: ServicesContext,
+        shutdown_broadcast_rx: broadcast::Receiver<()>,
+    ) -> Result<()> {
+        let dispatcher = WebhookDispatcher::new(services_context)?;
+        tokio::spawn(dispatcher.run(shutdown_broadcast_rx));
+        Ok(())
+    }
+
     pub async fn start_posthog(config: &PosthogConfig) -> Result<PosthogClient> {
         let (posthog_client, posthog_sender) = si_posthog::from_config(config)?;
 
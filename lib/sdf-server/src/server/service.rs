@@ -9,6 +9,7 @@ use telemetry::prelude::*;
 pub mod action;
 pub mod async_route;
 pub mod attribute;
+pub mod audit_log;
 pub mod change_set;
 pub mod component;
 pub mod diagram;
@@ -20,6 +21,7 @@ pub mod secret;
 pub mod session;
 pub mod v2;
 pub mod variant;
+pub mod webhook;
 pub mod ws;
 
 /// A module containing dev routes for local development only.
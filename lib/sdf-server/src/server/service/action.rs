@@ -5,17 +5,19 @@ use si_layer_cache::LayerDbError;
 use thiserror::Error;
 
 use dal::{
-    action::prototype::ActionPrototypeError, action::ActionId,
-    schema::SchemaError as DalSchemaError,
+    action::prototype::ActionPrototypeError, action::refresh_schedule::RefreshScheduleError,
+    action::ActionId, schema::SchemaError as DalSchemaError,
 };
 use dal::{ComponentError, ComponentId, StandardModelError, TransactionsError, UserError, UserPk};
 
 use crate::server::{impl_default_error_into_response, state::AppState};
 
 mod cancel;
+mod cancel_running;
 mod history;
 pub mod list_actions;
 mod put_on_hold;
+mod refresh_schedule;
 mod retry;
 
 #[remain::sorted]
@@ -49,6 +51,8 @@ pub enum ActionError {
     NoSchemaForComponent(ComponentId),
     #[error("no schema variant found for component {0}")]
     NoSchemaVariantForComponent(ComponentId),
+    #[error("refresh schedule error: {0}")]
+    RefreshSchedule(#[from] RefreshScheduleError),
     #[error(transparent)]
     StandardModel(#[from] StandardModelError),
     #[error("transactions error: {0}")]
@@ -68,6 +72,15 @@ pub fn routes() -> Router<AppState> {
         .route("/list", get(list_actions::list_actions))
         .route("/put_on_hold", post(put_on_hold::put_on_hold))
         .route("/cancel", post(cancel::cancel))
+        .route("/cancel_running", post(cancel_running::cancel_running))
         .route("/retry", post(retry::retry))
         .route("/history", get(history::history))
+        .route(
+            "/refresh_schedule",
+            get(refresh_schedule::get_refresh_schedule),
+        )
+        .route(
+            "/set_refresh_schedule",
+            post(refresh_schedule::set_refresh_schedule),
+        )
 }
@@ -0,0 +1,34 @@
+use axum::Json;
+use dal::action::Action;
+use dal::{action::ActionId, Visibility, WsEvent};
+use serde::{Deserialize, Serialize};
+
+use super::ActionResult;
+use crate::server::extract::{AccessBuilder, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CancelRunningRequest {
+    pub ids: Vec<ActionId>,
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+// batched
+pub async fn cancel_running(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    Json(request): Json<CancelRunningRequest>,
+) -> ActionResult<()> {
+    let ctx = builder.build(request_ctx.build(request.visibility)).await?;
+    for action_id in request.ids {
+        Action::cancel_running(&ctx, action_id).await?;
+    }
+    WsEvent::action_list_updated(&ctx)
+        .await?
+        .publish_on_commit(&ctx)
+        .await?;
+    ctx.commit().await?;
+
+    Ok(())
+}
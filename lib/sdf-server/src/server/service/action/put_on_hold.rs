@@ -28,7 +28,7 @@ pub async fn put_on_hold(
             ActionState::Running | ActionState::Dispatched | ActionState::OnHold => {
                 return Err(ActionError::InvalidOnHoldTransition(action_id))
             }
-            ActionState::Queued | ActionState::Failed => {}
+            ActionState::Queued | ActionState::Failed | ActionState::Cancelled => {}
         }
 
         Action::set_state(&ctx, action.id(), ActionState::OnHold).await?;
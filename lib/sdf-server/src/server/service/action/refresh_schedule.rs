@@ -0,0 +1,74 @@
+use axum::extract::{Json, Query};
+use dal::action::refresh_schedule::{RefreshSchedule, DEFAULT_REFRESH_INTERVAL_SECONDS};
+use dal::Visibility;
+use serde::{Deserialize, Serialize};
+
+use super::ActionResult;
+use crate::server::extract::{AccessBuilder, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct GetRefreshScheduleRequest {
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RefreshScheduleResponse {
+    pub enabled: bool,
+    pub interval_seconds: i64,
+    pub last_enqueued_at: Option<String>,
+}
+
+impl From<Option<RefreshSchedule>> for RefreshScheduleResponse {
+    fn from(value: Option<RefreshSchedule>) -> Self {
+        match value {
+            Some(schedule) => Self {
+                enabled: schedule.enabled,
+                interval_seconds: schedule.interval_seconds,
+                last_enqueued_at: schedule.last_enqueued_at.map(|at| at.to_rfc3339()),
+            },
+            None => Self {
+                enabled: false,
+                interval_seconds: DEFAULT_REFRESH_INTERVAL_SECONDS,
+                last_enqueued_at: None,
+            },
+        }
+    }
+}
+
+pub async fn get_refresh_schedule(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    Query(request): Query<GetRefreshScheduleRequest>,
+) -> ActionResult<Json<RefreshScheduleResponse>> {
+    let ctx = builder.build(request_ctx.build(request.visibility)).await?;
+
+    let schedule = RefreshSchedule::get_for_workspace(&ctx).await?;
+
+    Ok(Json(schedule.into()))
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SetRefreshScheduleRequest {
+    pub enabled: bool,
+    pub interval_seconds: i64,
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+pub async fn set_refresh_schedule(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    Json(request): Json<SetRefreshScheduleRequest>,
+) -> ActionResult<Json<RefreshScheduleResponse>> {
+    let ctx = builder.build(request_ctx.build(request.visibility)).await?;
+
+    let schedule = RefreshSchedule::upsert(&ctx, request.enabled, request.interval_seconds).await?;
+
+    ctx.commit_no_rebase().await?;
+
+    Ok(Json(Some(schedule).into()))
+}
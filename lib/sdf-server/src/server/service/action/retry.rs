@@ -1,6 +1,6 @@
 use axum::Json;
 use dal::action::{Action, ActionState};
-use dal::{action::ActionId, Visibility, WsEvent};
+use dal::{action::ActionId, AuditOperation, Visibility, WsEvent};
 use serde::{Deserialize, Serialize};
 
 use super::ActionResult;
@@ -21,6 +21,15 @@ pub async fn retry(
     Json(request): Json<RetryRequest>,
 ) -> ActionResult<()> {
     let ctx = builder.build(request_ctx.build(request.visibility)).await?;
+    ctx.record_audit(
+        AuditOperation::ActionRetry,
+        request
+            .ids
+            .iter()
+            .map(|&action_id| action_id.into())
+            .collect(),
+    )
+    .await?;
     for action_id in request.ids {
         let action = Action::get_by_id(&ctx, action_id).await?;
 
@@ -28,7 +37,10 @@ pub async fn retry(
             ActionState::Running | ActionState::Dispatched => {
                 return Err(ActionError::InvalidOnHoldTransition(action_id))
             }
-            ActionState::Queued | ActionState::Failed | ActionState::OnHold => {}
+            ActionState::Queued
+            | ActionState::Failed
+            | ActionState::OnHold
+            | ActionState::Cancelled => {}
         }
         Action::set_state(&ctx, action.id(), ActionState::Queued).await?;
     }
@@ -0,0 +1,28 @@
+use axum::{response::Response, routing::get, Json, Router};
+use dal::{AuditLogError as DalAuditLogError, TransactionsError};
+use thiserror::Error;
+
+use crate::server::impl_default_error_into_response;
+use crate::server::state::AppState;
+
+pub mod export_entries;
+pub mod list_entries;
+
+#[remain::sorted]
+#[derive(Debug, Error)]
+pub enum AuditLogError {
+    #[error("dal audit log error: {0}")]
+    DalAuditLog(#[from] DalAuditLogError),
+    #[error("transactions error: {0}")]
+    Transactions(#[from] TransactionsError),
+}
+
+pub type AuditLogResult<T> = Result<T, AuditLogError>;
+
+impl_default_error_into_response!(AuditLogError);
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/list_entries", get(list_entries::list_entries))
+        .route("/export_entries", get(export_entries::export_entries))
+}
@@ -0,0 +1,37 @@
+use axum::{extract::Query, http::header, response::IntoResponse};
+use dal::{audit_log, AuditLogEntry, AuditLogFilter};
+
+use super::AuditLogResult;
+use crate::server::extract::{AccessBuilder, HandlerContext};
+
+/// Exports every entry matching the filter as JSON lines, newest first.
+pub async fn export_entries(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Query(filter): Query<AuditLogFilter>,
+) -> AuditLogResult<impl IntoResponse> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let mut body = Vec::new();
+    let mut before = None;
+    loop {
+        let entries = AuditLogEntry::list(&ctx, &filter, before, audit_log::MAX_PAGE_SIZE).await?;
+        body.extend(audit_log::to_json_lines(&entries)?);
+
+        if (entries.len() as i64) < audit_log::MAX_PAGE_SIZE {
+            break;
+        }
+        before = entries.last().map(AuditLogEntry::pk);
+    }
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/x-ndjson"),
+            (
+                header::CONTENT_DISPOSITION,
+                "attachment; filename=\"audit-log.jsonl\"",
+            ),
+        ],
+        body,
+    ))
+}
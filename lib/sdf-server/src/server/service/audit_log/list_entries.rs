@@ -0,0 +1,65 @@
+use axum::{extract::Query, Json};
+use dal::{AuditLogEntry, AuditLogFilter, AuditLogPk, AuditOperation, ChangeSetId, UserPk};
+use serde::{Deserialize, Serialize};
+
+use super::AuditLogResult;
+use crate::server::extract::{AccessBuilder, HandlerContext};
+
+const DEFAULT_LIMIT: i64 = 100;
+
+// NOTE: the filter's fields are repeated here rather than flattened, as flattening breaks
+// deserializing non-string query params
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ListEntriesRequest {
+    pub change_set_id: Option<ChangeSetId>,
+    pub operation: Option<AuditOperation>,
+    pub user_pk: Option<UserPk>,
+    pub entity_id: Option<String>,
+    pub since: Option<chrono::DateTime<chrono::Utc>>,
+    pub until: Option<chrono::DateTime<chrono::Utc>>,
+    /// The last entry of the previous page, if any.
+    pub before: Option<AuditLogPk>,
+    pub limit: Option<i64>,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ListEntriesResponse {
+    pub entries: Vec<AuditLogEntry>,
+    /// Where the next page starts, if there may be one.
+    pub next_before: Option<AuditLogPk>,
+}
+
+pub async fn list_entries(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Query(request): Query<ListEntriesRequest>,
+) -> AuditLogResult<Json<ListEntriesResponse>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let limit = request
+        .limit
+        .unwrap_or(DEFAULT_LIMIT)
+        .clamp(1, dal::audit_log::MAX_PAGE_SIZE);
+    let filter = AuditLogFilter {
+        change_set_id: request.change_set_id,
+        operation: request.operation,
+        user_pk: request.user_pk,
+        entity_id: request.entity_id,
+        since: request.since,
+        until: request.until,
+    };
+
+    let entries = AuditLogEntry::list(&ctx, &filter, request.before, limit).await?;
+    let next_before = if entries.len() as i64 == limit {
+        entries.last().map(AuditLogEntry::pk)
+    } else {
+        None
+    };
+
+    Ok(Json(ListEntriesResponse {
+        entries,
+        next_before,
+    }))
+}
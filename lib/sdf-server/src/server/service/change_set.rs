@@ -12,6 +12,7 @@ use dal::{
     WorkspaceSnapshotError, WsEventError,
 };
 
+use dal::component::query::ComponentQueryError;
 use telemetry::prelude::*;
 use thiserror::Error;
 
@@ -20,6 +21,7 @@ use crate::server::state::AppState;
 pub mod abandon_change_set;
 mod abandon_vote;
 pub mod add_action;
+pub mod add_actions_for_query;
 pub mod apply_change_set;
 mod begin_abandon_approval_process;
 mod begin_approval_process;
@@ -44,6 +46,8 @@ pub enum ChangeSetError {
     ChangeSetNotFound,
     #[error("component error: {0}")]
     Component(#[from] ComponentError),
+    #[error("component query error: {0}")]
+    ComponentQuery(#[from] ComponentQueryError),
     #[error("dal change set error: {0}")]
     DalChangeSet(#[from] DalChangeSetError),
     #[error("dal change set apply error: {0}")]
@@ -56,6 +60,8 @@ pub enum ChangeSetError {
     Schema(#[from] SchemaError),
     #[error("schema variant error: {0}")]
     SchemaVariant(#[from] SchemaVariantError),
+    #[error("serde json error: {0}")]
+    SerdeJson(#[from] serde_json::Error),
     #[error("standard model error: {0}")]
     StandardModel(#[from] StandardModelError),
     #[error("transactions error: {0}")]
@@ -94,6 +100,10 @@ pub fn routes() -> Router<AppState> {
             get(list_open_change_sets::list_open_change_sets),
         )
         .route("/add_action", post(add_action::add_action))
+        .route(
+            "/add_actions_for_query",
+            post(add_actions_for_query::add_actions_for_query),
+        )
         .route(
             "/create_change_set",
             post(create_change_set::create_change_set),
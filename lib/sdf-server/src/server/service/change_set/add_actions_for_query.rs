@@ -0,0 +1,101 @@
+use super::ChangeSetResult;
+use crate::server::extract::{AccessBuilder, HandlerContext, PosthogClient};
+use crate::server::tracking::track;
+use axum::extract::{Json, OriginalUri};
+use axum::response::IntoResponse;
+use dal::action::prototype::ActionKind;
+use dal::action::ActionId;
+use dal::component::query::ComponentQuery;
+use dal::{
+    action::prototype::ActionPrototype, action::Action, ChangeSet, Component, ComponentId,
+    Visibility, WsEvent,
+};
+use serde::{Deserialize, Serialize};
+
+/// Enqueues the [`ActionPrototype`] of the given [`ActionKind`] for every targeted component.
+/// Components can be targeted by id, by a [`ComponentQuery`], or both. Components whose schema
+/// variant has no prototype of that kind, or that already have an equivalent action enqueued, are
+/// skipped.
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct AddActionsForQueryRequest {
+    pub kind: ActionKind,
+    #[serde(default)]
+    pub component_ids: Vec<ComponentId>,
+    pub query: Option<ComponentQuery>,
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct AddActionsForQueryResponse {
+    pub action_ids: Vec<ActionId>,
+}
+
+pub async fn add_actions_for_query(
+    OriginalUri(original_uri): OriginalUri,
+    PosthogClient(posthog_client): PosthogClient,
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    Json(request): Json<AddActionsForQueryRequest>,
+) -> ChangeSetResult<impl IntoResponse> {
+    let mut ctx = builder.build(request_ctx.build(request.visibility)).await?;
+
+    let force_change_set_id = ChangeSet::force_new(&mut ctx).await?;
+
+    let component_ids =
+        ComponentQuery::select_component_ids(&ctx, request.component_ids, request.query.as_ref())
+            .await?;
+
+    let mut action_ids = Vec::new();
+    for component_id in component_ids {
+        let schema_variant_id = Component::schema_variant_id(&ctx, component_id).await?;
+        for prototype in ActionPrototype::for_variant(&ctx, schema_variant_id).await? {
+            if prototype.kind != request.kind {
+                continue;
+            }
+            if Action::find_equivalent(&ctx, prototype.id(), Some(component_id))
+                .await?
+                .is_some()
+            {
+                continue;
+            }
+
+            let action = Action::new(&ctx, prototype.id(), Some(component_id)).await?;
+            action_ids.push(action.id());
+        }
+    }
+
+    track(
+        &posthog_client,
+        &ctx,
+        &original_uri,
+        "create_actions_for_query",
+        serde_json::json!({
+            "how": "/change_set/add_actions_for_query",
+            "action_kind": request.kind,
+            "query": request.query.as_ref().map(|query| query.source()),
+            "action_count": action_ids.len(),
+            "change_set_id": ctx.change_set_id(),
+        }),
+    );
+
+    WsEvent::action_list_updated(&ctx)
+        .await?
+        .publish_on_commit(&ctx)
+        .await?;
+
+    ctx.commit().await?;
+
+    let mut response = axum::response::Response::builder();
+    if let Some(force_change_set_id) = force_change_set_id {
+        response = response.header("force_change_set_id", force_change_set_id.to_string());
+    }
+    response = response.header("content-type", "application/json");
+    Ok(
+        response.body(serde_json::to_string(&AddActionsForQueryResponse {
+            action_ids,
+        })?)?,
+    )
+}
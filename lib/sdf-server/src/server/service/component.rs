@@ -4,6 +4,10 @@ use axum::{
     routing::{get, post},
     Json, Router,
 };
+use dal::component::drift::ResourceDriftError;
+use dal::component::query::ComponentQueryError;
+use dal::component::reconciliation::ReconciliationError;
+use dal::component::resource_import::ResourceImportError;
 use dal::prop::PropError;
 use dal::property_editor::PropertyEditorError;
 use dal::validation::ValidationError;
@@ -24,22 +28,27 @@ use thiserror::Error;
 use crate::server::state::AppState;
 use crate::service::component::conflicts_for_component::conflicts_for_component;
 
+pub mod accept_reconciliation;
 pub mod delete_property_editor_value;
 pub mod get_actions;
 pub mod get_diff;
+pub mod get_drift;
 pub mod get_property_editor_schema;
 pub mod get_property_editor_values;
 pub mod get_resource;
+pub mod import_resources;
 pub mod insert_property_editor_value;
 pub mod json;
 pub mod list_qualifications;
+pub mod query;
+pub mod set_labels;
 pub mod update_property_editor_value;
 // pub mod list_resources;
-pub mod refresh;
-// pub mod resource_domain_diff;
 pub mod conflicts_for_component;
 pub mod debug;
 pub mod get_code;
+pub mod refresh;
+pub mod resource_domain_diff;
 pub mod restore_default_function;
 pub mod set_type;
 mod upgrade;
@@ -59,6 +68,8 @@ pub enum ComponentError {
     ChangeSet(#[from] ChangeSetError),
     #[error("component debug view error: {0}")]
     ComponentDebugView(#[from] ComponentDebugViewError),
+    #[error("component query error: {0}")]
+    ComponentQuery(#[from] ComponentQueryError),
     #[error("dal component error: {0}")]
     DalComponent(#[from] DalComponentError),
     #[error("diagram error: {0}")]
@@ -77,6 +88,12 @@ pub enum ComponentError {
     PropertyEditor(#[from] PropertyEditorError),
     #[error("prop not found for id: {0}")]
     PropNotFound(PropId),
+    #[error("reconciliation error: {0}")]
+    Reconciliation(#[from] ReconciliationError),
+    #[error("resource drift error: {0}")]
+    ResourceDrift(#[from] ResourceDriftError),
+    #[error("resource import error: {0}")]
+    ResourceImport(#[from] ResourceImportError),
     #[error("schema not found")]
     SchemaNotFound,
     #[error("schema variant error: {0}")]
@@ -145,7 +162,13 @@ pub fn routes() -> Router<AppState> {
         )
         .route("/get_code", get(get_code::get_code))
         .route("/get_diff", get(get_diff::get_diff))
+        .route("/get_drift", get(get_drift::get_drift))
+        .route("/list_drifted", get(get_drift::list_drifted))
         .route("/get_resource", get(get_resource::get_resource))
+        .route(
+            "/import_resources",
+            post(import_resources::import_resources),
+        )
         .route(
             "/update_property_editor_value",
             post(update_property_editor_value::update_property_editor_value),
@@ -163,8 +186,14 @@ pub fn routes() -> Router<AppState> {
             post(restore_default_function::restore_default_function),
         )
         .route("/set_type", post(set_type::set_type))
+        .route("/set_labels", post(set_labels::set_labels))
+        .route("/query", get(query::query))
         .route("/refresh", post(refresh::refresh))
-        // .route("/resource_domain_diff", get(resource_domain_diff::get_diff))
+        .route("/resource_domain_diff", get(resource_domain_diff::get_diff))
+        .route(
+            "/accept_reconciliation",
+            post(accept_reconciliation::accept_reconciliation),
+        )
         .route("/debug", get(debug::debug_component))
         .route("/json", get(json::json))
         .route("/upgrade_component", post(upgrade::upgrade))
@@ -0,0 +1,102 @@
+use axum::extract::OriginalUri;
+use axum::{response::IntoResponse, Json};
+use dal::action::ActionId;
+use dal::change_status::ChangeStatus;
+use dal::component::reconciliation::Reconciliation;
+use dal::diagram::SummaryDiagramComponent;
+use dal::{AttributeValueId, ChangeSet, Component, ComponentId, Visibility, WsEvent};
+use serde::{Deserialize, Serialize};
+
+use super::ComponentResult;
+use crate::server::extract::{AccessBuilder, HandlerContext, PosthogClient};
+use crate::server::tracking::track;
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct AcceptReconciliationUpdate {
+    pub attribute_value_id: AttributeValueId,
+    pub value: serde_json::Value,
+}
+
+/// Accepts some (or all) of the updates and actions from a reconciliation proposal into the
+/// change set.
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct AcceptReconciliationRequest {
+    pub component_id: ComponentId,
+    #[serde(default)]
+    pub updates: Vec<AcceptReconciliationUpdate>,
+    #[serde(default)]
+    pub actions: Vec<String>,
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct AcceptReconciliationResponse {
+    pub action_ids: Vec<ActionId>,
+}
+
+pub async fn accept_reconciliation(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    PosthogClient(posthog_client): PosthogClient,
+    OriginalUri(original_uri): OriginalUri,
+    Json(request): Json<AcceptReconciliationRequest>,
+) -> ComponentResult<impl IntoResponse> {
+    let mut ctx = builder.build(request_ctx.build(request.visibility)).await?;
+
+    let force_change_set_id = ChangeSet::force_new(&mut ctx).await?;
+
+    let update_count = request.updates.len();
+    let updates = request
+        .updates
+        .into_iter()
+        .map(|update| (update.attribute_value_id, update.value))
+        .collect();
+    let action_ids =
+        Reconciliation::accept(&ctx, request.component_id, updates, request.actions).await?;
+
+    let component = Component::get_by_id(&ctx, request.component_id).await?;
+    let payload =
+        SummaryDiagramComponent::assemble(&ctx, &component, ChangeStatus::Unmodified).await?;
+    WsEvent::component_updated(&ctx, payload)
+        .await?
+        .publish_on_commit(&ctx)
+        .await?;
+
+    if !action_ids.is_empty() {
+        WsEvent::action_list_updated(&ctx)
+            .await?
+            .publish_on_commit(&ctx)
+            .await?;
+    }
+
+    track(
+        &posthog_client,
+        &ctx,
+        &original_uri,
+        "accept_reconciliation",
+        serde_json::json!({
+            "how": "/component/accept_reconciliation",
+            "component_id": request.component_id,
+            "update_count": update_count,
+            "action_count": action_ids.len(),
+            "change_set_id": ctx.change_set_id(),
+        }),
+    );
+
+    ctx.commit().await?;
+
+    let mut response = axum::response::Response::builder();
+    response = response.header("Content-Type", "application/json");
+    if let Some(force_change_set_id) = force_change_set_id {
+        response = response.header("force_change_set_id", force_change_set_id.to_string());
+    }
+    Ok(
+        response.body(serde_json::to_string(&AcceptReconciliationResponse {
+            action_ids,
+        })?)?,
+    )
+}
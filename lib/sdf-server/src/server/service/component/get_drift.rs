@@ -0,0 +1,58 @@
+use axum::{extract::Query, Json};
+use dal::component::drift::ResourceDrift;
+use dal::{ComponentId, Visibility};
+use serde::{Deserialize, Serialize};
+
+use super::ComponentResult;
+use crate::server::extract::{AccessBuilder, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct GetDriftRequest {
+    pub component_id: ComponentId,
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct GetDriftResponse {
+    pub drift: Option<ResourceDrift>,
+}
+
+pub async fn get_drift(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    Query(request): Query<GetDriftRequest>,
+) -> ComponentResult<Json<GetDriftResponse>> {
+    let ctx = builder.build(request_ctx.build(request.visibility)).await?;
+
+    let drift = ResourceDrift::get_for_component(&ctx, request.component_id).await?;
+
+    Ok(Json(GetDriftResponse { drift }))
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ListDriftedRequest {
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ListDriftedResponse {
+    pub drifted: Vec<ResourceDrift>,
+}
+
+pub async fn list_drifted(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    Query(request): Query<ListDriftedRequest>,
+) -> ComponentResult<Json<ListDriftedResponse>> {
+    let ctx = builder.build(request_ctx.build(request.visibility)).await?;
+
+    let drifted = ResourceDrift::list_drifted(&ctx).await?;
+
+    Ok(Json(ListDriftedResponse { drifted }))
+}
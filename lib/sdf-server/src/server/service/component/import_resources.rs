@@ -0,0 +1,74 @@
+use axum::extract::OriginalUri;
+use axum::{response::IntoResponse, Json};
+use dal::component::resource_import::{ResourceImport, ResourceImportOutcome};
+use dal::{ChangeSet, ComponentId, SchemaVariantId, Visibility};
+use serde::{Deserialize, Serialize};
+
+use super::ComponentResult;
+use crate::server::extract::{AccessBuilder, HandlerContext, PosthogClient};
+use crate::server::tracking::track;
+
+/// Adopts existing resources as new components of the given schema variant, using the variant's
+/// import func. Importing a single resource is a request with a single identifier.
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportResourcesRequest {
+    pub schema_variant_id: SchemaVariantId,
+    pub resource_ids: Vec<String>,
+    pub parent_id: Option<ComponentId>,
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportResourcesResponse {
+    pub outcomes: Vec<ResourceImportOutcome>,
+}
+
+pub async fn import_resources(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    PosthogClient(posthog_client): PosthogClient,
+    OriginalUri(original_uri): OriginalUri,
+    Json(request): Json<ImportResourcesRequest>,
+) -> ComponentResult<impl IntoResponse> {
+    let mut ctx = builder.build(request_ctx.build(request.visibility)).await?;
+
+    let force_change_set_id = ChangeSet::force_new(&mut ctx).await?;
+
+    let outcomes = ResourceImport::import_many(
+        &ctx,
+        request.schema_variant_id,
+        request.resource_ids,
+        request.parent_id,
+    )
+    .await?;
+
+    track(
+        &posthog_client,
+        &ctx,
+        &original_uri,
+        "import_resources",
+        serde_json::json!({
+            "how": "/component/import_resources",
+            "schema_variant_id": request.schema_variant_id,
+            "resource_count": outcomes.len(),
+            "imported_count": outcomes.iter().filter(|outcome| outcome.component_id.is_some()).count(),
+            "change_set_id": ctx.change_set_id(),
+        }),
+    );
+
+    ctx.commit().await?;
+
+    let mut response = axum::response::Response::builder();
+    response = response.header("Content-Type", "application/json");
+    if let Some(force_change_set_id) = force_change_set_id {
+        response = response.header("force_change_set_id", force_change_set_id.to_string());
+    }
+    Ok(
+        response.body(serde_json::to_string(&ImportResourcesResponse {
+            outcomes,
+        })?)?,
+    )
+}
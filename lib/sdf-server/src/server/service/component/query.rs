@@ -0,0 +1,50 @@
+use axum::extract::OriginalUri;
+use axum::{extract::Query, Json};
+use dal::component::query::ComponentQuery;
+use dal::{ComponentId, Visibility};
+use serde::{Deserialize, Serialize};
+
+use super::ComponentResult;
+use crate::server::extract::{AccessBuilder, HandlerContext, PosthogClient};
+use crate::server::tracking::track;
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryComponentsRequest {
+    pub query: ComponentQuery,
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryComponentsResponse {
+    pub component_ids: Vec<ComponentId>,
+}
+
+pub async fn query(
+    OriginalUri(original_uri): OriginalUri,
+    PosthogClient(posthog_client): PosthogClient,
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    Query(request): Query<QueryComponentsRequest>,
+) -> ComponentResult<Json<QueryComponentsResponse>> {
+    let ctx = builder.build(request_ctx.build(request.visibility)).await?;
+
+    let component_ids = request.query.list_matching_ids(&ctx).await?;
+
+    track(
+        &posthog_client,
+        &ctx,
+        &original_uri,
+        "query_components",
+        serde_json::json!({
+            "how": "/component/query",
+            "query": request.query.source(),
+            "match_count": component_ids.len(),
+            "change_set_id": ctx.change_set_id(),
+        }),
+    );
+
+    Ok(Json(QueryComponentsResponse { component_ids }))
+}
@@ -0,0 +1,92 @@
+use std::collections::BTreeMap;
+
+use axum::extract::OriginalUri;
+use axum::{response::IntoResponse, Json};
+use dal::change_status::ChangeStatus;
+use dal::component::query::ComponentQuery;
+use dal::diagram::SummaryDiagramComponent;
+use dal::{ChangeSet, Component, ComponentId, Visibility, WsEvent};
+use serde::{Deserialize, Serialize};
+
+use super::ComponentResult;
+use crate::server::extract::{AccessBuilder, HandlerContext, PosthogClient};
+use crate::server::tracking::track;
+
+/// Sets and removes labels on every targeted [`Component`]. Components can be targeted by id, by
+/// a [`ComponentQuery`], or both.
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SetLabelsRequest {
+    #[serde(default)]
+    pub component_ids: Vec<ComponentId>,
+    pub query: Option<ComponentQuery>,
+    #[serde(default)]
+    pub set: BTreeMap<String, String>,
+    #[serde(default)]
+    pub remove: Vec<String>,
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SetLabelsResponse {
+    pub component_ids: Vec<ComponentId>,
+}
+
+pub async fn set_labels(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    PosthogClient(posthog_client): PosthogClient,
+    OriginalUri(original_uri): OriginalUri,
+    Json(request): Json<SetLabelsRequest>,
+) -> ComponentResult<impl IntoResponse> {
+    let mut ctx = builder.build(request_ctx.build(request.visibility)).await?;
+
+    let force_change_set_id = ChangeSet::force_new(&mut ctx).await?;
+
+    let component_ids =
+        ComponentQuery::select_component_ids(&ctx, request.component_ids, request.query.as_ref())
+            .await?;
+
+    for &component_id in &component_ids {
+        let mut component = Component::get_by_id(&ctx, component_id).await?;
+        for key in &request.remove {
+            component = component.remove_label(&ctx, key).await?;
+        }
+        for (key, value) in &request.set {
+            component = component.set_label(&ctx, key, value).await?;
+        }
+
+        let payload =
+            SummaryDiagramComponent::assemble(&ctx, &component, ChangeStatus::Unmodified).await?;
+        WsEvent::component_updated(&ctx, payload)
+            .await?
+            .publish_on_commit(&ctx)
+            .await?;
+    }
+
+    track(
+        &posthog_client,
+        &ctx,
+        &original_uri,
+        "set_component_labels",
+        serde_json::json!({
+            "how": "/component/set_labels",
+            "component_ids": &component_ids,
+            "query": request.query.as_ref().map(|query| query.source()),
+            "set_keys": request.set.keys().collect::<Vec<_>>(),
+            "removed_keys": &request.remove,
+            "change_set_id": ctx.change_set_id(),
+        }),
+    );
+
+    ctx.commit().await?;
+
+    let mut response = axum::response::Response::builder();
+    response = response.header("Content-Type", "application/json");
+    if let Some(force_change_set_id) = force_change_set_id {
+        response = response.header("force_change_set_id", force_change_set_id.to_string());
+    }
+    Ok(response.body(serde_json::to_string(&SetLabelsResponse { component_ids })?)?)
+}
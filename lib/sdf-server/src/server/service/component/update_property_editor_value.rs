@@ -3,8 +3,8 @@ use axum::{response::IntoResponse, Json};
 use dal::change_status::ChangeStatus;
 use dal::diagram::SummaryDiagramComponent;
 use dal::{
-    AttributeValue, AttributeValueId, ChangeSet, Component, ComponentId, Prop, PropId, Secret,
-    SecretId, Visibility, WsEvent,
+    AttributeValue, AttributeValueId, AuditOperation, ChangeSet, Component, ComponentId, Prop,
+    PropId, Secret, SecretId, Visibility, WsEvent,
 };
 use serde::{Deserialize, Serialize};
 
@@ -50,6 +50,14 @@ pub async fn update_property_editor_value(
     } else {
         AttributeValue::update(&ctx, request.attribute_value_id, request.value).await?;
     }
+    ctx.record_audit(
+        AuditOperation::AttributeValueUpdate,
+        vec![
+            request.component_id.into(),
+            request.attribute_value_id.into(),
+        ],
+    )
+    .await?;
 
     // Track
     let component = Component::get_by_id(&ctx, request.component_id).await?;
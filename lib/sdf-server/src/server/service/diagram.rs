@@ -6,6 +6,7 @@ use axum::Router;
 use dal::attribute::prototype::argument::AttributePrototypeArgumentError;
 use dal::attribute::prototype::AttributePrototypeError;
 use dal::attribute::value::AttributeValueError;
+use dal::component::query::ComponentQueryError;
 use dal::component::ComponentError;
 use dal::socket::input::InputSocketError;
 use dal::socket::output::OutputSocketError;
@@ -46,6 +47,8 @@ pub enum DiagramError {
     Component(#[from] ComponentError),
     #[error("component not found")]
     ComponentNotFound,
+    #[error("component query error: {0}")]
+    ComponentQuery(#[from] ComponentQueryError),
     #[error(transparent)]
     ContextTransaction(#[from] TransactionsError),
     #[error("dal diagram error: {0}")]
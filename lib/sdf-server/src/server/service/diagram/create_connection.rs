@@ -2,7 +2,8 @@ use axum::extract::OriginalUri;
 use axum::{response::IntoResponse, Json};
 use dal::attribute::prototype::argument::AttributePrototypeArgumentId;
 use dal::{
-    ChangeSet, Component, ComponentId, InputSocketId, OutputSocketId, User, Visibility, WsEvent,
+    AuditOperation, ChangeSet, Component, ComponentId, InputSocketId, OutputSocketId, User,
+    Visibility, WsEvent,
 };
 use serde::{Deserialize, Serialize};
 
@@ -49,6 +50,16 @@ pub async fn create_connection(
     )
     .await?
     .ok_or(DiagramError::DuplicatedConnection)?;
+    ctx.record_audit(
+        AuditOperation::ConnectionCreate,
+        vec![
+            request.from_component_id.into(),
+            request.from_socket_id.into(),
+            request.to_component_id.into(),
+            request.to_socket_id.into(),
+        ],
+    )
+    .await?;
 
     track(
         &posthog_client,
@@ -1,8 +1,8 @@
 use axum::extract::OriginalUri;
 use axum::{response::IntoResponse, Json};
 use dal::{
-    ChangeSet, Component, ComponentId, InputSocket, InputSocketId, OutputSocket, OutputSocketId,
-    Visibility, WsEvent,
+    AuditOperation, ChangeSet, Component, ComponentId, InputSocket, InputSocketId, OutputSocket,
+    OutputSocketId, Visibility, WsEvent,
 };
 use serde::{Deserialize, Serialize};
 
@@ -41,6 +41,16 @@ pub async fn delete_connection(
         request.to_socket_id,
     )
     .await?;
+    ctx.record_audit(
+        AuditOperation::ConnectionDelete,
+        vec![
+            request.from_component_id.into(),
+            request.from_socket_id.into(),
+            request.to_component_id.into(),
+            request.to_socket_id.into(),
+        ],
+    )
+    .await?;
 
     let from_component_schema =
         Component::schema_for_component_id(&ctx, request.from_component_id).await?;
@@ -1,8 +1,10 @@
 use axum::extract::OriginalUri;
 use axum::{extract::Query, Json};
+use dal::component::query::ComponentQuery;
 use dal::diagram::Diagram;
-use dal::Visibility;
+use dal::{ComponentId, Visibility};
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 
 use super::DiagramResult;
 use crate::server::extract::{AccessBuilder, HandlerContext, PosthogClient};
@@ -11,6 +13,9 @@ use crate::server::tracking::track;
 #[derive(Deserialize, Serialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct GetDiagramRequest {
+    /// If provided, only the components matching the query (and the edges between them) are
+    /// returned.
+    pub query: Option<ComponentQuery>,
     #[serde(flatten)]
     pub visibility: Visibility,
 }
@@ -25,7 +30,21 @@ pub async fn get_diagram(
     Query(request): Query<GetDiagramRequest>,
 ) -> DiagramResult<Json<GetDiagramResponse>> {
     let ctx = builder.build(request_ctx.build(request.visibility)).await?;
-    let response = Diagram::assemble(&ctx).await?;
+    let mut response = Diagram::assemble(&ctx).await?;
+
+    if let Some(query) = &request.query {
+        let matching: HashSet<ComponentId> =
+            query.list_matching_ids(&ctx).await?.into_iter().collect();
+        response
+            .components
+            .retain(|component| matching.contains(&component.component_id));
+        response.edges.retain(|edge| {
+            matching.contains(&edge.from_component_id) && matching.contains(&edge.to_component_id)
+        });
+        response.inferred_edges.retain(|edge| {
+            matching.contains(&edge.from_component_id) && matching.contains(&edge.to_component_id)
+        });
+    }
 
     track(
         &posthog_client,
@@ -34,6 +53,7 @@ pub async fn get_diagram(
         "get_diagram",
         serde_json::json!({
             "how": "/diagram/get_diagram",
+            "query": request.query.as_ref().map(|query| query.source()),
             "change_set_id": ctx.change_set_id(),
         }),
     );
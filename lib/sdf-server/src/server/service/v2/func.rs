@@ -6,7 +6,10 @@ use axum::{
     Router,
 };
 use dal::{
-    func::{argument::FuncArgumentError, authoring::FuncAuthoringError, binding::FuncBindingError},
+    func::{
+        argument::FuncArgumentError, authoring::FuncAuthoringError, binding::FuncBindingError,
+        replay::FuncReplayError,
+    },
     ChangeSetError, DalContext, Func, FuncError, FuncId, WsEventError,
 };
 use si_frontend_types::FuncCode;
@@ -22,9 +25,12 @@ pub mod delete_func;
 pub mod execute_func;
 pub mod get_code;
 pub mod get_func_run;
+pub mod get_replay_bundle;
 pub mod list_all_funcs;
 pub mod list_funcs;
 pub mod save_code;
+pub mod search_func_runs;
+pub mod test_case;
 pub mod test_execute;
 pub mod update_func;
 
@@ -51,6 +57,8 @@ pub enum FuncAPIError {
     FuncNameReserved(String),
     #[error("The function does not exist")]
     FuncNotFound(FuncId),
+    #[error("func replay error: {0}")]
+    FuncReplay(#[from] FuncReplayError),
     #[error("hyper error: {0}")]
     Http(#[from] axum::http::Error),
     #[error("layer db error: {0}")]
@@ -105,6 +113,11 @@ impl IntoResponse for FuncAPIError {
 
             // Return 404 when the func is not found
             Self::FuncNotFound(_) => StatusCode::NOT_FOUND,
+            Self::FuncReplay(FuncReplayError::FuncRunNotFound(_)) => StatusCode::NOT_FOUND,
+            // Not every func run can be replayed
+            Self::FuncReplay(
+                FuncReplayError::MissingHandler(_) | FuncReplayError::UnsupportedBackendKind(_),
+            ) => StatusCode::UNPROCESSABLE_ENTITY,
             // When a graph node cannot be found for a schema variant, it is not found
             Self::SchemaVariant(dal::SchemaVariantError::NotFound(_)) => StatusCode::NOT_FOUND,
             _ => ApiError::DEFAULT_ERROR_STATUS_CODE,
@@ -121,7 +134,12 @@ pub fn v2_routes() -> Router<AppState> {
         .route("/", get(list_funcs::list_funcs))
         .route("/including_pruned", get(list_all_funcs::list_all_funcs))
         .route("/code", get(get_code::get_code)) // accepts a list of func_ids
+        .route("/runs", get(search_func_runs::search_func_runs))
         .route("/runs/:func_run_id", get(get_func_run::get_func_run)) // accepts a list of func_ids
+        .route(
+            "/runs/:func_run_id/replay_bundle",
+            get(get_replay_bundle::get_replay_bundle),
+        )
         .route("/", post(create_func::create_func))
         .route("/:func_id", put(update_func::update_func)) // only save the func's metadata
         .route("/:func_id/code", put(save_code::save_code)) // only saves func code
@@ -163,6 +181,19 @@ pub fn v2_routes() -> Router<AppState> {
             "/:func_id/arguments/:func_argument_id",
             delete(argument::delete_argument::delete_func_argument),
         )
+        // Func Test Cases
+        .route(
+            "/:func_id/test_cases",
+            put(test_case::set_test_case::set_test_case),
+        )
+        .route(
+            "/:func_id/test_cases/:test_case_name",
+            delete(test_case::delete_test_case::delete_test_case),
+        )
+        .route(
+            "/:func_id/test_cases/run",
+            post(test_case::run_test_cases::run_test_cases),
+        )
 }
 
 // helper to assemble the front end struct to return the code and types so SDF can decide when these events need to fire
@@ -178,7 +178,9 @@ pub async fn delete_binding(
             }
         }
         dal::func::FuncKind::Attribute
+        | dal::func::FuncKind::Import
         | dal::func::FuncKind::Intrinsic
+        | dal::func::FuncKind::Reconciliation
         | dal::func::FuncKind::SchemaVariantDefinition
         | dal::func::FuncKind::Unknown => return Err(FuncAPIError::CannotDeleteBindingForFunc),
     };
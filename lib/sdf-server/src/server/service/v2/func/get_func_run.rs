@@ -5,9 +5,10 @@ use dal::{ContentHash, WorkspacePk};
 use serde::{Deserialize, Serialize};
 use si_events::{
     ActionId, ActionKind, ActionPrototypeId, ActionResultState, Actor, AttributeValueId, CasValue,
-    ChangeSetId, ComponentId, FuncBackendKind, FuncBackendResponseType, FuncKind, FuncRun,
-    FuncRunId, FuncRunLog, FuncRunLogId, FuncRunState, OutputLine,
+    ChangeSetId, ComponentId, FuncBackendKind, FuncBackendResponseType, FuncKind, FuncMemoStatus,
+    FuncRun, FuncRunId, FuncRunLog, FuncRunLogId, FuncRunState, OutputLine,
 };
+use std::collections::BTreeMap;
 use std::sync::Arc;
 
 use crate::server::extract::{AccessBuilder, HandlerContext};
@@ -23,6 +24,7 @@ pub struct OutputLineView {
     pub group: Option<String>,
     pub message: String,
     pub timestamp: u64,
+    pub fields: BTreeMap<String, String>,
 }
 
 impl From<&OutputLine> for OutputLineView {
@@ -34,6 +36,7 @@ impl From<&OutputLine> for OutputLineView {
             group: output_line.group.clone(),
             message: output_line.message.clone(),
             timestamp: output_line.timestamp,
+            fields: output_line.fields.clone(),
         }
     }
 }
@@ -93,6 +96,7 @@ pub struct FuncRunView {
     result_value_cas_address: Option<ContentHash>,
     result_value: Option<serde_json::Value>,
     result_unprocessed_value_cas_address: Option<ContentHash>,
+    memo_status: Option<FuncMemoStatus>,
     logs: Option<FuncRunLogView>,
     created_at: DateTime<Utc>,
     updated_at: DateTime<Utc>,
@@ -137,6 +141,7 @@ impl FuncRunView {
             result_value_cas_address: func_run.result_value_cas_address(),
             result_value,
             result_unprocessed_value_cas_address: func_run.result_unprocessed_value_cas_address(),
+            memo_status: func_run.memo_status(),
             logs,
             created_at: func_run.created_at(),
             updated_at: func_run.updated_at(),
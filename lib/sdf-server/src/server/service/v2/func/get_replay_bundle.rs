@@ -0,0 +1,24 @@
+use axum::extract::Path;
+use axum::Json;
+use dal::{func::replay::export_replay_bundle, WorkspacePk};
+use si_events::FuncRunId;
+use veritech_client::ReplayBundle;
+
+use crate::server::extract::{AccessBuilder, HandlerContext};
+use crate::service::v2::func::FuncAPIResult;
+
+pub async fn get_replay_bundle(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Path((_workspace_pk, change_set_id, func_run_id)): Path<(
+        WorkspacePk,
+        dal::ChangeSetId,
+        FuncRunId,
+    )>,
+) -> FuncAPIResult<Json<ReplayBundle>> {
+    let ctx = builder
+        .build(access_builder.build(change_set_id.into()))
+        .await?;
+
+    Ok(Json(export_replay_bundle(&ctx, func_run_id).await?))
+}
@@ -5,8 +5,8 @@ use axum::{
 };
 
 use dal::{
-    func::authoring::FuncAuthoringClient, ChangeSet, ChangeSetId, Func, FuncId, WorkspacePk,
-    WsEvent,
+    func::authoring::FuncAuthoringClient, AuditOperation, ChangeSet, ChangeSetId, Func, FuncId,
+    WorkspacePk, WsEvent,
 };
 use serde::{Deserialize, Serialize};
 
@@ -37,6 +37,8 @@ pub async fn save_code(
     let force_change_set_id = ChangeSet::force_new(&mut ctx).await?;
 
     FuncAuthoringClient::save_code(&ctx, func_id, request.code).await?;
+    ctx.record_audit(AuditOperation::FuncCodeSave, vec![func_id.into()])
+        .await?;
     let func_code = get_code_response(&ctx, func_id).await?;
     let func = Func::get_by_id_or_error(&ctx, func_id).await?;
     WsEvent::func_code_saved(&ctx, func_code)
@@ -44,6 +46,14 @@ pub async fn save_code(
         .publish_on_commit(&ctx)
         .await?;
 
+    // The test cases stored alongside the func run whenever it is saved, and their results are
+    // found in the func run history
+    let test_case_results = if func.test_cases.is_empty() {
+        Vec::new()
+    } else {
+        FuncAuthoringClient::run_test_cases(&ctx, func_id, None, None).await?
+    };
+
     track(
         &posthog_client,
         &ctx,
@@ -54,6 +64,7 @@ pub async fn save_code(
             "func_id": func_id,
             "func_name": func.name.clone(),
             "func_kind": func.kind.clone(),
+            "test_case_count": test_case_results.len(),
         }),
     );
 
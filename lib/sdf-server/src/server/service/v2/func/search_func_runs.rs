@@ -0,0 +1,110 @@
+use axum::{
+    extract::{Path, Query},
+    Json,
+};
+use chrono::{DateTime, Utc};
+use dal::{ChangeSetId, WorkspacePk};
+use serde::{Deserialize, Serialize};
+use si_events::{ActionKind, ComponentId, FuncKind, FuncRun, FuncRunId, FuncRunState};
+use si_layer_cache::db::func_run::FuncRunSearch;
+
+use crate::server::extract::{AccessBuilder, HandlerContext};
+use crate::service::v2::func::FuncAPIResult;
+
+/// The most func runs returned in one page.
+const MAX_LIMIT: i64 = 200;
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchFuncRunsRequest {
+    pub function_kind: Option<FuncKind>,
+    pub state: Option<FuncRunState>,
+    pub component_id: Option<ComponentId>,
+    pub function_name: Option<String>,
+    pub created_after: Option<DateTime<Utc>>,
+    pub created_before: Option<DateTime<Utc>>,
+    /// Words which must all appear in the logs of the func run.
+    pub log_text: Option<String>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct FuncRunSummaryView {
+    id: FuncRunId,
+    state: FuncRunState,
+    function_name: String,
+    function_display_name: Option<String>,
+    function_kind: FuncKind,
+    component_id: Option<ComponentId>,
+    component_name: Option<String>,
+    schema_name: Option<String>,
+    action_kind: Option<ActionKind>,
+    action_display_name: Option<String>,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl From<FuncRun> for FuncRunSummaryView {
+    fn from(func_run: FuncRun) -> Self {
+        Self {
+            id: func_run.id(),
+            state: func_run.state(),
+            function_name: func_run.function_name().to_string(),
+            function_display_name: func_run.function_display_name().map(|v| v.to_string()),
+            function_kind: func_run.function_kind(),
+            component_id: func_run.component_id(),
+            component_name: func_run.component_name().map(|v| v.to_string()),
+            schema_name: func_run.schema_name().map(|v| v.to_string()),
+            action_kind: func_run.action_kind(),
+            action_display_name: func_run.action_display_name().map(|v| v.to_string()),
+            created_at: func_run.created_at(),
+            updated_at: func_run.updated_at(),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchFuncRunsResponse {
+    pub func_runs: Vec<FuncRunSummaryView>,
+    /// The offset of the next page, if there may be one.
+    pub next_offset: Option<i64>,
+}
+
+pub async fn search_func_runs(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Path((_workspace_pk, change_set_id)): Path<(WorkspacePk, ChangeSetId)>,
+    Query(request): Query<SearchFuncRunsRequest>,
+) -> FuncAPIResult<Json<SearchFuncRunsResponse>> {
+    let ctx = builder
+        .build(access_builder.build(change_set_id.into()))
+        .await?;
+
+    let mut search = FuncRunSearch::new(ctx.events_tenancy().workspace_pk);
+    search.function_kind = request.function_kind;
+    search.state = request.state;
+    search.component_id = request.component_id;
+    search.function_name = request.function_name;
+    search.created_after = request.created_after;
+    search.created_before = request.created_before;
+    search.log_text = request.log_text.filter(|text| !text.trim().is_empty());
+    search.limit = request
+        .limit
+        .unwrap_or(FuncRunSearch::DEFAULT_LIMIT)
+        .clamp(1, MAX_LIMIT);
+    search.offset = request.offset.unwrap_or(0).max(0);
+
+    let func_runs = ctx.layer_db().func_run().search(&search).await?;
+
+    // A full page means there may be more func runs after it
+    let next_offset =
+        (func_runs.len() as i64 == search.limit).then_some(search.offset + search.limit);
+
+    Ok(Json(SearchFuncRunsResponse {
+        func_runs: func_runs.into_iter().map(Into::into).collect(),
+        next_offset,
+    }))
+}
@@ -0,0 +1,3 @@
+pub mod delete_test_case;
+pub mod run_test_cases;
+pub mod set_test_case;
@@ -0,0 +1,63 @@
+use axum::{
+    extract::{OriginalUri, Path},
+    Json,
+};
+use serde::{Deserialize, Serialize};
+
+use dal::{
+    func::{authoring::FuncAuthoringClient, test_case::FuncTestCaseResult},
+    ChangeSetId, ComponentId, Func, FuncId, WorkspacePk,
+};
+
+use crate::{
+    server::{
+        extract::{AccessBuilder, HandlerContext, PosthogClient},
+        tracking::track,
+    },
+    service::v2::func::FuncAPIResult,
+};
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunTestCasesRequest {
+    /// Only run the test case with this name.
+    pub name: Option<String>,
+    /// The component providing secrets to the func, if any.
+    pub component_id: Option<ComponentId>,
+}
+
+pub async fn run_test_cases(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    PosthogClient(posthog_client): PosthogClient,
+    OriginalUri(original_uri): OriginalUri,
+    Path((_workspace_pk, change_set_id, func_id)): Path<(WorkspacePk, ChangeSetId, FuncId)>,
+    Json(request): Json<RunTestCasesRequest>,
+) -> FuncAPIResult<Json<Vec<FuncTestCaseResult>>> {
+    let ctx = builder
+        .build(access_builder.build(change_set_id.into()))
+        .await?;
+
+    let func = Func::get_by_id_or_error(&ctx, func_id).await?;
+    let results =
+        FuncAuthoringClient::run_test_cases(&ctx, func_id, request.name, request.component_id)
+            .await?;
+
+    track(
+        &posthog_client,
+        &ctx,
+        &original_uri,
+        "run_func_test_cases",
+        serde_json::json!({
+            "how": "/func/run_test_cases",
+            "func_id": func_id,
+            "func_name": func.name.clone(),
+            "test_case_count": results.len(),
+            "failed_count": results.iter().filter(|result| !result.passed).count(),
+        }),
+    );
+
+    ctx.commit().await?;
+
+    Ok(Json(results))
+}
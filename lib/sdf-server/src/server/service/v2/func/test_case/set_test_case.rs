@@ -0,0 +1,56 @@
+use axum::{
+    extract::{OriginalUri, Path},
+    response::IntoResponse,
+    Json,
+};
+use dal::{
+    func::{authoring::FuncAuthoringClient, test_case::FuncTestCase},
+    ChangeSet, ChangeSetId, FuncId, WorkspacePk,
+};
+
+use crate::{
+    server::{
+        extract::{AccessBuilder, HandlerContext, PosthogClient},
+        tracking::track,
+    },
+    service::v2::func::FuncAPIResult,
+};
+
+pub async fn set_test_case(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    PosthogClient(posthog_client): PosthogClient,
+    OriginalUri(original_uri): OriginalUri,
+    Path((_workspace_pk, change_set_id, func_id)): Path<(WorkspacePk, ChangeSetId, FuncId)>,
+    Json(request): Json<FuncTestCase>,
+) -> FuncAPIResult<impl IntoResponse> {
+    let mut ctx = builder
+        .build(access_builder.build(change_set_id.into()))
+        .await?;
+    let force_change_set_id = ChangeSet::force_new(&mut ctx).await?;
+
+    let test_case_name = request.name.clone();
+    let func = FuncAuthoringClient::set_test_case(&ctx, func_id, request).await?;
+
+    track(
+        &posthog_client,
+        &ctx,
+        &original_uri,
+        "set_func_test_case",
+        serde_json::json!({
+            "how": "/func/set_test_case",
+            "func_id": func_id,
+            "func_name": func.name.clone(),
+            "test_case_name": test_case_name,
+        }),
+    );
+
+    ctx.commit().await?;
+
+    let mut response = axum::response::Response::builder();
+    response = response.header("Content-Type", "application/json");
+    if let Some(force_change_set_id) = force_change_set_id {
+        response = response.header("force_change_set_id", force_change_set_id.to_string());
+    }
+    Ok(response.body(serde_json::to_string(&func.test_cases)?)?)
+}
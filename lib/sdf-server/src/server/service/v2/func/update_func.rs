@@ -21,6 +21,10 @@ use super::FuncAPIResult;
 pub struct UpdateFuncRequest {
     pub display_name: Option<String>,
     pub description: Option<String>,
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+    #[serde(default)]
+    pub is_pure: Option<bool>,
 }
 
 pub async fn update_func(
@@ -36,11 +40,17 @@ pub async fn update_func(
         .await?;
     let force_change_set_id = ChangeSet::force_new(&mut ctx).await?;
 
-    let updated_func =
-        FuncAuthoringClient::update_func(&ctx, func_id, request.display_name, request.description)
-            .await?
-            .into_frontend_type(&ctx)
-            .await?;
+    let updated_func = FuncAuthoringClient::update_func(
+        &ctx,
+        func_id,
+        request.display_name,
+        request.description,
+        request.timeout_secs,
+        request.is_pure,
+    )
+    .await?
+    .into_frontend_type(&ctx)
+    .await?;
 
     WsEvent::func_updated(&ctx, updated_func.clone())
         .await?
@@ -0,0 +1,59 @@
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use dal::{TransactionsError, WebhookError as DalWebhookError};
+use telemetry::prelude::*;
+use thiserror::Error;
+
+use crate::server::state::AppState;
+
+pub mod create_webhook;
+pub mod delete_webhook;
+pub mod list_deliveries;
+pub mod list_webhooks;
+
+#[remain::sorted]
+#[derive(Debug, Error)]
+pub enum WebhookError {
+    #[error("dal webhook error: {0}")]
+    DalWebhook(#[from] DalWebhookError),
+    #[error("invalid webhook url: {0}")]
+    InvalidUrl(String),
+    #[error("transactions error: {0}")]
+    Transactions(#[from] TransactionsError),
+}
+
+pub type WebhookResult<T> = Result<T, WebhookError>;
+
+impl IntoResponse for WebhookError {
+    fn into_response(self) -> Response {
+        let (status, error_message) = match self {
+            WebhookError::DalWebhook(DalWebhookError::WebhookSubscriptionNotFound(_)) => {
+                (StatusCode::NOT_FOUND, self.to_string())
+            }
+            WebhookError::DalWebhook(
+                DalWebhookError::EmptyEventKinds | DalWebhookError::UnknownEventKind(_),
+            )
+            | WebhookError::InvalidUrl(_) => (StatusCode::BAD_REQUEST, self.to_string()),
+            _ => (StatusCode::INTERNAL_SERVER_ERROR, self.to_string()),
+        };
+
+        let body = Json(
+            serde_json::json!({ "error": { "message": error_message, "code": 42, "statusCode": status.as_u16() } }),
+        );
+
+        error!(si.error.message = error_message);
+        (status, body).into_response()
+    }
+}
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/list_webhooks", get(list_webhooks::list_webhooks))
+        .route("/create_webhook", post(create_webhook::create_webhook))
+        .route("/delete_webhook", post(delete_webhook::delete_webhook))
+        .route("/list_deliveries", get(list_deliveries::list_deliveries))
+}
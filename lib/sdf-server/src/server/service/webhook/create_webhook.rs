@@ -0,0 +1,53 @@
+use axum::Json;
+use dal::WebhookSubscription;
+use serde::{Deserialize, Serialize};
+
+use super::{WebhookError, WebhookResult};
+use crate::server::{
+    extract::{AccessBuilder, HandlerContext},
+    webhooks::check_url_host,
+};
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateWebhookRequest {
+    pub url: String,
+    pub event_kinds: Vec<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateWebhookResponse {
+    pub webhook: WebhookSubscription,
+    /// The secret deliveries are signed with, which is only ever returned here.
+    pub signing_secret: String,
+}
+
+pub async fn create_webhook(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Json(request): Json<CreateWebhookRequest>,
+) -> WebhookResult<Json<CreateWebhookResponse>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let url =
+        url::Url::parse(&request.url).map_err(|err| WebhookError::InvalidUrl(err.to_string()))?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(WebhookError::InvalidUrl(format!(
+            "unsupported scheme: {}",
+            url.scheme()
+        )));
+    }
+    // Hosts which are names are checked when deliveries are made, as what they resolve to can
+    // change
+    check_url_host(&url).map_err(|err| WebhookError::InvalidUrl(err.to_string()))?;
+
+    let webhook = WebhookSubscription::new(&ctx, url, request.event_kinds).await?;
+
+    ctx.commit_no_rebase().await?;
+
+    Ok(Json(CreateWebhookResponse {
+        signing_secret: webhook.signing_secret_hex(),
+        webhook,
+    }))
+}
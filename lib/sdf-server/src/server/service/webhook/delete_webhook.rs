@@ -0,0 +1,29 @@
+use axum::Json;
+use dal::{WebhookSubscription, WebhookSubscriptionPk};
+use serde::{Deserialize, Serialize};
+
+use super::WebhookResult;
+use crate::server::extract::{AccessBuilder, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteWebhookRequest {
+    pub pk: WebhookSubscriptionPk,
+}
+
+pub async fn delete_webhook(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Json(request): Json<DeleteWebhookRequest>,
+) -> WebhookResult<Json<()>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    WebhookSubscription::get_by_pk(&ctx, request.pk)
+        .await?
+        .delete(&ctx)
+        .await?;
+
+    ctx.commit_no_rebase().await?;
+
+    Ok(Json(()))
+}
@@ -0,0 +1,34 @@
+use axum::{extract::Query, Json};
+use dal::{WebhookDelivery, WebhookSubscription, WebhookSubscriptionPk};
+use serde::{Deserialize, Serialize};
+
+use super::WebhookResult;
+use crate::server::extract::{AccessBuilder, HandlerContext};
+
+const DEFAULT_LIMIT: i64 = 50;
+const MAX_LIMIT: i64 = 500;
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ListDeliveriesRequest {
+    pub pk: WebhookSubscriptionPk,
+    pub limit: Option<i64>,
+}
+
+pub type ListDeliveriesResponse = Vec<WebhookDelivery>;
+
+pub async fn list_deliveries(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Query(request): Query<ListDeliveriesRequest>,
+) -> WebhookResult<Json<ListDeliveriesResponse>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    // Ensures the subscription belongs to the workspace before listing its deliveries
+    let webhook = WebhookSubscription::get_by_pk(&ctx, request.pk).await?;
+    let limit = request.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+
+    let deliveries = WebhookDelivery::list_for_subscription(&ctx, webhook.pk(), limit).await?;
+
+    Ok(Json(deliveries))
+}
@@ -0,0 +1,18 @@
+use axum::Json;
+use dal::WebhookSubscription;
+
+use super::WebhookResult;
+use crate::server::extract::{AccessBuilder, HandlerContext};
+
+pub type ListWebhooksResponse = Vec<WebhookSubscription>;
+
+pub async fn list_webhooks(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+) -> WebhookResult<Json<ListWebhooksResponse>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let webhooks = WebhookSubscription::list(&ctx).await?;
+
+    Ok(Json(webhooks))
+}
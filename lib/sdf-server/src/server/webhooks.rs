@@ -0,0 +1,491 @@
+//! This module contains the dispatcher which delivers [`WsEvents`](dal::WsEvent) to the
+//! [`WebhookSubscriptions`](WebhookSubscription) of their workspace.
+//!
+//! Events are received on a queue group so that each is only enqueued by one sdf instance, no
+//! matter how many are running. Deliveries are then claimed from the database by whichever
+//! instance gets to them first, which also picks up the retries of instances that have gone away.
+//!
+//! Deliveries are only made to public addresses, so that a subscription can't be used to reach
+//! services on our own hosts or networks. Hosts are checked each time they are resolved, as what a
+//! host resolves to can change after its subscription is created, and redirects are not followed.
+
+use std::{
+    collections::HashMap,
+    net::{IpAddr, SocketAddr},
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use chrono::Utc;
+use dal::{
+    webhook::{DELIVERY_HEADER, EVENT_KIND_HEADER, SIGNATURE_HEADER, TIMESTAMP_HEADER},
+    DalContext, ServicesContext, TransactionsError, WebhookDelivery, WebhookError,
+    WebhookSubscription, WebhookSubscriptionPk, WorkspacePk, WsEvent,
+};
+use futures::{future::join_all, StreamExt};
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use telemetry::prelude::*;
+use thiserror::Error;
+use tokio::sync::{broadcast, Notify};
+use url::{Host, Url};
+
+/// The subject that workspace events are published on.
+const WEBHOOK_EVENT_SUBJECT: &str = "si.workspace_pk.*.event";
+/// The queue group the dispatchers of every sdf instance subscribe with.
+const WEBHOOK_QUEUE_GROUP: &str = "sdf-webhooks";
+
+/// How long a delivery attempt may take before it is considered failed.
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(10);
+/// How often deliveries are claimed when no events are arriving, which is how retries are picked
+/// up.
+const DISPATCH_INTERVAL: Duration = Duration::from_secs(5);
+/// The most deliveries attempted at once.
+const DISPATCH_BATCH_SIZE: i64 = 20;
+/// How long a workspace's subscriptions are cached for, which is how long a new subscription may
+/// take to start receiving events.
+const SUBSCRIPTION_CACHE_TTL: Duration = Duration::from_secs(30);
+
+#[remain::sorted]
+#[derive(Debug, Error)]
+pub enum WebhookDispatcherError {
+    #[error("reqwest error: {0}")]
+    Reqwest(#[from] reqwest::Error),
+    #[error("json serialization error: {0}")]
+    SerdeJson(#[from] serde_json::Error),
+    #[error("transactions error: {0}")]
+    Transactions(#[from] TransactionsError),
+    #[error("webhook error: {0}")]
+    Webhook(#[from] WebhookError),
+}
+
+type WebhookDispatcherResult<T> = Result<T, WebhookDispatcherError>;
+
+/// A webhook host which is, or resolves to, an address deliveries may not be made to.
+#[derive(Debug, Error)]
+#[error("webhook host {host} is not a public address: {address}")]
+pub(crate) struct NonPublicAddressError {
+    host: String,
+    address: IpAddr,
+}
+
+/// Enqueues deliveries of workspace events and sends them to subscribers.
+#[derive(Clone)]
+pub struct WebhookDispatcher {
+    services_context: ServicesContext,
+    http_client: reqwest::Client,
+    due: Arc<Notify>,
+}
+
+/// The outcome of a single delivery attempt.
+enum Attempt {
+    Delivered(u16),
+    Failed(Option<u16>, String),
+}
+
+impl WebhookDispatcher {
+    pub fn new(services_context: ServicesContext) -> WebhookDispatcherResult<Self> {
+        Ok(Self {
+            services_context,
+            http_client: http_client()?,
+            due: Arc::new(Notify::new()),
+        })
+    }
+
+    /// Runs the dispatcher until shutdown.
+    pub async fn run(self, shutdown_broadcast_rx: broadcast::Receiver<()>) {
+        let mut subscriber = match self
+            .services_context
+            .nats_conn()
+            .queue_subscribe(WEBHOOK_EVENT_SUBJECT, WEBHOOK_QUEUE_GROUP.to_owned())
+            .await
+        {
+            Ok(subscriber) => subscriber,
+            Err(err) => {
+                error!(error = ?err, "failed to subscribe to workspace events for webhooks");
+                return;
+            }
+        };
+
+        tokio::spawn(self.clone().dispatch(shutdown_broadcast_rx.resubscribe()));
+
+        let mut shutdown_broadcast_rx = shutdown_broadcast_rx;
+        let mut subscriptions = HashMap::new();
+        loop {
+            tokio::select! {
+                _ = shutdown_broadcast_rx.recv() => {
+                    info!("webhook dispatcher received shutdown, ending event subscription");
+                    break;
+                }
+                maybe_message = subscriber.next() => {
+                    let Some(message) = maybe_message else {
+                        warn!("workspace event subscription closed, ending webhook dispatcher");
+                        break;
+                    };
+                    match self.enqueue(&mut subscriptions, message.payload()).await {
+                        Ok(true) => self.due.notify_one(),
+                        Ok(false) => {}
+                        Err(err) => warn!(error = ?err, "failed to enqueue webhook deliveries"),
+                    }
+                }
+            }
+        }
+    }
+
+    /// Sends due deliveries whenever some are enqueued, and periodically for retries.
+    async fn dispatch(self, mut shutdown_broadcast_rx: broadcast::Receiver<()>) {
+        let mut interval = tokio::time::interval(DISPATCH_INTERVAL);
+        loop {
+            tokio::select! {
+                _ = shutdown_broadcast_rx.recv() => {
+                    info!("webhook dispatcher received shutdown, ending deliveries");
+                    break;
+                }
+                _ = self.due.notified() => {}
+                _ = interval.tick() => {}
+            }
+
+            loop {
+                match self.dispatch_due().await {
+                    // A full batch means more deliveries may be due already
+                    Ok(count) if count as i64 == DISPATCH_BATCH_SIZE => continue,
+                    Ok(_) => break,
+                    Err(err) => {
+                        warn!(error = ?err, "failed to dispatch webhook deliveries");
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Enqueues a delivery of the event to each subscription of its workspace which accepts it,
+    /// returning whether any were enqueued.
+    async fn enqueue(
+        &self,
+        subscriptions: &mut HashMap<WorkspacePk, (Instant, Vec<WebhookSubscription>)>,
+        payload: &[u8],
+    ) -> WebhookDispatcherResult<bool> {
+        let event: WsEvent = serde_json::from_slice(payload)?;
+        let workspace_pk = event.workspace_pk();
+
+        let ctx = self.ctx().await?;
+
+        let cached = subscriptions
+            .get(&workspace_pk)
+            .filter(|(fetched_at, _)| fetched_at.elapsed() < SUBSCRIPTION_CACHE_TTL);
+        if cached.is_none() {
+            let fetched = WebhookSubscription::list_for_workspace(&ctx, workspace_pk).await?;
+            subscriptions.insert(workspace_pk, (Instant::now(), fetched));
+        }
+        let accepting: Vec<WebhookSubscriptionPk> = subscriptions
+            .get(&workspace_pk)
+            .map(|(_, subscriptions)| {
+                subscriptions
+                    .iter()
+                    .filter(|subscription| subscription.accepts(event.kind()))
+                    .map(WebhookSubscription::pk)
+                    .collect()
+            })
+            .unwrap_or_default();
+        if accepting.is_empty() {
+            return Ok(false);
+        }
+
+        let payload = serde_json::to_value(&event)?;
+        for subscription_pk in accepting {
+            WebhookDelivery::enqueue(
+                &ctx,
+                subscription_pk,
+                workspace_pk,
+                event.kind(),
+                payload.clone(),
+            )
+            .await?;
+        }
+        ctx.commit_no_rebase().await?;
+
+        Ok(true)
+    }
+
+    /// Claims and sends a batch of due deliveries, returning how many were claimed.
+    async fn dispatch_due(&self) -> WebhookDispatcherResult<usize> {
+        // Claims are committed before sending so that no other dispatcher sends them meanwhile
+        let ctx = self.ctx().await?;
+        let deliveries = WebhookDelivery::claim_due(&ctx, DISPATCH_BATCH_SIZE).await?;
+        ctx.commit_no_rebase().await?;
+        if deliveries.is_empty() {
+            return Ok(0);
+        }
+
+        let ctx = self.ctx().await?;
+        let mut subscriptions: HashMap<WebhookSubscriptionPk, WebhookSubscription> = HashMap::new();
+        for delivery in &deliveries {
+            if !subscriptions.contains_key(&delivery.subscription_pk()) {
+                for subscription in
+                    WebhookSubscription::list_for_workspace(&ctx, delivery.workspace_pk()).await?
+                {
+                    subscriptions.insert(subscription.pk(), subscription);
+                }
+            }
+        }
+
+        let attempts = join_all(deliveries.iter().map(|delivery| {
+            let subscription = subscriptions.get(&delivery.subscription_pk());
+            self.send(subscription, delivery)
+        }))
+        .await;
+
+        let count = deliveries.len();
+        for (mut delivery, attempt) in deliveries.into_iter().zip(attempts) {
+            match attempt {
+                Attempt::Delivered(status) => delivery.record_success(&ctx, status).await?,
+                Attempt::Failed(status, error) => {
+                    debug!(
+                        si.webhook.delivery.pk = %delivery.pk(),
+                        error, "webhook delivery attempt failed"
+                    );
+                    delivery.record_failure(&ctx, status, error).await?;
+                }
+            }
+        }
+        ctx.commit_no_rebase().await?;
+
+        Ok(count)
+    }
+
+    async fn send(
+        &self,
+        subscription: Option<&WebhookSubscription>,
+        delivery: &WebhookDelivery,
+    ) -> Attempt {
+        let Some(subscription) = subscription else {
+            return Attempt::Failed(None, "webhook subscription not found".to_owned());
+        };
+        let url = match Url::parse(subscription.url()) {
+            Ok(url) => url,
+            Err(err) => return Attempt::Failed(None, err.to_string()),
+        };
+        if let Err(err) = check_url_host(&url) {
+            return Attempt::Failed(None, err.to_string());
+        }
+
+        let body = match serde_json::to_vec(delivery.payload()) {
+            Ok(body) => body,
+            Err(err) => return Attempt::Failed(None, err.to_string()),
+        };
+        let timestamp = Utc::now();
+        let signature = match subscription.sign(timestamp, &body) {
+            Ok(signature) => signature,
+            Err(err) => return Attempt::Failed(None, err.to_string()),
+        };
+
+        let result = self
+            .http_client
+            .post(url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(SIGNATURE_HEADER, signature)
+            .header(TIMESTAMP_HEADER, timestamp.timestamp().to_string())
+            .header(EVENT_KIND_HEADER, delivery.event_kind())
+            .header(DELIVERY_HEADER, delivery.pk().to_string())
+            .body(body)
+            .send()
+            .await;
+
+        match result {
+            Ok(response) if response.status().is_success() => {
+                Attempt::Delivered(response.status().as_u16())
+            }
+            Ok(response) => Attempt::Failed(
+                Some(response.status().as_u16()),
+                format!("unexpected response status: {}", response.status()),
+            ),
+            Err(err) => Attempt::Failed(None, err.to_string()),
+        }
+    }
+
+    async fn ctx(&self) -> WebhookDispatcherResult<DalContext> {
+        Ok(self
+            .services_context
+            .clone()
+            .into_builder(false)
+            .build_default()
+            .await?)
+    }
+}
+
+/// Builds the client deliveries are sent with, which only connects to public addresses and doesn't
+/// follow redirects.
+fn http_client() -> reqwest::Result<reqwest::Client> {
+    reqwest::Client::builder()
+        .timeout(DELIVERY_TIMEOUT)
+        .redirect(reqwest::redirect::Policy::none())
+        .dns_resolver(Arc::new(PublicAddressResolver))
+        .build()
+}
+
+/// Resolves the hosts of webhook URLs, refusing any host with an address that isn't public.
+struct PublicAddressResolver;
+
+impl Resolve for PublicAddressResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(resolve_public(name))
+    }
+}
+
+async fn resolve_public(name: Name) -> Result<Addrs, Box<dyn std::error::Error + Send + Sync>> {
+    let host = name.as_str();
+    let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host, 0)).await?.collect();
+    if let Some(addr) = addrs.iter().find(|addr| !is_public_address(addr.ip())) {
+        return Err(Box::new(NonPublicAddressError {
+            host: host.to_owned(),
+            address: addr.ip(),
+        }));
+    }
+
+    Ok(Box::new(addrs.into_iter()))
+}
+
+/// Refuses URLs whose host is an address that isn't public. These are connected to without being
+/// resolved, so aren't checked by the [`PublicAddressResolver`].
+pub(crate) fn check_url_host(url: &Url) -> Result<(), NonPublicAddressError> {
+    let address = match url.host() {
+        Some(Host::Ipv4(address)) => IpAddr::V4(address),
+        Some(Host::Ipv6(address)) => IpAddr::V6(address),
+        Some(Host::Domain(_)) | None => return Ok(()),
+    };
+    if is_public_address(address) {
+        Ok(())
+    } else {
+        Err(NonPublicAddressError {
+            host: url.host_str().unwrap_or_default().to_owned(),
+            address,
+        })
+    }
+}
+
+/// Whether an address is one deliveries may be made to, which excludes the loopback, private,
+/// link-local, shared and unspecified addresses of our own hosts and networks.
+fn is_public_address(address: IpAddr) -> bool {
+    match address {
+        IpAddr::V4(address) => {
+            let [first, second, ..] = address.octets();
+            // 100.64.0.0/10 is shared address space, used by carrier-grade NAT and some VPNs
+            let is_shared = first == 100 && (second & 0b1100_0000) == 64;
+            !(address.is_loopback()
+                || address.is_private()
+                || address.is_link_local()
+                || address.is_unspecified()
+                || address.is_broadcast()
+                || address.is_documentation()
+                || is_shared)
+        }
+        IpAddr::V6(address) => match address.to_ipv4_mapped() {
+            Some(mapped) => is_public_address(IpAddr::V4(mapped)),
+            None => {
+                let first_segment = address.segments()[0];
+                // fc00::/7 is unique local and fe80::/10 is link-local
+                let is_unique_local = (first_segment & 0xfe00) == 0xfc00;
+                let is_link_local = (first_segment & 0xffc0) == 0xfe80;
+                !(address.is_loopback()
+                    || address.is_unspecified()
+                    || is_unique_local
+                    || is_link_local)
+            }
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use tokio::net::TcpListener;
+
+    use super::*;
+
+    fn url(url: &str) -> Url {
+        Url::parse(url).expect("invalid url")
+    }
+
+    #[test]
+    fn only_public_addresses_are_allowed() {
+        for address in [
+            "127.0.0.1",
+            "10.1.2.3",
+            "172.16.0.1",
+            "192.168.1.1",
+            "169.254.169.254",
+            "100.100.100.100",
+            "0.0.0.0",
+            "255.255.255.255",
+            "::1",
+            "::",
+            "fd00::1",
+            "fe80::1",
+            "::ffff:10.0.0.1",
+        ] {
+            let address = IpAddr::from_str(address).expect("invalid address");
+            assert!(
+                !is_public_address(address),
+                "{address} should not be public"
+            );
+        }
+        for address in ["93.184.216.34", "100.128.0.1", "2606:2800:220:1::1"] {
+            let address = IpAddr::from_str(address).expect("invalid address");
+            assert!(is_public_address(address), "{address} should be public");
+        }
+    }
+
+    #[test]
+    fn url_hosts_which_are_non_public_addresses_are_refused() {
+        for refused in [
+            "http://127.0.0.1:8080/hook",
+            "http://169.254.169.254/latest/meta-data",
+            "https://[::1]/hook",
+            "https://[::ffff:192.168.0.1]/hook",
+        ] {
+            assert!(check_url_host(&url(refused)).is_err(), "{refused}");
+        }
+        for allowed in [
+            "https://example.com/hook",
+            "http://localhost/hook",
+            "https://93.184.216.34/hook",
+        ] {
+            assert!(check_url_host(&url(allowed)).is_ok(), "{allowed}");
+        }
+    }
+
+    #[tokio::test]
+    async fn resolver_refuses_hosts_with_non_public_addresses() {
+        let name = Name::from_str("localhost").expect("invalid name");
+        let result = PublicAddressResolver.resolve(name).await;
+
+        let err = result.err().expect("localhost should be refused");
+        assert!(
+            err.downcast_ref::<NonPublicAddressError>().is_some(),
+            "unexpected error: {err}"
+        );
+    }
+
+    #[tokio::test]
+    async fn deliveries_are_not_made_to_hosts_with_non_public_addresses() {
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("failed to bind listener");
+        let port = listener
+            .local_addr()
+            .expect("listener has no address")
+            .port();
+
+        let result = http_client()
+            .expect("failed to build client")
+            .post(format!("http://localhost:{port}/hook"))
+            .body("{}")
+            .send()
+            .await;
+
+        assert!(result.is_err(), "delivery to localhost should fail");
+        let accepted = tokio::time::timeout(Duration::from_millis(100), listener.accept()).await;
+        assert!(accepted.is_err(), "delivery should not have connected");
+    }
+}
@@ -21,7 +21,10 @@ use std::{
     io::Write,
     net::ToSocketAddrs,
     path::Path,
-    sync::Arc,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc, Weak,
+    },
     time::Duration,
 };
 
@@ -32,7 +35,7 @@ use deadpool_postgres::{
     Config, ConfigError, CreatePoolError, Manager, ManagerConfig, Pool, PoolConfig, PoolError,
     RecyclingMethod, Transaction, TransactionBuilder,
 };
-use futures::{Stream, StreamExt};
+use futures::{future::join_all, Stream, StreamExt};
 
 use ouroboros::self_referencing;
 
@@ -56,6 +59,22 @@ const MAX_POOL_SIZE_MINIMUM: usize = 32;
 
 const TEST_QUERY: &str = "SELECT 1";
 
+/// How often the health of read replicas is checked.
+const REPLICA_HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+/// How long a replica has to answer a health check before it is considered unhealthy.
+const REPLICA_HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+/// How far a replica can lag behind the primary, by default, before it is considered unhealthy.
+const DEFAULT_MAX_REPLICATION_LAG_SECS: u64 = 30;
+
+// The time since the replica last replayed a transaction from the primary, in seconds. A replica
+// which has replayed everything it has received is caught up however long ago that was, and a
+// server which isn't replaying at all (such as a primary) has no lag, so both report `0`.
+const REPLICATION_LAG_QUERY: &str = "SELECT CASE
+        WHEN NOT pg_is_in_recovery() THEN 0
+        WHEN pg_last_wal_receive_lsn() = pg_last_wal_replay_lsn() THEN 0
+        ELSE COALESCE(EXTRACT(EPOCH FROM (now() - pg_last_xact_replay_timestamp())), 0)
+    END::float8";
+
 // If a table's structure changes, cached query plans against that table need to
 // be invalidated, or postgresql will return an error. This prevents that error
 // after migrating the database in a production system running pb_bouncer, which
@@ -114,6 +133,8 @@ pub enum PgPoolError {
     ReadPem(std::io::Error),
     #[error("migration error: {0}")]
     Refinery(#[from] refinery::Error),
+    #[error("replica is {0:.1}s behind the primary, more than the maximum of {1}s")]
+    ReplicationLag(f64, u64),
     #[error("reqwest error: {0}")]
     Reqwest(#[from] reqwest::Error),
     #[error("failed to resolve pg hostname")]
@@ -147,6 +168,7 @@ pub struct PgPoolConfig {
     pub pool_timeout_wait_secs: Option<u64>,
     pub pool_timeout_create_secs: Option<u64>,
     pub pool_timeout_recycle_secs: Option<u64>,
+    pub read_replicas: Vec<PgReplicaConfig>,
 }
 
 impl Default for PgPoolConfig {
@@ -167,24 +189,107 @@ impl Default for PgPoolConfig {
             pool_timeout_wait_secs: None,
             pool_timeout_create_secs: None,
             pool_timeout_recycle_secs: None,
+            read_replicas: Vec::new(),
         }
     }
 }
 
+/// A read replica of the primary database.
+///
+/// Replicas are connected to with the same credentials, database name, pool settings and
+/// certificates as the primary. A replica is only used while it is no more than
+/// `max_replication_lag_secs` behind the primary.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct PgReplicaConfig {
+    pub hostname: String,
+    #[serde(default = "default_replica_port")]
+    pub port: u16,
+    #[serde(default = "default_max_replication_lag_secs")]
+    pub max_replication_lag_secs: u64,
+}
+
+fn default_replica_port() -> u16 {
+    5432
+}
+
+fn default_max_replication_lag_secs() -> u64 {
+    DEFAULT_MAX_REPLICATION_LAG_SECS
+}
+
 #[derive(Clone)]
 pub struct PgPool {
     pool: Pool,
     metadata: Arc<ConnectionMetadata>,
+    replicas: Arc<[PgReplica]>,
+    next_replica: Arc<AtomicUsize>,
 }
 
 impl std::fmt::Debug for PgPool {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("PgPool")
             .field("metadata", &self.metadata)
+            .field("replicas", &self.replicas)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A pool of connections to a read replica, along with whether it passed its last health check.
+struct PgReplica {
+    pool: Pool,
+    metadata: Arc<ConnectionMetadata>,
+    max_replication_lag_secs: u64,
+    healthy: AtomicBool,
+}
+
+impl std::fmt::Debug for PgReplica {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PgReplica")
+            .field("metadata", &self.metadata)
+            .field("healthy", &self.is_healthy())
             .finish_non_exhaustive()
     }
 }
 
+impl PgReplica {
+    fn is_healthy(&self) -> bool {
+        self.healthy.load(Ordering::Relaxed)
+    }
+
+    fn set_healthy(&self, healthy: bool) {
+        if self.healthy.swap(healthy, Ordering::Relaxed) != healthy {
+            if healthy {
+                info!(
+                    db.connection_string = %self.metadata.db_connection_string,
+                    "pg read replica is healthy, routing read-only connections to it"
+                );
+            } else {
+                warn!(
+                    db.connection_string = %self.metadata.db_connection_string,
+                    "pg read replica is unhealthy, routing read-only connections elsewhere"
+                );
+            }
+        }
+    }
+
+    async fn check_health(&self) -> PgPoolResult<()> {
+        let conn = self.pool.get().await?;
+        let row = conn.query_one(REPLICATION_LAG_QUERY, &[]).await?;
+        let lag_secs: f64 = row.try_get(0)?;
+        check_replication_lag(lag_secs, self.max_replication_lag_secs)
+    }
+}
+
+// Replicas which lag too far behind would serve stale reads, so they are unhealthy
+fn check_replication_lag(lag_secs: f64, max_replication_lag_secs: u64) -> PgPoolResult<()> {
+    if lag_secs > max_replication_lag_secs as f64 {
+        return Err(PgPoolError::ReplicationLag(
+            lag_secs,
+            max_replication_lag_secs,
+        ));
+    }
+    Ok(())
+}
+
 #[derive(Clone, Debug)]
 struct ConnectionMetadata {
     db_system: &'static str,
@@ -214,9 +319,83 @@ impl PgPool {
         )
     )]
     pub async fn new(settings: &PgPoolConfig) -> PgPoolResult<Self> {
+        let tls_config = Self::tls_config(settings).await?;
+        let (pool, metadata) = Self::create_pool(
+            settings,
+            &settings.hostname,
+            settings.port,
+            tls_config.clone(),
+        )
+        .await?;
+
+        let mut replicas = Vec::with_capacity(settings.read_replicas.len());
+        for replica in &settings.read_replicas {
+            let (pool, metadata) = Self::create_pool(
+                settings,
+                &replica.hostname,
+                replica.port,
+                tls_config.clone(),
+            )
+            .await?;
+            replicas.push(PgReplica {
+                pool,
+                metadata: Arc::new(metadata),
+                max_replication_lag_secs: replica.max_replication_lag_secs,
+                // Replicas aren't used until they have passed a health check
+                healthy: AtomicBool::new(false),
+            });
+        }
+        let replicas: Arc<[PgReplica]> = replicas.into();
+
+        let span = Span::current();
+        span.record("db.system", metadata.db_system);
+        span.record(
+            "db.connection_string",
+            metadata.db_connection_string.as_str(),
+        );
+        span.record("db.name", metadata.db_name.as_str());
+        span.record("db.user", metadata.db_user.as_str());
+        span.record("db.pool.max_size", metadata.db_pool_max_size);
+        span.record("net.peer.ip", metadata.net_peer_ip.as_str());
+        span.record("net.peer.port", metadata.net_peer_port);
+        span.record("net.transport", metadata.net_transport);
+
+        let pg_pool = Self {
+            pool,
+            metadata: Arc::new(metadata),
+            replicas,
+            next_replica: Arc::new(AtomicUsize::new(0)),
+        };
+
+        // Warm up the pool and test that we can connect to the database. Note that this is only
+        // advisory--it will not terminate any process or service that may be running or about to
+        // be run. We assume that the pool is an autonomous actor that can make forward progress
+        // towards its goal and maintain its own healthiness. This is in order to prevent a
+        // database network connection hiccup from crashing a fleet of services which may get
+        // immediately rescheduled/restarted only to fall into a perpetual crash loop while not
+        // being able to serve any traffic--including health/readiness status.
+        drop(tokio::spawn(
+            test_connection_infallible_and_warm_up_pool_task(pg_pool.clone()),
+        ));
+        if !pg_pool.replicas.is_empty() {
+            drop(tokio::spawn(check_replica_health_task(Arc::downgrade(
+                &pg_pool.replicas,
+            ))));
+        }
+
+        Ok(pg_pool)
+    }
+
+    // Creates a pool of connections to the given host, using the rest of the settings
+    async fn create_pool(
+        settings: &PgPoolConfig,
+        hostname: &str,
+        port: u16,
+        tls_config: MakeRustlsConnect,
+    ) -> PgPoolResult<(Pool, ConnectionMetadata)> {
         let mut cfg = Config::new();
-        cfg.hosts = Some(vec![settings.hostname.clone()]);
-        cfg.port = Some(settings.port);
+        cfg.hosts = Some(vec![hostname.to_owned()]);
+        cfg.port = Some(port);
         cfg.user = Some(settings.user.clone());
         cfg.password = Some(settings.password.clone().into());
         cfg.dbname = Some(settings.dbname.clone());
@@ -240,12 +419,11 @@ impl PgPool {
         // to include the SSL bits.
         // cfg.ssl_mode = Some(SslMode::Require);
         cfg.ssl_mode = Some(SslMode::Prefer);
-        let tls_config = Self::tls_config(settings).await?;
         debug!(db.pool_config = ?pool_config);
         cfg.pool = Some(pool_config);
         let pool = cfg.create_pool(Some(deadpool_postgres::Runtime::Tokio1), tls_config)?;
 
-        let resolving_hostname = format!("{}:{}", settings.hostname, settings.port);
+        let resolving_hostname = format!("{hostname}:{port}");
         let net_peer_ip = tokio::task::spawn_blocking(move || {
             resolving_hostname
                 .to_socket_addrs()
@@ -259,46 +437,17 @@ impl PgPool {
             db_system: "postgresql",
             db_connection_string: format!(
                 "postgresql://{}:{}/{}?application_name={}",
-                settings.hostname, settings.port, settings.dbname, settings.application_name
+                hostname, port, settings.dbname, settings.application_name
             ),
             db_name: settings.dbname.clone(),
             db_user: settings.user.clone(),
             db_pool_max_size: settings.pool_max_size,
             net_peer_ip,
-            net_peer_port: settings.port,
+            net_peer_port: port,
             net_transport: "ip_tcp",
         };
 
-        let span = Span::current();
-        span.record("db.system", metadata.db_system);
-        span.record(
-            "db.connection_string",
-            metadata.db_connection_string.as_str(),
-        );
-        span.record("db.name", metadata.db_name.as_str());
-        span.record("db.user", metadata.db_user.as_str());
-        span.record("db.pool.max_size", metadata.db_pool_max_size);
-        span.record("net.peer.ip", metadata.net_peer_ip.as_str());
-        span.record("net.peer.port", metadata.net_peer_port);
-        span.record("net.transport", metadata.net_transport);
-
-        let pg_pool = Self {
-            pool,
-            metadata: Arc::new(metadata),
-        };
-
-        // Warm up the pool and test that we can connect to the database. Note that this is only
-        // advisory--it will not terminate any process or service that may be running or about to
-        // be run. We assume that the pool is an autonomous actor that can make forward progress
-        // towards its goal and maintain its own healthiness. This is in order to prevent a
-        // database network connection hiccup from crashing a fleet of services which may get
-        // immediately rescheduled/restarted only to fall into a perpetual crash loop while not
-        // being able to serve any traffic--including health/readiness status.
-        drop(tokio::spawn(
-            test_connection_infallible_and_warm_up_pool_task(pg_pool.clone()),
-        ));
-
-        Ok(pg_pool)
+        Ok((pool, metadata))
     }
 
     // Creates a tls_config for connecting to postgres securely
@@ -422,6 +571,11 @@ impl PgPool {
         &self.metadata.db_name
     }
 
+    /// Whether the pool was configured with any read replicas.
+    pub fn has_read_replicas(&self) -> bool {
+        !self.replicas.is_empty()
+    }
+
     /// Retrieve object from pool or wait for one to become available.
     #[instrument(
         name = "pool.get",
@@ -455,6 +609,62 @@ impl PgPool {
         })
     }
 
+    /// Retrieve an object for read-only queries, from a healthy read replica if there are any and
+    /// from the primary otherwise.
+    ///
+    /// Replicas lag behind the primary, so reads which must see writes that were only just
+    /// committed should use [`PgPool::get`] instead. Writes fail when made over a connection to a
+    /// replica.
+    #[instrument(
+        name = "pool.get_read_only",
+        skip_all,
+        level = "debug",
+        fields(
+            db.connection_string = Empty,
+            net.peer.ip = Empty,
+            net.peer.port = Empty,
+        )
+    )]
+    pub async fn get_read_only(&self) -> PgPoolResult<InstrumentedClient> {
+        if let Some(replica) = self.select_replica() {
+            match replica.pool.get().await {
+                Ok(inner) => {
+                    let span = Span::current();
+                    span.record(
+                        "db.connection_string",
+                        replica.metadata.db_connection_string.as_str(),
+                    );
+                    span.record("net.peer.ip", replica.metadata.net_peer_ip.as_str());
+                    span.record("net.peer.port", replica.metadata.net_peer_port);
+
+                    return Ok(InstrumentedClient {
+                        inner,
+                        metadata: replica.metadata.clone(),
+                    });
+                }
+                Err(err) => {
+                    warn!(error = %err, "failed to get read replica connection, using primary");
+                    replica.set_healthy(false);
+                }
+            }
+        }
+
+        self.get().await
+    }
+
+    // Picks the next healthy replica in turn, if any
+    fn select_replica(&self) -> Option<&PgReplica> {
+        let count = self.replicas.len();
+        if count == 0 {
+            return None;
+        }
+        let start = self.next_replica.fetch_add(1, Ordering::Relaxed);
+
+        (0..count)
+            .map(|offset| &self.replicas[start.wrapping_add(offset) % count])
+            .find(|replica| replica.is_healthy())
+    }
+
     #[instrument(
         name = "pool.migrate",
         skip_all,
@@ -2836,3 +3046,88 @@ impl PgOwnedTransaction {
 async fn test_connection_infallible_and_warm_up_pool_task(check_pool: PgPool) {
     let _result = check_pool.test_connection().await;
 }
+
+// Checks the health of each replica periodically, for as long as the pool is in use
+async fn check_replica_health_task(replicas: Weak<[PgReplica]>) {
+    let mut interval = tokio::time::interval(REPLICA_HEALTH_CHECK_INTERVAL);
+    loop {
+        interval.tick().await;
+        let Some(replicas) = replicas.upgrade() else {
+            break;
+        };
+
+        join_all(replicas.iter().map(|replica| async move {
+            let result =
+                tokio::time::timeout(REPLICA_HEALTH_CHECK_TIMEOUT, replica.check_health()).await;
+            match result {
+                Ok(Ok(())) => replica.set_healthy(true),
+                Ok(Err(err)) => {
+                    debug!(error = %err, "pg read replica failed health check");
+                    replica.set_healthy(false);
+                }
+                Err(_elapsed) => {
+                    debug!("pg read replica health check timed out");
+                    replica.set_healthy(false);
+                }
+            }
+        }))
+        .await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use serde::de::{value, IntoDeserializer};
+
+    use super::*;
+
+    #[test]
+    fn replica_config_requires_hostname() {
+        let result = PgReplicaConfig::deserialize(
+            std::iter::empty::<(&str, &str)>()
+                .collect::<HashMap<_, _>>()
+                .into_deserializer(),
+        )
+        .map_err(|err: value::Error| err.to_string());
+
+        assert_eq!(
+            Err("missing field `hostname`".to_string()),
+            result.map(|_| ())
+        );
+    }
+
+    #[test]
+    fn replica_config_defaults_port_and_lag() {
+        let config = PgReplicaConfig::deserialize(
+            [("hostname", "replica.example.com")]
+                .into_iter()
+                .collect::<HashMap<_, _>>()
+                .into_deserializer(),
+        )
+        .map_err(|err: value::Error| err.to_string())
+        .expect("failed to deserialize config");
+
+        assert_eq!("replica.example.com", config.hostname);
+        assert_eq!(5432, config.port);
+        assert_eq!(
+            DEFAULT_MAX_REPLICATION_LAG_SECS,
+            config.max_replication_lag_secs
+        );
+    }
+
+    #[test]
+    fn replicas_within_max_lag_are_healthy() {
+        assert!(check_replication_lag(0.0, 30).is_ok());
+        assert!(check_replication_lag(30.0, 30).is_ok());
+    }
+
+    #[test]
+    fn replicas_beyond_max_lag_are_unhealthy() {
+        assert!(matches!(
+            check_replication_lag(30.5, 30),
+            Err(PgPoolError::ReplicationLag(lag, 30)) if lag == 30.5
+        ));
+    }
+}
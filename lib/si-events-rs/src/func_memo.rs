@@ -0,0 +1,129 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use strum::{AsRefStr, Display};
+
+use crate::{create_xxhash_type, ContentHash, FuncRunId};
+
+create_xxhash_type!(FuncMemoKey);
+
+impl FuncMemoKey {
+    /// Computes the memoization key for running a func whose code hashes to `code_hash` with the
+    /// given arguments.
+    ///
+    /// Object keys in the arguments are sorted before hashing, so two argument sets that differ
+    /// only in key order produce the same key.
+    pub fn for_func_run(code_hash: ContentHash, args: &serde_json::Value) -> Self {
+        let mut hasher = Self::hasher();
+        hasher.update(code_hash.as_bytes());
+        hasher.update(canonicalize(args).to_string().as_bytes());
+        hasher.finalize()
+    }
+}
+
+fn canonicalize(value: &serde_json::Value) -> serde_json::Value {
+    match value {
+        serde_json::Value::Object(map) => {
+            let mut entries: Vec<_> = map.iter().collect();
+            entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+            serde_json::Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(key, value)| (key.to_owned(), canonicalize(value)))
+                    .collect(),
+            )
+        }
+        serde_json::Value::Array(items) => {
+            serde_json::Value::Array(items.iter().map(canonicalize).collect())
+        }
+        other => other.to_owned(),
+    }
+}
+
+/// Whether a [`FuncRun`](crate::FuncRun) was served from the memoization cache.
+#[derive(AsRefStr, Deserialize, Display, Serialize, Debug, Eq, PartialEq, Clone, Copy)]
+pub enum FuncMemoStatus {
+    Hit,
+    Miss,
+}
+
+/// The memoized result of running a pure func. Values are stored in the CAS and referenced by
+/// address.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct FuncMemo {
+    key: FuncMemoKey,
+    func_run_id: FuncRunId,
+    unprocessed_value_cas_address: Option<ContentHash>,
+    value_cas_address: Option<ContentHash>,
+    created_at: DateTime<Utc>,
+}
+
+impl FuncMemo {
+    pub fn new(
+        key: FuncMemoKey,
+        func_run_id: FuncRunId,
+        unprocessed_value_cas_address: Option<ContentHash>,
+        value_cas_address: Option<ContentHash>,
+    ) -> Self {
+        Self {
+            key,
+            func_run_id,
+            unprocessed_value_cas_address,
+            value_cas_address,
+            created_at: Utc::now(),
+        }
+    }
+
+    pub fn key(&self) -> FuncMemoKey {
+        self.key
+    }
+
+    /// The [`FuncRun`](crate::FuncRun) that produced the memoized result.
+    pub fn func_run_id(&self) -> FuncRunId {
+        self.func_run_id
+    }
+
+    pub fn unprocessed_value_cas_address(&self) -> Option<ContentHash> {
+        self.unprocessed_value_cas_address
+    }
+
+    pub fn value_cas_address(&self) -> Option<ContentHash> {
+        self.value_cas_address
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn key_ignores_object_key_order() {
+        let code_hash = ContentHash::new("function main() {}".as_bytes());
+
+        let a = json!({ "b": 1, "a": { "d": [1, 2], "c": null } });
+        let b = json!({ "a": { "c": null, "d": [1, 2] }, "b": 1 });
+
+        assert_eq!(
+            FuncMemoKey::for_func_run(code_hash, &a),
+            FuncMemoKey::for_func_run(code_hash, &b),
+        );
+    }
+
+    #[test]
+    fn key_depends_on_code_and_args() {
+        let code_hash = ContentHash::new("function main() {}".as_bytes());
+        let other_code_hash = ContentHash::new("function main() { return 1; }".as_bytes());
+
+        let args = json!({ "a": [1, 2] });
+        let reordered_array = json!({ "a": [2, 1] });
+
+        let key = FuncMemoKey::for_func_run(code_hash, &args);
+        assert_ne!(key, FuncMemoKey::for_func_run(other_code_hash, &args));
+        assert_ne!(key, FuncMemoKey::for_func_run(code_hash, &reordered_array));
+    }
+}
@@ -3,7 +3,7 @@ use derive_builder::Builder;
 use serde::{Deserialize, Serialize};
 use strum::{AsRefStr, Display, EnumIter, EnumString};
 
-use crate::{id, Actor, ChangeSetId, ContentHash, Tenancy, WorkspacePk};
+use crate::{id, Actor, ChangeSetId, ContentHash, FuncMemoStatus, Tenancy, WorkspacePk};
 
 id!(FuncRunId);
 id!(ComponentId);
@@ -21,10 +21,13 @@ pub enum FuncRunState {
     PostProcessing,
     Failure,
     Success,
+    /// The execution was cancelled while in flight.
+    Cancelled,
 }
 
 /// Describes the kind of [`Func`](crate::Func).
-#[remain::sorted]
+// NOTE: this enum is stored with postcard, which serializes variants by their position, so it is
+// not sorted and new variants must be added at the end.
 #[derive(AsRefStr, Deserialize, Display, Serialize, Debug, Eq, PartialEq, Clone, Copy, Hash)]
 pub enum FuncKind {
     Action,
@@ -35,9 +38,12 @@ pub enum FuncKind {
     Qualification,
     SchemaVariantDefinition,
     Unknown,
+    Reconciliation,
+    Import,
 }
 
-#[remain::sorted]
+// NOTE: this enum is stored with postcard, which serializes variants by their position, so it is
+// not sorted and new variants must be added at the end.
 #[derive(
     Deserialize,
     Serialize,
@@ -71,6 +77,16 @@ pub enum FuncBackendKind {
     String,
     Unset,
     Validation,
+    /// Base64 encoding of a string
+    Base64Encode,
+    /// Joining the items of an array into a string
+    JoinArray,
+    /// Picking a value out of JSON by a JSON pointer
+    JsonPointer,
+    /// Building a map from a list of objects
+    MapFromList,
+    /// Rendering a string template
+    StringTemplate,
 }
 
 #[remain::sorted]
@@ -177,6 +193,12 @@ pub struct FuncRun {
     result_unprocessed_value_cas_address: Option<ContentHash>,
     created_at: DateTime<Utc>,
     updated_at: DateTime<Utc>,
+    /// Set when the func is pure and its result was looked up in the memoization cache.
+    #[builder(default)]
+    memo_status: Option<FuncMemoStatus>,
+    /// The entrypoint the func's code was run with, which is needed to run it again.
+    #[builder(default)]
+    function_handler: Option<String>,
 }
 
 impl FuncRun {
@@ -202,6 +224,11 @@ impl FuncRun {
         self.updated_at = Utc::now();
     }
 
+    pub fn set_memo_status(&mut self, value: Option<FuncMemoStatus>) {
+        self.memo_status = value;
+        self.updated_at = Utc::now();
+    }
+
     pub fn set_state_to_dispatched(&mut self) {
         self.updated_at = Utc::now();
         self.state = FuncRunState::Dispatched;
@@ -227,6 +254,11 @@ impl FuncRun {
         self.state = FuncRunState::Failure;
     }
 
+    pub fn set_state_to_cancelled(&mut self) {
+        self.updated_at = Utc::now();
+        self.state = FuncRunState::Cancelled;
+    }
+
     pub fn id(&self) -> FuncRunId {
         self.id
     }
@@ -346,6 +378,106 @@ impl FuncRun {
     pub fn function_link(&self) -> Option<&str> {
         self.function_link.as_deref()
     }
+
+    pub fn memo_status(&self) -> Option<FuncMemoStatus> {
+        self.memo_status
+    }
+
+    pub fn function_handler(&self) -> Option<&str> {
+        self.function_handler.as_deref()
+    }
+}
+
+/// The stored form of a [`FuncRun`].
+///
+/// Func runs are stored with postcard, which serializes fields by their position, so a change to
+/// the fields of a func run needs a new version for those already stored to remain readable.
+/// Func runs stored before they were versioned are a bare [`FuncRunV1`] rather than a
+/// [`FuncRunContent`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum FuncRunContent {
+    V1(FuncRunV1),
+    V2(FuncRun),
+}
+
+impl FuncRunContent {
+    pub fn extract(self) -> FuncRun {
+        match self {
+            FuncRunContent::V1(v1) => FuncRun {
+                id: v1.id,
+                state: v1.state,
+                actor: v1.actor,
+                tenancy: v1.tenancy,
+                component_id: v1.component_id,
+                attribute_value_id: v1.attribute_value_id,
+                component_name: v1.component_name,
+                schema_name: v1.schema_name,
+                action_id: v1.action_id,
+                action_prototype_id: v1.action_prototype_id,
+                action_kind: v1.action_kind,
+                action_display_name: v1.action_display_name,
+                action_originating_change_set_id: v1.action_originating_change_set_id,
+                action_originating_change_set_name: v1.action_originating_change_set_name,
+                action_result_state: v1.action_result_state,
+                backend_kind: v1.backend_kind,
+                backend_response_type: v1.backend_response_type,
+                function_name: v1.function_name,
+                function_display_name: v1.function_display_name,
+                function_kind: v1.function_kind,
+                function_description: v1.function_description,
+                function_link: v1.function_link,
+                function_args_cas_address: v1.function_args_cas_address,
+                function_code_cas_address: v1.function_code_cas_address,
+                result_value_cas_address: v1.result_value_cas_address,
+                result_unprocessed_value_cas_address: v1.result_unprocessed_value_cas_address,
+                created_at: v1.created_at,
+                updated_at: v1.updated_at,
+                memo_status: None,
+                function_handler: None,
+            },
+            FuncRunContent::V2(v2) => v2,
+        }
+    }
+}
+
+impl From<FuncRun> for FuncRunContent {
+    fn from(value: FuncRun) -> Self {
+        FuncRunContent::V2(value)
+    }
+}
+
+/// A func run as stored before func runs recorded their memo status and the handler their code
+/// was run with. Func runs read from this version can't be replayed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FuncRunV1 {
+    pub id: FuncRunId,
+    pub state: FuncRunState,
+    pub actor: Actor,
+    pub tenancy: Tenancy,
+    pub component_id: Option<ComponentId>,
+    pub attribute_value_id: Option<AttributeValueId>,
+    pub component_name: Option<String>,
+    pub schema_name: Option<String>,
+    pub action_id: Option<ActionId>,
+    pub action_prototype_id: Option<ActionPrototypeId>,
+    pub action_kind: Option<ActionKind>,
+    pub action_display_name: Option<String>,
+    pub action_originating_change_set_id: Option<ChangeSetId>,
+    pub action_originating_change_set_name: Option<String>,
+    pub action_result_state: Option<ActionResultState>,
+    pub backend_kind: FuncBackendKind,
+    pub backend_response_type: FuncBackendResponseType,
+    pub function_name: String,
+    pub function_display_name: Option<String>,
+    pub function_kind: FuncKind,
+    pub function_description: Option<String>,
+    pub function_link: Option<String>,
+    pub function_args_cas_address: ContentHash,
+    pub function_code_cas_address: ContentHash,
+    pub result_value_cas_address: Option<ContentHash>,
+    pub result_unprocessed_value_cas_address: Option<ContentHash>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
 }
 
 #[derive(Debug)]
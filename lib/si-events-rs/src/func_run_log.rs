@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::str::FromStr;
 
 use chrono::{DateTime, Utc};
@@ -64,6 +65,8 @@ pub struct OutputLine {
     pub group: Option<String>,
     pub message: String,
     pub timestamp: u64,
+    #[serde(default)]
+    pub fields: BTreeMap<String, String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -127,3 +130,75 @@ impl FuncRunLog {
         self.finalized = true;
     }
 }
+
+/// The stored form of a [`FuncRunLog`].
+///
+/// Func run logs are stored with postcard, which serializes fields by their position, so a change
+/// to the fields of a log or its lines needs a new version for those already stored to remain
+/// readable. Logs stored before they were versioned are a bare [`FuncRunLogV1`] rather than a
+/// [`FuncRunLogContent`].
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum FuncRunLogContent {
+    V1(FuncRunLogV1),
+    V2(FuncRunLog),
+}
+
+impl FuncRunLogContent {
+    pub fn extract(self) -> FuncRunLog {
+        match self {
+            FuncRunLogContent::V1(v1) => FuncRunLog {
+                id: v1.id,
+                tenancy: v1.tenancy,
+                created_at: v1.created_at,
+                updated_at: v1.updated_at,
+                func_run_id: v1.func_run_id,
+                logs: v1.logs.into_iter().map(Into::into).collect(),
+                finalized: v1.finalized,
+            },
+            FuncRunLogContent::V2(v2) => v2,
+        }
+    }
+}
+
+impl From<FuncRunLog> for FuncRunLogContent {
+    fn from(value: FuncRunLog) -> Self {
+        FuncRunLogContent::V2(value)
+    }
+}
+
+/// A func run log as stored before its lines had structured fields.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct FuncRunLogV1 {
+    pub id: FuncRunLogId,
+    pub tenancy: Tenancy,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub func_run_id: FuncRunId,
+    pub logs: Vec<OutputLineV1>,
+    pub finalized: bool,
+}
+
+/// An [`OutputLine`] as stored before lines had structured fields.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub struct OutputLineV1 {
+    pub stream: String,
+    pub execution_id: String,
+    pub level: String,
+    pub group: Option<String>,
+    pub message: String,
+    pub timestamp: u64,
+}
+
+impl From<OutputLineV1> for OutputLine {
+    fn from(value: OutputLineV1) -> Self {
+        Self {
+            stream: value.stream,
+            execution_id: value.execution_id,
+            level: value.level,
+            group: value.group,
+            message: value.message,
+            timestamp: value.timestamp,
+            fields: BTreeMap::new(),
+        }
+    }
+}
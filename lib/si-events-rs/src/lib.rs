@@ -10,6 +10,7 @@ mod actor;
 mod cas;
 mod func;
 mod func_execution;
+mod func_memo;
 mod func_run;
 mod func_run_log;
 mod schema;
@@ -28,13 +29,16 @@ pub use crate::{
     encrypted_secret::EncryptedSecretKey,
     func::{FuncArgumentId, FuncId},
     func_execution::*,
+    func_memo::{FuncMemo, FuncMemoKey, FuncMemoStatus},
     func_run::{
         ActionId, ActionKind, ActionPrototypeId, ActionResultState, AttributePrototypeArgumentId,
         AttributePrototypeId, AttributeValueId, ComponentId, FuncBackendKind,
-        FuncBackendResponseType, FuncKind, FuncRun, FuncRunBuilder, FuncRunBuilderError, FuncRunId,
-        FuncRunState, FuncRunValue,
+        FuncBackendResponseType, FuncKind, FuncRun, FuncRunBuilder, FuncRunBuilderError,
+        FuncRunContent, FuncRunId, FuncRunState, FuncRunV1, FuncRunValue,
+    },
+    func_run_log::{
+        FuncRunLog, FuncRunLogContent, FuncRunLogId, FuncRunLogV1, OutputLine, OutputLineV1,
     },
-    func_run_log::{FuncRunLog, FuncRunLogId, OutputLine},
     schema::SchemaId,
     schema_variant::{PropId, SchemaVariantId},
     socket::{InputSocketId, OutputSocketId},
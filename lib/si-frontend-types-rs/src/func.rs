@@ -30,6 +30,8 @@ pub struct FuncSummary {
     pub display_name: Option<String>,
     pub description: Option<String>,
     pub is_locked: bool,
+    pub timeout_secs: Option<u64>,
+    pub is_pure: bool,
     pub arguments: Vec<FuncArgument>,
     #[serde(flatten)]
     pub bindings: FuncBindings,
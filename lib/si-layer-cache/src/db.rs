@@ -6,13 +6,14 @@ use std::{future::IntoFuture, io, path::Path, sync::Arc};
 use serde::{de::DeserializeOwned, Serialize};
 use si_data_nats::{NatsClient, NatsConfig};
 use si_data_pg::PgPool;
-use si_events::{FuncRun, FuncRunLog};
+use si_events::{FuncMemo, FuncRun, FuncRunLog};
 use telemetry::prelude::*;
 use tokio::sync::mpsc;
 use tokio_util::{sync::CancellationToken, task::TaskTracker};
 use ulid::Ulid;
 
 use crate::db::encrypted_secret::EncryptedSecretDb;
+use crate::db::func_memo::FuncMemoDb;
 use crate::db::func_run::FuncRunDb;
 use crate::db::func_run_log::FuncRunLogDb;
 use crate::memory_cache::MemoryCacheConfig;
@@ -31,6 +32,7 @@ use self::{
 mod cache_updates;
 pub mod cas;
 pub mod encrypted_secret;
+pub mod func_memo;
 pub mod func_run;
 pub mod func_run_log;
 pub mod rebase_batch;
@@ -47,6 +49,7 @@ where
 {
     cas: CasDb<CasValue>,
     encrypted_secret: EncryptedSecretDb<EncryptedSecretValue>,
+    func_memo: FuncMemoDb,
     func_run: FuncRunDb,
     func_run_log: FuncRunLogDb,
     rebase_batch: RebaseBatchDb<RebaseBatchValue>,
@@ -106,48 +109,65 @@ where
             disk_path,
             pg_pool.clone(),
             memory_cache_config.clone(),
-        )?;
+        )?
+        .with_pg_read_replicas();
 
         let encrypted_secret_cache: LayerCache<Arc<EncryptedSecretValue>> = LayerCache::new(
             encrypted_secret::CACHE_NAME,
             disk_path,
             pg_pool.clone(),
             memory_cache_config.clone(),
-        )?;
+        )?
+        .with_pg_read_replicas();
 
+        let func_memo_cache: LayerCache<Arc<FuncMemo>> = LayerCache::new(
+            func_memo::CACHE_NAME,
+            disk_path,
+            pg_pool.clone(),
+            memory_cache_config.clone(),
+        )?
+        .with_pg_read_replicas();
+
+        // NOTE: func runs and their logs are updated in place, so they are only read from the
+        // primary, where a lagging replica can't serve an outdated version of them
         let func_run_cache: LayerCache<Arc<FuncRun>> = LayerCache::new(
             func_run::CACHE_NAME,
             disk_path,
             pg_pool.clone(),
             memory_cache_config.clone(),
-        )?;
+        )?
+        .with_deserializer(func_run::from_bytes_for_cache);
 
         let func_run_log_cache: LayerCache<Arc<FuncRunLog>> = LayerCache::new(
             func_run_log::CACHE_NAME,
             disk_path,
             pg_pool.clone(),
             memory_cache_config.clone(),
-        )?;
+        )?
+        .with_deserializer(func_run_log::from_bytes_for_cache);
 
         let rebase_batch_cache: LayerCache<Arc<RebaseBatchValue>> = LayerCache::new(
             rebase_batch::CACHE_NAME,
             disk_path,
             pg_pool.clone(),
             memory_cache_config.clone(),
-        )?;
+        )?
+        .with_pg_read_replicas();
 
         let snapshot_cache: LayerCache<Arc<WorkspaceSnapshotValue>> = LayerCache::new(
             workspace_snapshot::CACHE_NAME,
             disk_path,
             pg_pool.clone(),
             memory_cache_config.clone(),
-        )?;
+        )?
+        .with_pg_read_replicas();
 
         let cache_updates_task = CacheUpdatesTask::create(
             instance_id,
             &nats_client,
             cas_cache.clone(),
             encrypted_secret_cache.clone(),
+            func_memo_cache.clone(),
             func_run_cache.clone(),
             func_run_log_cache.clone(),
             rebase_batch_cache.clone(),
@@ -171,6 +191,7 @@ where
         let cas = CasDb::new(cas_cache, persister_client.clone());
         let encrypted_secret =
             EncryptedSecretDb::new(encrypted_secret_cache, persister_client.clone());
+        let func_memo = FuncMemoDb::new(func_memo_cache, persister_client.clone());
         let func_run = FuncRunDb::new(func_run_cache, persister_client.clone());
         let func_run_log = FuncRunLogDb::new(func_run_log_cache, persister_client.clone());
         let workspace_snapshot = WorkspaceSnapshotDb::new(snapshot_cache, persister_client.clone());
@@ -183,6 +204,7 @@ where
             activity,
             cas,
             encrypted_secret,
+            func_memo,
             func_run,
             func_run_log,
             workspace_snapshot,
@@ -216,6 +238,10 @@ where
         &self.encrypted_secret
     }
 
+    pub fn func_memo(&self) -> &FuncMemoDb {
+        &self.func_memo
+    }
+
     pub fn func_run(&self) -> &FuncRunDb {
         &self.func_run
     }
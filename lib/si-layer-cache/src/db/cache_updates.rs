@@ -2,7 +2,7 @@ use std::sync::Arc;
 
 use serde::{de::DeserializeOwned, Serialize};
 use si_data_nats::NatsClient;
-use si_events::{FuncRun, FuncRunLog};
+use si_events::{FuncMemo, FuncRun, FuncRunLog};
 use strum::{AsRefStr, EnumString};
 use telemetry::prelude::*;
 use tokio::sync::mpsc::UnboundedReceiver;
@@ -21,6 +21,7 @@ use crate::{
 enum CacheName {
     Cas,
     EncryptedSecret,
+    FuncMemo,
     FuncRun,
     FuncRunLog,
     WorkspaceSnapshots,
@@ -39,6 +40,7 @@ pub struct CacheUpdatesTask<
 {
     cas_cache: LayerCache<Arc<CasValue>>,
     encrypted_secret_cache: LayerCache<Arc<EncryptedSecretValue>>,
+    func_memo_cache: LayerCache<Arc<FuncMemo>>,
     func_run_cache: LayerCache<Arc<FuncRun>>,
     func_run_log_cache: LayerCache<Arc<FuncRunLog>>,
     rebase_batch_cache: LayerCache<Arc<RebaseBatchValue>>,
@@ -64,6 +66,7 @@ where
         nats_client: &NatsClient,
         cas_cache: LayerCache<Arc<CasValue>>,
         encrypted_secret_cache: LayerCache<Arc<EncryptedSecretValue>>,
+        func_memo_cache: LayerCache<Arc<FuncMemo>>,
         func_run_cache: LayerCache<Arc<FuncRun>>,
         func_run_log_cache: LayerCache<Arc<FuncRunLog>>,
         rebase_batch_cache: LayerCache<Arc<RebaseBatchValue>>,
@@ -80,6 +83,7 @@ where
         Ok(Self {
             cas_cache,
             encrypted_secret_cache,
+            func_memo_cache,
             func_run_cache,
             func_run_log_cache,
             rebase_batch_cache,
@@ -109,6 +113,7 @@ where
             let cache_update_task = CacheUpdateTask::new(
                 self.cas_cache.clone(),
                 self.encrypted_secret_cache.clone(),
+                self.func_memo_cache.clone(),
                 self.func_run_cache.clone(),
                 self.func_run_log_cache.clone(),
                 self.snapshot_cache.clone(),
@@ -129,6 +134,7 @@ where
 {
     cas_cache: LayerCache<Arc<Q>>,
     encrypted_secret_cache: LayerCache<Arc<R>>,
+    func_memo_cache: LayerCache<Arc<FuncMemo>>,
     func_run_cache: LayerCache<Arc<FuncRun>>,
     func_run_log_cache: LayerCache<Arc<FuncRunLog>>,
     snapshot_cache: LayerCache<Arc<S>>,
@@ -145,6 +151,7 @@ where
     fn new(
         cas_cache: LayerCache<Arc<Q>>,
         encrypted_secret_cache: LayerCache<Arc<R>>,
+        func_memo_cache: LayerCache<Arc<FuncMemo>>,
         func_run_cache: LayerCache<Arc<FuncRun>>,
         func_run_log_cache: LayerCache<Arc<FuncRunLog>>,
         snapshot_cache: LayerCache<Arc<S>>,
@@ -153,6 +160,7 @@ where
         CacheUpdateTask {
             cas_cache,
             encrypted_secret_cache,
+            func_memo_cache,
             func_run_cache,
             func_run_log_cache,
             snapshot_cache,
@@ -186,6 +194,18 @@ where
                         .await?;
                 }
             }
+            crate::event::LayeredEventKind::FuncMemoWrite => {
+                if !self.func_memo_cache.contains(&event.key) {
+                    let memory_value = self
+                        .func_memo_cache
+                        .deserialize_memory_value(&event.payload.value)?;
+                    let serialized_value =
+                        Arc::try_unwrap(event.payload.value).unwrap_or_else(|arc| (*arc).clone());
+                    self.func_memo_cache
+                        .insert_from_cache_updates(event.key, memory_value, serialized_value)
+                        .await?;
+                }
+            }
             crate::event::LayeredEventKind::Raw => {
                 warn!("Recevied a 'raw' layered event kind - this is for testing only. Bug!");
             }
@@ -0,0 +1,69 @@
+use std::sync::Arc;
+
+use si_events::{Actor, FuncMemo, FuncMemoKey, Tenancy, WebEvent};
+
+use crate::{
+    error::LayerDbResult,
+    event::{LayeredEvent, LayeredEventKind},
+    layer_cache::LayerCache,
+    persister::{PersisterClient, PersisterStatusReader},
+};
+
+use super::serialize;
+
+const KEYWORD_SINGULAR: &str = "func_memo";
+const KEYWORD_PLURAL: &str = "func_memos";
+
+pub const PARTITION_KEY: &str = KEYWORD_PLURAL;
+pub const DBNAME: &str = KEYWORD_PLURAL;
+pub const CACHE_NAME: &str = KEYWORD_PLURAL;
+pub const SORT_KEY: &str = KEYWORD_SINGULAR;
+
+/// Memoized results of pure func runs, keyed by [`FuncMemoKey`]. Like the CAS, memos are not
+/// scoped to a workspace: the key is derived entirely from the func's code and arguments.
+#[derive(Debug, Clone)]
+pub struct FuncMemoDb {
+    pub cache: LayerCache<Arc<FuncMemo>>,
+    persister_client: PersisterClient,
+}
+
+impl FuncMemoDb {
+    pub fn new(cache: LayerCache<Arc<FuncMemo>>, persister_client: PersisterClient) -> Self {
+        FuncMemoDb {
+            cache,
+            persister_client,
+        }
+    }
+
+    pub async fn write(
+        &self,
+        value: Arc<FuncMemo>,
+        web_events: Option<Vec<WebEvent>>,
+        tenancy: Tenancy,
+        actor: Actor,
+    ) -> LayerDbResult<PersisterStatusReader> {
+        let postcard_value = serialize::to_vec(&value)?;
+
+        let cache_key: Arc<str> = value.key().to_string().into();
+
+        self.cache.insert(cache_key.clone(), value.clone()).await;
+
+        let event = LayeredEvent::new(
+            LayeredEventKind::FuncMemoWrite,
+            Arc::new(DBNAME.to_string()),
+            cache_key,
+            Arc::new(postcard_value),
+            Arc::new(SORT_KEY.to_string()),
+            web_events,
+            tenancy,
+            actor,
+        );
+        let reader = self.persister_client.write_event(event)?;
+
+        Ok(reader)
+    }
+
+    pub async fn read(&self, key: &FuncMemoKey) -> LayerDbResult<Option<Arc<FuncMemo>>> {
+        self.cache.get(key.to_string().into()).await
+    }
+}
@@ -1,9 +1,11 @@
 use std::sync::Arc;
 use std::time::Duration;
 
+use chrono::{DateTime, Utc};
+use si_data_pg::postgres_types::ToSql;
 use si_events::{
-    ActionId, ActionResultState, Actor, AttributeValueId, ContentHash, FuncRun, FuncRunId, Tenancy,
-    WebEvent, WorkspacePk,
+    ActionId, ActionResultState, Actor, AttributeValueId, ComponentId, ContentHash, FuncKind,
+    FuncRun, FuncRunContent, FuncRunId, FuncRunState, FuncRunV1, Tenancy, WebEvent, WorkspacePk,
 };
 
 use crate::event::LayeredEventPayload;
@@ -16,12 +18,48 @@ use crate::{
     persister::PersisterClient,
 };
 
-use super::serialize;
+use super::{func_run_log, serialize};
 
 pub const DBNAME: &str = "func_runs";
 pub const CACHE_NAME: &str = DBNAME;
 pub const PARTITION_KEY: &str = "workspace_id";
 
+/// Filters for [`FuncRunDb::search`]. Every filter that is set must match.
+#[derive(Clone, Debug)]
+pub struct FuncRunSearch {
+    pub workspace_id: WorkspacePk,
+    pub function_kind: Option<FuncKind>,
+    pub state: Option<FuncRunState>,
+    pub component_id: Option<ComponentId>,
+    pub function_name: Option<String>,
+    pub created_after: Option<DateTime<Utc>>,
+    pub created_before: Option<DateTime<Utc>>,
+    /// Words which must all appear in the messages or field values of the run's logs.
+    pub log_text: Option<String>,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+impl FuncRunSearch {
+    pub const DEFAULT_LIMIT: i64 = 50;
+
+    /// Creates a search matching every func run in the workspace.
+    pub fn new(workspace_id: WorkspacePk) -> Self {
+        Self {
+            workspace_id,
+            function_kind: None,
+            state: None,
+            component_id: None,
+            function_name: None,
+            created_after: None,
+            created_before: None,
+            log_text: None,
+            limit: Self::DEFAULT_LIMIT,
+            offset: 0,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct FuncRunDb {
     pub cache: LayerCache<Arc<FuncRun>>,
@@ -30,6 +68,7 @@ pub struct FuncRunDb {
     get_last_qualification_for_attribute_value_id: String,
     list_action_history: String,
     get_last_action_by_action_id: String,
+    delete_expired_batch: String,
 }
 
 impl FuncRunDb {
@@ -58,9 +97,128 @@ impl FuncRunDb {
                   ORDER BY updated_at DESC
                   LIMIT 1",
             ),
+            delete_expired_batch: format!(
+                "WITH expired AS (
+                    SELECT key FROM {DBNAME}
+                      WHERE function_kind = $1 AND created_at < $2
+                      LIMIT $3
+                ), deleted_logs AS (
+                    DELETE FROM {func_run_logs}
+                      WHERE func_run_id IN (SELECT key FROM expired)
+                )
+                DELETE FROM {DBNAME} WHERE key IN (SELECT key FROM expired)",
+                func_run_logs = func_run_log::DBNAME,
+            ),
         }
     }
 
+    /// Finds the func runs matching a [`FuncRunSearch`], most recently created first.
+    pub async fn search(&self, search: &FuncRunSearch) -> LayerDbResult<Vec<FuncRun>> {
+        let function_kind = search.function_kind.map(|v| v.to_string());
+        let state = search.state.map(|v| v.to_string());
+        let component_id = search.component_id.map(|v| v.to_string());
+
+        let mut conditions = vec!["workspace_id = $1".to_owned()];
+        let mut params: Vec<&(dyn ToSql + Sync)> = vec![&search.workspace_id];
+
+        if let Some(function_kind) = &function_kind {
+            push_filter(
+                &mut conditions,
+                &mut params,
+                "function_kind = $n",
+                function_kind,
+            );
+        }
+        if let Some(state) = &state {
+            push_filter(&mut conditions, &mut params, "state = $n", state);
+        }
+        if let Some(component_id) = &component_id {
+            push_filter(
+                &mut conditions,
+                &mut params,
+                "component_id = $n",
+                component_id,
+            );
+        }
+        if let Some(function_name) = &search.function_name {
+            push_filter(
+                &mut conditions,
+                &mut params,
+                "function_name = $n",
+                function_name,
+            );
+        }
+        if let Some(created_after) = &search.created_after {
+            push_filter(
+                &mut conditions,
+                &mut params,
+                "created_at >= $n",
+                created_after,
+            );
+        }
+        if let Some(created_before) = &search.created_before {
+            push_filter(
+                &mut conditions,
+                &mut params,
+                "created_at < $n",
+                created_before,
+            );
+        }
+        if let Some(log_text) = &search.log_text {
+            push_filter(
+                &mut conditions,
+                &mut params,
+                &format!(
+                    "key IN (SELECT func_run_id FROM {func_run_logs}
+                       WHERE workspace_id = $1
+                         AND search_vector @@ plainto_tsquery('simple', $n))",
+                    func_run_logs = func_run_log::DBNAME,
+                ),
+                log_text,
+            );
+        }
+
+        let query = format!(
+            "SELECT value FROM {DBNAME}
+               WHERE {}
+               ORDER BY created_at DESC, key DESC
+               LIMIT ${} OFFSET ${}",
+            conditions.join(" AND "),
+            params.len() + 1,
+            params.len() + 2,
+        );
+        params.push(&search.limit);
+        params.push(&search.offset);
+
+        let mut func_runs = Vec::new();
+        if let Some(rows) = self.cache.pg().query_read_only(&query, &params).await? {
+            for row in rows {
+                func_runs.push(from_bytes(row.get("value"))?);
+            }
+        }
+        Ok(func_runs)
+    }
+
+    /// Deletes up to `batch_size` func runs of the given kind which were created before `cutoff`,
+    /// along with their logs, returning how many were deleted.
+    ///
+    /// Deleted func runs are only removed from the database: copies already in a memory or disk
+    /// cache remain readable by id until they are evicted.
+    pub async fn delete_expired_batch(
+        &self,
+        function_kind: FuncKind,
+        cutoff: DateTime<Utc>,
+        batch_size: i64,
+    ) -> LayerDbResult<u64> {
+        self.cache
+            .pg()
+            .execute(
+                &self.delete_expired_batch,
+                &[&function_kind.to_string(), &cutoff, &batch_size],
+            )
+            .await
+    }
+
     pub async fn list_action_history(
         &self,
         workspace_id: WorkspacePk,
@@ -68,14 +226,14 @@ impl FuncRunDb {
         let maybe_rows = self
             .cache
             .pg()
-            .query(&self.list_action_history, &[&workspace_id])
+            .query_read_only(&self.list_action_history, &[&workspace_id])
             .await?;
         let result = match maybe_rows {
             Some(rows) => {
                 let mut result_rows = Vec::with_capacity(rows.len());
                 for row in rows.into_iter() {
                     let postcard_bytes: Vec<u8> = row.get("value");
-                    let func_run: FuncRun = serialize::from_bytes(&postcard_bytes[..])?;
+                    let func_run = from_bytes(&postcard_bytes[..])?;
                     result_rows.push(func_run);
                 }
                 Some(result_rows)
@@ -100,7 +258,7 @@ impl FuncRunDb {
             .await?;
 
         let maybe_func = if let Some(row) = maybe_row {
-            Some(serialize::from_bytes(row.get("value"))?)
+            Some(from_bytes(row.get("value"))?)
         } else {
             None
         };
@@ -127,7 +285,7 @@ impl FuncRunDb {
             let result = match maybe_row {
                 Some(row) => {
                     let postcard_bytes: Vec<u8> = row.get("value");
-                    let func_run: FuncRun = serialize::from_bytes(&postcard_bytes[..])?;
+                    let func_run = from_bytes(&postcard_bytes[..])?;
                     Some(func_run)
                 }
                 None => None,
@@ -149,7 +307,7 @@ impl FuncRunDb {
         tenancy: Tenancy,
         actor: Actor,
     ) -> LayerDbResult<()> {
-        let postcard_value = serialize::to_vec(&value)?;
+        let postcard_value = to_vec(&value)?;
         let cache_key: Arc<str> = value.id().to_string().into();
         let sort_key: Arc<str> = value.tenancy().workspace_pk.to_string().into();
 
@@ -242,7 +400,7 @@ impl FuncRunDb {
             )
             .await?
             .ok_or_else(|| LayerDbError::ActionIdNotFound(action_id))?;
-        let mut func_run: FuncRun = serialize::from_bytes(maybe_row.get("value"))?;
+        let mut func_run = from_bytes(maybe_row.get("value"))?;
         func_run.set_action_result_state(Some(action_result_state));
 
         self.write(Arc::new(func_run), None, tenancy, actor).await?;
@@ -271,14 +429,14 @@ impl FuncRunDb {
         let maybe_rows = self
             .cache
             .pg()
-            .query(&self.ready_many_for_workspace_id_query, &[&workspace_id])
+            .query_read_only(&self.ready_many_for_workspace_id_query, &[&workspace_id])
             .await?;
         match maybe_rows {
             Some(rows) => {
                 let mut func_runs = Vec::new();
                 for row in rows {
                     // NOTE(nick): higher order functions... yeah I want those errors, sorry.
-                    func_runs.push(serialize::from_bytes(row.get("value"))?)
+                    func_runs.push(Arc::new(from_bytes(row.get("value"))?))
                 }
                 Ok(Some(func_runs))
             }
@@ -290,7 +448,7 @@ impl FuncRunDb {
         pg: &PgLayer,
         event_payload: &LayeredEventPayload,
     ) -> LayerDbResult<()> {
-        let func_run: FuncRun = serialize::from_bytes(&event_payload.value[..])?;
+        let func_run = from_bytes(&event_payload.value[..])?;
         let json: serde_json::Value = serde_json::to_value(func_run.clone())?;
         pg.insert_raw(
             &format!(
@@ -308,6 +466,7 @@ impl FuncRunDb {
                     attribute_value_id,
                     action_id,
                     action_originating_change_set_id,
+                    function_name,
                     json_value,
                     value
                 ) VALUES (
@@ -325,7 +484,8 @@ impl FuncRunDb {
                     $12,
                     $13,
                     $14,
-                    $15
+                    $15,
+                    $16
                 ) ON CONFLICT (key) DO UPDATE SET
                     updated_at = EXCLUDED.updated_at,
                     state = EXCLUDED.state,
@@ -348,6 +508,7 @@ impl FuncRunDb {
                 &func_run
                     .action_originating_change_set_id()
                     .map(|v| v.to_string()),
+                &func_run.function_name(),
                 &json,
                 &&event_payload.value[..],
             ],
@@ -356,3 +517,40 @@ impl FuncRunDb {
         Ok(())
     }
 }
+
+/// Serializes a func run as the latest version of [`FuncRunContent`].
+pub fn to_vec(func_run: &FuncRun) -> LayerDbResult<Vec<u8>> {
+    serialize::to_vec(&FuncRunContent::from(func_run.clone()))
+}
+
+/// Deserializes a stored func run, whether it is a [`FuncRunContent`] or a bare [`FuncRunV1`]
+/// stored before func runs were versioned.
+///
+/// The two can't be mistaken for one another: a bare [`FuncRunV1`] starts with the length of its
+/// id, which is 26, and that is not the index of a [`FuncRunContent`] version.
+pub fn from_bytes(bytes: &[u8]) -> LayerDbResult<FuncRun> {
+    match serialize::from_bytes::<FuncRunContent>(bytes) {
+        Ok(content) => Ok(content.extract()),
+        Err(err) => match serialize::from_bytes::<FuncRunV1>(bytes) {
+            Ok(v1) => Ok(FuncRunContent::V1(v1).extract()),
+            Err(_) => Err(err),
+        },
+    }
+}
+
+/// Deserializes a stored func run for the func run [`LayerCache`].
+pub fn from_bytes_for_cache(bytes: &[u8]) -> LayerDbResult<Arc<FuncRun>> {
+    from_bytes(bytes).map(Arc::new)
+}
+
+/// Adds a condition on a parameter to a query being built, replacing `$n` in the condition with
+/// the parameter's position.
+fn push_filter<'a>(
+    conditions: &mut Vec<String>,
+    params: &mut Vec<&'a (dyn ToSql + Sync)>,
+    condition: &str,
+    param: &'a (dyn ToSql + Sync),
+) {
+    params.push(param);
+    conditions.push(condition.replace("$n", &format!("${}", params.len())));
+}
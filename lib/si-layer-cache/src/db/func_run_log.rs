@@ -1,6 +1,6 @@
 use std::sync::Arc;
 
-use si_events::{Actor, FuncRunId, FuncRunLog, Tenancy, WebEvent};
+use si_events::{Actor, FuncRunId, FuncRunLog, FuncRunLogContent, FuncRunLogV1, Tenancy, WebEvent};
 
 use crate::{
     error::LayerDbResult,
@@ -38,7 +38,7 @@ impl FuncRunLogDb {
         tenancy: Tenancy,
         actor: Actor,
     ) -> LayerDbResult<()> {
-        let postcard_value = serialize::to_vec(&value)?;
+        let postcard_value = to_vec(&value)?;
         let cache_key: Arc<str> = value.id().to_string().into();
         let sort_key: Arc<str> = value.tenancy().workspace_pk.to_string().into();
 
@@ -75,7 +75,7 @@ impl FuncRunLogDb {
             .query_opt(&self.get_for_func_run_id_query, &[&func_run_id])
             .await?;
         if let Some(row) = maybe_row {
-            Ok(Some(serialize::from_bytes(row.get("value"))?))
+            Ok(Some(Arc::new(from_bytes(row.get("value"))?)))
         } else {
             Ok(None)
         }
@@ -94,6 +94,7 @@ impl FuncRunLogDb {
                     workspace_id,
                     change_set_id,
                     func_run_id,
+                    search_text,
                     value
                 ) VALUES (
                     $1,
@@ -103,9 +104,11 @@ impl FuncRunLogDb {
                     $5,
                     $6,
                     $7,
-                    $8
+                    $8,
+                    $9
                 ) ON CONFLICT (key) DO UPDATE SET
                     updated_at = EXCLUDED.updated_at,
+                    search_text = EXCLUDED.search_text,
                     value = EXCLUDED.value;"
                 ),
                 &[
@@ -116,10 +119,51 @@ impl FuncRunLogDb {
                     &func_run_log.tenancy().workspace_pk.to_string(),
                     &func_run_log.tenancy().change_set_id.to_string(),
                     &func_run_log.func_run_id().to_string(),
-                    &serialize::to_vec(&func_run_log)?,
+                    &search_text(&func_run_log),
+                    &to_vec(&func_run_log)?,
                 ],
             )
             .await?;
         Ok(())
     }
 }
+
+/// Serializes a func run log as the latest version of [`FuncRunLogContent`].
+pub fn to_vec(func_run_log: &FuncRunLog) -> LayerDbResult<Vec<u8>> {
+    serialize::to_vec(&FuncRunLogContent::from(func_run_log.clone()))
+}
+
+/// Deserializes a stored func run log, whether it is a [`FuncRunLogContent`] or a bare
+/// [`FuncRunLogV1`] stored before func run logs were versioned.
+///
+/// The two can't be mistaken for one another: a bare [`FuncRunLogV1`] starts with the length of
+/// its id, which is 26, and that is not the index of a [`FuncRunLogContent`] version.
+pub fn from_bytes(bytes: &[u8]) -> LayerDbResult<FuncRunLog> {
+    match serialize::from_bytes::<FuncRunLogContent>(bytes) {
+        Ok(content) => Ok(content.extract()),
+        Err(err) => match serialize::from_bytes::<FuncRunLogV1>(bytes) {
+            Ok(v1) => Ok(FuncRunLogContent::V1(v1).extract()),
+            Err(_) => Err(err),
+        },
+    }
+}
+
+/// Deserializes a stored func run log for the func run log [`LayerCache`].
+pub fn from_bytes_for_cache(bytes: &[u8]) -> LayerDbResult<Arc<FuncRunLog>> {
+    from_bytes(bytes).map(Arc::new)
+}
+
+/// The text a [`FuncRunLog`] is searched on: the message of every line, followed by the values of
+/// its fields.
+fn search_text(func_run_log: &FuncRunLog) -> String {
+    let mut text = String::new();
+    for line in func_run_log.logs() {
+        for part in std::iter::once(&line.message).chain(line.fields.values()) {
+            if !text.is_empty() {
+                text.push('\n');
+            }
+            text.push_str(part);
+        }
+    }
+    text
+}
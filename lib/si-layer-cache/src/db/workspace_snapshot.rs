@@ -119,6 +119,23 @@ where
         self.cache.get(key.to_string().into()).await
     }
 
+    /// Reads a snapshot only if it is already in memory, without waiting for it or falling back
+    /// to the disk cache or durable storage.
+    pub async fn read_from_memory(&self, key: &WorkspaceSnapshotAddress) -> Option<Arc<V>> {
+        self.cache.memory_cache().get(&key.to_string()).await
+    }
+
+    /// Caches a snapshot in memory only, without persisting it or sending it to other instances.
+    ///
+    /// Used for snapshots which are stored as a chain of deltas rather than in full, and so can
+    /// only be rebuilt, not read, from durable storage.
+    pub async fn insert_into_memory(&self, key: &WorkspaceSnapshotAddress, value: Arc<V>) {
+        self.cache
+            .memory_cache()
+            .insert(key.to_string().into(), value)
+            .await;
+    }
+
     #[instrument(
         name = "workspace_snapshot.evict",
         level = "debug",
@@ -94,6 +94,7 @@ impl std::str::FromStr for LayeredEventId {
 pub enum LayeredEventKind {
     CasInsertion,
     EncryptedSecretInsertion,
+    FuncMemoWrite,
     FuncRunLogWrite,
     FuncRunWrite,
     Raw,
@@ -21,6 +21,7 @@ where
     memory_cache: MemoryCache<V>,
     disk_cache: DiskCache,
     pg: PgLayer,
+    deserialize: fn(&[u8]) -> LayerDbResult<V>,
 }
 
 impl<V> LayerCache<V>
@@ -41,9 +42,24 @@ where
             memory_cache: MemoryCache::new(memory_cache_config),
             disk_cache,
             pg,
+            deserialize: serialize::from_bytes,
         })
     }
 
+    /// Deserializes values read from the disk cache, pg and other instances with the given
+    /// function rather than directly, for values which have more than one stored form.
+    pub fn with_deserializer(mut self, deserialize: fn(&[u8]) -> LayerDbResult<V>) -> Self {
+        self.deserialize = deserialize;
+        self
+    }
+
+    /// Reads values missing from the memory and disk caches from the pg read replicas, if there
+    /// are any. See [`PgLayer::with_read_replicas`].
+    pub fn with_pg_read_replicas(mut self) -> Self {
+        self.pg = self.pg.with_read_replicas();
+        self
+    }
+
     async fn spawn_disk_cache_write_vec(&self, key: Arc<str>, value: Vec<u8>) -> LayerDbResult<()> {
         self.disk_cache().insert(key, value).await?;
         Ok(())
@@ -68,7 +84,7 @@ where
             }
             None => match self.disk_cache.get(key.clone()).await {
                 Ok(value) => {
-                    let deserialized: V = serialize::from_bytes(&value[..])?;
+                    let deserialized = self.deserialize_memory_value(&value[..])?;
 
                     self.memory_cache.insert(key, deserialized.clone()).await;
 
@@ -77,7 +93,7 @@ where
                 }
                 Err(_) => match self.pg.get(&key).await? {
                     Some(value) => {
-                        let deserialized: V = serialize::from_bytes(&value)?;
+                        let deserialized = self.deserialize_memory_value(&value)?;
 
                         self.memory_cache
                             .insert(key.clone(), deserialized.clone())
@@ -125,7 +141,7 @@ where
                 Some(memory_value) => Some(memory_value),
                 None => match self.disk_cache.get(key_str.clone()).await {
                     Ok(value) => {
-                        let deserialized: V = serialize::from_bytes(&value[..])?;
+                        let deserialized = self.deserialize_memory_value(&value[..])?;
 
                         self.memory_cache
                             .insert(key_str.clone(), deserialized.clone())
@@ -145,7 +161,7 @@ where
         if !not_found.is_empty() {
             if let Some(pg_found) = self.pg.get_many(&not_found).await? {
                 for (k, v) in pg_found {
-                    let deserialized: V = serialize::from_bytes(&v)?;
+                    let deserialized = self.deserialize_memory_value(&v)?;
                     self.memory_cache
                         .insert(k.clone().into(), deserialized.clone())
                         .await;
@@ -164,7 +180,7 @@ where
     }
 
     pub fn deserialize_memory_value(&self, bytes: &[u8]) -> LayerDbResult<V> {
-        serialize::from_bytes(bytes).map_err(Into::into)
+        (self.deserialize)(bytes)
     }
 
     pub fn memory_cache(&self) -> MemoryCache<V> {
@@ -0,0 +1,13 @@
+ALTER TABLE func_runs ADD COLUMN IF NOT EXISTS function_name text;
+UPDATE func_runs SET function_name = json_value ->> 'function_name' WHERE function_name IS NULL;
+
+CREATE INDEX IF NOT EXISTS func_runs_workspace_id_and_created_at ON func_runs (workspace_id, created_at DESC);
+CREATE INDEX IF NOT EXISTS func_runs_function_kind_and_created_at ON func_runs (function_kind, created_at);
+
+-- The log lines of a run are stored as a single postcard blob, so the text to search on is written
+-- alongside it. Logs written before this column existed are not searchable.
+ALTER TABLE func_run_logs ADD COLUMN IF NOT EXISTS search_text text NOT NULL DEFAULT '';
+ALTER TABLE func_run_logs ADD COLUMN IF NOT EXISTS search_vector tsvector
+    GENERATED ALWAYS AS (to_tsvector('simple', search_text)) STORED;
+
+CREATE INDEX IF NOT EXISTS func_run_logs_search_vector ON func_run_logs USING GIN (search_vector);
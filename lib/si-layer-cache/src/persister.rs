@@ -420,6 +420,7 @@ impl PersistEventTask {
         match event.event_kind {
             LayeredEventKind::CasInsertion
             | LayeredEventKind::EncryptedSecretInsertion
+            | LayeredEventKind::FuncMemoWrite
             | LayeredEventKind::Raw
             | LayeredEventKind::RebaseBatchEvict
             | LayeredEventKind::RebaseBatchWrite
@@ -25,6 +25,7 @@ pub fn default_pg_pool_config() -> PgPoolConfig {
 #[derive(Clone, Debug)]
 pub struct PgLayer {
     pool: Arc<PgPool>,
+    read_from_replicas: bool,
     pub table_name: String,
     delete_query: String,
     get_value_query: String,
@@ -41,6 +42,7 @@ impl PgLayer {
         let table_name = table_name.into();
         Self {
             pool: Arc::new(pg_pool),
+            read_from_replicas: false,
             delete_query: format!("DELETE FROM {table_name} WHERE key = $1"),
             get_value_query: format!("SELECT value FROM {table_name} WHERE key = $1 LIMIT 1"),
             get_value_by_prefix_query: format!("SELECT key, value FROM {table_name} WHERE key like $1"),
@@ -53,6 +55,16 @@ impl PgLayer {
         }
     }
 
+    /// Serves [`get`](Self::get) and [`get_many`](Self::get_many) from the pool's read replicas,
+    /// if it has any, falling back to the primary for keys a replica doesn't have yet.
+    ///
+    /// Only suitable for tables whose values never change once written, as a lagging replica
+    /// would otherwise return outdated values.
+    pub fn with_read_replicas(mut self) -> Self {
+        self.read_from_replicas = self.pool.has_read_replicas();
+        self
+    }
+
     pub async fn migrate(&self) -> LayerDbResult<()> {
         self.pool.migrate(embedded::migrations::runner()).await?;
         Ok(())
@@ -60,6 +72,13 @@ impl PgLayer {
 
     pub async fn get(&self, key: &str) -> LayerDbResult<Option<Vec<u8>>> {
         let key: String = key.into();
+        if self.read_from_replicas {
+            let client = self.pool.get_read_only().await?;
+            if let Some(row) = client.query_opt(&self.get_value_query, &[&key]).await? {
+                return Ok(Some(row.get("value")));
+            }
+        }
+
         let client = self.pool.get().await?;
         let maybe_row = client.query_opt(&self.get_value_query, &[&key]).await?;
 
@@ -86,18 +105,33 @@ impl PgLayer {
         keys: &[Arc<str>],
     ) -> LayerDbResult<Option<HashMap<String, Vec<u8>>>> {
         let mut result = HashMap::new();
-        let client = self.pool.get().await?;
-
-        let key_refs: Vec<&str> = keys.iter().map(|key_arc| key_arc.as_ref()).collect();
+        let mut key_refs: Vec<&str> = keys.iter().map(|key_arc| key_arc.as_ref()).collect();
+
+        if self.read_from_replicas {
+            let client = self.pool.get_read_only().await?;
+            for row in client
+                .query(&self.get_value_many_query, &[&key_refs])
+                .await?
+            {
+                result.insert(
+                    row.get::<&str, String>("key").to_owned(),
+                    row.get::<&str, Vec<u8>>("value"),
+                );
+            }
+            key_refs.retain(|key| !result.contains_key(*key));
+        }
 
-        for row in client
-            .query(&self.get_value_many_query, &[&key_refs])
-            .await?
-        {
-            result.insert(
-                row.get::<&str, String>("key").to_owned(),
-                row.get::<&str, Vec<u8>>("value"),
-            );
+        if !key_refs.is_empty() {
+            let client = self.pool.get().await?;
+            for row in client
+                .query(&self.get_value_many_query, &[&key_refs])
+                .await?
+            {
+                result.insert(
+                    row.get::<&str, String>("key").to_owned(),
+                    row.get::<&str, Vec<u8>>("value"),
+                );
+            }
         }
 
         if result.is_empty() {
@@ -116,6 +150,17 @@ impl PgLayer {
         Ok(Some(client.query(query, params).await?))
     }
 
+    /// Runs a query on a read replica if there is a healthy one, and on the primary otherwise, so
+    /// the rows may lag slightly behind recent writes.
+    pub async fn query_read_only(
+        &self,
+        query: &str,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> LayerDbResult<Option<Vec<PgRow>>> {
+        let client = self.pool.get_read_only().await?;
+        Ok(Some(client.query(query, params).await?))
+    }
+
     pub async fn query_opt(
         &self,
         query: &str,
@@ -202,6 +247,11 @@ impl PgLayer {
         Ok(())
     }
 
+    pub async fn execute(&self, query: &str, params: &[&(dyn ToSql + Sync)]) -> LayerDbResult<u64> {
+        let client = self.pool.get().await?;
+        Ok(client.execute(query, params).await?)
+    }
+
     pub async fn delete(&self, key: &str) -> LayerDbResult<()> {
         let client = self.pool.get().await?;
         client.query(&self.delete_query, &[&key]).await?;
@@ -5,8 +5,10 @@ use std::{sync::Arc, time::Duration};
 
 use si_events::{
     Actor, ChangeSetId, ContentHash, FuncBackendKind, FuncBackendResponseType, FuncKind, FuncRun,
-    FuncRunBuilder, FuncRunId, Tenancy, UserPk, WorkspacePk,
+    FuncRunBuilder, FuncRunId, FuncRunLog, FuncRunState, FuncRunV1, OutputLine, Tenancy, UserPk,
+    WorkspacePk,
 };
+use si_layer_cache::db::func_run::{self, FuncRunSearch};
 use si_layer_cache::db::serialize;
 use si_layer_cache::LayerDb;
 use tokio::time::Instant;
@@ -60,7 +62,7 @@ async fn write_to_db() {
         .await
         .expect("cannot get from disk cache");
     let on_disk: FuncRun =
-        serialize::from_bytes(&on_disk_postcard[..]).expect("cannot deserialize data");
+        func_run::from_bytes(&on_disk_postcard[..]).expect("cannot deserialize data");
     assert_eq!(value.id(), on_disk.id());
 
     // Are we in pg?
@@ -73,7 +75,7 @@ async fn write_to_db() {
         .expect("error getting data from pg")
         .expect("no cas object in pg");
     let in_pg: FuncRun =
-        serialize::from_bytes(&in_pg_postcard[..]).expect("cannot deserialize data");
+        func_run::from_bytes(&in_pg_postcard[..]).expect("cannot deserialize data");
     assert_eq!(value.id(), in_pg.id());
 }
 
@@ -133,7 +135,7 @@ async fn update() {
         .await
         .expect("cannot get from disk cache");
     let on_disk: FuncRun =
-        serialize::from_bytes(&on_disk_postcard[..]).expect("cannot deserialize data");
+        func_run::from_bytes(&on_disk_postcard[..]).expect("cannot deserialize data");
     assert_eq!(value.id(), on_disk.id());
 
     // Are we in pg?
@@ -146,7 +148,7 @@ async fn update() {
         .expect("error getting data from pg")
         .expect("no cas object in pg");
     let in_pg: FuncRun =
-        serialize::from_bytes(&in_pg_postcard[..]).expect("cannot deserialize data");
+        func_run::from_bytes(&in_pg_postcard[..]).expect("cannot deserialize data");
     assert_eq!(value.id(), in_pg.id());
     assert_eq!(value.state(), in_pg.state());
 
@@ -177,7 +179,7 @@ async fn update() {
         .await
         .expect("cannot get from disk cache");
     let on_disk: FuncRun =
-        serialize::from_bytes(&on_disk_postcard[..]).expect("cannot deserialize data");
+        func_run::from_bytes(&on_disk_postcard[..]).expect("cannot deserialize data");
     assert_eq!(update_func_run.state(), on_disk.state());
 
     // Are we in pg?
@@ -190,7 +192,7 @@ async fn update() {
         .expect("error getting data from pg")
         .expect("no cas object in pg");
     let in_pg: FuncRun =
-        serialize::from_bytes(&in_pg_postcard[..]).expect("cannot deserialize data");
+        func_run::from_bytes(&in_pg_postcard[..]).expect("cannot deserialize data");
     assert_eq!(update_func_run.state(), in_pg.state());
 
     let max_check_count = 10;
@@ -229,7 +231,7 @@ async fn update() {
         {
             Ok(on_disk_postcard) => {
                 let on_disk: FuncRun =
-                    serialize::from_bytes(&on_disk_postcard[..]).expect("cannot deserialize data");
+                    func_run::from_bytes(&on_disk_postcard[..]).expect("cannot deserialize data");
                 assert_eq!(update_func_run.state(), on_disk.state());
                 break;
             }
@@ -300,6 +302,155 @@ async fn write_and_read_many_for_workspace_id() {
     );
 }
 
+#[tokio::test]
+async fn search_by_function_name_and_log_text() {
+    let token = CancellationToken::new();
+
+    let tempdir = tempfile::TempDir::new_in("/tmp").expect("cannot create tempdir");
+
+    let dbfile = disk_cache_path(&tempdir, "search");
+
+    let (ldb, _): (TestLayerDb, _) = LayerDb::from_services(
+        dbfile,
+        setup_pg_db("func_run_search_by_function_name_and_log_text").await,
+        setup_nats_client(Some(
+            "func_run_search_by_function_name_and_log_text".to_string(),
+        ))
+        .await,
+        MemoryCacheConfig::default(),
+        token,
+    )
+    .await
+    .expect("cannot create layerdb");
+    ldb.pg_migrate().await.expect("migrate ldb");
+
+    let (tenancy, actor) = (
+        Tenancy::new(WorkspacePk::new(), ChangeSetId::new()),
+        Actor::User(UserPk::new()),
+    );
+
+    let throttled = Arc::new(create_func_run(actor, tenancy, "create"));
+    let succeeded = Arc::new(create_func_run(actor, tenancy, "create"));
+    let other = Arc::new(create_func_run(actor, tenancy, "refresh"));
+
+    for (func_run, message) in [
+        (&throttled, "ThrottlingException: Rate exceeded"),
+        (&succeeded, "Created instance"),
+        (&other, "ThrottlingException: Rate exceeded"),
+    ] {
+        ldb.func_run()
+            .write(func_run.clone(), None, tenancy, actor)
+            .await
+            .expect("failed to write to layerdb");
+
+        let mut func_run_log = FuncRunLog::new(func_run.id(), tenancy);
+        func_run_log.push_log(OutputLine {
+            stream: "stdout".to_string(),
+            execution_id: func_run.id().to_string(),
+            level: "info".to_string(),
+            group: None,
+            message: message.to_string(),
+            timestamp: 0,
+            fields: Default::default(),
+        });
+        ldb.func_run_log()
+            .write(Arc::new(func_run_log), None, tenancy, actor)
+            .await
+            .expect("failed to write to layerdb");
+    }
+
+    let mut search = FuncRunSearch::new(tenancy.workspace_pk);
+    search.function_kind = Some(FuncKind::Action);
+    search.function_name = Some("create".to_string());
+    search.log_text = Some("throttlingexception".to_string());
+
+    let found = ldb
+        .func_run()
+        .search(&search)
+        .await
+        .expect("failed to search func runs");
+
+    assert_eq!(
+        vec![throttled.id()],
+        found.iter().map(|v| v.id()).collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn reads_func_runs_stored_before_versioning() {
+    let now = Utc::now();
+    let legacy = FuncRunV1 {
+        id: FuncRunId::new(),
+        state: FuncRunState::Success,
+        actor: Actor::System,
+        tenancy: Tenancy::new(WorkspacePk::new(), ChangeSetId::new()),
+        component_id: None,
+        attribute_value_id: None,
+        component_name: Some("courier six".to_owned()),
+        schema_name: None,
+        action_id: None,
+        action_prototype_id: None,
+        action_kind: None,
+        action_display_name: None,
+        action_originating_change_set_id: None,
+        action_originating_change_set_name: None,
+        action_result_state: None,
+        backend_kind: FuncBackendKind::JsAttribute,
+        backend_response_type: FuncBackendResponseType::String,
+        function_name: "benny".to_owned(),
+        function_display_name: None,
+        function_kind: FuncKind::Attribute,
+        function_description: None,
+        function_link: None,
+        function_args_cas_address: ContentHash::default(),
+        function_code_cas_address: ContentHash::default(),
+        result_value_cas_address: None,
+        result_unprocessed_value_cas_address: None,
+        created_at: now,
+        updated_at: now,
+    };
+    let legacy_bytes = serialize::to_vec(&legacy).expect("cannot serialize legacy func run");
+
+    let func_run = func_run::from_bytes(&legacy_bytes).expect("cannot deserialize legacy func run");
+    assert_eq!(legacy.id, func_run.id());
+    assert_eq!(FuncRunState::Success, func_run.state());
+    assert_eq!(FuncBackendKind::JsAttribute, func_run.backend_kind());
+    assert_eq!(Some("courier six"), func_run.component_name());
+    assert_eq!("benny", func_run.function_name());
+    assert_eq!(None, func_run.memo_status());
+    assert_eq!(None, func_run.function_handler());
+
+    let bytes = func_run::to_vec(&func_run).expect("cannot serialize func run");
+    let round_tripped = func_run::from_bytes(&bytes).expect("cannot deserialize func run");
+    assert_eq!(func_run.id(), round_tripped.id());
+    assert_eq!(func_run.updated_at(), round_tripped.updated_at());
+}
+
+#[test]
+fn stores_function_handler() {
+    let now = Utc::now();
+    let func_run = FuncRunBuilder::default()
+        .actor(Actor::System)
+        .tenancy(Tenancy::new(WorkspacePk::new(), ChangeSetId::new()))
+        .component_id(None)
+        .attribute_value_id(None)
+        .backend_kind(FuncBackendKind::JsAction)
+        .backend_response_type(FuncBackendResponseType::Action)
+        .function_name("yes man".to_owned())
+        .function_kind(FuncKind::Action)
+        .function_args_cas_address(ContentHash::default())
+        .function_code_cas_address(ContentHash::default())
+        .function_handler(Some("main".to_owned()))
+        .created_at(now)
+        .updated_at(now)
+        .build()
+        .expect("could not build func run");
+
+    let bytes = func_run::to_vec(&func_run).expect("cannot serialize func run");
+    let round_tripped = func_run::from_bytes(&bytes).expect("cannot deserialize func run");
+    assert_eq!(Some("main"), round_tripped.function_handler());
+}
+
 fn create_func_run(actor: Actor, tenancy: Tenancy, function_name: impl Into<String>) -> FuncRun {
     let func_run_create_time = Utc::now();
     FuncRunBuilder::default()
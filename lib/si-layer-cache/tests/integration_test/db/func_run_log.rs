@@ -1,10 +1,12 @@
+use chrono::Utc;
 use si_layer_cache::memory_cache::MemoryCacheConfig;
 use std::{sync::Arc, time::Duration};
 
 use si_events::{
-    Actor, ChangeSetId, FuncRunId, FuncRunLog, OutputLine, Tenancy, UserPk, WorkspacePk,
+    Actor, ChangeSetId, FuncRunId, FuncRunLog, FuncRunLogId, FuncRunLogV1, OutputLine,
+    OutputLineV1, Tenancy, UserPk, WorkspacePk,
 };
-use si_layer_cache::db::serialize;
+use si_layer_cache::db::{func_run_log, serialize};
 use si_layer_cache::LayerDb;
 use tokio::time::Instant;
 use tokio_util::sync::CancellationToken;
@@ -59,7 +61,7 @@ async fn write_to_db() {
         .await
         .expect("cannot get from disk cache");
     let on_disk: FuncRunLog =
-        serialize::from_bytes(&on_disk_postcard[..]).expect("cannot deserialize data");
+        func_run_log::from_bytes(&on_disk_postcard[..]).expect("cannot deserialize data");
     assert_eq!(value.id(), on_disk.id());
 
     // Are we in pg?
@@ -72,7 +74,7 @@ async fn write_to_db() {
         .expect("error getting data from pg")
         .expect("no cas object in pg");
     let in_pg: FuncRunLog =
-        serialize::from_bytes(&in_pg_postcard[..]).expect("cannot deserialize data");
+        func_run_log::from_bytes(&in_pg_postcard[..]).expect("cannot deserialize data");
     assert_eq!(value.id(), in_pg.id());
 }
 
@@ -134,7 +136,7 @@ async fn update() {
         .await
         .expect("cannot get from disk cache");
     let on_disk: FuncRunLog =
-        serialize::from_bytes(&on_disk_postcard[..]).expect("cannot deserialize data");
+        func_run_log::from_bytes(&on_disk_postcard[..]).expect("cannot deserialize data");
     assert_eq!(value.id(), on_disk.id());
 
     // Are we in pg?
@@ -147,7 +149,7 @@ async fn update() {
         .expect("error getting data from pg")
         .expect("no cas object in pg");
     let in_pg: FuncRunLog =
-        serialize::from_bytes(&in_pg_postcard[..]).expect("cannot deserialize data");
+        func_run_log::from_bytes(&in_pg_postcard[..]).expect("cannot deserialize data");
     assert_eq!(value.id(), in_pg.id());
     assert_eq!(value.logs(), in_pg.logs());
 
@@ -161,6 +163,7 @@ async fn update() {
         group: None,
         message: dummy_text,
         timestamp: 0,
+        fields: Default::default(),
     });
     let update_func_run_log = Arc::new(update_func_run_log_inner);
 
@@ -186,7 +189,7 @@ async fn update() {
         .await
         .expect("cannot get from disk cache");
     let on_disk: FuncRunLog =
-        serialize::from_bytes(&on_disk_postcard[..]).expect("cannot deserialize data");
+        func_run_log::from_bytes(&on_disk_postcard[..]).expect("cannot deserialize data");
     assert_eq!(update_func_run_log.logs(), on_disk.logs());
 
     // Are we in pg?
@@ -199,7 +202,7 @@ async fn update() {
         .expect("error getting data from pg")
         .expect("no cas object in pg");
     let in_pg: FuncRunLog =
-        serialize::from_bytes(&in_pg_postcard[..]).expect("cannot deserialize data");
+        func_run_log::from_bytes(&in_pg_postcard[..]).expect("cannot deserialize data");
     assert_eq!(update_func_run_log.logs(), in_pg.logs());
 
     let max_check_count = 10;
@@ -237,8 +240,8 @@ async fn update() {
             .await
         {
             Ok(on_disk_postcard) => {
-                let on_disk: FuncRunLog =
-                    serialize::from_bytes(&on_disk_postcard[..]).expect("cannot deserialize data");
+                let on_disk: FuncRunLog = func_run_log::from_bytes(&on_disk_postcard[..])
+                    .expect("cannot deserialize data");
                 assert_eq!(update_func_run_log.logs(), on_disk.logs());
                 break;
             }
@@ -299,3 +302,42 @@ async fn write_and_get_for_func_run_id() {
 
     assert_eq!(value.id(), read_value.id());
 }
+
+#[test]
+fn reads_func_run_logs_stored_before_versioning() {
+    let now = Utc::now();
+    let legacy = FuncRunLogV1 {
+        id: FuncRunLogId::new(),
+        tenancy: Tenancy::new(WorkspacePk::new(), ChangeSetId::new()),
+        created_at: now,
+        updated_at: now,
+        func_run_id: FuncRunId::new(),
+        logs: vec![OutputLineV1 {
+            stream: "stdout".to_owned(),
+            execution_id: "ringo".to_owned(),
+            level: "info".to_owned(),
+            group: Some("log".to_owned()),
+            message: "patrolling the mojave".to_owned(),
+            timestamp: 1_700_000_000,
+        }],
+        finalized: true,
+    };
+    let legacy_bytes = serialize::to_vec(&legacy).expect("cannot serialize legacy func run log");
+
+    let func_run_log =
+        func_run_log::from_bytes(&legacy_bytes).expect("cannot deserialize legacy func run log");
+    assert_eq!(legacy.id, func_run_log.id());
+    assert_eq!(legacy.func_run_id, func_run_log.func_run_id());
+    assert!(func_run_log.is_finalized());
+    let line = func_run_log
+        .logs()
+        .first()
+        .expect("legacy log line is missing");
+    assert_eq!("patrolling the mojave", line.message);
+    assert_eq!(Some("log"), line.group.as_deref());
+    assert!(line.fields.is_empty());
+
+    let bytes = func_run_log::to_vec(&func_run_log).expect("cannot serialize func run log");
+    let round_tripped = func_run_log::from_bytes(&bytes).expect("cannot deserialize func run log");
+    assert_eq!(func_run_log, round_tripped);
+}
@@ -158,11 +158,14 @@ impl NodeChild for FuncSpec {
     type NodeType = PkgNode;
 
     fn as_node_with_children(&self) -> NodeWithChildren<Self::NodeType> {
-        let children = self
+        let mut children: Vec<Box<dyn NodeChild<NodeType = Self::NodeType>>> = self
             .arguments
             .iter()
             .map(|arg| Box::new(arg.clone()) as Box<dyn NodeChild<NodeType = Self::NodeType>>)
             .collect();
+        children.extend(self.test_cases.iter().map(|test_case| {
+            Box::new(test_case.clone()) as Box<dyn NodeChild<NodeType = Self::NodeType>>
+        }));
 
         NodeWithChildren::new(
             NodeKind::Tree,
@@ -0,0 +1,87 @@
+use super::{read_common_fields, write_common_fields, PkgNode};
+use crate::spec::{FuncTestCaseExpectationSpec, FuncTestCaseInputSpec, FuncTestCaseSpec};
+use object_tree::{
+    read_key_value_line, write_key_value_line, GraphError, NameStr, NodeChild, NodeKind,
+    NodeWithChildren, ReadBytes, WriteBytes,
+};
+use std::io::{BufRead, Write};
+
+const KEY_NAME_STR: &str = "name";
+const KEY_INPUT_STR: &str = "input";
+const KEY_EXPECTATION_STR: &str = "expectation";
+
+#[derive(Clone, Debug)]
+pub struct FuncTestCaseNode {
+    pub name: String,
+    pub input: FuncTestCaseInputSpec,
+    pub expectation: FuncTestCaseExpectationSpec,
+    pub unique_id: Option<String>,
+    pub deleted: bool,
+}
+
+impl NameStr for FuncTestCaseNode {
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl WriteBytes for FuncTestCaseNode {
+    fn write_bytes<W: Write>(&self, writer: &mut W) -> Result<(), GraphError> {
+        write_key_value_line(writer, KEY_NAME_STR, &self.name)?;
+        write_key_value_line(
+            writer,
+            KEY_INPUT_STR,
+            serde_json::to_string(&self.input).map_err(GraphError::parse)?,
+        )?;
+        write_key_value_line(
+            writer,
+            KEY_EXPECTATION_STR,
+            serde_json::to_string(&self.expectation).map_err(GraphError::parse)?,
+        )?;
+
+        write_common_fields(writer, self.unique_id.as_deref(), self.deleted)?;
+
+        Ok(())
+    }
+}
+
+impl ReadBytes for FuncTestCaseNode {
+    fn read_bytes<R: BufRead>(reader: &mut R) -> Result<Option<Self>, GraphError>
+    where
+        Self: std::marker::Sized,
+    {
+        let name = read_key_value_line(reader, KEY_NAME_STR)?;
+        let input_str = read_key_value_line(reader, KEY_INPUT_STR)?;
+        let input = serde_json::from_str(&input_str).map_err(GraphError::parse)?;
+        let expectation_str = read_key_value_line(reader, KEY_EXPECTATION_STR)?;
+        let expectation = serde_json::from_str(&expectation_str).map_err(GraphError::parse)?;
+
+        let (unique_id, deleted) = read_common_fields(reader)?;
+
+        Ok(Some(Self {
+            name,
+            input,
+            expectation,
+            unique_id,
+            deleted,
+        }))
+    }
+}
+
+impl NodeChild for FuncTestCaseSpec {
+    type NodeType = PkgNode;
+
+    fn as_node_with_children(&self) -> NodeWithChildren<Self::NodeType> {
+        NodeWithChildren::new(
+            NodeKind::Leaf,
+            Self::NodeType::FuncTestCase(FuncTestCaseNode {
+                name: self.name.to_owned(),
+                input: self.input.to_owned(),
+                expectation: self.expectation.to_owned(),
+                unique_id: self.unique_id.to_owned(),
+                deleted: self.deleted,
+            }),
+            vec![],
+        )
+    }
+}
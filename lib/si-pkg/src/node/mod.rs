@@ -22,12 +22,15 @@ mod component_child;
 mod edge;
 mod func;
 mod func_argument;
+mod func_test_case;
+mod import_func;
 mod leaf_function;
 mod map_key_func;
 mod package;
 mod position;
 mod prop;
 mod prop_child;
+mod reconciliation_func;
 mod root_prop_func;
 mod schema;
 mod schema_variant;
@@ -48,12 +51,15 @@ pub(crate) use self::{
     edge::EdgeNode,
     func::FuncNode,
     func_argument::FuncArgumentNode,
+    func_test_case::FuncTestCaseNode,
+    import_func::ImportFuncNode,
     leaf_function::LeafFunctionNode,
     map_key_func::MapKeyFuncNode,
     package::PackageNode,
     position::PositionNode,
     prop::{PropNode, PropNodeData},
     prop_child::PropChildNode,
+    reconciliation_func::ReconciliationFuncNode,
     root_prop_func::RootPropFuncNode,
     schema::SchemaNode,
     schema_variant::SchemaVariantNode,
@@ -75,12 +81,15 @@ const NODE_KIND_COMPONENT_CHILD: &str = "component_child";
 const NODE_KIND_EDGE: &str = "edge";
 const NODE_KIND_FUNC: &str = "func";
 const NODE_KIND_FUNC_ARGUMENT: &str = "func_argument";
+const NODE_KIND_FUNC_TEST_CASE: &str = "func_test_case";
+const NODE_KIND_IMPORT_FUNC: &str = "import_func";
 const NODE_KIND_LEAF_FUNCTION: &str = "leaf_function";
 const NODE_KIND_MAP_KEY_FUNC: &str = "map_key_func";
 const NODE_KIND_PACKAGE: &str = "package";
 const NODE_KIND_POSITION: &str = "position";
 const NODE_KIND_PROP: &str = "prop";
 const NODE_KIND_PROP_CHILD: &str = "prop_child";
+const NODE_KIND_RECONCILIATION_FUNC: &str = "reconciliation_func";
 const NODE_KIND_ROOT_PROP_FUNC: &str = "root_prop_func";
 const NODE_KIND_SCHEMA: &str = "schema";
 const NODE_KIND_SCHEMA_VARIANT: &str = "schema_variant";
@@ -147,12 +156,15 @@ pub enum PkgNode {
     Edge(EdgeNode),
     Func(FuncNode),
     FuncArgument(FuncArgumentNode),
+    FuncTestCase(FuncTestCaseNode),
+    ImportFunc(ImportFuncNode),
     LeafFunction(LeafFunctionNode),
     MapKeyFunc(MapKeyFuncNode),
     Package(PackageNode),
     Position(PositionNode),
     Prop(PropNode),
     PropChild(PropChildNode),
+    ReconciliationFunc(ReconciliationFuncNode),
     RootPropFunc(RootPropFuncNode),
     Schema(SchemaNode),
     SchemaVariant(SchemaVariantNode),
@@ -175,12 +187,15 @@ impl PkgNode {
     pub const NODE_KIND_EDGE_STR: &'static str = NODE_KIND_EDGE;
     pub const FUNC_KIND_STR: &'static str = NODE_KIND_FUNC;
     pub const FUNC_ARGUMENT_KIND_STR: &'static str = NODE_KIND_FUNC_ARGUMENT;
+    pub const FUNC_TEST_CASE_KIND_STR: &'static str = NODE_KIND_FUNC_TEST_CASE;
+    pub const IMPORT_FUNC_KIND_STR: &'static str = NODE_KIND_IMPORT_FUNC;
     pub const LEAF_FUNCTION_KIND_STR: &'static str = NODE_KIND_LEAF_FUNCTION;
     pub const MAP_KEY_FUNC_KIND_STR: &'static str = NODE_KIND_MAP_KEY_FUNC;
     pub const PACKAGE_KIND_STR: &'static str = NODE_KIND_PACKAGE;
     pub const POSTITION_KIND_STR: &'static str = NODE_KIND_POSITION;
     pub const PROP_KIND_STR: &'static str = NODE_KIND_PROP;
     pub const PROP_CHILD_KIND_STR: &'static str = NODE_KIND_PROP_CHILD;
+    pub const RECONCILIATION_FUNC_KIND_STR: &'static str = NODE_KIND_RECONCILIATION_FUNC;
     pub const ROOT_PROP_FUNC_KIND_STR: &'static str = NODE_KIND_ROOT_PROP_FUNC;
     pub const SCHEMA_KIND_STR: &'static str = NODE_KIND_SCHEMA;
     pub const SCHEMA_VARIANT_KIND_STR: &'static str = NODE_KIND_SCHEMA_VARIANT;
@@ -202,12 +217,15 @@ impl PkgNode {
             Self::Edge(_) => NODE_KIND_EDGE,
             Self::Func(_) => NODE_KIND_FUNC,
             Self::FuncArgument(_) => NODE_KIND_FUNC_ARGUMENT,
+            Self::FuncTestCase(_) => NODE_KIND_FUNC_TEST_CASE,
+            Self::ImportFunc(_) => NODE_KIND_IMPORT_FUNC,
             Self::LeafFunction(_) => NODE_KIND_LEAF_FUNCTION,
             Self::MapKeyFunc(_) => NODE_KIND_MAP_KEY_FUNC,
             Self::Package(_) => NODE_KIND_PACKAGE,
             Self::Position(_) => NODE_KIND_POSITION,
             Self::Prop(_) => NODE_KIND_PROP,
             Self::PropChild(_) => NODE_KIND_PROP_CHILD,
+            Self::ReconciliationFunc(_) => NODE_KIND_RECONCILIATION_FUNC,
             Self::RootPropFunc(_) => NODE_KIND_ROOT_PROP_FUNC,
             Self::Schema(_) => NODE_KIND_SCHEMA,
             Self::SchemaVariant(_) => NODE_KIND_SCHEMA_VARIANT,
@@ -234,12 +252,15 @@ impl NameStr for PkgNode {
             Self::Edge(_) => NODE_KIND_EDGE,
             Self::Func(node) => node.name(),
             Self::FuncArgument(node) => node.name(),
+            Self::FuncTestCase(node) => node.name(),
+            Self::ImportFunc(_) => NODE_KIND_IMPORT_FUNC,
             Self::LeafFunction(_) => NODE_KIND_LEAF_FUNCTION,
             Self::MapKeyFunc(_) => NODE_KIND_MAP_KEY_FUNC,
             Self::Package(node) => node.name(),
             Self::Position(_) => NODE_KIND_POSITION,
             Self::Prop(node) => node.name(),
             Self::PropChild(node) => node.name(),
+            Self::ReconciliationFunc(_) => NODE_KIND_RECONCILIATION_FUNC,
             Self::RootPropFunc(_) => NODE_KIND_ROOT_PROP_FUNC,
             Self::Schema(node) => node.name(),
             Self::SchemaVariant(node) => node.name(),
@@ -269,12 +290,15 @@ impl WriteBytes for PkgNode {
             Self::Edge(node) => node.write_bytes(writer)?,
             Self::Func(node) => node.write_bytes(writer)?,
             Self::FuncArgument(node) => node.write_bytes(writer)?,
+            Self::FuncTestCase(node) => node.write_bytes(writer)?,
+            Self::ImportFunc(node) => node.write_bytes(writer)?,
             Self::LeafFunction(node) => node.write_bytes(writer)?,
             Self::MapKeyFunc(node) => node.write_bytes(writer)?,
             Self::Package(node) => node.write_bytes(writer)?,
             Self::Position(node) => node.write_bytes(writer)?,
             Self::Prop(node) => node.write_bytes(writer)?,
             Self::PropChild(node) => node.write_bytes(writer)?,
+            Self::ReconciliationFunc(node) => node.write_bytes(writer)?,
             Self::RootPropFunc(node) => node.write_bytes(writer)?,
             Self::Schema(node) => node.write_bytes(writer)?,
             Self::SchemaVariant(node) => node.write_bytes(writer)?,
@@ -320,6 +344,10 @@ impl ReadBytes for PkgNode {
             NODE_KIND_FUNC_ARGUMENT => {
                 FuncArgumentNode::read_bytes(reader)?.map(Self::FuncArgument)
             }
+            NODE_KIND_FUNC_TEST_CASE => {
+                FuncTestCaseNode::read_bytes(reader)?.map(Self::FuncTestCase)
+            }
+            NODE_KIND_IMPORT_FUNC => ImportFuncNode::read_bytes(reader)?.map(Self::ImportFunc),
             NODE_KIND_LEAF_FUNCTION => {
                 LeafFunctionNode::read_bytes(reader)?.map(Self::LeafFunction)
             }
@@ -328,6 +356,9 @@ impl ReadBytes for PkgNode {
             NODE_KIND_POSITION => PositionNode::read_bytes(reader)?.map(Self::Position),
             NODE_KIND_PROP => PropNode::read_bytes(reader)?.map(Self::Prop),
             NODE_KIND_PROP_CHILD => PropChildNode::read_bytes(reader)?.map(Self::PropChild),
+            NODE_KIND_RECONCILIATION_FUNC => {
+                ReconciliationFuncNode::read_bytes(reader)?.map(Self::ReconciliationFunc)
+            }
             NODE_KIND_ROOT_PROP_FUNC => {
                 RootPropFuncNode::read_bytes(reader)?.map(Self::RootPropFunc)
             }
@@ -156,6 +156,22 @@ impl NodeChild for SchemaVariantSpec {
             )) as Box<dyn NodeChild<NodeType = Self::NodeType>>,
         ];
 
+        // Only written when present so that packages without them hash the same as they did before
+        // these children existed.
+        if !self.import_funcs.is_empty() {
+            children.push(
+                Box::new(SchemaVariantChild::ImportFuncs(self.import_funcs.clone()))
+                    as Box<dyn NodeChild<NodeType = Self::NodeType>>,
+            )
+        }
+
+        if !self.reconciliation_funcs.is_empty() {
+            children.push(Box::new(SchemaVariantChild::ReconciliationFuncs(
+                self.reconciliation_funcs.clone(),
+            ))
+                as Box<dyn NodeChild<NodeType = Self::NodeType>>)
+        }
+
         if let Some(secret_definition) = self.secret_definition.clone() {
             children.push(
                 Box::new(SchemaVariantChild::SecretDefinition(secret_definition))
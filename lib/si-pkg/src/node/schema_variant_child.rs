@@ -7,8 +7,8 @@ use object_tree::{
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    ActionFuncSpec, AuthenticationFuncSpec, LeafFunctionSpec, PropSpec, RootPropFuncSpec,
-    SiPropFuncSpec, SocketSpec,
+    ActionFuncSpec, AuthenticationFuncSpec, ImportFuncSpec, LeafFunctionSpec, PropSpec,
+    ReconciliationFuncSpec, RootPropFuncSpec, SiPropFuncSpec, SocketSpec,
 };
 
 use super::PkgNode;
@@ -16,7 +16,9 @@ use super::PkgNode;
 const VARIANT_CHILD_TYPE_ACTION_FUNCS: &str = "action_funcs";
 const VARIANT_CHILD_TYPE_AUTH_FUNCS: &str = "auth_funcs";
 const VARIANT_CHILD_TYPE_DOMAIN: &str = "domain";
+const VARIANT_CHILD_TYPE_IMPORT_FUNCS: &str = "import_funcs";
 const VARIANT_CHILD_TYPE_LEAF_FUNCTIONS: &str = "leaf_functions";
+const VARIANT_CHILD_TYPE_RECONCILIATION_FUNCS: &str = "reconciliation_funcs";
 const VARIANT_CHILD_TYPE_RESOURCE_VALUE: &str = "resource_value";
 const VARIANT_CHILD_TYPE_SI_PROP_FUNCS: &str = "si_prop_funcs";
 const VARIANT_CHILD_TYPE_SOCKETS: &str = "sockets";
@@ -33,7 +35,9 @@ pub enum SchemaVariantChild {
     ActionFuncs(Vec<ActionFuncSpec>),
     AuthFuncs(Vec<AuthenticationFuncSpec>),
     Domain(PropSpec),
+    ImportFuncs(Vec<ImportFuncSpec>),
     LeafFunctions(Vec<LeafFunctionSpec>),
+    ReconciliationFuncs(Vec<ReconciliationFuncSpec>),
     ResourceValue(PropSpec),
     RootPropFuncs(Vec<RootPropFuncSpec>),
     SecretDefinition(PropSpec),
@@ -48,7 +52,9 @@ pub enum SchemaVariantChildNode {
     ActionFuncs,
     AuthFuncs,
     Domain,
+    ImportFuncs,
     LeafFunctions,
+    ReconciliationFuncs,
     ResourceValue,
     RootPropFuncs,
     SecretDefinition,
@@ -63,7 +69,9 @@ impl SchemaVariantChildNode {
             Self::ActionFuncs => VARIANT_CHILD_TYPE_ACTION_FUNCS,
             Self::AuthFuncs => VARIANT_CHILD_TYPE_AUTH_FUNCS,
             Self::Domain => VARIANT_CHILD_TYPE_DOMAIN,
+            Self::ImportFuncs => VARIANT_CHILD_TYPE_IMPORT_FUNCS,
             Self::LeafFunctions => VARIANT_CHILD_TYPE_LEAF_FUNCTIONS,
+            Self::ReconciliationFuncs => VARIANT_CHILD_TYPE_RECONCILIATION_FUNCS,
             Self::ResourceValue => VARIANT_CHILD_TYPE_RESOURCE_VALUE,
             Self::RootPropFuncs => VARIANT_CHILD_TYPE_ROOT_PROP_FUNCS,
             Self::SecretDefinition => VARIANT_CHILD_TYPE_SECRET_DEFINITION,
@@ -81,6 +89,7 @@ impl NameStr for SchemaVariantChildNode {
             Self::AuthFuncs => VARIANT_CHILD_TYPE_AUTH_FUNCS,
             Self::Domain => VARIANT_CHILD_TYPE_DOMAIN,
             Self::LeafFunctions => VARIANT_CHILD_TYPE_LEAF_FUNCTIONS,
+            Self::ReconciliationFuncs => VARIANT_CHILD_TYPE_RECONCILIATION_FUNCS,
             Self::ResourceValue => VARIANT_CHILD_TYPE_RESOURCE_VALUE,
             Self::RootPropFuncs => VARIANT_CHILD_TYPE_ROOT_PROP_FUNCS,
             Self::SecretDefinition => VARIANT_CHILD_TYPE_SECRET_DEFINITION,
@@ -109,7 +118,9 @@ impl ReadBytes for SchemaVariantChildNode {
             VARIANT_CHILD_TYPE_ACTION_FUNCS => Self::ActionFuncs,
             VARIANT_CHILD_TYPE_AUTH_FUNCS => Self::AuthFuncs,
             VARIANT_CHILD_TYPE_DOMAIN => Self::Domain,
+            VARIANT_CHILD_TYPE_IMPORT_FUNCS => Self::ImportFuncs,
             VARIANT_CHILD_TYPE_LEAF_FUNCTIONS => Self::LeafFunctions,
+            VARIANT_CHILD_TYPE_RECONCILIATION_FUNCS => Self::ReconciliationFuncs,
             VARIANT_CHILD_TYPE_RESOURCE_VALUE => Self::ResourceValue,
             VARIANT_CHILD_TYPE_SI_PROP_FUNCS => Self::SiPropFuncs,
             VARIANT_CHILD_TYPE_ROOT_PROP_FUNCS => Self::RootPropFuncs,
@@ -152,6 +163,26 @@ impl NodeChild for SchemaVariantChild {
                     })
                     .collect(),
             ),
+            Self::ImportFuncs(funcs) => NodeWithChildren::new(
+                NodeKind::Tree,
+                Self::NodeType::SchemaVariantChild(SchemaVariantChildNode::ImportFuncs),
+                funcs
+                    .iter()
+                    .map(|func| {
+                        Box::new(func.clone()) as Box<dyn NodeChild<NodeType = Self::NodeType>>
+                    })
+                    .collect(),
+            ),
+            Self::ReconciliationFuncs(funcs) => NodeWithChildren::new(
+                NodeKind::Tree,
+                Self::NodeType::SchemaVariantChild(SchemaVariantChildNode::ReconciliationFuncs),
+                funcs
+                    .iter()
+                    .map(|func| {
+                        Box::new(func.clone()) as Box<dyn NodeChild<NodeType = Self::NodeType>>
+                    })
+                    .collect(),
+            ),
             Self::Domain(domain) => {
                 let domain =
                     Box::new(domain.clone()) as Box<dyn NodeChild<NodeType = Self::NodeType>>;
@@ -7,7 +7,8 @@ use crate::{
     node::PkgNode,
     spec::{
         FuncArgumentKind, FuncArgumentSpec, FuncSpec, FuncSpecBackendKind,
-        FuncSpecBackendResponseType, FuncSpecData,
+        FuncSpecBackendResponseType, FuncSpecData, FuncTestCaseExpectationSpec,
+        FuncTestCaseInputSpec, FuncTestCaseSpec,
     },
 };
 
@@ -96,6 +97,89 @@ impl<'a> TryFrom<SiPkgFuncArgument<'a>> for FuncArgumentSpec {
     }
 }
 
+#[derive(Clone, Debug)]
+pub struct SiPkgFuncTestCase<'a> {
+    name: String,
+    input: FuncTestCaseInputSpec,
+    expectation: FuncTestCaseExpectationSpec,
+    unique_id: Option<String>,
+    deleted: bool,
+
+    hash: Hash,
+    source: Source<'a>,
+}
+
+impl<'a> SiPkgFuncTestCase<'a> {
+    fn from_graph(
+        graph: &'a Graph<HashedNode<PkgNode>, ()>,
+        node_idx: NodeIndex,
+    ) -> PkgResult<Self> {
+        let hashed_node = &graph[node_idx];
+        let node = match hashed_node.inner() {
+            PkgNode::FuncTestCase(node) => node.clone(),
+            unexpected => {
+                return Err(SiPkgError::UnexpectedPkgNodeType(
+                    PkgNode::FUNC_TEST_CASE_KIND_STR,
+                    unexpected.node_kind_str(),
+                ))
+            }
+        };
+
+        Ok(Self {
+            name: node.name,
+            input: node.input,
+            expectation: node.expectation,
+            unique_id: node.unique_id,
+            deleted: node.deleted,
+
+            hash: hashed_node.hash(),
+            source: Source::new(graph, node_idx),
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn input(&self) -> &FuncTestCaseInputSpec {
+        &self.input
+    }
+
+    pub fn expectation(&self) -> &FuncTestCaseExpectationSpec {
+        &self.expectation
+    }
+
+    pub fn unique_id(&self) -> Option<&str> {
+        self.unique_id.as_deref()
+    }
+
+    pub fn deleted(&self) -> bool {
+        self.deleted
+    }
+
+    pub fn hash(&self) -> Hash {
+        self.hash
+    }
+
+    pub fn source(&self) -> &Source<'a> {
+        &self.source
+    }
+}
+
+impl<'a> TryFrom<SiPkgFuncTestCase<'a>> for FuncTestCaseSpec {
+    type Error = SiPkgError;
+
+    fn try_from(value: SiPkgFuncTestCase<'a>) -> Result<Self, Self::Error> {
+        Ok(FuncTestCaseSpec::builder()
+            .name(value.name)
+            .input(value.input)
+            .expectation(value.expectation)
+            .unique_id(value.unique_id)
+            .deleted(value.deleted)
+            .build()?)
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct SiPkgFuncData {
     name: String,
@@ -203,12 +287,30 @@ impl<'a> SiPkgFunc<'a> {
             .graph
             .neighbors_directed(self.source.node_idx, Outgoing)
         {
-            arguments.push(SiPkgFuncArgument::from_graph(self.source.graph, idx)?);
+            // Test cases are children of the func as well
+            if let PkgNode::FuncArgument(_) = self.source.graph[idx].inner() {
+                arguments.push(SiPkgFuncArgument::from_graph(self.source.graph, idx)?);
+            }
         }
 
         Ok(arguments)
     }
 
+    pub fn test_cases(&self) -> PkgResult<Vec<SiPkgFuncTestCase>> {
+        let mut test_cases = vec![];
+        for idx in self
+            .source
+            .graph
+            .neighbors_directed(self.source.node_idx, Outgoing)
+        {
+            if let PkgNode::FuncTestCase(_) = self.source.graph[idx].inner() {
+                test_cases.push(SiPkgFuncTestCase::from_graph(self.source.graph, idx)?);
+            }
+        }
+
+        Ok(test_cases)
+    }
+
     pub fn name(&self) -> &str {
         self.name.as_ref()
     }
@@ -320,6 +422,10 @@ impl<'a> TryFrom<SiPkgFunc<'a>> for FuncSpec {
             builder.argument(argument.try_into()?);
         }
 
+        for test_case in value.test_cases()? {
+            builder.test_case(test_case.try_into()?);
+        }
+
         Ok(builder.build()?)
     }
 }
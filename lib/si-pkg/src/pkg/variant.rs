@@ -15,7 +15,8 @@ use crate::{
     node::{PkgNode, PropChildNode, SchemaVariantChildNode},
     AttrFuncInputSpec, MapKeyFuncSpec, PropSpec, PropSpecBuilder, PropSpecKind, SchemaVariantSpec,
     SchemaVariantSpecBuilder, SchemaVariantSpecComponentType, SchemaVariantSpecData,
-    SchemaVariantSpecPropRoot, SiPkgAuthFunc, SiPkgRootPropFunc,
+    SchemaVariantSpecPropRoot, SiPkgAuthFunc, SiPkgImportFunc, SiPkgReconciliationFunc,
+    SiPkgRootPropFunc,
 };
 
 #[derive(Clone, Debug)]
@@ -169,6 +170,16 @@ impl<'a> SiPkgSchemaVariant<'a> {
         SiPkgActionFunc
     );
     impl_variant_children_from_graph!(auth_funcs, SchemaVariantChildNode::AuthFuncs, SiPkgAuthFunc);
+    impl_variant_children_from_graph!(
+        import_funcs,
+        SchemaVariantChildNode::ImportFuncs,
+        SiPkgImportFunc
+    );
+    impl_variant_children_from_graph!(
+        reconciliation_funcs,
+        SchemaVariantChildNode::ReconciliationFuncs,
+        SiPkgReconciliationFunc
+    );
     impl_variant_children_from_graph!(
         si_prop_funcs,
         SchemaVariantChildNode::SiPropFuncs,
@@ -433,6 +444,14 @@ impl<'a> SiPkgSchemaVariant<'a> {
             builder.action_func(action_func.try_into()?);
         }
 
+        for import_func in self.import_funcs()? {
+            builder.import_func(import_func.try_into()?);
+        }
+
+        for reconciliation_func in self.reconciliation_funcs()? {
+            builder.reconciliation_func(reconciliation_func.try_into()?);
+        }
+
         for socket in self.sockets()? {
             builder.socket(socket.try_into()?);
         }
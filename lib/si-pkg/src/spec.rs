@@ -11,10 +11,12 @@ mod change_set;
 mod component;
 mod edge;
 mod func;
+mod import_func;
 mod leaf_function;
 mod map_key_func;
 mod position;
 mod prop;
+mod reconciliation_func;
 mod root_prop_func;
 mod schema;
 mod si_prop_func;
@@ -23,8 +25,9 @@ mod variant;
 
 pub use {
     action_func::*, attr_func_input::*, attribute_value::*, authentication_func::*, change_set::*,
-    component::*, edge::*, func::*, leaf_function::*, map_key_func::*, position::*, prop::*,
-    root_prop_func::*, schema::*, si_prop_func::*, socket::*, variant::*,
+    component::*, edge::*, func::*, import_func::*, leaf_function::*, map_key_func::*, position::*,
+    prop::*, reconciliation_func::*, root_prop_func::*, schema::*, si_prop_func::*, socket::*,
+    variant::*,
 };
 
 use super::SiPkgKind;
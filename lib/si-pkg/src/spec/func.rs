@@ -57,25 +57,71 @@ impl FuncArgumentSpec {
     }
 }
 
+#[remain::sorted]
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(tag = "kind", content = "value", rename_all = "camelCase")]
+pub enum FuncTestCaseInputSpec {
+    Args(serde_json::Value),
+    Component(serde_json::Value),
+}
+
+#[remain::sorted]
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(tag = "kind", content = "value", rename_all = "camelCase")]
+pub enum FuncTestCaseExpectationSpec {
+    Contains(serde_json::Value),
+    Equals(serde_json::Value),
+    Succeeds,
+}
+
+#[derive(Builder, Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[builder(build_fn(error = "SpecError"))]
+pub struct FuncTestCaseSpec {
+    #[builder(setter(into))]
+    pub name: String,
+    #[builder(setter(into))]
+    pub input: FuncTestCaseInputSpec,
+    #[builder(setter(into))]
+    pub expectation: FuncTestCaseExpectationSpec,
+    #[builder(setter(into), default)]
+    #[serde(default)]
+    pub unique_id: Option<String>,
+    #[builder(setter(into), default)]
+    #[serde(default)]
+    pub deleted: bool,
+}
+
+impl FuncTestCaseSpec {
+    pub fn builder() -> FuncTestCaseSpecBuilder {
+        FuncTestCaseSpecBuilder::default()
+    }
+}
+
 #[remain::sorted]
 #[derive(Clone, Copy, Debug, Deserialize, Serialize, AsRefStr, Display, EnumIter, EnumString)]
 #[serde(rename_all = "camelCase")]
 pub enum FuncSpecBackendKind {
     Array,
+    Base64Encode,
     Boolean,
     Diff,
     Identity,
     Integer,
+    JoinArray,
     JsAction,
     JsAttribute,
     JsAuthentication,
     Json,
+    JsonPointer,
     JsReconciliation,
     JsSchemaVariantDefinition,
     JsValidation,
     Map,
+    MapFromList,
     Object,
     String,
+    StringTemplate,
     Unset,
     Validation,
 }
@@ -168,6 +214,10 @@ pub struct FuncSpec {
 
     #[builder(setter(each(name = "argument"), into), default)]
     pub arguments: Vec<FuncArgumentSpec>,
+
+    #[builder(setter(each(name = "test_case"), into), default)]
+    #[serde(default)]
+    pub test_cases: Vec<FuncTestCaseSpec>,
 }
 
 impl FuncSpecBuilder {
@@ -11,8 +11,8 @@ use crate::{
 };
 
 use super::{
-    ActionFuncSpec, LeafFunctionSpec, PropSpec, PropSpecData, PropSpecWidgetKind, RootPropFuncSpec,
-    SiPropFuncSpec, SocketSpec, SpecError,
+    ActionFuncSpec, ImportFuncSpec, LeafFunctionSpec, PropSpec, PropSpecData, PropSpecWidgetKind,
+    ReconciliationFuncSpec, RootPropFuncSpec, SiPropFuncSpec, SocketSpec, SpecError,
 };
 
 #[remain::sorted]
@@ -167,6 +167,14 @@ pub struct SchemaVariantSpec {
     #[builder(setter(each(name = "auth_func"), into), default)]
     pub auth_funcs: Vec<AuthenticationFuncSpec>,
 
+    #[builder(setter(each(name = "import_func"), into), default)]
+    #[serde(default)]
+    pub import_funcs: Vec<ImportFuncSpec>,
+
+    #[builder(setter(each(name = "reconciliation_func"), into), default)]
+    #[serde(default)]
+    pub reconciliation_funcs: Vec<ReconciliationFuncSpec>,
+
     #[builder(setter(each(name = "leaf_function"), into), default)]
     pub leaf_functions: Vec<LeafFunctionSpec>,
 
@@ -451,6 +459,8 @@ impl SchemaVariantSpec {
         // The inputs to these prototypes will always be available so we just copy
         schema_variant_builder.action_funcs = Some(other_spec.action_funcs.clone());
         schema_variant_builder.auth_funcs = Some(other_spec.auth_funcs.clone());
+        schema_variant_builder.import_funcs = Some(other_spec.import_funcs.clone());
+        schema_variant_builder.reconciliation_funcs = Some(other_spec.reconciliation_funcs.clone());
         schema_variant_builder.leaf_functions = Some(other_spec.leaf_functions.clone());
 
         // These are fake root props that include all the "root prop children"
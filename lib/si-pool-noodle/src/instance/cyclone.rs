@@ -2,7 +2,7 @@
 
 pub use local_http::{
     LocalHttpInstance, LocalHttpInstanceError, LocalHttpInstanceSpec, LocalHttpInstanceSpecBuilder,
-    LocalHttpSocketStrategy,
+    LocalHttpRuntimeStrategy, LocalHttpSocketStrategy,
 };
 pub use local_uds::{
     LocalUdsInstance, LocalUdsInstanceError, LocalUdsInstanceSpec, LocalUdsInstanceSpecBuilder,
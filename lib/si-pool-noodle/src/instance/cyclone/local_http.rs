@@ -50,6 +50,9 @@ pub enum LocalHttpInstanceError {
     /// Instance has exhausted its predefined request count.
     #[error("no remaining requests, cyclone server is considered unhealthy")]
     NoRemainingRequests,
+    /// Remote Cyclone server did not become ready with retries.
+    #[error("timeout while waiting for remote cyclone server to become ready")]
+    RemoteReadinessTimeout,
     /// Error when binding local socket.
     #[error("error when binding local socket")]
     SocketBind(#[source] io::Error),
@@ -69,13 +72,16 @@ pub enum LocalHttpInstanceError {
 
 type Result<T> = result::Result<T, LocalHttpInstanceError>;
 
-/// A local Cyclone [`Instance`], managed as a spawned child process, communicating over HTTP.
+/// A Cyclone [`Instance`] communicating over HTTP, either managed as a spawned child process or
+/// running as a long-lived server elsewhere (see [`LocalHttpRuntimeStrategy`]).
 #[derive(Debug)]
 pub struct LocalHttpInstance {
     client: HttpClient,
     limit_requests: Option<u32>,
-    child: Child,
-    watch_shutdown_tx: oneshot::Sender<()>,
+    // A remote Cyclone server is not ours to manage, so there is no child process or watch
+    // session to keep it alive.
+    child: Option<Child>,
+    watch_shutdown_tx: Option<oneshot::Sender<()>>,
 }
 
 #[async_trait]
@@ -84,7 +90,11 @@ impl Instance for LocalHttpInstance {
     type Error = LocalHttpInstanceError;
 
     async fn terminate(&mut self) -> result::Result<(), Self::Error> {
-        process::child_shutdown(&mut self.child, Some(process::Signal::SIGTERM), None).await?;
+        // A remote Cyclone server outlives any one instance connected to it, so only a spawned
+        // child process is ours to terminate
+        if let Some(child) = self.child.as_mut() {
+            process::child_shutdown(child, Some(process::Signal::SIGTERM), None).await?;
+        }
 
         Ok(())
     }
@@ -223,6 +233,21 @@ impl CycloneClient<TcpStream> for LocalHttpInstance {
 }
 
 impl LocalHttpInstance {
+    /// Creates an instance for a long-lived Cyclone server which is managed elsewhere.
+    ///
+    /// The client is cheap to clone, so any number of instances may share one client (see
+    /// [`LocalHttpInstanceSpec::connect_remote`]) to make concurrent requests to the same server.
+    pub fn remote(client: HttpClient) -> Self {
+        // A long-lived server handles many requests over its lifetime, so there is no request
+        // limit to count down
+        Self {
+            client,
+            limit_requests: None,
+            child: None,
+            watch_shutdown_tx: None,
+        }
+    }
+
     async fn ensure_healthy_client(&mut self) -> Result<()> {
         if !self.is_watch_shutdown_open() {
             return Err(LocalHttpInstanceError::WatchShutDown);
@@ -242,7 +267,10 @@ impl LocalHttpInstance {
     }
 
     fn is_watch_shutdown_open(&self) -> bool {
-        !self.watch_shutdown_tx.is_closed()
+        match &self.watch_shutdown_tx {
+            Some(watch_shutdown_tx) => !watch_shutdown_tx.is_closed(),
+            None => true,
+        }
     }
 
     fn count_request(&mut self) {
@@ -256,17 +284,21 @@ impl LocalHttpInstance {
 #[derive(Builder, Clone, Debug, Eq, PartialEq)]
 pub struct LocalHttpInstanceSpec {
     /// Canonical path to the `cyclone` program.
-    #[builder(try_setter, setter(into))]
+    #[builder(try_setter, setter(into), default)]
     cyclone_cmd_path: CanonicalCommand,
 
     /// Canonical path to the language server program.
-    #[builder(try_setter, setter(into))]
+    #[builder(try_setter, setter(into), default)]
     lang_server_cmd_path: CanonicalCommand,
 
     /// Socket strategy for a spawned Cyclone server.
     #[builder(default)]
     socket_strategy: LocalHttpSocketStrategy,
 
+    /// Runtime strategy for a Cyclone server.
+    #[builder(default)]
+    runtime_strategy: LocalHttpRuntimeStrategy,
+
     /// Sets the watch timeout value for a spawned Cyclone server.
     #[builder(setter(into, strip_option), default)]
     watch_timeout: Option<Duration>,
@@ -286,6 +318,10 @@ pub struct LocalHttpInstanceSpec {
     /// Enables the `action` execution endpoint for a spawned Cyclone server.
     #[builder(private, setter(name = "_action"), default = "false")]
     action: bool,
+
+    /// Size of the pool to configure for the spec.
+    #[builder(setter(into), default = "10")]
+    pub pool_size: u16,
 }
 
 #[async_trait]
@@ -303,11 +339,22 @@ impl Spec for LocalHttpInstanceSpec {
         Ok(())
     }
     async fn spawn(&self, _id: u32) -> result::Result<Self::Instance, Self::Error> {
+        match &self.runtime_strategy {
+            LocalHttpRuntimeStrategy::LocalProcess => self.spawn_local_process().await,
+            LocalHttpRuntimeStrategy::Remote(addr) => Self::connect_remote(addr)
+                .await
+                .map(LocalHttpInstance::remote),
+        }
+    }
+}
+
+impl LocalHttpInstanceSpec {
+    async fn spawn_local_process(&self) -> Result<LocalHttpInstance> {
         let socket_addr = socket_addr_from(&self.socket_strategy).await?;
         let mut cmd = self.build_command(&socket_addr);
 
         debug!("spawning child process; cmd={:?}", &cmd);
-        let child = cmd.spawn().map_err(Self::Error::ChildSpawn)?;
+        let child = cmd.spawn().map_err(LocalHttpInstanceError::ChildSpawn)?;
 
         let mut client = Client::http(socket_addr)?;
 
@@ -322,7 +369,7 @@ impl Spec for LocalHttpInstanceSpec {
                     break watch;
                 }
                 if retries < 1 {
-                    return Err(Self::Error::WatchInitTimeout);
+                    return Err(LocalHttpInstanceError::WatchInitTimeout);
                 }
                 retries -= 1;
                 time::sleep(Duration::from_millis(64)).await;
@@ -335,22 +382,53 @@ impl Spec for LocalHttpInstanceSpec {
         watch_progress
             .next()
             .await
-            .ok_or(Self::Error::WatchClosed)??;
+            .ok_or(LocalHttpInstanceError::WatchClosed)??;
 
         let (watch_shutdown_tx, watch_shutdown_rx) = oneshot::channel();
         // Spawn a task to keep the watch session open until we shut it down
         tokio::spawn(watch_task(watch_progress, watch_shutdown_rx));
 
-        Ok(Self::Instance {
+        Ok(LocalHttpInstance {
             client,
             limit_requests: self.limit_requests,
-            child,
-            watch_shutdown_tx,
+            child: Some(child),
+            watch_shutdown_tx: Some(watch_shutdown_tx),
         })
     }
-}
 
-impl LocalHttpInstanceSpec {
+    /// Returns the address of the Cyclone server when using the
+    /// [`LocalHttpRuntimeStrategy::Remote`] runtime strategy.
+    pub fn remote_addr(&self) -> Option<&str> {
+        match &self.runtime_strategy {
+            LocalHttpRuntimeStrategy::LocalProcess => None,
+            LocalHttpRuntimeStrategy::Remote(addr) => Some(addr.as_str()),
+        }
+    }
+
+    /// Connects to a long-lived Cyclone server at the given address, waiting for it to become
+    /// ready.
+    pub async fn connect_remote(addr: &str) -> Result<HttpClient> {
+        let mut client = Client::http(addr)?;
+
+        // The remote server may be restarting or still booting, so we will retry for a period
+        // before giving up and assuming that it is unavailable.
+        let mut retries = 30;
+        loop {
+            trace!("calling client.readiness()");
+            if let Ok(ReadinessStatus::Ready) = client.readiness().await {
+                trace!("remote cyclone server is ready");
+                break;
+            }
+            if retries < 1 {
+                return Err(LocalHttpInstanceError::RemoteReadinessTimeout);
+            }
+            retries -= 1;
+            time::sleep(Duration::from_millis(64)).await;
+        }
+
+        Ok(client)
+    }
+
     fn build_command(&self, socket: &SocketAddr) -> Command {
         let mut cmd = Command::new(&self.cyclone_cmd_path);
         cmd.arg("--bind-addr")
@@ -415,6 +493,23 @@ impl LocalHttpInstanceSpecBuilder {
     }
 }
 
+/// Runtime strategy when spawning [`Instance`]s.
+#[remain::sorted]
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum LocalHttpRuntimeStrategy {
+    /// Run processes on the local machine
+    LocalProcess,
+    /// Connect to a long-lived Cyclone server at the given address (e.g. `"cyclone:5157"`), which
+    /// is managed outside of the pool
+    Remote(String),
+}
+
+impl Default for LocalHttpRuntimeStrategy {
+    fn default() -> Self {
+        Self::LocalProcess
+    }
+}
+
 /// Socket strategy when spawning [`Instance`]s using a TCP socket.
 #[remain::sorted]
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
@@ -333,6 +333,16 @@ pub struct LocalUdsInstanceSpec {
     /// Sets the timeout for connecting to firecracker
     #[builder(setter(into), default = "10")]
     connect_timeout: u64,
+
+    /// Sets the memory limit, in megabytes, for each function run by a locally spawned Cyclone
+    /// server.
+    #[builder(setter(into), default)]
+    limit_memory_mb: Option<u64>,
+
+    /// Sets the CPU time limit, in seconds, for each function run by a locally spawned Cyclone
+    /// server.
+    #[builder(setter(into), default)]
+    limit_cpu_secs: Option<u64>,
 }
 
 #[async_trait]
@@ -584,6 +594,13 @@ impl LocalProcessRuntime {
         if spec.action {
             cmd.arg("--enable-action-run");
         }
+        if let Some(limit_memory_mb) = spec.limit_memory_mb {
+            cmd.arg("--limit-memory-mb")
+                .arg(limit_memory_mb.to_string());
+        }
+        if let Some(limit_cpu_secs) = spec.limit_cpu_secs {
+            cmd.arg("--limit-cpu-secs").arg(limit_cpu_secs.to_string());
+        }
 
         Ok(Box::new(LocalProcessRuntime {
             cmd,
@@ -16,17 +16,19 @@
 )]
 
 pub use self::instance::{Instance, Spec};
-pub use crate::pool_noodle::PoolNoodle;
+pub use crate::pool_noodle::{PoolNoodle, PoolNoodleConfig, PoolNoodleStats};
 
-pub use cyclone_client::{ClientError, CycloneClient, ExecutionError};
+pub use cyclone_client::{
+    Client, ClientError, Connection, CycloneClient, ExecutionError, HttpClient,
+};
 
 pub use cyclone_core::{
-    ActionRunRequest, ActionRunResultSuccess, BeforeFunction, ComponentView, CycloneRequest,
-    FunctionResult, FunctionResultFailure, FunctionResultFailureError, OutputStream,
-    ProgressMessage, ReconciliationRequest, ReconciliationResultSuccess, ResolverFunctionRequest,
-    ResolverFunctionResultSuccess, ResourceStatus, SchemaVariantDefinitionRequest,
-    SchemaVariantDefinitionResultSuccess, SensitiveStrings, ValidationRequest,
-    ValidationResultSuccess,
+    ActionRunRequest, ActionRunResultSuccess, BeforeFunction, CancelExecutionRequest,
+    ComponentView, CycloneRequest, FunctionResult, FunctionResultFailure,
+    FunctionResultFailureError, OutputStream, ProgressMessage, ReconciliationRequest,
+    ReconciliationResultSuccess, ResolverFunctionRequest, ResolverFunctionResultSuccess,
+    ResourceStatus, SchemaVariantDefinitionRequest, SchemaVariantDefinitionResultSuccess,
+    SensitiveStrings, ValidationRequest, ValidationResultSuccess,
 };
 
 /// [`PoolNoodleError`] implementations.
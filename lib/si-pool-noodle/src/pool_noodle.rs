@@ -3,11 +3,14 @@ use std::fmt;
 use std::fmt::Display;
 use std::fmt::Formatter;
 use std::sync::atomic::AtomicBool;
+use std::sync::atomic::AtomicU32;
+use std::sync::atomic::AtomicU64;
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
 use telemetry_utils::metric;
 use tokio::sync::Mutex;
 use tokio::time::sleep;
+use tokio::time::Instant;
 use tracing::info;
 
 use tokio::time::Duration;
@@ -61,6 +64,48 @@ use crate::{Instance, Spec};
 
 type Result<T> = result::Result<T, PoolNoodleError>;
 
+/// Sizing bounds and autoscaling parameters for a [`PoolNoodle`].
+///
+/// The pool keeps at least `min_size` instances provisioned and grows towards `max_size` when
+/// callers are left waiting for an instance. Ready instances left unused for longer than
+/// `idle_ttl` are reaped until the pool is back down to `min_size`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct PoolNoodleConfig {
+    /// The number of instances kept provisioned at all times.
+    pub min_size: u32,
+    /// The most instances the pool will ever provision.
+    pub max_size: u32,
+    /// How long a ready instance may sit unused before it is reaped.
+    pub idle_ttl: Duration,
+    /// Scale up when a caller waits at least this long for an instance.
+    pub scale_up_wait_threshold: Duration,
+    /// How often the pool re-evaluates its size.
+    pub scale_interval: Duration,
+}
+
+impl PoolNoodleConfig {
+    /// A pool which always keeps exactly `size` instances provisioned.
+    pub fn fixed(size: u32) -> Self {
+        Self {
+            min_size: size,
+            max_size: size,
+            ..Default::default()
+        }
+    }
+}
+
+impl Default for PoolNoodleConfig {
+    fn default() -> Self {
+        Self {
+            min_size: 5,
+            max_size: 5,
+            idle_ttl: Duration::from_secs(5 * 60),
+            scale_up_wait_threshold: Duration::from_millis(100),
+            scale_interval: Duration::from_millis(500),
+        }
+    }
+}
+
 /// Pool Noodle is a tool for ensuring that we maintain a bare minimum number of Firecracker Jails
 /// for function execution. We wrap it in an Arc Mutex so we can update the queues it manages
 /// across threads.
@@ -79,28 +124,49 @@ where
     I: Instance<SpecBuilder = B, Error = E> + Send + Sync + 'static,
     E: Send + Display + 'static,
 {
-    /// Creates a new instance of PoolNoodle
+    /// Creates a new instance of PoolNoodle with a fixed number of instances.
     pub fn new(pool_size: u32, spec: S, shutdown_rx: tokio::sync::broadcast::Receiver<()>) -> Self {
+        Self::with_config(PoolNoodleConfig::fixed(pool_size), spec, shutdown_rx)
+    }
+
+    /// Creates a new instance of PoolNoodle which scales between the bounds of the given config.
+    pub fn with_config(
+        config: PoolNoodleConfig,
+        spec: S,
+        shutdown_rx: tokio::sync::broadcast::Receiver<()>,
+    ) -> Self {
+        let max_size = config.max_size;
+        let min_size = config.min_size.min(max_size);
+        let config = PoolNoodleConfig { min_size, ..config };
+
         // start by cleaning jails just to make sure
-        let to_be_cleaned = ArrayQueue::new(pool_size as usize);
+        let to_be_cleaned = ArrayQueue::new(max_size as usize);
         let pool = PoolNoodle(Arc::new(PoolNoodleInner {
-            pool_size,
+            config,
             spec,
-            dropped: ArrayQueue::new(pool_size as usize),
-            ready: ArrayQueue::new(pool_size as usize),
+            dropped: ArrayQueue::new(max_size as usize),
+            ready: ArrayQueue::new(max_size as usize),
             to_be_cleaned,
-            unprepared: ArrayQueue::new(pool_size as usize),
+            unprepared: ArrayQueue::new(max_size as usize),
+            parked: ArrayQueue::new(max_size as usize),
+            pending_reaps: AtomicU32::new(0),
+            waiting: AtomicU32::new(0),
+            max_wait_ms: AtomicU64::new(0),
             shutdown_rx: shutdown_rx.into(),
         }));
-        for n in 1..=pool_size {
+        for n in 1..=min_size {
             let me = Arc::clone(&pool.0);
             Self::push_to_clean(me, n);
         }
+        for n in (min_size + 1)..=max_size {
+            let me = Arc::clone(&pool.0);
+            Self::push_to_parked(me, n);
+        }
         pool
     }
 
-    /// Gets the current pool stats from the inner struct
-    /// Only used for tests
+    /// Gets the current pool stats from the inner struct. The same figures are continuously
+    /// exported as `pool_noodle.*` metrics.
     pub async fn stats(&self) -> PoolNoodleStats {
         self.0.stats().await
     }
@@ -118,6 +184,8 @@ where
 
         let _ = tokio::spawn(Self::handle_shutdown(me.clone(), stop.clone()));
 
+        let _ = tokio::spawn(Self::handle_scale(me.clone(), stop.clone()));
+
         for _ in 0..10 {
             let _ = tokio::spawn(Self::handle_prepare(me.clone(), stop.clone()));
 
@@ -139,6 +207,78 @@ where
         stop.store(true, Ordering::Relaxed);
     }
 
+    async fn handle_scale(me: Arc<PoolNoodleInner<I, S>>, stop: Arc<AtomicBool>) {
+        debug!("PoolNoodle: starting scale handler...");
+
+        while !stop.load(Ordering::Relaxed) {
+            sleep(me.config.scale_interval).await;
+
+            let waiting = me.waiting.load(Ordering::Relaxed) as usize;
+            let max_wait = Duration::from_millis(me.max_wait_ms.swap(0, Ordering::Relaxed));
+            // Instances already on their way to becoming ready will serve some of the waiters
+            let in_flight = me.to_be_cleaned.len() + me.unprepared.len();
+
+            let wanted = if waiting > in_flight && me.ready.is_empty() {
+                waiting - in_flight
+            } else if max_wait >= me.config.scale_up_wait_threshold && in_flight == 0 {
+                1
+            } else {
+                0
+            };
+
+            if wanted > 0 {
+                Self::scale_up(me.clone(), wanted);
+            } else {
+                Self::reap_idle(me.clone());
+            }
+        }
+        debug!("PoolNoodle: received graceful shutdown signal, shutting down...");
+    }
+
+    /// Provisions up to `count` parked instances.
+    fn scale_up(me: Arc<PoolNoodleInner<I, S>>, count: usize) {
+        let mut scaled = 0;
+        while scaled < count {
+            match Self::pop_from_parked(me.clone()) {
+                Some(id) => {
+                    debug!("PoolNoodle: scaling up with instance {}", id);
+                    Self::push_to_clean(me.clone(), id);
+                    scaled += 1;
+                }
+                None => break,
+            }
+        }
+        if scaled > 0 {
+            metric!(monotonic_counter.pool_noodle.scaled_up = scaled);
+        }
+    }
+
+    /// Reaps ready instances which have been idle for longer than the TTL, down to the minimum
+    /// pool size.
+    fn reap_idle(me: Arc<PoolNoodleInner<I, S>>) {
+        while me.provisioned() > me.config.min_size {
+            let Some(ready) = Self::pop_from_ready(me.clone()) else {
+                break;
+            };
+            if ready.since.elapsed() < me.config.idle_ttl {
+                // The oldest ready instance is still warm, so all the others are too
+                Self::push_to_ready_since(me.clone(), ready.instance, ready.since);
+                break;
+            }
+
+            debug!("PoolNoodle: reaping idle instance: {}", ready.instance.id());
+            // The instance is terminated and cleaned as usual, after which an id is parked
+            // rather than prepared again
+            me.pending_reaps.fetch_add(1, Ordering::Relaxed);
+            if let Err(i) = me.dropped.push(ready.instance) {
+                warn!("PoolNoodle: failed to push instance to dropped: {}", i.id());
+                me.pending_reaps.fetch_sub(1, Ordering::Relaxed);
+                break;
+            }
+            metric!(monotonic_counter.pool_noodle.reaped = 1);
+        }
+    }
+
     async fn handle_prepare(me: Arc<PoolNoodleInner<I, S>>, stop: Arc<AtomicBool>) {
         debug!("PoolNoodle: starting prepare handler...");
 
@@ -182,7 +322,11 @@ where
                 match PoolNoodleInner::clean(id, &me.spec).await {
                     Ok(_) => {
                         debug!("PoolNoodle: instance cleaned: {}", id);
-                        Self::push_to_unprepared(me.clone(), id)
+                        if me.take_pending_reap() {
+                            Self::push_to_parked(me.clone(), id)
+                        } else {
+                            Self::push_to_unprepared(me.clone(), id)
+                        }
                     }
                     Err(e) => {
                         warn!("PoolNoodle: failed to clean instance: {}", id);
@@ -233,13 +377,20 @@ where
         })?
     }
 
-    fn pop_from_ready(me: Arc<PoolNoodleInner<I, S>>) -> Option<I> {
+    fn pop_from_ready(me: Arc<PoolNoodleInner<I, S>>) -> Option<ReadyInstance<I>> {
         me.ready.pop().map(|id| {
             metric!(counter.pool_noodle.ready = -1);
             Some(id)
         })?
     }
 
+    fn pop_from_parked(me: Arc<PoolNoodleInner<I, S>>) -> Option<u32> {
+        me.parked.pop().map(|id| {
+            metric!(counter.pool_noodle.parked = -1);
+            Some(id)
+        })?
+    }
+
     fn push_to_clean(me: Arc<PoolNoodleInner<I, S>>, id: u32) {
         if let Err(e) = me.to_be_cleaned.push(id) {
             warn!(
@@ -252,12 +403,27 @@ where
     }
 
     fn push_to_ready(me: Arc<PoolNoodleInner<I, S>>, instance: I) {
-        if let Err(i) = me.ready.push(instance) {
-            warn!("PoolNoodle: failed to push instance to ready: {}", i.id());
+        Self::push_to_ready_since(me, instance, Instant::now());
+    }
+
+    fn push_to_ready_since(me: Arc<PoolNoodleInner<I, S>>, instance: I, since: Instant) {
+        if let Err(i) = me.ready.push(ReadyInstance { instance, since }) {
+            warn!(
+                "PoolNoodle: failed to push instance to ready: {}",
+                i.instance.id()
+            );
         }
         metric!(counter.pool_noodle.ready = 1);
     }
 
+    fn push_to_parked(me: Arc<PoolNoodleInner<I, S>>, id: u32) {
+        if let Err(e) = me.parked.push(id) {
+            warn!("PoolNoodle: failed to push instance to parked: {}", id);
+            warn!("{:?}", e);
+        }
+        metric!(counter.pool_noodle.parked = 1);
+    }
+
     fn push_to_unprepared(me: Arc<PoolNoodleInner<I, S>>, id: u32) {
         if let Err(e) = me.unprepared.push(id) {
             warn!("PoolNoodle: failed to push instance to unprepared: {}", id);
@@ -272,13 +438,29 @@ where
     pub async fn get(&mut self) -> Result<LifeGuard<I, S>> {
         let me = Arc::clone(&self.0);
 
+        let started = Instant::now();
+        me.waiting.fetch_add(1, Ordering::Relaxed);
+        metric!(counter.pool_noodle.waiting = 1);
+
+        let result = Self::wait_for_ready(me.clone()).await;
+
+        me.waiting.fetch_sub(1, Ordering::Relaxed);
+        metric!(counter.pool_noodle.waiting = -1);
+        let waited_ms = started.elapsed().as_millis() as u64;
+        me.max_wait_ms.fetch_max(waited_ms, Ordering::Relaxed);
+        metric!(histogram.pool_noodle.get_wait_ms = waited_ms);
+
+        result
+    }
+
+    async fn wait_for_ready(me: Arc<PoolNoodleInner<I, S>>) -> Result<LifeGuard<I, S>> {
         let max_retries = 6000; // Set the maximum number of retries
         let mut retries = 0;
         loop {
             if retries >= max_retries {
                 return Err(PoolNoodleError::ExecutionPoolStarved);
             }
-            if let Some(mut instance) = Self::pop_from_ready(me.clone()) {
+            if let Some(ReadyInstance { mut instance, .. }) = Self::pop_from_ready(me.clone()) {
                 debug!("PoolNoodle: got instance: {}", instance.id());
                 // Try to ensure the item is healthy
                 match &mut instance.ensure_healthy().await {
@@ -331,17 +513,32 @@ where
     }
 }
 
+#[derive(Debug)]
+struct ReadyInstance<I> {
+    instance: I,
+    /// When the instance became ready, used to reap instances which sit idle
+    since: Instant,
+}
+
 #[derive(Debug)]
 struct PoolNoodleInner<I, S>
 where
     S: Spec,
 {
-    pool_size: u32,
+    config: PoolNoodleConfig,
     spec: S,
     dropped: ArrayQueue<I>,
-    ready: ArrayQueue<I>,
+    ready: ArrayQueue<ReadyInstance<I>>,
     to_be_cleaned: ArrayQueue<u32>,
     unprepared: ArrayQueue<u32>,
+    /// Ids which are not currently provisioned, available for scaling up
+    parked: ArrayQueue<u32>,
+    /// Reaped instances which should be parked, rather than prepared, once cleaned
+    pending_reaps: AtomicU32,
+    /// Callers currently waiting in [`PoolNoodle::get`]
+    waiting: AtomicU32,
+    /// The longest wait in [`PoolNoodle::get`] since the pool was last scaled
+    max_wait_ms: AtomicU64,
     shutdown_rx: Mutex<tokio::sync::broadcast::Receiver<()>>,
 }
 
@@ -378,14 +575,33 @@ where
             .map_err(|e| PoolNoodleError::InstanceTerminate(e.to_string()))
     }
 
+    /// The number of instances which are provisioned or on their way to being provisioned
+    fn provisioned(&self) -> u32 {
+        self.config
+            .max_size
+            .saturating_sub(self.parked.len() as u32)
+            .saturating_sub(self.pending_reaps.load(Ordering::Relaxed))
+    }
+
+    /// Claims one of the pending reaps, if there are any
+    fn take_pending_reap(&self) -> bool {
+        self.pending_reaps
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| n.checked_sub(1))
+            .is_ok()
+    }
+
     /// This outputs the current state of the pool
     pub async fn stats(&self) -> PoolNoodleStats {
         PoolNoodleStats {
-            pool_size: self.pool_size as usize,
+            min_size: self.config.min_size as usize,
+            max_size: self.config.max_size as usize,
+            provisioned: self.provisioned() as usize,
             dropped: self.dropped.len(),
             ready: self.ready.len(),
             to_be_cleaned: self.to_be_cleaned.len(),
             unprepared: self.unprepared.len(),
+            parked: self.parked.len(),
+            waiting: self.waiting.load(Ordering::Relaxed) as usize,
         }
     }
 }
@@ -393,8 +609,12 @@ where
 #[derive(Debug)]
 /// Gets the current stats for the pool
 pub struct PoolNoodleStats {
+    /// Number of instances the pool keeps provisioned at all times
+    pub min_size: usize,
     /// Total number of instances allowed in the pool
-    pub pool_size: usize,
+    pub max_size: usize,
+    /// Number of instances currently provisioned
+    pub provisioned: usize,
     /// Total number of instances dropped and awating to be cleaned
     pub dropped: usize,
     /// Total number of instances that have been fetched from the pool and not yet dropped
@@ -403,14 +623,26 @@ pub struct PoolNoodleStats {
     pub to_be_cleaned: usize,
     /// Total number of unclaimed instances waiting to be readied
     pub unprepared: usize,
+    /// Total number of instances not provisioned, available for scaling up
+    pub parked: usize,
+    /// Number of callers waiting for an instance
+    pub waiting: usize,
 }
 
 impl Display for PoolNoodleStats {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         write!(
             f,
-            "PoolNoodle Stats -- pool size: {}, dropped: {}, ready: {}, to be cleaned: {}, unprepared: {}",
-            self.pool_size,  self.dropped, self.ready, self.to_be_cleaned, self.unprepared
+            "PoolNoodle Stats -- min size: {}, max size: {}, provisioned: {}, dropped: {}, ready: {}, to be cleaned: {}, unprepared: {}, parked: {}, waiting: {}",
+            self.min_size,
+            self.max_size,
+            self.provisioned,
+            self.dropped,
+            self.ready,
+            self.to_be_cleaned,
+            self.unprepared,
+            self.parked,
+            self.waiting,
         )
     }
 }
@@ -574,4 +806,41 @@ mod tests {
         sleep(Duration::from_millis(1000)).await;
         assert_eq!(3, pool.stats().await.ready, "{}", pool.stats().await);
     }
+
+    #[tokio::test]
+    async fn pool_noodle_scales_between_bounds() {
+        let spec = DummyInstanceSpec {};
+        let config = PoolNoodleConfig {
+            min_size: 1,
+            max_size: 3,
+            idle_ttl: Duration::from_millis(200),
+            scale_up_wait_threshold: Duration::from_millis(10),
+            scale_interval: Duration::from_millis(50),
+        };
+
+        let (shutdown_broadcast_tx, _) = broadcast::channel(16);
+        let mut pool = PoolNoodle::with_config(config, spec, shutdown_broadcast_tx.subscribe());
+        pool.start(false).expect("failed to start");
+
+        // only the minimum is provisioned up front
+        sleep(Duration::from_millis(300)).await;
+        assert_eq!(1, pool.stats().await.ready, "{}", pool.stats().await);
+        assert_eq!(2, pool.stats().await.parked, "{}", pool.stats().await);
+
+        // waiting callers cause the pool to grow up to the maximum
+        let a = pool.get().await.expect("should be able to get an instance");
+        let b = pool.get().await.expect("should be able to get an instance");
+        let c = pool.get().await.expect("should be able to get an instance");
+        assert_eq!(3, pool.stats().await.provisioned, "{}", pool.stats().await);
+        assert_eq!(0, pool.stats().await.parked, "{}", pool.stats().await);
+        drop(a);
+        drop(b);
+        drop(c);
+
+        // idle instances are reaped back down to the minimum
+        sleep(Duration::from_millis(1500)).await;
+        assert_eq!(1, pool.stats().await.provisioned, "{}", pool.stats().await);
+        assert_eq!(2, pool.stats().await.parked, "{}", pool.stats().await);
+        assert_eq!(1, pool.stats().await.ready, "{}", pool.stats().await);
+    }
 }
@@ -14,6 +14,7 @@ use std::{
     env,
     future::{Future, IntoFuture},
     io::{self, IsTerminal},
+    net::SocketAddr,
     ops::Deref,
     pin::Pin,
     result, thread,
@@ -23,8 +24,13 @@ use tracing::Metadata;
 use tracing_subscriber::layer::Filter;
 
 use derive_builder::Builder;
+use metrics_endpoint::MetricsEndpointTask;
+use opentelemetry_otlp::MetricsExporterBuilder;
 use opentelemetry_sdk::{
-    metrics::SdkMeterProvider,
+    metrics::{
+        reader::{DefaultAggregationSelector, DefaultTemporalitySelector},
+        PeriodicReader, SdkMeterProvider,
+    },
     propagation::TraceContextPropagator,
     resource::{EnvResourceDetector, OsResourceDetector, ProcessResourceDetector},
     runtime,
@@ -54,6 +60,8 @@ use tracing_subscriber::{
 pub use telemetry::tracing;
 pub use telemetry::{ApplicationTelemetryClient, TelemetryClient};
 
+mod metrics_endpoint;
+
 pub mod prelude {
     pub use super::{ConsoleLogFormat, TelemetryConfig};
     pub use telemetry::prelude::*;
@@ -71,6 +79,10 @@ pub enum Error {
     DirectivesParse(#[from] ParseError),
     #[error("metrics error {0}")]
     Metrics(#[from] MetricsError),
+    #[error("failed to bind metrics endpoint: {0}")]
+    MetricsEndpointBind(#[source] hyper::Error),
+    #[error("prometheus error: {0}")]
+    Prometheus(#[from] prometheus::Error),
     #[error("error creating signal handler: {0}")]
     Signal(#[source] io::Error),
     #[error("failed to parse span event fmt token: {0}")]
@@ -151,6 +163,10 @@ pub struct TelemetryConfig {
 
     #[builder(default = "true")]
     signal_handlers: bool,
+
+    /// The address to serve Prometheus metrics on, if any.
+    #[builder(setter(into), default = "None")]
+    metrics_listen_addr: Option<SocketAddr>,
 }
 
 impl TelemetryConfig {
@@ -244,9 +260,23 @@ pub fn init(
     let tracing_level = default_tracing_level(&config);
     let span_events_fmt = default_span_events_fmt(&config)?;
 
-    let (subscriber, handles) = tracing_subscriber(&config, &tracing_level, span_events_fmt)?;
+    let metrics_endpoint = config
+        .metrics_listen_addr
+        .map(|listen_addr| MetricsEndpointTask::create(listen_addr, shutdown_token.clone()))
+        .transpose()?;
+
+    let (subscriber, handles) = tracing_subscriber(
+        &config,
+        &tracing_level,
+        span_events_fmt,
+        metrics_endpoint.as_ref().map(MetricsEndpointTask::registry),
+    )?;
     subscriber.try_init()?;
 
+    if let Some(metrics_endpoint) = metrics_endpoint {
+        tracker.spawn(metrics_endpoint.run());
+    }
+
     debug!(
         ?config,
         directives = TracingDirectives::from(&tracing_level).as_str(),
@@ -336,6 +366,7 @@ fn tracing_subscriber(
     config: &TelemetryConfig,
     tracing_level: &TracingLevel,
     span_events_fmt: FmtSpan,
+    prometheus_registry: Option<&prometheus::Registry>,
 ) -> Result<(impl Subscriber + Send + Sync, TelemetryHandles)> {
     let directives = TracingDirectives::from(tracing_level);
 
@@ -379,7 +410,7 @@ fn tracing_subscriber(
     };
 
     let (metrics_layer, metrics_filter_reload) = {
-        let layer = MetricsLayer::new(otel_metrics(config)?);
+        let layer = MetricsLayer::new(otel_metrics(config, prometheus_registry)?);
         let env_filter = EnvFilter::try_new(directives.as_str())?;
         let (filter, handle) = reload::Layer::new(env_filter);
         let layer = layer.with_filter(filter.and(IncludeMetricsFilter));
@@ -417,17 +448,36 @@ fn otel_tracer(config: &TelemetryConfig) -> result::Result<Tracer, TraceError> {
         .install_batch(runtime::Tokio)
 }
 
-fn otel_metrics(config: &TelemetryConfig) -> result::Result<SdkMeterProvider, MetricsError> {
-    opentelemetry_otlp::new_pipeline()
-        .metrics(opentelemetry_sdk::runtime::Tokio)
-        .with_exporter(opentelemetry_otlp::new_exporter().tonic())
+fn otel_metrics(
+    config: &TelemetryConfig,
+    prometheus_registry: Option<&prometheus::Registry>,
+) -> result::Result<SdkMeterProvider, MetricsError> {
+    let otlp_exporter = MetricsExporterBuilder::from(opentelemetry_otlp::new_exporter().tonic())
+        .build_metrics_exporter(
+            Box::new(DefaultTemporalitySelector::new()),
+            Box::new(DefaultAggregationSelector::new()),
+        )?;
+    let otlp_reader = PeriodicReader::builder(otlp_exporter, runtime::Tokio)
+        .with_interval(Duration::from_secs(1))
+        .with_timeout(Duration::from_secs(10))
+        .build();
+
+    let mut builder = SdkMeterProvider::builder()
+        .with_reader(otlp_reader)
         .with_resource(Resource::new(vec![KeyValue::new(
             "service.name",
             config.service_name,
-        )]))
-        .with_period(Duration::from_secs(1))
-        .with_timeout(Duration::from_secs(10))
-        .build()
+        )]));
+
+    // The same instruments are read by the Prometheus exporter when the endpoint is enabled
+    if let Some(registry) = prometheus_registry {
+        let prometheus_exporter = opentelemetry_prometheus::exporter()
+            .with_registry(registry.clone())
+            .build()?;
+        builder = builder.with_reader(prometheus_exporter);
+    }
+
+    Ok(builder.build())
 }
 
 fn telemetry_resource(config: &TelemetryConfig) -> Resource {
@@ -0,0 +1,268 @@
+//! A built-in Prometheus scrape endpoint, for deployments without an OpenTelemetry collector to
+//! push metrics to.
+//!
+//! The endpoint exposes the same instruments as are pushed over OTLP, as both are readers of the
+//! same meter provider, along with process metrics and metrics of the Tokio runtime.
+
+use std::{convert::Infallible, net::SocketAddr};
+
+use hyper::{
+    header,
+    server::{conn::AddrIncoming, Builder},
+    service::{make_service_fn, service_fn},
+    Body, Method, Request, Response, StatusCode,
+};
+use prometheus::{Encoder, IntGauge, Registry, TextEncoder};
+use telemetry::prelude::*;
+use tokio_util::sync::CancellationToken;
+
+use crate::{Error, Result};
+
+/// The path metrics are served on.
+const METRICS_PATH: &str = "/metrics";
+
+/// Metrics of the Tokio runtime, which are read when scraped rather than recorded as they change.
+#[derive(Clone)]
+struct RuntimeMetrics {
+    workers: IntGauge,
+    alive_tasks: IntGauge,
+}
+
+impl RuntimeMetrics {
+    fn register(registry: &Registry) -> Result<Self> {
+        let workers = IntGauge::new(
+            "tokio_workers",
+            "The number of worker threads used by the runtime",
+        )?;
+        let alive_tasks = IntGauge::new(
+            "tokio_alive_tasks",
+            "The number of tasks currently alive in the runtime",
+        )?;
+        registry.register(Box::new(workers.clone()))?;
+        registry.register(Box::new(alive_tasks.clone()))?;
+
+        Ok(Self {
+            workers,
+            alive_tasks,
+        })
+    }
+
+    fn update(&self) {
+        let metrics = tokio::runtime::Handle::current().metrics();
+        self.workers
+            .set(i64::try_from(metrics.num_workers()).unwrap_or(i64::MAX));
+        self.alive_tasks
+            .set(i64::try_from(metrics.num_alive_tasks()).unwrap_or(i64::MAX));
+    }
+}
+
+/// Serves the metrics in a [`Registry`] in the Prometheus text format.
+pub(crate) struct MetricsEndpointTask {
+    registry: Registry,
+    runtime_metrics: RuntimeMetrics,
+    listen_addr: SocketAddr,
+    server_builder: Builder<AddrIncoming>,
+    shutdown_token: CancellationToken,
+}
+
+impl MetricsEndpointTask {
+    const NAME: &'static str = "MetricsEndpointTask";
+
+    /// Binds the endpoint's listener, so that an unusable address is reported at startup.
+    pub(crate) fn create(
+        listen_addr: SocketAddr,
+        shutdown_token: CancellationToken,
+    ) -> Result<Self> {
+        let incoming = AddrIncoming::bind(&listen_addr).map_err(Error::MetricsEndpointBind)?;
+        // NOTE: this is the address that was bound, which differs from the one requested when
+        // binding to port 0.
+        let listen_addr = incoming.local_addr();
+        let server_builder = hyper::Server::builder(incoming);
+        let registry = Registry::new();
+
+        #[cfg(target_os = "linux")]
+        registry.register(Box::new(
+            prometheus::process_collector::ProcessCollector::for_self(),
+        ))?;
+        let runtime_metrics = RuntimeMetrics::register(&registry)?;
+
+        Ok(Self {
+            registry,
+            runtime_metrics,
+            listen_addr,
+            server_builder,
+            shutdown_token,
+        })
+    }
+
+    /// The registry the OpenTelemetry instruments are to be exported into.
+    pub(crate) fn registry(&self) -> &Registry {
+        &self.registry
+    }
+
+    pub(crate) async fn run(self) {
+        let registry = self.registry;
+        let runtime_metrics = self.runtime_metrics;
+
+        let make_service = make_service_fn(move |_| {
+            let registry = registry.clone();
+            let runtime_metrics = runtime_metrics.clone();
+            async move {
+                Ok::<_, Infallible>(service_fn(move |request| {
+                    let response = respond(&registry, &runtime_metrics, &request);
+                    async move { Ok::<_, Infallible>(response) }
+                }))
+            }
+        });
+
+        let server = self.server_builder.serve(make_service);
+        info!(
+            task = Self::NAME,
+            listen_addr = %self.listen_addr,
+            "serving metrics on {METRICS_PATH}",
+        );
+
+        let shutdown_token = self.shutdown_token;
+        if let Err(err) = server
+            .with_graceful_shutdown(async move { shutdown_token.cancelled().await })
+            .await
+        {
+            warn!(task = Self::NAME, error = ?err, "metrics endpoint failed");
+        }
+
+        debug!(task = Self::NAME, "shutdown complete");
+    }
+}
+
+fn respond(
+    registry: &Registry,
+    runtime_metrics: &RuntimeMetrics,
+    request: &Request<Body>,
+) -> Response<Body> {
+    if request.method() != Method::GET || request.uri().path() != METRICS_PATH {
+        return status_response(StatusCode::NOT_FOUND);
+    }
+
+    runtime_metrics.update();
+
+    match encode(registry) {
+        Ok(body) => Response::builder()
+            .header(header::CONTENT_TYPE, TextEncoder::new().format_type())
+            .body(Body::from(body))
+            .unwrap_or_else(|_| status_response(StatusCode::INTERNAL_SERVER_ERROR)),
+        Err(err) => {
+            warn!(error = ?err, "failed to encode metrics");
+            status_response(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+fn encode(registry: &Registry) -> Result<Vec<u8>> {
+    let mut buf = Vec::new();
+    TextEncoder::new().encode(&registry.gather(), &mut buf)?;
+    Ok(buf)
+}
+
+fn status_response(status: StatusCode) -> Response<Body> {
+    let mut response = Response::new(Body::empty());
+    *response.status_mut() = status;
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use hyper::body;
+
+    use super::*;
+
+    fn registry() -> (Registry, RuntimeMetrics) {
+        let registry = Registry::new();
+        let runtime_metrics =
+            RuntimeMetrics::register(&registry).expect("failed to register runtime metrics");
+        (registry, runtime_metrics)
+    }
+
+    fn request(method: Method, path: &str) -> Request<Body> {
+        Request::builder()
+            .method(method)
+            .uri(path)
+            .body(Body::empty())
+            .expect("failed to build request")
+    }
+
+    #[tokio::test]
+    async fn scrapes_runtime_metrics() {
+        let (registry, runtime_metrics) = registry();
+
+        let response = respond(
+            &registry,
+            &runtime_metrics,
+            &request(Method::GET, METRICS_PATH),
+        );
+
+        assert_eq!(StatusCode::OK, response.status());
+        assert_eq!(
+            Some(TextEncoder::new().format_type()),
+            response
+                .headers()
+                .get(header::CONTENT_TYPE)
+                .and_then(|value| value.to_str().ok()),
+        );
+        let body = body::to_bytes(response.into_body())
+            .await
+            .expect("failed to read response body");
+        let body = String::from_utf8(body.to_vec()).expect("metrics are not utf-8");
+        assert!(
+            body.contains("tokio_workers "),
+            "missing workers in: {body}"
+        );
+        assert!(
+            body.contains("tokio_alive_tasks "),
+            "missing alive tasks in: {body}"
+        );
+    }
+
+    #[tokio::test]
+    async fn only_serves_gets_of_the_metrics_path() {
+        let (registry, runtime_metrics) = registry();
+
+        for (method, path) in [(Method::GET, "/"), (Method::POST, METRICS_PATH)] {
+            let response = respond(&registry, &runtime_metrics, &request(method, path));
+            assert_eq!(StatusCode::NOT_FOUND, response.status());
+        }
+    }
+
+    #[tokio::test]
+    async fn serves_scrapes_until_shut_down() {
+        let shutdown_token = CancellationToken::new();
+        let task = MetricsEndpointTask::create(
+            "127.0.0.1:0".parse().expect("invalid address"),
+            shutdown_token.clone(),
+        )
+        .expect("failed to create metrics endpoint");
+        let uri = format!("http://{}{METRICS_PATH}", task.listen_addr)
+            .parse()
+            .expect("invalid uri");
+        let handle = tokio::spawn(task.run());
+
+        let response = hyper::Client::new()
+            .get(uri)
+            .await
+            .expect("failed to scrape metrics endpoint");
+        assert_eq!(StatusCode::OK, response.status());
+        let body = body::to_bytes(response.into_body())
+            .await
+            .expect("failed to read response body");
+        let body = String::from_utf8(body.to_vec()).expect("metrics are not utf-8");
+        assert!(
+            body.contains("tokio_alive_tasks "),
+            "missing alive tasks in: {body}"
+        );
+
+        shutdown_token.cancel();
+        tokio::time::timeout(std::time::Duration::from_secs(5), handle)
+            .await
+            .expect("metrics endpoint did not shut down")
+            .expect("metrics endpoint panicked");
+    }
+}
@@ -1,28 +1,31 @@
 use futures::{StreamExt, TryStreamExt};
 use nats_subscriber::{Subscriber, SubscriberError};
 use serde::{de::DeserializeOwned, Serialize};
-use si_data_nats::NatsClient;
+use si_data_nats::{HeaderMap, NatsClient};
 use telemetry::prelude::*;
 use telemetry_nats::propagation;
 use thiserror::Error;
 use tokio::sync::mpsc;
 use tokio_util::sync::CancellationToken;
 use veritech_core::{
-    nats_action_run_subject, nats_reconciliation_subject, nats_resolver_function_subject,
-    nats_schema_variant_definition_subject, nats_subject, nats_validation_subject,
-    reply_mailbox_for_keep_alive, reply_mailbox_for_output, reply_mailbox_for_result,
-    FINAL_MESSAGE_HEADER_KEY,
+    nats_action_run_subject, nats_cancel_execution_subject, nats_reconciliation_subject,
+    nats_resolver_function_subject, nats_schema_variant_definition_subject, nats_subject,
+    nats_validation_subject, reply_mailbox_for_keep_alive, reply_mailbox_for_output,
+    reply_mailbox_for_result, FINAL_MESSAGE_HEADER_KEY, PRIORITY_HEADER_KEY,
+    WORKSPACE_ID_HEADER_KEY,
 };
 
 pub use cyclone_core::{
-    ActionRunRequest, ActionRunResultSuccess, BeforeFunction, ComponentKind, ComponentView,
-    FunctionResult, FunctionResultFailure, OutputStream, ReconciliationRequest,
-    ReconciliationResultSuccess, ResolverFunctionComponent, ResolverFunctionRequest,
-    ResolverFunctionResponseType, ResolverFunctionResultSuccess, ResourceStatus,
-    SchemaVariantDefinitionRequest, SchemaVariantDefinitionResultSuccess, SensitiveContainer,
-    ValidationRequest, ValidationResultSuccess,
+    ActionRunRequest, ActionRunResultSuccess, BeforeFunction, CancelExecutionRequest,
+    ComponentKind, ComponentView, FunctionResult, FunctionResultFailure,
+    FunctionResultFailureError, OutputStream, ReconciliationRequest, ReconciliationResultSuccess,
+    RecordedExecution, RedactedSecret, ReplayBundle, ReplayDifference, ReplayRequest,
+    ResolverFunctionComponent, ResolverFunctionRequest, ResolverFunctionResponseType,
+    ResolverFunctionResultSuccess, ResourceStatus, SchemaVariantDefinitionRequest,
+    SchemaVariantDefinitionResultSuccess, SensitiveContainer, ValidationRequest,
+    ValidationResultSuccess,
 };
-pub use veritech_core::{encrypt_value_tree, VeritechValueEncryptError};
+pub use veritech_core::{encrypt_value_tree, RequestPriority, VeritechValueEncryptError};
 
 #[remain::sorted]
 #[derive(Error, Debug)]
@@ -48,11 +51,42 @@ pub type ClientResult<T> = Result<T, ClientError>;
 #[derive(Clone, Debug)]
 pub struct Client {
     nats: NatsClient,
+    workspace_id: Option<String>,
+    priority: Option<RequestPriority>,
 }
 
 impl Client {
     pub fn new(nats: NatsClient) -> Self {
-        Self { nats }
+        Self {
+            nats,
+            workspace_id: None,
+            priority: None,
+        }
+    }
+
+    /// Attributes this client's requests to a workspace so that veritech can share cyclone
+    /// instances fairly between workspaces.
+    pub fn for_workspace(mut self, workspace_id: impl Into<String>) -> Self {
+        self.workspace_id = Some(workspace_id.into());
+        self
+    }
+
+    /// Sets the [`RequestPriority`] of this client's requests. When unset, veritech picks a
+    /// priority based on the kind of request.
+    pub fn with_priority(mut self, priority: RequestPriority) -> Self {
+        self.priority = Some(priority);
+        self
+    }
+
+    fn request_headers(&self) -> HeaderMap {
+        let mut headers = propagation::empty_injected_headers();
+        if let Some(workspace_id) = &self.workspace_id {
+            headers.insert(WORKSPACE_ID_HEADER_KEY, workspace_id.as_str());
+        }
+        if let Some(priority) = self.priority {
+            headers.insert(PRIORITY_HEADER_KEY, priority.as_str());
+        }
+        headers
     }
 
     fn nats_subject_prefix(&self) -> Option<&str> {
@@ -224,6 +258,24 @@ impl Client {
         .await
     }
 
+    /// Asks veritech to cancel the in-flight execution with the request's execution id. The
+    /// cancelled failure result is delivered to the original caller as that execution's result.
+    #[instrument(name = "client.cancel_execution", level = "info", skip_all)]
+    pub async fn cancel_execution(&self, request: &CancelExecutionRequest) -> ClientResult<()> {
+        let msg = serde_json::to_vec(request).map_err(ClientError::JSONSerialize)?;
+        let subject =
+            nats_cancel_execution_subject(self.nats_subject_prefix(), &request.execution_id);
+        trace!(
+            messaging.destination = &subject.as_str(),
+            "publishing cancel message"
+        );
+        self.nats
+            .publish_with_headers(subject, propagation::empty_injected_headers(), msg.into())
+            .await?;
+
+        Ok(())
+    }
+
     async fn execute_request<R, S>(
         &self,
         subject: impl Into<String>,
@@ -296,7 +348,7 @@ impl Client {
             .publish_with_reply_and_headers(
                 subject,
                 reply_mailbox_root.clone(),
-                propagation::empty_injected_headers(),
+                self.request_headers(),
                 msg.into(),
             )
             .await?;
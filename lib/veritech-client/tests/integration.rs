@@ -107,6 +107,7 @@ async fn executes_simple_resolver_function() {
             "function numberOfInputs(input) { return Object.keys(input)?.length ?? 0; }",
         ),
         before: vec![],
+        timeout_secs: None,
     };
 
     let result = client
@@ -174,6 +175,7 @@ async fn type_checks_resolve_function() {
             response_type,
             code_base64: base64_encode("function returnInputValue(input) { return input.value; }"),
             before: vec![],
+            timeout_secs: None,
         };
 
         let result = client
@@ -236,6 +238,7 @@ async fn type_checks_resolve_function() {
             response_type: response_type.clone(),
             code_base64: base64_encode("function returnInputValue(input) { return input.value; }"),
             before: vec![],
+            timeout_secs: None,
         };
 
         let result = client
@@ -278,6 +281,7 @@ async fn executes_simple_validation() {
         validation_format: r#"{"type":"number","flags":{"presence":"required"},"rules":[{"name":"integer"},{"name":"min","args":{"limit":33}},{"name":"max","args":{"limit":33}}]}"#.to_string(),
         code_base64: "".to_string(),
         before: vec![],
+        timeout_secs: None,
     };
 
     let result = client
@@ -322,6 +326,7 @@ async fn executes_simple_schema_variant_definition() {
                     };
                 }",
         ),
+        timeout_secs: None,
     };
 
     let result = client
@@ -18,12 +18,50 @@ pub use crypto::{
 };
 
 const NATS_ACTION_RUN_DEFAULT_SUBJECT: &str = "veritech.fn.actionrun";
+const NATS_CANCEL_EXECUTION_DEFAULT_SUBJECT: &str = "veritech.cancel";
 const NATS_CONCILIATION_DEFAULT_SUBJECT: &str = "veritech.fn.reconciliation";
 const NATS_RESOLVER_FUNCTION_DEFAULT_SUBJECT: &str = "veritech.fn.resolverfunction";
 const NATS_SCHEMA_VARIANT_DEFINITION_DEFAULT_SUBJECT: &str = "veritech.fn.schemavariantdefinition";
 const NATS_VALIDATION_DEFAULT_SUBJECT: &str = "veritech.fn.validation";
 
 pub const FINAL_MESSAGE_HEADER_KEY: &str = "X-Final-Message";
+pub const PRIORITY_HEADER_KEY: &str = "X-Priority";
+pub const WORKSPACE_ID_HEADER_KEY: &str = "X-Workspace-Id";
+
+/// The scheduling class of a function execution request. Veritech hands out cyclone instances to
+/// waiting requests in this order, from [`Interactive`](Self::Interactive) down to
+/// [`Background`](Self::Background).
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum RequestPriority {
+    /// A user is waiting on the result, such as when test executing a func.
+    Interactive,
+    /// An action run.
+    Action,
+    /// Recomputation, such as attribute and validation funcs run during a dependent values
+    /// update.
+    Background,
+}
+
+impl RequestPriority {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Interactive => "interactive",
+            Self::Action => "action",
+            Self::Background => "background",
+        }
+    }
+
+    /// Parses the value of a [`PRIORITY_HEADER_KEY`] header, returning `None` if it is not a known
+    /// priority.
+    pub fn from_header_value(value: &str) -> Option<Self> {
+        match value {
+            "interactive" => Some(Self::Interactive),
+            "action" => Some(Self::Action),
+            "background" => Some(Self::Background),
+            _ => None,
+        }
+    }
+}
 
 pub fn reply_mailbox_for_keep_alive(reply_mailbox: &str) -> String {
     format!("{reply_mailbox}.keepalive")
@@ -37,6 +75,14 @@ pub fn reply_mailbox_for_result(reply_mailbox: &str) -> String {
     format!("{reply_mailbox}.result")
 }
 
+/// The subject on which a request to cancel the in-flight execution with the given id is sent.
+pub fn nats_cancel_execution_subject(prefix: Option<&str>, execution_id: &str) -> String {
+    nats_subject(
+        prefix,
+        format!("{NATS_CANCEL_EXECUTION_DEFAULT_SUBJECT}.{execution_id}"),
+    )
+}
+
 pub fn nats_resolver_function_subject(prefix: Option<&str>) -> String {
     nats_subject(prefix, NATS_RESOLVER_FUNCTION_DEFAULT_SUBJECT)
 }
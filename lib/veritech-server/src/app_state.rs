@@ -0,0 +1,57 @@
+//! Application state for executing functions.
+
+use std::sync::Arc;
+
+use naxum::extract::FromRef;
+use si_crypto::VeritechDecryptionKey;
+use si_data_nats::NatsClient;
+use si_pool_noodle::Spec;
+
+use crate::{cyclone_pool::CyclonePool, server::ServerMetadata};
+
+/// Application state.
+pub(crate) struct AppState<I, S: Spec> {
+    /// Server metadata, used with telemetry and to schedule requests
+    pub(crate) metadata: Arc<ServerMetadata>,
+    /// NATS client, used to send replies
+    pub(crate) nats: NatsClient,
+    /// Pool of Cyclone instances which execute functions
+    pub(crate) cyclone_pool: CyclonePool<I, S>,
+    /// Key used to decrypt the sensitive contents of requests
+    pub(crate) decryption_key: Arc<VeritechDecryptionKey>,
+}
+
+impl<I, S: Spec> AppState<I, S> {
+    /// Creates a new [`AppState`].
+    pub(crate) fn new(
+        metadata: Arc<ServerMetadata>,
+        nats: NatsClient,
+        cyclone_pool: CyclonePool<I, S>,
+        decryption_key: Arc<VeritechDecryptionKey>,
+    ) -> Self {
+        Self {
+            metadata,
+            nats,
+            cyclone_pool,
+            decryption_key,
+        }
+    }
+}
+
+// NOTE: derived `Clone` would require `I: Clone`, which instances needn't be
+impl<I, S: Spec> Clone for AppState<I, S> {
+    fn clone(&self) -> Self {
+        Self {
+            metadata: self.metadata.clone(),
+            nats: self.nats.clone(),
+            cyclone_pool: self.cyclone_pool.clone(),
+            decryption_key: self.decryption_key.clone(),
+        }
+    }
+}
+
+impl<I, S: Spec> FromRef<AppState<I, S>> for si_data_nats::async_nats::Client {
+    fn from_ref(input: &AppState<I, S>) -> Self {
+        input.nats.as_inner().clone()
+    }
+}
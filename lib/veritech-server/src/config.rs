@@ -14,8 +14,9 @@ use serde::{Deserialize, Serialize};
 use si_data_nats::NatsConfig;
 use si_pool_noodle::{
     instance::cyclone::{
-        LocalHttpInstance, LocalHttpInstanceSpec, LocalHttpSocketStrategy, LocalUdsInstance,
-        LocalUdsInstanceSpec, LocalUdsRuntimeStrategy, LocalUdsSocketStrategy,
+        LocalHttpInstance, LocalHttpInstanceSpec, LocalHttpRuntimeStrategy,
+        LocalHttpSocketStrategy, LocalUdsInstance, LocalUdsInstanceSpec, LocalUdsRuntimeStrategy,
+        LocalUdsSocketStrategy,
     },
     Instance,
 };
@@ -64,6 +65,15 @@ pub struct Config {
 
     #[builder(default = "healthcheck_pool_default()")]
     healthcheck_pool: bool,
+
+    #[builder(default)]
+    function_timeouts: FunctionTimeouts,
+
+    #[builder(default)]
+    cyclone_pool_scaling: CyclonePoolScaling,
+
+    #[builder(default)]
+    request_scheduling: RequestScheduling,
 }
 
 #[remain::sorted]
@@ -83,6 +93,70 @@ pub struct ConfigFile {
     pub cyclone: CycloneConfig,
     pub crypto: VeritechCryptoConfig,
     pub healthcheck_pool: bool,
+    #[serde(default)]
+    pub function_timeouts: FunctionTimeouts,
+    #[serde(default)]
+    pub cyclone_pool_scaling: CyclonePoolScaling,
+    #[serde(default)]
+    pub request_scheduling: RequestScheduling,
+}
+
+/// The default timeouts, in seconds, applied to each kind of function request which does not
+/// carry a timeout of its own.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default)]
+pub struct FunctionTimeouts {
+    pub action_run_secs: u64,
+    pub reconciliation_secs: u64,
+    pub resolver_function_secs: u64,
+    pub schema_variant_definition_secs: u64,
+    pub validation_secs: u64,
+}
+
+impl Default for FunctionTimeouts {
+    fn default() -> Self {
+        Self {
+            action_run_secs: 30 * 60,
+            reconciliation_secs: 5 * 60,
+            resolver_function_secs: 2 * 60,
+            schema_variant_definition_secs: 60,
+            validation_secs: 30,
+        }
+    }
+}
+
+/// Autoscaling of the cyclone pool. The cyclone `pool_size` is the most instances the pool will
+/// grow to. A remote cyclone server is not pooled, so it is not scaled either, though `pool_size`
+/// still limits how many requests it executes at once.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default)]
+pub struct CyclonePoolScaling {
+    /// The number of instances kept provisioned at all times. When unset, the pool is fixed at
+    /// `pool_size`.
+    pub min_size: Option<u16>,
+    /// How long a ready instance may sit unused before it is reaped.
+    pub idle_ttl_secs: u64,
+    /// Scale up when a request waits at least this long for an instance.
+    pub scale_up_wait_ms: u64,
+}
+
+impl Default for CyclonePoolScaling {
+    fn default() -> Self {
+        Self {
+            min_size: None,
+            idle_ttl_secs: 5 * 60,
+            scale_up_wait_ms: 100,
+        }
+    }
+}
+
+/// Scheduling of function requests onto the cyclone pool.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default)]
+pub struct RequestScheduling {
+    /// The most requests from a single workspace that may run at once. When unset, a workspace may
+    /// use the whole pool.
+    pub per_workspace_concurrency: Option<u16>,
 }
 
 impl ConfigFile {
@@ -92,6 +166,9 @@ impl ConfigFile {
             cyclone: CycloneConfig::default_local_http(),
             crypto: Default::default(),
             healthcheck_pool: healthcheck_pool_default(),
+            function_timeouts: Default::default(),
+            cyclone_pool_scaling: Default::default(),
+            request_scheduling: Default::default(),
         }
     }
 
@@ -101,6 +178,9 @@ impl ConfigFile {
             cyclone: CycloneConfig::default_local_uds(),
             crypto: Default::default(),
             healthcheck_pool: healthcheck_pool_default(),
+            function_timeouts: Default::default(),
+            cyclone_pool_scaling: Default::default(),
+            request_scheduling: Default::default(),
         }
     }
 }
@@ -119,6 +199,9 @@ impl TryFrom<ConfigFile> for Config {
         config.nats(value.nats);
         config.cyclone_spec(value.cyclone.try_into()?);
         config.crypto(value.crypto);
+        config.function_timeouts(value.function_timeouts);
+        config.cyclone_pool_scaling(value.cyclone_pool_scaling);
+        config.request_scheduling(value.request_scheduling);
         config.build().map_err(Into::into)
     }
 }
@@ -155,6 +238,21 @@ impl Config {
         self.healthcheck_pool
     }
 
+    /// Gets the config's default function timeouts.
+    pub fn function_timeouts(&self) -> FunctionTimeouts {
+        self.function_timeouts
+    }
+
+    /// Gets the config's cyclone pool autoscaling settings.
+    pub fn cyclone_pool_scaling(&self) -> CyclonePoolScaling {
+        self.cyclone_pool_scaling
+    }
+
+    /// Gets the config's request scheduling settings.
+    pub fn request_scheduling(&self) -> RequestScheduling {
+        self.request_scheduling
+    }
+
     // Consumes into a [`CycloneSpec`].
     pub fn into_cyclone_spec(self) -> CycloneSpec {
         self.cyclone_spec
@@ -201,6 +299,8 @@ pub enum CycloneConfig {
         #[serde(default)]
         socket_strategy: LocalHttpSocketStrategy,
         #[serde(default)]
+        runtime_strategy: LocalHttpRuntimeStrategy,
+        #[serde(default)]
         watch_timeout: Option<Duration>,
         #[serde(default = "default_limit_requests")]
         limit_requets: Option<u32>,
@@ -210,6 +310,8 @@ pub enum CycloneConfig {
         resolver: bool,
         #[serde(default = "default_enable_endpoint")]
         action: bool,
+        #[serde(default = "default_pool_size")]
+        pool_size: u16,
     },
     LocalUds {
         #[serde(default = "default_cyclone_cmd_path")]
@@ -234,6 +336,10 @@ pub enum CycloneConfig {
         pool_size: u16,
         #[serde(default)]
         connect_timeout: u64,
+        #[serde(default)]
+        limit_memory_mb: Option<u64>,
+        #[serde(default)]
+        limit_cpu_secs: Option<u64>,
     },
 }
 
@@ -243,11 +349,13 @@ impl CycloneConfig {
             cyclone_cmd_path: default_cyclone_cmd_path(),
             lang_server_cmd_path: default_lang_server_cmd_path(),
             socket_strategy: Default::default(),
+            runtime_strategy: Default::default(),
             watch_timeout: Default::default(),
             limit_requets: default_limit_requests(),
             ping: default_enable_endpoint(),
             resolver: default_enable_endpoint(),
             action: default_enable_endpoint(),
+            pool_size: default_pool_size(),
         }
     }
 
@@ -264,6 +372,8 @@ impl CycloneConfig {
             action: default_enable_endpoint(),
             pool_size: default_pool_size(),
             connect_timeout: default_connect_timeout(),
+            limit_memory_mb: Default::default(),
+            limit_cpu_secs: Default::default(),
         }
     }
 
@@ -367,6 +477,8 @@ impl TryFrom<CycloneConfig> for CycloneSpec {
                 action,
                 pool_size,
                 connect_timeout,
+                limit_memory_mb,
+                limit_cpu_secs,
             } => {
                 let mut builder = LocalUdsInstance::spec();
                 //we only need these if running local process. Maybe the builder should handle
@@ -396,6 +508,8 @@ impl TryFrom<CycloneConfig> for CycloneSpec {
                 }
                 builder.pool_size(pool_size);
                 builder.connect_timeout(connect_timeout);
+                builder.limit_memory_mb(limit_memory_mb);
+                builder.limit_cpu_secs(limit_cpu_secs);
 
                 Ok(Self::LocalUds(
                     builder.build().map_err(ConfigError::cyclone_spec_build)?,
@@ -405,20 +519,27 @@ impl TryFrom<CycloneConfig> for CycloneSpec {
                 cyclone_cmd_path,
                 lang_server_cmd_path,
                 socket_strategy,
+                runtime_strategy,
                 watch_timeout,
                 limit_requets,
                 ping,
                 resolver,
                 action,
+                pool_size,
             } => {
                 let mut builder = LocalHttpInstance::spec();
-                builder
-                    .try_cyclone_cmd_path(cyclone_cmd_path)
-                    .map_err(ConfigError::cyclone_spec_build)?;
-                builder
-                    .try_lang_server_cmd_path(lang_server_cmd_path)
-                    .map_err(ConfigError::cyclone_spec_build)?;
+                // A remote cyclone server is started elsewhere, so we only need the programs when
+                // spawning it ourselves
+                if matches!(runtime_strategy, LocalHttpRuntimeStrategy::LocalProcess) {
+                    builder
+                        .try_cyclone_cmd_path(cyclone_cmd_path)
+                        .map_err(ConfigError::cyclone_spec_build)?;
+                    builder
+                        .try_lang_server_cmd_path(lang_server_cmd_path)
+                        .map_err(ConfigError::cyclone_spec_build)?;
+                }
                 builder.socket_strategy(socket_strategy);
+                builder.runtime_strategy(runtime_strategy);
                 if let Some(watch_timeout) = watch_timeout {
                     builder.watch_timeout(watch_timeout);
                 }
@@ -432,6 +553,7 @@ impl TryFrom<CycloneConfig> for CycloneSpec {
                 if action {
                     builder.action();
                 }
+                builder.pool_size(pool_size);
 
                 Ok(Self::LocalHttp(
                     builder.build().map_err(ConfigError::cyclone_spec_build)?,
@@ -0,0 +1,161 @@
+//! The Cyclone instances which execute function requests.
+//!
+//! Cyclone servers which Veritech spawns itself are kept in a [`PoolNoodle`], which recycles each
+//! instance after use. A remote Cyclone server is long-lived and managed elsewhere, so rather than
+//! pooling many instances which all point at it, every request shares one client to it. In both
+//! cases, how many requests execute at once is bounded by the
+//! [`Scheduler`](crate::scheduler::Scheduler), which has as many permits as the pool size.
+
+use std::{
+    fmt::Display,
+    ops::{Deref, DerefMut},
+    sync::Arc,
+};
+
+use si_pool_noodle::{errors::PoolNoodleError, pool_noodle::LifeGuard, Instance, PoolNoodle, Spec};
+
+/// Makes a new handle on a shared Cyclone server.
+type SharedInstanceFn<I> = dyn Fn() -> I + Send + Sync;
+
+/// Where requests get the Cyclone instances they execute on.
+pub(crate) enum CyclonePool<I, S: Spec> {
+    /// Instances spawned and recycled by a [`PoolNoodle`].
+    Managed(PoolNoodle<I, S>),
+    /// A single long-lived Cyclone server, shared by every request.
+    Shared(Arc<SharedInstanceFn<I>>),
+}
+
+impl<I, S: Spec> CyclonePool<I, S> {
+    /// Creates a [`CyclonePool`] for a single shared Cyclone server, where `instance` makes a new
+    /// handle on the server for each request.
+    pub(crate) fn shared(instance: impl Fn() -> I + Send + Sync + 'static) -> Self {
+        Self::Shared(Arc::new(instance))
+    }
+}
+
+impl<B: 'static, I, E, S> CyclonePool<I, S>
+where
+    S: Spec<Error = E, Instance = I> + Send + Sync + 'static,
+    I: Instance<SpecBuilder = B, Error = E> + Send + Sync + 'static,
+    E: Send + Display + 'static,
+{
+    /// Gets an instance to execute a request on.
+    pub(crate) async fn get(&mut self) -> Result<CycloneInstance<I, S>, PoolNoodleError> {
+        match self {
+            Self::Managed(pool) => pool.get().await.map(CycloneInstance::Managed),
+            Self::Shared(instance) => Ok(CycloneInstance::Shared(instance())),
+        }
+    }
+}
+
+// NOTE: derived `Clone` would require `I: Clone`, which instances needn't be
+impl<I, S: Spec> Clone for CyclonePool<I, S> {
+    fn clone(&self) -> Self {
+        match self {
+            Self::Managed(pool) => Self::Managed(pool.clone()),
+            Self::Shared(instance) => Self::Shared(instance.clone()),
+        }
+    }
+}
+
+/// An instance from a [`CyclonePool`], which is returned to its pool (if it has one) when dropped.
+pub(crate) enum CycloneInstance<I, S>
+where
+    I: Instance + Send + Sync,
+    S: Spec,
+{
+    /// An instance on loan from a [`PoolNoodle`].
+    Managed(LifeGuard<I, S>),
+    /// A handle on a shared Cyclone server.
+    Shared(I),
+}
+
+impl<I, S> Deref for CycloneInstance<I, S>
+where
+    I: Instance + Send + Sync,
+    S: Spec,
+{
+    type Target = I;
+
+    fn deref(&self) -> &I {
+        match self {
+            Self::Managed(instance) => instance,
+            Self::Shared(instance) => instance,
+        }
+    }
+}
+
+impl<I, S> DerefMut for CycloneInstance<I, S>
+where
+    I: Instance + Send + Sync,
+    S: Spec,
+{
+    fn deref_mut(&mut self) -> &mut I {
+        match self {
+            Self::Managed(instance) => instance,
+            Self::Shared(instance) => instance,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use si_pool_noodle::instance::cyclone::{
+        LocalHttpInstance, LocalHttpInstanceSpec, LocalHttpRuntimeStrategy,
+    };
+
+    use super::*;
+
+    fn remote_spec(addr: &str) -> LocalHttpInstanceSpec {
+        LocalHttpInstance::spec()
+            .runtime_strategy(LocalHttpRuntimeStrategy::Remote(addr.to_string()))
+            .build()
+            .expect("failed to build spec")
+    }
+
+    #[test]
+    fn remote_addr_is_only_set_for_remote_specs() {
+        assert_eq!(
+            Some("cyclone:5157"),
+            remote_spec("cyclone:5157").remote_addr()
+        );
+        assert_eq!(
+            None,
+            LocalHttpInstance::spec()
+                .build()
+                .expect("failed to build spec")
+                .remote_addr()
+        );
+    }
+
+    #[tokio::test]
+    async fn shared_pool_hands_out_instances_on_one_client() {
+        let client =
+            si_pool_noodle::Client::http("127.0.0.1:5157").expect("failed to create client");
+        let handed_out = Arc::new(AtomicUsize::new(0));
+        let mut pool: CyclonePool<LocalHttpInstance, LocalHttpInstanceSpec> = {
+            let handed_out = handed_out.clone();
+            CyclonePool::shared(move || {
+                handed_out.fetch_add(1, Ordering::SeqCst);
+                LocalHttpInstance::remote(client.clone())
+            })
+        };
+
+        // Instances are available at once and held concurrently, without spawning or waiting on
+        // the server
+        let mut a = pool.get().await.expect("failed to get instance");
+        let b = pool.clone().get().await.expect("failed to get instance");
+        assert!(matches!(a, CycloneInstance::Shared(_)));
+        assert!(matches!(b, CycloneInstance::Shared(_)));
+        assert_eq!(2, handed_out.load(Ordering::SeqCst));
+
+        // Terminating a handle leaves the shared server alone for everyone else
+        a.terminate().await.expect("failed to terminate instance");
+        drop(a);
+        drop(b);
+        pool.get().await.expect("failed to get instance");
+        assert_eq!(3, handed_out.load(Ordering::SeqCst));
+    }
+}
@@ -0,0 +1,804 @@
+//! Application handlers for executing functions.
+//!
+//! Each handler executes a function request on the cyclone pool, streaming the function's output
+//! to the request's output mailbox as it runs. The function's result is the handler's response,
+//! which is published to the request's result mailbox.
+
+use std::{fmt::Display, future::Future};
+
+use futures::{Stream, StreamExt};
+use naxum::{
+    extract::{message_parts::Headers, State},
+    middleware::reply::Replier,
+    Json,
+};
+use si_data_nats::HeaderMap;
+use si_pool_noodle::{
+    ActionRunRequest, ActionRunResultSuccess, CancelExecutionRequest, Connection, CycloneClient,
+    CycloneRequest, FunctionResult, FunctionResultFailure, FunctionResultFailureError, Instance,
+    ProgressMessage, ReconciliationRequest, ReconciliationResultSuccess, ResolverFunctionRequest,
+    ResolverFunctionResultSuccess, SchemaVariantDefinitionRequest,
+    SchemaVariantDefinitionResultSuccess, SensitiveStrings, Spec, ValidationRequest,
+    ValidationResultSuccess,
+};
+use telemetry::prelude::*;
+use telemetry_nats::propagation;
+use telemetry_utils::metric;
+use tokio::io::{AsyncRead, AsyncWrite};
+use veritech_core::{nats_cancel_execution_subject, RequestPriority};
+
+use crate::{
+    app_state::AppState,
+    request::DecryptRequest,
+    scheduler::Ticket,
+    server::{timestamp, ServerError, ServerResult},
+    Publisher,
+};
+
+type Reply<T> = (HeaderMap, Json<FunctionResult<T>>);
+
+pub(crate) async fn process_resolver_function_request<B, I, E, S, Strm>(
+    State(state): State<AppState<I, S>>,
+    replier: Replier,
+    Headers(headers): Headers,
+    Json(request): Json<ResolverFunctionRequest>,
+) -> Reply<ResolverFunctionResultSuccess>
+where
+    S: Spec<Error = E, Instance = I> + Send + Sync + 'static,
+    I: Instance<SpecBuilder = B, Error = E> + CycloneClient<Strm> + Send + Sync + 'static,
+    B: 'static,
+    E: Send + Display + 'static,
+    Strm: AsyncRead + AsyncWrite + Connection + Unpin + Send + Sync + 'static,
+{
+    let execution_id = request.execution_id.clone();
+    let publisher = Publisher::new(&replier);
+
+    let result = resolver_function_request(state, &publisher, headers.as_ref(), request).await;
+
+    reply(execution_id, &publisher, result).await
+}
+
+pub(crate) async fn process_validation_request<B, I, E, S, Strm>(
+    State(state): State<AppState<I, S>>,
+    replier: Replier,
+    Headers(headers): Headers,
+    Json(request): Json<ValidationRequest>,
+) -> Reply<ValidationResultSuccess>
+where
+    S: Spec<Error = E, Instance = I> + Send + Sync + 'static,
+    I: Instance<SpecBuilder = B, Error = E> + CycloneClient<Strm> + Send + Sync + 'static,
+    B: 'static,
+    E: Send + Display + 'static,
+    Strm: AsyncRead + AsyncWrite + Connection + Unpin + Send + Sync + 'static,
+{
+    let execution_id = request.execution_id.clone();
+    let publisher = Publisher::new(&replier);
+
+    let result = validation_request(state, &publisher, headers.as_ref(), request).await;
+
+    reply(execution_id, &publisher, result).await
+}
+
+pub(crate) async fn process_schema_variant_definition_request<B, I, E, S, Strm>(
+    State(state): State<AppState<I, S>>,
+    replier: Replier,
+    Headers(headers): Headers,
+    Json(request): Json<SchemaVariantDefinitionRequest>,
+) -> Reply<SchemaVariantDefinitionResultSuccess>
+where
+    S: Spec<Error = E, Instance = I> + Send + Sync + 'static,
+    I: Instance<SpecBuilder = B, Error = E> + CycloneClient<Strm> + Send + Sync + 'static,
+    B: 'static,
+    E: Send + Display + 'static,
+    Strm: AsyncRead + AsyncWrite + Connection + Unpin + Send + Sync + 'static,
+{
+    let execution_id = request.execution_id.clone();
+    let publisher = Publisher::new(&replier);
+
+    let result =
+        schema_variant_definition_request(state, &publisher, headers.as_ref(), request).await;
+
+    reply(execution_id, &publisher, result).await
+}
+
+pub(crate) async fn process_action_run_request<B, I, E, S, Strm>(
+    State(state): State<AppState<I, S>>,
+    replier: Replier,
+    Headers(headers): Headers,
+    Json(request): Json<ActionRunRequest>,
+) -> Reply<ActionRunResultSuccess>
+where
+    S: Spec<Error = E, Instance = I> + Send + Sync + 'static,
+    I: Instance<SpecBuilder = B, Error = E> + CycloneClient<Strm> + Send + Sync + 'static,
+    B: 'static,
+    E: Send + Display + 'static,
+    Strm: AsyncRead + AsyncWrite + Connection + Unpin + Send + Sync + 'static,
+{
+    let execution_id = request.execution_id.clone();
+    let publisher = Publisher::new(&replier);
+
+    let result = action_run_request(state, &publisher, headers.as_ref(), request).await;
+
+    reply(execution_id, &publisher, result).await
+}
+
+pub(crate) async fn process_reconciliation_request<B, I, E, S, Strm>(
+    State(state): State<AppState<I, S>>,
+    replier: Replier,
+    Headers(headers): Headers,
+    Json(request): Json<ReconciliationRequest>,
+) -> Reply<ReconciliationResultSuccess>
+where
+    S: Spec<Error = E, Instance = I> + Send + Sync + 'static,
+    I: Instance<SpecBuilder = B, Error = E> + CycloneClient<Strm> + Send + Sync + 'static,
+    B: 'static,
+    E: Send + Display + 'static,
+    Strm: AsyncRead + AsyncWrite + Connection + Unpin + Send + Sync + 'static,
+{
+    let execution_id = request.execution_id.clone();
+    let publisher = Publisher::new(&replier);
+
+    let result = reconciliation_request(state, &publisher, headers.as_ref(), request).await;
+
+    reply(execution_id, &publisher, result).await
+}
+
+/// Waits for a queued request to get its turn to execute, unless a cancellation for it arrives
+/// first, in which case `None` is returned. The cancel stream ending is not a cancellation.
+async fn unless_cancelled<F, C>(queued: F, cancel: &mut C) -> Option<F::Output>
+where
+    F: Future,
+    C: Stream + Unpin,
+{
+    tokio::pin!(queued);
+    let mut listening_for_cancel = true;
+
+    loop {
+        tokio::select! {
+            output = &mut queued => return Some(output),
+            msg = cancel.next(), if listening_for_cancel => match msg {
+                Some(_) => return None,
+                None => listening_for_cancel = false,
+            },
+        }
+    }
+}
+
+/// Finishes the output stream of an execution and builds the final reply holding its result.
+///
+/// A request which failed to execute still gets a result, describing the failure, so that the
+/// requester isn't left waiting on it.
+async fn reply<T>(
+    execution_id: String,
+    publisher: &Publisher,
+    result: ServerResult<FunctionResult<T>>,
+) -> Reply<T> {
+    let function_result = if let Err(err) = publisher.finalize_output().await {
+        error!(error = ?err, "failed to finalize output by sending final message");
+        failure(
+            execution_id,
+            "failed to finalize output by sending final message".to_string(),
+        )
+    } else {
+        match result {
+            Ok(function_result) => function_result,
+            Err(err) => {
+                error!(error = ?err, "failure trying to run function to completion");
+                failure(execution_id, err.to_string())
+            }
+        }
+    };
+
+    (propagation::empty_injected_headers(), Json(function_result))
+}
+
+fn failure<T>(execution_id: String, message: String) -> FunctionResult<T> {
+    FunctionResult::Failure(FunctionResultFailure {
+        execution_id,
+        error: FunctionResultFailureError {
+            kind: "veritechServer".to_string(),
+            message,
+        },
+        timestamp: timestamp(),
+    })
+}
+
+#[instrument(
+    name = "veritech.resolver_function_request",
+    level = "info",
+    skip_all,
+    fields(
+        job.id = &request.execution_id,
+        job.instance = state.metadata.job_instance,
+        job.invoked_name = &request.handler,
+        job.invoked_provider = state.metadata.job_invoked_provider,
+        otel.kind = SpanKind::Server.as_str(),
+        otel.status_code = Empty,
+        otel.status_message = Empty,
+    )
+)]
+async fn resolver_function_request<B, I, E, S, Strm>(
+    state: AppState<I, S>,
+    publisher: &Publisher,
+    headers: Option<&HeaderMap>,
+    mut request: ResolverFunctionRequest,
+) -> ServerResult<FunctionResult<ResolverFunctionResultSuccess>>
+where
+    S: Spec<Error = E, Instance = I> + Send + Sync + 'static,
+    I: Instance<SpecBuilder = B, Error = E> + CycloneClient<Strm> + Send + Sync + 'static,
+    B: 'static,
+    E: Send + Display + 'static,
+    Strm: AsyncRead + AsyncWrite + Connection + Unpin + Send + Sync + 'static,
+{
+    propagation::associate_current_span_from_headers(headers);
+    let span = Span::current();
+    metric!(counter.function_run.resolver = 1);
+
+    let AppState {
+        metadata,
+        mut cyclone_pool,
+        decryption_key,
+        ..
+    } = state;
+
+    let mut sensitive_strings = SensitiveStrings::default();
+    // Decrypt the relevant contents of the request and track any resulting sensitive strings
+    // to be redacted
+    request.decrypt(&mut sensitive_strings, &decryption_key)?;
+    request
+        .timeout_secs
+        .get_or_insert(metadata.function_timeouts.resolver_function_secs);
+
+    let cyclone_request = CycloneRequest::from_parts(request, sensitive_strings);
+
+    // Wait for this request's turn on the cyclone pool, holding the permit until it completes
+    let _permit = metadata
+        .scheduler
+        .acquire(Ticket::from_headers(headers, RequestPriority::Background))
+        .await;
+
+    let mut client = cyclone_pool.get().await.map_err(|err| {
+        metric!(counter.function_run.resolver = -1);
+        span.record_err(ServerError::CyclonePool(Box::new(err)))
+    })?;
+
+    let mut progress = client
+        .execute_resolver(cyclone_request)
+        .await
+        .map_err(|err| {
+            metric!(counter.function_run.resolver = -1);
+            span.record_err(err)
+        })?
+        .start()
+        .await
+        .map_err(|err| span.record_err(err))?;
+
+    while let Some(msg) = progress.next().await {
+        match msg {
+            Ok(ProgressMessage::OutputStream(output)) => {
+                publisher.publish_output(&output).await.map_err(|err| {
+                    metric!(counter.function_run.resolver = -1);
+                    span.record_err(err)
+                })?
+            }
+            Ok(ProgressMessage::Heartbeat) => {
+                trace!("received heartbeat message");
+                publisher.publish_keep_alive().await.map_err(|err| {
+                    metric!(counter.function_run.resolver = -1);
+                    span.record_err(err)
+                })?
+            }
+            Err(err) => {
+                warn!(error = ?err, "next progress message was an error, bailing out");
+                break;
+            }
+        }
+    }
+
+    let function_result = progress.finish().await.map_err(|err| {
+        metric!(counter.function_run.resolver = -1);
+        span.record_err(err)
+    })?;
+
+    metric!(counter.function_run.resolver = -1);
+    span.record_ok();
+    Ok(function_result)
+}
+
+#[instrument(
+    name = "veritech.validation_request",
+    level = "info",
+    skip_all,
+    fields(
+        job.id = &request.execution_id,
+        job.instance = state.metadata.job_instance,
+        job.invoked_name = &request.handler,
+        job.invoked_provider = state.metadata.job_invoked_provider,
+        otel.kind = SpanKind::Server.as_str(),
+        otel.status_code = Empty,
+        otel.status_message = Empty,
+    )
+)]
+async fn validation_request<B, I, E, S, Strm>(
+    state: AppState<I, S>,
+    publisher: &Publisher,
+    headers: Option<&HeaderMap>,
+    mut request: ValidationRequest,
+) -> ServerResult<FunctionResult<ValidationResultSuccess>>
+where
+    S: Spec<Error = E, Instance = I> + Send + Sync + 'static,
+    I: Instance<SpecBuilder = B, Error = E> + CycloneClient<Strm> + Send + Sync + 'static,
+    B: 'static,
+    E: Send + Display + 'static,
+    Strm: AsyncRead + AsyncWrite + Connection + Unpin + Send + Sync + 'static,
+{
+    propagation::associate_current_span_from_headers(headers);
+    let span = Span::current();
+    metric!(counter.function_run.validation = 1);
+
+    let AppState {
+        metadata,
+        mut cyclone_pool,
+        decryption_key,
+        ..
+    } = state;
+
+    let mut sensitive_strings = SensitiveStrings::default();
+    // Decrypt the relevant contents of the request and track any resulting sensitive strings
+    // to be redacted
+    request.decrypt(&mut sensitive_strings, &decryption_key)?;
+    request
+        .timeout_secs
+        .get_or_insert(metadata.function_timeouts.validation_secs);
+
+    let cyclone_request = CycloneRequest::from_parts(request, sensitive_strings);
+
+    // Wait for this request's turn on the cyclone pool, holding the permit until it completes
+    let _permit = metadata
+        .scheduler
+        .acquire(Ticket::from_headers(headers, RequestPriority::Background))
+        .await;
+
+    let mut client = cyclone_pool.get().await.map_err(|err| {
+        metric!(counter.function_run.validation = -1);
+        span.record_err(ServerError::CyclonePool(Box::new(err)))
+    })?;
+
+    let mut progress = client
+        .execute_validation(cyclone_request)
+        .await
+        .map_err(|err| {
+            metric!(counter.function_run.validation = -1);
+            span.record_err(err)
+        })?
+        .start()
+        .await
+        .map_err(|err| {
+            metric!(counter.function_run.validation = -1);
+            span.record_err(err)
+        })?;
+
+    while let Some(msg) = progress.next().await {
+        match msg {
+            Ok(ProgressMessage::OutputStream(output)) => {
+                publisher.publish_output(&output).await.map_err(|err| {
+                    metric!(counter.function_run.validation = -1);
+                    span.record_err(err)
+                })?;
+            }
+            Ok(ProgressMessage::Heartbeat) => {
+                trace!("received heartbeat message");
+            }
+            Err(err) => {
+                warn!(error = ?err, "next progress message was an error, bailing out");
+                break;
+            }
+        }
+    }
+
+    let function_result = progress.finish().await.map_err(|err| {
+        metric!(counter.function_run.validation = -1);
+        span.record_err(err)
+    })?;
+
+    metric!(counter.function_run.validation = -1);
+    span.record_ok();
+    Ok(function_result)
+}
+
+#[instrument(
+    name = "veritech.schema_variant_definition_request",
+    level = "info",
+    skip_all,
+    fields(
+        job.id = &request.execution_id,
+        job.instance = state.metadata.job_instance,
+        job.invoked_name = &request.handler,
+        job.invoked_provider = state.metadata.job_invoked_provider,
+        otel.kind = SpanKind::Server.as_str(),
+        otel.status_code = Empty,
+        otel.status_message = Empty,
+    )
+)]
+async fn schema_variant_definition_request<B, I, E, S, Strm>(
+    state: AppState<I, S>,
+    publisher: &Publisher,
+    headers: Option<&HeaderMap>,
+    mut request: SchemaVariantDefinitionRequest,
+) -> ServerResult<FunctionResult<SchemaVariantDefinitionResultSuccess>>
+where
+    S: Spec<Error = E, Instance = I> + Send + Sync + 'static,
+    I: Instance<SpecBuilder = B, Error = E> + CycloneClient<Strm> + Send + Sync + 'static,
+    B: 'static,
+    E: Send + Display + 'static,
+    Strm: AsyncRead + AsyncWrite + Connection + Unpin + Send + Sync + 'static,
+{
+    propagation::associate_current_span_from_headers(headers);
+    let span = Span::current();
+    metric!(counter.function_run.schema_variant_definition = 1);
+
+    let AppState {
+        metadata,
+        mut cyclone_pool,
+        decryption_key,
+        ..
+    } = state;
+
+    let mut sensitive_strings = SensitiveStrings::default();
+    // Decrypt the relevant contents of the request and track any resulting sensitive strings
+    // to be redacted
+    request.decrypt(&mut sensitive_strings, &decryption_key)?;
+    request
+        .timeout_secs
+        .get_or_insert(metadata.function_timeouts.schema_variant_definition_secs);
+
+    let cyclone_request = CycloneRequest::from_parts(request, sensitive_strings);
+
+    // Wait for this request's turn on the cyclone pool, holding the permit until it completes
+    let _permit = metadata
+        .scheduler
+        .acquire(Ticket::from_headers(headers, RequestPriority::Interactive))
+        .await;
+
+    let mut client = cyclone_pool.get().await.map_err(|err| {
+        metric!(counter.function_run.schema_variant_definition = -1);
+        span.record_err(ServerError::CyclonePool(Box::new(err)))
+    })?;
+
+    let mut progress = client
+        .execute_schema_variant_definition(cyclone_request)
+        .await
+        .map_err(|err| {
+            metric!(counter.function_run.schema_variant_definition = -1);
+            span.record_err(err)
+        })?
+        .start()
+        .await
+        .map_err(|err| {
+            metric!(counter.function_run.schema_variant_definition = -1);
+            span.record_err(err)
+        })?;
+
+    while let Some(msg) = progress.next().await {
+        match msg {
+            Ok(ProgressMessage::OutputStream(output)) => {
+                publisher.publish_output(&output).await.map_err(|err| {
+                    metric!(counter.function_run.schema_variant_definition = -1);
+                    span.record_err(err)
+                })?;
+            }
+            Ok(ProgressMessage::Heartbeat) => {
+                trace!("received heartbeat message");
+            }
+            Err(err) => {
+                warn!(error = ?err, "next progress message was an error, bailing out");
+                break;
+            }
+        }
+    }
+
+    let function_result = progress.finish().await.map_err(|err| {
+        metric!(counter.function_run.schema_variant_definition = -1);
+        span.record_err(err)
+    })?;
+
+    metric!(counter.function_run.schema_variant_definition = -1);
+    span.record_ok();
+    Ok(function_result)
+}
+
+#[instrument(
+    name = "veritech.action_run_request",
+    level = "info",
+    skip_all,
+    fields(
+        job.id = &request.execution_id,
+        job.instance = state.metadata.job_instance,
+        job.invoked_name = &request.handler,
+        job.invoked_provider = state.metadata.job_invoked_provider,
+        otel.kind = SpanKind::Server.as_str(),
+        otel.status_code = Empty,
+        otel.status_message = Empty,
+    )
+)]
+async fn action_run_request<B, I, E, S, Strm>(
+    state: AppState<I, S>,
+    publisher: &Publisher,
+    headers: Option<&HeaderMap>,
+    mut request: ActionRunRequest,
+) -> ServerResult<FunctionResult<ActionRunResultSuccess>>
+where
+    S: Spec<Error = E, Instance = I> + Send + Sync + 'static,
+    I: Instance<SpecBuilder = B, Error = E> + CycloneClient<Strm> + Send + Sync + 'static,
+    B: 'static,
+    E: Send + Display + 'static,
+    Strm: AsyncRead + AsyncWrite + Connection + Unpin + Send + Sync + 'static,
+{
+    propagation::associate_current_span_from_headers(headers);
+    let span = Span::current();
+    metric!(counter.function_run.action = 1);
+
+    let AppState {
+        metadata,
+        nats,
+        mut cyclone_pool,
+        decryption_key,
+    } = state;
+
+    let mut sensitive_strings = SensitiveStrings::default();
+    // Decrypt the relevant contents of the request and track any resulting sensitive strings
+    // to be redacted
+    request.decrypt(&mut sensitive_strings, &decryption_key)?;
+    request
+        .timeout_secs
+        .get_or_insert(metadata.function_timeouts.action_run_secs);
+    let execution_id = request.execution_id.clone();
+
+    let cyclone_request = CycloneRequest::from_parts(request, sensitive_strings);
+
+    // Listen for a request to cancel this execution before waiting for its turn on the cyclone
+    // pool, so that requests cancelled while queued are never executed
+    let mut cancel_subscriber = nats
+        .subscribe(nats_cancel_execution_subject(
+            nats.metadata().subject_prefix(),
+            &execution_id,
+        ))
+        .await
+        .map_err(|err| {
+            metric!(counter.function_run.action = -1);
+            span.record_err(ServerError::Nats(err))
+        })?;
+
+    // Wait for this request's turn on the cyclone pool, holding the permit until it completes
+    let queued = async {
+        let permit = metadata
+            .scheduler
+            .acquire(Ticket::from_headers(headers, RequestPriority::Action))
+            .await;
+        (permit, cyclone_pool.get().await)
+    };
+    let Some((_permit, client)) = unless_cancelled(queued, &mut cancel_subscriber).await else {
+        info!(
+            execution_id = execution_id.as_str(),
+            "cancelling queued action run execution"
+        );
+        if let Err(err) = cancel_subscriber.unsubscribe().await {
+            warn!(error = ?err, "error when unsubscribing from cancel subscriber");
+        }
+        metric!(counter.function_run.action = -1);
+        span.record_ok();
+        return Ok(FunctionResult::Failure(FunctionResultFailure {
+            execution_id,
+            error: FunctionResultFailureError::cancelled(
+                "function execution was cancelled before it started",
+            ),
+            timestamp: timestamp(),
+        }));
+    };
+
+    let mut client = client.map_err(|err| {
+        metric!(counter.function_run.action = -1);
+        span.record_err(ServerError::CyclonePool(Box::new(err)))
+    })?;
+
+    let mut progress = client
+        .execute_action_run(cyclone_request)
+        .await
+        .map_err(|err| {
+            metric!(counter.function_run.action = -1);
+            span.record_err(err)
+        })?
+        .start()
+        .await
+        .map_err(|err| {
+            metric!(counter.function_run.action = -1);
+            span.record_err(err)
+        })?;
+
+    let mut listening_for_cancel = true;
+
+    loop {
+        tokio::select! {
+            msg = progress.next() => match msg {
+                Some(Ok(ProgressMessage::OutputStream(output))) => {
+                    publisher.publish_output(&output).await.map_err(|err| {
+                        metric!(counter.function_run.action = -1);
+                        span.record_err(err)
+                    })?;
+                }
+                Some(Ok(ProgressMessage::Heartbeat)) => {
+                    trace!("received heartbeat message");
+                }
+                Some(Err(err)) => {
+                    warn!(error = ?err, "next progress message was an error, bailing out");
+                    break;
+                }
+                None => break,
+            },
+            cancel = cancel_subscriber.next(), if listening_for_cancel => {
+                listening_for_cancel = false;
+                if cancel.is_some() {
+                    info!(
+                        execution_id = execution_id.as_str(),
+                        "cancelling action run execution"
+                    );
+                    progress
+                        .cancel(&CancelExecutionRequest {
+                            execution_id: execution_id.clone(),
+                        })
+                        .await
+                        .map_err(|err| {
+                            metric!(counter.function_run.action = -1);
+                            span.record_err(err)
+                        })?;
+                }
+            }
+        }
+    }
+    if let Err(err) = cancel_subscriber.unsubscribe().await {
+        warn!(error = ?err, "error when unsubscribing from cancel subscriber");
+    }
+
+    let function_result = progress.finish().await.map_err(|err| {
+        metric!(counter.function_run.action = -1);
+        span.record_err(err)
+    })?;
+
+    metric!(counter.function_run.action = -1);
+    span.record_ok();
+    Ok(function_result)
+}
+
+#[instrument(
+    name = "veritech.reconciliation_request",
+    level = "info",
+    skip_all,
+    fields(
+        job.id = &request.execution_id,
+        job.instance = state.metadata.job_instance,
+        job.invoked_name = &request.handler,
+        job.invoked_provider = state.metadata.job_invoked_provider,
+        otel.kind = SpanKind::Server.as_str(),
+        otel.status_code = Empty,
+        otel.status_message = Empty,
+    )
+)]
+async fn reconciliation_request<B, I, E, S, Strm>(
+    state: AppState<I, S>,
+    publisher: &Publisher,
+    headers: Option<&HeaderMap>,
+    mut request: ReconciliationRequest,
+) -> ServerResult<FunctionResult<ReconciliationResultSuccess>>
+where
+    S: Spec<Error = E, Instance = I> + Send + Sync + 'static,
+    I: Instance<SpecBuilder = B, Error = E> + CycloneClient<Strm> + Send + Sync + 'static,
+    B: 'static,
+    E: Send + Display + 'static,
+    Strm: AsyncRead + AsyncWrite + Connection + Unpin + Send + Sync + 'static,
+{
+    propagation::associate_current_span_from_headers(headers);
+    let span = Span::current();
+    metric!(counter.function_run.reconciliation = 1);
+
+    let AppState {
+        metadata,
+        mut cyclone_pool,
+        decryption_key,
+        ..
+    } = state;
+
+    let mut sensitive_strings = SensitiveStrings::default();
+    // Decrypt the relevant contents of the request and track any resulting sensitive strings
+    // to be redacted
+    request.decrypt(&mut sensitive_strings, &decryption_key)?;
+    request
+        .timeout_secs
+        .get_or_insert(metadata.function_timeouts.reconciliation_secs);
+
+    let cyclone_request = CycloneRequest::from_parts(request, sensitive_strings);
+
+    // Wait for this request's turn on the cyclone pool, holding the permit until it completes
+    let _permit = metadata
+        .scheduler
+        .acquire(Ticket::from_headers(headers, RequestPriority::Background))
+        .await;
+
+    let mut client = cyclone_pool.get().await.map_err(|err| {
+        metric!(counter.function_run.reconciliation = -1);
+        span.record_err(ServerError::CyclonePool(Box::new(err)))
+    })?;
+
+    let mut progress = client
+        .execute_reconciliation(cyclone_request)
+        .await
+        .map_err(|err| {
+            metric!(counter.function_run.reconciliation = -1);
+            span.record_err(err)
+        })?
+        .start()
+        .await
+        .map_err(|err| {
+            metric!(counter.function_run.reconciliation = -1);
+            span.record_err(err)
+        })?;
+
+    while let Some(msg) = progress.next().await {
+        match msg {
+            Ok(ProgressMessage::OutputStream(output)) => {
+                publisher.publish_output(&output).await.map_err(|err| {
+                    metric!(counter.function_run.reconciliation = -1);
+                    span.record_err(err)
+                })?
+            }
+            Ok(ProgressMessage::Heartbeat) => {
+                trace!("received heartbeat message");
+            }
+            Err(err) => {
+                warn!(error = ?err, "next progress message was an error, bailing out");
+                break;
+            }
+        }
+    }
+
+    let function_result = progress.finish().await.map_err(|err| {
+        metric!(counter.function_run.reconciliation = -1);
+        span.record_err(err)
+    })?;
+
+    metric!(counter.function_run.reconciliation = -1);
+    span.record_ok();
+    Ok(function_result)
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::{future, stream};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn queued_request_runs_when_not_cancelled() {
+        let output = unless_cancelled(async { 42 }, &mut stream::pending::<()>()).await;
+
+        assert_eq!(Some(42), output);
+    }
+
+    #[tokio::test]
+    async fn queued_request_is_dropped_when_cancelled() {
+        let output = unless_cancelled(future::pending::<()>(), &mut stream::iter([()])).await;
+
+        assert_eq!(None, output);
+    }
+
+    #[tokio::test]
+    async fn end_of_cancel_stream_is_not_a_cancellation() {
+        let queued = async {
+            tokio::task::yield_now().await;
+            42
+        };
+
+        let output = unless_cancelled(queued, &mut stream::empty::<()>()).await;
+
+        assert_eq!(Some(42), output);
+    }
+}
@@ -1,18 +1,19 @@
+mod app_state;
 mod config;
+mod cyclone_pool;
+mod handlers;
 mod publisher;
 mod request;
+mod scheduler;
 mod server;
-mod subscriber;
 
+pub(crate) use crate::publisher::{Publisher, PublisherError};
 pub use crate::{
     config::{
         detect_and_configure_development, Config, ConfigBuilder, ConfigError, ConfigFile,
-        CycloneSpec, CycloneStream, StandardConfig, StandardConfigFile,
+        CyclonePoolScaling, CycloneSpec, CycloneStream, FunctionTimeouts, RequestScheduling,
+        StandardConfig, StandardConfigFile,
     },
     server::{Server, ServerError, VeritechShutdownHandle},
 };
-pub(crate) use crate::{
-    publisher::{Publisher, PublisherError},
-    subscriber::FunctionSubscriber,
-};
 pub use si_pool_noodle::{instance::cyclone::LocalUdsInstance, Instance};
@@ -1,11 +1,9 @@
-use serde::Serialize;
-use si_data_nats::{NatsClient, Subject};
-use si_pool_noodle::{FunctionResult, OutputStream};
+use naxum::middleware::reply::Replier;
+use si_pool_noodle::OutputStream;
 use telemetry_nats::propagation;
 use thiserror::Error;
 use veritech_core::{
-    reply_mailbox_for_keep_alive, reply_mailbox_for_output, reply_mailbox_for_result,
-    FINAL_MESSAGE_HEADER_KEY,
+    reply_mailbox_for_keep_alive, reply_mailbox_for_output, FINAL_MESSAGE_HEADER_KEY,
 };
 
 #[remain::sorted]
@@ -14,80 +12,62 @@ pub enum PublisherError {
     #[error("failed to serialize json message")]
     JSONSerialize(#[source] serde_json::Error),
     #[error("failed to publish message to nats subject: {1}")]
-    NatsPublish(#[source] si_data_nats::NatsError, String),
+    NatsPublish(#[source] si_data_nats::async_nats::PublishError, String),
 }
 
 type Result<T> = std::result::Result<T, PublisherError>;
 
+/// Publishes the intermediate replies to a function execution request.
+///
+/// The final reply, holding the function's result, is the response of the request's handler.
 #[derive(Debug)]
-pub struct Publisher<'a> {
-    nats: &'a NatsClient,
-    reply_mailbox_keep_alive: Subject,
-    reply_mailbox_output: Subject,
-    reply_mailbox_result: Subject,
+pub struct Publisher {
+    reply_mailbox_keep_alive: Replier,
+    reply_mailbox_output: Replier,
 }
 
-impl<'a> Publisher<'a> {
-    pub fn new(nats: &'a NatsClient, reply_mailbox: &str) -> Self {
+impl Publisher {
+    pub fn new(replier: &Replier) -> Self {
         Self {
-            nats,
-            reply_mailbox_output: reply_mailbox_for_output(reply_mailbox).into(),
-            reply_mailbox_result: reply_mailbox_for_result(reply_mailbox).into(),
-            reply_mailbox_keep_alive: reply_mailbox_for_keep_alive(reply_mailbox).into(),
+            reply_mailbox_output: replier.for_mailbox(reply_mailbox_for_output),
+            reply_mailbox_keep_alive: replier.for_mailbox(reply_mailbox_for_keep_alive),
         }
     }
 
     pub async fn publish_output(&self, output: &OutputStream) -> Result<()> {
         let nats_msg = serde_json::to_string(output).map_err(PublisherError::JSONSerialize)?;
 
-        self.nats
-            .publish_with_headers(
-                self.reply_mailbox_output.clone(),
-                propagation::empty_injected_headers(),
-                nats_msg.into(),
-            )
+        self.reply_mailbox_output
+            .send(propagation::empty_injected_headers(), nats_msg.into())
             .await
-            .map_err(|err| PublisherError::NatsPublish(err, self.reply_mailbox_output.to_string()))
+            .map_err(|err| {
+                PublisherError::NatsPublish(err, self.reply_mailbox_output.subject().to_string())
+            })
     }
 
     pub async fn finalize_output(&self) -> Result<()> {
         let mut headers = si_data_nats::HeaderMap::new();
         headers.insert(FINAL_MESSAGE_HEADER_KEY, "true");
         propagation::inject_headers(&mut headers);
-        self.nats
-            .publish_with_headers(self.reply_mailbox_output.clone(), headers, vec![].into())
-            .await
-            .map_err(|err| PublisherError::NatsPublish(err, self.reply_mailbox_output.to_string()))
-    }
-
-    pub async fn publish_result<R>(&self, result: &FunctionResult<R>) -> Result<()>
-    where
-        R: Serialize,
-    {
-        let nats_msg = serde_json::to_string(result).map_err(PublisherError::JSONSerialize)?;
-
-        self.nats
-            .publish_with_headers(
-                self.reply_mailbox_result.clone(),
-                propagation::empty_injected_headers(),
-                nats_msg.into(),
-            )
+        self.reply_mailbox_output
+            .send(headers, vec![].into())
             .await
-            .map_err(|err| PublisherError::NatsPublish(err, self.reply_mailbox_result.to_string()))
+            .map_err(|err| {
+                PublisherError::NatsPublish(err, self.reply_mailbox_output.subject().to_string())
+            })
     }
 
     pub async fn publish_keep_alive(&self) -> Result<()> {
         let nats_msg = serde_json::to_string(&()).map_err(PublisherError::JSONSerialize)?;
 
-        self.nats
-            .publish_with_headers(
-                self.reply_mailbox_keep_alive.clone(),
-                propagation::empty_injected_headers(),
-                nats_msg.into(),
-            )
+        self.reply_mailbox_keep_alive
+            .send(propagation::empty_injected_headers(), nats_msg.into())
             .await
             .map_err(|err| {
-                PublisherError::NatsPublish(err, self.reply_mailbox_keep_alive.to_string())
+                PublisherError::NatsPublish(
+                    err,
+                    self.reply_mailbox_keep_alive.subject().to_string(),
+                )
             })
     }
 }
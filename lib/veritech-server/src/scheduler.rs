@@ -0,0 +1,322 @@
+//! Fair scheduling of function execution requests onto the cyclone pool.
+//!
+//! Every request must hold a [`Permit`] before it takes an instance from the pool, and there are
+//! as many permits as the pool has instances at its largest. Waiting requests are granted permits
+//! by [`RequestPriority`] first and then round-robin across workspaces within a priority, so that
+//! one workspace's recomputation cannot starve another's interactive work. A workspace may also
+//! be capped to a number of concurrently running requests.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+use si_data_nats::{header::HeaderValue, HeaderMap};
+use telemetry::prelude::*;
+use telemetry_utils::metric;
+use tokio::sync::oneshot;
+use veritech_core::{RequestPriority, PRIORITY_HEADER_KEY, WORKSPACE_ID_HEADER_KEY};
+
+/// The workspace that requests which do not identify one are scheduled under.
+const UNKNOWN_WORKSPACE: &str = "unknown";
+
+const PRIORITIES: [RequestPriority; 3] = [
+    RequestPriority::Interactive,
+    RequestPriority::Action,
+    RequestPriority::Background,
+];
+
+/// Who a request is scheduled for and at what priority.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) struct Ticket {
+    priority: RequestPriority,
+    workspace_id: Arc<str>,
+}
+
+impl Ticket {
+    pub(crate) fn new(priority: RequestPriority, workspace_id: Option<&str>) -> Self {
+        Self {
+            priority,
+            workspace_id: workspace_id.unwrap_or(UNKNOWN_WORKSPACE).into(),
+        }
+    }
+
+    /// Builds a ticket from a request's headers, using `default_priority` when the request does not
+    /// carry a known priority.
+    pub(crate) fn from_headers(
+        headers: Option<&HeaderMap>,
+        default_priority: RequestPriority,
+    ) -> Self {
+        let header = |key: &str| {
+            headers
+                .and_then(|headers| headers.get(key))
+                .map(<HeaderValue as AsRef<str>>::as_ref)
+        };
+
+        let priority = header(PRIORITY_HEADER_KEY)
+            .and_then(RequestPriority::from_header_value)
+            .unwrap_or(default_priority);
+
+        Self::new(priority, header(WORKSPACE_ID_HEADER_KEY))
+    }
+}
+
+/// Grants [`Permits`](Permit) to run requests on the cyclone pool.
+#[derive(Clone, Debug)]
+pub(crate) struct Scheduler {
+    state: Arc<Mutex<SchedulerState>>,
+}
+
+impl Scheduler {
+    /// Creates a scheduler granting up to `concurrency` permits at once, and at most
+    /// `per_workspace_concurrency` of those to any one workspace.
+    pub(crate) fn new(concurrency: usize, per_workspace_concurrency: Option<usize>) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(SchedulerState {
+                available: concurrency,
+                per_workspace_concurrency,
+                running: HashMap::new(),
+                queues: Default::default(),
+            })),
+        }
+    }
+
+    /// Waits for a permit to run the request described by `ticket`.
+    pub(crate) async fn acquire(&self, ticket: Ticket) -> Permit {
+        let (tx, rx) = oneshot::channel();
+
+        let grants = {
+            let mut state = self.lock();
+            state.queues[ticket.priority as usize].push(ticket.workspace_id, tx);
+            metric!(counter.veritech.scheduler.waiting = 1);
+            state.grant(self)
+        };
+        Self::deliver(grants);
+
+        // The sender is only dropped after a permit is sent, so this can only fail if the
+        // scheduler itself is gone
+        match rx.await {
+            Ok(permit) => permit,
+            Err(_) => unreachable!("scheduler dropped a waiting request"),
+        }
+    }
+
+    fn release(&self, workspace_id: &Arc<str>) {
+        let grants = {
+            let mut state = self.lock();
+            state.available += 1;
+            if let Some(running) = state.running.get_mut(workspace_id) {
+                *running -= 1;
+                if *running == 0 {
+                    state.running.remove(workspace_id);
+                }
+            }
+            state.grant(self)
+        };
+        Self::deliver(grants);
+    }
+
+    // Permits are sent outside of the lock: a permit whose waiter has gone away is dropped on a
+    // failed send, and dropping a permit takes the lock to release it.
+    fn deliver(grants: Vec<(oneshot::Sender<Permit>, Permit)>) {
+        for (tx, permit) in grants {
+            if tx.send(permit).is_err() {
+                trace!("request stopped waiting before it was granted a permit");
+            }
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, SchedulerState> {
+        // A panic while holding the lock cannot leave the state half-updated, so a poisoned lock
+        // is safe to keep using
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Permission to run one request on the cyclone pool, returned to the [`Scheduler`] when dropped.
+#[derive(Debug)]
+pub(crate) struct Permit {
+    scheduler: Scheduler,
+    workspace_id: Arc<str>,
+}
+
+impl Drop for Permit {
+    fn drop(&mut self) {
+        self.scheduler.release(&self.workspace_id);
+    }
+}
+
+#[derive(Debug)]
+struct SchedulerState {
+    available: usize,
+    per_workspace_concurrency: Option<usize>,
+    running: HashMap<Arc<str>, usize>,
+    queues: [PriorityQueue; PRIORITIES.len()],
+}
+
+impl SchedulerState {
+    /// Hands out as many permits as are available, returning each with the sender of the waiter it
+    /// was granted to.
+    fn grant(&mut self, scheduler: &Scheduler) -> Vec<(oneshot::Sender<Permit>, Permit)> {
+        let mut grants = Vec::new();
+
+        for priority in PRIORITIES {
+            while self.available > 0 {
+                let running = &self.running;
+                let limit = self.per_workspace_concurrency;
+                let next = self.queues[priority as usize].pop(|workspace_id| match limit {
+                    Some(limit) => running.get(workspace_id).copied().unwrap_or(0) < limit,
+                    None => true,
+                });
+
+                let Some((workspace_id, tx)) = next else {
+                    break;
+                };
+
+                self.available -= 1;
+                *self.running.entry(workspace_id.clone()).or_default() += 1;
+                metric!(counter.veritech.scheduler.waiting = -1);
+
+                grants.push((
+                    tx,
+                    Permit {
+                        scheduler: scheduler.clone(),
+                        workspace_id,
+                    },
+                ));
+            }
+        }
+
+        grants
+    }
+}
+
+/// The requests waiting at one priority, queued per workspace.
+#[derive(Debug, Default)]
+struct PriorityQueue {
+    /// Workspaces with waiting requests, in the order they will next be served.
+    rotation: VecDeque<Arc<str>>,
+    waiting: HashMap<Arc<str>, VecDeque<oneshot::Sender<Permit>>>,
+}
+
+impl PriorityQueue {
+    fn push(&mut self, workspace_id: Arc<str>, tx: oneshot::Sender<Permit>) {
+        let waiting = self.waiting.entry(workspace_id.clone()).or_default();
+        if waiting.is_empty() {
+            self.rotation.push_back(workspace_id);
+        }
+        waiting.push_back(tx);
+    }
+
+    /// Takes the oldest waiter of the next workspace in the rotation that `can_run`, moving that
+    /// workspace to the back of the rotation.
+    fn pop(
+        &mut self,
+        can_run: impl Fn(&Arc<str>) -> bool,
+    ) -> Option<(Arc<str>, oneshot::Sender<Permit>)> {
+        let position = self.rotation.iter().position(can_run)?;
+        let workspace_id = self.rotation.remove(position)?;
+
+        let waiting = self.waiting.get_mut(&workspace_id)?;
+        let tx = waiting.pop_front()?;
+        if waiting.is_empty() {
+            self.waiting.remove(&workspace_id);
+        } else {
+            self.rotation.push_back(workspace_id.clone());
+        }
+
+        Some((workspace_id, tx))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use tokio::{task::JoinHandle, time::timeout};
+
+    use super::*;
+
+    async fn spawn_acquire(scheduler: &Scheduler, ticket: Ticket) -> JoinHandle<Permit> {
+        let scheduler = scheduler.clone();
+        let handle = tokio::spawn(async move { scheduler.acquire(ticket).await });
+        // Give the request a chance to join the queue
+        tokio::task::yield_now().await;
+        handle
+    }
+
+    #[tokio::test]
+    async fn interactive_requests_go_first_and_workspaces_take_turns() {
+        let scheduler = Scheduler::new(1, None);
+        let running = scheduler
+            .acquire(Ticket::new(RequestPriority::Background, Some("a")))
+            .await;
+
+        let (order_tx, mut order_rx) = tokio::sync::mpsc::unbounded_channel();
+        let mut handles = Vec::new();
+        for (priority, workspace_id) in [
+            (RequestPriority::Background, "a"),
+            (RequestPriority::Background, "a"),
+            (RequestPriority::Background, "b"),
+            (RequestPriority::Interactive, "c"),
+        ] {
+            let scheduler = scheduler.clone();
+            let order_tx = order_tx.clone();
+            handles.push(tokio::spawn(async move {
+                let permit = scheduler
+                    .acquire(Ticket::new(priority, Some(workspace_id)))
+                    .await;
+                order_tx
+                    .send(workspace_id)
+                    .expect("failed to record grant order");
+                drop(permit);
+            }));
+            tokio::task::yield_now().await;
+        }
+        drop(order_tx);
+
+        drop(running);
+        for handle in handles {
+            handle.await.expect("request task panicked");
+        }
+
+        let mut order = Vec::new();
+        while let Some(workspace_id) = order_rx.recv().await {
+            order.push(workspace_id);
+        }
+        assert_eq!(vec!["c", "a", "b", "a"], order);
+    }
+
+    #[tokio::test]
+    async fn workspaces_are_capped() {
+        let scheduler = Scheduler::new(3, Some(1));
+        let running = scheduler
+            .acquire(Ticket::new(RequestPriority::Background, Some("a")))
+            .await;
+
+        let capped = spawn_acquire(
+            &scheduler,
+            Ticket::new(RequestPriority::Interactive, Some("a")),
+        )
+        .await;
+        let other = spawn_acquire(
+            &scheduler,
+            Ticket::new(RequestPriority::Background, Some("b")),
+        )
+        .await;
+
+        timeout(Duration::from_secs(1), other)
+            .await
+            .expect("other workspace should not wait")
+            .expect("request task panicked");
+        assert!(!capped.is_finished());
+
+        drop(running);
+        timeout(Duration::from_secs(1), capped)
+            .await
+            .expect("capped workspace should run once its request finishes")
+            .expect("request task panicked");
+    }
+}
@@ -1,31 +1,46 @@
-use std::{io, sync::Arc};
-use telemetry_utils::metric;
+use std::{convert::Infallible, io, sync::Arc, time::Duration};
 
 use chrono::Utc;
 use futures::{channel::oneshot, join, StreamExt};
-use nats_subscriber::Request;
+use naxum::{
+    handler::Handler,
+    middleware::{
+        reply::ReplyLayer,
+        trace::{DefaultMakeSpan, DefaultOnRequest, DefaultOnResponse, TraceLayer},
+    },
+    ServiceExt as _,
+};
 use si_crypto::{VeritechDecryptionKey, VeritechDecryptionKeyError};
-use si_data_nats::NatsClient;
+use si_data_nats::{async_nats, InnerMessage, NatsClient};
 use si_pool_noodle::{
-    instance::cyclone::{LocalUdsInstance, LocalUdsInstanceSpec},
-    ActionRunRequest, ActionRunResultSuccess, CycloneClient, CycloneRequest, FunctionResult,
-    FunctionResultFailure, FunctionResultFailureError, PoolNoodle, ProgressMessage,
-    ReconciliationRequest, ReconciliationResultSuccess, ResolverFunctionRequest,
-    ResolverFunctionResultSuccess, SchemaVariantDefinitionRequest,
-    SchemaVariantDefinitionResultSuccess, SensitiveStrings, Spec, ValidationRequest,
-    ValidationResultSuccess,
+    instance::cyclone::{
+        LocalHttpInstance, LocalHttpInstanceSpec, LocalUdsInstance, LocalUdsInstanceSpec,
+    },
+    ActionRunResultSuccess, Connection, CycloneClient, Instance, PoolNoodle, PoolNoodleConfig,
+    ReconciliationResultSuccess, ResolverFunctionResultSuccess,
+    SchemaVariantDefinitionResultSuccess, Spec, ValidationResultSuccess,
 };
 use telemetry::prelude::*;
 use thiserror::Error;
 use tokio::{
+    io::{AsyncRead, AsyncWrite},
     signal::unix,
     sync::{broadcast, mpsc},
 };
-use veritech_core::VeritechValueDecryptError;
+use tower::ServiceBuilder;
+use veritech_core::{
+    nats_action_run_subject, nats_reconciliation_subject, nats_resolver_function_subject,
+    nats_schema_variant_definition_subject, nats_validation_subject, reply_mailbox_for_result,
+    VeritechValueDecryptError,
+};
 
 use crate::{
-    config::CycloneSpec, request::DecryptRequest, Config, FunctionSubscriber, Publisher,
-    PublisherError,
+    app_state::AppState,
+    config::{CycloneSpec, FunctionTimeouts},
+    cyclone_pool::CyclonePool,
+    handlers,
+    scheduler::Scheduler,
+    Config, PublisherError,
 };
 
 #[remain::sorted]
@@ -43,10 +58,12 @@ pub enum ServerError {
     CycloneSetupError(#[source] Box<dyn std::error::Error + Sync + Send + 'static>),
     #[error("cyclone spec builder error: {0}")]
     CycloneSpec(#[source] Box<dyn std::error::Error + Sync + Send + 'static>),
+    #[error("nats error: {0}")]
+    Nats(#[source] si_data_nats::NatsError),
     #[error("error connecting to nats: {0}")]
     NatsConnect(#[source] si_data_nats::NatsError),
-    #[error("no reply mailbox found")]
-    NoReplyMailboxFound,
+    #[error("naxum error: {0}")]
+    Naxum(#[source] io::Error),
     #[error(transparent)]
     Publisher(#[from] PublisherError),
     #[error(transparent)]
@@ -59,8 +76,8 @@ pub enum ServerError {
     ),
     #[error("failed to setup signal handler")]
     Signal(#[source] io::Error),
-    #[error(transparent)]
-    Subscriber(#[from] nats_subscriber::SubscriberError),
+    #[error("failed to subscribe for requests: {0}")]
+    Subscribe(#[source] async_nats::SubscribeError),
     #[error(transparent)]
     Validation(#[from] si_pool_noodle::ExecutionError<ValidationResultSuccess>),
     #[error("decryption key error: {0}")]
@@ -71,12 +88,16 @@ pub enum ServerError {
     WrongCycloneSpec(&'static str, Box<CycloneSpec>),
 }
 
-type ServerResult<T> = Result<T, ServerError>;
+pub(crate) type ServerResult<T> = Result<T, ServerError>;
 
-pub struct Server {
+/// A Veritech server, dispatching function execution requests to a pool of Cyclone instances.
+///
+/// The server is generic over the Cyclone [`Instance`] and its [`Spec`] so that the same request
+/// handling is used whichever Cyclone backend is configured.
+pub struct Server<I = LocalUdsInstance, S: Spec = LocalUdsInstanceSpec> {
     nats: NatsClient,
     subject_prefix: Option<String>,
-    cyclone_pool: PoolNoodle<LocalUdsInstance, LocalUdsInstanceSpec>,
+    cyclone_pool: CyclonePool<I, S>,
     decryption_key: Arc<VeritechDecryptionKey>,
     shutdown_broadcast_tx: broadcast::Sender<()>,
     shutdown_tx: mpsc::Sender<ShutdownSource>,
@@ -84,83 +105,46 @@ pub struct Server {
     metadata: Arc<ServerMetadata>,
 }
 
-impl Server {
+impl Server<LocalHttpInstance, LocalHttpInstanceSpec> {
     #[instrument(name = "veritech.init.cyclone.http", level = "info", skip_all)]
-    pub async fn for_cyclone_http(config: Config) -> ServerResult<Server> {
+    pub async fn for_cyclone_http(config: Config) -> ServerResult<Self> {
         match config.cyclone_spec() {
-            CycloneSpec::LocalHttp(_spec) => {
-                // TODO(fnichol): Hi there! Ultimately, the Veritech server should be able to work
-                // with a LocalUds Cyclone backend and a LocalHttp version. But since this involves
-                // threading through some generic types which themselves have trait
-                // constraints--well we can add this back in the near future... Note that the
-                // immediately prior state to this line change is roughly the starting point for
-                // adding the types back. Good luck to us all.
-                //
-                // let nats = connect_to_nats(&config).await?;
-                // let manager = Manager::new(spec.clone());
-                // let cyclone_pool = Pool::builder(manager)
-                //     .build()
-                //     .map_err(|err| ServerError::CycloneSpec(Box::new(err)))?;
-
-                // Ok(Server { nats, cyclone_pool })
-                unimplemented!("get ready for a surprise!!")
-            }
+            CycloneSpec::LocalHttp(spec) => match spec.remote_addr() {
+                // A remote Cyclone server is long-lived and managed elsewhere, so every request
+                // shares one client to it rather than pooling instances which all point at it.
+                // The pool size still bounds how many requests it executes at once.
+                Some(addr) => {
+                    let client = LocalHttpInstanceSpec::connect_remote(addr)
+                        .await
+                        .map_err(|e| ServerError::CycloneSetupError(Box::new(e)))?;
+                    let cyclone_pool =
+                        CyclonePool::shared(move || LocalHttpInstance::remote(client.clone()));
+                    let (shutdown_broadcast_tx, _) = broadcast::channel(16);
+
+                    Self::from_cyclone_pool(
+                        &config,
+                        cyclone_pool,
+                        shutdown_broadcast_tx,
+                        spec.pool_size,
+                    )
+                    .await
+                }
+                None => Self::from_cyclone_spec(&config, spec.clone(), spec.pool_size).await,
+            },
             wrong @ CycloneSpec::LocalUds(_) => Err(ServerError::WrongCycloneSpec(
                 "LocalHttp",
                 Box::new(wrong.clone()),
             )),
         }
     }
+}
 
+impl Server<LocalUdsInstance, LocalUdsInstanceSpec> {
     #[instrument(name = "veritech.init.cyclone.uds", level = "info", skip_all)]
-    pub async fn for_cyclone_uds(config: Config) -> ServerResult<Server> {
+    pub async fn for_cyclone_uds(config: Config) -> ServerResult<Self> {
         match config.cyclone_spec() {
             CycloneSpec::LocalUds(spec) => {
-                let (shutdown_tx, shutdown_rx) = mpsc::channel(4);
-                // Note the channel parameter corresponds to the number of channels that may be
-                // maintained when the sender is guaranteeing delivery. While this number may end
-                // of being related to the number of subscribers, it's not
-                // necessarily the same number.
-                let (shutdown_broadcast_tx, _) = broadcast::channel(16);
-
-                let nats = connect_to_nats(&config).await?;
-
-                let mut cyclone_pool: PoolNoodle<LocalUdsInstance, LocalUdsInstanceSpec> =
-                    PoolNoodle::new(
-                        spec.pool_size.into(),
-                        spec.clone(),
-                        shutdown_broadcast_tx.subscribe(),
-                    );
-
-                spec.clone()
-                    .setup()
-                    .await
-                    .map_err(|e| ServerError::CycloneSetupError(Box::new(e)))?;
-                cyclone_pool
-                    .start(config.healthcheck_pool())
-                    .map_err(|e| ServerError::CyclonePool(Box::new(e)))?;
-
-                let metadata = ServerMetadata {
-                    job_instance: config.instance_id().into(),
-                    job_invoked_provider: "si",
-                };
-
-                let graceful_shutdown_rx =
-                    prepare_graceful_shutdown(shutdown_rx, shutdown_broadcast_tx.clone())?;
-
-                let decryption_key =
-                    VeritechDecryptionKey::from_config(config.crypto().clone()).await?;
-
-                Ok(Server {
-                    nats,
-                    subject_prefix: config.subject_prefix().map(|s| s.to_string()),
-                    cyclone_pool,
-                    decryption_key: Arc::new(decryption_key),
-                    shutdown_broadcast_tx,
-                    shutdown_tx,
-                    shutdown_rx: graceful_shutdown_rx,
-                    metadata: Arc::new(metadata),
-                })
+                Self::from_cyclone_spec(&config, spec.clone(), spec.pool_size).await
             }
             wrong @ CycloneSpec::LocalHttp(_) => Err(ServerError::WrongCycloneSpec(
                 "LocalUds",
@@ -168,6 +152,88 @@ impl Server {
             )),
         }
     }
+}
+
+impl<B, I, E, S> Server<I, S>
+where
+    S: Spec<Error = E, Instance = I> + Send + Sync + 'static,
+    I: Instance<SpecBuilder = B, Error = E> + Send + Sync + 'static,
+    B: 'static,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    async fn from_cyclone_spec(config: &Config, mut spec: S, pool_size: u16) -> ServerResult<Self> {
+        // Note the channel parameter corresponds to the number of channels that may be
+        // maintained when the sender is guaranteeing delivery. While this number may end
+        // of being related to the number of subscribers, it's not
+        // necessarily the same number.
+        let (shutdown_broadcast_tx, _) = broadcast::channel(16);
+
+        spec.setup()
+            .await
+            .map_err(|e| ServerError::CycloneSetupError(Box::new(e)))?;
+
+        let scaling = config.cyclone_pool_scaling();
+        let pool_config = PoolNoodleConfig {
+            min_size: scaling.min_size.unwrap_or(pool_size).into(),
+            max_size: pool_size.into(),
+            idle_ttl: Duration::from_secs(scaling.idle_ttl_secs),
+            scale_up_wait_threshold: Duration::from_millis(scaling.scale_up_wait_ms),
+            ..Default::default()
+        };
+        let mut cyclone_pool: PoolNoodle<I, S> =
+            PoolNoodle::with_config(pool_config, spec, shutdown_broadcast_tx.subscribe());
+        cyclone_pool
+            .start(config.healthcheck_pool())
+            .map_err(|e| ServerError::CyclonePool(Box::new(e)))?;
+
+        Self::from_cyclone_pool(
+            config,
+            CyclonePool::Managed(cyclone_pool),
+            shutdown_broadcast_tx,
+            pool_size,
+        )
+        .await
+    }
+
+    async fn from_cyclone_pool(
+        config: &Config,
+        cyclone_pool: CyclonePool<I, S>,
+        shutdown_broadcast_tx: broadcast::Sender<()>,
+        pool_size: u16,
+    ) -> ServerResult<Self> {
+        let (shutdown_tx, shutdown_rx) = mpsc::channel(4);
+
+        let nats = connect_to_nats(config).await?;
+
+        let metadata = ServerMetadata {
+            job_instance: config.instance_id().into(),
+            job_invoked_provider: "si",
+            function_timeouts: config.function_timeouts(),
+            scheduler: Scheduler::new(
+                pool_size.into(),
+                config
+                    .request_scheduling()
+                    .per_workspace_concurrency
+                    .map(Into::into),
+            ),
+        };
+
+        let graceful_shutdown_rx =
+            prepare_graceful_shutdown(shutdown_rx, shutdown_broadcast_tx.clone())?;
+
+        let decryption_key = VeritechDecryptionKey::from_config(config.crypto().clone()).await?;
+
+        Ok(Server {
+            nats,
+            subject_prefix: config.subject_prefix().map(|s| s.to_string()),
+            cyclone_pool,
+            decryption_key: Arc::new(decryption_key),
+            shutdown_broadcast_tx,
+            shutdown_tx,
+            shutdown_rx: graceful_shutdown_rx,
+            metadata: Arc::new(metadata),
+        })
+    }
 
     /// Gets a shutdown handle that can trigger the server's graceful shutdown process.
     pub fn shutdown_handle(&self) -> VeritechShutdownHandle {
@@ -175,49 +241,54 @@ impl Server {
             shutdown_tx: self.shutdown_tx.clone(),
         }
     }
-}
 
-impl Server {
-    pub async fn run(self) -> ServerResult<()> {
+    pub async fn run<Strm>(self) -> ServerResult<()>
+    where
+        I: CycloneClient<Strm>,
+        Strm: AsyncRead + AsyncWrite + Connection + Unpin + Send + Sync + 'static,
+    {
+        let state = AppState::new(
+            self.metadata.clone(),
+            self.nats.clone(),
+            self.cyclone_pool.clone(),
+            self.decryption_key.clone(),
+        );
+        let subject_prefix = self.subject_prefix.as_deref();
+
         let _ = join!(
-            process_resolver_function_requests_task(
-                self.metadata.clone(),
-                self.nats.clone(),
-                self.subject_prefix.clone(),
-                self.cyclone_pool.clone(),
-                self.decryption_key.clone(),
+            serve_requests_task(
+                state.clone(),
+                nats_resolver_function_subject(subject_prefix),
+                "resolver",
+                handlers::process_resolver_function_request::<B, I, E, S, Strm>,
                 self.shutdown_broadcast_tx.subscribe(),
             ),
-            process_validation_requests_task(
-                self.metadata.clone(),
-                self.nats.clone(),
-                self.subject_prefix.clone(),
-                self.cyclone_pool.clone(),
-                self.decryption_key.clone(),
+            serve_requests_task(
+                state.clone(),
+                nats_validation_subject(subject_prefix),
+                "validation",
+                handlers::process_validation_request::<B, I, E, S, Strm>,
                 self.shutdown_broadcast_tx.subscribe(),
             ),
-            process_action_run_requests_task(
-                self.metadata.clone(),
-                self.nats.clone(),
-                self.subject_prefix.clone(),
-                self.cyclone_pool.clone(),
-                self.decryption_key.clone(),
+            serve_requests_task(
+                state.clone(),
+                nats_action_run_subject(subject_prefix),
+                "action",
+                handlers::process_action_run_request::<B, I, E, S, Strm>,
                 self.shutdown_broadcast_tx.subscribe(),
             ),
-            process_reconciliation_requests_task(
-                self.metadata.clone(),
-                self.nats.clone(),
-                self.subject_prefix.clone(),
-                self.cyclone_pool.clone(),
-                self.decryption_key.clone(),
+            serve_requests_task(
+                state.clone(),
+                nats_reconciliation_subject(subject_prefix),
+                "reconciliation",
+                handlers::process_reconciliation_request::<B, I, E, S, Strm>,
                 self.shutdown_broadcast_tx.subscribe(),
             ),
-            process_schema_variant_definition_requests_task(
-                self.metadata.clone(),
-                self.nats.clone(),
-                self.subject_prefix.clone(),
-                self.cyclone_pool.clone(),
-                self.decryption_key.clone(),
+            serve_requests_task(
+                state,
+                nats_schema_variant_definition_subject(subject_prefix),
+                "schema_variant_definition",
+                handlers::process_schema_variant_definition_request::<B, I, E, S, Strm>,
                 self.shutdown_broadcast_tx.subscribe(),
             ),
         );
@@ -231,8 +302,10 @@ impl Server {
 
 #[derive(Clone, Debug)]
 pub struct ServerMetadata {
-    job_instance: String,
-    job_invoked_provider: &'static str,
+    pub(crate) job_instance: String,
+    pub(crate) job_invoked_provider: &'static str,
+    pub(crate) function_timeouts: FunctionTimeouts,
+    pub(crate) scheduler: Scheduler,
 }
 
 pub struct VeritechShutdownHandle {
@@ -247,1033 +320,69 @@ impl VeritechShutdownHandle {
     }
 }
 
-// NOTE(fnichol): resolver function, action are parallel and extremely similar, so there
-// is a lurking "unifying" refactor here. It felt like waiting until the third time adding one of
-// these would do the trick, and as a result the first 2 impls are here and not split apart into
-// their own modules.
-
-async fn process_resolver_function_requests_task(
-    metadata: Arc<ServerMetadata>,
-    nats: NatsClient,
-    subject_prefix: Option<String>,
-    cyclone_pool: PoolNoodle<LocalUdsInstance, LocalUdsInstanceSpec>,
-    decryption_key: Arc<VeritechDecryptionKey>,
-    shutdown_broadcast_rx: broadcast::Receiver<()>,
-) {
-    if let Err(err) = process_resolver_function_requests(
-        metadata,
-        nats,
-        subject_prefix,
-        cyclone_pool,
-        decryption_key,
-        shutdown_broadcast_rx,
-    )
-    .await
-    {
-        warn!(error = ?err, "processing resolver function requests failed");
-    }
-}
-
-async fn process_resolver_function_requests(
-    metadata: Arc<ServerMetadata>,
-    nats: NatsClient,
-    subject_prefix: Option<String>,
-    cyclone_pool: PoolNoodle<LocalUdsInstance, LocalUdsInstanceSpec>,
-    decryption_key: Arc<VeritechDecryptionKey>,
-    mut shutdown_broadcast_rx: broadcast::Receiver<()>,
-) -> ServerResult<()> {
-    let mut requests =
-        FunctionSubscriber::resolver_function(&nats, subject_prefix.as_deref()).await?;
-
-    loop {
-        tokio::select! {
-            // Got a broadcasted shutdown message
-            _ = shutdown_broadcast_rx.recv() => {
-                trace!("process resolver function requests task received shutdown");
-                break;
-            }
-            // Got the next message on from the subscriber
-            request = requests.next() => {
-                match request {
-                    Some(Ok(request)) => {
-                        // Spawn a task an process the request
-                        tokio::spawn(resolver_function_request_task(
-                            metadata.clone(),
-                            nats.clone(),
-                            cyclone_pool.clone(),
-                            decryption_key.clone(),
-                            request,
-                        ));
-                    }
-                    Some(Err(err)) => {
-                        warn!(error = ?err, "next resolver function request had error");
-                    }
-                    None => {
-                        trace!("resolver function requests subscriber stream has closed");
-                        break;
-                    }
-                }
-            }
-            // All other arms are closed, nothing left to do but return
-            else => {
-                trace!("returning with all select arms closed");
-                break
-            }
-        }
-    }
-
-    // Unsubscribe from subscriber without draining the channel
-    requests.unsubscribe_after(0).await?;
-
-    Ok(())
-}
-
-async fn resolver_function_request_task(
-    metadata: Arc<ServerMetadata>,
-    nats: NatsClient,
-    cyclone_pool: PoolNoodle<LocalUdsInstance, LocalUdsInstanceSpec>,
-    decryption_key: Arc<VeritechDecryptionKey>,
-    request: Request<ResolverFunctionRequest>,
-) {
-    let cyclone_request = request.payload;
-
-    let reply_mailbox = match request.reply {
-        Some(reply) => reply,
-        None => {
-            error!("no reply mailbox found");
-            return;
-        }
-    };
-    let execution_id = cyclone_request.execution_id.clone();
-    let publisher = Publisher::new(&nats, &reply_mailbox);
-
-    let function_result = resolver_function_request(
-        metadata,
-        &publisher,
-        cyclone_pool,
-        decryption_key,
-        cyclone_request,
-        &request.process_span,
-    )
-    .await;
-
-    if let Err(err) = publisher.finalize_output().await {
-        error!(error = ?err, "failed to finalize output by sending final message");
-        let result = si_pool_noodle::FunctionResult::Failure::<ResolverFunctionResultSuccess>(
-            FunctionResultFailure {
-                execution_id,
-                error: FunctionResultFailureError {
-                    kind: "veritechServer".to_string(),
-                    message: "failed to finalize output by sending final message".to_string(),
-                },
-                timestamp: timestamp(),
-            },
-        );
-        if let Err(err) = publisher.publish_result(&result).await {
-            error!(error = ?err, "failed to publish errored result");
-        }
-        return;
-    }
-
-    let function_result = match function_result {
-        Ok(fr) => fr,
-        Err(err) => {
-            error!(error = ?err, "failure trying to run function to completion");
-            si_pool_noodle::FunctionResult::Failure::<ResolverFunctionResultSuccess>(
-                FunctionResultFailure {
-                    execution_id,
-                    error: FunctionResultFailureError {
-                        kind: "veritechServer".to_string(),
-                        message: err.to_string(),
-                    },
-                    timestamp: timestamp(),
-                },
-            )
-        }
-    };
-
-    if let Err(err) = publisher.publish_result(&function_result).await {
-        error!(error = ?err, "failed to publish result");
-    };
-}
-
-#[instrument(
-    name = "veritech.resolver_function_request",
-    parent = process_span,
-    level = "info",
-    skip_all,
-    fields(
-        job.id = &request.execution_id,
-        job.instance = metadata.job_instance,
-        job.invoked_name = &request.handler,
-        job.invoked_provider = metadata.job_invoked_provider,
-        otel.kind = SpanKind::Server.as_str(),
-        otel.status_code = Empty,
-        otel.status_message = Empty,
-    )
-)]
-async fn resolver_function_request(
-    metadata: Arc<ServerMetadata>,
-    publisher: &Publisher<'_>,
-    mut cyclone_pool: PoolNoodle<LocalUdsInstance, LocalUdsInstanceSpec>,
-    decryption_key: Arc<VeritechDecryptionKey>,
-    mut request: ResolverFunctionRequest,
-    process_span: &Span,
-) -> ServerResult<FunctionResult<ResolverFunctionResultSuccess>> {
-    let span = Span::current();
-    metric!(counter.function_run.resolver = 1);
-
-    let mut sensitive_strings = SensitiveStrings::default();
-    // Decrypt the relevant contents of the request and track any resulting sensitive strings
-    // to be redacted
-    request.decrypt(&mut sensitive_strings, &decryption_key)?;
-
-    let cyclone_request = CycloneRequest::from_parts(request, sensitive_strings);
-
-    let mut client = cyclone_pool.get().await.map_err(|err| {
-        metric!(counter.function_run.resolver = -1);
-        span.record_err(ServerError::CyclonePool(Box::new(err)))
-    })?;
-
-    let mut progress = client
-        .execute_resolver(cyclone_request)
-        .await
-        .map_err(|err| {
-            metric!(counter.function_run.resolver = -1);
-            span.record_err(err)
-        })?
-        .start()
-        .await
-        .map_err(|err| span.record_err(err))?;
-
-    while let Some(msg) = progress.next().await {
-        match msg {
-            Ok(ProgressMessage::OutputStream(output)) => {
-                publisher.publish_output(&output).await.map_err(|err| {
-                    metric!(counter.function_run.resolver = -1);
-                    span.record_err(err)
-                })?
-            }
-            Ok(ProgressMessage::Heartbeat) => {
-                trace!("received heartbeat message");
-                publisher.publish_keep_alive().await.map_err(|err| {
-                    metric!(counter.function_run.resolver = -1);
-                    span.record_err(err)
-                })?
-            }
-            Err(err) => {
-                warn!(error = ?err, "next progress message was an error, bailing out");
-                break;
-            }
-        }
-    }
-
-    let function_result = progress.finish().await.map_err(|err| {
-        metric!(counter.function_run.resolver = -1);
-        span.record_err(err)
-    })?;
-
-    metric!(counter.function_run.resolver = -1);
-    span.record_ok();
-    Ok(function_result)
-}
-
-async fn process_validation_requests_task(
-    metadata: Arc<ServerMetadata>,
-    nats: NatsClient,
-    subject_prefix: Option<String>,
-    cyclone_pool: PoolNoodle<LocalUdsInstance, LocalUdsInstanceSpec>,
-    decryption_key: Arc<VeritechDecryptionKey>,
+async fn serve_requests_task<H, T, I, S>(
+    state: AppState<I, S>,
+    subject: String,
+    queue_name: &'static str,
+    handler: H,
     shutdown_broadcast_rx: broadcast::Receiver<()>,
-) {
-    if let Err(err) = process_validation_requests(
-        metadata,
-        nats,
-        subject_prefix,
-        cyclone_pool,
-        decryption_key,
-        shutdown_broadcast_rx,
-    )
-    .await
+) where
+    H: Handler<T, AppState<I, S>, InnerMessage>,
+    T: 'static,
+    S: Spec + Send + Sync + 'static,
+    I: Send + Sync + 'static,
+{
+    if let Err(err) =
+        serve_requests(state, subject, queue_name, handler, shutdown_broadcast_rx).await
     {
-        warn!(error = ?err, "processing validation requests failed");
+        warn!(error = ?err, "processing {queue_name} requests failed");
     }
 }
 
-async fn process_validation_requests(
-    metadata: Arc<ServerMetadata>,
-    nats: NatsClient,
-    subject_prefix: Option<String>,
-    cyclone_pool: PoolNoodle<LocalUdsInstance, LocalUdsInstanceSpec>,
-    decryption_key: Arc<VeritechDecryptionKey>,
+/// Serves one kind of function execution request until shutdown.
+///
+/// Requests are load balanced across Veritech instances by a queue group, and each request is
+/// replied to with the result of its function on the request's result mailbox.
+async fn serve_requests<H, T, I, S>(
+    state: AppState<I, S>,
+    subject: String,
+    queue_name: &'static str,
+    handler: H,
     mut shutdown_broadcast_rx: broadcast::Receiver<()>,
-) -> ServerResult<()> {
-    let mut requests = FunctionSubscriber::validation(&nats, subject_prefix.as_deref()).await?;
-
-    loop {
-        tokio::select! {
-            // Got a broadcasted shutdown message
-            _ = shutdown_broadcast_rx.recv() => {
-                trace!("process validation requests task received shutdown");
-                break;
-            }
-            // Got the next message on from the subscriber
-            request = requests.next() => {
-                match request {
-                    Some(Ok(request)) => {
-                        // Spawn a task an process the request
-                        tokio::spawn(validation_request_task(
-                            metadata.clone(),
-                            nats.clone(),
-                            cyclone_pool.clone(),
-                            decryption_key.clone(),
-                            request,
-                        ));
-                    }
-                    Some(Err(err)) => {
-                        warn!(error = ?err, "next validation request had error");
-                    }
-                    None => {
-                        trace!("validation requests subscriber stream has closed");
-                        break;
-                    }
-                }
-            }
-            // All other arms are closed, nothing left to do but return
-            else => {
-                trace!("returning with all select arms closed");
-                break
-            }
-        }
-    }
-
-    // Unsubscribe from subscriber without draining the channel
-    requests.unsubscribe_after(0).await?;
-
-    Ok(())
-}
-
-async fn validation_request_task(
-    metadata: Arc<ServerMetadata>,
-    nats: NatsClient,
-    cyclone_pool: PoolNoodle<LocalUdsInstance, LocalUdsInstanceSpec>,
-    decryption_key: Arc<VeritechDecryptionKey>,
-    request: Request<ValidationRequest>,
-) {
-    let process_span = request.process_span.clone();
-    if let Err(err) = validation_request(
-        metadata,
-        nats,
-        cyclone_pool,
-        decryption_key,
-        request,
-        &process_span,
-    )
-    .await
-    {
-        warn!(error = ?err, "validation execution failed");
-    }
-}
-
-#[instrument(
-    name = "veritech.validation_request",
-    parent = process_span,
-    level = "info",
-    skip_all,
-    fields(
-        job.id = &request.payload.execution_id,
-        job.instance = metadata.job_instance,
-        job.invoked_name = &request.payload.handler,
-        job.invoked_provider = metadata.job_invoked_provider,
-        otel.kind = SpanKind::Server.as_str(),
-        otel.status_code = Empty,
-        otel.status_message = Empty,
-    )
-)]
-async fn validation_request(
-    metadata: Arc<ServerMetadata>,
-    nats: NatsClient,
-    mut cyclone_pool: PoolNoodle<LocalUdsInstance, LocalUdsInstanceSpec>,
-    decryption_key: Arc<VeritechDecryptionKey>,
-    request: Request<ValidationRequest>,
-    process_span: &Span,
-) -> ServerResult<()> {
-    let span = Span::current();
-    metric!(counter.function_run.validation = 1);
-
-    let mut payload_request = request.payload;
-
-    let mut sensitive_strings = SensitiveStrings::default();
-    // Decrypt the relevant contents of the request and track any resulting sensitive strings
-    // to be redacted
-    payload_request.decrypt(&mut sensitive_strings, &decryption_key)?;
-
-    let cyclone_request = CycloneRequest::from_parts(payload_request, sensitive_strings);
-
-    let reply_mailbox = request
-        .reply
-        .ok_or(ServerError::NoReplyMailboxFound)
-        .map_err(|err| {
-            metric!(counter.function_run.validation = -1);
-            span.record_err(err)
-        })?;
-
-    let publisher = Publisher::new(&nats, &reply_mailbox);
-    let mut client = cyclone_pool.get().await.map_err(|err| {
-        metric!(counter.function_run.validation = -1);
-        span.record_err(ServerError::CyclonePool(Box::new(err)))
-    })?;
-
-    let mut progress = client
-        .execute_validation(cyclone_request)
+) -> ServerResult<()>
+where
+    H: Handler<T, AppState<I, S>, InnerMessage>,
+    T: 'static,
+    S: Spec + Send + Sync + 'static,
+    I: Send + Sync + 'static,
+{
+    debug!(
+        messaging.destination = subject.as_str(),
+        "subscribing for {queue_name} requests"
+    );
+    let client = state.nats.as_inner().clone();
+    let incoming = client
+        .queue_subscribe(subject, queue_name.to_owned())
         .await
-        .map_err(|err| {
-            metric!(counter.function_run.validation = -1);
-            span.record_err(err)
-        })?
-        .start()
+        .map_err(ServerError::Subscribe)?
+        .map(Ok::<_, Infallible>);
+
+    let app = ServiceBuilder::new()
+        .layer(
+            TraceLayer::new()
+                .make_span_with(DefaultMakeSpan::new().level(Level::TRACE))
+                .on_request(DefaultOnRequest::new().level(Level::TRACE))
+                .on_response(DefaultOnResponse::new().level(Level::TRACE)),
+        )
+        .layer(ReplyLayer::new(client).final_reply_subject(reply_mailbox_for_result))
+        .service(handler.with_state(state));
+
+    naxum::serve(incoming, app.into_make_service())
+        .with_graceful_shutdown(async move {
+            let _ = shutdown_broadcast_rx.recv().await;
+        })
         .await
-        .map_err(|err| {
-            metric!(counter.function_run.validation = -1);
-            span.record_err(err)
-        })?;
-
-    while let Some(msg) = progress.next().await {
-        match msg {
-            Ok(ProgressMessage::OutputStream(output)) => {
-                publisher.publish_output(&output).await.map_err(|err| {
-                    metric!(counter.function_run.validation = -1);
-                    span.record_err(err)
-                })?;
-            }
-            Ok(ProgressMessage::Heartbeat) => {
-                trace!("received heartbeat message");
-            }
-            Err(err) => {
-                warn!(error = ?err, "next progress message was an error, bailing out");
-                break;
-            }
-        }
-    }
-    publisher.finalize_output().await.map_err(|err| {
-        metric!(counter.function_run.validation = -1);
-        span.record_err(err)
-    })?;
-
-    let function_result = progress.finish().await.map_err(|err| {
-        metric!(counter.function_run.validation = -1);
-        span.record_err(err)
-    })?;
-    publisher
-        .publish_result(&function_result)
-        .await
-        .map_err(|err| {
-            metric!(counter.function_run.validation = -1);
-            span.record_err(err)
-        })?;
-
-    metric!(counter.function_run.validation = -1);
-    span.record_ok();
-    Ok(())
-}
-
-async fn process_schema_variant_definition_requests_task(
-    metadata: Arc<ServerMetadata>,
-    nats: NatsClient,
-    subject_prefix: Option<String>,
-    cyclone_pool: PoolNoodle<LocalUdsInstance, LocalUdsInstanceSpec>,
-    decryption_key: Arc<VeritechDecryptionKey>,
-    shutdown_broadcast_rx: broadcast::Receiver<()>,
-) {
-    if let Err(err) = process_schema_variant_definition_requests(
-        metadata,
-        nats,
-        subject_prefix,
-        cyclone_pool,
-        decryption_key,
-        shutdown_broadcast_rx,
-    )
-    .await
-    {
-        warn!(error = ?err, "processing schema variant definition requests failed");
-    }
-}
-
-async fn process_schema_variant_definition_requests(
-    metadata: Arc<ServerMetadata>,
-    nats: NatsClient,
-    subject_prefix: Option<String>,
-    cyclone_pool: PoolNoodle<LocalUdsInstance, LocalUdsInstanceSpec>,
-    decryption_key: Arc<VeritechDecryptionKey>,
-    mut shutdown_broadcast_rx: broadcast::Receiver<()>,
-) -> ServerResult<()> {
-    let mut requests =
-        FunctionSubscriber::schema_variant_definition(&nats, subject_prefix.as_deref()).await?;
-
-    loop {
-        tokio::select! {
-            // Got a broadcasted shutdown message
-            _ = shutdown_broadcast_rx.recv() => {
-                trace!("process schema_variant_definition requests task received shutdown");
-                break;
-            }
-            // Got the next message on from the subscriber
-            request = requests.next() => {
-                match request {
-                    Some(Ok(request)) => {
-                        // Spawn a task an process the request
-                        tokio::spawn(schema_variant_definition_request_task(
-                            metadata.clone(),
-                            nats.clone(),
-                            cyclone_pool.clone(),
-                            decryption_key.clone(),
-                            request,
-                        ));
-                    }
-                    Some(Err(err)) => {
-                        warn!(error = ?err, "next schema variant definition request had error");
-                    }
-                    None => {
-                        trace!("schema variant definition requests subscriber stream has closed");
-                        break;
-                    }
-                }
-            }
-            // All other arms are closed, nothing left to do but return
-            else => {
-                trace!("returning with all select arms closed");
-                break
-            }
-        }
-    }
-
-    // Unsubscribe from subscriber without draining the channel
-    requests.unsubscribe_after(0).await?;
-
-    Ok(())
-}
-
-async fn schema_variant_definition_request_task(
-    metadata: Arc<ServerMetadata>,
-    nats: NatsClient,
-    cyclone_pool: PoolNoodle<LocalUdsInstance, LocalUdsInstanceSpec>,
-    decryption_key: Arc<VeritechDecryptionKey>,
-    request: Request<SchemaVariantDefinitionRequest>,
-) {
-    let process_span = request.process_span.clone();
-    if let Err(err) = schema_variant_definition_request(
-        metadata,
-        nats,
-        cyclone_pool,
-        decryption_key,
-        request,
-        &process_span,
-    )
-    .await
-    {
-        warn!(error = ?err, "schema variant definition execution failed");
-    }
-}
-
-#[instrument(
-    name = "veritech.schema_variant_definition_request",
-    parent = process_span,
-    level = "info",
-    skip_all,
-    fields(
-        job.id = &request.payload.execution_id,
-        job.instance = metadata.job_instance,
-        job.invoked_name = &request.payload.handler,
-        job.invoked_provider = metadata.job_invoked_provider,
-        otel.kind = SpanKind::Server.as_str(),
-        otel.status_code = Empty,
-        otel.status_message = Empty,
-    )
-)]
-async fn schema_variant_definition_request(
-    metadata: Arc<ServerMetadata>,
-    nats: NatsClient,
-    mut cyclone_pool: PoolNoodle<LocalUdsInstance, LocalUdsInstanceSpec>,
-    decryption_key: Arc<VeritechDecryptionKey>,
-    request: Request<SchemaVariantDefinitionRequest>,
-    process_span: &Span,
-) -> ServerResult<()> {
-    let span = Span::current();
-    metric!(counter.function_run.schema_variant_definition = 1);
-
-    let mut payload_request = request.payload;
-
-    let mut sensitive_strings = SensitiveStrings::default();
-    // Decrypt the relevant contents of the request and track any resulting sensitive strings
-    // to be redacted
-    payload_request.decrypt(&mut sensitive_strings, &decryption_key)?;
-
-    let cyclone_request = CycloneRequest::from_parts(payload_request, sensitive_strings);
-
-    let reply_mailbox = request
-        .reply
-        .ok_or(ServerError::NoReplyMailboxFound)
-        .map_err(|err| {
-            metric!(counter.function_run.schema_variant_definition = -1);
-            span.record_err(err)
-        })?;
-
-    let publisher = Publisher::new(&nats, &reply_mailbox);
-    let mut client = cyclone_pool.get().await.map_err(|err| {
-        metric!(counter.function_run.schema_variant_definition = 1);
-        span.record_err(ServerError::CyclonePool(Box::new(err)))
-    })?;
-
-    let mut progress = client
-        .execute_schema_variant_definition(cyclone_request)
-        .await
-        .map_err(|err| {
-            metric!(counter.function_run.schema_variant_definition = -1);
-            span.record_err(err)
-        })?
-        .start()
-        .await
-        .map_err(|err| {
-            metric!(counter.function_run.schema_variant_definition = -1);
-            span.record_err(err)
-        })?;
-
-    while let Some(msg) = progress.next().await {
-        match msg {
-            Ok(ProgressMessage::OutputStream(output)) => {
-                publisher.publish_output(&output).await.map_err(|err| {
-                    metric!(counter.function_run.schema_variant_definition = -1);
-                    span.record_err(err)
-                })?;
-            }
-            Ok(ProgressMessage::Heartbeat) => {
-                trace!("received heartbeat message");
-            }
-            Err(err) => {
-                warn!(error = ?err, "next progress message was an error, bailing out");
-                break;
-            }
-        }
-    }
-    publisher.finalize_output().await.map_err(|err| {
-        metric!(counter.function_run.schema_variant_definition = -1);
-        span.record_err(err)
-    })?;
-
-    let function_result = progress.finish().await.map_err(|err| {
-        metric!(counter.function_run.schema_variant_definition = -1);
-        span.record_err(err)
-    })?;
-    publisher
-        .publish_result(&function_result)
-        .await
-        .map_err(|err| {
-            metric!(counter.function_run.schema_variant_definition = -1);
-            span.record_err(err)
-        })?;
-
-    metric!(counter.function_run.schema_variant_definition = -1);
-    span.record_ok();
-    Ok(())
-}
-
-async fn process_action_run_requests_task(
-    metadata: Arc<ServerMetadata>,
-    nats: NatsClient,
-    subject_prefix: Option<String>,
-    cyclone_pool: PoolNoodle<LocalUdsInstance, LocalUdsInstanceSpec>,
-    decryption_key: Arc<VeritechDecryptionKey>,
-    shutdown_broadcast_rx: broadcast::Receiver<()>,
-) {
-    if let Err(err) = process_action_run_requests(
-        metadata,
-        nats,
-        subject_prefix,
-        cyclone_pool,
-        decryption_key,
-        shutdown_broadcast_rx,
-    )
-    .await
-    {
-        warn!(error = ?err, "processing action run requests failed");
-    }
-}
-
-async fn process_action_run_requests(
-    metadata: Arc<ServerMetadata>,
-    nats: NatsClient,
-    subject_prefix: Option<String>,
-    cyclone_pool: PoolNoodle<LocalUdsInstance, LocalUdsInstanceSpec>,
-    decryption_key: Arc<VeritechDecryptionKey>,
-    mut shutdown_broadcast_rx: broadcast::Receiver<()>,
-) -> ServerResult<()> {
-    let mut requests = FunctionSubscriber::action_run(&nats, subject_prefix.as_deref()).await?;
-
-    loop {
-        tokio::select! {
-            // Got a broadcasted shutdown message
-            _ = shutdown_broadcast_rx.recv() => {
-                trace!("process action_run requests task received shutdown");
-                break;
-            }
-            // Got the next message on from the subscriber
-            request = requests.next() => {
-                match request {
-                    Some(Ok(request)) => {
-                        // Spawn a task an process the request
-                        tokio::spawn(action_run_request_task(
-                            metadata.clone(),
-                            nats.clone(),
-                            cyclone_pool.clone(),
-                            decryption_key.clone(),
-                            request,
-                        ));
-                    }
-                    Some(Err(err)) => {
-                        warn!(error = ?err, "next action run request had error");
-                    }
-                    None => {
-                        trace!("action run requests subscriber stream has closed");
-                        break;
-                    }
-                }
-            }
-            // All other arms are closed, nothing left to do but return
-            else => {
-                trace!("returning with all select arms closed");
-                break
-            }
-        }
-    }
-
-    // Unsubscribe from subscriber without draining the channel
-    requests.unsubscribe_after(0).await?;
-
-    Ok(())
-}
-
-async fn action_run_request_task(
-    metadata: Arc<ServerMetadata>,
-    nats: NatsClient,
-    cyclone_pool: PoolNoodle<LocalUdsInstance, LocalUdsInstanceSpec>,
-    decryption_key: Arc<VeritechDecryptionKey>,
-    request: Request<ActionRunRequest>,
-) {
-    let process_span = request.process_span.clone();
-    if let Err(err) = action_run_request(
-        metadata,
-        nats,
-        cyclone_pool,
-        decryption_key,
-        request,
-        &process_span,
-    )
-    .await
-    {
-        warn!(error = ?err, "action run execution failed");
-    }
-}
-
-#[instrument(
-    name = "veritech.action_run_request",
-    parent = process_span,
-    level = "info",
-    skip_all,
-    fields(
-        job.id = &request.payload.execution_id,
-        job.instance = metadata.job_instance,
-        job.invoked_name = &request.payload.handler,
-        job.invoked_provider = metadata.job_invoked_provider,
-        otel.kind = SpanKind::Server.as_str(),
-        otel.status_code = Empty,
-        otel.status_message = Empty,
-    )
-)]
-async fn action_run_request(
-    metadata: Arc<ServerMetadata>,
-    nats: NatsClient,
-    mut cyclone_pool: PoolNoodle<LocalUdsInstance, LocalUdsInstanceSpec>,
-    decryption_key: Arc<VeritechDecryptionKey>,
-    request: Request<ActionRunRequest>,
-    process_span: &Span,
-) -> ServerResult<()> {
-    let span = Span::current();
-    metric!(counter.function_run.action = 1);
-
-    let mut payload_request = request.payload;
-
-    let mut sensitive_strings = SensitiveStrings::default();
-    // Decrypt the relevant contents of the request and track any resulting sensitive strings
-    // to be redacted
-    payload_request.decrypt(&mut sensitive_strings, &decryption_key)?;
-
-    let cyclone_request = CycloneRequest::from_parts(payload_request, sensitive_strings);
-
-    let reply_mailbox = request
-        .reply
-        .ok_or(ServerError::NoReplyMailboxFound)
-        .map_err(|err| {
-            metric!(counter.function_run.action = -1);
-            span.record_err(err)
-        })?;
-
-    let publisher = Publisher::new(&nats, &reply_mailbox);
-    let mut client = cyclone_pool.get().await.map_err(|err| {
-        metric!(counter.function_run.action = -1);
-        span.record_err(ServerError::CyclonePool(Box::new(err)))
-    })?;
-
-    let mut progress = client
-        .execute_action_run(cyclone_request)
-        .await
-        .map_err(|err| {
-            metric!(counter.function_run.action = -1);
-            span.record_err(err)
-        })?
-        .start()
-        .await
-        .map_err(|err| {
-            metric!(counter.function_run.action = -1);
-            span.record_err(err)
-        })?;
-
-    while let Some(msg) = progress.next().await {
-        match msg {
-            Ok(ProgressMessage::OutputStream(output)) => {
-                publisher.publish_output(&output).await.map_err(|err| {
-                    metric!(counter.function_run.action = -1);
-                    span.record_err(err)
-                })?;
-            }
-            Ok(ProgressMessage::Heartbeat) => {
-                trace!("received heartbeat message");
-            }
-            Err(err) => {
-                warn!(error = ?err, "next progress message was an error, bailing out");
-                break;
-            }
-        }
-    }
-    publisher.finalize_output().await.map_err(|err| {
-        metric!(counter.function_run.action = -1);
-        span.record_err(err)
-    })?;
-
-    let function_result = progress.finish().await.map_err(|err| {
-        metric!(counter.function_run.action = -1);
-        span.record_err(err)
-    })?;
-    publisher
-        .publish_result(&function_result)
-        .await
-        .map_err(|err| {
-            metric!(counter.function_run.action = -1);
-            span.record_err(err)
-        })?;
-
-    metric!(counter.function_run.action = -1);
-    span.record_ok();
-    Ok(())
-}
-
-async fn process_reconciliation_requests_task(
-    metadata: Arc<ServerMetadata>,
-    nats: NatsClient,
-    subject_prefix: Option<String>,
-    cyclone_pool: PoolNoodle<LocalUdsInstance, LocalUdsInstanceSpec>,
-    decryption_key: Arc<VeritechDecryptionKey>,
-    shutdown_broadcast_rx: broadcast::Receiver<()>,
-) {
-    if let Err(err) = process_reconciliation_requests(
-        metadata,
-        nats,
-        subject_prefix,
-        cyclone_pool,
-        decryption_key,
-        shutdown_broadcast_rx,
-    )
-    .await
-    {
-        warn!(error = ?err, "processing reconciliation requests failed");
-    }
-}
-
-async fn process_reconciliation_requests(
-    metadata: Arc<ServerMetadata>,
-    nats: NatsClient,
-    subject_prefix: Option<String>,
-    cyclone_pool: PoolNoodle<LocalUdsInstance, LocalUdsInstanceSpec>,
-    decryption_key: Arc<VeritechDecryptionKey>,
-    mut shutdown_broadcast_rx: broadcast::Receiver<()>,
-) -> ServerResult<()> {
-    let mut requests = FunctionSubscriber::reconciliation(&nats, subject_prefix.as_deref()).await?;
-
-    loop {
-        tokio::select! {
-            // Got a broadcasted shutdown message
-            _ = shutdown_broadcast_rx.recv() => {
-                trace!("process reconciliation requests task received shutdown");
-                break;
-            }
-            // Got the next message on from the subscriber
-            request = requests.next() => {
-                match request {
-                    Some(Ok(request)) => {
-                        // Spawn a task an process the request
-                        tokio::spawn(reconciliation_request_task(
-                            metadata.clone(),
-                            nats.clone(),
-                            cyclone_pool.clone(),
-                            decryption_key.clone(),
-                            request,
-                        ));
-                    }
-                    Some(Err(err)) => {
-                        warn!(error = ?err, "next reconciliation request had error");
-                    }
-                    None => {
-                        trace!("reconciliation requests subscriber stream has closed");
-                        break;
-                    }
-                }
-            }
-            // All other arms are closed, nothing left to do but return
-            else => {
-                trace!("returning with all select arms closed");
-                break
-            }
-        }
-    }
-
-    // Unsubscribe from subscriber without draining the channel
-    requests.unsubscribe_after(0).await?;
-
-    Ok(())
-}
-
-async fn reconciliation_request_task(
-    metadata: Arc<ServerMetadata>,
-    nats: NatsClient,
-    cyclone_pool: PoolNoodle<LocalUdsInstance, LocalUdsInstanceSpec>,
-    decryption_key: Arc<VeritechDecryptionKey>,
-    request: Request<ReconciliationRequest>,
-) {
-    let process_span = request.process_span.clone();
-    if let Err(err) = reconciliation_request(
-        metadata,
-        nats,
-        cyclone_pool,
-        decryption_key,
-        request,
-        &process_span,
-    )
-    .await
-    {
-        warn!(error = ?err, "reconciliation execution failed");
-    }
-}
-
-#[instrument(
-    name = "veritech.reconciliation_request",
-    parent = process_span,
-    level = "info",
-    skip_all,
-    fields(
-        job.id = &request.payload.execution_id,
-        job.instance = metadata.job_instance,
-        job.invoked_name = &request.payload.handler,
-        job.invoked_provider = metadata.job_invoked_provider,
-        otel.kind = SpanKind::Server.as_str(),
-        otel.status_code = Empty,
-        otel.status_message = Empty,
-    )
-)]
-async fn reconciliation_request(
-    metadata: Arc<ServerMetadata>,
-    nats: NatsClient,
-    mut cyclone_pool: PoolNoodle<LocalUdsInstance, LocalUdsInstanceSpec>,
-    decryption_key: Arc<VeritechDecryptionKey>,
-    request: Request<ReconciliationRequest>,
-    process_span: &Span,
-) -> ServerResult<()> {
-    let span = Span::current();
-    metric!(counter.function_run.reconciliation = 1);
-
-    let mut payload_request = request.payload;
-
-    let mut sensitive_strings = SensitiveStrings::default();
-    // Decrypt the relevant contents of the request and track any resulting sensitive strings
-    // to be redacted
-    payload_request.decrypt(&mut sensitive_strings, &decryption_key)?;
-
-    let cyclone_request = CycloneRequest::from_parts(payload_request, sensitive_strings);
-
-    let reply_mailbox = request
-        .reply
-        .ok_or(ServerError::NoReplyMailboxFound)
-        .map_err(|err| {
-            metric!(counter.function_run.reconciliation = -1);
-            span.record_err(err)
-        })?;
-
-    let publisher = Publisher::new(&nats, &reply_mailbox);
-
-    let mut client = cyclone_pool.get().await.map_err(|err| {
-        metric!(counter.function_run.reconciliation = -1);
-        span.record_err(ServerError::CyclonePool(Box::new(err)))
-    })?;
-
-    let mut progress = client
-        .execute_reconciliation(cyclone_request)
-        .await
-        .map_err(|err| {
-            metric!(counter.function_run.reconciliation = -1);
-            span.record_err(err)
-        })?
-        .start()
-        .await
-        .map_err(|err| {
-            metric!(counter.function_run.reconciliation = -1);
-            span.record_err(err)
-        })?;
-
-    while let Some(msg) = progress.next().await {
-        match msg {
-            Ok(ProgressMessage::OutputStream(output)) => {
-                publisher.publish_output(&output).await.map_err(|err| {
-                    metric!(counter.function_run.reconciliation = -1);
-                    span.record_err(err)
-                })?
-            }
-            Ok(ProgressMessage::Heartbeat) => {
-                trace!("received heartbeat message");
-            }
-            Err(err) => {
-                warn!(error = ?err, "next progress message was an error, bailing out");
-                break;
-            }
-        }
-    }
-    publisher.finalize_output().await.map_err(|err| {
-        metric!(counter.function_run.reconciliation = -1);
-        span.record_err(err)
-    })?;
-
-    let function_result = progress.finish().await.map_err(|err| {
-        metric!(counter.function_run.reconciliation = -1);
-        span.record_err(err)
-    })?;
-    publisher
-        .publish_result(&function_result)
-        .await
-        .map_err(|err| {
-            metric!(counter.function_run.reconciliation = -1);
-            span.record_err(err)
-        })?;
-
-    metric!(counter.function_run.reconciliation = -1);
-    span.record_ok();
-    Ok(())
+        .map_err(ServerError::Naxum)
 }
 
 async fn connect_to_nats(config: &Config) -> ServerResult<NatsClient> {
@@ -1347,3 +456,132 @@ impl Default for ShutdownSource {
         Self::Handle
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use si_data_nats::NatsConfig;
+    use si_pool_noodle::{
+        instance::cyclone::{LocalHttpInstanceError, LocalHttpInstanceSpecBuilder},
+        FunctionResult, ResolverFunctionResultSuccess,
+    };
+    use tokio::{net::TcpStream, time};
+    use veritech_core::{reply_mailbox_for_output, FINAL_MESSAGE_HEADER_KEY};
+
+    use super::*;
+
+    async fn nats(subject_prefix: &str) -> NatsClient {
+        let mut config = NatsConfig::default();
+        #[allow(clippy::disallowed_methods)] // Used only in tests & so prefixed with `SI_TEST_`
+        if let Ok(value) = env::var("SI_TEST_NATS_URL") {
+            config.url = value;
+        }
+        config.subject_prefix = Some(subject_prefix.to_owned());
+
+        NatsClient::new(&config)
+            .await
+            .expect("failed to connect to NATS")
+    }
+
+    #[tokio::test]
+    async fn failed_execution_is_replied_to_on_result_mailbox() {
+        let subject_prefix = ulid::Ulid::new().to_string();
+        let nats = nats(&subject_prefix).await;
+
+        // Nothing listens on this address, so every execution fails to reach cyclone
+        let client = si_pool_noodle::Client::http("127.0.0.1:1").expect("failed to create client");
+        let cyclone_pool = CyclonePool::shared(move || LocalHttpInstance::remote(client.clone()));
+        let decryption_key = VeritechDecryptionKey::load(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/src/dev.decryption.key"
+        ))
+        .await
+        .expect("failed to load decryption key");
+        let metadata = ServerMetadata {
+            job_instance: "test".to_owned(),
+            job_invoked_provider: "si",
+            function_timeouts: FunctionTimeouts::default(),
+            scheduler: Scheduler::new(1, None),
+        };
+        let state = AppState::new(
+            Arc::new(metadata),
+            nats.clone(),
+            cyclone_pool,
+            Arc::new(decryption_key),
+        );
+        let subject = nats_resolver_function_subject(Some(&subject_prefix));
+        let (shutdown_broadcast_tx, _) = broadcast::channel(1);
+        tokio::spawn(serve_requests(
+            state,
+            subject.clone(),
+            "resolver",
+            handlers::process_resolver_function_request::<
+                LocalHttpInstanceSpecBuilder,
+                LocalHttpInstance,
+                LocalHttpInstanceError,
+                LocalHttpInstanceSpec,
+                TcpStream,
+            >,
+            shutdown_broadcast_tx.subscribe(),
+        ));
+
+        let client = nats.as_inner();
+        let inbox = client.new_inbox();
+        let mut results = client
+            .subscribe(reply_mailbox_for_result(&inbox))
+            .await
+            .expect("failed to subscribe");
+        let mut output = client
+            .subscribe(reply_mailbox_for_output(&inbox))
+            .await
+            .expect("failed to subscribe");
+        let request = serde_json::json!({
+            "executionId": "1234",
+            "handler": "main",
+            "component": { "data": { "kind": "standard", "properties": {} }, "parents": [] },
+            "responseType": "Unset",
+            "codeBase64": "",
+            "before": [],
+        });
+
+        // The server subscribes in the background, so keep asking until it is listening
+        let result = time::timeout(Duration::from_secs(10), async {
+            loop {
+                client
+                    .publish_with_reply(subject.clone(), inbox.clone(), request.to_string().into())
+                    .await
+                    .expect("failed to publish request");
+                if let Ok(Some(msg)) =
+                    time::timeout(Duration::from_millis(100), results.next()).await
+                {
+                    break msg;
+                }
+            }
+        })
+        .await
+        .expect("timed out waiting for result");
+
+        let result: FunctionResult<ResolverFunctionResultSuccess> =
+            serde_json::from_slice(&result.payload).expect("failed to deserialize result");
+        match result {
+            FunctionResult::Failure(failure) => {
+                assert_eq!("1234", failure.execution_id);
+                assert_eq!("veritechServer", failure.error.kind);
+            }
+            FunctionResult::Success(_) => panic!("execution should have failed"),
+        }
+
+        // The output stream is finished ahead of the result
+        let msg = output.next().await.expect("subscription ended");
+        assert_eq!(
+            Some("true"),
+            msg.headers
+                .as_ref()
+                .and_then(|headers| headers.get(FINAL_MESSAGE_HEADER_KEY))
+                .map(|value| value.as_str())
+        );
+
+        let _ = shutdown_broadcast_tx.send(());
+    }
+}